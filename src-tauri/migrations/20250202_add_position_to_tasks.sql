@@ -0,0 +1,4 @@
+-- Add position column to tasks table for manual ordering on the kanban board
+ALTER TABLE tasks ADD COLUMN position INTEGER;
+
+CREATE INDEX idx_tasks_project_position ON tasks(project_id, position);
@@ -0,0 +1,14 @@
+-- Project-level environment variables injected into agent executions and dev servers
+CREATE TABLE IF NOT EXISTS project_env_vars (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    is_secret BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE(project_id, key)
+);
+
+CREATE INDEX idx_project_env_vars_project_id ON project_env_vars(project_id);
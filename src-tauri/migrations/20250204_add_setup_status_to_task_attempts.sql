@@ -0,0 +1,3 @@
+-- Track the project setup script run in each freshly created worktree
+-- NULL when the project has no setup script; otherwise running, completed, failed or skipped
+ALTER TABLE task_attempts ADD COLUMN setup_status TEXT;
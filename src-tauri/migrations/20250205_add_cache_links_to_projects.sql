@@ -0,0 +1,5 @@
+-- Paths shared from the main checkout into new worktrees (JSON array of relative paths)
+ALTER TABLE projects ADD COLUMN cache_links TEXT;
+
+-- How cache paths are shared: 'symlink' or 'clone' (hard-link copy)
+ALTER TABLE projects ADD COLUMN cache_link_mode TEXT NOT NULL DEFAULT 'symlink';
@@ -0,0 +1,2 @@
+-- Accumulated Claude API cost (USD) of all executions run in an attempt
+ALTER TABLE task_attempts ADD COLUMN cost_usd REAL;
@@ -0,0 +1,31 @@
+-- Project scripts run outside of any task attempt, so task_attempt_id becomes optional
+-- SQLite doesn't support ALTER COLUMN, so we need to recreate the table
+
+CREATE TABLE execution_processes_new (
+    id TEXT PRIMARY KEY,
+    task_attempt_id TEXT,
+    process_type TEXT NOT NULL,
+    executor_type TEXT,
+    status TEXT NOT NULL DEFAULT 'running',
+    command TEXT NOT NULL,
+    args TEXT,
+    working_directory TEXT NOT NULL,
+    stdout TEXT,
+    stderr TEXT,
+    exit_code INTEGER,
+    started_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at DATETIME,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+-- Copy data from old table
+INSERT INTO execution_processes_new SELECT * FROM execution_processes;
+
+-- Drop old table
+DROP TABLE execution_processes;
+
+-- Rename new table
+ALTER TABLE execution_processes_new RENAME TO execution_processes;
+
+-- Recreate indexes
+CREATE INDEX IF NOT EXISTS idx_execution_processes_task_attempt_id ON execution_processes(task_attempt_id);
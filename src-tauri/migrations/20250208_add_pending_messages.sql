@@ -0,0 +1,12 @@
+-- Follow-up messages sent while an execution was still running, dispatched in order once it completes
+CREATE TABLE IF NOT EXISTS pending_messages (
+    id TEXT PRIMARY KEY,
+    task_attempt_id TEXT NOT NULL,
+    task_id TEXT NOT NULL,
+    message TEXT NOT NULL,
+    images TEXT, -- JSON array
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_pending_messages_task_attempt_id ON pending_messages(task_attempt_id);
@@ -0,0 +1,9 @@
+-- MCP servers attached to a project and passed to its agent sessions
+-- server_id refers to the in-memory McpServerManager registry; stale ids are pruned at execution time
+CREATE TABLE IF NOT EXISTS project_mcp_servers (
+    project_id TEXT NOT NULL,
+    server_id TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, server_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Individual CI jobs/check runs of the MR's head pipeline, stored as JSON
+ALTER TABLE merge_requests ADD COLUMN pipeline_jobs TEXT;
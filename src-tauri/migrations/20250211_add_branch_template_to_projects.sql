@@ -0,0 +1,2 @@
+-- Optional branch name template, e.g. "feature/{slug}-{id}". NULL uses the default
+ALTER TABLE projects ADD COLUMN branch_template TEXT;
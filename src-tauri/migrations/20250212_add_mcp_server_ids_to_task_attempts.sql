@@ -0,0 +1,2 @@
+-- JSON array of MCP server ids injected into this attempt in addition to the project's servers
+ALTER TABLE task_attempts ADD COLUMN mcp_server_ids TEXT;
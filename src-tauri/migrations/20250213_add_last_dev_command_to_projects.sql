@@ -0,0 +1,2 @@
+-- Command the dev server was last started with, reused when none is given
+ALTER TABLE projects ADD COLUMN last_dev_command TEXT;
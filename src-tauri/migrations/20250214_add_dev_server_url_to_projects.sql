@@ -0,0 +1,2 @@
+-- Manual override for the dev server URL, NULL uses the URL detected in its output
+ALTER TABLE projects ADD COLUMN dev_server_url TEXT;
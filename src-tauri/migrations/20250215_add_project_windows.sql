@@ -0,0 +1,11 @@
+-- Project windows that were open when the app quit, restored on the next start
+CREATE TABLE IF NOT EXISTS project_windows (
+    project_id TEXT PRIMARY KEY,
+    x INTEGER,
+    y INTEGER,
+    width INTEGER,
+    height INTEGER,
+    maximized BOOLEAN NOT NULL DEFAULT 0,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
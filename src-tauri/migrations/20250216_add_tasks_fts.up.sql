@@ -0,0 +1,32 @@
+-- Full-text index over task titles, descriptions and tags, kept in sync with tasks by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS tasks_fts USING fts5(
+    title,
+    description,
+    tags,
+    content='tasks',
+    content_rowid='rowid',
+    tokenize='unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER tasks_fts_after_insert AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO tasks_fts(rowid, title, description, tags)
+    VALUES (NEW.rowid, NEW.title, NEW.description, NEW.tags);
+END;
+
+CREATE TRIGGER tasks_fts_after_delete AFTER DELETE ON tasks
+BEGIN
+    INSERT INTO tasks_fts(tasks_fts, rowid, title, description, tags)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.description, OLD.tags);
+END;
+
+CREATE TRIGGER tasks_fts_after_update AFTER UPDATE OF title, description, tags ON tasks
+BEGIN
+    INSERT INTO tasks_fts(tasks_fts, rowid, title, description, tags)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.description, OLD.tags);
+    INSERT INTO tasks_fts(rowid, title, description, tags)
+    VALUES (NEW.rowid, NEW.title, NEW.description, NEW.tags);
+END;
+
+-- Index the tasks that already exist
+INSERT INTO tasks_fts(tasks_fts) VALUES ('rebuild');
@@ -0,0 +1,4 @@
+-- Archived tasks keep their record and history but are hidden from task lists
+ALTER TABLE tasks ADD COLUMN archived_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_project_archived ON tasks(project_id, archived_at);
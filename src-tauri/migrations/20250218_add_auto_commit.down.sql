@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_attempt_commits_task_attempt_id;
+DROP TABLE IF EXISTS attempt_commits;
+
+ALTER TABLE projects DROP COLUMN auto_commit;
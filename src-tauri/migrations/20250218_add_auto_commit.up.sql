@@ -0,0 +1,14 @@
+-- Commit the worktree changes automatically after each agent execution
+ALTER TABLE projects ADD COLUMN auto_commit INTEGER NOT NULL DEFAULT 0;
+
+-- Commits created automatically for an attempt
+CREATE TABLE IF NOT EXISTS attempt_commits (
+    id TEXT PRIMARY KEY,
+    task_attempt_id TEXT NOT NULL,
+    commit_hash TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_commits_task_attempt_id ON attempt_commits(task_attempt_id);
@@ -0,0 +1,4 @@
+ALTER TABLE task_attempts DROP COLUMN model;
+
+ALTER TABLE projects DROP COLUMN default_model;
+ALTER TABLE projects DROP COLUMN default_executor;
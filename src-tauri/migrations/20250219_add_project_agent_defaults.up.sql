@@ -0,0 +1,6 @@
+-- Agent and model used for new attempts of a project when none is picked
+ALTER TABLE projects ADD COLUMN default_executor TEXT;
+ALTER TABLE projects ADD COLUMN default_model TEXT;
+
+-- Model the attempt's agent runs with, NULL for the agent's own default
+ALTER TABLE task_attempts ADD COLUMN model TEXT;
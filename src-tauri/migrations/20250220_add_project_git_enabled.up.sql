@@ -0,0 +1,2 @@
+-- Projects in folders without git run attempts in the project directory itself
+ALTER TABLE projects ADD COLUMN git_enabled BOOLEAN NOT NULL DEFAULT 1;
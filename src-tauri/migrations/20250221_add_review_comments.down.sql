@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_review_comments_task_attempt_id;
+DROP TABLE IF EXISTS review_comments;
@@ -0,0 +1,18 @@
+-- Review comments left on lines of an attempt's diff, sent to the agent on request
+CREATE TABLE IF NOT EXISTS review_comments (
+    id TEXT PRIMARY KEY,
+    task_attempt_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    -- 'old' for a line of the base version, 'new' for the attempt's version
+    side TEXT NOT NULL DEFAULT 'new',
+    body TEXT NOT NULL,
+    -- The commented line when the comment was made, to notice when it changes
+    line_content TEXT,
+    resolved INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_review_comments_task_attempt_id ON review_comments(task_attempt_id);
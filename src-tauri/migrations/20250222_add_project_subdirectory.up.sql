@@ -0,0 +1,2 @@
+-- Run agents and scope diffs to a subdirectory of the repository (monorepos)
+ALTER TABLE projects ADD COLUMN subdirectory TEXT;
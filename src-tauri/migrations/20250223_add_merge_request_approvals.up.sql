@@ -0,0 +1,2 @@
+-- Number of approvals a GitLab MR has, refreshed when approvals are fetched or changed
+ALTER TABLE merge_requests ADD COLUMN approval_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,18 @@
+-- Put the output back into the process rows
+UPDATE execution_processes SET
+    stdout = (
+        SELECT group_concat(content, char(10)) || char(10) FROM (
+            SELECT content FROM process_output_lines
+            WHERE process_id = execution_processes.id AND stream = 'stdout'
+            ORDER BY line_number
+        )
+    ),
+    stderr = (
+        SELECT group_concat(content, char(10)) || char(10) FROM (
+            SELECT content FROM process_output_lines
+            WHERE process_id = execution_processes.id AND stream = 'stderr'
+            ORDER BY line_number
+        )
+    );
+
+DROP TABLE process_output_lines;
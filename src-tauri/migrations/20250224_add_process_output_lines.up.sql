@@ -0,0 +1,28 @@
+-- Process output line by line, so long outputs can be read a page at a time
+CREATE TABLE process_output_lines (
+    process_id TEXT NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    stream TEXT NOT NULL CHECK (stream IN ('stdout', 'stderr')),
+    line_number INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    PRIMARY KEY (process_id, stream, line_number)
+);
+
+-- Split the output recorded so far into lines
+INSERT INTO process_output_lines (process_id, stream, line_number, content)
+WITH RECURSIVE split(process_id, stream, line_number, content, rest) AS (
+    SELECT id, 'stdout', -1, '',
+           CASE WHEN substr(stdout, -1) = char(10) THEN stdout ELSE stdout || char(10) END
+    FROM execution_processes WHERE stdout IS NOT NULL AND stdout != ''
+    UNION ALL
+    SELECT id, 'stderr', -1, '',
+           CASE WHEN substr(stderr, -1) = char(10) THEN stderr ELSE stderr || char(10) END
+    FROM execution_processes WHERE stderr IS NOT NULL AND stderr != ''
+    UNION ALL
+    SELECT process_id, stream, line_number + 1,
+           substr(rest, 1, instr(rest, char(10)) - 1),
+           substr(rest, instr(rest, char(10)) + 1)
+    FROM split WHERE rest != ''
+)
+SELECT process_id, stream, line_number, content FROM split WHERE line_number >= 0;
+
+UPDATE execution_processes SET stdout = NULL, stderr = NULL;
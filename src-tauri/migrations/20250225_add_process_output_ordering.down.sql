@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_process_output_lines_sequence;
+
+ALTER TABLE process_output_lines DROP COLUMN emitted_at;
+ALTER TABLE process_output_lines DROP COLUMN sequence;
@@ -0,0 +1,7 @@
+-- When each output line was read, so stdout and stderr can be interleaved
+-- in the order the process wrote them. `sequence` counts lines across both
+-- streams of a process; lines recorded before this have 0 for both.
+ALTER TABLE process_output_lines ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE process_output_lines ADD COLUMN emitted_at INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_process_output_lines_sequence ON process_output_lines(process_id, sequence);
@@ -0,0 +1,14 @@
+-- What happened to each task, in order: status changes, agent executions,
+-- commits and merge requests. `data` holds the event as JSON.
+CREATE TABLE IF NOT EXISTS task_events (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL,
+    task_attempt_id TEXT,
+    event_type TEXT NOT NULL,
+    data TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_events_task_id ON task_events(task_id, created_at);
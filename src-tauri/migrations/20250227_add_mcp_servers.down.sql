@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_mcp_servers_project_id;
+DROP TABLE IF EXISTS mcp_servers;
@@ -0,0 +1,19 @@
+-- MCP servers registered in McpServerManager, so they survive restarts.
+-- Servers without a project are available to every project; the others only
+-- to theirs. `args` and `env` hold JSON.
+CREATE TABLE IF NOT EXISTS mcp_servers (
+    id TEXT PRIMARY KEY,
+    project_id TEXT,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    args TEXT NOT NULL DEFAULT '[]',
+    env TEXT NOT NULL DEFAULT '{}',
+    auto_restart BOOLEAN NOT NULL DEFAULT 0,
+    health_check_interval_seconds INTEGER,
+    request_timeout_seconds INTEGER,
+    log_lines INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_mcp_servers_project_id ON mcp_servers(project_id);
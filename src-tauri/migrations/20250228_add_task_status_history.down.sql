@@ -0,0 +1,3 @@
+ALTER TABLE tasks DROP COLUMN status_triggered_by;
+DROP INDEX IF EXISTS idx_task_status_history_task_id;
+DROP TABLE IF EXISTS task_status_history;
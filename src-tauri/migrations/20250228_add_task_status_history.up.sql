@@ -0,0 +1,16 @@
+-- Every change of a task's status and what caused it: user, agent_completed,
+-- vcs_merged, vcs_closed or system. The task keeps the cause of its latest
+-- change so lists can show it without reading the history.
+CREATE TABLE IF NOT EXISTS task_status_history (
+    id TEXT PRIMARY KEY,
+    task_id TEXT NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    triggered_by TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_status_history_task_id ON task_status_history(task_id, created_at);
+
+ALTER TABLE tasks ADD COLUMN status_triggered_by TEXT;
@@ -0,0 +1,41 @@
+DROP TRIGGER IF EXISTS tasks_fts_after_conversation_update;
+DROP TRIGGER IF EXISTS attempt_conversations_search_text_after_update;
+DROP TRIGGER IF EXISTS attempt_conversations_search_text_after_insert;
+DROP TRIGGER IF EXISTS tasks_fts_after_update;
+DROP TRIGGER IF EXISTS tasks_fts_after_delete;
+DROP TRIGGER IF EXISTS tasks_fts_after_insert;
+DROP TABLE IF EXISTS tasks_fts;
+
+ALTER TABLE attempt_conversations DROP COLUMN search_text;
+
+-- Back to the index over task titles, descriptions and tags
+CREATE VIRTUAL TABLE tasks_fts USING fts5(
+    title,
+    description,
+    tags,
+    content='tasks',
+    content_rowid='rowid',
+    tokenize='unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER tasks_fts_after_insert AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO tasks_fts(rowid, title, description, tags)
+    VALUES (NEW.rowid, NEW.title, NEW.description, NEW.tags);
+END;
+
+CREATE TRIGGER tasks_fts_after_delete AFTER DELETE ON tasks
+BEGIN
+    INSERT INTO tasks_fts(tasks_fts, rowid, title, description, tags)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.description, OLD.tags);
+END;
+
+CREATE TRIGGER tasks_fts_after_update AFTER UPDATE OF title, description, tags ON tasks
+BEGIN
+    INSERT INTO tasks_fts(tasks_fts, rowid, title, description, tags)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.description, OLD.tags);
+    INSERT INTO tasks_fts(rowid, title, description, tags)
+    VALUES (NEW.rowid, NEW.title, NEW.description, NEW.tags);
+END;
+
+INSERT INTO tasks_fts(tasks_fts) VALUES ('rebuild');
@@ -0,0 +1,84 @@
+-- The task search index gets the message contents of the task's attempt
+-- conversations as a fourth column. Conversations aren't columns of tasks, so
+-- the index now keeps its own copy of the text. Rows are found by task id;
+-- rowids of tasks can change when the database is vacuumed into a backup.
+DROP TRIGGER IF EXISTS tasks_fts_after_update;
+DROP TRIGGER IF EXISTS tasks_fts_after_delete;
+DROP TRIGGER IF EXISTS tasks_fts_after_insert;
+DROP TABLE IF EXISTS tasks_fts;
+
+CREATE VIRTUAL TABLE tasks_fts USING fts5(
+    title,
+    description,
+    tags,
+    conversations,
+    task_id UNINDEXED,
+    tokenize='unicode61 remove_diacritics 2'
+);
+
+-- The messages of a conversation, one per line, so a changed conversation is
+-- the only one whose messages are read again
+ALTER TABLE attempt_conversations ADD COLUMN search_text TEXT;
+
+UPDATE attempt_conversations SET search_text = (
+    SELECT group_concat(json_extract(m.value, '$.content'), char(10))
+    FROM json_each(CASE WHEN json_valid(attempt_conversations.messages) THEN attempt_conversations.messages ELSE '[]' END) m
+);
+
+-- Index the tasks and conversations that already exist
+INSERT INTO tasks_fts(task_id, title, description, tags, conversations)
+SELECT t.id, t.title, t.description, t.tags, (
+    SELECT group_concat(c.search_text, char(10))
+    FROM task_attempts a
+    JOIN attempt_conversations c ON c.task_attempt_id = a.id
+    WHERE a.task_id = t.id
+)
+FROM tasks t;
+
+CREATE TRIGGER tasks_fts_after_insert AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO tasks_fts(task_id, title, description, tags)
+    VALUES (NEW.id, NEW.title, NEW.description, NEW.tags);
+END;
+
+CREATE TRIGGER tasks_fts_after_delete AFTER DELETE ON tasks
+BEGIN
+    DELETE FROM tasks_fts WHERE task_id = OLD.id;
+END;
+
+CREATE TRIGGER tasks_fts_after_update AFTER UPDATE OF title, description, tags ON tasks
+BEGIN
+    UPDATE tasks_fts SET title = NEW.title, description = NEW.description, tags = NEW.tags
+    WHERE task_id = NEW.id;
+END;
+
+CREATE TRIGGER attempt_conversations_search_text_after_insert AFTER INSERT ON attempt_conversations
+BEGIN
+    UPDATE attempt_conversations SET search_text = (
+        SELECT group_concat(json_extract(m.value, '$.content'), char(10))
+        FROM json_each(CASE WHEN json_valid(NEW.messages) THEN NEW.messages ELSE '[]' END) m
+    )
+    WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER attempt_conversations_search_text_after_update AFTER UPDATE OF messages ON attempt_conversations
+BEGIN
+    UPDATE attempt_conversations SET search_text = (
+        SELECT group_concat(json_extract(m.value, '$.content'), char(10))
+        FROM json_each(CASE WHEN json_valid(NEW.messages) THEN NEW.messages ELSE '[]' END) m
+    )
+    WHERE id = NEW.id;
+END;
+
+-- Only the task of the changed conversation is indexed again, from the text
+-- its conversations already have
+CREATE TRIGGER tasks_fts_after_conversation_update AFTER UPDATE OF search_text ON attempt_conversations
+BEGIN
+    UPDATE tasks_fts SET conversations = (
+        SELECT group_concat(c.search_text, char(10))
+        FROM task_attempts a
+        JOIN attempt_conversations c ON c.task_attempt_id = a.id
+        WHERE a.task_id = tasks_fts.task_id
+    )
+    WHERE task_id = (SELECT task_id FROM task_attempts WHERE id = NEW.task_attempt_id);
+END;
@@ -0,0 +1,4 @@
+-- Deleted tasks stay in the database until they are purged, so a deletion can be undone
+ALTER TABLE tasks ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_deleted_at ON tasks(deleted_at);
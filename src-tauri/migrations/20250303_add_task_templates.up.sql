@@ -0,0 +1,21 @@
+-- Reusable task blueprints of a project; `{{name}}` placeholders in the title,
+-- description and initial prompt are filled in when a task is created from one
+CREATE TABLE IF NOT EXISTS task_templates (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    title_pattern TEXT NOT NULL,
+    description_template TEXT,
+    priority TEXT NOT NULL DEFAULT 'Medium',
+    -- JSON array, like tasks.tags
+    tags TEXT,
+    -- Agent of the first attempt, the project's default when NULL
+    executor TEXT,
+    -- Sent to the agent once the first attempt's worktree is ready
+    initial_prompt TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_templates_project_id ON task_templates(project_id);
@@ -0,0 +1,172 @@
+use crate::models::{BackupConfig, DevServerConfig, FileWatcherConfig, GitHostsConfig, NetworkConfig, NotificationConfig, StorageConfig, WindowConfig};
+use crate::services::ConfigService;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+
+#[tauri::command]
+pub async fn get_proxy_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<NetworkConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_network_config().clone())
+}
+
+#[tauri::command]
+pub async fn set_proxy_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    proxy_url: Option<String>,
+    no_proxy: Vec<String>,
+) -> Result<(), String> {
+    let mut config_service = config_state.lock().await;
+    config_service.set_proxy(proxy_url, no_proxy).await
+}
+
+#[tauri::command]
+pub async fn get_window_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<WindowConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_window_config().clone())
+}
+
+#[tauri::command]
+pub async fn update_window_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: WindowConfig,
+) -> Result<(), String> {
+    let mut config_service = config_state.lock().await;
+    config_service.update_window_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_backup_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<BackupConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_backup_config().clone())
+}
+
+#[tauri::command]
+pub async fn update_backup_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: BackupConfig,
+) -> Result<(), String> {
+    if config.max_backups == 0 {
+        return Err("At least one backup has to be kept".to_string());
+    }
+    let mut config_service = config_state.lock().await;
+    config_service.update_backup_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_dev_server_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<DevServerConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_dev_server_config().clone())
+}
+
+/// Save the extra dev server URL patterns; every pattern has to be a valid regex
+#[tauri::command]
+pub async fn update_dev_server_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: DevServerConfig,
+) -> Result<(), String> {
+    let mut config = config;
+    config.url_patterns.retain(|pattern| !pattern.trim().is_empty());
+    for pattern in &config.url_patterns {
+        regex::Regex::new(pattern).map_err(|e| format!("Invalid pattern {}: {}", pattern, e))?;
+    }
+    let mut config_service = config_state.lock().await;
+    config_service.update_dev_server_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_storage_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<StorageConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_storage_config().clone())
+}
+
+#[tauri::command]
+pub async fn update_storage_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: StorageConfig,
+) -> Result<(), String> {
+    let mut config_service = config_state.lock().await;
+    config_service.update_storage_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_file_watcher_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<FileWatcherConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_file_watcher_config().clone())
+}
+
+/// Takes effect for directories watched from now on
+#[tauri::command]
+pub async fn update_file_watcher_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: FileWatcherConfig,
+) -> Result<(), String> {
+    for pattern in &config.ignore_patterns {
+        glob::Pattern::new(pattern).map_err(|e| format!("Invalid pattern {}: {}", pattern, e))?;
+    }
+    if config.max_watched_directories == 0 {
+        return Err("At least one directory has to be watchable".to_string());
+    }
+    let mut config_service = config_state.lock().await;
+    config_service.update_file_watcher_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_notification_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<NotificationConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_notification_config().clone())
+}
+
+#[tauri::command]
+pub async fn update_notification_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: NotificationConfig,
+) -> Result<(), String> {
+    let mut config_service = config_state.lock().await;
+    config_service.update_notification_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_git_hosts_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<GitHostsConfig, String> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_git_hosts_config().clone())
+}
+
+/// Save the hosts mapped to a provider. Run `refresh_all_git_providers`
+/// afterwards to apply them to existing projects.
+#[tauri::command]
+pub async fn update_git_hosts_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: GitHostsConfig,
+) -> Result<(), String> {
+    let mut config = config;
+    config.hosts.retain(|mapping| !mapping.host_name().is_empty());
+    for mapping in &mut config.hosts {
+        mapping.host = mapping.host.trim().trim_end_matches('/').to_string();
+    }
+    let mut config_service = config_state.lock().await;
+    config_service.update_git_hosts_config(config).await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,120 @@
+use crate::db::backup::{self, BackupKind, DatabaseBackup};
+use crate::db::{self, DbPool, RolledBackMigration, DATABASE_FILE};
+use crate::services::ConfigService;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+/// Name the backup is restored under while it is checked and migrated
+const RESTORE_FILE: &str = "pivo-restore.db";
+
+pub struct DatabaseState {
+    pub pool: DbPool,
+}
+
+#[tauri::command]
+pub async fn list_database_backups(app_handle: AppHandle) -> Result<Vec<DatabaseBackup>, String> {
+    let app_dir = db::app_data_dir(&app_handle).map_err(|e| e.to_string())?;
+    backup::list_backups(&app_dir).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_database_backup(
+    app_handle: AppHandle,
+    db_state: State<'_, DatabaseState>,
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<DatabaseBackup, String> {
+    let app_dir = db::app_data_dir(&app_handle).map_err(|e| e.to_string())?;
+    let backup = backup::snapshot_database(&db_state.pool, &app_dir, BackupKind::Manual).await?;
+
+    let keep = config_state.lock().await.get_backup_config().max_backups;
+    if let Err(e) = backup::prune_backups(&app_dir, keep) {
+        log::warn!("Failed to remove old database backups: {}", e);
+    }
+
+    Ok(backup)
+}
+
+/// Replace the database with a backup and restart the app. The backup is
+/// migrated on a copy first, so a backup that can't be opened leaves the
+/// current database untouched. The current database is backed up before
+/// it is replaced, and put back from that backup if the files can't be
+/// replaced; the error is returned then and the app isn't restarted.
+#[tauri::command]
+pub async fn restore_database_backup(
+    app_handle: AppHandle,
+    db_state: State<'_, DatabaseState>,
+    backup_id: String,
+) -> Result<(), String> {
+    let app_dir = db::app_data_dir(&app_handle).map_err(|e| e.to_string())?;
+    let backup_path = backup::backup_path(&app_dir, &backup_id)?;
+    let restore_path = app_dir.join(RESTORE_FILE);
+
+    backup::copy_backup_to(&backup_path, &restore_path)
+        .map_err(|e| format!("Failed to copy backup {}: {}", backup_id, e))?;
+    match db::open_and_migrate(&restore_path).await {
+        Ok(pool) => pool.close().await,
+        Err(e) => {
+            let _ = std::fs::remove_file(&restore_path);
+            return Err(format!("Backup {} can't be restored: {}", backup_id, e));
+        }
+    }
+
+    // Taken while the pool is open, so nothing has been touched if it fails
+    let previous = match backup::snapshot_database(&db_state.pool, &app_dir, BackupKind::PreRestore).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            let _ = std::fs::remove_file(&restore_path);
+            return Err(format!("Failed to back up the current database before restoring: {}", e));
+        }
+    };
+    log::info!("Backed up current database to {}", previous.id);
+
+    // Every service shares this pool, so closing it stops all database access
+    db_state.pool.close().await;
+
+    let db_path = app_dir.join(DATABASE_FILE);
+    if let Err(e) = backup::replace_database_files(&restore_path, &db_path) {
+        log::error!("Failed to restore backup {}: {}", backup_id, e);
+        let put_back = backup::backup_path(&app_dir, &previous.id)
+            .and_then(|previous_path| backup::copy_backup_to(&previous_path, &db_path).map_err(|e| e.to_string()));
+
+        // Services hold the closed pool either way, so the app has to be restarted
+        return Err(match put_back {
+            Ok(()) => format!(
+                "Failed to restore backup {}: {}. The current database was kept; restart Pivo to continue.",
+                backup_id, e
+            ),
+            Err(put_back_error) => format!(
+                "Failed to restore backup {}: {}. The current database could not be put back either ({}); it is saved as backup {}.",
+                backup_id, e, put_back_error, previous.id
+            ),
+        });
+    }
+    log::info!("Restored database backup {}, restarting", backup_id);
+
+    // Services hold the closed pool; restarting reopens the database and runs migrations again
+    app_handle.restart()
+}
+
+/// Undo the most recent migration, e.g. before going back to an older version
+/// of the app. The database is backed up first. The app quits afterwards:
+/// its services expect the current schema, and a restart would apply the
+/// migration again.
+#[tauri::command]
+pub async fn rollback_last_migration(
+    app_handle: AppHandle,
+    db_state: State<'_, DatabaseState>,
+) -> Result<RolledBackMigration, String> {
+    let app_dir = db::app_data_dir(&app_handle).map_err(|e| e.to_string())?;
+    let backup = backup::snapshot_database(&db_state.pool, &app_dir, BackupKind::PreRollback).await?;
+    log::info!("Backed up database to {} before rolling back", backup.id);
+
+    let migration = db::rollback_last_migration(&db_state.pool).await?;
+    log::warn!("Rolled back migration {} ({}), quitting", migration.version, migration.description);
+
+    db_state.pool.close().await;
+    app_handle.exit(0);
+
+    Ok(migration)
+}
@@ -1,49 +1,403 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::process::Stdio;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
 use tauri::{AppHandle, Emitter, State};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 use uuid::Uuid;
+use crate::models::ProjectEnvironment;
+use crate::services::ConfigService;
+use crate::AppState;
 
+/// Output lines kept per dev server so a window opened later can catch up
+const OUTPUT_BUFFER_LINES: usize = 200;
+
+lazy_static! {
+    static ref ANSI_ESCAPE: Regex = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap();
+    /// Lines announcing the server address (vite, next, webpack-dev-server, generic)
+    static ref URL_LINE_PATTERNS: Vec<Regex> = vec![
+        Regex::new(r"(?i)\b(local|network):\s+https?://").unwrap(),
+        Regex::new(r"(?i)started server on .*url:\s+https?://").unwrap(),
+        Regex::new(r"(?i)project is running at:?\s+https?://").unwrap(),
+        Regex::new(r"(?i)(loopback|on your network).*https?://").unwrap(),
+        Regex::new(r"(?i)(local|listening)").unwrap(),
+    ];
+    static ref URL: Regex = Regex::new(r"https?://[^\s'\x22<>]+").unwrap();
+}
+
+/// Tail of the server output and the URL detected in it, shared with the readers
+#[derive(Default)]
+struct OutputState {
+    lines: VecDeque<String>,
+    url: Option<String>,
+}
+
+type OutputBuffer = Arc<std::sync::Mutex<OutputState>>;
+
+/// Patterns from the dev server settings, tried after `URL_LINE_PATTERNS`
+type UrlPatterns = Arc<Vec<Regex>>;
+
+/// Servers that exit this soon after starting count towards a crash loop
+const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(5);
+
+/// Everything needed to run the dev command again after a crash
+struct Launch {
+    project_id: String,
+    process_id: String,
+    command: String,
+    working_dir: String,
+    env: ProjectEnvironment,
+    url_patterns: UrlPatterns,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RestartPolicy {
+    enabled: bool,
+    max_restarts: u32,
+    crash_loop_limit: u32,
+}
+
+/// Name of the server started without one
+const DEFAULT_SERVER_NAME: &str = "dev";
+
+struct DevServer {
+    process_id: String,
+    project_id: String,
+    /// Human readable name, unique per project and attempt, e.g. "backend"
+    name: String,
+    /// Attempt whose worktree the server runs in, if any
+    task_attempt_id: Option<String>,
+    child: Child,
+    pid: u32,
+    command: String,
+    started_at: Instant,
+    output: OutputBuffer,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerInfo {
+    pub project_id: String,
+    pub process_id: String,
+    pub name: String,
+    pub task_attempt_id: Option<String>,
+    pub pid: u32,
+    pub command: String,
+    pub uptime_seconds: u64,
+    pub url: Option<String>,
+    pub output: Vec<String>,
+}
+
+/// Running dev servers by process ID. A project can run several servers,
+/// told apart by name and by the attempt they belong to. The metadata lives
+/// here rather than in a window, so a reloaded window can list what is
+/// still running and reattach to it.
 pub struct DevServerManager {
-    processes: Arc<Mutex<HashMap<String, Child>>>,
+    servers: Arc<Mutex<HashMap<String, DevServer>>>,
 }
 
 impl DevServerManager {
     pub fn new() -> Self {
         Self {
-            processes: Arc::new(Mutex::new(HashMap::new())),
+            servers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
 
+/// Process ID of the server with this name in the project or attempt
+fn find_server(
+    servers: &HashMap<String, DevServer>,
+    project_id: &str,
+    task_attempt_id: Option<&str>,
+    name: &str,
+) -> Option<String> {
+    servers
+        .values()
+        .find(|server| {
+            server.project_id == project_id
+                && server.task_attempt_id.as_deref() == task_attempt_id
+                && server.name == name
+        })
+        .map(|server| server.process_id.clone())
+}
+
+/// Default server name for an empty or missing one
+fn server_name(name: Option<String>) -> String {
+    name.map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_SERVER_NAME.to_string())
+}
+
+/// Start a dev server of a project. Without `command` the last used command
+/// or the project's dev script is run. Servers are identified by `name`
+/// ("dev" by default) so a project can run e.g. a backend and a frontend;
+/// with `task_attempt_id` the server runs in that attempt's worktree. If a
+/// server of that name is already running it is returned as is, unless
+/// `restart` is set. `dev-server-ready` is emitted once the server's address
+/// shows up in its output.
+///
+/// With `auto_restart` (defaulting to the dev server settings) a server that
+/// exits with an error is started again with the same command, emitting
+/// `dev-server-restarted`, up to `max_restarts` times. When it keeps dying
+/// right after starting, restarts stop with `dev-server-crash-loop`.
 #[tauri::command]
 pub async fn start_dev_server(
     app_handle: AppHandle,
+    state: State<'_, AppState>,
     dev_manager: State<'_, DevServerManager>,
-    project_path: String,
-    command: String,
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    project_id: String,
+    command: Option<String>,
+    restart: Option<bool>,
+    auto_restart: Option<bool>,
+    name: Option<String>,
+    task_attempt_id: Option<String>,
+    env_files: Option<Vec<String>>,
+    override_env: Option<bool>,
 ) -> Result<serde_json::Value, String> {
+    let project_uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    let project = state
+        .project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+
+    let command = command
+        .filter(|c| !c.trim().is_empty())
+        .or(project.last_dev_command.clone())
+        .or(project.dev_script.clone())
+        .ok_or("No dev command configured for this project")?;
+
+    let name = server_name(name);
+    let working_dir = match &task_attempt_id {
+        Some(attempt_id) => {
+            let attempt_uuid = Uuid::parse_str(attempt_id).map_err(|e| e.to_string())?;
+            state
+                .task_service
+                .get_task_attempt(attempt_uuid)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or("Task attempt not found")?
+                .worktree_path
+        }
+        None => project.path.clone(),
+    };
+
+    let existing = {
+        let mut servers = dev_manager.servers.lock().await;
+        match find_server(&servers, &project_id, task_attempt_id.as_deref(), &name) {
+            Some(process_id) if !restart.unwrap_or(false) => {
+                let server = &servers[&process_id];
+                return Ok(serde_json::json!({
+                    "process_id": server.process_id,
+                    "pid": server.pid,
+                    "name": server.name,
+                    "already_running": true
+                }));
+            }
+            Some(process_id) => servers.remove(&process_id),
+            None => None,
+        }
+    };
+    if let Some(mut server) = existing {
+        terminate(&mut server.child).await;
+        emit_stopped(&app_handle, &server, Some(-1));
+    }
+
+    let dev_server_config = config_state.lock().await.get_dev_server_config().clone();
+    let env = state
+        .project_service
+        .get_environment(&project.id)
+        .await
+        .map_err(|e| e.to_string())?
+        .with_env_files(
+            Path::new(&working_dir),
+            env_files.as_deref().unwrap_or(&dev_server_config.env_files),
+            override_env.unwrap_or(dev_server_config.override_env),
+        );
+    let url_patterns: UrlPatterns = Arc::new(
+        dev_server_config
+            .url_patterns
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern)
+                .map_err(|e| log::warn!("Ignoring invalid dev server URL pattern {}: {}", pattern, e))
+                .ok())
+            .collect()
+    );
+    let restart_policy = RestartPolicy {
+        enabled: auto_restart.unwrap_or(dev_server_config.auto_restart),
+        max_restarts: dev_server_config.max_restarts,
+        crash_loop_limit: dev_server_config.crash_loop_limit,
+    };
+
     // Generate a unique process ID
     let process_id = Uuid::new_v4().to_string();
-    
+    let launch = Launch {
+        project_id: project_id.clone(),
+        process_id: process_id.clone(),
+        command: command.clone(),
+        working_dir,
+        env,
+        url_patterns,
+    };
+    let output: OutputBuffer = Arc::new(std::sync::Mutex::new(OutputState::default()));
+    let (child, pid) = spawn_dev_process(&app_handle, &launch, &output)?;
+
+    // Store the child process first
+    let mut servers = dev_manager.servers.lock().await;
+    servers.insert(process_id.clone(), DevServer {
+        process_id: process_id.clone(),
+        project_id: project_id.clone(),
+        name: name.clone(),
+        task_attempt_id: task_attempt_id.clone(),
+        child,
+        pid,
+        command: command.clone(),
+        started_at: Instant::now(),
+        output,
+    });
+    drop(servers); // Release the lock
+
+    if let Err(e) = state.project_service.update_last_dev_command(project_uuid, &command).await {
+        log::warn!("Failed to remember dev command for project {}: {}", project_id, e);
+    }
+
+    // Monitor process completion
+    let app_monitor = app_handle.clone();
+    let manager = dev_manager.servers.clone();
+
+    tokio::spawn(async move {
+        let project_id_monitor = launch.project_id.clone();
+        let proc_id_monitor = launch.process_id.clone();
+        let mut restarts = 0;
+        let mut quick_crashes = 0;
+
+        // Wait a bit to ensure the process is properly started
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        loop {
+            // Stop monitoring once the server was stopped or replaced by a restart
+            let mut servers = manager.lock().await;
+            let Some(server) = servers.get_mut(&proc_id_monitor) else {
+                break;
+            };
+
+            match server.child.try_wait() {
+                Ok(Some(status)) => {
+                    // Process has exited
+                    let exit_code = status.code();
+                    if !status.success() && restart_policy.enabled {
+                        if server.started_at.elapsed() < CRASH_LOOP_WINDOW {
+                            quick_crashes += 1;
+                        } else {
+                            quick_crashes = 0;
+                        }
+
+                        if quick_crashes > restart_policy.crash_loop_limit {
+                            log::warn!("Dev server of project {} keeps crashing on start, not restarting", project_id_monitor);
+                            let _ = app_monitor.emit("dev-server-crash-loop", serde_json::json!({
+                                "project_id": project_id_monitor,
+                                "process_id": proc_id_monitor,
+                                "name": server.name,
+                                "exit_code": exit_code,
+                                "restarts": restarts
+                            }));
+                            if let Some(server) = servers.remove(&proc_id_monitor) {
+                                emit_stopped(&app_monitor, &server, exit_code);
+                            }
+                            break;
+                        }
+
+                        if restarts < restart_policy.max_restarts {
+                            restarts += 1;
+                            if let Ok(mut state) = server.output.lock() {
+                                state.url = None;
+                            }
+                            match spawn_dev_process(&app_monitor, &launch, &server.output) {
+                                Ok((child, pid)) => {
+                                    log::info!("Restarted dev server of project {} ({}/{})", project_id_monitor, restarts, restart_policy.max_restarts);
+                                    server.child = child;
+                                    server.pid = pid;
+                                    server.started_at = Instant::now();
+                                    let _ = app_monitor.emit("dev-server-restarted", serde_json::json!({
+                                        "project_id": project_id_monitor,
+                                        "process_id": proc_id_monitor,
+                                        "pid": pid,
+                                        "exit_code": exit_code,
+                                        "restarts": restarts
+                                    }));
+                                    drop(servers);
+                                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                                    continue;
+                                }
+                                Err(e) => log::error!("Failed to restart dev server of project {}: {}", project_id_monitor, e),
+                            }
+                        }
+                    }
+
+                    if let Some(server) = servers.remove(&proc_id_monitor) {
+                        emit_stopped(&app_monitor, &server, exit_code);
+                    }
+                    break;
+                }
+                Ok(None) => {
+                    // Process is still running
+                    drop(servers);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                }
+                Err(e) => {
+                    eprintln!("Error checking process status: {}", e);
+                    if let Some(server) = servers.remove(&proc_id_monitor) {
+                        emit_stopped(&app_monitor, &server, None);
+                    }
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(serde_json::json!({
+        "process_id": process_id,
+        "pid": pid,
+        "name": name,
+        "already_running": false
+    }))
+}
+
+fn emit_stopped(app_handle: &AppHandle, server: &DevServer, exit_code: Option<i32>) {
+    let _ = app_handle.emit("dev-server-stopped", serde_json::json!({
+        "project_id": server.project_id,
+        "process_id": server.process_id,
+        "name": server.name,
+        "task_attempt_id": server.task_attempt_id,
+        "exit_code": exit_code
+    }));
+}
+
+/// Run the dev command through the platform shell and forward its output
+fn spawn_dev_process(app_handle: &AppHandle, launch: &Launch, output: &OutputBuffer) -> Result<(Child, u32), String> {
     // For complex commands like 'pnpm tauri dev', we need to run them through a shell
     // This ensures that npm/pnpm/yarn scripts work correctly
     let mut cmd;
-    
+
     #[cfg(target_os = "windows")]
     {
         cmd = Command::new("cmd");
-        cmd.args(&["/C", &command]);
+        cmd.args(&["/C", &launch.command]);
     }
-    
+
     #[cfg(not(target_os = "windows"))]
     {
         cmd = Command::new("sh");
-        cmd.args(&["-c", &command]);
-        
+        cmd.args(&["-c", &launch.command]);
+
         // On macOS, ensure we have access to user's PATH
         #[cfg(target_os = "macos")]
         {
@@ -62,221 +416,320 @@ pub async fn start_dev_server(
             }
         }
     }
-    
-    cmd.current_dir(&project_path)
+
+    cmd.current_dir(&launch.working_dir)
+        .envs(launch.env.vars())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .stdin(Stdio::null())
         .kill_on_drop(true);
-    
+
     // Spawn the process
-    eprintln!("[DEV_SERVER] About to spawn command: {}", command);
+    eprintln!("[DEV_SERVER] About to spawn command: {}", launch.env.mask(&launch.command));
     let mut child = cmd.spawn().map_err(|e| format!("Failed to start dev server: {}", e))?;
-    
+
     let pid = child.id().unwrap_or(0);
     eprintln!("[DEV_SERVER] Process spawned successfully with PID: {}", pid);
-    let proc_id = process_id.clone();
-    let app = app_handle.clone();
-    
-    // Handle stdout
+
     if let Some(stdout) = child.stdout.take() {
-        let reader = BufReader::new(stdout);
-        let proc_id = proc_id.clone();
-        let app = app.clone();
-        
-        tokio::spawn(async move {
-            let mut lines = reader.lines();
-            eprintln!("[DEV_SERVER] Started stdout reader for process {}", proc_id);
-            while let Ok(Some(line)) = lines.next_line().await {
-                eprintln!("[DEV_SERVER] STDOUT: {}", line);
-                let emit_result = app.emit("dev-server-output", serde_json::json!({
-                    "process_id": proc_id,
-                    "type": "stdout",
-                    "data": line
-                }));
-                if let Err(e) = emit_result {
-                    eprintln!("[DEV_SERVER] Failed to emit stdout: {}", e);
-                }
-            }
-            eprintln!("[DEV_SERVER] STDOUT reader ended for process {}", proc_id);
-        });
+        spawn_output_reader(stdout, "stdout", app_handle, launch, output);
     }
-    
-    // Handle stderr
     if let Some(stderr) = child.stderr.take() {
-        let reader = BufReader::new(stderr);
-        let proc_id = proc_id.clone();
-        let app = app.clone();
-        
-        tokio::spawn(async move {
-            let mut lines = reader.lines();
-            eprintln!("[DEV_SERVER] Started stderr reader for process {}", proc_id);
-            while let Ok(Some(line)) = lines.next_line().await {
-                eprintln!("[DEV_SERVER] STDERR: {}", line);
-                let emit_result = app.emit("dev-server-output", serde_json::json!({
-                    "process_id": proc_id,
-                    "type": "stderr",
-                    "data": line
-                }));
-                if let Err(e) = emit_result {
-                    eprintln!("[DEV_SERVER] Failed to emit stderr: {}", e);
-                }
-            }
-            eprintln!("[DEV_SERVER] STDERR reader ended for process {}", proc_id);
-        });
+        spawn_output_reader(stderr, "stderr", app_handle, launch, output);
     }
-    
-    // Store the child process first
-    let mut processes = dev_manager.processes.lock().await;
-    processes.insert(process_id.clone(), child);
-    drop(processes); // Release the lock
-    
-    // Monitor process completion
-    let proc_id_monitor = process_id.clone();
-    let app_monitor = app_handle.clone();
-    let manager = dev_manager.processes.clone();
-    
+
+    Ok((child, pid))
+}
+
+/// Forward a stream of the dev server to the frontend and keep its tail in `output`
+fn spawn_output_reader<R: AsyncRead + Unpin + Send + 'static>(
+    stream: R,
+    stream_type: &'static str,
+    app_handle: &AppHandle,
+    launch: &Launch,
+    output: &OutputBuffer,
+) {
+    let reader = BufReader::new(stream);
+    let app = app_handle.clone();
+    let project_id = launch.project_id.clone();
+    let proc_id = launch.process_id.clone();
+    let env = launch.env.clone();
+    let output = output.clone();
+    let url_patterns = launch.url_patterns.clone();
+
     tokio::spawn(async move {
-        // Wait a bit to ensure the process is properly started
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        
-        loop {
-            // Check if process still exists
-            let should_wait = {
-                let processes = manager.lock().await;
-                processes.contains_key(&proc_id_monitor)
-            };
-            
-            if !should_wait {
-                break;
-            }
-            
-            // Check process status
-            let mut processes = manager.lock().await;
-            if let Some(child) = processes.get_mut(&proc_id_monitor) {
-                match child.try_wait() {
-                    Ok(Some(status)) => {
-                        // Process has exited
-                        let exit_code = status.code();
-                        processes.remove(&proc_id_monitor);
-                        let _ = app_monitor.emit("dev-server-stopped", serde_json::json!({
-                            "process_id": proc_id_monitor,
-                            "exit_code": exit_code
-                        }));
-                        break;
-                    }
-                    Ok(None) => {
-                        // Process is still running
-                        drop(processes);
-                        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        let mut lines = reader.lines();
+        eprintln!("[DEV_SERVER] Started {} reader for process {}", stream_type, proc_id);
+        while let Ok(Some(line)) = lines.next_line().await {
+            let line = env.mask(&line);
+            let detected_url = match output.lock() {
+                Ok(mut state) => {
+                    if state.lines.len() == OUTPUT_BUFFER_LINES {
+                        state.lines.pop_front();
                     }
-                    Err(e) => {
-                        eprintln!("Error checking process status: {}", e);
-                        processes.remove(&proc_id_monitor);
-                        let _ = app_monitor.emit("dev-server-stopped", serde_json::json!({
-                            "process_id": proc_id_monitor
-                        }));
-                        break;
+                    state.lines.push_back(line.clone());
+                    
+                    // Keep the first URL, unless a localhost one shows up after a network address
+                    match detect_url(&line, &url_patterns) {
+                        Some(url) if state.url.as_deref().is_none_or(|current| !is_local_url(current) && is_local_url(&url)) => {
+                            state.url = Some(url.clone());
+                            Some(url)
+                        }
+                        _ => None,
                     }
                 }
-            } else {
-                break;
+                Err(_) => None,
+            };
+            if let Some(url) = detected_url {
+                let _ = app.emit("dev-server-ready", serde_json::json!({
+                    "project_id": project_id,
+                    "process_id": proc_id,
+                    "port": url_port(&url),
+                    "url": url
+                }));
+            }
+            let emit_result = app.emit("dev-server-output", serde_json::json!({
+                "project_id": project_id,
+                "process_id": proc_id,
+                "type": stream_type,
+                "data": line
+            }));
+            if let Err(e) = emit_result {
+                eprintln!("[DEV_SERVER] Failed to emit {}: {}", stream_type, e);
             }
         }
+        eprintln!("[DEV_SERVER] {} reader ended for process {}", stream_type, proc_id);
     });
+}
+
+/// Extract the server address from a line such as "  ➜  Local:   http://localhost:5173/"
+fn detect_url(line: &str, extra_patterns: &[Regex]) -> Option<String> {
+    let line = ANSI_ESCAPE.replace_all(line, "");
+    if !URL_LINE_PATTERNS.iter().chain(extra_patterns).any(|pattern| pattern.is_match(&line)) {
+        return None;
+    }
     
-    Ok(serde_json::json!({
-        "process_id": process_id,
-        "pid": pid
-    }))
+    URL.find(&line)
+        .map(|m| m.as_str().trim_end_matches(|c| matches!(c, ',' | '.' | ';' | ')')).to_string())
+}
+
+/// Port of a detected address, the scheme's default when none is given
+fn url_port(url: &str) -> Option<u16> {
+    reqwest::Url::parse(url).ok()?.port_or_known_default()
+}
+
+fn is_local_url(url: &str) -> bool {
+    let host = url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split(['/', '?']).next())
+        .unwrap_or_default();
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => host,
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "[::1]" | "[::]")
+}
+
+/// Kill the dev server and everything its shell started
+async fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        if let Some(pid) = child.id() {
+            unsafe {
+                // The shell typically creates a new process group
+                let pgid = pid as i32;
+
+                // Send SIGTERM to the process itself
+                libc::kill(pgid, libc::SIGTERM);
+
+                // Also try to kill as a process group (negative PID)
+                libc::kill(-pgid, libc::SIGTERM);
+
+                // Give processes time to clean up
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+                // Check if process is still running
+                if let Ok(None) = child.try_wait() {
+                    // Still running, force kill
+                    libc::kill(pgid, libc::SIGKILL);
+                    libc::kill(-pgid, libc::SIGKILL);
+                    let _ = child.kill().await;
+                }
+            }
+        } else {
+            // Fallback to normal kill
+            let _ = child.kill().await;
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        // On Windows, kill the process tree
+        if let Some(pid) = child.id() {
+            // Use taskkill to kill the process tree
+            let _ = std::process::Command::new("taskkill")
+                .args(&["/F", "/T", "/PID", &pid.to_string()])
+                .output();
+        }
+        // Also try normal kill as fallback
+        let _ = child.kill().await;
+    }
 }
 
+/// Stop a dev server, picked by `process_id` or by `name` ("dev" by default)
+/// within the project or `task_attempt_id`
 #[tauri::command]
 pub async fn stop_dev_server(
     app_handle: AppHandle,
     dev_manager: State<'_, DevServerManager>,
-    process_id: String,
+    project_id: String,
+    name: Option<String>,
+    task_attempt_id: Option<String>,
+    process_id: Option<String>,
 ) -> Result<(), String> {
-    let mut processes = dev_manager.processes.lock().await;
-    
-    if let Some(mut child) = processes.remove(&process_id) {
-        // Try to kill the process and all its children
-        // For shell-spawned processes, we need to be more aggressive
-        
-        #[cfg(unix)]
-        {
-            if let Some(pid) = child.id() {
-                unsafe {
-                    // First, try to kill the process group
-                    // The shell typically creates a new process group
-                    let pgid = pid as i32;
-                    
-                    // Send SIGTERM to the process itself
-                    libc::kill(pgid, libc::SIGTERM);
-                    
-                    // Also try to kill as a process group (negative PID)
-                    libc::kill(-pgid, libc::SIGTERM);
-                    
-                    // Give processes time to clean up
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    
-                    // Check if process is still running
-                    match child.try_wait() {
-                        Ok(None) => {
-                            // Still running, force kill
-                            libc::kill(pgid, libc::SIGKILL);
-                            libc::kill(-pgid, libc::SIGKILL);
-                            let _ = child.kill().await;
-                        }
-                        _ => {}
-                    }
-                }
-            } else {
-                // Fallback to normal kill
-                let _ = child.kill().await;
-            }
-        }
-        
-        #[cfg(not(unix))]
-        {
-            // On Windows, kill the process tree
-            if let Some(pid) = child.id() {
-                // Use taskkill to kill the process tree
-                let _ = std::process::Command::new("taskkill")
-                    .args(&["/F", "/T", "/PID", &pid.to_string()])
-                    .output();
-            }
-            // Also try normal kill as fallback
-            let _ = child.kill().await;
-        }
-        
+    let server = {
+        let mut servers = dev_manager.servers.lock().await;
+        let process_id = process_id.or_else(|| {
+            find_server(&servers, &project_id, task_attempt_id.as_deref(), &server_name(name.clone()))
+        });
+        process_id.and_then(|process_id| servers.remove(&process_id))
+    };
+
+    if let Some(mut server) = server {
+        terminate(&mut server.child).await;
+
         // Emit stopped event
-        let _ = app_handle.emit("dev-server-stopped", serde_json::json!({
-            "process_id": process_id,
-            "exit_code": -1
-        }));
-        
-        Ok(())
+        emit_stopped(&app_handle, &server, Some(-1));
     } else {
-        // Process not found, might have already stopped
+        // Server not found, might have already stopped
         // Still emit the stopped event to update UI
         let _ = app_handle.emit("dev-server-stopped", serde_json::json!({
-            "process_id": process_id
+            "project_id": project_id,
+            "name": server_name(name),
+            "task_attempt_id": task_attempt_id
         }));
-        Ok(())
     }
+
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn get_dev_server_status(
     dev_manager: State<'_, DevServerManager>,
-    process_id: String,
+    project_id: String,
+    name: Option<String>,
+    task_attempt_id: Option<String>,
 ) -> Result<String, String> {
-    let processes = dev_manager.processes.lock().await;
-    
-    if processes.contains_key(&process_id) {
+    let servers = dev_manager.servers.lock().await;
+
+    if find_server(&servers, &project_id, task_attempt_id.as_deref(), &server_name(name)).is_some() {
         Ok("running".to_string())
     } else {
         Ok("stopped".to_string())
     }
-}
\ No newline at end of file
+}
+
+/// Running dev servers with their buffered output, only those of one attempt
+/// when `attempt_id` is given
+#[tauri::command]
+pub async fn list_dev_servers(
+    dev_manager: State<'_, DevServerManager>,
+    attempt_id: Option<String>,
+) -> Result<Vec<DevServerInfo>, String> {
+    let servers = dev_manager.servers.lock().await;
+
+    let mut infos: Vec<DevServerInfo> = servers
+        .values()
+        .filter(|server| attempt_id.is_none() || server.task_attempt_id == attempt_id)
+        .map(|server| DevServerInfo {
+            project_id: server.project_id.clone(),
+            process_id: server.process_id.clone(),
+            name: server.name.clone(),
+            task_attempt_id: server.task_attempt_id.clone(),
+            pid: server.pid,
+            command: server.command.clone(),
+            uptime_seconds: server.started_at.elapsed().as_secs(),
+            url: server.output.lock().ok().and_then(|state| state.url.clone()),
+            output: server
+                .output
+                .lock()
+                .map(|state| state.lines.iter().cloned().collect())
+                .unwrap_or_default(),
+        })
+        .collect();
+    infos.sort_by(|a, b| (&a.project_id, &a.task_attempt_id, &a.name).cmp(&(&b.project_id, &b.task_attempt_id, &b.name)));
+    Ok(infos)
+}
+
+/// URL of the project's dev server: the manual override if set, otherwise the
+/// address detected in the output of its default server, or of any other
+/// server of the project outside attempts
+#[tauri::command]
+pub async fn get_dev_server_url(
+    state: State<'_, AppState>,
+    dev_manager: State<'_, DevServerManager>,
+    project_id: String,
+) -> Result<Option<String>, String> {
+    let project_uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    let project = state
+        .project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+
+    if project.dev_server_url.is_some() {
+        return Ok(project.dev_server_url);
+    }
+
+    let servers = dev_manager.servers.lock().await;
+    let detected_url = |server: &DevServer| server.output.lock().ok().and_then(|state| state.url.clone());
+    let default_url = find_server(&servers, &project_id, None, DEFAULT_SERVER_NAME)
+        .and_then(|process_id| detected_url(&servers[&process_id]));
+    Ok(default_url.or_else(|| {
+        servers
+            .values()
+            .filter(|server| server.project_id == project_id && server.task_attempt_id.is_none())
+            .find_map(detected_url)
+    }))
+}
+
+/// Override the detected dev server URL. `None` or an empty URL removes the override.
+#[tauri::command]
+pub async fn set_dev_server_url(
+    state: State<'_, AppState>,
+    project_id: String,
+    url: Option<String>,
+) -> Result<(), String> {
+    let project_uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    let url = url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+    if let Some(url) = url.as_deref() {
+        if !URL.is_match(url) {
+            return Err(format!("Invalid URL: {}", url));
+        }
+    }
+
+    state
+        .project_service
+        .update_dev_server_url(project_uuid, url.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn open_dev_server_in_browser(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    dev_manager: State<'_, DevServerManager>,
+    project_id: String,
+) -> Result<(), String> {
+    let url = get_dev_server_url(state, dev_manager, project_id)
+        .await?
+        .ok_or("No dev server URL detected yet")?;
+
+    app_handle
+        .opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| e.to_string())
+}
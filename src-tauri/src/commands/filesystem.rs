@@ -1,5 +1,8 @@
+use crate::services::FileWatcherService;
+use crate::utils::gitignore::GitIgnore;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::SystemTime;
 use walkdir::WalkDir;
 
@@ -87,6 +90,141 @@ pub async fn search_project_files(
     Ok(results)
 }
 
+/// Names always left out of the project tree, on top of `ignore_patterns`
+const DEFAULT_TREE_IGNORES: [&str; 3] = [".git", "node_modules", "target"];
+
+/// A file or directory of the project tree. `children` is `None` for files
+/// and for directories below `max_depth`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub children: Option<Vec<DirNode>>,
+    pub size: Option<u64>,
+    pub modified: Option<String>,
+}
+
+/// Directory tree under `root_path` for the file explorer, `max_depth` levels
+/// deep. Entries matched by `ignore_patterns` (gitignore syntax) or by the
+/// `.gitignore` files along the way are left out.
+#[tauri::command]
+pub async fn read_project_tree(
+    root_path: String,
+    max_depth: usize,
+    ignore_patterns: Option<Vec<String>>,
+) -> Result<DirNode, String> {
+    let root = PathBuf::from(&root_path);
+    if !root.is_dir() {
+        return Err("Invalid project path".to_string());
+    }
+
+    let extra_patterns = ignore_patterns.unwrap_or_default();
+    let base_ignore = GitIgnore::parse(
+        DEFAULT_TREE_IGNORES.iter().copied().chain(extra_patterns.iter().map(String::as_str))
+    );
+    let mut scopes = vec![(String::new(), base_ignore)];
+
+    let name = root.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| root_path.clone());
+    let metadata = std::fs::metadata(&root).map_err(|e| e.to_string())?;
+    Ok(DirNode {
+        name,
+        path: root.to_string_lossy().to_string(),
+        is_dir: true,
+        children: (max_depth > 0).then(|| read_tree_children(&root, "", 1, max_depth, &mut scopes)),
+        size: None,
+        modified: modified_time(&metadata),
+    })
+}
+
+/// Entries of `dir` (at `relative` from the root), directories first.
+/// `scopes` holds the ignore rules in effect, each with the directory it
+/// applies to, deepest last.
+fn read_tree_children(
+    dir: &Path,
+    relative: &str,
+    depth: usize,
+    max_depth: usize,
+    scopes: &mut Vec<(String, GitIgnore)>,
+) -> Vec<DirNode> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Failed to read directory {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let pushed_scope = match GitIgnore::from_dir(dir) {
+        Some(ignore) if !ignore.is_empty() => {
+            scopes.push((relative.to_string(), ignore));
+            true
+        }
+        _ => false,
+    };
+
+    let mut nodes = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let entry_relative = if relative.is_empty() { name.clone() } else { format!("{}/{}", relative, name) };
+        let path = entry.path();
+        // Symlinked directories are listed but not entered, they could loop
+        let is_symlink = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+        let metadata = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        let is_dir = metadata.is_dir();
+        if is_tree_ignored(scopes, &entry_relative, is_dir) {
+            continue;
+        }
+
+        let children = (is_dir && !is_symlink && depth < max_depth)
+            .then(|| read_tree_children(&path, &entry_relative, depth + 1, max_depth, scopes));
+        nodes.push(DirNode {
+            name,
+            path: path.to_string_lossy().to_string(),
+            is_dir,
+            children,
+            size: (!is_dir).then(|| metadata.len()),
+            modified: modified_time(&metadata),
+        });
+    }
+
+    if pushed_scope {
+        scopes.pop();
+    }
+
+    nodes.sort_by(|a, b| {
+        b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    nodes
+}
+
+/// The deepest `.gitignore` with a matching rule decides
+fn is_tree_ignored(scopes: &[(String, GitIgnore)], relative: &str, is_dir: bool) -> bool {
+    scopes
+        .iter()
+        .rev()
+        .find_map(|(base, ignore)| {
+            let path = if base.is_empty() {
+                relative
+            } else {
+                relative.strip_prefix(base.as_str())?.strip_prefix('/')?
+            };
+            ignore.matched(path, is_dir)
+        })
+        .unwrap_or(false)
+}
+
+fn modified_time(metadata: &std::fs::Metadata) -> Option<String> {
+    metadata.modified()
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339())
+}
+
 #[tauri::command]
 pub async fn search_files_from_current_dir(
     current_path: String,
@@ -123,4 +261,21 @@ pub async fn search_files_from_current_dir(
         query,
         Some(max_results),
     ).await
+}
+
+#[tauri::command]
+pub async fn watch_project_root(
+    project_id: String,
+    project_path: String,
+    state: tauri::State<'_, Arc<FileWatcherService>>,
+) -> Result<(), String> {
+    state.watch_project_root(project_id, project_path).await
+}
+
+#[tauri::command]
+pub async fn unwatch_project_root(
+    project_id: String,
+    state: tauri::State<'_, Arc<FileWatcherService>>,
+) -> Result<(), String> {
+    state.unwatch_project_root(project_id).await
 }
\ No newline at end of file
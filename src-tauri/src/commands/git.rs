@@ -1,6 +1,11 @@
-use crate::models::{DiffMode, DiffResult, RebaseStatus};
+use crate::models::{BlameLine, CommitGraph, DiffMode, FetchResult, FileCommit, FileDiffDetail, DiffResult, DiffStatSummary, MergeMethod, MergeResult, RebaseStatus, RemoteBranch};
 use crate::services::GitService;
+use crate::utils::project_type::GitignoreTemplate;
+use crate::AppState;
+use crate::error::PivoError;
 use std::path::Path;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
 
 // Original git commands
 #[tauri::command]
@@ -8,13 +13,13 @@ pub async fn create_worktree(
     repo_path: String,
     branch_name: String,
     base_branch: String,
-) -> Result<String, String> {
+) -> Result<String, PivoError> {
     let git_service = GitService::new();
     let worktree_path = git_service.create_worktree(
         Path::new(&repo_path),
         &branch_name,
         &base_branch,
-    )?;
+    ).map_err(PivoError::git)?;
     Ok(worktree_path.to_string_lossy().to_string())
 }
 
@@ -22,54 +27,179 @@ pub async fn create_worktree(
 pub async fn remove_worktree(
     repo_path: String,
     worktree_path: String,
-) -> Result<(), String> {
+) -> Result<(), PivoError> {
     let git_service = GitService::new();
     git_service.remove_worktree(Path::new(&repo_path), Path::new(&worktree_path))
+        .map_err(PivoError::git)
 }
 
 #[tauri::command]
-pub async fn get_current_branch(repo_path: String) -> Result<String, String> {
-    GitService::get_current_branch(Path::new(&repo_path))
+pub async fn get_current_branch(repo_path: String) -> Result<String, PivoError> {
+    GitService::get_current_branch(Path::new(&repo_path)).map_err(PivoError::git)
 }
 
 #[tauri::command]
-pub async fn list_branches(repo_path: String) -> Result<Vec<String>, String> {
-    GitService::list_branches(Path::new(&repo_path))
+pub async fn list_branches(repo_path: String) -> Result<Vec<String>, PivoError> {
+    GitService::list_branches(Path::new(&repo_path)).map_err(PivoError::git)
 }
 
+/// Remote-tracking branches as of the last fetch
 #[tauri::command]
-pub async fn get_git_status(repo_path: String) -> Result<crate::services::GitStatus, String> {
+pub async fn get_remote_branches(repo_path: String) -> Result<Vec<RemoteBranch>, PivoError> {
+    GitService::get_remote_branches(Path::new(&repo_path)).map_err(PivoError::git)
+}
+
+/// Fetch one remote, or all of them, and list the remote branches that changed
+#[tauri::command]
+pub async fn fetch_remote(
+    repo_path: String,
+    remote: Option<String>,
+    prune: Option<bool>,
+) -> Result<FetchResult, PivoError> {
+    GitService::fetch_remote(Path::new(&repo_path), remote.as_deref(), prune.unwrap_or(false)).map_err(PivoError::git)
+}
+
+#[tauri::command]
+pub async fn get_git_status(repo_path: String) -> Result<crate::services::GitStatus, PivoError> {
     let git_service = GitService::new();
-    git_service.get_status(Path::new(&repo_path))
+    git_service.get_status(Path::new(&repo_path)).map_err(PivoError::git)
+}
+
+#[tauri::command]
+pub async fn stage_files(repo_path: String, files: Vec<String>) -> Result<(), PivoError> {
+    let file_refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
+    GitService::stage_files(Path::new(&repo_path), &file_refs).map_err(PivoError::git)
 }
 
 #[tauri::command]
-pub async fn stage_files(repo_path: String, files: Vec<String>) -> Result<(), String> {
+pub async fn unstage_files(repo_path: String, files: Vec<String>) -> Result<(), PivoError> {
     let file_refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
-    GitService::stage_files(Path::new(&repo_path), &file_refs)
+    GitService::unstage_files(Path::new(&repo_path), &file_refs).map_err(PivoError::git)
+}
+
+#[tauri::command]
+pub async fn unstage_all(repo_path: String) -> Result<(), PivoError> {
+    GitService::unstage_all(Path::new(&repo_path)).map_err(PivoError::git)
+}
+
+#[tauri::command]
+pub async fn commit_changes(repo_path: String, message: String) -> Result<String, PivoError> {
+    GitService::commit(Path::new(&repo_path), &message).map_err(PivoError::git)
+}
+
+/// Undo one commit with a new commit and return its hash, or apply the
+/// reversal without committing (`no_commit`, returns ""). Merge commits need
+/// `mainline`, the parent to keep.
+#[tauri::command]
+pub async fn revert_commit(
+    repo_path: String,
+    commit_sha: String,
+    no_commit: bool,
+    mainline: Option<u32>,
+) -> Result<String, PivoError> {
+    GitService::revert_commit(Path::new(&repo_path), &commit_sha, no_commit, mainline).map_err(PivoError::git)
+}
+
+/// Undo every commit after `commit_sha` as a single commit and return its hash
+#[tauri::command]
+pub async fn revert_to_commit(repo_path: String, commit_sha: String) -> Result<String, PivoError> {
+    GitService::revert_to_commit(Path::new(&repo_path), &commit_sha).map_err(PivoError::git)
+}
+
+/// Squash every commit after `base_commit` into one with `message` and return its hash
+#[tauri::command]
+pub async fn squash_commits(repo_path: String, base_commit: String, message: String) -> Result<String, PivoError> {
+    GitService::squash_commits(Path::new(&repo_path), &base_commit, &message).map_err(PivoError::git)
 }
 
+/// The `git rebase -i` todo list that squashes every commit after `base_commit`
+/// into the first, for the user to edit before rebasing
 #[tauri::command]
-pub async fn commit_changes(repo_path: String, message: String) -> Result<String, String> {
-    GitService::commit(Path::new(&repo_path), &message)
+pub async fn prepare_interactive_rebase(repo_path: String, base_commit: String) -> Result<String, PivoError> {
+    GitService::squash_commits_interactive_prepare(Path::new(&repo_path), &base_commit).map_err(PivoError::git)
+}
+
+/// Initialise git in a project directory. `gitignore_template` picks the .gitignore
+/// ("node", "rust", "python", "none", ...); by default it follows the detected project type.
+#[tauri::command]
+pub async fn init_repository(
+    path: String,
+    initial_branch: String,
+    gitignore_template: Option<String>,
+) -> Result<(), PivoError> {
+    let repo_path = Path::new(&path);
+    let result = match gitignore_template {
+        Some(name) => {
+            let template = GitignoreTemplate::parse(&name)
+                .ok_or_else(|| PivoError::InvalidInput(format!("Unknown .gitignore template: {}", name)))?;
+            GitService::init_repository_with_gitignore(repo_path, &initial_branch, template)
+        }
+        None => GitService::init_repository(repo_path, &initial_branch),
+    };
+    result.map_err(PivoError::git)
+}
+
+#[tauri::command]
+pub async fn push_branch(repo_path: String, branch: String, force: bool) -> Result<(), PivoError> {
+    GitService::push(Path::new(&repo_path), &branch, force).map_err(PivoError::git)
+}
+
+/// Subdirectory that diffs and file listings are confined to, if the project
+/// is scoped to one. Without a project they cover the whole checkout.
+async fn project_subdirectory(state: &State<'_, AppState>, project_id: Option<&str>) -> Result<Option<String>, PivoError> {
+    let Some(project_id) = project_id else {
+        return Ok(None);
+    };
+    let uuid = Uuid::parse_str(project_id).map_err(PivoError::from)?;
+    let project = state
+        .project_service
+        .get_project(uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or_else(|| PivoError::NotFound(format!("Project {} not found", project_id)))?;
+    Ok(project.subdirectory)
 }
 
 #[tauri::command]
-pub async fn push_branch(repo_path: String, branch: String, force: bool) -> Result<(), String> {
-    GitService::push(Path::new(&repo_path), &branch, force)
+pub async fn get_diff(
+    state: State<'_, AppState>,
+    repo_path: String,
+    staged: bool,
+    project_id: Option<String>,
+) -> Result<String, PivoError> {
+    let subdirectory = project_subdirectory(&state, project_id.as_deref()).await?;
+    GitService::get_diff(Path::new(&repo_path), staged, subdirectory.as_deref()).map_err(PivoError::git)
 }
 
 #[tauri::command]
-pub async fn get_diff(repo_path: String, staged: bool) -> Result<String, String> {
-    GitService::get_diff(Path::new(&repo_path), staged)
+pub async fn get_diff_stat(
+    state: State<'_, AppState>,
+    repo_path: String,
+    base_ref: Option<String>,
+    staged: bool,
+    project_id: Option<String>,
+) -> Result<DiffStatSummary, PivoError> {
+    let subdirectory = project_subdirectory(&state, project_id.as_deref()).await?;
+    GitService::get_diff_stat(Path::new(&repo_path), base_ref.as_deref(), staged, subdirectory.as_deref())
+        .map_err(PivoError::git)
 }
 
+/// Files of the checkout relative to its root, only those inside the
+/// project's subdirectory when it has one
 #[tauri::command]
-pub async fn list_all_files(repo_path: String) -> Result<Vec<String>, String> {
+pub async fn list_all_files(
+    state: State<'_, AppState>,
+    repo_path: String,
+    project_id: Option<String>,
+) -> Result<Vec<String>, PivoError> {
     use std::fs;
     use std::path::PathBuf;
     
     let repo_path_buf = PathBuf::from(&repo_path);
+    let start_path = match project_subdirectory(&state, project_id.as_deref()).await? {
+        Some(subdirectory) => repo_path_buf.join(subdirectory),
+        None => repo_path_buf.clone(),
+    };
     let mut all_files = Vec::new();
     
     // Function to recursively collect files
@@ -108,8 +238,8 @@ pub async fn list_all_files(repo_path: String) -> Result<Vec<String>, String> {
     }
     
     // Collect all files recursively
-    collect_files(&repo_path_buf, &repo_path_buf, &mut all_files)
-        .map_err(|e| format!("Failed to list files: {}", e))?;
+    collect_files(&start_path, &repo_path_buf, &mut all_files)
+        .map_err(|e| PivoError::Io(format!("Failed to list files: {}", e)))?;
     
     // Sort files for consistent ordering
     all_files.sort();
@@ -125,41 +255,170 @@ pub async fn list_all_files(repo_path: String) -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-pub async fn read_file_content(repo_path: String, file_path: String) -> Result<String, String> {
+pub async fn read_file_content(repo_path: String, file_path: String) -> Result<String, PivoError> {
     let full_path = Path::new(&repo_path).join(&file_path);
     std::fs::read_to_string(&full_path)
-        .map_err(|e| format!("Failed to read file: {}", e))
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
-pub async fn get_file_from_ref(repo_path: String, file_ref: String) -> Result<String, String> {
-    GitService::get_file_from_ref(Path::new(&repo_path), &file_ref)
+pub async fn get_file_from_ref(repo_path: String, file_ref: String) -> Result<String, PivoError> {
+    GitService::get_file_from_ref(Path::new(&repo_path), &file_ref).map_err(PivoError::git)
 }
 
 // New enhanced diff commands
 #[tauri::command]
 pub async fn get_git_diff(
+    state: State<'_, AppState>,
+    worktree_path: String,
+    mode: DiffMode,
+    project_id: Option<String>,
+) -> Result<DiffResult, PivoError> {
+    let git_service = GitService::new();
+    let mut diff = git_service.get_comprehensive_diff(Path::new(&worktree_path), mode).map_err(PivoError::git)?;
+    if let Some(subdirectory) = project_subdirectory(&state, project_id.as_deref()).await? {
+        diff.retain_subdirectory(&subdirectory);
+    }
+    Ok(diff)
+}
+
+/// Old and new content of one file of a diff with its hunks. `old_path` is
+/// the file's path before a rename.
+#[tauri::command]
+pub async fn get_file_diff_detail(
     worktree_path: String,
+    file_path: String,
+    old_path: Option<String>,
     mode: DiffMode,
-) -> Result<DiffResult, String> {
+) -> Result<FileDiffDetail, PivoError> {
     let git_service = GitService::new();
-    git_service.get_comprehensive_diff(Path::new(&worktree_path), mode)
+    git_service
+        .get_file_diff_detail(Path::new(&worktree_path), &file_path, old_path.as_deref(), &mode)
+        .map_err(PivoError::git)
 }
 
 #[tauri::command]
 pub async fn check_rebase_status(
     worktree_path: String,
     base_branch: String,
-) -> Result<RebaseStatus, String> {
+) -> Result<RebaseStatus, PivoError> {
     let git_service = GitService::new();
-    git_service.check_rebase_status(Path::new(&worktree_path), &base_branch)
+    git_service.check_rebase_status(Path::new(&worktree_path), &base_branch).map_err(PivoError::git)
 }
 
 #[tauri::command]
 pub async fn get_branch_commit(
     repo_path: String,
     branch: String,
-) -> Result<String, String> {
+) -> Result<String, PivoError> {
+    let git_service = GitService::new();
+    git_service.get_branch_commit(Path::new(&repo_path), &branch).map_err(PivoError::git)
+}
+
+#[tauri::command]
+pub async fn get_commit_graph(
+    repo_path: String,
+    max_commits: Option<usize>,
+) -> Result<CommitGraph, PivoError> {
+    GitService::get_commit_graph(Path::new(&repo_path), max_commits.unwrap_or(200)).map_err(PivoError::git)
+}
+
+/// Commits that touched `file_path`, newest first
+#[tauri::command]
+pub async fn get_file_history(
+    repo_path: String,
+    file_path: String,
+    max_count: Option<usize>,
+) -> Result<Vec<FileCommit>, PivoError> {
+    GitService::get_file_history(Path::new(&repo_path), &file_path, max_count.unwrap_or(50)).map_err(PivoError::git)
+}
+
+/// Blame `file_path` (relative to the worktree) in an attempt's worktree and
+/// flag the lines the attempt wrote
+#[tauri::command]
+pub async fn get_file_blame(
+    state: State<'_, AppState>,
+    attempt_id: String,
+    file_path: String,
+) -> Result<Vec<BlameLine>, PivoError> {
+    let attempt_uuid = Uuid::parse_str(&attempt_id).map_err(PivoError::from)?;
+    let attempt = state
+        .task_service
+        .get_task_attempt(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or(PivoError::NotFound("Task attempt not found".to_string()))?;
+    
+    let base = attempt.base_commit.filter(|commit| !commit.is_empty()).unwrap_or(attempt.base_branch);
+    GitService::blame_file(Path::new(&attempt.worktree_path), &file_path, &base).map_err(PivoError::git)
+}
+
+/// Merge an attempt's branch into its base branch without a pull request,
+/// optionally push the result and mark the task Done
+#[tauri::command]
+pub async fn integrate_attempt(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    attempt_id: String,
+    method: MergeMethod,
+    push: Option<bool>,
+) -> Result<MergeResult, PivoError> {
+    let attempt_uuid = Uuid::parse_str(&attempt_id).map_err(PivoError::from)?;
+    let attempt = state
+        .task_service
+        .get_task_attempt(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or(PivoError::NotFound("Task attempt not found".to_string()))?;
+    let task_uuid = Uuid::parse_str(&attempt.task_id).map_err(PivoError::from)?;
+    let task = state
+        .task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or(PivoError::NotFound("Task not found".to_string()))?;
+    let project_uuid = Uuid::parse_str(&task.project_id).map_err(PivoError::from)?;
+    let project = state
+        .project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or(PivoError::NotFound("Project not found".to_string()))?;
+    if !project.git_enabled {
+        return Err(PivoError::not_git_repository());
+    }
+    
+    let worktree_path = Path::new(&attempt.worktree_path);
+    if worktree_path.exists() {
+        let dirty = GitService::uncommitted_files(worktree_path).map_err(PivoError::git)?;
+        if !dirty.is_empty() {
+            return Err(PivoError::InvalidInput(format!(
+                "The attempt has uncommitted changes in {}. Commit them before integrating.",
+                dirty.join(", ")
+            )));
+        }
+    }
+    
+    let repo_path = Path::new(&project.path);
     let git_service = GitService::new();
-    git_service.get_branch_commit(Path::new(&repo_path), &branch)
-}
\ No newline at end of file
+    let result = git_service.merge_branch(
+        repo_path,
+        &attempt.branch,
+        &attempt.base_branch,
+        method,
+        Some(&task.title),
+    ).map_err(PivoError::git)?;
+    
+    if let MergeResult::Merged { .. } = result {
+        if push.unwrap_or(false) {
+            GitService::push(repo_path, &attempt.base_branch, false)
+                .map_err(|e| PivoError::GitCommandFailed {
+                    message: format!("Merged locally, but pushing '{}' failed", attempt.base_branch),
+                    stderr: e.trim().to_string(),
+                })?;
+        }
+        crate::commands::tasks::mark_attempt_task_done(&state, &app_handle, &attempt_id).await?;
+    }
+    
+    Ok(result)
+}
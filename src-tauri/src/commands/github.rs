@@ -1,16 +1,19 @@
-use crate::models::{GitHubConfig, MergeRequestInfo, GitRemoteInfo, CreateMergeRequestData, MergeRequestState};
-use crate::services::{ConfigService, GitHubService, GitPlatformService};
+use crate::models::{BranchStatus, GitHubConfig, GitProvider, RepositoryInfo, MergeRequestInfo, GitRemoteInfo, CreateMergeRequestData, MergeRequestState, MergeMethod, MergeRequestComment, PushMode};
+use crate::services::{ensure_branch_pushed, ConfigService, GitHubService, GitPlatformService};
 use crate::AppState;
+use crate::error::PivoError;
+use std::path::Path;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex;
 use chrono::Utc;
+use uuid::Uuid;
 use std::str::FromStr;
 
 #[tauri::command]
 pub async fn get_github_config(
     state: State<'_, Arc<Mutex<ConfigService>>>,
-) -> Result<Option<GitHubConfig>, String> {
+) -> Result<Option<GitHubConfig>, PivoError> {
     let config_service = state.lock().await;
     Ok(config_service.get_github_config().cloned())
 }
@@ -19,10 +22,10 @@ pub async fn get_github_config(
 pub async fn update_github_config(
     state: State<'_, Arc<Mutex<ConfigService>>>,
     config: GitHubConfig,
-) -> Result<(), String> {
+) -> Result<(), PivoError> {
     let mut config_service = state.lock().await;
     config_service.update_github_config(config).await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
@@ -35,7 +38,8 @@ pub async fn create_github_pr(
     description: String,
     source_branch: String,
     target_branch: String,
-) -> Result<MergeRequestInfo, String> {
+    auto_push: bool,
+) -> Result<MergeRequestInfo, PivoError> {
     let config_service = config_state.lock().await;
     let github_config = config_service.get_github_config()
         .ok_or("GitHub not configured")?
@@ -47,6 +51,31 @@ pub async fn create_github_pr(
         .ok_or("Invalid remote URL")?;
     
     let github_service = GitHubService::new(github_config);
+    
+    let attempt_uuid = Uuid::parse_str(&task_attempt_id).map_err(PivoError::from)?;
+    let attempt = app_state.task_service.get_task_attempt(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or(PivoError::NotFound("Task attempt not found".to_string()))?;
+    // Attempts of a project without git run in the project directory, on no branch
+    if attempt.branch.is_empty() {
+        return Err(PivoError::not_git_repository());
+    }
+    let worktree_path = Path::new(&attempt.worktree_path);
+    if worktree_path.is_dir() {
+        ensure_branch_pushed(&github_service, worktree_path, &source_branch, auto_push).await?;
+    }
+    
+    // Opening the same PR twice fails on the provider; hand back the open one instead
+    if let Some(existing) = github_service.find_open_merge_request(&remote_info, &source_branch, &target_branch).await? {
+        log::info!("GitHub PR #{} already open for {} -> {}", existing.number, source_branch, target_branch);
+        app_state.merge_request_service
+            .sync_merge_request_from_api("github", existing.id, github_merge_request_data(task_attempt_id, &existing))
+            .await
+            .map_err(PivoError::from)?;
+        return Ok(existing);
+    }
+    
     let pr_info = github_service.create_merge_request(
         &remote_info,
         &title,
@@ -56,30 +85,9 @@ pub async fn create_github_pr(
     ).await?;
     
     // Store PR in database
-    let pr_data = CreateMergeRequestData {
-        task_attempt_id,
-        provider: "github".to_string(),
-        mr_id: pr_info.id,
-        mr_iid: pr_info.iid,
-        mr_number: pr_info.number,
-        title: pr_info.title.clone(),
-        description: pr_info.description.clone(),
-        state: format!("{:?}", pr_info.state).to_lowercase(),
-        source_branch: pr_info.source_branch.clone(),
-        target_branch: pr_info.target_branch.clone(),
-        web_url: pr_info.web_url.clone(),
-        merge_status: pr_info.merge_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
-        has_conflicts: pr_info.has_conflicts,
-        pipeline_status: pr_info.pipeline_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
-        pipeline_url: None,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-        merged_at: None,
-    };
-    
-    app_state.merge_request_service.create_merge_request(pr_data)
+    app_state.merge_request_service.create_merge_request(github_merge_request_data(task_attempt_id, &pr_info))
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
     
     Ok(pr_info)
 }
@@ -91,7 +99,7 @@ pub async fn get_github_pr_status(
     task_attempt_id: String,
     remote_url: String,
     pr_number: i64,
-) -> Result<MergeRequestInfo, String> {
+) -> Result<MergeRequestInfo, PivoError> {
     let config_service = config_state.lock().await;
     let github_config = config_service.get_github_config()
         .ok_or("GitHub not configured")?
@@ -121,7 +129,8 @@ pub async fn get_github_pr_status(
         merge_status: pr_info.merge_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
         has_conflicts: pr_info.has_conflicts,
         pipeline_status: pr_info.pipeline_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
-        pipeline_url: None,
+        pipeline_url: pr_info.pipeline_url.clone(),
+        pipeline_jobs: pr_info.pipeline_jobs.clone(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         merged_at: None,
@@ -129,18 +138,63 @@ pub async fn get_github_pr_status(
     
     app_state.merge_request_service.sync_merge_request_from_api("github", pr_info.id, pr_data)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
     
     Ok(pr_info)
 }
 
+/// Branches of the repository behind `remote_url` with their CI status,
+/// used to suggest a base branch when creating a PR
+#[tauri::command]
+pub async fn list_repo_branches(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    remote_url: String,
+) -> Result<Vec<BranchStatus>, PivoError> {
+    let config_service = config_state.lock().await;
+    let github_config = config_service.get_github_config()
+        .ok_or("GitHub not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url_with_github_host(&remote_url, github_config.enterprise_host())
+        .ok_or("Invalid remote URL")?;
+    
+    let github_service = GitHubService::new(github_config);
+    github_service.list_branches_with_status(&remote_info).await
+        .map_err(PivoError::from)
+}
+
+/// Metadata of the GitHub repository behind `remote_url`, used to pre-fill a
+/// project being imported. Works without an account for public repositories.
+#[tauri::command]
+pub async fn get_repository_info(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    remote_url: String,
+) -> Result<RepositoryInfo, PivoError> {
+    let config_service = config_state.lock().await;
+    let github_config = config_service.get_github_config()
+        .cloned()
+        .unwrap_or_default();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url_with_github_host(&remote_url, github_config.enterprise_host())
+        .filter(|info| info.provider == GitProvider::GitHub)
+        .ok_or_else(|| PivoError::InvalidInput(format!("Not a GitHub remote: {}", remote_url)))?;
+    
+    let github_service = GitHubService::new(github_config);
+    github_service.get_repository_info(&remote_info).await
+        .map_err(PivoError::from)
+}
+
 #[tauri::command]
 pub async fn push_to_github(
     config_state: State<'_, Arc<Mutex<ConfigService>>>,
     repo_path: String,
     branch: String,
     force: bool,
-) -> Result<(), String> {
+) -> Result<(), PivoError> {
     let config_service = config_state.lock().await;
     let github_config = config_service.get_github_config()
         .ok_or("GitHub not configured")?
@@ -148,7 +202,13 @@ pub async fn push_to_github(
     
     drop(config_service); // Release lock
     
-    let github_service = GitHubService::new(github_config);
+    let github_service = GitHubService::new(github_config.clone());
+    
+    // SSH pushes don't use the token at all
+    if github_config.push_mode == PushMode::Ssh {
+        return github_service.push_branch(&repo_path, &branch, force).await
+            .map_err(PivoError::from);
+    }
     
     // Verify token before attempting to push
     match github_service.verify_token().await {
@@ -158,7 +218,7 @@ pub async fn push_to_github(
         },
         Err(e) => {
             log::error!("Failed to verify GitHub token: {}", e);
-            return Err(format!("GitHub token verification failed: {}", e));
+            return Err(PivoError::from(format!("GitHub token verification failed: {}", e)));
         }
     }
     
@@ -186,7 +246,7 @@ pub async fn push_to_github(
         Ok(has_access) => {
             if !has_access {
                 log::error!("No access to 12Particles organization");
-                return Err("OAuth App does not have access to 12Particles organization. Please grant access at: https://github.com/settings/connections/applications/Ov23limL5nB8uf0tDrQX".to_string());
+                return Err(PivoError::from("OAuth App does not have access to 12Particles organization. Please grant access at: https://github.com/settings/connections/applications/Ov23limL5nB8uf0tDrQX".to_string()));
             }
         },
         Err(e) => {
@@ -195,13 +255,156 @@ pub async fn push_to_github(
     }
     
     github_service.push_branch(&repo_path, &branch, force).await
+        .map_err(PivoError::from)
+}
+
+fn github_merge_request_data(task_attempt_id: String, pr_info: &MergeRequestInfo) -> CreateMergeRequestData {
+    CreateMergeRequestData {
+        task_attempt_id,
+        provider: "github".to_string(),
+        mr_id: pr_info.id,
+        mr_iid: pr_info.iid,
+        mr_number: pr_info.number,
+        title: pr_info.title.clone(),
+        description: pr_info.description.clone(),
+        state: format!("{:?}", pr_info.state).to_lowercase(),
+        source_branch: pr_info.source_branch.clone(),
+        target_branch: pr_info.target_branch.clone(),
+        web_url: pr_info.web_url.clone(),
+        merge_status: pr_info.merge_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
+        has_conflicts: pr_info.has_conflicts,
+        pipeline_status: pr_info.pipeline_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
+        pipeline_url: pr_info.pipeline_url.clone(),
+        pipeline_jobs: pr_info.pipeline_jobs.clone(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        merged_at: if pr_info.state == MergeRequestState::Merged { Some(Utc::now()) } else { None },
+    }
+}
+
+#[tauri::command]
+pub async fn merge_github_pr(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    app_handle: AppHandle,
+    task_attempt_id: String,
+    remote_url: String,
+    pr_number: i64,
+    merge_method: MergeMethod,
+    delete_source_branch: bool,
+) -> Result<MergeRequestInfo, PivoError> {
+    let config_service = config_state.lock().await;
+    let github_config = config_service.get_github_config()
+        .ok_or("GitHub not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let github_service = GitHubService::new(github_config);
+    let pr_info = github_service.merge_merge_request(
+        &remote_info,
+        pr_number,
+        &merge_method,
+        delete_source_branch,
+    ).await?;
+    
+    let pr_data = github_merge_request_data(task_attempt_id.clone(), &pr_info);
+    app_state.merge_request_service.sync_merge_request_from_api("github", pr_info.id, pr_data)
+        .await
+        .map_err(PivoError::from)?;
+    
+    // The sync service only polls open PRs, so flip the task here
+    if pr_info.state == MergeRequestState::Merged {
+        crate::commands::tasks::mark_attempt_task_done(&app_state, &app_handle, &task_attempt_id).await?;
+    }
+    
+    Ok(pr_info)
+}
+
+#[tauri::command]
+pub async fn close_github_pr(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    remote_url: String,
+    pr_number: i64,
+) -> Result<MergeRequestInfo, PivoError> {
+    let config_service = config_state.lock().await;
+    let github_config = config_service.get_github_config()
+        .ok_or("GitHub not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let github_service = GitHubService::new(github_config);
+    let pr_info = github_service.close_merge_request(&remote_info, pr_number).await?;
+    
+    let pr_data = github_merge_request_data(task_attempt_id, &pr_info);
+    app_state.merge_request_service.sync_merge_request_from_api("github", pr_info.id, pr_data)
+        .await
+        .map_err(PivoError::from)?;
+    
+    Ok(pr_info)
+}
+
+#[tauri::command]
+pub async fn list_pr_comments(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    remote_url: String,
+    pr_number: i64,
+) -> Result<Vec<MergeRequestComment>, PivoError> {
+    let config_service = config_state.lock().await;
+    let github_config = config_service.get_github_config()
+        .ok_or("GitHub not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let github_service = GitHubService::new(github_config);
+    github_service.list_comments(&remote_info, pr_number).await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn create_pr_comment(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    remote_url: String,
+    pr_number: i64,
+    body: String,
+) -> Result<MergeRequestComment, PivoError> {
+    if body.trim().is_empty() {
+        return Err(PivoError::InvalidInput("Comment body cannot be empty".to_string()));
+    }
+    
+    let config_service = config_state.lock().await;
+    let github_config = config_service.get_github_config()
+        .ok_or("GitHub not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let github_service = GitHubService::new(github_config);
+    github_service.post_comment(&remote_info, pr_number, &body).await
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
 pub async fn get_pull_requests_by_attempt(
     app_state: State<'_, AppState>,
     task_attempt_id: String,
-) -> Result<Vec<MergeRequestInfo>, String> {
+) -> Result<Vec<MergeRequestInfo>, PivoError> {
     app_state.merge_request_service
         .get_merge_requests_by_attempt(&task_attempt_id)
         .await
@@ -219,18 +422,20 @@ pub async fn get_pull_requests_by_attempt(
                 merge_status: mr.merge_status.and_then(|s| s.parse().ok()),
                 has_conflicts: mr.has_conflicts,
                 pipeline_status: mr.pipeline_status.and_then(|s| s.parse().ok()),
+                pipeline_url: mr.pipeline_url,
+                pipeline_jobs: mr.pipeline_jobs,
                 created_at: mr.created_at.to_rfc3339(),
                 updated_at: mr.updated_at.to_rfc3339(),
             }
         }).collect())
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
 pub async fn get_pull_requests_by_task(
     app_state: State<'_, AppState>,
     task_id: String,
-) -> Result<Vec<MergeRequestInfo>, String> {
+) -> Result<Vec<MergeRequestInfo>, PivoError> {
     app_state.merge_request_service
         .get_merge_requests_by_task(&task_id)
         .await
@@ -248,11 +453,13 @@ pub async fn get_pull_requests_by_task(
                 merge_status: mr.merge_status.and_then(|s| s.parse().ok()),
                 has_conflicts: mr.has_conflicts,
                 pipeline_status: mr.pipeline_status.and_then(|s| s.parse().ok()),
+                pipeline_url: mr.pipeline_url,
+                pipeline_jobs: mr.pipeline_jobs,
                 created_at: mr.created_at.to_rfc3339(),
                 updated_at: mr.updated_at.to_rfc3339(),
             }
         }).collect())
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 use serde::{Serialize, Deserialize};
@@ -269,15 +476,21 @@ pub struct DeviceCodeResponse {
 
 
 #[tauri::command]
-pub async fn github_start_device_flow() -> Result<DeviceCodeResponse, String> {
+pub async fn github_start_device_flow(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<DeviceCodeResponse, PivoError> {
+    let web_base_url = config_state.lock().await
+        .get_github_config()
+        .map(|config| config.web_base_url())
+        .unwrap_or_else(|| "https://github.com".to_string());
     let client_id = "Ov23limL5nB8uf0tDrQX"; // Your GitHub OAuth App Client ID - Note: First character is letter O, not zero
     
     log::info!("Starting GitHub device flow with client_id: {}", client_id);
     
-    let client = reqwest::Client::new();
+    let client = crate::utils::http::client();
     
     // Build the request
-    let url = "https://github.com/login/device/code";
+    let url = format!("{}/login/device/code", web_base_url);
     log::info!("Sending POST request to: {}", url);
     
     // Build form body WITHOUT client_secret - Device Flow doesn't need it
@@ -286,7 +499,7 @@ pub async fn github_start_device_flow() -> Result<DeviceCodeResponse, String> {
     log::info!("Request body: {}", body);
     
     let response = client
-        .post(url)
+        .post(&url)
         .header("Accept", "application/json")
         .header("Content-Type", "application/x-www-form-urlencoded")
         .header("User-Agent", "pivo-app")
@@ -302,17 +515,17 @@ pub async fn github_start_device_flow() -> Result<DeviceCodeResponse, String> {
         let error_text = response.text().await.unwrap_or_default();
         
         if status == 404 {
-            return Err(format!(
+            return Err(PivoError::from(format!(
                 "GitHub Device Flow API not found (404). Please ensure:\n\
                 1. Device Flow is enabled in your GitHub OAuth App settings\n\
                 2. Go to GitHub Settings -> Developer settings -> OAuth Apps\n\
                 3. Edit your app and enable 'Device Flow'\n\
                 Error details: {}", 
                 error_text
-            ));
+            )));
         }
         
-        return Err(format!("GitHub API error: {} - {}", status, error_text));
+        return Err(PivoError::from(format!("GitHub API error: {} - {}", status, error_text)));
     }
     
     let device_code_response = response
@@ -327,14 +540,19 @@ pub async fn github_start_device_flow() -> Result<DeviceCodeResponse, String> {
 pub async fn github_poll_device_auth(
     config_state: State<'_, Arc<Mutex<ConfigService>>>,
     device_code: String,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, PivoError> {
     let client_id = "Ov23limL5nB8uf0tDrQX"; // Your GitHub OAuth App Client ID - Note: First character is letter O, not zero
     
     log::debug!("Polling device auth for device_code: {}", device_code);
     
-    let client = reqwest::Client::new();
+    let web_base_url = config_state.lock().await
+        .get_github_config()
+        .map(|config| config.web_base_url())
+        .unwrap_or_else(|| "https://github.com".to_string());
+    
+    let client = crate::utils::http::client();
     let response = client
-        .post("https://github.com/login/oauth/access_token")
+        .post(format!("{}/login/oauth/access_token", web_base_url))
         .header("Accept", "application/json")
         .form(&[
             ("client_id", client_id),
@@ -409,6 +627,36 @@ pub async fn github_poll_device_auth(
         }
     } else {
         log::error!("Unexpected response format: {:?}", json_response);
-        Err("Unexpected response format".to_string())
+        Err(PivoError::from("Unexpected response format".to_string()))
     }
-}
\ No newline at end of file
+}
+
+/// Refresh all open pull requests of a project's tasks from GitHub at once
+#[tauri::command]
+pub async fn sync_project_pull_requests(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    app_handle: AppHandle,
+    project_id: String,
+) -> Result<Vec<crate::models::MergeRequest>, PivoError> {
+    let config_service = config_state.lock().await;
+    let github_config = config_service.get_github_config()
+        .ok_or("GitHub not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let github_service = GitHubService::new(github_config);
+    let updated = app_state.merge_request_service
+        .sync_all_for_project(&project_id, None, Some(&github_service))
+        .await
+        .map_err(PivoError::from)?;
+    
+    let _ = app_handle.emit("vcs:project-sync-completed", serde_json::json!({
+        "projectId": project_id,
+        "provider": "github",
+        "updatedCount": updated.len(),
+    }));
+    
+    Ok(updated)
+}
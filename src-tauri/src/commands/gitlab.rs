@@ -1,27 +1,61 @@
-use crate::models::{GitLabConfig, MergeRequestInfo, GitRemoteInfo, CreateMergeRequestData};
-use crate::services::{ConfigService, GitLabService, GitPlatformService};
+use crate::models::{ApprovalState, GitLabConfig, GitLabConnectionTest, MergeRequestInfo, GitRemoteInfo, CreateMergeRequestData, MergeRequestState, MergeMethod, MergeRequestComment};
+use crate::services::{detect_remote_info, ensure_branch_pushed, ConfigService, GitLabService, GitPlatformService};
 use crate::AppState;
+use crate::error::PivoError;
+use std::path::Path;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex;
 use chrono::Utc;
+use uuid::Uuid;
 
 #[tauri::command]
 pub async fn get_gitlab_config(
     state: State<'_, Arc<Mutex<ConfigService>>>,
-) -> Result<Option<GitLabConfig>, String> {
+) -> Result<Option<GitLabConfig>, PivoError> {
     let config_service = state.lock().await;
     Ok(config_service.get_gitlab_config().cloned())
 }
 
+/// Save the GitLab settings. With `validate`, the token is checked against the
+/// instance first and a missing username/email is filled in from the account.
 #[tauri::command]
 pub async fn update_gitlab_config(
     state: State<'_, Arc<Mutex<ConfigService>>>,
     config: GitLabConfig,
-) -> Result<(), String> {
+    validate: Option<bool>,
+) -> Result<(), PivoError> {
+    let mut config = config;
+    if validate.unwrap_or(false) {
+        let user = GitLabService::new(config.clone()).verify_token().await?;
+        if config.username.as_deref().is_none_or(str::is_empty) {
+            config.username = Some(user.username);
+        }
+        if config.primary_email.as_deref().is_none_or(str::is_empty) {
+            config.primary_email = user.email;
+        }
+    }
+    
     let mut config_service = state.lock().await;
     config_service.update_gitlab_config(config).await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
+}
+
+/// Check a GitLab URL and token without saving them. Falls back to the saved
+/// settings when `config` is omitted.
+#[tauri::command]
+pub async fn test_gitlab_connection(
+    state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: Option<GitLabConfig>,
+) -> Result<GitLabConnectionTest, PivoError> {
+    let config = match config {
+        Some(config) => config,
+        None => state.lock().await.get_gitlab_config()
+            .cloned()
+            .ok_or_else(|| PivoError::VcsNotConfigured("GitLab not configured".to_string()))?,
+    };
+    
+    Ok(GitLabService::new(config).verify_token().await.into())
 }
 
 #[tauri::command]
@@ -34,7 +68,8 @@ pub async fn create_gitlab_mr(
     description: String,
     source_branch: String,
     target_branch: String,
-) -> Result<MergeRequestInfo, String> {
+    auto_push: bool,
+) -> Result<MergeRequestInfo, PivoError> {
     let config_service = config_state.lock().await;
     let gitlab_config = config_service.get_gitlab_config()
         .ok_or("GitLab not configured")?
@@ -46,6 +81,31 @@ pub async fn create_gitlab_mr(
         .ok_or("Invalid remote URL")?;
     
     let gitlab_service = GitLabService::new(gitlab_config);
+    
+    let attempt_uuid = Uuid::parse_str(&task_attempt_id).map_err(PivoError::from)?;
+    let attempt = app_state.task_service.get_task_attempt(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or(PivoError::NotFound("Task attempt not found".to_string()))?;
+    // Attempts of a project without git run in the project directory, on no branch
+    if attempt.branch.is_empty() {
+        return Err(PivoError::not_git_repository());
+    }
+    let worktree_path = Path::new(&attempt.worktree_path);
+    if worktree_path.is_dir() {
+        ensure_branch_pushed(&gitlab_service, worktree_path, &source_branch, auto_push).await?;
+    }
+    
+    // Opening the same MR twice fails on the provider; hand back the open one instead
+    if let Some(existing) = gitlab_service.find_open_merge_request(&remote_info, &source_branch, &target_branch).await? {
+        log::info!("GitLab MR !{} already open for {} -> {}", existing.number, source_branch, target_branch);
+        app_state.merge_request_service
+            .sync_merge_request_from_api("gitlab", existing.id, gitlab_merge_request_data(task_attempt_id, &existing))
+            .await
+            .map_err(PivoError::from)?;
+        return Ok(existing);
+    }
+    
     let mr_info = gitlab_service.create_merge_request(
         &remote_info,
         &title,
@@ -55,6 +115,35 @@ pub async fn create_gitlab_mr(
     ).await?;
     
     // Store MR in database
+    app_state.merge_request_service.create_merge_request(gitlab_merge_request_data(task_attempt_id, &mr_info))
+        .await
+        .map_err(PivoError::from)?;
+    
+    Ok(mr_info)
+}
+
+#[tauri::command]
+pub async fn get_gitlab_mr_status(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    remote_url: String,
+    mr_number: i64,
+) -> Result<MergeRequestInfo, PivoError> {
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let gitlab_service = GitLabService::new(gitlab_config);
+    let mr_info = gitlab_service.update_merge_request_status(&remote_info, mr_number).await?;
+    
+    // Sync MR to database
     let mr_data = CreateMergeRequestData {
         task_attempt_id,
         provider: "gitlab".to_string(),
@@ -70,27 +159,27 @@ pub async fn create_gitlab_mr(
         merge_status: mr_info.merge_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
         has_conflicts: mr_info.has_conflicts,
         pipeline_status: mr_info.pipeline_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
-        pipeline_url: None, // TODO: Get from API if available
+        pipeline_url: mr_info.pipeline_url.clone(),
+        pipeline_jobs: mr_info.pipeline_jobs.clone(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         merged_at: None,
     };
     
-    app_state.merge_request_service.create_merge_request(mr_data)
+    app_state.merge_request_service.sync_merge_request_from_api("gitlab", mr_info.id, mr_data)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
     
     Ok(mr_info)
 }
 
 #[tauri::command]
-pub async fn get_gitlab_mr_status(
+pub async fn push_to_gitlab(
     config_state: State<'_, Arc<Mutex<ConfigService>>>,
-    app_state: State<'_, AppState>,
-    task_attempt_id: String,
-    remote_url: String,
-    mr_number: i64,
-) -> Result<MergeRequestInfo, String> {
+    repo_path: String,
+    branch: String,
+    force: bool,
+) -> Result<(), PivoError> {
     let config_service = config_state.lock().await;
     let gitlab_config = config_service.get_gitlab_config()
         .ok_or("GitLab not configured")?
@@ -98,14 +187,13 @@ pub async fn get_gitlab_mr_status(
     
     drop(config_service); // Release lock
     
-    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
-        .ok_or("Invalid remote URL")?;
-    
     let gitlab_service = GitLabService::new(gitlab_config);
-    let mr_info = gitlab_service.update_merge_request_status(&remote_info, mr_number).await?;
-    
-    // Sync MR to database
-    let mr_data = CreateMergeRequestData {
+    gitlab_service.push_branch(&repo_path, &branch, force).await
+        .map_err(PivoError::from)
+}
+
+fn gitlab_merge_request_data(task_attempt_id: String, mr_info: &MergeRequestInfo) -> CreateMergeRequestData {
+    CreateMergeRequestData {
         task_attempt_id,
         provider: "gitlab".to_string(),
         mr_id: mr_info.id,
@@ -120,26 +208,64 @@ pub async fn get_gitlab_mr_status(
         merge_status: mr_info.merge_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
         has_conflicts: mr_info.has_conflicts,
         pipeline_status: mr_info.pipeline_status.as_ref().map(|s| format!("{:?}", s).to_lowercase()),
-        pipeline_url: None, // TODO: Get from API if available
+        pipeline_url: mr_info.pipeline_url.clone(),
+        pipeline_jobs: mr_info.pipeline_jobs.clone(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
-        merged_at: None,
-    };
+        merged_at: if mr_info.state == MergeRequestState::Merged { Some(Utc::now()) } else { None },
+    }
+}
+
+#[tauri::command]
+pub async fn merge_gitlab_mr(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    app_handle: AppHandle,
+    task_attempt_id: String,
+    remote_url: String,
+    mr_number: i64,
+    merge_method: MergeMethod,
+    delete_source_branch: bool,
+) -> Result<MergeRequestInfo, PivoError> {
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let gitlab_service = GitLabService::new(gitlab_config);
+    let mr_info = gitlab_service.merge_merge_request(
+        &remote_info,
+        mr_number,
+        &merge_method,
+        delete_source_branch,
+    ).await?;
     
+    let mr_data = gitlab_merge_request_data(task_attempt_id.clone(), &mr_info);
     app_state.merge_request_service.sync_merge_request_from_api("gitlab", mr_info.id, mr_data)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
+    
+    // The sync service only polls open MRs, so flip the task here
+    if mr_info.state == MergeRequestState::Merged {
+        crate::commands::tasks::mark_attempt_task_done(&app_state, &app_handle, &task_attempt_id).await?;
+    }
     
     Ok(mr_info)
 }
 
 #[tauri::command]
-pub async fn push_to_gitlab(
+pub async fn close_gitlab_mr(
     config_state: State<'_, Arc<Mutex<ConfigService>>>,
-    repo_path: String,
-    branch: String,
-    force: bool,
-) -> Result<(), String> {
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    remote_url: String,
+    mr_number: i64,
+) -> Result<MergeRequestInfo, PivoError> {
     let config_service = config_state.lock().await;
     let gitlab_config = config_service.get_gitlab_config()
         .ok_or("GitLab not configured")?
@@ -147,15 +273,171 @@ pub async fn push_to_gitlab(
     
     drop(config_service); // Release lock
     
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
     let gitlab_service = GitLabService::new(gitlab_config);
-    gitlab_service.push_branch(&repo_path, &branch, force).await
+    let mr_info = gitlab_service.close_merge_request(&remote_info, mr_number).await?;
+    
+    let mr_data = gitlab_merge_request_data(task_attempt_id, &mr_info);
+    app_state.merge_request_service.sync_merge_request_from_api("gitlab", mr_info.id, mr_data)
+        .await
+        .map_err(PivoError::from)?;
+    
+    Ok(mr_info)
+}
+
+/// Fetch the approvals of an attempt's MR and remember how many it has
+async fn sync_approval_state(
+    app_state: &AppState,
+    gitlab_service: &GitLabService,
+    remote_info: &GitRemoteInfo,
+    task_attempt_id: &str,
+    mr_number: i64,
+) -> Result<ApprovalState, PivoError> {
+    let approvals = gitlab_service.get_approval_state(remote_info, mr_number).await?;
+    
+    app_state.merge_request_service
+        .update_approval_count(task_attempt_id, "gitlab", mr_number, approvals.approved_by.len() as i64)
+        .await
+        .map_err(PivoError::from)?;
+    
+    Ok(approvals)
 }
 
 #[tauri::command]
-pub async fn detect_git_provider(remote_url: String) -> Result<String, String> {
+pub async fn approve_gitlab_mr(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    remote_url: String,
+    mr_number: i64,
+) -> Result<ApprovalState, PivoError> {
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let gitlab_service = GitLabService::new(gitlab_config);
+    gitlab_service.approve_mr(&remote_info, mr_number).await?;
+    
+    sync_approval_state(&app_state, &gitlab_service, &remote_info, &task_attempt_id, mr_number).await
+}
+
+#[tauri::command]
+pub async fn unapprove_gitlab_mr(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    remote_url: String,
+    mr_number: i64,
+) -> Result<ApprovalState, PivoError> {
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let gitlab_service = GitLabService::new(gitlab_config);
+    gitlab_service.unapprove_mr(&remote_info, mr_number).await?;
+    
+    sync_approval_state(&app_state, &gitlab_service, &remote_info, &task_attempt_id, mr_number).await
+}
+
+#[tauri::command]
+pub async fn get_gitlab_mr_approvals(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    remote_url: String,
+    mr_number: i64,
+) -> Result<ApprovalState, PivoError> {
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let gitlab_service = GitLabService::new(gitlab_config);
+    sync_approval_state(&app_state, &gitlab_service, &remote_info, &task_attempt_id, mr_number).await
+}
+
+#[tauri::command]
+pub async fn list_mr_comments(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    remote_url: String,
+    mr_number: i64,
+) -> Result<Vec<MergeRequestComment>, PivoError> {
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
+        .ok_or("Invalid remote URL")?;
+    
+    let gitlab_service = GitLabService::new(gitlab_config);
+    gitlab_service.list_comments(&remote_info, mr_number).await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn create_mr_comment(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    remote_url: String,
+    mr_number: i64,
+    body: String,
+) -> Result<MergeRequestComment, PivoError> {
+    if body.trim().is_empty() {
+        return Err(PivoError::InvalidInput("Comment body cannot be empty".to_string()));
+    }
+    
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
     let remote_info = GitRemoteInfo::from_remote_url(&remote_url)
         .ok_or("Invalid remote URL")?;
     
+    let gitlab_service = GitLabService::new(gitlab_config);
+    gitlab_service.post_comment(&remote_info, mr_number, &body).await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn detect_git_provider(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    remote_url: String,
+) -> Result<String, PivoError> {
+    let config_service = config_state.lock().await;
+    let enterprise_host = config_service.get_github_config()
+        .and_then(|config| config.enterprise_host())
+        .map(|host| host.to_string());
+    let probe = config_service.get_git_hosts_config().probe_unknown_hosts;
+    
+    drop(config_service); // Release lock
+    
+    let remote_info = detect_remote_info(&remote_url, enterprise_host.as_deref(), probe).await
+        .ok_or("Invalid remote URL")?;
+    
     Ok(remote_info.provider.display_name().to_string())
 }
 
@@ -163,31 +445,61 @@ pub async fn detect_git_provider(remote_url: String) -> Result<String, String> {
 pub async fn get_merge_requests_by_attempt(
     app_state: State<'_, AppState>,
     task_attempt_id: String,
-) -> Result<Vec<crate::models::MergeRequest>, String> {
+) -> Result<Vec<crate::models::MergeRequest>, PivoError> {
     app_state.merge_request_service
         .get_merge_requests_by_attempt(&task_attempt_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
 pub async fn get_merge_requests_by_task(
     app_state: State<'_, AppState>,
     task_id: String,
-) -> Result<Vec<crate::models::MergeRequest>, String> {
+) -> Result<Vec<crate::models::MergeRequest>, PivoError> {
     app_state.merge_request_service
         .get_merge_requests_by_task(&task_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
 pub async fn get_active_merge_requests(
     app_state: State<'_, AppState>,
     provider: Option<String>,
-) -> Result<Vec<crate::models::MergeRequest>, String> {
+) -> Result<Vec<crate::models::MergeRequest>, PivoError> {
     app_state.merge_request_service
         .get_active_merge_requests(provider.as_deref())
         .await
-        .map_err(|e| e.to_string())
-}
\ No newline at end of file
+        .map_err(PivoError::from)
+}
+
+/// Refresh all open merge requests of a project's tasks from GitLab at once
+#[tauri::command]
+pub async fn sync_project_merge_requests(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    app_handle: AppHandle,
+    project_id: String,
+) -> Result<Vec<crate::models::MergeRequest>, PivoError> {
+    let config_service = config_state.lock().await;
+    let gitlab_config = config_service.get_gitlab_config()
+        .ok_or("GitLab not configured")?
+        .clone();
+    
+    drop(config_service); // Release lock
+    
+    let gitlab_service = GitLabService::new(gitlab_config);
+    let updated = app_state.merge_request_service
+        .sync_all_for_project(&project_id, Some(&gitlab_service), None)
+        .await
+        .map_err(PivoError::from)?;
+    
+    let _ = app_handle.emit("vcs:project-sync-completed", serde_json::json!({
+        "projectId": project_id,
+        "provider": "gitlab",
+        "updatedCount": updated.len(),
+    }));
+    
+    Ok(updated)
+}
@@ -1,5 +1,17 @@
 use crate::logging::get_log_file_path;
+use crate::models::{LogEntry, LogLevel};
+use crate::services::LoggingService;
+use crate::services::coding_agent_executor::raw_log::{get_execution_log_path, get_rotated_log_path};
 use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Default number of bytes returned by get_execution_raw_log
+const DEFAULT_RAW_LOG_LIMIT: u64 = 256 * 1024;
+
+/// Default page size of get_log_entries
+const DEFAULT_LOG_ENTRY_LIMIT: usize = 200;
 
 #[tauri::command]
 pub async fn get_log_content(
@@ -24,6 +36,25 @@ pub async fn get_log_content(
     }
 }
 
+/// Parsed log entries, newest first, for the log viewer
+#[tauri::command]
+pub async fn get_log_entries(
+    level_filter: Option<LogLevel>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    LoggingService::new().get_log_entries(
+        level_filter,
+        limit.unwrap_or(DEFAULT_LOG_ENTRY_LIMIT),
+        offset.unwrap_or(0),
+    )
+}
+
+#[tauri::command]
+pub async fn get_log_entry_count(level_filter: Option<LogLevel>) -> Result<usize, String> {
+    LoggingService::new().get_log_entry_count(level_filter)
+}
+
 #[tauri::command]
 pub async fn get_log_path() -> Result<String, String> {
     Ok(get_log_file_path().to_string_lossy().to_string())
@@ -72,5 +103,92 @@ pub async fn clear_logs() -> Result<(), String> {
     }
     
     log::info!("Logs cleared");
+    Ok(())
+}
+
+fn resolve_execution_log_path(execution_id: &str) -> Result<PathBuf, String> {
+    // Execution ids are UUIDs; reject anything else so the id can't escape the log dir
+    Uuid::parse_str(execution_id).map_err(|e| e.to_string())?;
+    
+    let log_path = get_execution_log_path(execution_id);
+    if !log_path.exists() {
+        return Err(format!("No log file found for execution {}", execution_id));
+    }
+    
+    Ok(log_path)
+}
+
+#[tauri::command]
+pub async fn get_execution_raw_log(
+    execution_id: String,
+    offset: Option<u64>,
+    limit_bytes: Option<u64>,
+) -> Result<String, String> {
+    let log_path = resolve_execution_log_path(&execution_id)?;
+    
+    // The rotated file holds the older lines; offsets run across both files
+    let mut offset = offset.unwrap_or(0);
+    let mut remaining = limit_bytes.unwrap_or(DEFAULT_RAW_LOG_LIMIT);
+    let mut buffer = Vec::new();
+    for path in [get_rotated_log_path(&log_path), log_path] {
+        if remaining == 0 {
+            break;
+        }
+        if !path.exists() {
+            continue;
+        }
+        
+        let mut file = fs::File::open(&path)
+            .map_err(|e| format!("Failed to open execution log: {}", e))?;
+        let len = file.metadata()
+            .map_err(|e| format!("Failed to read execution log: {}", e))?
+            .len();
+        if offset >= len {
+            offset -= len;
+            continue;
+        }
+        
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("Failed to seek execution log: {}", e))?;
+        offset = 0;
+        
+        let read = file.take(remaining)
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("Failed to read execution log: {}", e))?;
+        remaining -= read as u64;
+    }
+    
+    Ok(String::from_utf8_lossy(&buffer).to_string())
+}
+
+#[tauri::command]
+pub async fn open_execution_log_file(execution_id: String) -> Result<(), String> {
+    let log_path = resolve_execution_log_path(&execution_id)?;
+    
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("-t")
+            .arg(&log_path)
+            .spawn()
+            .map_err(|e| format!("Failed to open execution log: {}", e))?;
+    }
+    
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("notepad")
+            .arg(&log_path)
+            .spawn()
+            .map_err(|e| format!("Failed to open execution log: {}", e))?;
+    }
+    
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(&log_path)
+            .spawn()
+            .map_err(|e| format!("Failed to open execution log: {}", e))?;
+    }
+    
     Ok(())
 }
\ No newline at end of file
@@ -1,9 +1,10 @@
-use crate::services::mcp_server::{
-    McpServer, McpServerManager, McpCapabilities, McpServerStatus
-};
+use crate::services::mcp_server::{McpServer, McpServerManager, NewMcpServer};
+use crate::AppState;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::State;
 use serde_json::Value;
+use uuid::Uuid;
 
 pub struct McpState {
     pub manager: Arc<McpServerManager>,
@@ -16,22 +17,49 @@ pub async fn register_mcp_server(
     command: String,
     args: Vec<String>,
     env: std::collections::HashMap<String, String>,
+    auto_restart: Option<bool>,
+    health_check_interval_seconds: Option<u64>,
+    request_timeout_seconds: Option<u64>,
+    log_lines: Option<usize>,
 ) -> Result<String, String> {
-    let server = McpServer {
-        id: uuid::Uuid::new_v4().to_string(),
+    let server = NewMcpServer {
         name,
         command,
         args,
         env,
-        capabilities: McpCapabilities {
-            tools: true,
-            resources: true,
-            prompts: true,
-        },
-        status: McpServerStatus::Stopped,
+        auto_restart: auto_restart.unwrap_or(false),
+        health_check_interval_seconds,
+        request_timeout_seconds,
+        log_lines,
     };
 
-    state.manager.register_server(server)
+    state.manager.register_server(server.into_server(None)).await
+}
+
+/// Register a server only this project's agent sessions use
+#[tauri::command]
+pub async fn register_project_mcp_server(
+    state: State<'_, McpState>,
+    app_state: State<'_, AppState>,
+    project_id: String,
+    server: NewMcpServer,
+) -> Result<String, String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    app_state.project_service
+        .get_project(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+
+    state.manager.register_server(server.into_server(Some(project_id))).await
+}
+
+#[tauri::command]
+pub async fn unregister_mcp_server(
+    state: State<'_, McpState>,
+    server_id: String,
+) -> Result<(), String> {
+    state.manager.unregister_server(&server_id).await
 }
 
 #[tauri::command]
@@ -50,11 +78,21 @@ pub async fn stop_mcp_server(
     state.manager.stop_server(&server_id)
 }
 
+#[tauri::command]
+pub async fn restart_mcp_server(
+    state: State<'_, McpState>,
+    server_id: String,
+) -> Result<(), String> {
+    state.manager.restart_server(&server_id)
+}
+
+/// Every server, or with `project_id` the global ones and that project's own
 #[tauri::command]
 pub async fn list_mcp_servers(
     state: State<'_, McpState>,
+    project_id: Option<String>,
 ) -> Result<Vec<McpServer>, String> {
-    Ok(state.manager.list_servers())
+    Ok(state.manager.list_servers(project_id.as_deref()))
 }
 
 #[tauri::command]
@@ -65,22 +103,45 @@ pub async fn get_mcp_server(
     Ok(state.manager.get_server(&server_id))
 }
 
+/// The last `tail` lines of a server's stderr
+#[tauri::command]
+pub async fn get_mcp_server_logs(
+    state: State<'_, McpState>,
+    server_id: String,
+    tail: usize,
+) -> Result<Vec<String>, String> {
+    state.manager.get_server_logs(&server_id, tail)
+}
+
 #[tauri::command]
 pub async fn send_mcp_request(
     state: State<'_, McpState>,
     server_id: String,
     method: String,
     params: Option<Value>,
-) -> Result<String, String> {
-    state.manager.send_request(&server_id, &method, params)
+    timeout_seconds: Option<u64>,
+) -> Result<Value, String> {
+    state.manager
+        .send_request(&server_id, &method, params, timeout_seconds.map(Duration::from_secs))
+        .await
 }
 
 #[tauri::command]
 pub async fn list_mcp_tools(
     state: State<'_, McpState>,
     server_id: String,
-) -> Result<String, String> {
-    state.manager.send_request(&server_id, "tools/list", None)
+) -> Result<Value, String> {
+    state.manager.send_request(&server_id, "tools/list", None, None).await
+}
+
+#[tauri::command]
+pub async fn call_mcp_tool(
+    state: State<'_, McpState>,
+    server_id: String,
+    tool_name: String,
+    arguments: Value,
+) -> Result<Value, String> {
+    state.manager.call_tool(&server_id, &tool_name, arguments).await
 }
 
 
@@ -88,8 +149,8 @@ pub async fn list_mcp_tools(
 pub async fn list_mcp_resources(
     state: State<'_, McpState>,
     server_id: String,
-) -> Result<String, String> {
-    state.manager.send_request(&server_id, "resources/list", None)
+) -> Result<Value, String> {
+    state.manager.send_request(&server_id, "resources/list", None, None).await
 }
 
 #[tauri::command]
@@ -97,20 +158,21 @@ pub async fn read_mcp_resource(
     state: State<'_, McpState>,
     server_id: String,
     uri: String,
-) -> Result<String, String> {
+) -> Result<Value, String> {
     state.manager.send_request(
         &server_id,
         "resources/read",
         Some(serde_json::json!({ "uri": uri })),
-    )
+        None,
+    ).await
 }
 
 #[tauri::command]
 pub async fn list_mcp_prompts(
     state: State<'_, McpState>,
     server_id: String,
-) -> Result<String, String> {
-    state.manager.send_request(&server_id, "prompts/list", None)
+) -> Result<Value, String> {
+    state.manager.send_request(&server_id, "prompts/list", None, None).await
 }
 
 #[tauri::command]
@@ -119,7 +181,7 @@ pub async fn get_mcp_prompt(
     server_id: String,
     name: String,
     arguments: Value,
-) -> Result<String, String> {
+) -> Result<Value, String> {
     state.manager.send_request(
         &server_id,
         "prompts/get",
@@ -127,5 +189,57 @@ pub async fn get_mcp_prompt(
             "name": name,
             "arguments": arguments,
         })),
-    )
-}
\ No newline at end of file
+        None,
+    ).await
+}
+
+/// Attach a registered MCP server to a project so its agent sessions can use it
+#[tauri::command]
+pub async fn attach_mcp_server_to_project(
+    state: State<'_, McpState>,
+    app_state: State<'_, AppState>,
+    project_id: String,
+    server_id: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    let server = state.manager.get_server(&server_id).ok_or("Server not found")?;
+    if !server.is_available_to(Some(&project_id)) {
+        return Err(format!("MCP server {} belongs to another project", server.name));
+    }
+
+    app_state.project_service
+        .attach_mcp_server(uuid, &server_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn detach_mcp_server_from_project(
+    app_state: State<'_, AppState>,
+    project_id: String,
+    server_id: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    app_state.project_service
+        .detach_mcp_server(uuid, &server_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// MCP servers attached to a project that are still registered
+#[tauri::command]
+pub async fn list_project_mcp_servers(
+    state: State<'_, McpState>,
+    app_state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<McpServer>, String> {
+    let server_ids = app_state.project_service
+        .list_mcp_server_ids(&project_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(server_ids
+        .iter()
+        .filter_map(|id| state.manager.get_server(id))
+        .collect())
+}
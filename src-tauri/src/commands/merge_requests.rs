@@ -0,0 +1,110 @@
+use crate::models::{AttemptPipelineStatus, GitProvider, GitRemoteInfo, MergeRequest, MergeRequestComment, MergeRequestDraft};
+use crate::services::{ConfigService, GitHubService, GitLabService, GitPlatformService};
+use crate::AppState;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+
+/// Resolve the most recent merge request stored for an attempt together with
+/// a platform service able to talk to its provider
+async fn resolve_attempt_merge_request(
+    config_state: &Arc<Mutex<ConfigService>>,
+    app_state: &AppState,
+    task_attempt_id: &str,
+) -> Result<(MergeRequest, GitRemoteInfo, Box<dyn GitPlatformService>), String> {
+    let mr = app_state.merge_request_service
+        .get_merge_requests_by_attempt(task_attempt_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or("No merge request found for this attempt")?;
+
+    let config_service = config_state.lock().await;
+    let (provider, service): (GitProvider, Box<dyn GitPlatformService>) = match mr.provider.as_str() {
+        "github" => {
+            let github_config = config_service.get_github_config()
+                .ok_or("GitHub not configured")?
+                .clone();
+            (GitProvider::GitHub, Box::new(GitHubService::new(github_config)))
+        }
+        "gitlab" => {
+            let gitlab_config = config_service.get_gitlab_config()
+                .ok_or("GitLab not configured")?
+                .clone();
+            (GitProvider::GitLab, Box::new(GitLabService::new(gitlab_config)))
+        }
+        other => return Err(format!("Unsupported provider: {}", other)),
+    };
+    drop(config_service); // Release lock
+
+    let remote_info = GitRemoteInfo::from_merge_request_url(provider, &mr.web_url)
+        .ok_or("Invalid merge request URL")?;
+
+    Ok((mr, remote_info, service))
+}
+
+#[tauri::command]
+pub async fn list_merge_request_comments(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+) -> Result<Vec<MergeRequestComment>, String> {
+    let (mr, remote_info, service) =
+        resolve_attempt_merge_request(&config_state, &app_state, &task_attempt_id).await?;
+
+    service.list_comments(&remote_info, mr.mr_number).await
+}
+
+#[tauri::command]
+pub async fn post_merge_request_comment(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    body: String,
+) -> Result<MergeRequestComment, String> {
+    if body.trim().is_empty() {
+        return Err("Comment body cannot be empty".to_string());
+    }
+
+    let (mr, remote_info, service) =
+        resolve_attempt_merge_request(&config_state, &app_state, &task_attempt_id).await?;
+
+    service.post_comment(&remote_info, mr.mr_number, &body).await
+}
+
+/// CI status, pipeline link and individual jobs of the attempt's latest MR/PR,
+/// as last synced from the provider
+#[tauri::command]
+pub async fn get_pipeline_status_by_attempt(
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+) -> Result<Option<AttemptPipelineStatus>, String> {
+    let mr = app_state.merge_request_service
+        .get_merge_requests_by_attempt(&task_attempt_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next();
+
+    Ok(mr.map(|mr| AttemptPipelineStatus {
+        merge_request_id: mr.id,
+        status: mr.parsed_pipeline_status(),
+        url: mr.pipeline_url,
+        jobs: mr.pipeline_jobs,
+    }))
+}
+
+/// Suggested title and description for a PR/MR of the attempt, for the user to
+/// edit before creating it. `polish` lets the coding agent rewrite the description.
+#[tauri::command]
+pub async fn generate_merge_request_content(
+    app_state: State<'_, AppState>,
+    task_attempt_id: String,
+    polish: Option<bool>,
+) -> Result<MergeRequestDraft, String> {
+    app_state.merge_request_service
+        .generate_merge_request_content(&task_attempt_id, polish.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -11,7 +11,13 @@ pub mod logging;
 pub mod window;
 pub mod gitlab;
 pub mod github;
+pub mod merge_requests;
 pub mod system;
 pub mod filesystem;
 pub mod command;
-pub mod dev_server;
\ No newline at end of file
+pub mod dev_server;
+pub mod vcs_sync;
+pub mod config;
+pub mod database;
+pub mod review_comments;
+pub mod task_templates;
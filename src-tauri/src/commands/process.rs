@@ -1,6 +1,7 @@
-use crate::models::ExecutionProcess;
+use crate::models::{ExecutionProcess, OutputStreamType, ProcessOutputLine, ProcessOutputPage, ProcessType};
+use crate::utils::command::user_shell_command;
 use crate::AppState;
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
 #[tauri::command]
@@ -16,6 +17,37 @@ pub async fn get_process(
         .map_err(|e| e.to_string())
 }
 
+/// A page of a process's stdout or stderr, for outputs too long to load at once
+#[tauri::command]
+pub async fn get_process_output(
+    state: State<'_, AppState>,
+    process_id: String,
+    stream: OutputStreamType,
+    offset: usize,
+    limit: usize,
+) -> Result<ProcessOutputPage, String> {
+    let uuid = Uuid::parse_str(&process_id).map_err(|e| e.to_string())?;
+    state
+        .process_service
+        .get_process_output(uuid, stream, offset, limit)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Everything a process wrote to stdout and stderr, in the order it was written
+#[tauri::command]
+pub async fn get_process_log(
+    state: State<'_, AppState>,
+    process_id: String,
+) -> Result<Vec<ProcessOutputLine>, String> {
+    let uuid = Uuid::parse_str(&process_id).map_err(|e| e.to_string())?;
+    state
+        .process_service
+        .get_process_log(uuid)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn list_processes_for_attempt(
     state: State<'_, AppState>,
@@ -27,4 +59,82 @@ pub async fn list_processes_for_attempt(
         .list_processes_for_attempt(uuid)
         .await
         .map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+/// Run an arbitrary command (e.g. `pnpm test`) in an attempt's worktree without
+/// involving the agent. Output is streamed through `process-output` events.
+#[tauri::command]
+pub async fn run_attempt_command(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    attempt_id: String,
+    command: String,
+    args: Vec<String>,
+    env_files: Option<Vec<String>>,
+) -> Result<Uuid, String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err("Command cannot be empty".to_string());
+    }
+
+    let uuid = Uuid::parse_str(&attempt_id).map_err(|e| e.to_string())?;
+    let attempt = state
+        .task_service
+        .get_task_attempt(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task attempt not found")?;
+
+    if !std::path::Path::new(&attempt.worktree_path).is_dir() {
+        return Err(format!("Worktree {} does not exist", attempt.worktree_path));
+    }
+    let task_uuid = Uuid::parse_str(&attempt.task_id).map_err(|e| e.to_string())?;
+    let task = state
+        .task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task not found")?;
+
+    let (program, program_args) = user_shell_command(command, &args);
+
+    state
+        .process_service
+        .spawn_process(
+            &task.project_id,
+            Some(uuid),
+            ProcessType::Manual,
+            program,
+            program_args,
+            attempt.worktree_path,
+            env_files,
+            app_handle,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn kill_attempt_process(
+    state: State<'_, AppState>,
+    attempt_id: String,
+    process_id: String,
+) -> Result<(), String> {
+    let process_uuid = Uuid::parse_str(&process_id).map_err(|e| e.to_string())?;
+    let process = state
+        .process_service
+        .get_process(process_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Process not found")?;
+
+    if process.task_attempt_id.as_deref() != Some(attempt_id.as_str()) {
+        return Err("Process does not belong to this attempt".to_string());
+    }
+
+    state
+        .process_service
+        .kill_process(process_uuid)
+        .await
+        .map_err(|e| e.to_string())
+}
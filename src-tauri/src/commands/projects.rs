@@ -1,10 +1,18 @@
-use crate::models::{CreateProjectRequest, Project, UpdateProjectRequest};
+use crate::models::{
+    CreateProjectRequest, GitProvider, ImportProjectResult, Project, ProjectBundle, ProjectEnvVar, SetProjectEnvVarRequest,
+    UpdateCacheLinksRequest, UpdateProjectRequest,
+};
 use crate::AppState;
+use crate::commands::mcp::McpState;
 use crate::utils::command::execute_git;
+use crate::services::{detect_remote_info, ConfigService, GitService, ProjectService};
+use crate::utils::project_type::ProjectMarkers;
+use std::sync::Arc;
 use tauri::State;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +33,10 @@ pub async fn create_project(
     state: State<'_, AppState>,
     request: CreateProjectRequest,
 ) -> Result<Project, String> {
+    let mut request = request;
+    if let Some(subdirectory) = &request.subdirectory {
+        request.subdirectory = ProjectService::validate_subdirectory(&request.path, subdirectory)?;
+    }
     state
         .project_service
         .create_project(request)
@@ -63,6 +75,27 @@ pub async fn update_project(
     request: UpdateProjectRequest,
 ) -> Result<Project, String> {
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+    let mut request = request;
+    if request.git_enabled == Some(true) || request.subdirectory.is_some() {
+        let path = match &request.path {
+            Some(path) => path.clone(),
+            None => state
+                .project_service
+                .get_project(uuid)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or("Project not found")?
+                .path,
+        };
+        if request.git_enabled == Some(true) && !GitService::is_repository(Path::new(&path)) {
+            return Err(format!("{} is not a git repository, initialise it first", path));
+        }
+        if let Some(subdirectory) = &request.subdirectory {
+            request.subdirectory = Some(
+                ProjectService::validate_subdirectory(&path, subdirectory)?.unwrap_or_default()
+            );
+        }
+    }
     state
         .project_service
         .update_project(uuid, request)
@@ -73,6 +106,7 @@ pub async fn update_project(
 #[tauri::command]
 pub async fn delete_project(
     state: State<'_, AppState>,
+    mcp_state: State<'_, McpState>,
     id: String,
 ) -> Result<(), String> {
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
@@ -80,14 +114,175 @@ pub async fn delete_project(
         .project_service
         .delete_project(uuid)
         .await
+        .map_err(|e| e.to_string())?;
+
+    // The project's MCP servers were deleted with it
+    mcp_state.manager.unregister_project_servers(&id);
+    Ok(())
+}
+
+/// Detect the repository's default branch and store it if the project's
+/// main branch doesn't exist
+#[tauri::command]
+pub async fn detect_main_branch(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<String, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+    state
+        .project_service
+        .detect_and_update_main_branch(uuid)
+        .await
+}
+
+#[tauri::command]
+pub async fn update_project_cache_links(
+    state: State<'_, AppState>,
+    id: String,
+    request: UpdateCacheLinksRequest,
+) -> Result<Project, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+    state
+        .project_service
+        .update_cache_links(uuid, request)
+        .await
         .map_err(|e| e.to_string())
 }
 
+/// Write the project, its tasks, attempts, conversations and merge requests to a JSON bundle
+#[tauri::command]
+pub async fn export_project_data(
+    state: State<'_, AppState>,
+    project_id: String,
+    path: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    let bundle = state
+        .project_service
+        .export_project_bundle(uuid)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Import a bundle written by `export_project_data` for the checkout at `target_project_path`
+#[tauri::command]
+pub async fn import_project_data(
+    state: State<'_, AppState>,
+    path: String,
+    target_project_path: String,
+) -> Result<ImportProjectResult, String> {
+    let target = PathBuf::from(&target_project_path);
+    if !target.is_dir() {
+        return Err(format!("Project path {} does not exist", target_project_path));
+    }
+
+    let json = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let bundle = ProjectBundle::from_json(&json)?;
+
+    state
+        .project_service
+        .import_project_bundle(bundle, &target_project_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Serialize a project with its tasks, attempts and conversations into a
+/// versioned JSON document
+#[tauri::command]
+pub async fn export_project(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<String, String> {
+    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+    let bundle = state
+        .project_service
+        .export_project_bundle(uuid)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())
+}
+
+/// Recreate a document written by `export_project` as a new project with new
+/// ids. Attempts are imported without worktrees and marked for repair.
+#[tauri::command]
+pub async fn import_project(
+    state: State<'_, AppState>,
+    json: String,
+) -> Result<ImportProjectResult, String> {
+    let bundle = ProjectBundle::from_json(&json)?;
+
+    state
+        .project_service
+        .import_project_bundle_as_new(bundle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Secret values are masked; they are only ever written, never read back
+#[tauri::command]
+pub async fn list_project_env_vars(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<ProjectEnvVar>, String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    state
+        .project_service
+        .list_env_vars(uuid)
+        .await
+        .map(|vars| vars.into_iter().map(ProjectEnvVar::masked).collect())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_project_env_var(
+    state: State<'_, AppState>,
+    project_id: String,
+    request: SetProjectEnvVarRequest,
+) -> Result<ProjectEnvVar, String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    state
+        .project_service
+        .set_env_var(uuid, request)
+        .await
+        .map(ProjectEnvVar::masked)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_project_env_var(
+    state: State<'_, AppState>,
+    project_id: String,
+    key: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    state
+        .project_service
+        .delete_env_var(uuid, &key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Detect the provider of every project with a remote again, e.g. after the
+/// git host settings changed. Returns the projects whose provider changed.
 #[tauri::command]
 pub async fn refresh_all_git_providers(
     state: State<'_, AppState>,
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
 ) -> Result<Vec<Project>, String> {
-    // Get all projects
+    let (enterprise_host, probe) = {
+        let config_service = config_state.lock().await;
+        (
+            config_service.get_github_config()
+                .and_then(|config| config.enterprise_host())
+                .map(|host| host.to_string()),
+            config_service.get_git_hosts_config().probe_unknown_hosts,
+        )
+    };
+
     let projects = state
         .project_service
         .list_projects()
@@ -95,32 +290,34 @@ pub async fn refresh_all_git_providers(
         .map_err(|e| e.to_string())?;
     
     let mut updated_projects = Vec::new();
-    
-    // Update each project that has a git_repo but no git_provider
     for project in projects {
-        if project.git_repo.is_some() && project.git_provider.is_none() {
-            // Create an update request with just the git_repo to trigger provider detection
-            let update_req = UpdateProjectRequest {
-                name: None,
-                description: None,
-                path: None,
-                git_repo: project.git_repo.clone(),
-                main_branch: None,
-                setup_script: None,
-                dev_script: None,
-            };
-            
-            match state
-                .project_service
-                .update_project(Uuid::parse_str(&project.id).unwrap(), update_req)
-                .await
-            {
-                Ok(updated_project) => {
-                    updated_projects.push(updated_project);
-                }
-                Err(e) => {
-                    log::error!("Failed to update project {}: {}", project.id, e);
-                }
+        let Some(git_repo) = project.git_repo.as_deref() else {
+            continue;
+        };
+        let Some(remote_info) = detect_remote_info(git_repo, enterprise_host.as_deref(), probe).await else {
+            continue;
+        };
+        
+        // Unknown hosts keep their provider; new projects default to GitLab for them
+        let git_provider = match remote_info.provider {
+            GitProvider::GitHub => "github",
+            GitProvider::GitLab => "gitlab",
+            GitProvider::Other => project.git_provider.as_deref().unwrap_or("gitlab"),
+        };
+        if project.git_provider.as_deref() == Some(git_provider) {
+            continue;
+        }
+        
+        match state
+            .project_service
+            .update_git_provider(Uuid::parse_str(&project.id).map_err(|e| e.to_string())?, git_provider)
+            .await
+        {
+            Ok(updated_project) => {
+                updated_projects.push(updated_project);
+            }
+            Err(e) => {
+                log::error!("Failed to update project {}: {}", project.id, e);
             }
         }
     }
@@ -193,10 +390,7 @@ pub async fn read_project_info(path: String) -> Result<ProjectInfo, String> {
     let git_path = project_path.join(".git");
     let has_git = git_path.exists() && git_path.is_dir();
     
-    // Validate git repository
-    if !has_git {
-        return Err("Selected directory is not a valid Git repository. Please select a directory with an initialized Git repository.".to_string());
-    }
+    // Directories without git are fine, the repository can be initialised with `init_repository`
     
     // Get git remote URL if available
     let mut git_repo = None;
@@ -287,9 +481,11 @@ pub async fn read_project_info(path: String) -> Result<ProjectInfo, String> {
         }
     }
     
+    let markers = ProjectMarkers::detect(&project_path);
+    
     // Check for package.json
     let package_json_path = project_path.join("package.json");
-    let has_package_json = package_json_path.exists();
+    let has_package_json = markers.package_json;
     
     let mut description = None;
     let mut setup_script = None;
@@ -329,14 +525,17 @@ pub async fn read_project_info(path: String) -> Result<ProjectInfo, String> {
     }
     
     // Check for other common project files
-    let composer_json = project_path.join("composer.json").exists();
-    let cargo_toml = project_path.join("Cargo.toml").exists();
-    let pom_xml = project_path.join("pom.xml").exists();
-    let build_gradle = project_path.join("build.gradle").exists();
-    let requirements_txt = project_path.join("requirements.txt").exists();
-    let pipfile = project_path.join("Pipfile").exists();
-    let gemfile = project_path.join("Gemfile").exists();
-    let go_mod = project_path.join("go.mod").exists();
+    let ProjectMarkers {
+        composer_json,
+        cargo_toml,
+        pom_xml,
+        build_gradle,
+        requirements_txt,
+        pipfile,
+        gemfile,
+        go_mod,
+        ..
+    } = markers;
     
     // Set default scripts based on project type
     if setup_script.is_none() {
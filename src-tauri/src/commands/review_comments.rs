@@ -0,0 +1,76 @@
+use crate::error::PivoError;
+use crate::models::{CreateReviewCommentRequest, ReviewComment, UpdateReviewCommentRequest};
+use crate::AppState;
+use tauri::State;
+use uuid::Uuid;
+
+/// Comment on a line of an attempt's diff
+#[tauri::command]
+pub async fn create_review_comment(
+    state: State<'_, AppState>,
+    request: CreateReviewCommentRequest,
+) -> Result<ReviewComment, PivoError> {
+    if request.body.trim().is_empty() {
+        return Err(PivoError::InvalidInput("Review comment is empty".to_string()));
+    }
+    if request.line < 1 {
+        return Err(PivoError::InvalidInput(format!("Invalid line number: {}", request.line)));
+    }
+
+    state
+        .task_service
+        .create_review_comment(request)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Review comments of an attempt, resolved ones included
+#[tauri::command]
+pub async fn list_review_comments(
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> Result<Vec<ReviewComment>, PivoError> {
+    let uuid = Uuid::parse_str(&attempt_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .list_review_comments(uuid)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Edit a comment's text or mark it (un)resolved
+#[tauri::command]
+pub async fn update_review_comment(
+    state: State<'_, AppState>,
+    id: String,
+    request: UpdateReviewCommentRequest,
+) -> Result<ReviewComment, PivoError> {
+    if request.body.as_deref().is_some_and(|body| body.trim().is_empty()) {
+        return Err(PivoError::InvalidInput("Review comment is empty".to_string()));
+    }
+
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .update_review_comment(uuid, request)
+        .await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn delete_review_comment(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    let deleted = state
+        .task_service
+        .delete_review_comment(uuid)
+        .await
+        .map_err(PivoError::from)?;
+
+    if !deleted {
+        return Err(PivoError::NotFound("Review comment not found".to_string()));
+    }
+    Ok(())
+}
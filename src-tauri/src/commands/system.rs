@@ -1,6 +1,10 @@
 use std::process::Command;
-use crate::utils::command::execute_command;
+use crate::models::ScriptType;
+use crate::utils::command::{execute_command, shell_script_command};
+use crate::AppState;
 use std::path::Path;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
 
 #[tauri::command]
 pub async fn open_in_terminal(path: String) -> Result<(), String> {
@@ -127,4 +131,53 @@ pub async fn show_in_file_manager(file_path: String) -> Result<(), String> {
             Err("Failed to open file manager".to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+/// Run a project's setup, dev or custom script as a managed process in the
+/// project directory. Output is streamed through `process-output` events.
+#[tauri::command]
+pub async fn run_project_script(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+    script_type: ScriptType,
+) -> Result<Uuid, String> {
+    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    let project = state
+        .project_service
+        .get_project(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+    
+    let script = match &script_type {
+        ScriptType::Setup => project.setup_script.clone(),
+        ScriptType::Dev => project.dev_script.clone(),
+        ScriptType::Custom(command) => Some(command.clone()),
+    };
+    let script = match script.as_deref().map(str::trim) {
+        Some(script) if !script.is_empty() => script.to_string(),
+        _ => return Err(match script_type {
+            ScriptType::Setup => "Project has no setup script configured".to_string(),
+            ScriptType::Dev => "Project has no dev script configured".to_string(),
+            ScriptType::Custom(_) => "Script command cannot be empty".to_string(),
+        }),
+    };
+    
+    let (command, args) = shell_script_command(&script);
+    
+    state
+        .process_service
+        .spawn_process(
+            &project.id,
+            None,
+            script_type.process_type(),
+            command,
+            args,
+            project.path.clone(),
+            None,
+            app_handle,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,14 @@
-use crate::models::TaskAttempt;
+use crate::commands::cli::CliState;
+use crate::commands::mcp::McpState;
+use crate::commands::task_commands::dispatch_after_setup;
+use crate::commands::tasks::{ensure_base_branch_exists, run_attempt_setup, start_attempt_setup};
+use crate::error::PivoError;
+use crate::models::{AllWorktreesUsage, AttemptCommit, CreateTaskAttemptRequest, DiffResult, ProcessStatus, ProcessType, TaskAttempt, WorktreeUsage};
+use crate::services::GitService;
+use crate::services::coding_agent_executor::TimelineEvent;
+use std::path::Path;
 use crate::AppState;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
 #[tauri::command]
@@ -29,6 +37,141 @@ pub async fn list_task_attempts(
         .map_err(|e| e.to_string())
 }
 
+/// Commits made automatically after the attempt's executions
+#[tauri::command]
+pub async fn list_attempt_commits(
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> Result<Vec<AttemptCommit>, String> {
+    let uuid = Uuid::parse_str(&attempt_id).map_err(|e| e.to_string())?;
+    state
+        .task_service
+        .list_attempt_commits(uuid)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Disk space taken by an attempt's worktree and its largest top-level paths
+#[tauri::command]
+pub async fn get_worktree_usage(
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> Result<WorktreeUsage, String> {
+    let uuid = Uuid::parse_str(&attempt_id).map_err(|e| e.to_string())?;
+    let worktree = state
+        .task_service
+        .get_attempt_worktree(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("The attempt has no worktree of its own")?;
+    if !Path::new(&worktree.worktree_path).is_dir() {
+        return Err(format!("Worktree {} does not exist", worktree.worktree_path));
+    }
+
+    tokio::task::spawn_blocking(move || crate::services::worktree_usage::worktree_usage(worktree))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Disk space taken by the worktrees of every attempt, for the storage settings
+#[tauri::command]
+pub async fn get_all_worktrees_usage(
+    state: State<'_, AppState>,
+) -> Result<AllWorktreesUsage, String> {
+    let worktrees = state
+        .task_service
+        .list_attempt_worktrees()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tokio::task::spawn_blocking(move || crate::services::worktree_usage::all_worktrees_usage(worktrees))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create an additional attempt for a task, optionally branching off a chosen
+/// base branch instead of the project's main branch
+#[tauri::command]
+pub async fn create_task_attempt(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    task_id: String,
+    base_branch: Option<String>,
+) -> Result<TaskAttempt, String> {
+    let uuid = Uuid::parse_str(&task_id).map_err(|e| e.to_string())?;
+    let task = state
+        .task_service
+        .get_task(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task not found")?;
+    
+    if let Some(base_branch) = base_branch.as_deref() {
+        ensure_base_branch_exists(&state, &task.project_id, base_branch).await?;
+    }
+    
+    let attempt = state
+        .task_service
+        .create_task_attempt(CreateTaskAttemptRequest {
+            task_id: uuid,
+            executor: None,
+            base_branch,
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    if let Err(e) = start_attempt_setup(&state, &app_handle, &task.project_id, &attempt).await {
+        log::error!("Failed to start setup script for attempt {}: {}", attempt.id, e);
+    }
+    
+    Ok(attempt)
+}
+
+/// Diff the branches of two attempts of the same task against each other
+#[tauri::command]
+pub async fn compare_attempts(
+    state: State<'_, AppState>,
+    attempt_id_a: String,
+    attempt_id_b: String,
+) -> Result<DiffResult, String> {
+    let mut attempts = Vec::with_capacity(2);
+    for id in [&attempt_id_a, &attempt_id_b] {
+        let uuid = Uuid::parse_str(id).map_err(|e| e.to_string())?;
+        let attempt = state
+            .task_service
+            .get_task_attempt(uuid)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Task attempt {} not found", id))?;
+        attempts.push(attempt);
+    }
+    let (attempt_a, attempt_b) = (&attempts[0], &attempts[1]);
+    if attempt_a.task_id != attempt_b.task_id {
+        return Err("Only attempts of the same task can be compared".to_string());
+    }
+    
+    let task_uuid = Uuid::parse_str(&attempt_a.task_id).map_err(|e| e.to_string())?;
+    let task = state
+        .task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task not found")?;
+    let project_uuid = Uuid::parse_str(&task.project_id).map_err(|e| e.to_string())?;
+    let project = state
+        .project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+    if !project.git_enabled {
+        return Err(PivoError::not_git_repository().into());
+    }
+    
+    // Attempt branches live in the project repository, even when a worktree is gone
+    GitService::new().diff_branches(Path::new(&project.path), &attempt_a.branch, &attempt_b.branch)
+}
+
 #[tauri::command]
 pub async fn update_attempt_claude_session(
     state: State<'_, AppState>,
@@ -41,4 +184,141 @@ pub async fn update_attempt_claude_session(
         .update_attempt_claude_session(uuid, claude_session_id)
         .await
         .map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+/// Select additional MCP servers for an attempt. They are injected next to the
+/// project's servers on the next agent execution
+#[tauri::command]
+pub async fn update_attempt_mcp_servers(
+    state: State<'_, AppState>,
+    mcp_state: State<'_, McpState>,
+    attempt_id: String,
+    server_ids: Vec<String>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&attempt_id).map_err(|e| e.to_string())?;
+    let attempt = state
+        .task_service
+        .get_task_attempt(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task attempt not found")?;
+    let task_uuid = Uuid::parse_str(&attempt.task_id).map_err(|e| e.to_string())?;
+    let task = state
+        .task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task not found")?;
+    
+    for id in &server_ids {
+        let server = mcp_state.manager.get_server(id).ok_or_else(|| format!("MCP server {} not found", id))?;
+        if !server.is_available_to(Some(&task.project_id)) {
+            return Err(format!("MCP server {} belongs to another project", server.name));
+        }
+    }
+    
+    let mut unique_ids: Vec<String> = Vec::new();
+    for id in server_ids {
+        if !unique_ids.contains(&id) {
+            unique_ids.push(id);
+        }
+    }
+    
+    state
+        .task_service
+        .update_attempt_mcp_servers(uuid, &unique_ids)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stop a running setup script so the agent can be used right away
+#[tauri::command]
+pub async fn skip_attempt_setup(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&attempt_id).map_err(|e| e.to_string())?;
+    let attempt = state
+        .task_service
+        .get_task_attempt(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task attempt not found")?;
+    
+    let processes = state
+        .process_service
+        .list_processes_for_attempt(uuid)
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    for process in processes.iter().filter(|p| {
+        p.process_type == ProcessType::SetupScript && p.status == ProcessStatus::Running
+    }) {
+        let process_uuid = Uuid::parse_str(&process.id).map_err(|e| e.to_string())?;
+        state
+            .process_service
+            .kill_process(process_uuid)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    
+    state
+        .task_service
+        .update_attempt_setup_status(uuid, "skipped")
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    let _ = app_handle.emit("attempt:setup-completed", &serde_json::json!({
+        "attemptId": attempt_id,
+        "success": false,
+        "skipped": true,
+    }));
+    
+    // Send the messages held back by the setup script now. Whoever waited on a
+    // running one sees it skipped too; dispatching holds back while the agent is busy
+    tauri::async_runtime::spawn(dispatch_after_setup(app_handle, attempt.task_id, attempt_id));
+    
+    Ok(())
+}
+
+/// Run the project's setup script in an attempt's worktree again, e.g. after
+/// fixing what made it fail. Messages queued for the attempt go out once it succeeds.
+#[tauri::command]
+pub async fn rerun_attempt_setup(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&attempt_id).map_err(|e| e.to_string())?;
+    let attempt = state
+        .task_service
+        .get_task_attempt(uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task attempt not found")?;
+    if attempt.setup_status.as_deref() == Some("running") {
+        return Err("The setup script is already running".to_string());
+    }
+    
+    let task_uuid = Uuid::parse_str(&attempt.task_id).map_err(|e| e.to_string())?;
+    let task = state
+        .task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task not found")?;
+    
+    run_attempt_setup(&state, &app_handle, &task.project_id, &attempt)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Timestamped start, message, tool call and completion events of an attempt's executions
+#[tauri::command]
+pub async fn get_execution_timeline(
+    cli_state: State<'_, CliState>,
+    attempt_id: String,
+) -> Result<Vec<TimelineEvent>, String> {
+    cli_state.service.get_execution_timeline(&attempt_id).await
+}
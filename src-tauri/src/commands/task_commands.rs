@@ -1,11 +1,19 @@
+use crate::error::PivoError;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, State, Emitter};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tauri::{AppHandle, State, Emitter, Manager};
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::{
     commands::cli::CliState,
+    commands::tasks::{run_attempt_setup, start_attempt_setup},
     AppState,
-    models::TaskStatus,
+    models::{CreateTaskAttemptRequest, DiffSide, ExecutionConfig, PendingMessage, ProcessType, ReviewComment, StatusChangeTrigger, TaskAttempt, TaskStatus},
+    services::{ConfigService, TaskService},
 };
 
 // Simplified command system based on RFC
@@ -29,6 +37,19 @@ pub enum TaskCommand {
     },
 }
 
+/// What happened to a task command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum TaskCommandResult {
+    Started,
+    /// The agent was busy, the message runs once the current execution completes
+    Queued {
+        #[serde(rename = "pendingMessageId")]
+        pending_message_id: String,
+    },
+    Stopped,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationState {
     messages: Vec<ServiceConversationMessage>,
@@ -42,6 +63,34 @@ pub struct ConversationState {
     current_execution: Option<crate::services::coding_agent_executor::types::CodingAgentExecution>,
     #[serde(rename = "worktreePath")]
     worktree_path: Option<String>,
+    #[serde(rename = "setupRunning")]
+    setup_running: bool,
+}
+
+/// Agents a bulk prompt runs at the same time unless the caller asks otherwise
+const DEFAULT_BULK_CONCURRENCY: usize = 3;
+
+/// How often the bulk scheduler checks for finished executions
+const BULK_POLL_INTERVAL_SECS: u64 = 2;
+
+/// How often a replay or queued messages check whether an attempt's setup script finished
+const SETUP_POLL_INTERVAL_SECS: u64 = 2;
+
+/// Outcome of one task in a bulk prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum BulkTaskResult {
+    Started,
+    Queued,
+    Skipped { reason: String },
+    Error { message: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkExecutionResponse {
+    #[serde(rename = "bulkId")]
+    bulk_id: String,
+    results: HashMap<String, BulkTaskResult>,
 }
 
 // Use ConversationMessage from the service module
@@ -54,7 +103,7 @@ pub async fn execute_task_command(
     state: State<'_, AppState>,
     cli_state: State<'_, CliState>,
     command: TaskCommand,
-) -> Result<(), String> {
+) -> Result<TaskCommandResult, PivoError> {
     log::info!("Executing task command: {:?}", command);
     
     match command {
@@ -73,20 +122,20 @@ pub async fn get_conversation_state(
     state: State<'_, AppState>,
     cli_state: State<'_, CliState>,
     task_id: String,
-) -> Result<ConversationState, String> {
+) -> Result<ConversationState, PivoError> {
     let task_service = &state.task_service;
-    let task_uuid = Uuid::parse_str(&task_id).map_err(|e| e.to_string())?;
+    let task_uuid = Uuid::parse_str(&task_id).map_err(PivoError::from)?;
     
     // Get task
     let _task = task_service.get_task(task_uuid)
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(PivoError::from)?
         .ok_or("Task not found")?;
     
     // Get attempts
     let attempts = task_service.list_task_attempts(task_uuid)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
     
     // Get latest attempt
     let current_attempt = attempts.last();
@@ -103,13 +152,19 @@ pub async fn get_conversation_state(
     let is_executing = current_execution.as_ref().map(|e| 
         matches!(e.status, 
             crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Running | 
-            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Starting
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Starting |
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Paused
         )
     ).unwrap_or(false);
     
     log::info!("get_conversation_state for task {}: is_executing = {}, has_attempt = {}", 
         task_id, is_executing, current_attempt.is_some());
     
+    // Block sending until the worktree's setup script has finished
+    let setup_running = current_attempt
+        .map(|a| a.setup_status.as_deref() == Some("running"))
+        .unwrap_or(false);
+    
     // Get messages from current attempt
     let messages = if let Some(attempt) = current_attempt {
         get_attempt_messages(&state, &attempt.id).await.unwrap_or_default()
@@ -121,41 +176,331 @@ pub async fn get_conversation_state(
         messages,
         is_executing,
         current_attempt_id: current_attempt.map(|a| a.id.clone()),
-        can_send_message: !is_executing && current_attempt.is_some(),
+        can_send_message: !is_executing && !setup_running && current_attempt.is_some(),
         current_execution,
         worktree_path: current_attempt.map(|a| a.worktree_path.clone()),
+        setup_running,
     })
 }
 
+/// Pause the running execution of a task
+#[tauri::command]
+pub async fn pause_execution(
+    app: AppHandle,
+    cli_state: State<'_, CliState>,
+    task_id: String,
+) -> Result<(), PivoError> {
+    let execution = cli_state.service.list_executions()
+        .into_iter()
+        .find(|e| e.task_id == task_id && e.status == crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Running)
+        .ok_or("No running execution found for this task")?;
+    
+    cli_state.service.pause_execution(&execution.id).await?;
+    
+    let attempt_id = cli_state.service.get_execution_attempt_id(&execution.id);
+    let _ = app.emit("execution:paused", &serde_json::json!({
+        "taskId": task_id,
+        "attemptId": attempt_id,
+        "executionId": execution.id,
+    }));
+    
+    Ok(())
+}
+
+/// Resume a paused execution of a task
+#[tauri::command]
+pub async fn resume_execution(
+    app: AppHandle,
+    cli_state: State<'_, CliState>,
+    task_id: String,
+) -> Result<(), PivoError> {
+    let execution = cli_state.service.list_executions()
+        .into_iter()
+        .find(|e| e.task_id == task_id && e.status == crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Paused)
+        .ok_or("No paused execution found for this task, it may have already exited")?;
+    
+    cli_state.service.resume_execution(&execution.id).await?;
+    
+    let attempt_id = cli_state.service.get_execution_attempt_id(&execution.id);
+    let _ = app.emit("execution:resumed", &serde_json::json!({
+        "taskId": task_id,
+        "attemptId": attempt_id,
+        "executionId": execution.id,
+    }));
+    
+    Ok(())
+}
+
+/// Send the same message to several tasks. Tasks that are already executing are
+/// skipped, at most `max_concurrency` agents run at once and the rest are queued
+/// and started as slots free up. Progress is reported via `bulk-execution-progress`.
+#[tauri::command]
+pub async fn execute_bulk_task_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    cli_state: State<'_, CliState>,
+    task_ids: Vec<String>,
+    message: String,
+    max_concurrency: Option<usize>,
+) -> Result<BulkExecutionResponse, PivoError> {
+    if message.trim().is_empty() {
+        return Err(PivoError::InvalidInput("Message cannot be empty".to_string()));
+    }
+    
+    let bulk_id = Uuid::new_v4().to_string();
+    let max_concurrency = max_concurrency.unwrap_or(DEFAULT_BULK_CONCURRENCY).max(1);
+    log::info!("Starting bulk prompt {} for {} tasks (concurrency {})", bulk_id, task_ids.len(), max_concurrency);
+    
+    let mut results = HashMap::new();
+    let mut eligible = Vec::new();
+    
+    // Validate every task before starting anything
+    let executions = cli_state.service.list_executions();
+    for task_id in task_ids {
+        if results.contains_key(&task_id) || eligible.contains(&task_id) {
+            continue;
+        }
+        
+        if is_task_executing(&executions, &task_id) {
+            results.insert(task_id, BulkTaskResult::Skipped {
+                reason: "Task already has an active execution".to_string(),
+            });
+            continue;
+        }
+        
+        match validate_bulk_task(&state, &task_id).await {
+            Ok(()) => eligible.push(task_id),
+            Err(error) => {
+                results.insert(task_id, BulkTaskResult::Error { message: error.to_string() });
+            }
+        }
+    }
+    
+    let mut running = Vec::new();
+    let mut queue = VecDeque::new();
+    
+    for task_id in eligible {
+        if running.len() >= max_concurrency {
+            results.insert(task_id.clone(), BulkTaskResult::Queued);
+            queue.push_back(task_id);
+            continue;
+        }
+        
+        match handle_send_message(&app, &state, &cli_state, &task_id, message.clone(), None).await {
+            Ok(_) => {
+                results.insert(task_id.clone(), BulkTaskResult::Started);
+                running.push(task_id);
+            }
+            Err(error) => {
+                results.insert(task_id, BulkTaskResult::Error { message: error.to_string() });
+            }
+        }
+    }
+    
+    if !running.is_empty() || !queue.is_empty() {
+        spawn_bulk_scheduler(app.clone(), bulk_id.clone(), message, running, queue, max_concurrency);
+    }
+    
+    Ok(BulkExecutionResponse { bulk_id, results })
+}
+
+/// Check that a task can receive a message: it exists, has an attempt and
+/// its worktree setup is not still running
+async fn validate_bulk_task(state: &State<'_, AppState>, task_id: &str) -> Result<(), PivoError> {
+    let task_uuid = Uuid::parse_str(task_id).map_err(PivoError::from)?;
+    
+    state.task_service.get_task(task_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task not found")?;
+    
+    let attempts = state.task_service.list_task_attempts(task_uuid)
+        .await
+        .map_err(PivoError::from)?;
+    let attempt = attempts.last()
+        .ok_or("No attempt found for this task. Please create an attempt first.")?;
+    
+    if attempt.setup_status.as_deref() == Some("running") {
+        return Err(PivoError::InvalidInput("The setup script is still running".to_string()));
+    }
+    
+    Ok(())
+}
+
+fn is_task_executing(
+    executions: &[crate::services::coding_agent_executor::types::CodingAgentExecution],
+    task_id: &str,
+) -> bool {
+    executions.iter().any(|e|
+        e.task_id == task_id &&
+        matches!(e.status,
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Running |
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Starting |
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Paused
+        )
+    )
+}
+
+fn emit_bulk_progress(
+    app: &AppHandle,
+    bulk_id: &str,
+    task_id: Option<&str>,
+    result: &str,
+    error: Option<&str>,
+    running: usize,
+    queued: usize,
+) {
+    let _ = app.emit("bulk-execution-progress", &serde_json::json!({
+        "bulkId": bulk_id,
+        "taskId": task_id,
+        "status": result,
+        "error": error,
+        "running": running,
+        "queued": queued,
+    }));
+}
+
+/// Watch the running executions of a bulk prompt and start queued tasks as they finish
+fn spawn_bulk_scheduler(
+    app: AppHandle,
+    bulk_id: String,
+    message: String,
+    mut running: Vec<String>,
+    mut queue: VecDeque<String>,
+    max_concurrency: usize,
+) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let cli_state = app.state::<CliState>();
+        
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(BULK_POLL_INTERVAL_SECS)).await;
+            
+            let executions = cli_state.service.list_executions();
+            let (still_running, finished): (Vec<String>, Vec<String>) = running
+                .into_iter()
+                .partition(|task_id| is_task_executing(&executions, task_id));
+            running = still_running;
+            
+            for task_id in finished {
+                emit_bulk_progress(&app, &bulk_id, Some(&task_id), "completed", None, running.len(), queue.len());
+            }
+            
+            while running.len() < max_concurrency {
+                let task_id = match queue.pop_front() {
+                    Some(task_id) => task_id,
+                    None => break,
+                };
+                
+                // The task may have been started by hand while it waited
+                if is_task_executing(&cli_state.service.list_executions(), &task_id) {
+                    emit_bulk_progress(&app, &bulk_id, Some(&task_id), "skipped", None, running.len(), queue.len());
+                    continue;
+                }
+                
+                match handle_send_message(&app, &state, &cli_state, &task_id, message.clone(), None).await {
+                    Ok(_) => {
+                        running.push(task_id.clone());
+                        emit_bulk_progress(&app, &bulk_id, Some(&task_id), "started", None, running.len(), queue.len());
+                    }
+                    Err(e) => {
+                        log::error!("Bulk prompt {} failed to start task {}: {}", bulk_id, task_id, e);
+                        emit_bulk_progress(&app, &bulk_id, Some(&task_id), "error", Some(e.message()), running.len(), queue.len());
+                    }
+                }
+            }
+            
+            if running.is_empty() && queue.is_empty() {
+                log::info!("Bulk prompt {} finished", bulk_id);
+                emit_bulk_progress(&app, &bulk_id, None, "finished", None, 0, 0);
+                break;
+            }
+        }
+    });
+}
+
 // Core logic based on RFC
-async fn handle_send_message(
+pub(crate) async fn handle_send_message(
     app: &AppHandle,
     state: &State<'_, AppState>,
     cli_state: &State<'_, CliState>,
     task_id: &str,
     message: String,
     images: Option<Vec<String>>,
-) -> Result<(), String> {
-    let task_service = &state.task_service;
-    let task_uuid = Uuid::parse_str(task_id).map_err(|e| e.to_string())?;
+) -> Result<TaskCommandResult, PivoError> {
+    let task_uuid = Uuid::parse_str(task_id).map_err(PivoError::from)?;
     
     // 1. Get the latest Attempt, error if none exists
-    let attempts = task_service.list_task_attempts(task_uuid)
+    let attempts = state.task_service.list_task_attempts(task_uuid)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
     
-    let mut attempt = attempts.last()
+    let attempt = attempts.last()
         .ok_or("No attempt found for this task. Please create an attempt first.")?
         .clone();
     
+    send_message_to_attempt(app, state, cli_state, task_id, attempt, message, images, None).await
+}
+
+/// Send a message to a specific attempt of a task, resuming its agent session,
+/// or queue it while the task's agent is busy. `queued` is the queued message
+/// being dispatched; if it has to wait again it goes back to its old place.
+#[allow(clippy::too_many_arguments)]
+async fn send_message_to_attempt(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+    cli_state: &State<'_, CliState>,
+    task_id: &str,
+    mut attempt: TaskAttempt,
+    message: String,
+    images: Option<Vec<String>>,
+    queued: Option<&PendingMessage>,
+) -> Result<TaskCommandResult, PivoError> {
+    let task_service = &state.task_service;
+    let task_uuid = Uuid::parse_str(task_id).map_err(PivoError::from)?;
+    
+    // 1. The setup script runs before the agent first touches the worktree:
+    //    an attempt that never ran it does so now, and messages wait for it
+    let execution_config = app.state::<Arc<Mutex<ConfigService>>>()
+        .lock()
+        .await
+        .get_execution_config()
+        .clone();
+    let attempt_uuid = Uuid::parse_str(&attempt.id).map_err(PivoError::from)?;
+    
+    if attempt.setup_status.is_none() && execution_config.run_setup_script {
+        let first_use = task_service.get_attempt_conversation(attempt_uuid)
+            .await
+            .map_err(PivoError::from)?
+            .is_none_or(|conversation| conversation.messages.is_empty());
+        if first_use {
+            let task = task_service.get_task(task_uuid)
+                .await
+                .map_err(PivoError::from)?
+                .ok_or("Task not found")?;
+            run_attempt_setup(state, app, &task.project_id, &attempt).await?;
+            attempt.setup_status = task_service.get_task_attempt(attempt_uuid)
+                .await
+                .map_err(PivoError::from)?
+                .and_then(|a| a.setup_status);
+        }
+    }
+    
+    match attempt.setup_status.as_deref() {
+        Some("running") => {
+            let pending_message_id = queue_message(app, state, task_id, &attempt.id, &message, images.as_ref(), queued).await?;
+            log::info!("Setup of attempt {} is still running, queued message {}", attempt.id, pending_message_id);
+            
+            return Ok(TaskCommandResult::Queued { pending_message_id });
+        }
+        Some("failed") if execution_config.require_setup_success => {
+            return Err(setup_failure(state, attempt_uuid).await);
+        }
+        _ => {}
+    }
+    
     // 2. Determine agent type and update executor field if needed
-    let agent_type = match attempt.executor.as_deref() {
-        Some("claude") | Some("claude_code") | Some("ClaudeCode") => 
-            crate::services::coding_agent_executor::CodingAgentType::ClaudeCode,
-        Some("gemini") | Some("gemini_cli") | Some("GeminiCli") => 
-            crate::services::coding_agent_executor::CodingAgentType::GeminiCli,
-        _ => crate::services::coding_agent_executor::CodingAgentType::ClaudeCode, // Default to Claude
-    };
+    let agent_type = crate::services::coding_agent_executor::CodingAgentType::from_executor(attempt.executor.as_deref());
     
     // Update executor field if not set or different
     let executor_str = match &agent_type {
@@ -165,10 +510,9 @@ async fn handle_send_message(
     
     if attempt.executor.as_deref() != Some(executor_str) {
         log::info!("Updating attempt {} executor from {:?} to {}", attempt.id, attempt.executor, executor_str);
-        let attempt_uuid = Uuid::parse_str(&attempt.id).map_err(|e| e.to_string())?;
         task_service.update_attempt_executor(attempt_uuid, executor_str.to_string())
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(PivoError::from)?;
         
         // Update local attempt object
         attempt.executor = Some(executor_str.to_string());
@@ -186,37 +530,52 @@ async fn handle_send_message(
         _ => None,
     };
     
-    // 4. Check if there's already an active execution and stop it first
+    // 4. If there's already an active execution, queue the message behind it
+    //    (or stop it first when configured to interrupt)
     let executions = cli_state.service.list_executions();
     if let Some(exec) = executions.iter().find(|e| 
         e.task_id == task_id && 
         matches!(e.status, 
             crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Running | 
-            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Starting
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Starting |
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Paused
         )
     ) {
-        log::info!("Stopping existing execution {} before starting new one", exec.id);
-        cli_state.service.stop_execution(&exec.id).await?;
+        let interrupt = app.state::<Arc<Mutex<ConfigService>>>()
+            .lock()
+            .await
+            .get_execution_config()
+            .interrupt_on_new_message;
+        
+        if interrupt {
+            log::info!("Stopping existing execution {} before starting new one", exec.id);
+            cli_state.service.stop_execution(&exec.id).await?;
+        } else {
+            let pending_message_id = queue_message(app, state, task_id, &attempt.id, &message, images.as_ref(), queued).await?;
+            log::info!("Execution {} is still running, queued message {} for attempt {}", exec.id, pending_message_id, attempt.id);
+            
+            return Ok(TaskCommandResult::Queued { pending_message_id });
+        }
     }
     
     // 5. Get task and project info
     let task = task_service.get_task(task_uuid)
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(PivoError::from)?
         .ok_or("Task not found")?;
     
-    let project_uuid = Uuid::parse_str(&task.project_id).map_err(|e| e.to_string())?;
+    let project_uuid = Uuid::parse_str(&task.project_id).map_err(PivoError::from)?;
     let project = state.project_service
         .get_project(project_uuid)
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(PivoError::from)?
         .ok_or("Project not found")?;
     
     // 6. Update task status to Working if not already
     if task.status != TaskStatus::Working {
-        let updated_task = task_service.update_task_status(task_uuid, TaskStatus::Working)
+        let updated_task = task_service.update_task_status(task_uuid, TaskStatus::Working, StatusChangeTrigger::System)
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(PivoError::from)?;
         
         // Emit task:status-changed event
         let _ = app.emit("task:status-changed", &serde_json::json!({
@@ -244,7 +603,7 @@ async fn handle_send_message(
         prompt,
         task_id.to_string(),
         attempt.id.clone(),
-        if attempt.worktree_path.is_empty() { project.path.clone() } else { attempt.worktree_path.clone() },
+        project.working_directory(if attempt.worktree_path.is_empty() { &project.path } else { &attempt.worktree_path }),
         agent_type,
         resume_session_id, // Use saved session ID
     ).await?;
@@ -259,7 +618,7 @@ async fn handle_send_message(
     // 10. Don't emit state update immediately - let the frontend handle the state change
     // The execution:started event is enough to update the UI state
     
-    Ok(())
+    Ok(TaskCommandResult::Started)
 }
 
 async fn handle_stop_execution(
@@ -267,19 +626,29 @@ async fn handle_stop_execution(
     state: &State<'_, AppState>,
     cli_state: &State<'_, CliState>,
     task_id: &str,
-) -> Result<(), String> {
+) -> Result<(), PivoError> {
     let task_service = &state.task_service;
-    let task_uuid = Uuid::parse_str(task_id).map_err(|e| e.to_string())?;
+    let task_uuid = Uuid::parse_str(task_id).map_err(PivoError::from)?;
     
     // Get current execution and attempt ID from the latest attempt
     let attempts = task_service.list_task_attempts(task_uuid)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
     
     let attempt_id = attempts.last()
         .map(|a| a.id.clone())
         .unwrap_or_default();
     
+    // Stopping also drops queued follow-ups so they don't start once the agent exits
+    if let Ok(attempt_uuid) = Uuid::parse_str(&attempt_id) {
+        let cleared = task_service.clear_pending_messages(attempt_uuid)
+            .await
+            .map_err(PivoError::from)?;
+        for pending in cleared {
+            emit_message_dequeued(app, &pending, "cancelled");
+        }
+    }
+    
     let executions = cli_state.service.list_executions();
     if let Some(execution) = executions.iter().find(|e| e.task_id == task_id) {
         let exec_id = execution.id.clone();
@@ -298,13 +667,13 @@ async fn handle_stop_execution(
     // Update task status back to Backlog when stopping
     let task = task_service.get_task(task_uuid)
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(PivoError::from)?
         .ok_or("Task not found")?;
     
     if task.status != TaskStatus::Backlog {
-        let updated_task = task_service.update_task_status(task_uuid, TaskStatus::Backlog)
+        let updated_task = task_service.update_task_status(task_uuid, TaskStatus::Backlog, StatusChangeTrigger::System)
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(PivoError::from)?;
         
         // Emit task:status-changed event
         let _ = app.emit("task:status-changed", &serde_json::json!({
@@ -317,9 +686,418 @@ async fn handle_stop_execution(
     
     // Don't emit state update immediately - let the frontend handle the state change
     
+    Ok(TaskCommandResult::Stopped)
+}
+
+/// List messages waiting for the attempt's current execution to finish
+#[tauri::command]
+pub async fn list_pending_messages(
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> Result<Vec<PendingMessage>, PivoError> {
+    let uuid = Uuid::parse_str(&attempt_id).map_err(PivoError::from)?;
+    state.task_service
+        .list_pending_messages(uuid)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Remove a queued message before it is sent
+#[tauri::command]
+pub async fn cancel_pending_message(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pending_message_id: String,
+) -> Result<(), PivoError> {
+    let uuid = Uuid::parse_str(&pending_message_id).map_err(PivoError::from)?;
+    let pending = state.task_service
+        .get_pending_message(uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Pending message not found, it may have already been sent")?;
+    
+    state.task_service
+        .delete_pending_message(uuid)
+        .await
+        .map_err(PivoError::from)?;
+    
+    emit_message_dequeued(&app, &pending, "cancelled");
     Ok(())
 }
 
+/// Start a new attempt of the same task, on a fresh worktree branch, and
+/// re-send the user messages of `attempt_id` to it. When the project has a
+/// setup script the replay starts once the script finishes.
+#[tauri::command]
+pub async fn replay_attempt(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    attempt_id: String,
+) -> Result<TaskAttempt, PivoError> {
+    let old_uuid = Uuid::parse_str(&attempt_id).map_err(PivoError::from)?;
+    let old_attempt = state.task_service
+        .get_task_attempt(old_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task attempt not found")?;
+
+    let task_uuid = Uuid::parse_str(&old_attempt.task_id).map_err(PivoError::from)?;
+    let task = state.task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task not found")?;
+
+    let attempt = state.task_service
+        .create_task_attempt(CreateTaskAttemptRequest {
+            task_id: task_uuid,
+            executor: old_attempt.executor.clone(),
+            base_branch: Some(old_attempt.base_branch.clone()),
+        })
+        .await
+        .map_err(PivoError::from)?;
+
+    if let Err(e) = start_attempt_setup(&state, &app, &task.project_id, &attempt).await {
+        log::error!("Failed to start setup script for attempt {}: {}", attempt.id, e);
+    }
+
+    let attempt_uuid = Uuid::parse_str(&attempt.id).map_err(PivoError::from)?;
+    let attempt = state.task_service
+        .get_task_attempt(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task attempt not found")?;
+
+    if attempt.setup_status.as_deref() == Some("running") {
+        let app = app.clone();
+        let new_attempt_id = attempt.id.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(std::time::Duration::from_secs(SETUP_POLL_INTERVAL_SECS)).await;
+                let setup_status = app.state::<AppState>().task_service
+                    .get_task_attempt(attempt_uuid)
+                    .await
+                    .ok()
+                    .flatten()
+                    .and_then(|a| a.setup_status);
+                if setup_status.as_deref() != Some("running") {
+                    break;
+                }
+            }
+            if let Err(e) = start_replay(&app, &attempt_id, &new_attempt_id).await {
+                log::error!("Failed to replay attempt {} on attempt {}: {}", attempt_id, new_attempt_id, e);
+            }
+        });
+    } else {
+        start_replay(&app, &attempt_id, &attempt.id).await?;
+    }
+
+    Ok(attempt)
+}
+
+/// Send the unresolved review comments of an attempt to its agent as one
+/// prompt, resuming the attempt's session. Comments stay open until they are
+/// resolved by hand or their line changes.
+#[tauri::command]
+pub async fn send_review_to_agent(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    cli_state: State<'_, CliState>,
+    attempt_id: String,
+) -> Result<TaskCommandResult, PivoError> {
+    let attempt_uuid = Uuid::parse_str(&attempt_id).map_err(PivoError::from)?;
+    let attempt = state.task_service
+        .get_task_attempt(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task attempt not found")?;
+    
+    let comments: Vec<ReviewComment> = state.task_service
+        .list_review_comments(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .into_iter()
+        .filter(|c| !c.resolved)
+        .collect();
+    if comments.is_empty() {
+        return Err(PivoError::InvalidInput("There are no unresolved review comments to send".to_string()));
+    }
+    
+    let task_id = attempt.task_id.clone();
+    log::info!("Sending {} review comments to attempt {}", comments.len(), attempt_id);
+    send_message_to_attempt(&app, &state, &cli_state, &task_id, attempt, review_prompt(&comments), None, None).await
+}
+
+/// "In src/foo.rs line 42: ..." for each comment, in file and line order
+fn review_prompt(comments: &[ReviewComment]) -> String {
+    let mut prompt = String::from("Please address the following review comments on your changes:\n");
+    for comment in comments {
+        let side = match comment.side {
+            DiffSide::New => "",
+            DiffSide::Old => " of the original version",
+        };
+        prompt.push_str(&format!("\nIn {} line {}{}: {}", comment.file_path, comment.line, side, comment.body.trim()));
+        if let Some(line) = comment.line_content.as_deref().filter(|l| !l.trim().is_empty()) {
+            prompt.push_str(&format!("\n    > {}", line.trim()));
+        }
+    }
+    prompt
+}
+
+/// Send the replay prompt to the new attempt and mark its task as working
+async fn start_replay(app: &AppHandle, old_attempt_id: &str, new_attempt_id: &str) -> Result<(), PivoError> {
+    let state = app.state::<AppState>();
+    let cli_state = app.state::<CliState>();
+
+    let execution = cli_state.service
+        .replay_attempt(old_attempt_id, new_attempt_id)
+        .await?;
+
+    let task_uuid = Uuid::parse_str(&execution.task_id).map_err(PivoError::from)?;
+    if let Some(task) = state.task_service.get_task(task_uuid).await.map_err(PivoError::from)? {
+        if task.status != TaskStatus::Working {
+            let updated_task = state.task_service.update_task_status(task_uuid, TaskStatus::Working, StatusChangeTrigger::System)
+                .await
+                .map_err(PivoError::from)?;
+            let _ = app.emit("task:status-changed", &serde_json::json!({
+                "taskId": execution.task_id,
+                "previousStatus": task.status,
+                "newStatus": TaskStatus::Working,
+                "task": updated_task,
+            }));
+        }
+    }
+
+    let _ = app.emit("execution:started", &serde_json::json!({
+        "taskId": execution.task_id,
+        "attemptId": new_attempt_id,
+        "executionId": execution.id,
+    }));
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_execution_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<ExecutionConfig, PivoError> {
+    let config_service = config_state.lock().await;
+    Ok(config_service.get_execution_config().clone())
+}
+
+#[tauri::command]
+pub async fn update_execution_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    config: ExecutionConfig,
+) -> Result<(), PivoError> {
+    let mut config_service = config_state.lock().await;
+    config_service.update_execution_config(config).await
+        .map_err(PivoError::from)
+}
+
+/// Add a message to the end of an attempt's queue and report it, or put the
+/// queued message being dispatched back in its old place
+async fn queue_message(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+    task_id: &str,
+    attempt_id: &str,
+    message: &str,
+    images: Option<&Vec<String>>,
+    queued: Option<&PendingMessage>,
+) -> Result<String, PivoError> {
+    if let Some(pending) = queued {
+        state.task_service.requeue_pending_message(pending).await.map_err(PivoError::from)?;
+        return Ok(pending.id.clone());
+    }
+    
+    let attempt_uuid = Uuid::parse_str(attempt_id).map_err(PivoError::from)?;
+    let task_uuid = Uuid::parse_str(task_id).map_err(PivoError::from)?;
+    let pending = state.task_service.enqueue_pending_message(attempt_uuid, task_uuid, message, images)
+        .await
+        .map_err(PivoError::from)?;
+    
+    let _ = app.emit("message:queued", &serde_json::json!({
+        "taskId": task_id,
+        "attemptId": attempt_id,
+        "pendingMessage": &pending,
+    }));
+    
+    Ok(pending.id)
+}
+
+/// Start the oldest queued message of an attempt, resuming the agent session.
+/// Called when an execution completes; returns false if nothing was sent.
+pub(crate) async fn dispatch_next_pending_message(app: &AppHandle, task_id: &str, attempt_id: &str) -> bool {
+    let state = app.state::<AppState>();
+    let cli_state = app.state::<CliState>();
+    
+    let Ok(attempt_uuid) = Uuid::parse_str(attempt_id) else {
+        return false;
+    };
+    let agent_busy = cli_state.service.list_executions().iter().any(|e|
+        e.task_id == task_id &&
+        matches!(e.status,
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Running |
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Starting |
+            crate::services::coding_agent_executor::types::CodingAgentExecutionStatus::Paused
+        )
+    );
+    
+    let (state, cli_state) = (&state, &cli_state);
+    dispatch_pending_message(
+        &state.task_service,
+        attempt_uuid,
+        agent_busy,
+        move |attempt, pending| async move {
+            send_message_to_attempt(app, state, cli_state, task_id, attempt, pending.message.clone(), pending.images.clone(), Some(&pending)).await
+        },
+        |event, payload| {
+            let _ = app.emit(event, payload);
+        },
+    ).await
+}
+
+/// Hand the oldest queued message of an attempt to `send`, unless the attempt's
+/// setup script or agent is still busy. The message then stays where it is. A
+/// sent message is reported as dequeued with reason "dispatched". One that
+/// fails goes back to the front of the queue and is reported with reason
+/// "failed". One that `send` queued again is back in its place already and is
+/// not reported.
+pub(crate) async fn dispatch_pending_message<F, Fut>(
+    task_service: &TaskService,
+    attempt_uuid: Uuid,
+    agent_busy: bool,
+    send: F,
+    emit: impl Fn(&str, serde_json::Value),
+) -> bool
+where
+    F: FnOnce(TaskAttempt, PendingMessage) -> Fut,
+    Fut: Future<Output = Result<TaskCommandResult, PivoError>>,
+{
+    let attempt = match task_service.get_task_attempt(attempt_uuid).await {
+        Ok(Some(attempt)) => attempt,
+        Ok(None) => return false,
+        Err(e) => {
+            log::error!("Failed to read attempt {}: {}", attempt_uuid, e);
+            return false;
+        }
+    };
+    if agent_busy || attempt.setup_status.as_deref() == Some("running") {
+        log::info!("Attempt {} is still busy, its queued messages wait", attempt_uuid);
+        return false;
+    }
+    
+    let pending = match task_service.take_next_pending_message(attempt_uuid).await {
+        Ok(Some(pending)) => pending,
+        Ok(None) => return false,
+        Err(e) => {
+            log::error!("Failed to read pending messages for attempt {}: {}", attempt_uuid, e);
+            return false;
+        }
+    };
+    
+    log::info!("Dispatching queued message {} for attempt {}", pending.id, attempt_uuid);
+    match send(attempt, pending.clone()).await {
+        Ok(TaskCommandResult::Queued { .. }) => {
+            log::info!("Queued message {} for attempt {} has to wait again", pending.id, attempt_uuid);
+            false
+        }
+        Ok(_) => {
+            emit(MESSAGE_DEQUEUED_EVENT, message_dequeued_payload(&pending, "dispatched"));
+            true
+        }
+        Err(e) => {
+            log::error!("Failed to send queued message {} for task {}: {}", pending.id, pending.task_id, e);
+            if let Err(e) = task_service.requeue_pending_message(&pending).await {
+                log::error!("Failed to put back queued message {}: {}", pending.id, e);
+            }
+            emit(MESSAGE_DEQUEUED_EVENT, message_dequeued_payload(&pending, "failed"));
+            false
+        }
+    }
+}
+
+/// Send the messages left in the queue by the last session, one per attempt;
+/// the rest follow as those runs complete
+pub(crate) async fn dispatch_leftover_pending_messages(app: &AppHandle) {
+    let attempts = match app.state::<AppState>().task_service.list_attempts_with_pending_messages().await {
+        Ok(attempts) => attempts,
+        Err(e) => {
+            log::error!("Failed to list queued messages: {}", e);
+            return;
+        }
+    };
+    
+    for (task_id, attempt_id) in attempts {
+        dispatch_next_pending_message(app, &task_id, &attempt_id).await;
+    }
+}
+
+/// Why messages to an attempt are held back after its setup script failed
+async fn setup_failure(state: &AppState, attempt_uuid: Uuid) -> PivoError {
+    let setup_process = state.process_service
+        .list_processes_for_attempt(attempt_uuid)
+        .await
+        .ok()
+        .and_then(|processes| processes.into_iter().find(|p| p.process_type == ProcessType::SetupScript));
+    
+    let failure = match setup_process {
+        Some(process) => match process.exit_code {
+            Some(code) => format!("The setup script `{}` failed with exit code {}", process.command, code),
+            None => format!("The setup script `{}` failed", process.command),
+        },
+        None => "The setup script could not be started".to_string(),
+    };
+    PivoError::InvalidInput(format!("{}. Fix the problem and run setup again, or skip it to send anyway.", failure))
+}
+
+/// Wait for an attempt's setup script to finish and send the messages queued
+/// behind it, unless it failed and has to succeed first. Boxed because sending
+/// can start a setup script, which leads back here.
+pub(crate) fn dispatch_after_setup(app: AppHandle, task_id: String, attempt_id: String) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    Box::pin(async move {
+        let Ok(attempt_uuid) = Uuid::parse_str(&attempt_id) else {
+            return;
+        };
+        let setup_status = loop {
+            tokio::time::sleep(std::time::Duration::from_secs(SETUP_POLL_INTERVAL_SECS)).await;
+            let setup_status = app.state::<AppState>().task_service
+                .get_task_attempt(attempt_uuid)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|a| a.setup_status);
+            if setup_status.as_deref() != Some("running") {
+                break setup_status;
+            }
+        };
+        let require_setup_success = app.state::<Arc<Mutex<ConfigService>>>()
+            .lock()
+            .await
+            .get_execution_config()
+            .require_setup_success;
+        if setup_status.as_deref() != Some("failed") || !require_setup_success {
+            dispatch_next_pending_message(&app, &task_id, &attempt_id).await;
+        }
+    })
+}
+
+const MESSAGE_DEQUEUED_EVENT: &str = "message:dequeued";
+
+fn emit_message_dequeued(app: &AppHandle, pending: &PendingMessage, reason: &str) {
+    let _ = app.emit(MESSAGE_DEQUEUED_EVENT, message_dequeued_payload(pending, reason));
+}
+
+fn message_dequeued_payload(pending: &PendingMessage, reason: &str) -> serde_json::Value {
+    serde_json::json!({
+        "taskId": pending.task_id,
+        "attemptId": pending.task_attempt_id,
+        "pendingMessageId": pending.id,
+        "reason": reason,
+    })
+}
+
 // Removed emit_state_update function - no longer needed
 // State updates are now handled through granular events:
 // - execution:started
@@ -330,8 +1108,8 @@ async fn handle_stop_execution(
 async fn get_attempt_messages(
     state: &State<'_, AppState>,
     attempt_id: &str,
-) -> Result<Vec<ServiceConversationMessage>, String> {
-    let attempt_uuid = Uuid::parse_str(attempt_id).map_err(|e| e.to_string())?;
+) -> Result<Vec<ServiceConversationMessage>, PivoError> {
+    let attempt_uuid = Uuid::parse_str(attempt_id).map_err(PivoError::from)?;
     
     // Get messages from attempt conversation
     if let Ok(Some(conversation)) = state.task_service.get_attempt_conversation(attempt_uuid).await {
@@ -377,4 +1155,64 @@ async fn get_attempt_messages(
     } else {
         Ok(vec![])
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::TestDb;
+    use std::cell::RefCell;
+
+    #[tokio::test]
+    async fn queued_messages_keep_their_order_until_the_attempt_is_free() {
+        let db = TestDb::new().await;
+        let (_, attempt_ids) = db.seed_attempts(1).await;
+        let task_service = TaskService::new(db.pool.clone());
+        let attempt_id = attempt_ids[0];
+        let task_id = Uuid::parse_str(&task_service.get_task_attempt(attempt_id).await.unwrap().unwrap().task_id).unwrap();
+        task_service.update_attempt_setup_status(attempt_id, "running").await.unwrap();
+        let first = task_service.enqueue_pending_message(attempt_id, task_id, "first", None).await.unwrap();
+        let second = task_service.enqueue_pending_message(attempt_id, task_id, "second", None).await.unwrap();
+
+        let events = RefCell::new(Vec::new());
+        let emit = |event: &str, payload: serde_json::Value| {
+            events.borrow_mut().push((event.to_string(), payload["pendingMessageId"].clone(), payload["reason"].clone()));
+        };
+        let queue = || async {
+            task_service.list_pending_messages(attempt_id).await.unwrap().into_iter().map(|p| p.id).collect::<Vec<_>>()
+        };
+
+        // Setup still running, then the agent still busy: nothing is taken
+        let sent = dispatch_pending_message(&task_service, attempt_id, false, |_, _| async { unreachable!("sent during setup") }, &emit).await;
+        assert!(!sent);
+        task_service.update_attempt_setup_status(attempt_id, "completed").await.unwrap();
+        let sent = dispatch_pending_message(&task_service, attempt_id, true, |_, _| async { unreachable!("sent while busy") }, &emit).await;
+        assert!(!sent);
+        assert_eq!(queue().await, vec![first.id.clone(), second.id.clone()]);
+
+        // Queued again by the send, as when a setup script starts on first use
+        let sent = dispatch_pending_message(&task_service, attempt_id, false, |_, pending| {
+            let task_service = &task_service;
+            async move {
+                task_service.requeue_pending_message(&pending).await.unwrap();
+                Ok(TaskCommandResult::Queued { pending_message_id: pending.id })
+            }
+        }, &emit).await;
+        assert!(!sent);
+        assert_eq!(queue().await, vec![first.id.clone(), second.id.clone()]);
+        assert!(events.borrow().is_empty());
+
+        let sent = dispatch_pending_message(&task_service, attempt_id, false, |_, pending| {
+            assert_eq!(pending.message, "first");
+            async { Ok(TaskCommandResult::Started) }
+        }, &emit).await;
+        assert!(sent);
+        assert_eq!(queue().await, vec![second.id.clone()]);
+        assert_eq!(*events.borrow(), vec![(
+            MESSAGE_DEQUEUED_EVENT.to_string(),
+            serde_json::json!(first.id),
+            serde_json::json!("dispatched"),
+        )]);
+
+        db.close().await;
+    }
+}
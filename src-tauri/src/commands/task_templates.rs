@@ -0,0 +1,170 @@
+use crate::commands::cli::CliState;
+use crate::commands::task_commands::handle_send_message;
+use crate::commands::tasks::start_first_attempt_setup;
+use crate::error::PivoError;
+use crate::models::{CreateTaskRequest, CreateTaskTemplateRequest, Task, TaskTemplate, UpdateTaskTemplateRequest};
+use crate::AppState;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+/// Executors a template may pick for its first attempt
+const TEMPLATE_EXECUTORS: [&str; 2] = ["claude_code", "gemini_cli"];
+
+fn validate_executor(executor: Option<&str>) -> Result<(), PivoError> {
+    match executor.map(str::trim).filter(|e| !e.is_empty()) {
+        Some(executor) if !TEMPLATE_EXECUTORS.contains(&executor) => Err(PivoError::InvalidInput(format!(
+            "Unknown executor '{}', expected one of: {}",
+            executor,
+            TEMPLATE_EXECUTORS.join(", ")
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn create_task_template(
+    state: State<'_, AppState>,
+    project_id: String,
+    request: CreateTaskTemplateRequest,
+) -> Result<TaskTemplate, PivoError> {
+    if request.name.trim().is_empty() {
+        return Err(PivoError::InvalidInput("Template name is empty".to_string()));
+    }
+    if request.title_pattern.trim().is_empty() {
+        return Err(PivoError::InvalidInput("Template title is empty".to_string()));
+    }
+    validate_executor(request.executor.as_deref())?;
+
+    let project_uuid = Uuid::parse_str(&project_id).map_err(PivoError::from)?;
+    state
+        .project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or_else(|| PivoError::NotFound(format!("Project {} not found", project_id)))?;
+
+    state
+        .task_service
+        .create_task_template(project_uuid, request)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Task templates of a project by name
+#[tauri::command]
+pub async fn list_task_templates(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<TaskTemplate>, PivoError> {
+    let uuid = Uuid::parse_str(&project_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .list_task_templates(uuid)
+        .await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn get_task_template(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<TaskTemplate, PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .get_task_template(uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or_else(|| PivoError::NotFound("Task template not found".to_string()))
+}
+
+#[tauri::command]
+pub async fn update_task_template(
+    state: State<'_, AppState>,
+    id: String,
+    request: UpdateTaskTemplateRequest,
+) -> Result<TaskTemplate, PivoError> {
+    if request.name.as_deref().is_some_and(|name| name.trim().is_empty()) {
+        return Err(PivoError::InvalidInput("Template name is empty".to_string()));
+    }
+    if request.title_pattern.as_deref().is_some_and(|title| title.trim().is_empty()) {
+        return Err(PivoError::InvalidInput("Template title is empty".to_string()));
+    }
+    validate_executor(request.executor.as_deref())?;
+
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .update_task_template(uuid, request)
+        .await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn delete_task_template(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    let deleted = state
+        .task_service
+        .delete_task_template(uuid)
+        .await
+        .map_err(PivoError::from)?;
+
+    if !deleted {
+        return Err(PivoError::NotFound("Task template not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Create a task from a template with its placeholders filled in from
+/// `placeholder_values`, then send the template's initial prompt to the first
+/// attempt, which waits for the setup script like any other message. Missing
+/// placeholder values are reported before anything is created.
+#[tauri::command]
+pub async fn create_task_from_template(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    cli_state: State<'_, CliState>,
+    template_id: String,
+    placeholder_values: HashMap<String, String>,
+) -> Result<Task, PivoError> {
+    let template_uuid = Uuid::parse_str(&template_id).map_err(PivoError::from)?;
+    let template = state
+        .task_service
+        .get_task_template(template_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or_else(|| PivoError::NotFound("Task template not found".to_string()))?;
+    let rendered = template.render(&placeholder_values).map_err(PivoError::InvalidInput)?;
+
+    let request = CreateTaskRequest {
+        project_id: Uuid::parse_str(&template.project_id).map_err(PivoError::from)?,
+        title: rendered.title,
+        description: rendered.description,
+        priority: template.priority,
+        parent_task_id: None,
+        assignee: None,
+        tags: template.tags,
+        base_branch: None,
+        executor: template.executor,
+    };
+    let task = state
+        .task_service
+        .create_task(request)
+        .await
+        .map_err(PivoError::from)?;
+    start_first_attempt_setup(&state, &app_handle, &task).await?;
+
+    // Same path as the SEND_MESSAGE task command, so the prompt is queued while
+    // the setup script runs. The task exists by now, so a failure only gets logged.
+    if let Some(prompt) = rendered.initial_prompt {
+        if let Err(e) = handle_send_message(&app_handle, &state, &cli_state, &task.id, prompt, None).await {
+            log::error!("Failed to send the initial prompt of template {} to task {}: {}", template.id, task.id, e);
+        }
+    }
+
+    Ok(task)
+}
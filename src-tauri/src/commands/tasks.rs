@@ -1,44 +1,336 @@
-use crate::models::{CreateTaskRequest, Task, TaskStatus, UpdateTaskRequest};
+use crate::models::{
+    CreateTaskRequest, ProcessStatus, ProcessType, StatusChangeTrigger, Task, TaskAttempt, TaskEvent, TaskPage, TaskSearchFilters, TaskSearchResult,
+    TaskStatus, TaskStatusChange, UpdateTaskRequest,
+};
+use crate::commands::cli::CliState;
+use crate::commands::task_commands::dispatch_after_setup;
+use crate::services::{ConfigService, GitService};
+use crate::utils::command::shell_script_command;
 use crate::AppState;
-use tauri::{State, AppHandle, Emitter};
+use crate::error::PivoError;
+use tauri::{State, AppHandle, Emitter, Manager};
+use tokio::sync::Mutex;
+use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[tauri::command]
 pub async fn create_task(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     request: CreateTaskRequest,
-) -> Result<Task, String> {
-    state
+) -> Result<Task, PivoError> {
+    if let Some(base_branch) = request.base_branch.as_deref() {
+        ensure_base_branch_exists(&state, &request.project_id.to_string(), base_branch).await?;
+    }
+    
+    let task = state
         .task_service
         .create_task(request)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)?;
+    
+    start_first_attempt_setup(&state, &app_handle, &task).await?;
+    Ok(task)
+}
+
+/// Create a child task whose worktree branches off the parent's current branch
+#[tauri::command]
+pub async fn create_subtask(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    parent_id: String,
+    request: CreateTaskRequest,
+) -> Result<Task, PivoError> {
+    let parent_uuid = Uuid::parse_str(&parent_id).map_err(PivoError::from)?;
+    let parent = state
+        .task_service
+        .get_task(parent_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or_else(|| PivoError::NotFound(format!("Task {} not found", parent_id)))?;
+    if let Some(base_branch) = request.base_branch.as_deref().filter(|b| !b.trim().is_empty()) {
+        ensure_base_branch_exists(&state, &parent.project_id, base_branch).await?;
+    }
+    
+    let task = state
+        .task_service
+        .create_subtask(parent_uuid, request)
+        .await
+        .map_err(PivoError::from)?;
+    
+    start_first_attempt_setup(&state, &app_handle, &task).await?;
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn list_subtasks(
+    state: State<'_, AppState>,
+    parent_id: String,
+) -> Result<Vec<Task>, PivoError> {
+    let uuid = Uuid::parse_str(&parent_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .list_subtasks(uuid)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Run the setup script in the worktree of a new task. Setup problems are
+/// reported on the attempt, the task itself was created.
+pub(crate) async fn start_first_attempt_setup(state: &AppState, app_handle: &AppHandle, task: &Task) -> Result<(), PivoError> {
+    let task_uuid = Uuid::parse_str(&task.id).map_err(PivoError::from)?;
+    let attempt = state
+        .task_service
+        .list_task_attempts(task_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .into_iter()
+        .next();
+    if let Some(attempt) = attempt {
+        if let Err(e) = start_attempt_setup(state, app_handle, &task.project_id, &attempt).await {
+            log::error!("Failed to start setup script for task {}: {}", task.id, e);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Reject an explicitly chosen base branch that does not resolve in the project
+/// repository, instead of silently falling back to the default branch
+pub(crate) async fn ensure_base_branch_exists(
+    state: &AppState,
+    project_id: &str,
+    base_branch: &str,
+) -> Result<(), PivoError> {
+    if base_branch.trim().is_empty() {
+        return Ok(());
+    }
+    
+    let project_uuid = Uuid::parse_str(project_id).map_err(PivoError::from)?;
+    let project = state
+        .project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Project not found")?;
+    // Without git every attempt runs in the project directory
+    if !project.git_enabled {
+        return Ok(());
+    }
+    
+    let repo_path = Path::new(&project.path);
+    let git_service = GitService::new();
+    if git_service.get_branch_commit(repo_path, base_branch).is_ok() {
+        return Ok(());
+    }
+    
+    let default_branch = git_service
+        .detect_default_branch(repo_path)
+        .unwrap_or_else(|_| project.main_branch.clone());
+    Err(PivoError::InvalidInput(format!(
+        "Base branch '{}' does not exist in {} (default branch is '{}')",
+        base_branch, project.path, default_branch
+    )))
+}
+
+/// Run the project's setup script in the attempt's freshly created worktree,
+/// unless that is turned off in the execution settings
+pub(crate) async fn start_attempt_setup(
+    state: &AppState,
+    app_handle: &AppHandle,
+    project_id: &str,
+    attempt: &TaskAttempt,
+) -> Result<(), PivoError> {
+    let run_setup_script = app_handle
+        .state::<Arc<Mutex<ConfigService>>>()
+        .lock()
+        .await
+        .get_execution_config()
+        .run_setup_script;
+    if !run_setup_script {
+        return Ok(());
+    }
+    
+    run_attempt_setup(state, app_handle, project_id, attempt).await
+}
+
+/// Run the project's setup script in an attempt's worktree. The attempt is
+/// marked ready once the script finishes (see ProcessService), and messages
+/// queued while it ran are sent if it succeeded.
+pub(crate) async fn run_attempt_setup(
+    state: &AppState,
+    app_handle: &AppHandle,
+    project_id: &str,
+    attempt: &TaskAttempt,
+) -> Result<(), PivoError> {
+    let project_uuid = Uuid::parse_str(project_id).map_err(PivoError::from)?;
+    let project = state
+        .project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Project not found")?;
+    
+    let setup_script = match project.setup_script.as_deref().map(str::trim) {
+        Some(script) if !script.is_empty() => script.to_string(),
+        _ => return Ok(()),
+    };
+    
+    let attempt_uuid = Uuid::parse_str(&attempt.id).map_err(PivoError::from)?;
+    
+    state
+        .task_service
+        .update_attempt_setup_status(attempt_uuid, "running")
+        .await
+        .map_err(PivoError::from)?;
+    
+    let (command, args) = shell_script_command(&setup_script);
+    
+    let process_id = match state
+        .process_service
+        .spawn_process(
+            &project.id,
+            Some(attempt_uuid),
+            ProcessType::SetupScript,
+            command,
+            args,
+            attempt.worktree_path.clone(),
+            None,
+            app_handle.clone(),
+        )
+        .await
+        .map_err(PivoError::from)
+    {
+        Ok(process_id) => process_id,
+        Err(e) => {
+            let _ = state.task_service.update_attempt_setup_status(attempt_uuid, "failed").await;
+            let _ = app_handle.emit("attempt:setup-completed", &serde_json::json!({
+                "attemptId": attempt.id,
+                "success": false,
+                "error": format!("The setup script could not be started: {}", e),
+            }));
+            return Err(e);
+        }
+    };
+    
+    let _ = app_handle.emit("attempt:setup-started", &serde_json::json!({
+        "taskId": attempt.task_id,
+        "attemptId": attempt.id,
+        "processId": process_id,
+        "command": setup_script,
+    }));
+    
+    tauri::async_runtime::spawn(dispatch_after_setup(app_handle.clone(), attempt.task_id.clone(), attempt.id.clone()));
+    
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn get_task(
     state: State<'_, AppState>,
     id: String,
-) -> Result<Option<Task>, String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+) -> Result<Option<Task>, PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
     state
         .task_service
         .get_task(uuid)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
 pub async fn list_tasks(
     state: State<'_, AppState>,
     project_id: String,
-) -> Result<Vec<Task>, String> {
-    let uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    include_archived: Option<bool>,
+) -> Result<Vec<Task>, PivoError> {
+    let uuid = Uuid::parse_str(&project_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .list_tasks(uuid, include_archived.unwrap_or(false))
+        .await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn list_tasks_paginated(
+    state: State<'_, AppState>,
+    project_id: String,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    status_filter: Option<Vec<TaskStatus>>,
+) -> Result<TaskPage, PivoError> {
+    let uuid = Uuid::parse_str(&project_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .list_tasks_paginated(uuid, cursor, limit.unwrap_or(50), status_filter)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Full-text search over task titles, descriptions, tags and, with
+/// `filters.include_conversations`, agent conversations, in one project or all
+#[tauri::command]
+pub async fn search_tasks(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    query: String,
+    filters: Option<TaskSearchFilters>,
+) -> Result<Vec<TaskSearchResult>, PivoError> {
+    let project_uuid = project_id
+        .map(|id| Uuid::parse_str(&id).map_err(PivoError::from))
+        .transpose()?;
+    state
+        .task_service
+        .search_tasks(project_uuid, &query, filters.unwrap_or_default())
+        .await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn get_project_cost(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<f64, PivoError> {
+    let uuid = Uuid::parse_str(&project_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .get_total_cost_for_project(uuid)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// A task's activity: status changes, agent executions, commits and merge
+/// requests. Returns the latest `limit` events before `before`, oldest first.
+#[tauri::command]
+pub async fn get_task_timeline(
+    state: State<'_, AppState>,
+    task_id: String,
+    limit: Option<usize>,
+    before: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Vec<TaskEvent>, PivoError> {
+    let uuid = Uuid::parse_str(&task_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .get_task_timeline(uuid, limit.unwrap_or(50), before)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Every status change of a task and whether the user, the agent, a merge
+/// request or the app made it, oldest first
+#[tauri::command]
+pub async fn get_task_status_history(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<Vec<TaskStatusChange>, PivoError> {
+    let uuid = Uuid::parse_str(&task_id).map_err(PivoError::from)?;
     state
         .task_service
-        .list_tasks(uuid)
+        .get_task_status_history(uuid)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
@@ -46,26 +338,122 @@ pub async fn update_task(
     state: State<'_, AppState>,
     id: String,
     request: UpdateTaskRequest,
-) -> Result<Task, String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+) -> Result<Task, PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
     state
         .task_service
         .update_task(uuid, request)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
 pub async fn delete_task(
     state: State<'_, AppState>,
     id: String,
-) -> Result<(), String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+) -> Result<(), PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
     state
         .task_service
         .delete_task(uuid)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(PivoError::from)
+}
+
+/// Undo the deletion of a task that hasn't been purged yet
+#[tauri::command]
+pub async fn restore_task(
+    state: State<'_, AppState>,
+    task_id: String,
+) -> Result<Task, PivoError> {
+    let uuid = Uuid::parse_str(&task_id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .restore_task(uuid)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Permanently remove tasks deleted more than `older_than_days` days ago,
+/// stopping whatever still runs for them. Returns how many were purged.
+#[tauri::command]
+pub async fn purge_deleted_tasks(
+    state: State<'_, AppState>,
+    cli_state: State<'_, CliState>,
+    older_than_days: u32,
+) -> Result<usize, PivoError> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days as i64);
+    let tasks = state
+        .task_service
+        .list_deleted_tasks_before(cutoff)
+        .await
+        .map_err(PivoError::from)?;
+
+    for task in &tasks {
+        for execution in cli_state.service.list_executions().iter().filter(|e| e.task_id == task.id) {
+            if let Err(e) = cli_state.service.stop_execution(&execution.id).await {
+                log::warn!("Failed to stop execution {} of purged task {}: {}", execution.id, task.id, e);
+            }
+        }
+
+        let task_uuid = Uuid::parse_str(&task.id).map_err(PivoError::from)?;
+        for attempt in state.task_service.list_task_attempts(task_uuid).await.map_err(PivoError::from)? {
+            let attempt_uuid = Uuid::parse_str(&attempt.id).map_err(PivoError::from)?;
+            let processes = state
+                .process_service
+                .list_processes_for_attempt(attempt_uuid)
+                .await
+                .map_err(PivoError::from)?;
+            for process in processes.iter().filter(|p| p.status == ProcessStatus::Running) {
+                let process_uuid = Uuid::parse_str(&process.id).map_err(PivoError::from)?;
+                if let Err(e) = state.process_service.kill_process(process_uuid).await {
+                    log::warn!("Failed to stop process {} of purged task {}: {}", process.id, task.id, e);
+                }
+            }
+        }
+    }
+
+    state
+        .task_service
+        .purge_tasks(&tasks)
+        .await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn archive_task(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Task, PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    let task = state
+        .task_service
+        .get_task(uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or_else(|| format!("Task {} not found", id))?;
+    if task.status == TaskStatus::Working {
+        return Err(PivoError::InvalidInput("Stop the running task before archiving it".to_string()));
+    }
+
+    state
+        .task_service
+        .archive_task(uuid)
+        .await
+        .map_err(PivoError::from)
+}
+
+#[tauri::command]
+pub async fn unarchive_task(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Task, PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    state
+        .task_service
+        .unarchive_task(uuid)
+        .await
+        .map_err(PivoError::from)
 }
 
 #[tauri::command]
@@ -74,23 +462,23 @@ pub async fn update_task_status(
     app_handle: AppHandle,
     id: String,
     status: TaskStatus,
-) -> Result<Task, String> {
-    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+) -> Result<Task, PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
     
     // Get previous status before update
     let previous_task = state
         .task_service
         .get_task(uuid)
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(PivoError::from)?
         .ok_or("Task not found")?;
     let previous_status = previous_task.status.clone();
     
     let task = state
         .task_service
-        .update_task_status(uuid, status.clone())
+        .update_task_status(uuid, status.clone(), StatusChangeTrigger::User)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(PivoError::from)?;
     
     // Emit task status update event with new format
     let _ = app_handle.emit("task:status-changed", &serde_json::json!({
@@ -103,5 +491,151 @@ pub async fn update_task_status(
     Ok(task)
 }
 
+/// Move several tasks of one project to `status` at once, e.g. when several
+/// cards are dragged on the board. A single `task:bulk-status-changed` event
+/// is emitted for the whole batch.
+#[tauri::command]
+pub async fn bulk_update_task_status(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    task_ids: Vec<String>,
+    status: String,
+) -> Result<Vec<Task>, PivoError> {
+    let status: TaskStatus = serde_json::from_value(serde_json::Value::String(status))
+        .map_err(|e| PivoError::InvalidInput(format!("Invalid task status: {}", e)))?;
+    let uuids = task_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id).map_err(PivoError::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    // Get previous statuses before update
+    let mut previous_statuses = serde_json::Map::new();
+    for uuid in &uuids {
+        if let Some(task) = state.task_service.get_task(*uuid).await.map_err(PivoError::from)?.filter(|task| task.deleted_at.is_none()) {
+            previous_statuses.insert(task.id.clone(), serde_json::json!(task.status));
+        }
+    }
+    
+    let tasks = state
+        .task_service
+        .update_tasks_status(uuids, status.clone(), StatusChangeTrigger::User)
+        .await
+        .map_err(PivoError::from)?;
+    
+    if let Some(first) = tasks.first() {
+        let _ = app_handle.emit("task:bulk-status-changed", &serde_json::json!({
+            "projectId": first.project_id,
+            "taskIds": tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>(),
+            "previousStatuses": previous_statuses,
+            "newStatus": status,
+            "tasks": &tasks
+        }));
+    }
+    
+    Ok(tasks)
+}
+
+#[tauri::command]
+pub async fn move_task_to_project(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    id: String,
+    target_project_id: String,
+) -> Result<Task, PivoError> {
+    let uuid = Uuid::parse_str(&id).map_err(PivoError::from)?;
+    let target_uuid = Uuid::parse_str(&target_project_id).map_err(PivoError::from)?;
+    
+    let previous_task = state
+        .task_service
+        .get_task(uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task not found")?;
+    
+    let moved = state
+        .task_service
+        .move_task_to_project(uuid, target_uuid)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => "Target project not found".to_string(),
+            e => e.to_string(),
+        })?;
+    
+    if previous_task.project_id != moved.task.project_id {
+        let _ = app_handle.emit("task:moved", &serde_json::json!({
+            "taskId": id,
+            "previousProjectId": previous_task.project_id,
+            "newProjectId": moved.task.project_id,
+            "task": &moved.task,
+            "leftBehindWorktrees": moved.left_behind_worktrees
+        }));
+    }
+    
+    Ok(moved.task)
+}
+
+#[tauri::command]
+pub async fn reorder_tasks(
+    state: State<'_, AppState>,
+    project_id: String,
+    ordered_ids: Vec<String>,
+) -> Result<(), PivoError> {
+    let project_uuid = Uuid::parse_str(&project_id).map_err(PivoError::from)?;
+    let ordered_uuids = ordered_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(PivoError::from)?;
+
+    state
+        .task_service
+        .reorder_tasks(project_uuid, ordered_uuids)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Move the task owning `task_attempt_id` to Done and notify the frontend.
+/// Used when a merge request is merged from within the app.
+pub(crate) async fn mark_attempt_task_done(
+    state: &AppState,
+    app_handle: &AppHandle,
+    task_attempt_id: &str,
+) -> Result<(), PivoError> {
+    let attempt_uuid = Uuid::parse_str(task_attempt_id).map_err(PivoError::from)?;
+    let attempt = state
+        .task_service
+        .get_task_attempt(attempt_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task attempt not found")?;
+    let task_uuid = Uuid::parse_str(&attempt.task_id).map_err(PivoError::from)?;
+    
+    let previous_task = state
+        .task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(PivoError::from)?
+        .ok_or("Task not found")?;
+    
+    if previous_task.status == TaskStatus::Done {
+        return Ok(());
+    }
+    
+    let task = state
+        .task_service
+        .update_task_status(task_uuid, TaskStatus::Done, StatusChangeTrigger::VcsMerged)
+        .await
+        .map_err(PivoError::from)?;
+    
+    let _ = app_handle.emit("task:status-changed", &serde_json::json!({
+        "taskId": attempt.task_id,
+        "previousStatus": previous_task.status,
+        "newStatus": TaskStatus::Done,
+        "task": &task
+    }));
+    
+    Ok(())
+}
+
 // Removed execute_task - functionality moved to SendMessage in task_commands
 // Tasks must have an existing attempt before sending messages
\ No newline at end of file
@@ -0,0 +1,48 @@
+use crate::services::{ConfigService, VcsSyncConfig, MIN_VCS_SYNC_INTERVAL_SECONDS};
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::{watch, Mutex};
+
+/// Pushes updated settings to the running `VcsSyncService` loop
+pub struct VcsSyncState {
+    pub config_tx: watch::Sender<VcsSyncConfig>,
+}
+
+#[tauri::command]
+pub async fn get_vcs_sync_config(
+    state: State<'_, Arc<Mutex<ConfigService>>>,
+) -> Result<VcsSyncConfig, String> {
+    let config_service = state.lock().await;
+    Ok(config_service.get_vcs_sync_config().clone())
+}
+
+#[tauri::command]
+pub async fn update_vcs_sync_config(
+    config_state: State<'_, Arc<Mutex<ConfigService>>>,
+    vcs_sync_state: State<'_, VcsSyncState>,
+    config: VcsSyncConfig,
+) -> Result<(), String> {
+    if config.sync_interval_seconds < MIN_VCS_SYNC_INTERVAL_SECONDS {
+        return Err(format!(
+            "Sync interval must be at least {} seconds",
+            MIN_VCS_SYNC_INTERVAL_SECONDS
+        ));
+    }
+    
+    let mut config_service = config_state.lock().await;
+    config_service.update_vcs_sync_config(config.clone()).await
+        .map_err(|e| e.to_string())?;
+    drop(config_service); // Release lock
+    
+    // Only wake the sync loop when something actually changed
+    vcs_sync_state.config_tx.send_if_modified(|current| {
+        if *current == config {
+            false
+        } else {
+            *current = config;
+            true
+        }
+    });
+    
+    Ok(())
+}
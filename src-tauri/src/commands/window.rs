@@ -1,5 +1,6 @@
 use tauri::{Manager, State, WebviewUrl, WebviewWindowBuilder};
 use crate::AppState;
+use uuid::Uuid;
 
 #[tauri::command]
 pub async fn show_log_viewer(app: tauri::AppHandle) -> Result<(), String> {
@@ -34,6 +35,34 @@ pub async fn open_project_window(
     state.window_manager.open_project_window(&project_id, &project_name).await
 }
 
+/// Open (or focus) the window of a project and select one of its tasks,
+/// e.g. when a notification about the task is clicked
+#[tauri::command]
+pub async fn open_project_window_at_task(
+    project_id: String,
+    task_id: String,
+    state: State<'_, AppState>
+) -> Result<String, String> {
+    let project_uuid = Uuid::parse_str(&project_id).map_err(|e| e.to_string())?;
+    let project = state.project_service
+        .get_project(project_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Project not found")?;
+    
+    let task_uuid = Uuid::parse_str(&task_id).map_err(|e| e.to_string())?;
+    let task = state.task_service
+        .get_task(task_uuid)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Task not found")?;
+    if task.project_id != project.id {
+        return Err("Task does not belong to this project".to_string());
+    }
+    
+    state.window_manager.open_project_window_at_task(&project.id, &project.name, &task.id).await
+}
+
 #[tauri::command]
 pub async fn close_project_window(
     project_id: String,
@@ -0,0 +1,316 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use crate::services::ConfigService;
+use super::{DbPool, DATABASE_FILE};
+
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "pivo-";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+/// SQLite keeps uncheckpointed writes next to the database in these files
+const SIDECAR_SUFFIXES: [&str; 2] = ["-wal", "-shm"];
+
+const DAILY_BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Backups taken to recover from a restore, rollback or failed migration don't
+/// count towards `max_backups` and are kept this long instead
+pub const RECOVERY_BACKUP_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupKind {
+    Startup,
+    Daily,
+    Manual,
+    /// The database as it was before a backup was restored over it
+    PreRestore,
+    /// The database as it was before its last migration was rolled back
+    PreRollback,
+    /// A database that failed to migrate and was replaced by a fresh one
+    Corrupt,
+}
+
+impl BackupKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupKind::Startup => "startup",
+            BackupKind::Daily => "daily",
+            BackupKind::Manual => "manual",
+            BackupKind::PreRestore => "pre-restore",
+            BackupKind::PreRollback => "pre-rollback",
+            BackupKind::Corrupt => "corrupt",
+        }
+    }
+
+    /// Whether the backup holds data that exists nowhere else after a restore,
+    /// rollback or failed migration
+    fn is_recovery(self) -> bool {
+        matches!(self, BackupKind::PreRestore | BackupKind::PreRollback | BackupKind::Corrupt)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [BackupKind::Startup, BackupKind::Daily, BackupKind::Manual, BackupKind::PreRestore, BackupKind::PreRollback, BackupKind::Corrupt]
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseBackup {
+    /// File name without extension, e.g. "pivo-20250216-093000123-startup"
+    pub id: String,
+    pub kind: BackupKind,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+impl DatabaseBackup {
+    fn from_path(path: &Path) -> Option<Self> {
+        let id = path.file_name()?.to_str()?.strip_suffix(".db")?;
+        let rest = id.strip_prefix(BACKUP_PREFIX)?;
+        let (timestamp, kind) = rest.split_at_checked(18)?;
+        let created_at = NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).ok()?.and_utc();
+        let kind = BackupKind::parse(kind.strip_prefix('-')?)?;
+
+        Some(Self {
+            id: id.to_string(),
+            kind,
+            created_at,
+            size_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        })
+    }
+}
+
+pub fn backup_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(BACKUP_DIR)
+}
+
+fn new_backup_path(app_dir: &Path, kind: BackupKind) -> io::Result<PathBuf> {
+    let dir = backup_dir(app_dir);
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!(
+        "{}{}-{}.db",
+        BACKUP_PREFIX,
+        Utc::now().format(BACKUP_TIMESTAMP_FORMAT),
+        kind.as_str()
+    )))
+}
+
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Copy the database file together with its WAL and SHM files. Only consistent
+/// while no connection is open; returns None if there is no database yet.
+pub fn copy_database_files(app_dir: &Path, kind: BackupKind) -> io::Result<Option<DatabaseBackup>> {
+    let db_path = app_dir.join(DATABASE_FILE);
+    if !db_path.exists() {
+        return Ok(None);
+    }
+
+    let backup_path = new_backup_path(app_dir, kind)?;
+    fs::copy(&db_path, &backup_path)?;
+    for suffix in SIDECAR_SUFFIXES {
+        let source = sidecar(&db_path, suffix);
+        if source.exists() {
+            fs::copy(&source, sidecar(&backup_path, suffix))?;
+        }
+    }
+
+    Ok(DatabaseBackup::from_path(&backup_path))
+}
+
+/// Move the database and its WAL and SHM files into the backups folder
+pub fn move_database_aside(app_dir: &Path, kind: BackupKind) -> io::Result<Option<DatabaseBackup>> {
+    let db_path = app_dir.join(DATABASE_FILE);
+    if !db_path.exists() {
+        return Ok(None);
+    }
+
+    let backup_path = new_backup_path(app_dir, kind)?;
+    fs::rename(&db_path, &backup_path)?;
+    for suffix in SIDECAR_SUFFIXES {
+        let source = sidecar(&db_path, suffix);
+        if source.exists() {
+            fs::rename(&source, sidecar(&backup_path, suffix))?;
+        }
+    }
+
+    Ok(DatabaseBackup::from_path(&backup_path))
+}
+
+/// Write a consistent copy of the open database with `VACUUM INTO`
+pub async fn snapshot_database(pool: &DbPool, app_dir: &Path, kind: BackupKind) -> Result<DatabaseBackup, String> {
+    let backup_path = new_backup_path(app_dir, kind).map_err(|e| e.to_string())?;
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(backup_path.to_string_lossy().to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to back up database: {}", e))?;
+
+    DatabaseBackup::from_path(&backup_path).ok_or_else(|| "Backup was not written".to_string())
+}
+
+/// Backups in the backups folder, newest first
+pub fn list_backups(app_dir: &Path) -> io::Result<Vec<DatabaseBackup>> {
+    let dir = backup_dir(app_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<DatabaseBackup> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| DatabaseBackup::from_path(&entry.path()))
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(backups)
+}
+
+/// Delete all but the `keep` newest startup, daily and manual backups, and
+/// recovery backups older than `RECOVERY_BACKUP_RETENTION_DAYS`. A backup that
+/// can't be removed is skipped.
+pub fn prune_backups(app_dir: &Path, keep: usize) -> io::Result<()> {
+    let dir = backup_dir(app_dir);
+    let recovery_cutoff = Utc::now() - chrono::Duration::days(RECOVERY_BACKUP_RETENTION_DAYS);
+    let (recovery, routine): (Vec<_>, Vec<_>) = list_backups(app_dir)?
+        .into_iter()
+        .partition(|backup| backup.kind.is_recovery());
+    let expired = routine
+        .into_iter()
+        .skip(keep.max(1))
+        .chain(recovery.into_iter().filter(|backup| backup.created_at < recovery_cutoff));
+
+    for backup in expired {
+        let path = dir.join(format!("{}.db", backup.id));
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!("Failed to remove database backup {}: {}", backup.id, e);
+            continue;
+        }
+        for suffix in SIDECAR_SUFFIXES {
+            let _ = fs::remove_file(sidecar(&path, suffix));
+        }
+        log::info!("Removed old database backup {}", backup.id);
+    }
+
+    Ok(())
+}
+
+/// Path of an existing backup; ids are checked so they can't point outside the backups folder
+pub fn backup_path(app_dir: &Path, backup_id: &str) -> Result<PathBuf, String> {
+    if !backup_id.starts_with(BACKUP_PREFIX) || !backup_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid backup id: {}", backup_id));
+    }
+
+    let path = backup_dir(app_dir).join(format!("{}.db", backup_id));
+    if !path.exists() {
+        return Err(format!("Backup {} not found", backup_id));
+    }
+
+    Ok(path)
+}
+
+/// Copy a backup with its WAL and SHM files to `target`, replacing what is there
+pub fn copy_backup_to(backup: &Path, target: &Path) -> io::Result<()> {
+    fs::copy(backup, target)?;
+    for suffix in SIDECAR_SUFFIXES {
+        let source = sidecar(backup, suffix);
+        let destination = sidecar(target, suffix);
+        if source.exists() {
+            fs::copy(&source, &destination)?;
+        } else if destination.exists() {
+            fs::remove_file(&destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace `target` and its WAL and SHM files with `source`'s
+pub fn replace_database_files(source: &Path, target: &Path) -> io::Result<()> {
+    for suffix in SIDECAR_SUFFIXES {
+        let stale = sidecar(target, suffix);
+        if stale.exists() {
+            fs::remove_file(&stale)?;
+        }
+    }
+
+    fs::rename(source, target)?;
+    for suffix in SIDECAR_SUFFIXES {
+        let source_sidecar = sidecar(source, suffix);
+        if source_sidecar.exists() {
+            fs::rename(&source_sidecar, sidecar(target, suffix))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Take a backup once a day for as long as the app runs. The startup backup
+/// covers the first day, so the first one is taken a day after launch.
+pub async fn run_daily_backups(pool: DbPool, app_dir: PathBuf, config_service: Arc<Mutex<ConfigService>>) {
+    let mut ticker = interval(DAILY_BACKUP_INTERVAL);
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        if pool.is_closed() {
+            break;
+        }
+
+        match snapshot_database(&pool, &app_dir, BackupKind::Daily).await {
+            Ok(backup) => log::info!("Created daily database backup {}", backup.id),
+            Err(e) => {
+                log::error!("Daily database backup failed: {}", e);
+                continue;
+            }
+        }
+
+        let keep = config_service.lock().await.get_backup_config().max_backups;
+        if let Err(e) = prune_backups(&app_dir, keep) {
+            log::warn!("Failed to remove old database backups: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_backup(app_dir: &Path, created_at: DateTime<Utc>, kind: BackupKind) -> String {
+        let id = format!("{}{}-{}", BACKUP_PREFIX, created_at.format(BACKUP_TIMESTAMP_FORMAT), kind.as_str());
+        fs::write(backup_dir(app_dir).join(format!("{}.db", id)), b"").unwrap();
+        id
+    }
+
+    #[test]
+    fn pruning_keeps_recovery_backups_for_their_retention_window() {
+        let app_dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(backup_dir(app_dir.path())).unwrap();
+        let now = Utc::now();
+        let corrupt = write_backup(app_dir.path(), now - chrono::Duration::days(20), BackupKind::Corrupt);
+        let pre_restore = write_backup(app_dir.path(), now - chrono::Duration::days(10), BackupKind::PreRestore);
+        let expired = write_backup(app_dir.path(), now - chrono::Duration::days(RECOVERY_BACKUP_RETENTION_DAYS + 1), BackupKind::PreRollback);
+        let startups: Vec<String> = (0..5)
+            .map(|i| write_backup(app_dir.path(), now - chrono::Duration::hours(i), BackupKind::Startup))
+            .collect();
+
+        prune_backups(app_dir.path(), 2).unwrap();
+
+        let mut remaining: Vec<String> = list_backups(app_dir.path()).unwrap().into_iter().map(|b| b.id).collect();
+        remaining.sort();
+        let mut expected = vec![corrupt, pre_restore, startups[0].clone(), startups[1].clone()];
+        expected.sort();
+        assert_eq!(remaining, expected);
+        assert!(!remaining.contains(&expired));
+    }
+}
@@ -1,72 +1,385 @@
-use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite, migrate::Migrator};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+    Pool, Sqlite, migrate::Migrator,
+};
+use serde::Serialize;
 use tauri::{AppHandle, Manager};
 
+pub mod backup;
+#[cfg(test)]
+pub(crate) mod test_support;
+
+use backup::BackupKind;
+
 pub type DbPool = Pool<Sqlite>;
 
+pub const DATABASE_FILE: &str = "pivo.db";
+
+/// How long a connection waits for another writer before giving up with SQLITE_BUSY
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// SQLite allows a single writer at a time, so more connections only help
+/// readers. Enough for the UI, process output, MR syncs and conversation saves.
+const MAX_CONNECTIONS: u32 = 8;
+
+/// How long a query waits for a free connection before failing
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Attempts made by `retry_on_busy` before the error is returned
+const BUSY_RETRY_ATTEMPTS: u32 = 5;
+const BUSY_RETRY_DELAY: Duration = Duration::from_millis(100);
+
 // Embed migrations at compile time
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
-pub async fn init_database(app_handle: &AppHandle) -> Result<DbPool, Box<dyn std::error::Error>> {
+pub fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, Box<dyn std::error::Error>> {
     let app_dir = app_handle
         .path()
         .app_data_dir()?;
-    
+
     // Create app data directory if it doesn't exist
     std::fs::create_dir_all(&app_dir)?;
-    
-    let db_path = app_dir.join("pivo.db");
-    let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
-    
+
+    Ok(app_dir)
+}
+
+/// Open the database at `db_path`, creating it if needed, and bring its schema up to date
+pub async fn open_and_migrate(db_path: &Path) -> Result<DbPool, Box<dyn std::error::Error>> {
+    // Pragmas set here are applied to every connection the pool opens:
+    // - WAL lets readers continue while another service is writing
+    // - NORMAL sync is durable under WAL except on power loss, and much cheaper
+    //   for the many small writes of process output
+    // - the busy timeout makes concurrent writers wait for the lock instead of
+    //   failing straight away with "database is locked"
+    let options = SqliteConnectOptions::new()
+        .filename(db_path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(BUSY_TIMEOUT)
+        .foreign_keys(true);
+
     // Create connection pool
     let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&db_url)
+        .max_connections(MAX_CONNECTIONS)
+        .acquire_timeout(ACQUIRE_TIMEOUT)
+        .connect_with(options)
         .await?;
-    
+
     // Run embedded migrations using SQLx's standard approach
-    match MIGRATOR.run(&pool).await {
-        Ok(_) => {
+    let known_good = applied_migrations(&pool).await.last().copied();
+    if let Err(e) = MIGRATOR.run(&pool).await {
+        if let Some(version) = known_good {
+            roll_back_to(&pool, version).await;
+        }
+        pool.close().await;
+        return Err(Box::new(e));
+    }
+
+    Ok(pool)
+}
+
+/// Versions of the migrations applied to the database, oldest first.
+/// Empty for a new database that has no migrations table yet.
+async fn applied_migrations(pool: &DbPool) -> Vec<i64> {
+    sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success = 1 ORDER BY version")
+        .fetch_all(pool)
+        .await
+        .unwrap_or_default()
+}
+
+/// Undo the migrations an interrupted upgrade applied after `version`, so the
+/// database is left as the last version that opened it successfully rather
+/// than half-upgraded. Only reversible migrations can be undone.
+async fn roll_back_to(pool: &DbPool, version: i64) {
+    let applied = applied_migrations(pool).await;
+    if applied.last().is_none_or(|&latest| latest <= version) {
+        return;
+    }
+
+    match MIGRATOR.undo(pool, version).await {
+        Ok(()) => log::warn!("Rolled back database migrations to version {}", version),
+        Err(e) => log::error!("Failed to roll back database migrations to version {}: {}", version, e),
+    }
+
+    let remaining: Vec<i64> = applied_migrations(pool).await.into_iter().filter(|&v| v > version).collect();
+    if !remaining.is_empty() {
+        log::warn!("Migrations {:?} have no down migration and were left applied", remaining);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RolledBackMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+/// Undo the most recently applied migration. Fails when it has no down migration.
+pub async fn rollback_last_migration(pool: &DbPool) -> Result<RolledBackMigration, String> {
+    let applied = applied_migrations(pool).await;
+    let Some((&latest, earlier)) = applied.split_last() else {
+        return Err("No migrations have been applied".to_string());
+    };
+
+    let migration = MIGRATOR
+        .iter()
+        .find(|m| m.version == latest && m.migration_type.is_down_migration())
+        .ok_or_else(|| format!("Migration {} can't be rolled back", latest))?;
+
+    MIGRATOR
+        .undo(pool, earlier.last().copied().unwrap_or(0))
+        .await
+        .map_err(|e| format!("Failed to roll back migration {}: {}", latest, e))?;
+
+    Ok(RolledBackMigration {
+        version: latest,
+        description: migration.description.to_string(),
+    })
+}
+
+/// Whether `error` means the database was locked by another connection
+pub fn is_busy_error(error: &sqlx::Error) -> bool {
+    // Extended result codes keep the primary code (SQLITE_BUSY = 5, SQLITE_LOCKED = 6) in the low byte
+    error
+        .as_database_error()
+        .and_then(|e| e.code())
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+}
+
+/// Run `operation`, retrying with a growing delay while the database is busy.
+/// For writes that must not be lost when the busy timeout alone isn't enough.
+pub async fn retry_on_busy<T, F, Fut>(mut operation: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(e) if attempt < BUSY_RETRY_ATTEMPTS && is_busy_error(&e) => {
+                log::warn!("Database busy (attempt {}/{}), retrying: {}", attempt, BUSY_RETRY_ATTEMPTS, e);
+                tokio::time::sleep(BUSY_RETRY_DELAY * attempt).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Back up the database, then open and migrate it. A failed upgrade is rolled
+/// back as far as the down migrations allow before the error is returned; the
+/// caller decides whether to start over with `recreate_database`.
+pub async fn init_database(app_handle: &AppHandle) -> Result<DbPool, Box<dyn std::error::Error>> {
+    let app_dir = app_data_dir(app_handle)?;
+
+    // No connection is open yet, so the files can be copied as they are
+    match backup::copy_database_files(&app_dir, BackupKind::Startup) {
+        Ok(Some(backup)) => log::info!("Backed up database to {}", backup.id),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to back up database before migrating: {}", e),
+    }
+
+    match open_and_migrate(&app_dir.join(DATABASE_FILE)).await {
+        Ok(pool) => {
             log::info!("Database migrations completed successfully");
             Ok(pool)
         }
         Err(e) => {
             log::error!("Database migration failed: {}", e);
-            
-            // Close the connection pool
-            pool.close().await;
-            
-            // Delete the database file
-            if db_path.exists() {
-                log::warn!("Removing corrupted database file: {:?}", db_path);
-                if let Err(remove_err) = std::fs::remove_file(&db_path) {
-                    log::error!("Failed to remove database file: {}", remove_err);
-                    return Err(Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Migration failed and couldn't remove database: {}", e)
-                    )));
-                }
-                
-                // Try to recreate the database
-                log::info!("Attempting to recreate database...");
-                let new_pool = SqlitePoolOptions::new()
-                    .max_connections(5)
-                    .connect(&db_url)
-                    .await?;
-                
-                // Try migrations again
-                match MIGRATOR.run(&new_pool).await {
-                    Ok(_) => {
-                        log::info!("Database recreated and migrations completed successfully");
-                        Ok(new_pool)
-                    }
-                    Err(retry_err) => {
-                        log::error!("Failed to recreate database: {}", retry_err);
-                        Err(Box::new(retry_err))
-                    }
+            Err(e)
+        }
+    }
+}
+
+/// Start over with an empty database. The current one is moved into the backups
+/// folder rather than deleted, so it can still be restored or inspected.
+pub async fn recreate_database(app_handle: &AppHandle) -> Result<DbPool, Box<dyn std::error::Error>> {
+    let app_dir = app_data_dir(app_handle)?;
+
+    if let Some(backup) = backup::move_database_aside(&app_dir, BackupKind::Corrupt)? {
+        log::warn!("Moved unusable database to backup {}", backup.id);
+    }
+
+    log::info!("Attempting to recreate database...");
+    let pool = open_and_migrate(&app_dir.join(DATABASE_FILE)).await?;
+    log::info!("Database recreated and migrations completed successfully");
+
+    Ok(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::test_support::TestDb;
+    use std::sync::Arc;
+    use uuid::Uuid;
+    use crate::models::{CapturedLine, ConversationMessage, CreateMergeRequestData, OutputStreamType};
+    use crate::repository::{ConversationRepository, DatabaseRepository};
+    use crate::services::{MergeRequestService, ProcessService, TaskService};
+
+    const WRITERS: usize = 8;
+    const MESSAGES_PER_WRITER: usize = 50;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_message_writes_are_not_lost() {
+        let db = TestDb::new().await;
+        let pool = db.pool.clone();
+
+        let (project_id, attempt_ids) = db.seed_attempts(WRITERS).await;
+
+        let repository = Arc::new(DatabaseRepository::new(pool.clone()));
+        let mut handles = Vec::new();
+        for attempt_id in attempt_ids.iter().copied() {
+            let repository = repository.clone();
+            handles.push(tokio::spawn(async move {
+                let conversations = ConversationRepository::new(&repository);
+                for i in 0..MESSAGES_PER_WRITER {
+                    let message = ConversationMessage {
+                        role: "assistant".to_string(),
+                        content: format!("message {}", i),
+                        timestamp: chrono::Utc::now().to_rfc3339(),
+                    };
+                    retry_on_busy(|| conversations.add_message(attempt_id, message.clone())).await?;
                 }
-            } else {
-                Err(Box::new(e))
+                Ok::<(), sqlx::Error>(())
+            }));
+        }
+
+        let reader_pool = pool.clone();
+        let reader = tokio::spawn(async move {
+            let task_service = TaskService::new(reader_pool);
+            for _ in 0..MESSAGES_PER_WRITER {
+                let tasks = task_service.list_tasks(project_id, false).await?;
+                assert_eq!(tasks.len(), WRITERS);
             }
+            Ok::<(), sqlx::Error>(())
+        });
+
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+        reader.await.unwrap().unwrap();
+
+        let conversations = ConversationRepository::new(&repository);
+        for attempt_id in attempt_ids {
+            let conversation = conversations.get_attempt_conversation(attempt_id).await.unwrap().unwrap();
+            let contents: Vec<String> = conversation.messages.into_iter().map(|m| m.content).collect();
+            let expected: Vec<String> = (0..MESSAGES_PER_WRITER).map(|i| format!("message {}", i)).collect();
+            assert_eq!(contents, expected);
+        }
+
+        db.close().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn process_output_and_merge_request_syncs_do_not_contend() {
+        let db = TestDb::new().await;
+        let pool = db.pool.clone();
+
+        let (_, attempt_ids) = db.seed_attempts(WRITERS).await;
+        let merge_requests = MergeRequestService::new(pool.clone());
+        let mut handles = Vec::new();
+        let mut syncs = Vec::new();
+        for (i, attempt_id) in attempt_ids.iter().enumerate() {
+            let process_id = Uuid::new_v4();
+            sqlx::query(
+                "INSERT INTO execution_processes (id, task_attempt_id, process_type, status, command, working_directory) VALUES (?, ?, 'devserver', 'running', 'npm', ?)",
+            )
+            .bind(process_id.to_string())
+            .bind(attempt_id.to_string())
+            .bind(db.dir().to_string_lossy().to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let now = chrono::Utc::now();
+            let mr = merge_requests
+                .create_merge_request(CreateMergeRequestData {
+                    task_attempt_id: attempt_id.to_string(),
+                    provider: "gitlab".to_string(),
+                    mr_id: i as i64,
+                    mr_iid: i as i64,
+                    mr_number: i as i64,
+                    title: format!("MR {}", i),
+                    description: None,
+                    state: "opened".to_string(),
+                    source_branch: format!("task-{}", i),
+                    target_branch: "main".to_string(),
+                    web_url: String::new(),
+                    merge_status: None,
+                    has_conflicts: false,
+                    pipeline_status: None,
+                    pipeline_url: None,
+                    pipeline_jobs: Vec::new(),
+                    created_at: now,
+                    updated_at: now,
+                    merged_at: None,
+                })
+                .await
+                .unwrap();
+
+            let output_pool = pool.clone();
+            handles.push(tokio::spawn(async move {
+                for line in 0..MESSAGES_PER_WRITER {
+                    ProcessService::append_output(&output_pool, process_id, OutputStreamType::Stdout, &[CapturedLine::new(line as i64, line.to_string())]).await?;
+                }
+                Ok::<Uuid, sqlx::Error>(process_id)
+            }));
+
+            let sync_pool = pool.clone();
+            syncs.push(tokio::spawn(async move {
+                let service = MergeRequestService::new(sync_pool);
+                let mut mr = mr;
+                for run in 0..MESSAGES_PER_WRITER {
+                    mr.pipeline_status = Some(format!("run {}", run));
+                    service.update_sync_status(&mr).await.map_err(|e| e.to_string())?;
+                }
+                Ok::<(), String>(())
+            }));
+        }
+
+        for sync in syncs {
+            sync.await.unwrap().unwrap();
         }
+
+        let process_service = ProcessService::new(pool.clone());
+        let expected: Vec<String> = (0..MESSAGES_PER_WRITER).map(|line| line.to_string()).collect();
+        for handle in handles {
+            let process_id = handle.await.unwrap().unwrap();
+            let page = process_service
+                .get_process_output(process_id, OutputStreamType::Stdout, 0, MESSAGES_PER_WRITER)
+                .await
+                .unwrap();
+            assert_eq!(page.lines, expected);
+            assert_eq!(page.total_lines, MESSAGES_PER_WRITER);
+        }
+
+        let synced = merge_requests.get_open_merge_requests().await.unwrap();
+        assert_eq!(synced.len(), WRITERS);
+        let last_run = format!("run {}", MESSAGES_PER_WRITER - 1);
+        assert!(synced.iter().all(|mr| mr.pipeline_status.as_deref() == Some(last_run.as_str())));
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn rolled_back_migration_is_applied_again_on_next_open() {
+        let db = TestDb::new().await;
+        let pool = db.pool.clone();
+        let latest = *applied_migrations(&pool).await.last().unwrap();
+
+        let migration = rollback_last_migration(&pool).await.unwrap();
+        assert_eq!(migration.version, latest);
+        assert!(!applied_migrations(&pool).await.contains(&latest));
+        pool.close().await;
+
+        let pool = open_and_migrate(&db.dir().join(DATABASE_FILE)).await.unwrap();
+        assert_eq!(applied_migrations(&pool).await.last(), Some(&latest));
+
+        pool.close().await;
     }
-}
\ No newline at end of file
+}
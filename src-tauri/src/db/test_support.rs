@@ -0,0 +1,83 @@
+//! Database fixture shared by the tests of the db module and the services
+
+use std::path::Path;
+use uuid::Uuid;
+use crate::models::{CreateProjectRequest, CreateTaskRequest, TaskPriority, WorktreeInfo};
+use crate::repository::TaskRepository;
+use crate::services::ProjectService;
+use super::{open_and_migrate, DbPool, DATABASE_FILE};
+
+/// A migrated database in a temporary directory, removed when dropped
+pub(crate) struct TestDb {
+    pub pool: DbPool,
+    dir: tempfile::TempDir,
+}
+
+impl TestDb {
+    pub async fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = open_and_migrate(&dir.path().join(DATABASE_FILE)).await.unwrap();
+        Self { pool, dir }
+    }
+
+    /// Directory holding the database file, also used as project path
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// A project with `count` tasks of one attempt each. Rows are inserted
+    /// directly; creating tasks through the service needs a git worktree.
+    pub async fn seed_attempts(&self, count: usize) -> (Uuid, Vec<Uuid>) {
+        let project = ProjectService::new(self.pool.clone())
+            .create_project(CreateProjectRequest {
+                name: "Stress".to_string(),
+                description: None,
+                path: self.dir().to_string_lossy().to_string(),
+                git_repo: None,
+                main_branch: None,
+                setup_script: None,
+                dev_script: None,
+                git_enabled: None,
+                subdirectory: None,
+            })
+            .await
+            .unwrap();
+        let project_id = Uuid::parse_str(&project.id).unwrap();
+
+        let mut attempt_ids = Vec::new();
+        let mut tx = self.pool.begin().await.unwrap();
+        for i in 0..count {
+            let task_id = Uuid::new_v4();
+            let request = CreateTaskRequest {
+                project_id,
+                title: format!("Task {}", i),
+                description: None,
+                priority: TaskPriority::Medium,
+                parent_task_id: None,
+                assignee: None,
+                tags: None,
+                base_branch: None,
+                executor: None,
+            };
+            TaskRepository::insert_task(&mut tx, task_id, &request).await.unwrap();
+
+            let attempt_id = Uuid::new_v4();
+            let worktree = WorktreeInfo {
+                path: String::new(),
+                branch: format!("task-{}", i),
+                base_branch: "main".to_string(),
+                base_commit: String::new(),
+            };
+            TaskRepository::insert_attempt(&mut tx, attempt_id, task_id, &worktree, None, None).await.unwrap();
+            attempt_ids.push(attempt_id);
+        }
+        tx.commit().await.unwrap();
+
+        (project_id, attempt_ids)
+    }
+
+    /// Close the pool before the directory is removed
+    pub async fn close(self) {
+        self.pool.close().await;
+    }
+}
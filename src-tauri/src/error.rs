@@ -0,0 +1,351 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+use crate::models::{GitLabConnectionError, RemoteBranchStatus};
+
+/// Error returned by commands. It reaches the frontend as
+/// `{ code, message, details? }`, so callers can tell failures apart by `code`
+/// and still show `message` as before.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PivoError {
+    NotFound(String),
+    InvalidInput(String),
+    /// No GitHub or GitLab account has been set up
+    VcsNotConfigured(String),
+    /// The provider rejected the credentials (401/403)
+    VcsAuth(String),
+    RateLimited(String),
+    Network(String),
+    /// ssh refused the remote's host key (unknown or changed)
+    SshHostKey(String),
+    /// The remote rejected the user's SSH key
+    SshAuth(String),
+    /// A git command exited with an error; `stderr` is its output
+    GitCommandFailed { message: String, stderr: String },
+    /// A git feature was used on a project or directory without git
+    NotGitRepository(String),
+    /// The source branch of a PR/MR is missing on the remote
+    BranchNotPushed { message: String, branch: String, commits_ahead: usize },
+    /// The source branch on the remote differs from the local one
+    BranchStale { message: String, branch: String, commits_ahead: usize, commits_behind: usize },
+    /// The coding agent CLI could not be started
+    AgentNotInstalled(String),
+    DatabaseError(String),
+    Io(String),
+    Other(String),
+}
+
+pub type PivoResult<T> = Result<T, PivoError>;
+
+impl PivoError {
+    /// Git errors come back from `GitService` as the command's stderr, sometimes
+    /// prefixed with what was being attempted
+    pub fn git(stderr: impl Into<String>) -> Self {
+        let stderr = stderr.into();
+        if is_not_git_repository(&stderr) {
+            return PivoError::not_git_repository();
+        }
+        let first_line = stderr.lines().next().unwrap_or_default().trim();
+        PivoError::GitCommandFailed {
+            message: if first_line.is_empty() {
+                "Git command failed".to_string()
+            } else {
+                first_line.to_string()
+            },
+            stderr: stderr.trim().to_string(),
+        }
+    }
+
+    pub fn not_git_repository() -> Self {
+        PivoError::NotGitRepository("This project is not a git repository".to_string())
+    }
+
+    /// The source branch of a PR/MR can't be used as it is on the remote
+    pub fn branch_out_of_sync(branch: &str, status: &RemoteBranchStatus) -> Self {
+        if !status.exists {
+            PivoError::BranchNotPushed {
+                message: format!("Branch {} has not been pushed", branch),
+                branch: branch.to_string(),
+                commits_ahead: status.commits_ahead,
+            }
+        } else {
+            PivoError::BranchStale {
+                message: format!(
+                    "Branch {} on the remote is out of date ({} ahead, {} behind)",
+                    branch, status.commits_ahead, status.commits_behind
+                ),
+                branch: branch.to_string(),
+                commits_ahead: status.commits_ahead,
+                commits_behind: status.commits_behind,
+            }
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            PivoError::NotFound(_) => "not_found",
+            PivoError::InvalidInput(_) => "invalid_input",
+            PivoError::VcsNotConfigured(_) => "vcs_not_configured",
+            PivoError::VcsAuth(_) => "vcs_auth",
+            PivoError::RateLimited(_) => "rate_limited",
+            PivoError::Network(_) => "network",
+            PivoError::SshHostKey(_) => "ssh_host_key",
+            PivoError::SshAuth(_) => "ssh_auth",
+            PivoError::GitCommandFailed { .. } => "git_command_failed",
+            PivoError::NotGitRepository(_) => "not_git_repository",
+            PivoError::BranchNotPushed { .. } => "branch_not_pushed",
+            PivoError::BranchStale { .. } => "branch_stale",
+            PivoError::AgentNotInstalled(_) => "agent_not_installed",
+            PivoError::DatabaseError(_) => "database_error",
+            PivoError::Io(_) => "io",
+            PivoError::Other(_) => "other",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            PivoError::NotFound(message)
+            | PivoError::InvalidInput(message)
+            | PivoError::VcsNotConfigured(message)
+            | PivoError::VcsAuth(message)
+            | PivoError::RateLimited(message)
+            | PivoError::Network(message)
+            | PivoError::SshHostKey(message)
+            | PivoError::SshAuth(message)
+            | PivoError::NotGitRepository(message)
+            | PivoError::AgentNotInstalled(message)
+            | PivoError::DatabaseError(message)
+            | PivoError::Io(message)
+            | PivoError::Other(message) => message,
+            PivoError::GitCommandFailed { message, .. }
+            | PivoError::BranchNotPushed { message, .. }
+            | PivoError::BranchStale { message, .. } => message,
+        }
+    }
+
+    pub fn details(&self) -> Option<&str> {
+        match self {
+            PivoError::GitCommandFailed { stderr, .. } if !stderr.is_empty() => Some(stderr),
+            _ => None,
+        }
+    }
+
+    /// Sort a service error message into a category. Platform services report
+    /// failures as "<Provider> API error (<status>): ...", agents as
+    /// "Failed to start <agent>: <io error>".
+    fn classify(message: String) -> Self {
+        let lower = message.to_lowercase();
+        if is_not_git_repository(&message) {
+            PivoError::not_git_repository()
+        } else if lower.starts_with("ssh host key verification failed") {
+            PivoError::SshHostKey(message)
+        } else if lower.starts_with("ssh authentication failed") {
+            PivoError::SshAuth(message)
+        } else if lower.contains("not configured") {
+            PivoError::VcsNotConfigured(message)
+        } else if message.contains("(401") || message.contains("(403") {
+            PivoError::VcsAuth(message)
+        } else if message.contains("(429") || lower.contains("rate limit") {
+            PivoError::RateLimited(message)
+        } else if lower.starts_with("failed to start") && (lower.contains("no such file") || lower.contains("not found")) {
+            PivoError::AgentNotInstalled(message)
+        } else if message.contains("(404") || lower.contains("not found") {
+            PivoError::NotFound(message)
+        } else {
+            PivoError::Other(message)
+        }
+    }
+}
+
+/// git's "fatal: not a git repository (or any of the parent directories): .git"
+fn is_not_git_repository(message: &str) -> bool {
+    message.to_lowercase().contains("not a git repository")
+}
+
+impl fmt::Display for PivoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for PivoError {}
+
+impl Serialize for PivoError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let details = self.details();
+        let branch_fields = match self {
+            PivoError::BranchNotPushed { .. } => 2,
+            PivoError::BranchStale { .. } => 3,
+            _ => 0,
+        };
+        let mut state = serializer.serialize_struct("PivoError", 2 + usize::from(details.is_some()) + branch_fields)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        if let Some(details) = details {
+            state.serialize_field("details", details)?;
+        }
+        // Ahead/behind counts let the UI offer to push
+        match self {
+            PivoError::BranchNotPushed { branch, commits_ahead, .. } => {
+                state.serialize_field("branch", branch)?;
+                state.serialize_field("commitsAhead", commits_ahead)?;
+            }
+            PivoError::BranchStale { branch, commits_ahead, commits_behind, .. } => {
+                state.serialize_field("branch", branch)?;
+                state.serialize_field("commitsAhead", commits_ahead)?;
+                state.serialize_field("commitsBehind", commits_behind)?;
+            }
+            _ => {}
+        }
+        state.end()
+    }
+}
+
+impl From<String> for PivoError {
+    fn from(message: String) -> Self {
+        PivoError::classify(message)
+    }
+}
+
+impl From<&str> for PivoError {
+    fn from(message: &str) -> Self {
+        PivoError::classify(message.to_string())
+    }
+}
+
+impl From<sqlx::Error> for PivoError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => PivoError::NotFound("Record not found".to_string()),
+            // Services reject bad requests inside a transaction this way
+            sqlx::Error::Io(error) if error.kind() == std::io::ErrorKind::InvalidInput => {
+                PivoError::InvalidInput(error.to_string())
+            }
+            other => PivoError::DatabaseError(other.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for PivoError {
+    fn from(error: reqwest::Error) -> Self {
+        match error.status().map(|status| status.as_u16()) {
+            Some(401) | Some(403) => PivoError::VcsAuth(error.to_string()),
+            Some(404) => PivoError::NotFound(error.to_string()),
+            Some(429) => PivoError::RateLimited(error.to_string()),
+            _ => PivoError::Network(error.to_string()),
+        }
+    }
+}
+
+impl From<GitLabConnectionError> for PivoError {
+    fn from(error: GitLabConnectionError) -> Self {
+        match error {
+            GitLabConnectionError::InvalidUrl(message) => PivoError::InvalidInput(message),
+            GitLabConnectionError::InvalidToken(message) => PivoError::VcsAuth(message),
+            GitLabConnectionError::Network(message) => PivoError::Network(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for PivoError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => PivoError::NotFound(error.to_string()),
+            _ => PivoError::Io(error.to_string()),
+        }
+    }
+}
+
+impl From<uuid::Error> for PivoError {
+    fn from(error: uuid::Error) -> Self {
+        PivoError::InvalidInput(format!("Invalid id: {}", error))
+    }
+}
+
+impl From<serde_json::Error> for PivoError {
+    fn from(error: serde_json::Error) -> Self {
+        PivoError::InvalidInput(error.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for PivoError {
+    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        match error.downcast::<sqlx::Error>() {
+            Ok(error) => PivoError::from(*error),
+            Err(error) => PivoError::classify(error.to_string()),
+        }
+    }
+}
+
+/// For commands outside the migrated modules that still return `Result<_, String>`
+impl From<PivoError> for String {
+    fn from(error: PivoError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_rows_are_not_found() {
+        assert_eq!(PivoError::from(sqlx::Error::RowNotFound).code(), "not_found");
+        assert_eq!(PivoError::from("Task attempt not found").code(), "not_found");
+    }
+
+    #[test]
+    fn provider_errors_are_classified_by_status() {
+        assert_eq!(PivoError::from("GitHub not configured").code(), "vcs_not_configured");
+        assert_eq!(PivoError::from("GitHub API error (401): Bad credentials".to_string()).code(), "vcs_auth");
+        assert_eq!(PivoError::from("GitLab API error (429): Too Many Requests".to_string()).code(), "rate_limited");
+        assert_eq!(PivoError::from("GitLab API error (500): boom".to_string()).code(), "other");
+    }
+
+    #[test]
+    fn ssh_push_failures_are_distinct() {
+        let error = PivoError::from("SSH host key verification failed. Connect to the host once from a terminal to trust its key: Host key verification failed.".to_string());
+        assert_eq!(error.code(), "ssh_host_key");
+        let error = PivoError::from("SSH authentication failed. Make sure your key is loaded in ssh-agent and can push to the repository: git@example.com: Permission denied (publickey).".to_string());
+        assert_eq!(error.code(), "ssh_auth");
+    }
+
+    #[test]
+    fn missing_agent_cli_is_reported() {
+        let error = PivoError::from("Failed to start Claude Code: No such file or directory (os error 2)".to_string());
+        assert_eq!(error.code(), "agent_not_installed");
+    }
+
+    #[test]
+    fn git_failures_keep_stderr() {
+        let error = PivoError::git("fatal: invalid reference: nope\nhint: check the branch name\n");
+        assert_eq!(error.code(), "git_command_failed");
+        assert_eq!(error.message(), "fatal: invalid reference: nope");
+        assert_eq!(error.details(), Some("fatal: invalid reference: nope\nhint: check the branch name"));
+    }
+
+    #[test]
+    fn missing_repository_is_reported() {
+        let stderr = "fatal: not a git repository (or any of the parent directories): .git\n";
+        assert_eq!(PivoError::git(stderr).code(), "not_git_repository");
+        assert_eq!(PivoError::from(format!("Failed to get status: {}", stderr)).code(), "not_git_repository");
+    }
+
+    #[test]
+    fn serializes_code_message_and_details() {
+        let json = serde_json::to_value(PivoError::git("fatal: bad revision 'nope'")).unwrap();
+        assert_eq!(json["code"], "git_command_failed");
+        assert_eq!(json["message"], "fatal: bad revision 'nope'");
+        assert_eq!(json["details"], "fatal: bad revision 'nope'");
+
+        let status = RemoteBranchStatus { exists: true, commits_ahead: 2, commits_behind: 1 };
+        let json = serde_json::to_value(PivoError::branch_out_of_sync("task-1", &status)).unwrap();
+        assert_eq!(json["code"], "branch_stale");
+        assert_eq!(json["branch"], "task-1");
+        assert_eq!(json["commitsAhead"], 2);
+        assert_eq!(json["commitsBehind"], 1);
+
+        let json = serde_json::to_value(PivoError::from(uuid::Uuid::parse_str("nope").unwrap_err())).unwrap();
+        assert_eq!(json["code"], "invalid_input");
+        assert!(json.get("details").is_none());
+    }
+}
@@ -2,6 +2,7 @@ mod db;
 mod models;
 mod services;
 mod repository;
+mod error;
 mod commands;
 mod logging;
 mod menu;
@@ -9,15 +10,18 @@ mod window_manager;
 mod utils;
 
 use std::sync::Arc;
-use services::{TaskService, ProjectService, ProcessService, McpServerManager, CodingAgentExecutorService, MergeRequestService, ConfigService, FileWatcherService, VcsSyncService, VcsSyncConfig, GitLabService, GitHubService};
+use services::{TaskService, ProjectService, ProcessService, McpServerManager, CodingAgentExecutorService, MergeRequestService, ConfigService, FileWatcherService, VcsSyncService, GitLabService, GitHubService};
 use models::{GitLabConfig, GitHubConfig};
 use repository::DatabaseRepository;
 use tauri::{Manager, Emitter};
 use tokio::sync::Mutex;
 use commands::mcp::McpState;
 use commands::cli::CliState;
+use commands::vcs_sync::VcsSyncState;
 use commands::dev_server::DevServerManager;
+use commands::database::DatabaseState;
 use window_manager::ProjectWindowManager;
+use services::notification_service::NotificationService;
 
 pub struct AppState {
     pub task_service: Arc<TaskService>,
@@ -35,6 +39,14 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(true) = event {
+                if let Some(notifications) = window.try_state::<Arc<NotificationService>>() {
+                    notifications.window_focused();
+                }
+            }
+        })
         .setup(|app| {
             let handle = app.handle();
             
@@ -50,7 +62,15 @@ pub fn run() {
             
             // Initialize database and services
             let setup_result = tauri::async_runtime::block_on(async {
-                match db::init_database(&handle).await {
+                let database = match db::init_database(&handle).await {
+                    Ok(pool) => Ok(pool),
+                    Err(e) => {
+                        log::error!("Database could not be opened, starting with a new one: {}", e);
+                        // The old database is kept in the backups folder and can be restored from there
+                        db::recreate_database(&handle).await
+                    }
+                };
+                match database {
                     Ok(pool) => {
                         // Create database repository
                         let db_repository = Arc::new(DatabaseRepository::new(pool.clone()));
@@ -60,20 +80,24 @@ pub fn run() {
                         let project_service = Arc::new(ProjectService::new(pool.clone()));
                         let process_service = Arc::new(ProcessService::new(pool.clone()));
                         let merge_request_service = Arc::new(MergeRequestService::new(pool.clone()));
-                        let mcp_manager = Arc::new(McpServerManager::new(handle.clone()));
+                        let mcp_manager = Arc::new(McpServerManager::new(handle.clone(), pool.clone()));
+                        if let Err(e) = mcp_manager.load_servers().await {
+                            log::warn!("Failed to load MCP servers: {}", e);
+                        }
                         let cli_service = Arc::new(CodingAgentExecutorService::new(handle.clone(), db_repository.clone()));
                         let mut config_service_inner = ConfigService::new(pool.clone());
                         config_service_inner.load_from_db().await
                             .unwrap_or_else(|e| log::warn!("Failed to load config from db: {}", e));
                         let config_service = Arc::new(Mutex::new(config_service_inner));
                         let file_watcher_service = Arc::new(FileWatcherService::new(handle.clone()));
-                        let window_manager = Arc::new(ProjectWindowManager::new(handle.clone()));
-                        
-                        // Initialize VCS sync service
-                        let vcs_sync_config = VcsSyncConfig::default();
+                        let window_manager = Arc::new(ProjectWindowManager::new(handle.clone(), pool.clone()));
                         
                         // Get configs from config service
                         let config = config_service.lock().await;
+                        let vcs_sync_config = config.get_vcs_sync_config().clone();
+                        let restore_project_windows = config.get_window_config().restore_project_windows;
+                        let max_backups = config.get_backup_config().max_backups;
+                        let raw_log_retention_days = config.get_execution_config().raw_log_retention_days;
                         let gitlab_config = config.get_gitlab_config().cloned()
                             .unwrap_or_else(|| GitLabConfig {
                                 gitlab_url: Some("https://gitlab.com".to_string()),
@@ -81,34 +105,98 @@ pub fn run() {
                                 username: None,
                                 primary_email: None,
                                 default_mr_base: None,
+                                max_pages: None,
+                                push_mode: Default::default(),
                             });
                         let github_config = config.get_github_config().cloned()
                             .unwrap_or_else(|| GitHubConfig {
                                 access_token: None,
                                 username: None,
                                 default_pr_base: None,
+                                max_pages: None,
+                                enterprise_host: None,
+                                push_mode: Default::default(),
                             });
                         drop(config);
                         
+                        // Drop raw execution logs that are past the retention window
+                        if raw_log_retention_days > 0 {
+                            services::coding_agent_executor::raw_log::cleanup_old_execution_logs(raw_log_retention_days);
+                        }
+                        
+                        // Keep the configured number of backups and take one a day from now on
+                        match db::app_data_dir(handle) {
+                            Ok(app_dir) => {
+                                if let Err(e) = db::backup::prune_backups(&app_dir, max_backups) {
+                                    log::warn!("Failed to remove old database backups: {}", e);
+                                }
+                                tauri::async_runtime::spawn(db::backup::run_daily_backups(
+                                    pool.clone(),
+                                    app_dir,
+                                    config_service.clone(),
+                                ));
+                            }
+                            Err(e) => log::warn!("Database backups are disabled: {}", e),
+                        }
+                        
+                        // Purge tasks that were deleted longer ago than they can be restored.
+                        // Nothing runs yet, so only worktrees and rows are left to remove
+                        let purge_service = task_service.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let cutoff = chrono::Utc::now() - chrono::Duration::days(services::DELETED_TASK_RETENTION_DAYS);
+                            let purged = match purge_service.list_deleted_tasks_before(cutoff).await {
+                                Ok(tasks) => purge_service.purge_tasks(&tasks).await,
+                                Err(e) => Err(e),
+                            };
+                            match purged {
+                                Ok(0) => {}
+                                Ok(count) => log::info!("Purged {} deleted tasks", count),
+                                Err(e) => log::warn!("Failed to purge deleted tasks: {}", e),
+                            }
+                        });
+                        
+                        // Notify about finished agent runs, done tasks and merge requests
+                        let notification_service = Arc::new(NotificationService::new(
+                            handle.clone(),
+                            task_service.clone(),
+                            config_service.clone(),
+                        ));
+                        notification_service.start();
+                        
+                        // Warn about attempt worktrees that grow too big
+                        tauri::async_runtime::spawn(services::worktree_usage::run_worktree_size_checks(
+                            handle.clone(),
+                            task_service.clone(),
+                            config_service.clone(),
+                        ));
+                        
                         let gitlab_service = Arc::new(Mutex::new(GitLabService::new(gitlab_config)));
                         let github_service = Arc::new(Mutex::new(GitHubService::new(github_config)));
                         
-                        if vcs_sync_config.enabled {
-                            let vcs_sync_service = Arc::new(VcsSyncService::new(
-                                pool.clone(),
-                                gitlab_service.clone(),
-                                github_service.clone(),
-                                vcs_sync_config.sync_interval_seconds,
-                                handle.clone(),
-                            ));
-                            
-                            // Start background sync service
-                            let sync_service = vcs_sync_service.clone();
-                            tokio::spawn(async move {
-                                sync_service.start_background_sync().await;
+                        // Initialize VCS sync service. It always runs and pauses
+                        // itself while disabled so the setting can change at runtime
+                        let (vcs_sync_tx, vcs_sync_rx) = tokio::sync::watch::channel(vcs_sync_config);
+                        let vcs_sync_service = Arc::new(VcsSyncService::new(
+                            pool.clone(),
+                            gitlab_service.clone(),
+                            github_service.clone(),
+                            vcs_sync_rx,
+                            handle.clone(),
+                        ));
+                        
+                        // Start background sync service
+                        let sync_service = vcs_sync_service.clone();
+                        tokio::spawn(async move {
+                            sync_service.start_background_sync().await;
+                        });
+                        
+                        // Reopen the project windows of the last session
+                        if restore_project_windows {
+                            let window_manager = window_manager.clone();
+                            let project_service = project_service.clone();
+                            tauri::async_runtime::spawn(async move {
+                                window_manager.restore_windows(&project_service).await;
                             });
-                            
-                            log::info!("VCS sync service started with {} seconds interval", vcs_sync_config.sync_interval_seconds);
                         }
                         
                         // Store app state
@@ -123,6 +211,14 @@ pub fn run() {
                         // Store config service
                         app.manage(config_service);
                         
+                        // Store database pool for backups and restores
+                        app.manage(DatabaseState { pool });
+                        
+                        // Store VCS sync settings sender
+                        app.manage(VcsSyncState {
+                            config_tx: vcs_sync_tx,
+                        });
+                        
                         
                         // Store MCP state
                         app.manage(McpState {
@@ -137,9 +233,25 @@ pub fn run() {
                         // Store file watcher service
                         app.manage(file_watcher_service);
                         
+                        // Store notification service
+                        app.manage(notification_service);
+                        
                         // Store dev server manager
                         app.manage(DevServerManager::new());
                         
+                        // Messages queued when the app was closed would otherwise wait forever.
+                        // Setup scripts that were running then are gone and count as failed
+                        let dispatch_handle = handle.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let task_service = dispatch_handle.state::<AppState>().task_service.clone();
+                            match task_service.fail_interrupted_setups().await {
+                                Ok(0) => {}
+                                Ok(count) => log::info!("Marked {} interrupted setup scripts as failed", count),
+                                Err(e) => log::warn!("Failed to reset interrupted setup scripts: {}", e),
+                            }
+                            commands::task_commands::dispatch_leftover_pending_messages(&dispatch_handle).await;
+                        });
+                        
                         Ok(())
                     }
                     Err(e) => {
@@ -167,102 +279,244 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::tasks::create_task,
+            commands::tasks::create_subtask,
+            commands::tasks::list_subtasks,
             commands::tasks::get_task,
             commands::tasks::list_tasks,
+            commands::tasks::list_tasks_paginated,
+            commands::tasks::search_tasks,
+            commands::tasks::get_project_cost,
+            commands::tasks::get_task_timeline,
+            commands::tasks::get_task_status_history,
+            commands::vcs_sync::get_vcs_sync_config,
+            commands::vcs_sync::update_vcs_sync_config,
             commands::tasks::update_task,
             commands::tasks::delete_task,
+            commands::tasks::restore_task,
+            commands::tasks::purge_deleted_tasks,
+            commands::tasks::archive_task,
+            commands::tasks::unarchive_task,
             commands::tasks::update_task_status,
+            commands::tasks::bulk_update_task_status,
+            commands::tasks::reorder_tasks,
+            commands::tasks::move_task_to_project,
             commands::task_commands::execute_task_command,
+            commands::task_commands::execute_bulk_task_command,
+            commands::task_commands::list_pending_messages,
+            commands::task_commands::cancel_pending_message,
+            commands::task_commands::replay_attempt,
+            commands::task_commands::send_review_to_agent,
+            commands::task_commands::get_execution_config,
+            commands::task_commands::update_execution_config,
+            commands::config::get_window_config,
+            commands::config::update_window_config,
+            commands::config::get_backup_config,
+            commands::config::update_backup_config,
+            commands::config::get_dev_server_config,
+            commands::config::update_dev_server_config,
+            commands::config::get_storage_config,
+            commands::config::update_storage_config,
+            commands::config::get_file_watcher_config,
+            commands::config::update_file_watcher_config,
+            commands::config::get_notification_config,
+            commands::config::update_notification_config,
+            commands::config::get_git_hosts_config,
+            commands::config::update_git_hosts_config,
+            commands::database::list_database_backups,
+            commands::database::create_database_backup,
+            commands::database::restore_database_backup,
+            commands::database::rollback_last_migration,
+            commands::config::get_proxy_config,
+            commands::config::set_proxy_config,
             commands::task_commands::get_conversation_state,
+            commands::task_commands::pause_execution,
+            commands::task_commands::resume_execution,
             commands::task_attempts::get_task_attempt,
             commands::task_attempts::list_task_attempts,
+            commands::task_attempts::list_attempt_commits,
+            commands::task_attempts::get_worktree_usage,
+            commands::task_attempts::get_all_worktrees_usage,
+            commands::review_comments::create_review_comment,
+            commands::review_comments::list_review_comments,
+            commands::review_comments::update_review_comment,
+            commands::review_comments::delete_review_comment,
+            commands::task_templates::create_task_template,
+            commands::task_templates::list_task_templates,
+            commands::task_templates::get_task_template,
+            commands::task_templates::update_task_template,
+            commands::task_templates::delete_task_template,
+            commands::task_templates::create_task_from_template,
+            commands::task_attempts::create_task_attempt,
+            commands::task_attempts::compare_attempts,
+            commands::task_attempts::get_execution_timeline,
             commands::task_attempts::update_attempt_claude_session,
+            commands::task_attempts::skip_attempt_setup,
+            commands::task_attempts::rerun_attempt_setup,
+            commands::task_attempts::update_attempt_mcp_servers,
             commands::projects::create_project,
             commands::projects::get_project,
             commands::projects::list_projects,
             commands::projects::update_project,
             commands::projects::delete_project,
+            commands::projects::update_project_cache_links,
+            commands::projects::detect_main_branch,
+            commands::projects::export_project_data,
+            commands::projects::import_project_data,
+            commands::projects::export_project,
+            commands::projects::import_project,
+            commands::projects::list_project_env_vars,
+            commands::projects::set_project_env_var,
+            commands::projects::delete_project_env_var,
             commands::projects::refresh_all_git_providers,
             commands::projects::update_project_last_opened,
             commands::projects::get_recent_projects,
             commands::projects::select_project_directory,
             commands::projects::read_project_info,
             commands::process::get_process,
+            commands::process::get_process_output,
+            commands::process::get_process_log,
             commands::process::list_processes_for_attempt,
+            commands::process::run_attempt_command,
+            commands::process::kill_attempt_process,
             commands::git::create_worktree,
             commands::git::remove_worktree,
             commands::git::get_current_branch,
             commands::git::list_branches,
+            commands::git::get_remote_branches,
+            commands::git::fetch_remote,
             commands::git::get_git_status,
             commands::git::stage_files,
+            commands::git::unstage_files,
+            commands::git::unstage_all,
             commands::git::commit_changes,
+            commands::git::revert_commit,
+            commands::git::revert_to_commit,
+            commands::git::squash_commits,
+            commands::git::prepare_interactive_rebase,
             commands::git::push_branch,
+            commands::git::init_repository,
             commands::git::get_diff,
+            commands::git::get_diff_stat,
             commands::git::list_all_files,
             commands::git::read_file_content,
             commands::git::get_file_from_ref,
             commands::git::get_git_diff,
+            commands::git::get_file_diff_detail,
             commands::git::check_rebase_status,
             commands::git::get_branch_commit,
+            commands::git::integrate_attempt,
+            commands::git::get_commit_graph,
+            commands::git::get_file_history,
+            commands::git::get_file_blame,
             commands::mcp::register_mcp_server,
+            commands::mcp::register_project_mcp_server,
+            commands::mcp::unregister_mcp_server,
             commands::mcp::start_mcp_server,
             commands::mcp::stop_mcp_server,
+            commands::mcp::restart_mcp_server,
             commands::mcp::list_mcp_servers,
             commands::mcp::get_mcp_server,
+            commands::mcp::get_mcp_server_logs,
             commands::mcp::send_mcp_request,
             commands::mcp::list_mcp_tools,
+            commands::mcp::call_mcp_tool,
             commands::mcp::list_mcp_resources,
             commands::mcp::read_mcp_resource,
             commands::mcp::list_mcp_prompts,
             commands::mcp::get_mcp_prompt,
+            commands::mcp::attach_mcp_server_to_project,
+            commands::mcp::detach_mcp_server_from_project,
+            commands::mcp::list_project_mcp_servers,
             commands::cli::configure_claude_api_key,
             commands::cli::configure_gemini_api_key,
             commands::cli::save_images_to_temp,
             commands::cli::get_running_tasks,
             commands::git_info::extract_git_info_from_path,
             commands::logging::get_log_content,
+            commands::logging::get_log_entries,
+            commands::logging::get_log_entry_count,
             commands::logging::get_log_path,
             commands::logging::open_log_file,
             commands::logging::clear_logs,
+            commands::logging::get_execution_raw_log,
+            commands::logging::open_execution_log_file,
             commands::window::show_log_viewer,
             commands::window::open_project_window,
+            commands::window::open_project_window_at_task,
             commands::window::close_project_window,
             commands::window::get_project_window,
             commands::window::list_open_project_windows,
             commands::gitlab::get_gitlab_config,
             commands::gitlab::update_gitlab_config,
+            commands::gitlab::test_gitlab_connection,
             commands::gitlab::create_gitlab_mr,
             commands::gitlab::get_gitlab_mr_status,
             commands::gitlab::push_to_gitlab,
+            commands::gitlab::merge_gitlab_mr,
+            commands::gitlab::close_gitlab_mr,
+            commands::gitlab::approve_gitlab_mr,
+            commands::gitlab::unapprove_gitlab_mr,
+            commands::gitlab::get_gitlab_mr_approvals,
+            commands::gitlab::list_mr_comments,
+            commands::gitlab::create_mr_comment,
             commands::gitlab::detect_git_provider,
             commands::gitlab::get_merge_requests_by_attempt,
             commands::gitlab::get_merge_requests_by_task,
             commands::gitlab::get_active_merge_requests,
+            commands::gitlab::sync_project_merge_requests,
             commands::github::get_github_config,
             commands::github::update_github_config,
             commands::github::create_github_pr,
             commands::github::get_github_pr_status,
+            commands::github::list_repo_branches,
+            commands::github::get_repository_info,
             commands::github::push_to_github,
+            commands::github::merge_github_pr,
+            commands::github::close_github_pr,
+            commands::github::list_pr_comments,
+            commands::github::create_pr_comment,
             commands::github::get_pull_requests_by_attempt,
             commands::github::get_pull_requests_by_task,
             commands::github::github_start_device_flow,
             commands::github::github_poll_device_auth,
+            commands::github::sync_project_pull_requests,
+            commands::merge_requests::list_merge_request_comments,
+            commands::merge_requests::post_merge_request_comment,
+            commands::merge_requests::get_pipeline_status_by_attempt,
+            commands::merge_requests::generate_merge_request_content,
             commands::system::open_in_terminal,
             commands::system::show_in_file_manager,
+            commands::system::run_project_script,
             commands::filesystem::search_project_files,
             commands::filesystem::search_files_from_current_dir,
+            commands::filesystem::read_project_tree,
+            commands::filesystem::watch_project_root,
+            commands::filesystem::unwatch_project_root,
             commands::command::search_commands,
             commands::command::get_command_content,
             commands::dev_server::start_dev_server,
             commands::dev_server::stop_dev_server,
             commands::dev_server::get_dev_server_status,
+            commands::dev_server::list_dev_servers,
+            commands::dev_server::get_dev_server_url,
+            commands::dev_server::set_dev_server_url,
+            commands::dev_server::open_dev_server_in_browser,
             services::watch_worktree,
             services::unwatch_worktree,
             services::unwatch_all,
         ])
-        .run(tauri::generate_context!())
+        .build(tauri::generate_context!())
         .unwrap_or_else(|e| {
             eprintln!("Error while running tauri application: {}", e);
             std::process::exit(1);
+        })
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                // Remember which project windows were open for the next start
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    let window_manager = state.window_manager.clone();
+                    tauri::async_runtime::block_on(window_manager.save_open_windows());
+                }
+            }
         });
 }
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A commit made automatically after an execution of the attempt's agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttemptCommit {
+    pub id: String,
+    pub task_attempt_id: String,
+    pub commit_hash: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct AttemptCommitRow {
+    pub id: String,
+    pub task_attempt_id: String,
+    pub commit_hash: String,
+    pub message: String,
+    pub created_at: String,
+}
+
+impl From<AttemptCommitRow> for AttemptCommit {
+    fn from(row: AttemptCommitRow) -> Self {
+        Self {
+            id: row.id,
+            task_attempt_id: row.task_attempt_id,
+            commit_hash: row.commit_hash,
+            message: row.message,
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+}
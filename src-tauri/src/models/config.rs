@@ -1,9 +1,242 @@
 use serde::{Deserialize, Serialize};
+use super::{GitProvider, DEFAULT_ENV_FILES};
+
+/// Default maximum number of pages fetched from paginated platform APIs
+pub const DEFAULT_MAX_PAGES: u32 = 20;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub gitlab: Option<GitLabConfig>,
     pub github: Option<GitHubConfig>,
+    #[serde(default)]
+    pub execution: ExecutionConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub windows: WindowConfig,
+    #[serde(default)]
+    pub backups: BackupConfig,
+    #[serde(default)]
+    pub dev_server: DevServerConfig,
+    #[serde(default)]
+    pub git_hosts: GitHostsConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub file_watcher: FileWatcherConfig,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+}
+
+/// Git servers whose provider can't be told from their name, such as a
+/// self-hosted GitLab at git.internal.example.com
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GitHostsConfig {
+    pub hosts: Vec<GitHostMapping>,
+    /// Ask hosts that are neither known nor mapped which API they serve
+    /// (`/api/v4/version` for GitLab, `/api/v3` for GitHub Enterprise)
+    pub probe_unknown_hosts: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHostMapping {
+    /// Host name, optionally with scheme and port when the web UI is not
+    /// served over https on the default port, e.g. "http://git.local:8080"
+    pub host: String,
+    pub provider: GitProvider,
+}
+
+impl GitHostMapping {
+    /// The host name without scheme, port or trailing slash, lowercased
+    pub fn host_name(&self) -> String {
+        let host = self.host.trim().trim_end_matches('/');
+        let host = host.split_once("://").map_or(host, |(_, rest)| rest);
+        host.split(':').next().unwrap_or(host).to_lowercase()
+    }
+
+    /// Base URL of the server, used as `GitRemoteInfo::host`
+    pub fn base_url(&self) -> String {
+        let host = self.host.trim().trim_end_matches('/');
+        if host.contains("://") {
+            host.to_string()
+        } else {
+            format!("https://{}", host)
+        }
+    }
+}
+
+/// How the address of a running dev server is recognised in its output, and
+/// what happens when the server crashes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DevServerConfig {
+    /// Extra regexes for lines announcing the server, tried after the built-in
+    /// ones (vite, next, webpack-dev-server, generic). The URL is the first
+    /// http(s) address on a matching line.
+    pub url_patterns: Vec<String>,
+    /// Run the command again when the server exits with an error
+    pub auto_restart: bool,
+    /// Restarts of one server before it is left stopped
+    pub max_restarts: u32,
+    /// Crashes right after starting tolerated in a row; one more stops the
+    /// restarts as a crash loop
+    pub crash_loop_limit: u32,
+    /// Env files loaded from the working directory, relative to it; empty to
+    /// load none
+    pub env_files: Vec<String>,
+    /// Let the env files replace variables inherited from the app's environment
+    pub override_env: bool,
+}
+
+impl Default for DevServerConfig {
+    fn default() -> Self {
+        Self {
+            url_patterns: Vec::new(),
+            auto_restart: false,
+            max_restarts: 5,
+            crash_loop_limit: 3,
+            env_files: DEFAULT_ENV_FILES.iter().map(|file| file.to_string()).collect(),
+            override_env: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WindowConfig {
+    /// Reopen the project windows that were open when the app quit
+    pub restore_project_windows: bool,
+}
+
+/// Database backups taken on startup and once a day while the app runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BackupConfig {
+    /// Startup, daily and manual backups kept in the backups folder; older
+    /// ones are deleted. Recovery backups are kept for 30 days instead.
+    pub max_backups: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self { max_backups: 10 }
+    }
+}
+
+/// Default size above which a worktree is reported as too big, 2 GiB
+pub const DEFAULT_WORKTREE_WARNING_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Disk space checks of attempt worktrees
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StorageConfig {
+    /// Size in bytes above which a worktree triggers a `worktree-size-warning`;
+    /// 0 turns the check off
+    pub worktree_warning_bytes: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self { worktree_warning_bytes: DEFAULT_WORKTREE_WARNING_BYTES }
+    }
+}
+
+/// Which file changes in worktrees and project roots are reported, and how often
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FileWatcherConfig {
+    /// Changes are collected until none arrive for this long, then reported
+    /// together. Changes that keep coming are reported every 2 seconds.
+    pub debounce_ms: u64,
+    /// Leave out paths the repository's .gitignore files ignore
+    pub respect_gitignore: bool,
+    /// Glob patterns of paths to leave out, matched against the path relative
+    /// to the watched directory and against each of its components,
+    /// e.g. "node_modules" or "*.log"
+    pub ignore_patterns: Vec<String>,
+    /// Directories watched at the same time, worktrees and project roots
+    /// together. Each one holds OS watch handles for all its subdirectories.
+    pub max_watched_directories: usize,
+}
+
+impl Default for FileWatcherConfig {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 250,
+            respect_gitignore: true,
+            ignore_patterns: ["node_modules", "target", ".DS_Store", "*.swp", "*.tmp"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            max_watched_directories: 32,
+        }
+    }
+}
+
+/// Desktop notifications, sent only while the window showing the task isn't focused
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NotificationConfig {
+    /// An agent run finished or failed
+    pub execution_completed: bool,
+    /// A task moved to Done
+    pub task_done: bool,
+    /// A merge request was merged or its pipeline failed
+    pub merge_request_updated: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            execution_completed: true,
+            task_done: true,
+            merge_request_updated: true,
+        }
+    }
+}
+
+/// Proxy used for GitHub, GitLab and agent traffic when the environment sets none
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NetworkConfig {
+    pub proxy_url: Option<String>,
+    /// Hosts or domains reached directly, e.g. "localhost" or ".corp.example.com"
+    pub proxy_no_proxy: Vec<String>,
+}
+
+/// Default number of days raw execution logs are kept
+pub const DEFAULT_RAW_LOG_RETENTION_DAYS: u64 = 14;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExecutionConfig {
+    /// Stop a running execution when a new message is sent instead of queueing the message
+    pub interrupt_on_new_message: bool,
+    /// Run the project's setup script in a new attempt's worktree before its agent first runs
+    pub run_setup_script: bool,
+    /// Hold messages to an attempt until its setup script succeeds or is skipped,
+    /// instead of only while it runs
+    pub require_setup_success: bool,
+    /// Days the raw agent output of an execution is kept after its last write,
+    /// checked on startup; 0 keeps the logs forever
+    pub raw_log_retention_days: u64,
+    /// Directory the raw agent output is written to; unset uses `executions`
+    /// in the app's log directory
+    pub execution_log_dir: Option<String>,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            interrupt_on_new_message: false,
+            run_setup_script: true,
+            require_setup_success: true,
+            raw_log_retention_days: DEFAULT_RAW_LOG_RETENTION_DAYS,
+            execution_log_dir: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +246,10 @@ pub struct GitLabConfig {
     pub primary_email: Option<String>,    // User email
     pub default_mr_base: Option<String>,  // Default target branch (defaults to "main")
     pub gitlab_url: Option<String>,       // GitLab instance URL (defaults to "https://gitlab.com")
+    #[serde(default)]
+    pub max_pages: Option<u32>,           // Page cap for list endpoints (defaults to DEFAULT_MAX_PAGES)
+    #[serde(default)]
+    pub push_mode: PushMode,              // How branches are pushed (defaults to the token)
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -22,6 +259,23 @@ pub struct GitHubConfig {
     pub username: Option<String>,         // GitHub username
     #[serde(rename = "defaultBranch")]
     pub default_pr_base: Option<String>,  // Default target branch (defaults to "main")
+    #[serde(default)]
+    pub max_pages: Option<u32>,           // Page cap for list endpoints (defaults to DEFAULT_MAX_PAGES)
+    #[serde(default)]
+    pub enterprise_host: Option<String>,  // GitHub Enterprise Server host (defaults to github.com)
+    #[serde(default)]
+    pub push_mode: PushMode,              // How branches are pushed (defaults to the token)
+}
+
+/// How branches are pushed to a provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushMode {
+    /// Push to an HTTPS URL carrying the access token, whatever `origin` is
+    #[default]
+    Token,
+    /// Push to `origin` as configured, authenticating through the user's SSH agent
+    Ssh,
 }
 
 impl Default for AppConfig {
@@ -29,6 +283,15 @@ impl Default for AppConfig {
         Self {
             gitlab: None,
             github: None,
+            execution: ExecutionConfig::default(),
+            network: NetworkConfig::default(),
+            windows: WindowConfig::default(),
+            backups: BackupConfig::default(),
+            dev_server: DevServerConfig::default(),
+            git_hosts: GitHostsConfig::default(),
+            storage: StorageConfig::default(),
+            file_watcher: FileWatcherConfig::default(),
+            notifications: NotificationConfig::default(),
         }
     }
 }
@@ -38,9 +301,41 @@ impl GitLabConfig {
         self.gitlab_url.as_deref().unwrap_or("https://gitlab.com")
     }
     
+    pub fn max_pages(&self) -> u32 {
+        self.max_pages.unwrap_or(DEFAULT_MAX_PAGES).max(1)
+    }
+    
     // Removed unused method default_mr_base
 }
 
 impl GitHubConfig {
     // Removed unused method default_pr_base
+    
+    pub fn max_pages(&self) -> u32 {
+        self.max_pages.unwrap_or(DEFAULT_MAX_PAGES).max(1)
+    }
+    
+    /// Enterprise host without scheme or trailing slash, if one is configured
+    pub fn enterprise_host(&self) -> Option<&str> {
+        self.enterprise_host
+            .as_deref()
+            .map(|host| host.trim().trim_start_matches("https://").trim_start_matches("http://").trim_end_matches('/'))
+            .filter(|host| !host.is_empty() && *host != "github.com")
+    }
+    
+    /// Base URL of the REST API: api.github.com or https://<host>/api/v3 for Enterprise Server
+    pub fn api_base_url(&self) -> String {
+        match self.enterprise_host() {
+            Some(host) => format!("https://{}/api/v3", host),
+            None => "https://api.github.com".to_string(),
+        }
+    }
+    
+    /// Base URL of the web UI, which also serves the OAuth device flow endpoints
+    pub fn web_base_url(&self) -> String {
+        match self.enterprise_host() {
+            Some(host) => format!("https://{}", host),
+            None => "https://github.com".to_string(),
+        }
+    }
 }
\ No newline at end of file
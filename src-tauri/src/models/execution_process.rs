@@ -2,18 +2,18 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+/// A process and how it ended. Its output is read a page at a time with
+/// `ProcessService::get_process_output`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionProcess {
     pub id: String,
-    pub task_attempt_id: String,
+    pub task_attempt_id: Option<String>, // None for project-level scripts
     pub process_type: ProcessType,
     pub executor_type: Option<String>,
     pub status: ProcessStatus,
     pub command: String,
     pub args: Option<String>,
     pub working_directory: String,
-    pub stdout: Option<String>,
-    pub stderr: Option<String>,
     pub exit_code: Option<i32>,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
@@ -22,15 +22,13 @@ pub struct ExecutionProcess {
 #[derive(Debug, FromRow)]
 pub struct ExecutionProcessRow {
     pub id: String,
-    pub task_attempt_id: String,
+    pub task_attempt_id: Option<String>,
     pub process_type: String,
     pub executor_type: Option<String>,
     pub status: String,
     pub command: String,
     pub args: Option<String>,
     pub working_directory: String,
-    pub stdout: Option<String>,
-    pub stderr: Option<String>,
     pub exit_code: Option<i32>,
     pub started_at: String,
     pub completed_at: Option<String>,
@@ -49,8 +47,6 @@ impl From<ExecutionProcessRow> for ExecutionProcess {
             command: row.command,
             args: row.args,
             working_directory: row.working_directory,
-            stdout: row.stdout,
-            stderr: row.stderr,
             exit_code: row.exit_code,
             started_at: DateTime::parse_from_rfc3339(&row.started_at)
                 .map(|dt| dt.with_timezone(&Utc))
@@ -72,6 +68,26 @@ pub enum ProcessType {
     CodingAgent,
     DevServer,
     Terminal,
+    Manual,
+}
+
+/// Which project script to run: the configured setup/dev script or an ad-hoc command
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptType {
+    Setup,
+    Dev,
+    Custom(String),
+}
+
+impl ScriptType {
+    pub fn process_type(&self) -> ProcessType {
+        match self {
+            ScriptType::Setup => ProcessType::SetupScript,
+            ScriptType::Dev => ProcessType::DevServer,
+            ScriptType::Custom(_) => ProcessType::Terminal,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::Type)]
@@ -84,4 +100,85 @@ pub enum ProcessStatus {
     Killed,
 }
 
-// ProcessOutput struct removed as it's not being used
\ No newline at end of file
+/// Which output of a process a line was written to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStreamType {
+    Stdout,
+    Stderr,
+}
+
+impl OutputStreamType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputStreamType::Stdout => "stdout",
+            OutputStreamType::Stderr => "stderr",
+        }
+    }
+}
+
+/// A line read from a running process, before it's stored
+#[derive(Debug, Clone)]
+pub struct CapturedLine {
+    /// Position of the line across both streams of the process
+    pub sequence: i64,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: i64,
+    pub content: String,
+}
+
+impl CapturedLine {
+    /// A line read just now
+    pub fn new(sequence: i64, content: String) -> Self {
+        Self {
+            sequence,
+            timestamp: Utc::now().timestamp_millis(),
+            content,
+        }
+    }
+}
+
+/// One line of a process's output, as recorded in its combined log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessOutputLine {
+    pub stream: OutputStreamType,
+    /// Position of the line within its own stream (0-based)
+    pub line_number: i64,
+    /// Position of the line across both streams; orders the combined log
+    pub sequence: i64,
+    /// When the line was read, in milliseconds since the Unix epoch
+    pub timestamp: i64,
+    pub content: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ProcessOutputLineRow {
+    pub stream: String,
+    pub line_number: i64,
+    pub sequence: i64,
+    pub emitted_at: i64,
+    pub content: String,
+}
+
+impl From<ProcessOutputLineRow> for ProcessOutputLine {
+    fn from(row: ProcessOutputLineRow) -> Self {
+        Self {
+            stream: serde_json::from_str(&format!("\"{}\"", row.stream))
+                .unwrap_or(OutputStreamType::Stdout),
+            line_number: row.line_number,
+            sequence: row.sequence,
+            timestamp: row.emitted_at,
+            content: row.content,
+        }
+    }
+}
+
+/// A window of the lines a process wrote to one of its outputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessOutputPage {
+    pub lines: Vec<String>,
+    /// Lines written to the stream so far
+    pub total_lines: usize,
+    /// Offset of the line after this page; equals `total_lines` at the end
+    pub next_offset: usize,
+}
\ No newline at end of file
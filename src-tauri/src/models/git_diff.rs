@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,9 +27,34 @@ pub struct DiffResult {
     pub files: Vec<FileDiff>,
     pub stats: DiffStats,
     pub has_conflicts: bool,
+    /// Files that would conflict, for merge previews
+    pub conflicted_files: Vec<String>,
     pub large_files: Vec<String>,
 }
 
+impl DiffResult {
+    /// Drop files outside `subdirectory` (a repository-relative path) and
+    /// recount the stats. Renames count when either side is inside.
+    pub fn retain_subdirectory(&mut self, subdirectory: &str) {
+        self.files.retain(|file| {
+            is_in_subdirectory(&file.path, subdirectory)
+                || file.old_path.as_deref().is_some_and(|old| is_in_subdirectory(old, subdirectory))
+        });
+        self.large_files.retain(|path| is_in_subdirectory(path, subdirectory));
+        self.stats = DiffStats {
+            files_changed: self.files.len(),
+            additions: self.files.iter().map(|file| file.additions).sum(),
+            deletions: self.files.iter().map(|file| file.deletions).sum(),
+        };
+    }
+}
+
+/// `path` is `subdirectory` itself or lies below it
+pub fn is_in_subdirectory(path: &str, subdirectory: &str) -> bool {
+    path.strip_prefix(subdirectory)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileDiff {
@@ -71,6 +97,23 @@ pub struct DiffLine {
     pub new_line_number: Option<usize>,
 }
 
+/// Both sides of one changed file with its hunks, for side-by-side review.
+/// Line numbers in `hunks` are 1-based positions in `old_content`/`new_content`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiffDetail {
+    pub path: String,
+    pub old_path: Option<String>,
+    /// `None` when the file doesn't exist on that side or is binary
+    pub old_content: Option<String>,
+    pub new_content: Option<String>,
+    pub hunks: Vec<DiffChunk>,
+    pub binary: bool,
+    /// A side was larger than the large file threshold and was cut at a line
+    /// boundary; the hunks still cover the whole file
+    pub truncated: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LineType {
@@ -87,6 +130,25 @@ pub struct DiffStats {
     pub deletions: usize,
 }
 
+/// Per-file line counts without patch text, cheap enough for large changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStatSummary {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<FileStat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStat {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub status: FileStatus,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RebaseStatus {
@@ -95,6 +157,64 @@ pub struct RebaseStatus {
     pub commits_ahead: usize,
     pub can_fast_forward: bool,
     pub has_conflicts: bool,
+    /// Files that would conflict when bringing in the base branch
+    pub conflicted_files: Vec<String>,
+}
+
+/// How the copy of a branch on `origin` compares with the local branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteBranchStatus {
+    /// Whether `origin` has the branch at all
+    pub exists: bool,
+    /// Local commits missing on the remote
+    pub commits_ahead: usize,
+    /// Remote commits missing locally
+    pub commits_behind: usize,
+}
+
+impl RemoteBranchStatus {
+    pub fn is_up_to_date(&self) -> bool {
+        self.exists && self.commits_ahead == 0 && self.commits_behind == 0
+    }
+}
+
+/// A remote-tracking branch as last fetched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteBranch {
+    /// Branch name on the remote, without the remote prefix
+    pub name: String,
+    pub remote: String,
+    pub commit: String,
+    /// The remote's default branch, as pointed to by `<remote>/HEAD`
+    pub is_head: bool,
+}
+
+/// Remote-tracking branches changed by a fetch, as `<remote>/<branch>`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchResult {
+    pub new_branches: Vec<String>,
+    pub deleted_branches: Vec<String>,
+    pub updated_branches: Vec<String>,
+}
+
+/// How a branch is integrated into its target
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeMethod {
+    FastForward,
+    NoFf,
+    Squash,
+}
+
+/// Outcome of a merge. Conflicting merges are aborted and leave the target untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum MergeResult {
+    Merged { commit: String },
+    Conflicts { files: Vec<String> },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,4 +224,55 @@ pub struct WorktreeInfo {
     pub branch: String,
     pub base_branch: String,
     pub base_commit: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitNode {
+    pub sha: String,
+    pub parents: Vec<String>,
+    pub refs: Vec<String>,
+    pub author: String,
+    pub message: String,
+    pub date: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGraph {
+    pub commits: Vec<CommitNode>,
+    /// (child sha, parent sha) pairs
+    pub edges: Vec<(String, String)>,
+}
+
+/// Who last changed a line of a file in an attempt's worktree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlameLine {
+    /// 1-based line number in the current file
+    pub line_number: usize,
+    pub content: String,
+    /// All zeros for lines that are not committed yet
+    pub commit: String,
+    pub author: String,
+    /// Author time in Unix seconds
+    pub timestamp: i64,
+    /// The line was written after the attempt's base commit, by a commit of
+    /// the attempt or in the working tree
+    pub introduced_in_attempt: bool,
+    /// The line is only in the working tree, from the current session
+    pub uncommitted: bool,
+}
+
+/// A commit that touched a given file, with the lines it changed in that file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileCommit {
+    pub sha: String,
+    pub message: String,
+    pub author: String,
+    /// Author date in RFC 3339
+    pub date: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
@@ -1,5 +1,42 @@
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use std::sync::RwLock;
+use super::GitHostMapping;
+
+lazy_static! {
+    /// Hosts mapped to a provider in the git host settings, mirrored here by
+    /// ConfigService so URL parsing can consult them
+    static ref GIT_HOSTS: RwLock<Vec<GitHostMapping>> = RwLock::new(Vec::new());
+}
+
+pub fn set_git_host_mappings(hosts: Vec<GitHostMapping>) {
+    if let Ok(mut current) = GIT_HOSTS.write() {
+        *current = hosts;
+    }
+}
+
+/// Mapping for the host of `url`, which may be a remote URL or a bare host name
+fn find_git_host_mapping(url: &str) -> Option<GitHostMapping> {
+    let host = url_host(url)?.to_lowercase();
+    GIT_HOSTS.read().ok()?
+        .iter()
+        .find(|mapping| mapping.host_name() == host)
+        .cloned()
+}
+
+/// Host of "git@host:owner/repo", "ssh://git@host:22/...", "https://host/..." or "host"
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.trim();
+    let rest = rest.split_once("://").map_or(rest, |(_, rest)| rest);
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let rest = match rest[..authority_end].rfind('@') {
+        Some(at) => &rest[at + 1..],
+        None => rest,
+    };
+    let host = rest.split([':', '/']).next()?;
+    (!host.is_empty()).then_some(host)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -12,7 +49,12 @@ pub enum GitProvider {
 impl GitProvider {
     /// Detect provider from remote URL
     pub fn from_remote_url(url: &str) -> Self {
-        if url.contains("github.com") {
+        if let Some(mapping) = find_git_host_mapping(url) {
+            return mapping.provider;
+        }
+        
+        // Enterprise Server instances are usually served from github.<company>.com
+        if url.contains("github.com") || url.contains("github.") {
             GitProvider::GitHub
         } else if url.contains("gitlab.com") || url.contains("gitlab.") {
             GitProvider::GitLab
@@ -42,8 +84,18 @@ pub struct GitRemoteInfo {
 }
 
 impl GitRemoteInfo {
-    /// Parse remote URL to extract provider and repository information
+    /// Parse remote URL to extract provider and repository information. Hosts
+    /// from the git host settings get their provider and base URL from there.
     pub fn from_remote_url(url: &str) -> Option<Self> {
+        let mut info = Self::parse_remote_url(url)?;
+        if let Some(mapping) = find_git_host_mapping(url) {
+            info.provider = mapping.provider.clone();
+            info.host = Some(mapping.base_url());
+        }
+        Some(info)
+    }
+    
+    fn parse_remote_url(url: &str) -> Option<Self> {
         // Handle SSH URLs like git@gitlab.com:owner/repo.git
         if let Some(ssh_match) = regex::Regex::new(r"git@([^:]+):([^/]+)/(.+?)(?:\.git)?$")
             .ok()
@@ -105,6 +157,75 @@ impl GitRemoteInfo {
         
         None
     }
+    
+    /// Parse a remote URL, treating the configured GitHub Enterprise host as GitHub
+    /// even when its name gives no hint of the provider
+    pub fn from_remote_url_with_github_host(url: &str, enterprise_host: Option<&str>) -> Option<Self> {
+        let mut info = Self::from_remote_url(url)?;
+        
+        if let (Some(host), Some(enterprise_host)) = (info.host.as_deref(), enterprise_host) {
+            let host = host.trim_start_matches("https://").trim_start_matches("http://");
+            if host.eq_ignore_ascii_case(enterprise_host) {
+                info.provider = GitProvider::GitHub;
+            }
+        }
+        
+        Some(info)
+    }
+    
+    /// Parse a merge/pull request web URL such as
+    /// https://gitlab.com/group/sub/repo/-/merge_requests/1 or https://github.com/owner/repo/pull/1
+    pub fn from_merge_request_url(provider: GitProvider, web_url: &str) -> Option<Self> {
+        let url = reqwest::Url::parse(web_url).ok()?;
+        let host = url.host_str()?.to_string();
+        
+        let segments: Vec<&str> = url.path_segments()?
+            .take_while(|s| *s != "-" && *s != "pull" && *s != "merge_requests")
+            .collect();
+        
+        if segments.len() < 2 {
+            return None;
+        }
+        
+        let (repo, owner) = segments.split_last()?;
+        
+        Some(GitRemoteInfo {
+            host: if host != "github.com" && host != "gitlab.com" {
+                Some(format!("{}://{}", url.scheme(), host))
+            } else {
+                None
+            },
+            provider,
+            owner: owner.join("/"),
+            repo: repo.to_string(),
+        })
+    }
+}
+
+/// A comment on a merge/pull request discussion thread
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRequestComment {
+    pub id: i64,
+    pub author: String,
+    pub body: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "webUrl")]
+    pub web_url: Option<String>,
+    /// GitHub conversation comments are never resolvable
+    pub resolvable: bool,
+    pub resolved: bool,
+}
+
+/// Approvals of a GitLab merge request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalState {
+    pub approved: bool,
+    /// Usernames of the approvers
+    pub approved_by: Vec<String>,
+    pub approvals_required: u32,
+    pub approvals_left: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,12 +248,60 @@ pub struct MergeRequestInfo {
     pub has_conflicts: bool,
     #[serde(rename = "pipelineStatus")]
     pub pipeline_status: Option<PipelineStatus>,
+    #[serde(rename = "pipelineUrl", default)]
+    pub pipeline_url: Option<String>,
+    #[serde(rename = "pipelineJobs", default)]
+    pub pipeline_jobs: Vec<PipelineJob>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
 }
 
+/// A single CI job (GitLab) or check run (GitHub) of a pipeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineJob {
+    pub name: String,
+    pub status: Option<PipelineStatus>,
+    /// Raw status/conclusion as reported by the provider
+    pub conclusion: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Repository metadata used to pre-fill a project imported from a remote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryInfo {
+    pub name: String,
+    /// `owner/name`
+    pub full_name: String,
+    pub description: Option<String>,
+    pub default_branch: String,
+    pub is_private: bool,
+    pub is_fork: bool,
+    pub clone_url: String,
+    pub ssh_url: String,
+    pub open_issues_count: u32,
+}
+
+/// A remote branch with its latest CI result and distance from the default branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchStatus {
+    pub name: String,
+    #[serde(rename = "commitSha")]
+    pub commit_sha: String,
+    #[serde(rename = "pipelineStatus")]
+    pub pipeline_status: Option<PipelineStatus>,
+    #[serde(rename = "isDefault")]
+    pub is_default: bool,
+    #[serde(rename = "isProtected")]
+    pub is_protected: bool,
+    #[serde(rename = "aheadBy")]
+    pub ahead_by: Option<u32>,
+    #[serde(rename = "behindBy")]
+    pub behind_by: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum MergeRequestState {
@@ -156,6 +325,14 @@ impl FromStr for MergeRequestState {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum MergeStatus {
@@ -182,4 +359,55 @@ pub enum PipelineStatus {
     Scheduled,
 }
 
-// Removed unused PipelineStatus methods
\ No newline at end of file
+// Removed unused PipelineStatus methods
+
+/// The account a GitLab Personal Access Token belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLabUserInfo {
+    pub username: String,
+    /// Primary email; only returned when the token has the `read_user` scope
+    pub email: Option<String>,
+}
+
+/// Why a GitLab URL and token could not be verified
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitLabConnectionError {
+    /// The URL is malformed or does not point at a GitLab instance
+    InvalidUrl(String),
+    /// The instance was reached but rejected (or was not given) a token
+    InvalidToken(String),
+    /// The instance could not be reached
+    Network(String),
+}
+
+impl std::fmt::Display for GitLabConnectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitLabConnectionError::InvalidUrl(message)
+            | GitLabConnectionError::InvalidToken(message)
+            | GitLabConnectionError::Network(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Outcome of `test_gitlab_connection`, tagged by `status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum GitLabConnectionTest {
+    Ok { username: String, email: Option<String> },
+    InvalidUrl { message: String },
+    InvalidToken { message: String },
+    NetworkError { message: String },
+}
+
+impl From<Result<GitLabUserInfo, GitLabConnectionError>> for GitLabConnectionTest {
+    fn from(result: Result<GitLabUserInfo, GitLabConnectionError>) -> Self {
+        match result {
+            Ok(user) => GitLabConnectionTest::Ok { username: user.username, email: user.email },
+            Err(GitLabConnectionError::InvalidUrl(message)) => GitLabConnectionTest::InvalidUrl { message },
+            Err(GitLabConnectionError::InvalidToken(message)) => GitLabConnectionTest::InvalidToken { message },
+            Err(GitLabConnectionError::Network(message)) => GitLabConnectionTest::NetworkError { message },
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use super::git_provider::{PipelineJob, PipelineStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergeRequest {
@@ -20,6 +21,10 @@ pub struct MergeRequest {
     pub has_conflicts: bool,
     pub pipeline_status: Option<String>,
     pub pipeline_url: Option<String>,
+    pub pipeline_jobs: Vec<PipelineJob>,
+    /// Approvals given so far; only tracked for GitLab
+    #[serde(default)]
+    pub approval_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub merged_at: Option<DateTime<Utc>>,
@@ -44,6 +49,8 @@ pub struct MergeRequestRow {
     pub has_conflicts: bool,
     pub pipeline_status: Option<String>,
     pub pipeline_url: Option<String>,
+    pub pipeline_jobs: Option<String>,
+    pub approval_count: i64,
     pub created_at: String,
     pub updated_at: String,
     pub merged_at: Option<String>,
@@ -69,6 +76,10 @@ impl From<MergeRequestRow> for MergeRequest {
             has_conflicts: row.has_conflicts,
             pipeline_status: row.pipeline_status,
             pipeline_url: row.pipeline_url,
+            pipeline_jobs: row.pipeline_jobs
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            approval_count: row.approval_count,
             created_at: DateTime::parse_from_rfc3339(&row.created_at)
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or_else(|_| Utc::now()),
@@ -87,6 +98,17 @@ impl From<MergeRequestRow> for MergeRequest {
     }
 }
 
+/// Suggested title and description for a new PR/MR, edited by the user before creating it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRequestDraft {
+    pub title: String,
+    /// Markdown
+    pub description: String,
+    pub commit_count: usize,
+    /// Whether the coding agent rewrote the generated description
+    pub polished: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateMergeRequestData {
     pub task_attempt_id: String,
@@ -104,7 +126,24 @@ pub struct CreateMergeRequestData {
     pub has_conflicts: bool,
     pub pipeline_status: Option<String>,
     pub pipeline_url: Option<String>,
+    pub pipeline_jobs: Vec<PipelineJob>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub merged_at: Option<DateTime<Utc>>,
+}
+
+impl MergeRequest {
+    /// Stored pipeline statuses come from `{:?}` formatting, so match them case-insensitively
+    pub fn parsed_pipeline_status(&self) -> Option<PipelineStatus> {
+        self.pipeline_status.as_ref().and_then(|s| s.to_lowercase().parse().ok())
+    }
+}
+
+/// CI state of the most recent merge request of an attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttemptPipelineStatus {
+    pub merge_request_id: i64,
+    pub status: Option<PipelineStatus>,
+    pub url: Option<String>,
+    pub jobs: Vec<PipelineJob>,
 }
\ No newline at end of file
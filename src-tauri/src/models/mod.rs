@@ -1,5 +1,6 @@
 pub mod task;
 pub mod project;
+pub mod project_env_var;
 pub mod task_attempt;
 pub mod execution_process;
 pub mod git_diff;
@@ -8,9 +9,18 @@ pub mod config;
 pub mod merge_request;
 pub mod conversation;
 pub mod command;
+pub mod pending_message;
+pub mod project_bundle;
+pub mod log_entry;
+pub mod attempt_commit;
+pub mod review_comment;
+pub mod worktree_usage;
+pub mod task_event;
+pub mod task_template;
 
 pub use task::*;
 pub use project::*;
+pub use project_env_var::*;
 pub use task_attempt::*;
 pub use execution_process::*;
 pub use git_diff::*;
@@ -18,4 +28,12 @@ pub use git_provider::*;
 pub use config::*;
 pub use merge_request::*;
 pub use conversation::*;
-pub use command::*;
\ No newline at end of file
+pub use command::*;
+pub use pending_message::*;
+pub use project_bundle::*;
+pub use log_entry::*;
+pub use attempt_commit::*;
+pub use review_comment::*;
+pub use worktree_usage::*;
+pub use task_event::*;
+pub use task_template::*;
\ No newline at end of file
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A message queued for an attempt while its agent was still busy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingMessage {
+    pub id: String,
+    pub task_attempt_id: String,
+    pub task_id: String,
+    pub message: String,
+    pub images: Option<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct PendingMessageRow {
+    pub id: String,
+    pub task_attempt_id: String,
+    pub task_id: String,
+    pub message: String,
+    pub images: Option<String>,
+    pub created_at: String,
+}
+
+impl From<PendingMessageRow> for PendingMessage {
+    fn from(row: PendingMessageRow) -> Self {
+        Self {
+            id: row.id,
+            task_attempt_id: row.task_attempt_id,
+            task_id: row.task_id,
+            message: row.message,
+            images: row.images.and_then(|s| serde_json::from_str(&s).ok()),
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+}
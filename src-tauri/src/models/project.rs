@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -16,6 +17,46 @@ pub struct Project {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_opened: Option<DateTime<Utc>>,
+    pub cache_links: Vec<String>,
+    pub cache_link_mode: CacheLinkMode,
+    /// Branch name template with `{slug}`, `{id}`, `{assignee}` and `{date}` placeholders
+    pub branch_template: Option<String>,
+    /// Command the dev server was last started with
+    pub last_dev_command: Option<String>,
+    /// Dev server URL set by the user, takes precedence over the detected one
+    pub dev_server_url: Option<String>,
+    /// Commit the worktree changes after each agent execution
+    #[serde(default)]
+    pub auto_commit: bool,
+    /// Agent for new attempts when none is picked, e.g. "claude_code"
+    #[serde(default)]
+    pub default_executor: Option<String>,
+    /// Model passed to the default agent
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// The project is a git repository. Without git, attempts run in the
+    /// project directory itself and git features are unavailable.
+    #[serde(default = "git_enabled_default")]
+    pub git_enabled: bool,
+    /// Path inside the repository the agent works in, e.g. "packages/api".
+    /// Worktrees still cover the whole repository.
+    #[serde(default)]
+    pub subdirectory: Option<String>,
+}
+
+fn git_enabled_default() -> bool {
+    true
+}
+
+impl Project {
+    /// Directory the agent runs in for a checkout of this project at `root`,
+    /// the project path itself or an attempt's worktree
+    pub fn working_directory(&self, root: &str) -> String {
+        match &self.subdirectory {
+            Some(subdirectory) => Path::new(root).join(subdirectory).to_string_lossy().to_string(),
+            None => root.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, FromRow)]
@@ -32,6 +73,16 @@ pub struct ProjectRow {
     pub created_at: String,
     pub updated_at: String,
     pub last_opened: Option<String>,
+    pub cache_links: Option<String>,
+    pub cache_link_mode: String,
+    pub branch_template: Option<String>,
+    pub last_dev_command: Option<String>,
+    pub dev_server_url: Option<String>,
+    pub auto_commit: bool,
+    pub default_executor: Option<String>,
+    pub default_model: Option<String>,
+    pub git_enabled: bool,
+    pub subdirectory: Option<String>,
 }
 
 impl From<ProjectRow> for Project {
@@ -57,10 +108,40 @@ impl From<ProjectRow> for Project {
                     .map(|dt| dt.with_timezone(&Utc))
                     .ok()
             }),
+            cache_links: row.cache_links
+                .and_then(|links| serde_json::from_str(&links).ok())
+                .unwrap_or_default(),
+            cache_link_mode: serde_json::from_str(&format!("\"{}\"", row.cache_link_mode))
+                .unwrap_or_default(),
+            branch_template: row.branch_template,
+            last_dev_command: row.last_dev_command,
+            dev_server_url: row.dev_server_url,
+            auto_commit: row.auto_commit,
+            default_executor: row.default_executor,
+            default_model: row.default_model,
+            git_enabled: row.git_enabled,
+            subdirectory: row.subdirectory,
         }
     }
 }
 
+/// How cache paths are shared from the main checkout into a worktree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheLinkMode {
+    /// Symlink the path; fast, but writes go to the main checkout
+    #[default]
+    Symlink,
+    /// Recreate the tree with hard links, copying where that is not possible
+    Clone,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCacheLinksRequest {
+    pub cache_links: Vec<String>,
+    pub cache_link_mode: Option<CacheLinkMode>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateProjectRequest {
     pub name: String,
@@ -70,6 +151,11 @@ pub struct CreateProjectRequest {
     pub main_branch: Option<String>,
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
+    /// Detected from the directory when not set
+    pub git_enabled: Option<bool>,
+    /// Relative path inside `path` to confine the agent to
+    #[serde(default)]
+    pub subdirectory: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -81,4 +167,15 @@ pub struct UpdateProjectRequest {
     pub main_branch: Option<String>,
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
+    /// An empty string resets the project to the default branch naming
+    pub branch_template: Option<String>,
+    pub auto_commit: Option<bool>,
+    /// An empty string clears the default and new attempts use Claude Code
+    pub default_executor: Option<String>,
+    /// An empty string clears the default model
+    pub default_model: Option<String>,
+    /// Can only be turned on for a directory that is a git repository
+    pub git_enabled: Option<bool>,
+    /// An empty string scopes the project to the whole repository again
+    pub subdirectory: Option<String>,
 }
\ No newline at end of file
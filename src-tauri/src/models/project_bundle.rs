@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use super::{ConversationMessage, MergeRequest, Project, ReviewComment, Task, TaskAttempt};
+
+/// Bumped whenever the bundle layout changes in a way older readers can't follow
+pub const PROJECT_BUNDLE_VERSION: u32 = 1;
+
+/// `setup_status` of imported attempts: the branch may not exist locally, so the
+/// worktree has to be created again before the attempt can run
+pub const NEEDS_WORKTREE_SETUP_STATUS: &str = "needs_worktree";
+
+/// A project with its tasks, attempts, conversations, merge requests and review
+/// comments, used to move a project between Pivo databases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub project: Project,
+    pub tasks: Vec<Task>,
+    /// Worktree paths are machine specific and exported empty
+    pub attempts: Vec<TaskAttempt>,
+    pub conversations: Vec<BundleConversation>,
+    pub merge_requests: Vec<MergeRequest>,
+    /// Missing from bundles exported before review comments existed
+    #[serde(default)]
+    pub review_comments: Vec<ReviewComment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleConversation {
+    pub task_attempt_id: String,
+    pub messages: Vec<ConversationMessage>,
+}
+
+impl ProjectBundle {
+    /// Parse a bundle, rejecting other format versions before reading anything else
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| format!("Not a valid project bundle: {}", e))?;
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or("Not a valid project bundle: missing format version")?;
+
+        if version < PROJECT_BUNDLE_VERSION as u64 {
+            return Err(format!(
+                "Project bundle format version {} is no longer supported (expected {}). Export it again with a current Pivo.",
+                version, PROJECT_BUNDLE_VERSION
+            ));
+        }
+        if version > PROJECT_BUNDLE_VERSION as u64 {
+            return Err(format!(
+                "Project bundle format version {} was written by a newer Pivo (this one reads {}). Update Pivo to import it.",
+                version, PROJECT_BUNDLE_VERSION
+            ));
+        }
+
+        serde_json::from_value(value).map_err(|e| format!("Project bundle is incomplete or corrupt: {}", e))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProjectResult {
+    pub project: Project,
+    pub imported_tasks: usize,
+    /// Tasks whose title already existed in the target project
+    pub skipped_tasks: usize,
+    pub imported_attempts: usize,
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use std::path::Path;
+
+/// Placeholder shown instead of secret values
+pub const SECRET_MASK: &str = "********";
+
+/// Env files loaded from a process's working directory unless others are given
+pub const DEFAULT_ENV_FILES: [&str; 2] = [".env", ".env.local"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectEnvVar {
+    pub id: String,
+    pub project_id: String,
+    pub key: String,
+    pub value: String,
+    pub is_secret: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProjectEnvVar {
+    /// Copy of the variable with the value hidden when it is flagged as secret
+    pub fn masked(mut self) -> Self {
+        if self.is_secret {
+            self.value = SECRET_MASK.to_string();
+        }
+        self
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct ProjectEnvVarRow {
+    pub id: String,
+    pub project_id: String,
+    pub key: String,
+    pub value: String,
+    pub is_secret: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<ProjectEnvVarRow> for ProjectEnvVar {
+    fn from(row: ProjectEnvVarRow) -> Self {
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            key: row.key,
+            value: row.value,
+            is_secret: row.is_secret,
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: DateTime::parse_from_rfc3339(&row.updated_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetProjectEnvVarRequest {
+    pub key: String,
+    pub value: String,
+    #[serde(default)]
+    pub is_secret: bool,
+}
+
+/// Resolved environment for a process started inside a project
+#[derive(Clone, Default)]
+pub struct ProjectEnvironment {
+    vars: Vec<(String, String)>,
+    secrets: Vec<String>,
+}
+
+impl ProjectEnvironment {
+    pub fn from_vars(env_vars: Vec<ProjectEnvVar>) -> Self {
+        let mut env = Self::default();
+        for var in env_vars {
+            if var.is_secret && !var.value.is_empty() {
+                env.secrets.push(var.value.clone());
+            }
+            env.vars.push((var.key, var.value));
+        }
+        env
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Key/value pairs suitable for `Command::envs`
+    pub fn vars(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.vars.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Add the variables of the env files in `working_dir`, later files winning
+    /// over earlier ones. Project variables always take precedence, and
+    /// variables already set in this process's environment are only replaced
+    /// when `override_existing` is set. Missing files are skipped.
+    pub fn with_env_files(mut self, working_dir: &Path, env_files: &[String], override_existing: bool) -> Self {
+        let mut file_vars: Vec<(String, String)> = Vec::new();
+        for file in env_files {
+            let path = working_dir.join(file);
+            if !path.is_file() {
+                continue;
+            }
+            let entries = match dotenvy::from_path_iter(&path) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::warn!("Failed to read env file {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            for entry in entries {
+                match entry {
+                    Ok((key, value)) => {
+                        file_vars.retain(|(existing, _)| *existing != key);
+                        file_vars.push((key, value));
+                    }
+                    Err(e) => log::warn!("Skipping invalid line in {}: {}", path.display(), e),
+                }
+            }
+        }
+
+        file_vars.retain(|(key, _)| {
+            !self.vars.iter().any(|(project_key, _)| project_key == key)
+                && (override_existing || std::env::var_os(key).is_none())
+        });
+        self.vars.extend(file_vars);
+        self
+    }
+
+    /// Replace every secret value occurring in `text` with a mask
+    pub fn mask(&self, text: &str) -> String {
+        let mut masked = text.to_string();
+        for secret in &self.secrets {
+            if masked.contains(secret.as_str()) {
+                masked = masked.replace(secret.as_str(), SECRET_MASK);
+            }
+        }
+        masked
+    }
+}
+
+// Only keys are printed so secrets never end up in debug logs
+impl fmt::Debug for ProjectEnvironment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.vars.iter().map(|(k, _)| k))
+            .finish()
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Which version of a file a review comment's line number refers to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffSide {
+    /// The base branch version
+    Old,
+    /// The attempt's version
+    #[default]
+    New,
+}
+
+/// A comment on a line of an attempt's diff, fed back to the agent with
+/// `send_review_to_agent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub id: String,
+    pub task_attempt_id: String,
+    pub file_path: String,
+    /// 1-based line number on `side`
+    pub line: i64,
+    pub side: DiffSide,
+    pub body: String,
+    /// The commented line when the comment was made
+    pub line_content: Option<String>,
+    pub resolved: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ReviewCommentRow {
+    pub id: String,
+    pub task_attempt_id: String,
+    pub file_path: String,
+    pub line: i64,
+    pub side: String,
+    pub body: String,
+    pub line_content: Option<String>,
+    pub resolved: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<ReviewCommentRow> for ReviewComment {
+    fn from(row: ReviewCommentRow) -> Self {
+        Self {
+            id: row.id,
+            task_attempt_id: row.task_attempt_id,
+            file_path: row.file_path,
+            line: row.line,
+            side: serde_json::from_str(&format!("\"{}\"", row.side)).unwrap_or_default(),
+            body: row.body,
+            line_content: row.line_content,
+            resolved: row.resolved,
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: DateTime::parse_from_rfc3339(&row.updated_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateReviewCommentRequest {
+    pub task_attempt_id: String,
+    pub file_path: String,
+    pub line: i64,
+    #[serde(default)]
+    pub side: DiffSide,
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateReviewCommentRequest {
+    pub body: Option<String>,
+    pub resolved: Option<bool>,
+}
@@ -14,8 +14,15 @@ pub struct Task {
     pub parent_task_id: Option<String>,
     pub assignee: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub position: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Set while the task is archived; archived tasks are left out of task lists
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Set while the task is deleted but not yet purged; it can still be restored
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// What caused the latest status change; None before the first one
+    pub status_triggered_by: Option<StatusChangeTrigger>,
 }
 
 // Database row representation
@@ -30,8 +37,12 @@ pub struct TaskRow {
     pub parent_task_id: Option<String>,
     pub assignee: Option<String>,
     pub tags: Option<String>,
+    pub position: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
+    pub archived_at: Option<String>,
+    pub deleted_at: Option<String>,
+    pub status_triggered_by: Option<String>,
 }
 
 impl From<TaskRow> for Task {
@@ -46,12 +57,21 @@ impl From<TaskRow> for Task {
             parent_task_id: row.parent_task_id,
             assignee: row.assignee,
             tags: row.tags.and_then(|t| serde_json::from_str(&t).ok()),
+            position: row.position,
             created_at: DateTime::parse_from_rfc3339(&row.created_at)
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or_else(|_| Utc::now()),
             updated_at: DateTime::parse_from_rfc3339(&row.updated_at)
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or_else(|_| Utc::now()),
+            archived_at: row.archived_at
+                .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            deleted_at: row.deleted_at
+                .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            status_triggered_by: row.status_triggered_by
+                .and_then(|trigger| serde_json::from_str(&format!("\"{}\"", trigger)).ok()),
         }
     }
 }
@@ -66,6 +86,71 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+/// What moved a task to a new status
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusChangeTrigger {
+    /// Changed by hand, e.g. by dragging the card
+    User,
+    /// The agent finished and the task went to review
+    AgentCompleted,
+    /// Its merge request was merged
+    VcsMerged,
+    /// Its merge request was closed without merging
+    VcsClosed,
+    /// The app moved it along with something else, e.g. sending a message
+    System,
+}
+
+impl StatusChangeTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusChangeTrigger::User => "user",
+            StatusChangeTrigger::AgentCompleted => "agent_completed",
+            StatusChangeTrigger::VcsMerged => "vcs_merged",
+            StatusChangeTrigger::VcsClosed => "vcs_closed",
+            StatusChangeTrigger::System => "system",
+        }
+    }
+}
+
+/// One change in a task's status history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatusChange {
+    pub id: String,
+    pub task_id: String,
+    /// None when the previous status couldn't be read
+    pub from_status: Option<TaskStatus>,
+    pub to_status: TaskStatus,
+    pub triggered_by: StatusChangeTrigger,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct TaskStatusChangeRow {
+    pub id: String,
+    pub task_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub triggered_by: String,
+    pub created_at: String,
+}
+
+impl From<TaskStatusChangeRow> for TaskStatusChange {
+    fn from(row: TaskStatusChangeRow) -> Self {
+        Self {
+            id: row.id,
+            task_id: row.task_id,
+            from_status: row.from_status.and_then(|status| serde_json::from_str(&format!("\"{}\"", status)).ok()),
+            to_status: serde_json::from_str(&format!("\"{}\"", row.to_status)).unwrap_or(TaskStatus::Backlog),
+            triggered_by: serde_json::from_str(&format!("\"{}\"", row.triggered_by)).unwrap_or(StatusChangeTrigger::System),
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::Type)]
 #[sqlx(rename_all = "lowercase")]
 pub enum TaskPriority {
@@ -75,6 +160,70 @@ pub enum TaskPriority {
     Urgent,
 }
 
+/// Narrows down and orders the results of `search_tasks`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskSearchFilters {
+    pub statuses: Option<Vec<TaskStatus>>,
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub order_by: TaskSearchOrder,
+    pub limit: Option<usize>,
+    /// Also search the messages of the tasks' attempt conversations
+    #[serde(default)]
+    pub include_conversations: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskSearchOrder {
+    /// Best matches first; title hits rank above tag, description and
+    /// conversation hits
+    #[default]
+    Relevance,
+    /// Newest first
+    Date,
+}
+
+/// Where `search_tasks` found a task
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchField {
+    Title,
+    Description,
+    Tags,
+    /// A message of one of the task's attempt conversations
+    Conversation,
+}
+
+/// A task found by `search_tasks`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskSearchResult {
+    pub task: Task,
+    /// Higher is better
+    pub score: f32,
+    pub match_field: MatchField,
+    /// Text around the match with the matching words in `<mark>` tags
+    pub snippet: String,
+}
+
+/// One page of tasks, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    /// Opaque cursor for the next page, None on the last page
+    pub next_cursor: Option<String>,
+    /// Number of tasks matching the filter across all pages
+    pub total_count: usize,
+}
+
+/// A task moved by `move_task_to_project`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovedTask {
+    pub task: Task,
+    /// Worktrees of the moved tasks' attempts, which stay in the old project's repository
+    pub left_behind_worktrees: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateTaskRequest {
     pub project_id: Uuid,
@@ -84,6 +233,10 @@ pub struct CreateTaskRequest {
     pub parent_task_id: Option<Uuid>,
     pub assignee: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub base_branch: Option<String>, // Defaults to the project's main branch
+    #[serde(default)]
+    pub executor: Option<String>, // Agent of the first attempt, defaults to the project's
 }
 
 #[derive(Debug, Serialize, Deserialize)]
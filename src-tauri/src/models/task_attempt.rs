@@ -18,6 +18,12 @@ pub struct TaskAttempt {
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub claude_session_id: Option<String>,
+    pub setup_status: Option<String>, // "running", "completed", "failed", "skipped" or "needs_worktree"
+    pub cost_usd: Option<f64>,
+    pub mcp_server_ids: Vec<String>,
+    /// Model the agent runs with, `None` for the agent's default
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 #[derive(Debug, FromRow)]
@@ -35,6 +41,10 @@ pub struct TaskAttemptRow {
     pub created_at: String,
     pub completed_at: Option<String>,
     pub claude_session_id: Option<String>,
+    pub setup_status: Option<String>,
+    pub cost_usd: Option<f64>,
+    pub mcp_server_ids: Option<String>,
+    pub model: Option<String>,
 }
 
 impl From<TaskAttemptRow> for TaskAttempt {
@@ -64,6 +74,12 @@ impl From<TaskAttemptRow> for TaskAttempt {
                     .ok()
             ),
             claude_session_id: row.claude_session_id,
+            setup_status: row.setup_status,
+            cost_usd: row.cost_usd,
+            mcp_server_ids: row.mcp_server_ids
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+            model: row.model,
         }
     }
 }
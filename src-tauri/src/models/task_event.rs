@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::TaskStatus;
+
+/// Something that happened to a task, shown on its activity timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub id: String,
+    pub task_id: String,
+    /// The attempt it happened in; None for changes to the task itself
+    pub task_attempt_id: Option<String>,
+    #[serde(flatten)]
+    pub kind: TaskEventKind,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What happened, with the details the timeline shows. Serialized with a
+/// `type` tag, which is also stored in `task_events.event_type`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskEventKind {
+    StatusChanged {
+        /// None when the previous status couldn't be read
+        from: Option<TaskStatus>,
+        to: TaskStatus,
+    },
+    ExecutionStarted {
+        execution_id: String,
+        executor: String,
+    },
+    ExecutionCompleted {
+        execution_id: String,
+        cost_usd: Option<f64>,
+    },
+    ExecutionStopped {
+        execution_id: String,
+    },
+    Committed {
+        commit_hash: String,
+        message: String,
+    },
+    MergeRequestCreated {
+        provider: String,
+        number: i64,
+        title: String,
+        url: String,
+    },
+    MergeRequestMerged {
+        provider: String,
+        number: i64,
+        title: String,
+        url: String,
+    },
+    MergeRequestClosed {
+        provider: String,
+        number: i64,
+        title: String,
+        url: String,
+    },
+}
+
+impl TaskEventKind {
+    /// The `type` tag of the event
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            TaskEventKind::StatusChanged { .. } => "status_changed",
+            TaskEventKind::ExecutionStarted { .. } => "execution_started",
+            TaskEventKind::ExecutionCompleted { .. } => "execution_completed",
+            TaskEventKind::ExecutionStopped { .. } => "execution_stopped",
+            TaskEventKind::Committed { .. } => "committed",
+            TaskEventKind::MergeRequestCreated { .. } => "merge_request_created",
+            TaskEventKind::MergeRequestMerged { .. } => "merge_request_merged",
+            TaskEventKind::MergeRequestClosed { .. } => "merge_request_closed",
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct TaskEventRow {
+    pub id: String,
+    pub task_id: String,
+    pub task_attempt_id: Option<String>,
+    pub event_type: String,
+    pub data: String,
+    pub created_at: String,
+}
+
+impl TryFrom<TaskEventRow> for TaskEvent {
+    type Error = serde_json::Error;
+
+    fn try_from(row: TaskEventRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            task_id: row.task_id,
+            task_attempt_id: row.task_attempt_id,
+            kind: serde_json::from_str(&row.data)?,
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+}
@@ -0,0 +1,181 @@
+use super::TaskPriority;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// A reusable blueprint for tasks of a project. The title, description and
+/// initial prompt may contain `{{name}}` placeholders, filled in by
+/// `create_task_from_template`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub title_pattern: String,
+    pub description_template: Option<String>,
+    pub priority: TaskPriority,
+    pub tags: Option<Vec<String>>,
+    /// Agent of the first attempt, the project's default when unset
+    pub executor: Option<String>,
+    /// Sent to the agent once the first attempt's worktree is ready
+    pub initial_prompt: Option<String>,
+    /// Placeholder names in the order they first appear
+    pub placeholders: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Title, description and initial prompt of a template with its placeholders filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedTaskTemplate {
+    pub title: String,
+    pub description: Option<String>,
+    pub initial_prompt: Option<String>,
+}
+
+impl TaskTemplate {
+    /// Fill in the placeholders. Fails, naming every placeholder without a
+    /// non-blank value, before anything is rendered; unused values are ignored.
+    pub fn render(&self, values: &HashMap<String, String>) -> Result<RenderedTaskTemplate, String> {
+        let missing: Vec<&str> = self
+            .placeholders
+            .iter()
+            .filter(|name| values.get(*name).is_none_or(|value| value.trim().is_empty()))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Missing values for placeholders: {}", missing.join(", ")));
+        }
+
+        let title = render_placeholders(&self.title_pattern, values).trim().to_string();
+        if title.is_empty() {
+            return Err("The rendered task title is empty".to_string());
+        }
+        Ok(RenderedTaskTemplate {
+            title,
+            description: self.description_template.as_deref().map(|text| render_placeholders(text, values)),
+            initial_prompt: self
+                .initial_prompt
+                .as_deref()
+                .map(|text| render_placeholders(text, values))
+                .filter(|prompt| !prompt.trim().is_empty()),
+        })
+    }
+}
+
+/// Placeholder names used in `text`, each once, in the order they first appear.
+/// `{{ name }}` counts as `{{name}}`; braces around anything but letters, digits,
+/// `_` and `-` are left as they are.
+pub fn placeholder_names(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some((_, name, after)) = next_placeholder(rest) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = after;
+    }
+    names
+}
+
+fn render_placeholders(text: &str, values: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((before, name, after)) = next_placeholder(rest) {
+        rendered.push_str(before);
+        match values.get(name) {
+            Some(value) => rendered.push_str(value.trim()),
+            None => rendered.push_str(&rest[before.len()..rest.len() - after.len()]),
+        }
+        rest = after;
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// The text before the first placeholder of `text`, its name and the text after it
+fn next_placeholder(text: &str) -> Option<(&str, &str, &str)> {
+    let mut search_from = 0;
+    loop {
+        let start = search_from + text[search_from..].find("{{")?;
+        let end = start + 2 + text[start + 2..].find("}}")?;
+        let name = text[start + 2..end].trim();
+        let valid = !name.is_empty()
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if valid {
+            return Some((&text[..start], name, &text[end + 2..]));
+        }
+        search_from = start + 2;
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct TaskTemplateRow {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub title_pattern: String,
+    pub description_template: Option<String>,
+    pub priority: String,
+    pub tags: Option<String>,
+    pub executor: Option<String>,
+    pub initial_prompt: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<TaskTemplateRow> for TaskTemplate {
+    fn from(row: TaskTemplateRow) -> Self {
+        let mut placeholders = placeholder_names(&row.title_pattern);
+        for text in [&row.description_template, &row.initial_prompt].into_iter().flatten() {
+            for name in placeholder_names(text) {
+                if !placeholders.contains(&name) {
+                    placeholders.push(name);
+                }
+            }
+        }
+
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            title_pattern: row.title_pattern,
+            description_template: row.description_template,
+            priority: serde_json::from_str(&format!("\"{}\"", row.priority)).unwrap_or(TaskPriority::Medium),
+            tags: row.tags.and_then(|t| serde_json::from_str(&t).ok()),
+            executor: row.executor,
+            initial_prompt: row.initial_prompt,
+            placeholders,
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: DateTime::parse_from_rfc3339(&row.updated_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTaskTemplateRequest {
+    pub name: String,
+    pub title_pattern: String,
+    pub description_template: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub tags: Option<Vec<String>>,
+    pub executor: Option<String>,
+    pub initial_prompt: Option<String>,
+}
+
+/// Unset fields are kept; an empty description, executor or initial prompt clears it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTaskTemplateRequest {
+    pub name: Option<String>,
+    pub title_pattern: Option<String>,
+    pub description_template: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub tags: Option<Vec<String>>,
+    pub executor: Option<String>,
+    pub initial_prompt: Option<String>,
+}
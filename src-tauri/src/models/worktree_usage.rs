@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// An attempt with a worktree of its own, as listed for disk usage reports
+#[derive(Debug, Clone, FromRow)]
+pub struct AttemptWorktree {
+    pub attempt_id: String,
+    pub task_id: String,
+    pub task_title: String,
+    pub project_id: String,
+    pub project_path: String,
+    pub worktree_path: String,
+}
+
+/// Disk space taken by one top-level entry of a worktree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathUsage {
+    /// Relative to the worktree
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// Disk space taken by an attempt's worktree. The git objects it shares with
+/// the project repository are not included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeUsage {
+    pub attempt_id: String,
+    pub task_id: String,
+    pub task_title: String,
+    pub project_id: String,
+    pub project_path: String,
+    pub worktree_path: String,
+    pub bytes: u64,
+    /// The largest top-level files and directories, biggest first
+    pub largest_paths: Vec<PathUsage>,
+}
+
+/// Disk space taken by every attempt worktree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllWorktreesUsage {
+    /// Worktrees plus the shared git data
+    pub total_bytes: u64,
+    /// `.git` directories of the projects with worktrees, each counted once
+    pub shared_git_bytes: u64,
+    /// Biggest first
+    pub worktrees: Vec<WorktreeUsage>,
+}
@@ -81,20 +81,32 @@ impl<'a> ConversationRepository<'a> {
         }
     }
     
+    /// Append a message in a single statement, so concurrent writers can't
+    /// overwrite each other's messages
     pub async fn add_message(
         &self,
         attempt_id: Uuid,
         message: ConversationMessage
     ) -> Result<(), sqlx::Error> {
-        // Get existing conversation or create new one
-        let mut messages = if let Some(conversation) = self.get_attempt_conversation(attempt_id).await? {
-            conversation.messages
-        } else {
-            vec![]
-        };
+        let message_json = serde_json::to_string(&message)
+            .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        
+        sqlx::query(
+            r#"
+            INSERT INTO attempt_conversations (id, task_attempt_id, messages, created_at, updated_at)
+            VALUES (?, ?, json_array(json(?)), datetime('now'), datetime('now'))
+            ON CONFLICT(task_attempt_id) DO UPDATE SET
+                messages = json_insert(attempt_conversations.messages, '$[#]', json(?)),
+                updated_at = datetime('now')
+            "#
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(attempt_id.to_string())
+        .bind(&message_json)
+        .bind(&message_json)
+        .execute(self.db.pool())
+        .await?;
         
-        messages.push(message);
-        self.save_attempt_conversation(attempt_id, messages).await?;
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 pub mod database_repository;
 pub mod conversation_repository;
+pub mod task_repository;
 
 pub use database_repository::DatabaseRepository;
-pub use conversation_repository::ConversationRepository;
\ No newline at end of file
+pub use conversation_repository::ConversationRepository;
+pub use task_repository::{TaskRepository, TaskSearchHit, TaskSearchQuery, TaskTemplateValues};
\ No newline at end of file
@@ -0,0 +1,1173 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::{Executor, Sqlite, Transaction};
+use uuid::Uuid;
+use crate::models::{
+    AttemptWorktree, CreateTaskRequest, StatusChangeTrigger, Task, TaskAttempt, TaskAttemptRow, TaskEventKind, TaskEventRow, TaskRow,
+    TaskStatus, TaskStatusChange, TaskStatusChangeRow, UpdateTaskRequest, WorktreeInfo,
+};
+use crate::models::{AttemptCommit, AttemptCommitRow, AttemptConversation, AttemptStatus, ConversationMessage, PendingMessage, PendingMessageRow};
+use crate::models::{CreateReviewCommentRequest, ReviewComment, ReviewCommentRow, TaskPriority, TaskTemplate, TaskTemplateRow};
+
+/// Project settings a new attempt's branch and worktree are derived from
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AttemptProjectSettings {
+    pub path: String,
+    pub main_branch: String,
+    pub cache_links: Option<String>,
+    pub cache_link_mode: String,
+    pub branch_template: Option<String>,
+    pub default_executor: Option<String>,
+    pub default_model: Option<String>,
+    pub git_enabled: bool,
+    pub subdirectory: Option<String>,
+}
+
+impl AttemptProjectSettings {
+    /// Executor and model of a new attempt: the requested executor, or the
+    /// project's default one. The default model only goes with the default executor.
+    pub fn attempt_agent(&self, executor: Option<String>) -> (Option<String>, Option<String>) {
+        match executor {
+            Some(executor) if self.default_executor.as_deref() != Some(executor.as_str()) => (Some(executor), None),
+            _ => (self.default_executor.clone(), self.default_model.clone()),
+        }
+    }
+}
+
+/// A task matched by `search_tasks`, with its bm25 rank and a snippet per indexed column
+#[derive(sqlx::FromRow)]
+pub struct TaskSearchHit {
+    #[sqlx(flatten)]
+    pub task: TaskRow,
+    pub rank: f64,
+    pub title_snippet: Option<String>,
+    pub description_snippet: Option<String>,
+    pub tags_snippet: Option<String>,
+    pub conversations_snippet: Option<String>,
+}
+
+/// What `search_tasks` looks for; every filter that is set has to match
+pub struct TaskSearchQuery<'a> {
+    pub project_id: Option<Uuid>,
+    /// FTS5 expression, `None` to list the filtered tasks
+    pub match_expr: Option<&'a str>,
+    pub statuses: &'a [String],
+    pub assignee: Option<&'a str>,
+    pub order_by_rank: bool,
+    pub limit: usize,
+}
+
+/// Column values of a task template as they are stored
+pub struct TaskTemplateValues<'a> {
+    pub name: &'a str,
+    pub title_pattern: &'a str,
+    pub description_template: Option<&'a str>,
+    pub priority: TaskPriority,
+    pub tags: Option<&'a Vec<String>>,
+    pub executor: Option<&'a str>,
+    pub initial_prompt: Option<&'a str>,
+}
+
+/// Event times are stored with a fixed number of digits so they sort as text
+fn event_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// SQL for tasks, their attempts and templates, and what is kept per attempt:
+/// conversations, queued messages, commits and review comments. Writes take a
+/// transaction so services can combine several of them into one unit; reads
+/// accept any executor.
+pub struct TaskRepository;
+
+impl TaskRepository {
+    pub async fn get_task<'e, E>(executor: E, id: Uuid) -> Result<Option<Task>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(row.map(Task::from))
+    }
+
+    pub async fn get_attempt<'e, E>(executor: E, id: Uuid) -> Result<Option<TaskAttempt>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row = sqlx::query_as::<_, TaskAttemptRow>("SELECT * FROM task_attempts WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(row.map(TaskAttempt::from))
+    }
+
+    pub async fn list_attempts<'e, E>(executor: E, task_id: Uuid) -> Result<Vec<TaskAttempt>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, TaskAttemptRow>(
+            "SELECT * FROM task_attempts WHERE task_id = ? ORDER BY created_at DESC"
+        )
+        .bind(task_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(TaskAttempt::from).collect())
+    }
+
+    /// Attempts with a worktree of their own, or just `attempt_id`. Attempts
+    /// without a branch run in the project directory and are left out.
+    pub async fn list_attempt_worktrees<'e, E>(executor: E, attempt_id: Option<&str>) -> Result<Vec<AttemptWorktree>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, AttemptWorktree>(
+            r#"
+            SELECT a.id AS attempt_id, a.task_id, t.title AS task_title, t.project_id,
+                   p.path AS project_path, a.worktree_path
+            FROM task_attempts a
+            JOIN tasks t ON t.id = a.task_id
+            JOIN projects p ON p.id = t.project_id
+            WHERE a.branch != '' AND a.worktree_path != p.path AND (?1 IS NULL OR a.id = ?1)
+            ORDER BY a.created_at
+            "#,
+        )
+        .bind(attempt_id)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Add an event about the task itself to its timeline
+    pub async fn record_event(tx: &mut Transaction<'_, Sqlite>, task_id: &str, kind: &TaskEventKind) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(kind).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+        sqlx::query(
+            "INSERT INTO task_events (id, task_id, event_type, data, created_at) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(task_id)
+        .bind(kind.event_type())
+        .bind(data)
+        .bind(event_timestamp(Utc::now()))
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Add a change of a task's status to its history and timeline and keep
+    /// what caused it on the task
+    pub async fn record_status_change(
+        tx: &mut Transaction<'_, Sqlite>,
+        task_id: &str,
+        from: Option<TaskStatus>,
+        to: TaskStatus,
+        trigger: StatusChangeTrigger,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO task_status_history (id, task_id, from_status, to_status, triggered_by, created_at) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(task_id)
+        .bind(from.as_ref().map(|status| format!("{:?}", status)))
+        .bind(format!("{:?}", to))
+        .bind(trigger.as_str())
+        .bind(event_timestamp(Utc::now()))
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query("UPDATE tasks SET status_triggered_by = ? WHERE id = ?")
+            .bind(trigger.as_str())
+            .bind(task_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Self::record_event(tx, task_id, &TaskEventKind::StatusChanged { from, to }).await
+    }
+
+    /// A task's status changes, oldest first
+    pub async fn list_status_changes<'e, E>(executor: E, task_id: Uuid) -> Result<Vec<TaskStatusChange>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, TaskStatusChangeRow>(
+            "SELECT * FROM task_status_history WHERE task_id = ? ORDER BY created_at, rowid"
+        )
+        .bind(task_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(TaskStatusChange::from).collect())
+    }
+
+    /// Add an event that happened in an attempt to its task's timeline. Does
+    /// nothing if the attempt doesn't exist.
+    pub async fn record_attempt_event(tx: &mut Transaction<'_, Sqlite>, attempt_id: &str, kind: &TaskEventKind) -> Result<(), sqlx::Error> {
+        let data = serde_json::to_string(kind).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO task_events (id, task_id, task_attempt_id, event_type, data, created_at)
+            SELECT ?, task_id, id, ?, ?, ? FROM task_attempts WHERE id = ?
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(kind.event_type())
+        .bind(data)
+        .bind(event_timestamp(Utc::now()))
+        .bind(attempt_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The latest `limit` events of a task from before `before` (all of them
+    /// without it), oldest first
+    pub async fn list_events<'e, E>(executor: E, task_id: Uuid, limit: usize, before: Option<DateTime<Utc>>) -> Result<Vec<TaskEventRow>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let mut rows = sqlx::query_as::<_, TaskEventRow>(
+            r#"
+            SELECT * FROM task_events
+            WHERE task_id = ?1 AND (?2 IS NULL OR created_at < ?2)
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT ?3
+            "#,
+        )
+        .bind(task_id.to_string())
+        .bind(before.map(event_timestamp))
+        .bind(limit as i64)
+        .fetch_all(executor)
+        .await?;
+
+        rows.reverse();
+        Ok(rows)
+    }
+
+    pub async fn attempt_project_settings<'e, E>(executor: E, project_id: &str) -> Result<AttemptProjectSettings, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, AttemptProjectSettings>(
+            "SELECT path, main_branch, cache_links, cache_link_mode, branch_template, default_executor, default_model, git_enabled, subdirectory FROM projects WHERE id = ?"
+        )
+        .bind(project_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn insert_task(tx: &mut Transaction<'_, Sqlite>, id: Uuid, req: &CreateTaskRequest) -> Result<(), sqlx::Error> {
+        let tags_json = req.tags.as_ref().map(|t| serde_json::to_string(t).unwrap_or_default());
+
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (id, project_id, title, description, status, priority, parent_task_id, assignee, tags, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(req.project_id.to_string())
+        .bind(&req.title)
+        .bind(&req.description)
+        .bind("Backlog")
+        .bind(format!("{:?}", req.priority))
+        .bind(req.parent_task_id.map(|id| id.to_string()))
+        .bind(&req.assignee)
+        .bind(&tags_json)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn insert_attempt(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: Uuid,
+        task_id: Uuid,
+        worktree: &WorktreeInfo,
+        executor: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO task_attempts (id, task_id, worktree_path, branch, base_branch, base_commit, executor, model, status, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(task_id.to_string())
+        .bind(&worktree.path)
+        .bind(&worktree.branch)
+        .bind(&worktree.base_branch)
+        .bind(&worktree.base_commit)
+        .bind(executor)
+        .bind(model)
+        .bind("running")
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Set the setup status of every attempt whose setup script is recorded as
+    /// running to "failed". Returns how many there were.
+    pub async fn fail_running_setups(tx: &mut Transaction<'_, Sqlite>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("UPDATE task_attempts SET setup_status = 'failed' WHERE setup_status = 'running'")
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete a task with its attempts and everything recorded for them.
+    /// Subtasks are kept and detached. Returns false if the task didn't exist.
+    pub async fn delete_task(tx: &mut Transaction<'_, Sqlite>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let id = id.to_string();
+
+        sqlx::query("UPDATE tasks SET parent_task_id = NULL WHERE parent_task_id = ?")
+            .bind(&id)
+            .execute(&mut **tx)
+            .await?;
+
+        for table in ["pending_messages", "execution_processes", "merge_requests", "attempt_conversations", "attempt_commits", "review_comments"] {
+            sqlx::query(&format!(
+                "DELETE FROM {} WHERE task_attempt_id IN (SELECT id FROM task_attempts WHERE task_id = ?)",
+                table
+            ))
+            .bind(&id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for table in ["task_events", "task_status_history"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE task_id = ?", table))
+                .bind(&id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM task_attempts WHERE task_id = ?")
+            .bind(&id)
+            .execute(&mut **tx)
+            .await?;
+
+        let result = sqlx::query("DELETE FROM tasks WHERE id = ?")
+            .bind(&id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Direct children of a task, oldest first
+    pub async fn list_subtasks<'e, E>(executor: E, parent_id: Uuid) -> Result<Vec<Task>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, TaskRow>(
+            "SELECT * FROM tasks WHERE parent_task_id = ? AND deleted_at IS NULL ORDER BY created_at ASC",
+        )
+        .bind(parent_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(Task::from).collect())
+    }
+
+    /// The task with its subtasks at any depth, deleted ones included
+    pub async fn list_subtree_ids<'e, E>(executor: E, task_id: Uuid) -> Result<Vec<String>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            WITH RECURSIVE subtree(id) AS (
+                SELECT id FROM tasks WHERE id = ?
+                UNION
+                SELECT tasks.id FROM tasks JOIN subtree ON tasks.parent_task_id = subtree.id
+            )
+            SELECT id FROM subtree
+            "#,
+        )
+        .bind(task_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Tasks of a project that are not deleted, in manual order
+    pub async fn list_tasks<'e, E>(executor: E, project_id: Uuid, include_archived: bool) -> Result<Vec<Task>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, TaskRow>(
+            "SELECT * FROM tasks WHERE project_id = ? AND deleted_at IS NULL AND (? OR archived_at IS NULL) ORDER BY position ASC, created_at DESC",
+        )
+        .bind(project_id.to_string())
+        .bind(include_archived)
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(Task::from).collect())
+    }
+
+    /// Tasks with one of the given ids, in no particular order
+    pub async fn list_tasks_by_id<'e, E>(executor: E, ids: &[String]) -> Result<Vec<Task>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!("SELECT * FROM tasks WHERE id IN ({})", placeholders(ids.len()));
+        let mut query = sqlx::query_as::<_, TaskRow>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(executor).await?;
+
+        Ok(rows.into_iter().map(Task::from).collect())
+    }
+
+    /// Visible tasks of a project with one of `statuses` (any status when empty)
+    pub async fn count_visible_tasks<'e, E>(executor: E, project_id: Uuid, statuses: &[String]) -> Result<usize, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let sql = format!("SELECT COUNT(*) FROM tasks WHERE {}", visible_tasks_filter(statuses.len()));
+        let mut query = sqlx::query_as::<_, (i64,)>(&sql).bind(project_id.to_string());
+        for status in statuses {
+            query = query.bind(status);
+        }
+
+        Ok(query.fetch_one(executor).await?.0 as usize)
+    }
+
+    /// Up to `limit` visible tasks of a project, newest first, that come after
+    /// the `(created_at, id)` pair `after`
+    pub async fn list_visible_tasks_page<'e, E>(
+        executor: E,
+        project_id: Uuid,
+        statuses: &[String],
+        after: Option<&(String, String)>,
+        limit: usize,
+    ) -> Result<Vec<TaskRow>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let mut filter = visible_tasks_filter(statuses.len());
+        if after.is_some() {
+            filter.push_str(" AND (created_at < ? OR (created_at = ? AND id < ?))");
+        }
+
+        let sql = format!("SELECT * FROM tasks WHERE {} ORDER BY created_at DESC, id DESC LIMIT ?", filter);
+        let mut query = sqlx::query_as::<_, TaskRow>(&sql).bind(project_id.to_string());
+        for status in statuses {
+            query = query.bind(status);
+        }
+        if let Some((created_at, id)) = after {
+            query = query.bind(created_at).bind(created_at).bind(id);
+        }
+
+        query.bind(limit as i64).fetch_all(executor).await
+    }
+
+    /// Tasks that are not deleted matching `search`, best match first when
+    /// ordered by rank, newest first otherwise
+    pub async fn search_tasks<'e, E>(executor: E, search: &TaskSearchQuery<'_>) -> Result<Vec<TaskSearchHit>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        // bm25 weights follow the column order: title, description, tags, conversations
+        let mut sql = match search.match_expr {
+            Some(_) => String::from(
+                "SELECT t.*, bm25(tasks_fts, 10.0, 1.0, 5.0, 0.5) AS rank, \
+                 snippet(tasks_fts, 0, '<mark>', '</mark>', '…', 12) AS title_snippet, \
+                 snippet(tasks_fts, 1, '<mark>', '</mark>', '…', 12) AS description_snippet, \
+                 snippet(tasks_fts, 2, '<mark>', '</mark>', '…', 12) AS tags_snippet, \
+                 snippet(tasks_fts, 3, '<mark>', '</mark>', '…', 16) AS conversations_snippet \
+                 FROM tasks_fts JOIN tasks t ON t.id = tasks_fts.task_id \
+                 WHERE tasks_fts MATCH ? AND t.deleted_at IS NULL"
+            ),
+            None => String::from(
+                "SELECT t.*, 0.0 AS rank, NULL AS title_snippet, NULL AS description_snippet, \
+                 NULL AS tags_snippet, NULL AS conversations_snippet \
+                 FROM tasks t WHERE t.deleted_at IS NULL"
+            ),
+        };
+        if search.project_id.is_some() {
+            sql.push_str(" AND t.project_id = ?");
+        }
+        if !search.statuses.is_empty() {
+            sql.push_str(&format!(" AND t.status IN ({})", placeholders(search.statuses.len())));
+        }
+        if search.assignee.is_some() {
+            sql.push_str(" AND t.assignee = ?");
+        }
+        if search.match_expr.is_some() && search.order_by_rank {
+            sql.push_str(" ORDER BY rank, t.created_at DESC");
+        } else {
+            sql.push_str(" ORDER BY t.created_at DESC");
+        }
+        sql.push_str(" LIMIT ?");
+
+        let mut query = sqlx::query_as::<_, TaskSearchHit>(&sql);
+        if let Some(match_expr) = search.match_expr {
+            query = query.bind(match_expr);
+        }
+        if let Some(project_id) = search.project_id {
+            query = query.bind(project_id.to_string());
+        }
+        for status in search.statuses {
+            query = query.bind(status);
+        }
+        if let Some(assignee) = search.assignee {
+            query = query.bind(assignee);
+        }
+
+        query.bind(search.limit as i64).fetch_all(executor).await
+    }
+
+    /// Write the fields `req` sets
+    pub async fn update_task(tx: &mut Transaction<'_, Sqlite>, id: Uuid, req: &UpdateTaskRequest) -> Result<(), sqlx::Error> {
+        let mut update_parts = vec!["updated_at = datetime('now')"];
+        let mut params: Vec<String> = vec![];
+
+        if let Some(title) = &req.title {
+            update_parts.push("title = ?");
+            params.push(title.clone());
+        }
+
+        if let Some(description) = &req.description {
+            update_parts.push("description = ?");
+            params.push(description.clone());
+        }
+
+        if let Some(status) = &req.status {
+            update_parts.push("status = ?");
+            params.push(format!("{:?}", status).to_lowercase());
+        }
+
+        if let Some(priority) = &req.priority {
+            update_parts.push("priority = ?");
+            params.push(format!("{:?}", priority).to_lowercase());
+        }
+
+        if let Some(assignee) = &req.assignee {
+            update_parts.push("assignee = ?");
+            params.push(assignee.clone());
+        }
+
+        if let Some(tags) = &req.tags {
+            update_parts.push("tags = ?");
+            params.push(serde_json::to_string(tags).unwrap());
+        }
+
+        let sql = format!("UPDATE tasks SET {} WHERE id = ?", update_parts.join(", "));
+        params.push(id.to_string());
+
+        let mut query = sqlx::query(&sql);
+        for param in params {
+            query = query.bind(param);
+        }
+        query.execute(&mut **tx).await?;
+
+        Ok(())
+    }
+
+    /// Set the status of the given tasks with a single `UPDATE`
+    pub async fn set_tasks_status(tx: &mut Transaction<'_, Sqlite>, ids: &[String], status: &TaskStatus) -> Result<(), sqlx::Error> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let sql = format!("UPDATE tasks SET status = ?, updated_at = datetime('now') WHERE id IN ({})", placeholders(ids.len()));
+        let mut query = sqlx::query(&sql).bind(format!("{:?}", status));
+        for id in ids {
+            query = query.bind(id);
+        }
+        query.execute(&mut **tx).await?;
+
+        Ok(())
+    }
+
+    /// Project, status and whether it is deleted, of each task with one of the given ids
+    pub async fn task_owners<'e, E>(executor: E, ids: &[String]) -> Result<Vec<(String, String, String, bool)>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT id, project_id, status, deleted_at IS NOT NULL FROM tasks WHERE id IN ({})",
+            placeholders(ids.len())
+        );
+        let mut query = sqlx::query_as::<_, (String, String, String, bool)>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+
+        query.fetch_all(executor).await
+    }
+
+    /// Put the given tasks in the same project, without a manual position
+    pub async fn set_tasks_project(tx: &mut Transaction<'_, Sqlite>, ids: &[String], project_id: Uuid) -> Result<(), sqlx::Error> {
+        for id in ids {
+            sqlx::query("UPDATE tasks SET project_id = ?, position = NULL, updated_at = datetime('now') WHERE id = ?")
+                .bind(project_id.to_string())
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn detach_from_parent(tx: &mut Transaction<'_, Sqlite>, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE tasks SET parent_task_id = NULL WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_task_position(tx: &mut Transaction<'_, Sqlite>, id: Uuid, project_id: Uuid, position: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE tasks SET position = ? WHERE id = ? AND project_id = ?")
+            .bind(position)
+            .bind(id.to_string())
+            .bind(project_id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Archive a task at `archived_at`, or bring it back with `None`. Returns
+    /// false if the task didn't exist.
+    pub async fn set_task_archived(tx: &mut Transaction<'_, Sqlite>, id: Uuid, archived_at: Option<DateTime<Utc>>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE tasks SET archived_at = ?, updated_at = datetime('now') WHERE id = ?")
+            .bind(archived_at.map(|at| at.to_rfc3339()))
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move a task to the trash; a task already there keeps its deletion time.
+    /// Returns false if the task didn't exist.
+    pub async fn soft_delete_task(tx: &mut Transaction<'_, Sqlite>, id: Uuid, deleted_at: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE tasks SET deleted_at = COALESCE(deleted_at, ?), updated_at = datetime('now') WHERE id = ?")
+            .bind(deleted_at.to_rfc3339())
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns false if the task didn't exist or wasn't deleted
+    pub async fn restore_task(tx: &mut Transaction<'_, Sqlite>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE tasks SET deleted_at = NULL, updated_at = datetime('now') WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Tasks deleted before `cutoff`, oldest deletion first
+    pub async fn list_deleted_tasks_before<'e, E>(executor: E, cutoff: DateTime<Utc>) -> Result<Vec<Task>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, TaskRow>(
+            "SELECT * FROM tasks WHERE deleted_at IS NOT NULL AND deleted_at < ? ORDER BY deleted_at ASC",
+        )
+        .bind(cutoff.to_rfc3339())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(Task::from).collect())
+    }
+
+    /// Checkout directory of a project, `None` if there is no such project
+    pub async fn project_path<'e, E>(executor: E, project_id: &str) -> Result<Option<String>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row: Option<(String,)> = sqlx::query_as("SELECT path FROM projects WHERE id = ?")
+            .bind(project_id)
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(row.map(|(path,)| path))
+    }
+
+    /// Set an attempt's status; finished ones get their completion time
+    pub async fn set_attempt_status(tx: &mut Transaction<'_, Sqlite>, id: Uuid, status: &AttemptStatus) -> Result<(), sqlx::Error> {
+        let finished = matches!(status, AttemptStatus::Success | AttemptStatus::Failed | AttemptStatus::Cancelled);
+
+        sqlx::query("UPDATE task_attempts SET status = ?, completed_at = CASE WHEN ? THEN datetime('now') ELSE completed_at END WHERE id = ?")
+            .bind(format!("{:?}", status).to_lowercase())
+            .bind(finished)
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_attempt_claude_session(tx: &mut Transaction<'_, Sqlite>, id: Uuid, claude_session_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE task_attempts SET claude_session_id = ? WHERE id = ?")
+            .bind(claude_session_id)
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// `None` clears the attempt's MCP server selection
+    pub async fn set_attempt_mcp_servers(tx: &mut Transaction<'_, Sqlite>, id: Uuid, server_ids_json: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE task_attempts SET mcp_server_ids = ? WHERE id = ?")
+            .bind(server_ids_json)
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_attempt_executor(tx: &mut Transaction<'_, Sqlite>, id: Uuid, executor: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE task_attempts SET executor = ? WHERE id = ?")
+            .bind(executor)
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_attempt_setup_status(tx: &mut Transaction<'_, Sqlite>, id: Uuid, setup_status: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE task_attempts SET setup_status = ? WHERE id = ?")
+            .bind(setup_status)
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn add_attempt_cost(tx: &mut Transaction<'_, Sqlite>, id: Uuid, cost_usd: f64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE task_attempts SET cost_usd = COALESCE(cost_usd, 0) + ? WHERE id = ?")
+            .bind(cost_usd)
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Cost of every attempt of a project's tasks
+    pub async fn total_project_cost<'e, E>(executor: E, project_id: Uuid) -> Result<f64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let (total,): (f64,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(ta.cost_usd), 0.0)
+            FROM task_attempts ta
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE t.project_id = ?
+            "#,
+        )
+        .bind(project_id.to_string())
+        .fetch_one(executor)
+        .await?;
+
+        Ok(total)
+    }
+
+    /// Replace the messages of an attempt's conversation, creating it if needed
+    pub async fn save_conversation(tx: &mut Transaction<'_, Sqlite>, attempt_id: Uuid, messages_json: &str) -> Result<(), sqlx::Error> {
+        let existing: Option<(String,)> = sqlx::query_as("SELECT id FROM attempt_conversations WHERE task_attempt_id = ?")
+            .bind(attempt_id.to_string())
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        if let Some((existing_id,)) = existing {
+            sqlx::query("UPDATE attempt_conversations SET messages = ?, updated_at = datetime('now') WHERE id = ?")
+                .bind(messages_json)
+                .bind(&existing_id)
+                .execute(&mut **tx)
+                .await?;
+        } else {
+            sqlx::query(
+                r#"
+                INSERT INTO attempt_conversations (id, task_attempt_id, messages, created_at, updated_at)
+                VALUES (?, ?, ?, datetime('now'), datetime('now'))
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(attempt_id.to_string())
+            .bind(messages_json)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_conversation<'e, E>(executor: E, attempt_id: Uuid) -> Result<Option<AttemptConversation>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row: Option<(String, String, String, String, String)> = sqlx::query_as(
+            "SELECT id, task_attempt_id, messages, created_at, updated_at FROM attempt_conversations WHERE task_attempt_id = ?"
+        )
+        .bind(attempt_id.to_string())
+        .fetch_optional(executor)
+        .await?;
+
+        let Some((id, task_attempt_id, messages_json, created_at, updated_at)) = row else {
+            return Ok(None);
+        };
+        let messages: Vec<ConversationMessage> = serde_json::from_str(&messages_json)
+            .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+
+        Ok(Some(AttemptConversation {
+            id,
+            task_attempt_id,
+            messages,
+            created_at,
+            updated_at,
+        }))
+    }
+
+    /// Add a message to its attempt's queue. A message that is already queued
+    /// stays as it is.
+    pub async fn insert_pending_message(tx: &mut Transaction<'_, Sqlite>, pending: &PendingMessage) -> Result<(), sqlx::Error> {
+        let images_json = pending.images.as_ref().map(|imgs| serde_json::to_string(imgs).unwrap_or_default());
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO pending_messages (id, task_attempt_id, task_id, message, images, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&pending.id)
+        .bind(&pending.task_attempt_id)
+        .bind(&pending.task_id)
+        .bind(&pending.message)
+        .bind(images_json)
+        .bind(pending.created_at.to_rfc3339())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_pending_message<'e, E>(executor: E, id: &str) -> Result<Option<PendingMessage>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row = sqlx::query_as::<_, PendingMessageRow>("SELECT * FROM pending_messages WHERE id = ?")
+            .bind(id)
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(row.map(PendingMessage::from))
+    }
+
+    /// Pending messages of an attempt in the order they will be sent
+    pub async fn list_pending_messages<'e, E>(executor: E, attempt_id: Uuid) -> Result<Vec<PendingMessage>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, PendingMessageRow>(
+            "SELECT * FROM pending_messages WHERE task_attempt_id = ? ORDER BY created_at ASC, rowid ASC"
+        )
+        .bind(attempt_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(PendingMessage::from).collect())
+    }
+
+    /// Task and attempt ids of every attempt with queued messages
+    pub async fn list_attempts_with_pending_messages<'e, E>(executor: E) -> Result<Vec<(String, String)>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, (String, String)>("SELECT DISTINCT task_id, task_attempt_id FROM pending_messages")
+            .fetch_all(executor)
+            .await
+    }
+
+    pub async fn delete_pending_message(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM pending_messages WHERE id = ?")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drop every pending message of an attempt
+    pub async fn delete_pending_messages(tx: &mut Transaction<'_, Sqlite>, attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM pending_messages WHERE task_attempt_id = ?")
+            .bind(attempt_id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn insert_attempt_commit(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: Uuid,
+        attempt_id: Uuid,
+        commit_hash: &str,
+        message: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO attempt_commits (id, task_attempt_id, commit_hash, message, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(attempt_id.to_string())
+        .bind(commit_hash)
+        .bind(message)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_attempt_commit<'e, E>(executor: E, id: Uuid) -> Result<Option<AttemptCommit>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row = sqlx::query_as::<_, AttemptCommitRow>("SELECT * FROM attempt_commits WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(row.map(AttemptCommit::from))
+    }
+
+    /// Automatic commits of an attempt, oldest first
+    pub async fn list_attempt_commits<'e, E>(executor: E, attempt_id: Uuid) -> Result<Vec<AttemptCommit>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, AttemptCommitRow>(
+            "SELECT * FROM attempt_commits WHERE task_attempt_id = ? ORDER BY created_at ASC, rowid ASC"
+        )
+        .bind(attempt_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(AttemptCommit::from).collect())
+    }
+
+    /// Save an open review comment on `attempt_id`. `line_content` is the line it
+    /// points at in the attempt's version of the file.
+    pub async fn insert_review_comment(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: Uuid,
+        attempt_id: &str,
+        req: &CreateReviewCommentRequest,
+        line_content: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO review_comments (id, task_attempt_id, file_path, line, side, body, line_content, resolved, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(attempt_id)
+        .bind(&req.file_path)
+        .bind(req.line)
+        .bind(format!("{:?}", req.side).to_lowercase())
+        .bind(&req.body)
+        .bind(line_content)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_review_comment<'e, E>(executor: E, id: Uuid) -> Result<Option<ReviewComment>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row = sqlx::query_as::<_, ReviewCommentRow>("SELECT * FROM review_comments WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(row.map(ReviewComment::from))
+    }
+
+    /// Review comments of an attempt in file and line order
+    pub async fn list_review_comments<'e, E>(executor: E, attempt_id: Uuid) -> Result<Vec<ReviewComment>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, ReviewCommentRow>(
+            "SELECT * FROM review_comments WHERE task_attempt_id = ? ORDER BY file_path ASC, line ASC, created_at ASC"
+        )
+        .bind(attempt_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(ReviewComment::from).collect())
+    }
+
+    pub async fn update_review_comment(tx: &mut Transaction<'_, Sqlite>, id: Uuid, body: &str, resolved: bool) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE review_comments SET body = ?, resolved = ?, updated_at = ? WHERE id = ?")
+            .bind(body)
+            .bind(resolved)
+            .bind(Utc::now().to_rfc3339())
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns false if the comment didn't exist
+    pub async fn delete_review_comment(tx: &mut Transaction<'_, Sqlite>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM review_comments WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_task_template(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: Uuid,
+        project_id: Uuid,
+        values: &TaskTemplateValues<'_>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO task_templates (id, project_id, name, title_pattern, description_template, priority, tags, executor, initial_prompt, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(project_id.to_string())
+        .bind(values.name)
+        .bind(values.title_pattern)
+        .bind(values.description_template)
+        .bind(format!("{:?}", values.priority))
+        .bind(values.tags.map(|t| serde_json::to_string(t).unwrap_or_default()))
+        .bind(values.executor)
+        .bind(values.initial_prompt)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_task_template<'e, E>(executor: E, id: Uuid) -> Result<Option<TaskTemplate>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let row = sqlx::query_as::<_, TaskTemplateRow>("SELECT * FROM task_templates WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(row.map(TaskTemplate::from))
+    }
+
+    /// Task templates of a project by name
+    pub async fn list_task_templates<'e, E>(executor: E, project_id: Uuid) -> Result<Vec<TaskTemplate>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let rows = sqlx::query_as::<_, TaskTemplateRow>(
+            "SELECT * FROM task_templates WHERE project_id = ? ORDER BY name COLLATE NOCASE ASC, created_at ASC"
+        )
+        .bind(project_id.to_string())
+        .fetch_all(executor)
+        .await?;
+
+        Ok(rows.into_iter().map(TaskTemplate::from).collect())
+    }
+
+    pub async fn update_task_template(tx: &mut Transaction<'_, Sqlite>, id: Uuid, values: &TaskTemplateValues<'_>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE task_templates
+            SET name = ?, title_pattern = ?, description_template = ?, priority = ?, tags = ?, executor = ?, initial_prompt = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(values.name)
+        .bind(values.title_pattern)
+        .bind(values.description_template)
+        .bind(format!("{:?}", values.priority))
+        .bind(values.tags.map(|t| serde_json::to_string(t).unwrap_or_default()))
+        .bind(values.executor)
+        .bind(values.initial_prompt)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id.to_string())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns false if the template didn't exist
+    pub async fn delete_task_template(tx: &mut Transaction<'_, Sqlite>, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM task_templates WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// `count` comma separated `?` placeholders
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+/// Tasks of a project, bound first, that are neither archived nor deleted and
+/// have one of `status_count` statuses, bound next
+fn visible_tasks_filter(status_count: usize) -> String {
+    let mut filter = String::from("project_id = ? AND archived_at IS NULL AND deleted_at IS NULL");
+    if status_count > 0 {
+        filter.push_str(&format!(" AND status IN ({})", placeholders(status_count)));
+    }
+    filter
+}
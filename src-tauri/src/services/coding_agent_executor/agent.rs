@@ -1,6 +1,8 @@
 use async_trait::async_trait;
 use std::sync::mpsc::Sender;
 use super::types::*;
+use crate::models::ProjectEnvironment;
+use crate::services::mcp_server::McpServer;
 
 /// Channel message that routes ConversationMessage to the correct task/attempt
 pub struct ChannelMessage {
@@ -27,6 +29,24 @@ pub trait CodingAgent: Send + Sync {
         execution_id: &str,
         execution_context: &ExecutionContext,
     ) -> Result<(), String>;
+    
+    /// Suspend a running execution without terminating it
+    async fn pause_execution(
+        &self,
+        _execution_id: &str,
+        _execution_context: &ExecutionContext,
+    ) -> Result<(), String> {
+        Err("Pausing is not supported by this agent".to_string())
+    }
+    
+    /// Continue a previously paused execution
+    async fn resume_execution(
+        &self,
+        _execution_id: &str,
+        _execution_context: &ExecutionContext,
+    ) -> Result<(), String> {
+        Err("Resuming is not supported by this agent".to_string())
+    }
 }
 
 /// Context for executing a coding agent
@@ -37,4 +57,7 @@ pub struct ExecutionContext {
     pub attempt_id: String,
     pub working_directory: String,
     pub resume_session_id: Option<String>, // For agents that support resuming
+    pub env: ProjectEnvironment, // Project-level environment variables
+    pub mcp_servers: Vec<McpServer>, // Running MCP servers of the project and the attempt
+    pub model: Option<String>, // Model of the attempt, None for the agent's default
 }
\ No newline at end of file
@@ -12,7 +12,9 @@ use tauri::AppHandle;
 
 use super::agent::{CodingAgent, ExecutionContext, ChannelMessage};
 use super::stateful_claude_converter::StatefulClaudeMessageConverter;
+use super::raw_log::RawLogWriter;
 use super::types::*;
+use crate::services::mcp_server::McpServer;
 
 pub struct ClaudeCodeAgent {
     app_handle: AppHandle,
@@ -92,6 +94,30 @@ impl ClaudeCodeAgent {
         None
     }
 
+    /// Answer a single prompt without a session and return the plain text
+    /// reply. For short text generation outside of a task conversation.
+    pub async fn complete(prompt: &str, working_directory: &str, timeout: std::time::Duration) -> Result<String, String> {
+        let claude_cmd = Self::find_claude_command()
+            .ok_or("Failed to start Claude Code: command not found")?;
+
+        let output = tokio::process::Command::new(&claude_cmd)
+            .args(["--print", "--output-format", "text", prompt])
+            .current_dir(working_directory)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(timeout, output)
+            .await
+            .map_err(|_| format!("Claude Code did not answer within {} seconds", timeout.as_secs()))?
+            .map_err(|e| format!("Failed to start Claude Code: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Claude Code failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     fn find_npx_path() -> Option<String> {
         // Check common locations
         let common_paths = [
@@ -187,6 +213,146 @@ impl ClaudeCodeAgent {
         
         None
     }
+    
+    /// Suspend or continue the process of a running execution
+    /// Write a temporary `--mcp-config` file describing the given MCP servers
+    fn write_mcp_config(execution_id: &str, servers: &[McpServer]) -> Result<std::path::PathBuf, String> {
+        let mut mcp_servers = serde_json::Map::new();
+        for server in servers {
+            // Server names are keys in the config, keep the first one on collision
+            let mut name = server.name.clone();
+            if mcp_servers.contains_key(&name) {
+                name = format!("{}-{}", server.name, server.id);
+            }
+            mcp_servers.insert(name, serde_json::json!({
+                "command": server.command,
+                "args": server.args,
+                "env": server.env,
+            }));
+        }
+        
+        let config = serde_json::json!({ "mcpServers": mcp_servers });
+        let path = std::env::temp_dir().join(format!("pivo-mcp-{}.json", execution_id));
+        std::fs::write(&path, config.to_string())
+            .map_err(|e| format!("Failed to write MCP config: {}", e))?;
+        
+        info!("Attached {} MCP server(s) via {:?}", servers.len(), path);
+        Ok(path)
+    }
+    
+    fn set_suspended(&self, execution_id: &str, suspend: bool) -> Result<(), String> {
+        let mut processes = self.running_processes.lock().unwrap();
+        let child = processes.get_mut(execution_id)
+            .ok_or("No running process found for this execution")?;
+        
+        // The process may have exited while it was paused
+        if let Ok(Some(status)) = child.try_wait() {
+            processes.remove(execution_id);
+            return Err(format!("Execution process has already exited ({})", status));
+        }
+        
+        suspend_process_tree(child.id(), suspend)
+    }
+}
+
+/// Send SIGSTOP/SIGCONT to the execution's process group (the shell and claude itself)
+#[cfg(unix)]
+fn suspend_process_tree(pid: u32, suspend: bool) -> Result<(), String> {
+    let signal = if suspend { libc::SIGSTOP } else { libc::SIGCONT };
+    let result = unsafe { libc::killpg(pid as i32, signal) };
+    if result != 0 {
+        return Err(format!("Failed to signal process {}: {}", pid, std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// How long a stopped execution gets to shut down before it is killed
+#[cfg(unix)]
+const STOP_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Stop the execution's process group and reap the shell. The group is continued
+/// first, a paused execution would not handle the SIGTERM otherwise, and whatever
+/// is still running after `STOP_GRACE_PERIOD` gets a SIGKILL.
+#[cfg(unix)]
+fn terminate_process_tree(child: &mut Child) -> Result<(), String> {
+    let pgid = child.id() as i32;
+    unsafe {
+        libc::killpg(pgid, libc::SIGCONT);
+        if libc::killpg(pgid, libc::SIGTERM) != 0 {
+            // The group is gone when everything in it has exited already
+            let _ = child.kill();
+        }
+    }
+    
+    let deadline = std::time::Instant::now() + STOP_GRACE_PERIOD;
+    loop {
+        // A shell that has exited but isn't reaped still counts as part of the group
+        let _ = child.try_wait();
+        if unsafe { libc::killpg(pgid, 0) } != 0 {
+            break;
+        }
+        if std::time::Instant::now() >= deadline {
+            log::warn!("Process group {} did not exit after SIGTERM, killing it", pgid);
+            unsafe {
+                libc::killpg(pgid, libc::SIGKILL);
+            }
+            break;
+        }
+        thread::sleep(std::time::Duration::from_millis(50));
+    }
+    
+    let status = child.wait().map_err(|e| format!("Error waiting for process {} to terminate: {}", pgid, e))?;
+    log::info!("Process terminated with status: {:?}", status);
+    Ok(())
+}
+
+/// Terminating a process works whether or not its threads are suspended
+#[cfg(windows)]
+fn terminate_process_tree(child: &mut Child) -> Result<(), String> {
+    child.kill().map_err(|e| format!("Failed to kill process {}: {}", child.id(), e))?;
+    let status = child.wait().map_err(|e| format!("Error waiting for process {} to terminate: {}", child.id(), e))?;
+    log::info!("Process terminated with status: {:?}", status);
+    Ok(())
+}
+
+/// Suspend or resume every thread owned by the process
+#[cfg(windows)]
+fn suspend_process_tree(pid: u32, suspend: bool) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use windows_sys::Win32::System::Threading::{OpenThread, ResumeThread, SuspendThread, THREAD_SUSPEND_RESUME};
+    
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(format!("Failed to enumerate threads: {}", std::io::Error::last_os_error()));
+        }
+        
+        let mut entry: THREADENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+        
+        let mut has_entry = Thread32First(snapshot, &mut entry) != 0;
+        while has_entry {
+            if entry.th32OwnerProcessID == pid {
+                let thread = OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID);
+                if !thread.is_null() {
+                    if suspend {
+                        SuspendThread(thread);
+                    } else {
+                        ResumeThread(thread);
+                    }
+                    CloseHandle(thread);
+                }
+            }
+            has_entry = Thread32Next(snapshot, &mut entry) != 0;
+        }
+        
+        CloseHandle(snapshot);
+    }
+    
+    Ok(())
 }
 
 #[async_trait]
@@ -236,6 +402,28 @@ impl CodingAgent for ClaudeCodeAgent {
             cmd_args.push(session_id);
         }
         
+        let escaped_model = execution_context.model
+            .as_ref()
+            .map(|model| shell_escape::escape(model.as_str().into()).to_string());
+        if let Some(model) = &escaped_model {
+            cmd_args.push("--model");
+            cmd_args.push(model);
+        }
+        
+        // Forward the project's running MCP servers to the session
+        let mcp_config_path = if execution_context.mcp_servers.is_empty() {
+            None
+        } else {
+            Some(Self::write_mcp_config(&execution_id, &execution_context.mcp_servers)?)
+        };
+        let escaped_mcp_config_path = mcp_config_path
+            .as_ref()
+            .map(|path| shell_escape::escape(path.to_string_lossy()).to_string());
+        if let Some(path) = &escaped_mcp_config_path {
+            cmd_args.push("--mcp-config");
+            cmd_args.push(path);
+        }
+        
         // Build the full shell command
         let shell_cmd = if using_npx {
             format!("{} {}", claude_cmd, cmd_args.join(" "))
@@ -256,9 +444,18 @@ impl CodingAgent for ClaudeCodeAgent {
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
         
+        // Run in its own process group so pause/stop reach claude and not just the shell
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        
         // Set environment
         command.env("FORCE_COLOR", "0");
         command.env("TERM", "dumb");
+        command.envs(crate::utils::http::proxy_env_vars());
+        command.envs(execution_context.env.vars());
         
         if let Ok(anthropic_key) = std::env::var("ANTHROPIC_API_KEY") {
             command.env("ANTHROPIC_API_KEY", anthropic_key);
@@ -269,11 +466,18 @@ impl CodingAgent for ClaudeCodeAgent {
             .map_err(|e| {
                 let err_msg = format!("Failed to start Claude Code: {}", e);
                 log::error!("{}", err_msg);
+                if let Some(path) = &mcp_config_path {
+                    let _ = std::fs::remove_file(path);
+                }
                 err_msg
             })?;
         
         info!("Claude Code process started with PID: {:?}", child.id());
         
+        // Persist the raw stream-json output for troubleshooting
+        let raw_log = Arc::new(RawLogWriter::create(&execution_id));
+        let log_path = raw_log.path().to_string_lossy().to_string();
+        
         // Store the child process
         let _child_pid = child.id();
         
@@ -294,6 +498,9 @@ impl CodingAgent for ClaudeCodeAgent {
             let attempt_id = execution_context.attempt_id.clone();
             let _app_handle = self.app_handle.clone();
             let message_sender_clone = message_sender.clone();
+            let raw_log = raw_log.clone();
+            let env = execution_context.env.clone();
+            let mcp_config_path = mcp_config_path.clone();
             
             thread::spawn(move || {
                 let reader = BufReader::new(stdout);
@@ -301,7 +508,10 @@ impl CodingAgent for ClaudeCodeAgent {
                 
                 for line in reader.lines() {
                     if let Ok(content) = line {
+                        // Never let project secrets leak into logs or messages
+                        let content = env.mask(&content);
                         debug!("Claude stdout: {}", content);
+                        raw_log.write_line("stdout", &content);
                         
                         // Try to convert to unified message format
                         if let Some(agent_output) = converter.convert_to_unified(&content) {
@@ -357,6 +567,11 @@ impl CodingAgent for ClaudeCodeAgent {
                     }
                 }
                 
+                // Claude has exited, the MCP config is no longer needed
+                if let Some(path) = &mcp_config_path {
+                    let _ = std::fs::remove_file(path);
+                }
+                
                 // Send execution complete message when process ends
                 let complete_msg = ConversationMessage {
                     id: format!("{}-complete-{}", Utc::now().to_rfc3339(), {
@@ -377,7 +592,8 @@ impl CodingAgent for ClaudeCodeAgent {
                     timestamp: Utc::now(),
                     metadata: Some(serde_json::json!({
                         "execution_id": execution_id_clone,
-                        "status": "completed"
+                        "status": "completed",
+                        "cost_usd": converter.total_cost_usd(),
                     })),
                 };
                 
@@ -395,11 +611,17 @@ impl CodingAgent for ClaudeCodeAgent {
             let task_id = execution_context.task_id.clone();
             let attempt_id = execution_context.attempt_id.clone();
             let message_sender_clone = message_sender.clone();
+            let raw_log = raw_log.clone();
+            let env = execution_context.env.clone();
             
             thread::spawn(move || {
                 let reader = BufReader::new(stderr);
                 for line in reader.lines() {
                     if let Ok(content) = line {
+                        // Never let project secrets leak into logs or messages
+                        let content = env.mask(&content);
+                        raw_log.write_line("stderr", &content);
+                        
                         // Only log actual errors, not normal output
                         if !content.trim().is_empty() && 
                            !content.contains("Using Claude model") &&
@@ -447,6 +669,7 @@ impl CodingAgent for ClaudeCodeAgent {
             working_directory: execution_context.working_directory.clone(),
             status: CodingAgentExecutionStatus::Running,
             created_at: Utc::now(),
+            log_path: Some(log_path),
         };
         
         Ok(execution)
@@ -459,81 +682,11 @@ impl CodingAgent for ClaudeCodeAgent {
     ) -> Result<(), String> {
         log::info!("Stopping Claude execution: {}", execution_id);
         
-        // Try to get and kill the child process
-        let mut processes = self.running_processes.lock().unwrap();
-        if let Some(mut child) = processes.remove(execution_id) {
-            log::info!("Found child process for execution {}, attempting to kill", execution_id);
-            
-            // Try to kill the process
-            let pid = child.id();
-            match child.kill() {
-                Ok(_) => {
-                    log::info!("Successfully sent kill signal to process PID {}", pid);
-                    
-                    // On macOS/Unix, also kill any child processes
-                    #[cfg(unix)]
-                    {
-                        std::thread::sleep(std::time::Duration::from_millis(100));
-                        let _ = std::process::Command::new("pkill")
-                            .arg("-P")
-                            .arg(pid.to_string())
-                            .output();
-                    }
-                    
-                    // Wait for the process to actually terminate
-                    match child.wait() {
-                        Ok(status) => {
-                            log::info!("Process terminated with status: {:?}", status);
-                        }
-                        Err(e) => {
-                            log::warn!("Error waiting for process to terminate: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    log::error!("Failed to kill process: {}", e);
-                    
-                    // Try platform-specific kill as fallback
-                    #[cfg(unix)]
-                    {
-                        let pid = child.id();
-                        log::info!("Trying SIGKILL on PID {} and its process group", pid);
-                        unsafe {
-                            // First try to kill the entire process group
-                            let pgid = libc::getpgid(pid as i32);
-                            if pgid > 0 {
-                                log::info!("Killing process group {}", pgid);
-                                let _ = libc::killpg(pgid, libc::SIGTERM);
-                                std::thread::sleep(std::time::Duration::from_millis(100));
-                                let _ = libc::killpg(pgid, libc::SIGKILL);
-                            }
-                            
-                            // Then kill the specific process
-                            let result = libc::kill(pid as i32, libc::SIGKILL);
-                            if result != 0 {
-                                log::error!("SIGKILL failed with error code: {}", result);
-                            }
-                        }
-                    }
-                    
-                    #[cfg(target_os = "macos")]
-                    {
-                        // On macOS, also try to kill child processes
-                        let pid = child.id();
-                        let output = std::process::Command::new("pkill")
-                            .arg("-P")
-                            .arg(pid.to_string())
-                            .output();
-                        
-                        if let Ok(output) = output {
-                            if !output.status.success() {
-                                log::warn!("pkill -P {} failed", pid);
-                            } else {
-                                log::info!("Successfully killed child processes of PID {}", pid);
-                            }
-                        }
-                    }
-                }
+        let child = self.running_processes.lock().unwrap().remove(execution_id);
+        if let Some(mut child) = child {
+            log::info!("Terminating process group of PID {} for execution {}", child.id(), execution_id);
+            if let Err(e) = terminate_process_tree(&mut child) {
+                log::error!("{}", e);
             }
         } else {
             log::warn!("No child process found for execution {}", execution_id);
@@ -541,4 +694,82 @@ impl CodingAgent for ClaudeCodeAgent {
         
         Ok(())
     }
-}
\ No newline at end of file
+    
+    async fn pause_execution(
+        &self,
+        execution_id: &str,
+        _execution_context: &ExecutionContext,
+    ) -> Result<(), String> {
+        info!("Pausing Claude Code execution: {}", execution_id);
+        self.set_suspended(execution_id, true)
+    }
+    
+    async fn resume_execution(
+        &self,
+        execution_id: &str,
+        _execution_context: &ExecutionContext,
+    ) -> Result<(), String> {
+        info!("Resuming Claude Code execution: {}", execution_id);
+        self.set_suspended(execution_id, false)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::CommandExt;
+    use std::time::{Duration, Instant};
+
+    /// Whether a process still exists and isn't a zombie waiting to be reaped
+    fn is_alive(pid: u32) -> bool {
+        Command::new("ps")
+            .args(["-o", "stat=", "-p", &pid.to_string()])
+            .output()
+            .map(|output| {
+                let stat = String::from_utf8_lossy(&output.stdout);
+                output.status.success() && !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+            })
+            .unwrap_or(false)
+    }
+
+    /// Whether any process of the group is still running
+    fn group_is_alive(pgid: i32) -> bool {
+        Command::new("ps")
+            .args(["-e", "-o", "pgid=,stat="])
+            .output()
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+                    let mut fields = line.split_whitespace();
+                    fields.next() == Some(pgid.to_string().as_str())
+                        && fields.next().is_some_and(|stat| !stat.starts_with('Z'))
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn stopping_a_paused_execution_kills_its_process_group() {
+        // Stands in for the shell running claude. Both ignore SIGHUP and SIGTERM,
+        // as an agent that doesn't shut down would
+        let mut shell = Command::new("sh")
+            .args(["-c", "trap '' HUP TERM; sleep 30 & echo $!; wait"])
+            .stdout(Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let pgid = shell.id() as i32;
+        let mut line = String::new();
+        BufReader::new(shell.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let sleep_pid: u32 = line.trim().parse().unwrap();
+
+        suspend_process_tree(shell.id(), true).unwrap();
+        terminate_process_tree(&mut shell).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while group_is_alive(pgid) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(!is_alive(sleep_pid), "sleep {} survived stopping its paused execution", sleep_pid);
+        assert!(!group_is_alive(pgid), "process group {} is still running", pgid);
+    }
+}
@@ -11,6 +11,8 @@ use super::agent::{CodingAgent, ExecutionContext, ChannelMessage};
 use super::types::*;
 use super::message::MessageConverter;
 use super::gemini_converter::GeminiMessageConverter;
+use super::raw_log::RawLogWriter;
+use crate::models::ProjectEnvironment;
 
 pub struct GeminiCliAgent {
     app_handle: AppHandle,
@@ -38,6 +40,8 @@ impl GeminiCliAgent {
         attempt_id: &str,
         working_directory: &str,
         context_files: Vec<String>,
+        env: &ProjectEnvironment,
+        model: Option<&str>,
         message_sender: Sender<ChannelMessage>,
     ) -> Result<(), String> {
         let mut command = Command::new("google-gemini");
@@ -49,6 +53,13 @@ impl GeminiCliAgent {
             command.args(&["--context-file", file]);
         }
         
+        if let Some(model) = model {
+            command.args(&["--model", model]);
+        }
+        
+        command.envs(crate::utils::http::proxy_env_vars());
+        command.envs(env.vars());
+        
         command.stdin(Stdio::piped());
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
@@ -57,6 +68,7 @@ impl GeminiCliAgent {
             .map_err(|e| format!("Failed to start Gemini CLI: {}", e))?;
         
         let stdin = child.stdin.take();
+        let raw_log = Arc::new(RawLogWriter::create(execution_id));
         
         // Handle stdout
         if let Some(stdout) = child.stdout.take() {
@@ -64,6 +76,8 @@ impl GeminiCliAgent {
             let execution_id_clone = execution_id.to_string();
             let task_id_clone = task_id.to_string();
             let attempt_id_clone = attempt_id.to_string();
+            let raw_log = raw_log.clone();
+            let env = env.clone();
             
             thread::spawn(move || {
                 let reader = BufReader::new(stdout);
@@ -71,7 +85,9 @@ impl GeminiCliAgent {
                 
                 for line in reader.lines() {
                     if let Ok(content) = line {
+                        let content = env.mask(&content);
                         debug!("Gemini stdout: {}", content);
+                        raw_log.write_line("stdout", &content);
                         
                         // Try to convert to unified message format
                         if let Some(agent_output) = converter.convert_to_unified(&content) {
@@ -131,12 +147,15 @@ impl GeminiCliAgent {
             let _app_handle = self.app_handle.clone();
             let execution_id_clone = execution_id.to_string();
             let _task_id_clone = task_id.to_string();
+            let raw_log = raw_log.clone();
+            let env = env.clone();
             
             thread::spawn(move || {
                 let reader = BufReader::new(stderr);
                 for line in reader.lines() {
-                    if let Ok(_content) = line {
-                        // Debug stderr output removed - no longer needed with new event architecture
+                    if let Ok(content) = line {
+                        let content = env.mask(&content);
+                        raw_log.write_line("stderr", &content);
                     }
                 }
                 debug!("Stderr reader thread ended for execution: {}", execution_id_clone);
@@ -170,13 +189,15 @@ impl CodingAgent for GeminiCliAgent {
             working_directory: execution_context.working_directory.clone(),
             status: CodingAgentExecutionStatus::Running,
             created_at: Utc::now(),
+            log_path: Some(super::raw_log::get_execution_log_path(&execution_id).to_string_lossy().to_string()),
         };
         
         // User message will be created by the service layer
         
         // Start the Gemini process with the prompt
         self.spawn_process(&execution_id, &execution_context.task_id, &execution_context.attempt_id, 
-                          &execution_context.working_directory, vec![], message_sender)?;
+                          &execution_context.working_directory, vec![], &execution_context.env,
+                          execution_context.model.as_deref(), message_sender)?;
         
         // Send the prompt to the process stdin
         let mut processes = self.active_processes.lock().unwrap();
@@ -8,6 +8,7 @@ pub mod claude_converter;
 pub mod stateful_claude_converter;
 pub mod gemini_converter;
 pub mod metadata;
+pub mod raw_log;
 
 pub use types::*;
 pub use service::*;
@@ -0,0 +1,141 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::{info, warn};
+
+use crate::logging::get_log_dir;
+
+/// Maximum size of a single execution log file before it is rotated
+pub const MAX_RAW_LOG_BYTES: u64 = 20 * 1024 * 1024;
+
+lazy_static! {
+    /// Log directory from the execution settings, mirrored here for the agent reader threads
+    static ref EXECUTION_LOG_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
+/// Write execution logs to `dir` from now on, `None` or a blank path uses the default
+pub fn set_execution_log_dir(dir: Option<&str>) {
+    let dir = dir.map(str::trim).filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    if let Ok(mut current) = EXECUTION_LOG_DIR.write() {
+        *current = dir;
+    }
+}
+
+pub fn get_execution_log_dir() -> PathBuf {
+    let configured = EXECUTION_LOG_DIR.read().ok().and_then(|dir| dir.clone());
+    let dir = configured.unwrap_or_else(|| get_log_dir().join("executions"));
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+pub fn get_execution_log_path(execution_id: &str) -> PathBuf {
+    get_execution_log_dir().join(format!("{}.log", execution_id))
+}
+
+/// Where the older lines of a log go once it reaches `MAX_RAW_LOG_BYTES`
+pub fn get_rotated_log_path(log_path: &Path) -> PathBuf {
+    log_path.with_extension("log.1")
+}
+
+/// Appends raw agent stdout/stderr lines to a per-execution log file.
+/// Shared between the stdout and stderr reader threads of one execution.
+pub struct RawLogWriter {
+    path: PathBuf,
+    state: Mutex<RawLogState>,
+}
+
+struct RawLogState {
+    file: Option<File>,
+    written: u64,
+}
+
+impl RawLogWriter {
+    pub fn create(execution_id: &str) -> Self {
+        let path = get_execution_log_path(execution_id);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| warn!("Failed to open execution log {:?}: {}", path, e))
+            .ok();
+        let written = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+        Self {
+            path,
+            state: Mutex::new(RawLogState { file, written }),
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn write_line(&self, stream: &str, line: &str) {
+        let entry = format!("{} [{}] {}\n", Utc::now().to_rfc3339(), stream, line);
+        let mut state = self.state.lock().unwrap();
+
+        if state.written + entry.len() as u64 > MAX_RAW_LOG_BYTES {
+            self.rotate(&mut state);
+        }
+
+        if let Some(file) = state.file.as_mut() {
+            if file.write_all(entry.as_bytes()).is_ok() {
+                state.written += entry.len() as u64;
+            }
+        }
+    }
+
+    /// Move the current file to `<id>.log.1` and start a fresh one
+    fn rotate(&self, state: &mut RawLogState) {
+        state.file = None;
+        let rotated = get_rotated_log_path(&self.path);
+        if let Err(e) = fs::rename(&self.path, &rotated) {
+            warn!("Failed to rotate execution log {:?}: {}", self.path, e);
+        }
+        state.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)
+            .ok();
+        state.written = 0;
+    }
+}
+
+/// Remove execution logs that have not been modified within the retention window
+pub fn cleanup_old_execution_logs(retention_days: u64) {
+    let dir = get_execution_log_dir();
+    let max_age = Duration::from_secs(retention_days * 24 * 60 * 60);
+    let now = SystemTime::now();
+    let mut removed = 0;
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to read execution log dir {:?}: {}", dir, e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map(|age| age > max_age)
+            .unwrap_or(false);
+
+        if expired && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+
+    if removed > 0 {
+        info!("Removed {} execution logs older than {} days", removed, retention_days);
+    }
+}
@@ -2,10 +2,10 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc::{channel, Receiver};
 use std::thread;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 use log::info;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use super::types::*;
 use super::agent::{CodingAgent, ExecutionContext, ChannelMessage};
 use super::claude_agent::ClaudeCodeAgent;
@@ -13,6 +13,8 @@ use super::gemini_agent::GeminiCliAgent;
 use super::message::AgentOutput;
 use super::metadata::{AssistantMetadata, ToolUseMetadata, ToolResultMetadata};
 use crate::models::task::TaskStatus;
+use crate::models::{StatusChangeTrigger, TaskEventKind};
+use crate::services::mcp_server::{McpServer, McpServerStatus};
 
 pub struct CodingAgentExecutorService {
     // Key: execution_id -> AgentProcess
@@ -59,6 +61,9 @@ impl CodingAgentExecutorService {
         let app_handle = self.app_handle.clone();
         
         thread::spawn(move || {
+            // Final text of the agent, used for the auto-commit message
+            let mut last_assistant_text: Option<String> = None;
+            
             while let Ok(agent_msg) = receiver.recv() {
                 let attempt_id = agent_msg.attempt_id;
                 let task_id = agent_msg.task_id;
@@ -126,19 +131,59 @@ impl CodingAgentExecutorService {
                         "status": "success",
                     }));
                     
+                    let cost_usd = conversation_msg.metadata.as_ref()
+                        .and_then(|metadata| metadata.get("cost_usd"))
+                        .and_then(|v| v.as_f64());
+                    
                     // Update task status to Reviewing
                     let task_uuid = Uuid::parse_str(&task_id).unwrap();
+                    let attempt_id_clone = attempt_id.clone();
                     let db_repo_clone = db_repository.clone();
                     let app_handle_clone = app_handle.clone();
+                    let summary = last_assistant_text.take();
                     tauri::async_runtime::spawn(async move {
                         use crate::services::task_service::TaskService;
                         let task_service = TaskService::new(db_repo_clone.pool().clone());
                         
+                        task_service
+                            .record_attempt_event(&attempt_id_clone, TaskEventKind::ExecutionCompleted { execution_id: exec_id, cost_usd })
+                            .await;
+                        
+                        // Record what the execution cost before the task moves on
+                        if let (Some(cost_usd), Ok(attempt_uuid)) = (cost_usd, Uuid::parse_str(&attempt_id_clone)) {
+                            if let Err(e) = task_service.update_attempt_cost(attempt_uuid, cost_usd).await {
+                                log::error!("Failed to update cost for attempt {}: {}", attempt_id_clone, e);
+                            }
+                        }
+                        
+                        // Commit before a queued follow-up starts changing the worktree again
+                        auto_commit_attempt(&db_repo_clone, &app_handle_clone, &task_id, &attempt_id_clone, summary.as_deref()).await;
+                        
+                        // Review comments on lines the agent has changed count as addressed
+                        if let Ok(attempt_uuid) = Uuid::parse_str(&attempt_id_clone) {
+                            match task_service.resolve_changed_review_comments(attempt_uuid).await {
+                                Ok(resolved) if !resolved.is_empty() => {
+                                    let _ = app_handle_clone.emit("review:comments-resolved", serde_json::json!({
+                                        "taskId": task_id,
+                                        "attemptId": attempt_id_clone,
+                                        "comments": resolved,
+                                    }));
+                                }
+                                Ok(_) => {}
+                                Err(e) => log::error!("Failed to resolve review comments of attempt {}: {}", attempt_id_clone, e),
+                            }
+                        }
+                        
+                        // Follow-ups queued during the run go out next, resuming the same session
+                        if crate::commands::task_commands::dispatch_next_pending_message(&app_handle_clone, &task_id, &attempt_id_clone).await {
+                            return;
+                        }
+                        
                         // Get current task status first
                         if let Ok(Some(current_task)) = task_service.get_task(task_uuid).await {
                             let previous_status = current_task.status.clone();
                             
-                            if let Ok(updated_task) = task_service.update_task_status(task_uuid, TaskStatus::Reviewing).await {
+                            if let Ok(updated_task) = task_service.update_task_status(task_uuid, TaskStatus::Reviewing, StatusChangeTrigger::AgentCompleted).await {
                                 // Emit task:status-changed event with before/after status
                                 let _ = app_handle_clone.emit("task:status-changed", serde_json::json!({
                                     "taskId": task_id,
@@ -153,6 +198,13 @@ impl CodingAgentExecutorService {
                     continue; // Don't save execution_complete messages
                 }
                 
+                if matches!(conversation_msg.role, MessageRole::Assistant)
+                    && conversation_msg.message_type == "text"
+                    && !conversation_msg.content.trim().is_empty()
+                {
+                    last_assistant_text = Some(conversation_msg.content.clone());
+                }
+                
                 // Add to in-memory messages
                 if let Ok(mut execs) = executions.lock() {
                     for (_exec_id, process) in execs.iter_mut() {
@@ -172,27 +224,19 @@ impl CodingAgentExecutorService {
                 }
                 
                 // Save to database - encode the full message data
-                let db_message = crate::models::ConversationMessage {
-                    role: match conversation_msg.role {
-                        MessageRole::User => "user",
-                        MessageRole::Assistant => "assistant",
-                        MessageRole::System => "system",
-                    }.to_string(),
-                    content: serde_json::json!({
-                        "type": conversation_msg.message_type,
-                        "content": conversation_msg.content,
-                        "metadata": conversation_msg.metadata,
-                    }).to_string(),
-                    timestamp: conversation_msg.timestamp.to_rfc3339(),
-                };
+                let db_message = to_db_message(&conversation_msg);
                 
+                // Persist before handling the next message so messages are stored in order
                 let attempt_uuid = Uuid::parse_str(&attempt_id).unwrap();
                 let db_repo = db_repository.clone();
-                tauri::async_runtime::spawn(async move {
+                let save_result = tauri::async_runtime::block_on(async move {
                     use crate::repository::ConversationRepository;
                     let conversation_repo = ConversationRepository::new(&db_repo);
-                    let _ = conversation_repo.add_message(attempt_uuid, db_message).await;
+                    crate::db::retry_on_busy(|| conversation_repo.add_message(attempt_uuid, db_message.clone())).await
                 });
+                if let Err(e) = save_result {
+                    log::error!("Failed to save message for attempt {}: {}", attempt_id, e);
+                }
                 
                 // Emit message:added event
                 let _ = app_handle.emit("message:added", serde_json::json!({
@@ -204,6 +248,66 @@ impl CodingAgentExecutorService {
         });
     }
     
+    /// Project of an attempt's task. Several projects can share one checkout,
+    /// so the working directory doesn't tell.
+    async fn attempt_project_id(&self, attempt_id: &str) -> Option<String> {
+        let task_service = crate::services::TaskService::new(self.db_repository.pool().clone());
+        let attempt_uuid = Uuid::parse_str(attempt_id).ok()?;
+        let task_id = match task_service.get_task_attempt(attempt_uuid).await {
+            Ok(attempt) => attempt?.task_id,
+            Err(e) => {
+                log::warn!("Failed to resolve the project of attempt {}: {}", attempt_id, e);
+                return None;
+            }
+        };
+        let task_uuid = Uuid::parse_str(&task_id).ok()?;
+        task_service.get_task(task_uuid).await.ok().flatten().map(|task| task.project_id)
+    }
+    
+    /// Split the MCP servers of the project (its own and attached ones) and the
+    /// ones selected for the attempt into running and stopped ones, and add the
+    /// running global servers. Servers that were unregistered are detached, and
+    /// another project's servers are never used.
+    async fn resolve_mcp_servers(&self, project_id: Option<&str>, attempt_id: &str) -> (Vec<McpServer>, Vec<McpServer>) {
+        let project_service = crate::services::ProjectService::new(self.db_repository.pool().clone());
+        let mut server_ids = match project_id {
+            Some(project_id) => project_service.list_mcp_server_ids(project_id).await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+        
+        let task_service = crate::services::TaskService::new(self.db_repository.pool().clone());
+        if let Ok(attempt_uuid) = Uuid::parse_str(attempt_id) {
+            match task_service.get_task_attempt(attempt_uuid).await {
+                Ok(Some(attempt)) => {
+                    for id in attempt.mcp_server_ids {
+                        if !server_ids.contains(&id) {
+                            server_ids.push(id);
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to load MCP servers for attempt {}: {}", attempt_id, e),
+            }
+        }
+        
+        let Some(mcp_state) = self.app_handle.try_state::<crate::commands::mcp::McpState>() else {
+            return (Vec::new(), Vec::new());
+        };
+        
+        let (running, skipped, missing) = select_mcp_servers(
+            project_id,
+            server_ids,
+            mcp_state.manager.list_agent_servers(project_id),
+            |server_id| mcp_state.manager.get_server(server_id),
+        );
+        for server_id in missing {
+            log::info!("MCP server {} no longer exists, detaching it", server_id);
+            let _ = project_service.detach_mcp_server_everywhere(&server_id).await;
+        }
+        
+        (running, skipped)
+    }
+    
     async fn execute_prompt_internal(
         &self,
         prompt: &str,
@@ -230,6 +334,32 @@ impl CodingAgentExecutorService {
             working_directory: working_directory.to_string(),
             status: CodingAgentExecutionStatus::Starting,
             created_at: Utc::now(),
+            log_path: None,
+        };
+        
+        // Resolve the environment variables and MCP servers of the attempt's project
+        let project_id = self.attempt_project_id(attempt_id).await;
+        let env = match &project_id {
+            Some(project_id) => crate::services::ProjectService::new(self.db_repository.pool().clone())
+                .get_environment(project_id)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to load the environment of project {}: {}", project_id, e);
+                    Default::default()
+                }),
+            None => Default::default(),
+        };
+        
+        let (mcp_servers, skipped_mcp_servers) = self.resolve_mcp_servers(project_id.as_deref(), attempt_id).await;
+        
+        let model = match Uuid::parse_str(attempt_id) {
+            Ok(attempt_uuid) => crate::services::TaskService::new(self.db_repository.pool().clone())
+                .get_task_attempt(attempt_uuid)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|attempt| attempt.model),
+            Err(_) => None,
         };
         
         let execution_context = ExecutionContext {
@@ -238,6 +368,9 @@ impl CodingAgentExecutorService {
             attempt_id: attempt_id.to_string(),
             working_directory: working_directory.to_string(),
             resume_session_id,
+            env,
+            mcp_servers,
+            model,
         };
         
         info!("Executing prompt for task_id: {}, attempt_id: {}", task_id, attempt_id);
@@ -279,6 +412,20 @@ impl CodingAgentExecutorService {
             message: user_message,
         });
         
+        // Let the user know which attached MCP servers the agent won't see
+        for server in skipped_mcp_servers {
+            let _ = sender.send(ChannelMessage {
+                attempt_id: attempt_id.to_string(),
+                task_id: task_id.to_string(),
+                message: ConversationMessage::new(
+                    MessageRole::System,
+                    "error".to_string(),
+                    format!("MCP server \"{}\" is not running and was not attached to this session", server.name),
+                    Some(serde_json::json!({ "source": "mcp", "server_id": server.id })),
+                ),
+            });
+        }
+        
         // Get the appropriate agent
         let agent = self.agents.get(&agent_type)
             .ok_or_else(|| format!("Agent type {:?} not supported", agent_type))?;
@@ -314,6 +461,13 @@ impl CodingAgentExecutorService {
         
         // State updates are handled through conversation state sync
         
+        crate::services::TaskService::new(self.db_repository.pool().clone())
+            .record_attempt_event(attempt_id, TaskEventKind::ExecutionStarted {
+                execution_id: execution_id.clone(),
+                executor: format!("{:?}", agent_type),
+            })
+            .await;
+        
         Ok(final_execution)
     }
     
@@ -380,19 +534,98 @@ impl CodingAgentExecutorService {
             None,
         ).await
     }
-    
+
+    /// Re-send every user message of an earlier attempt, in order, as a single
+    /// prompt to a new attempt. The new attempt starts a fresh agent session.
+    pub async fn replay_attempt(&self, old_attempt_id: &str, new_attempt_id: &str) -> Result<CodingAgentExecution, String> {
+        use crate::repository::ConversationRepository;
+
+        let old_uuid = Uuid::parse_str(old_attempt_id).map_err(|e| e.to_string())?;
+        let new_uuid = Uuid::parse_str(new_attempt_id).map_err(|e| e.to_string())?;
+
+        let conversation = ConversationRepository::new(&self.db_repository)
+            .get_attempt_conversation(old_uuid)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("No conversation found for attempt {}", old_attempt_id))?;
+
+        // Messages are stored as {"type", "content", "metadata"}, the timestamp
+        // lives on the envelope and is left out of the replay
+        let user_messages: Vec<String> = conversation.messages
+            .iter()
+            .filter(|msg| msg.role == MessageRole::User.to_string())
+            .filter_map(|msg| {
+                let parsed = serde_json::from_str::<serde_json::Value>(&msg.content).ok();
+                let message_type = parsed.as_ref()
+                    .and_then(|v| v.get("type"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("text");
+                if message_type != "text" {
+                    return None;
+                }
+                let content = parsed.as_ref()
+                    .and_then(|v| v.get("content"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(&msg.content)
+                    .trim()
+                    .to_string();
+                (!content.is_empty()).then_some(content)
+            })
+            .collect();
+        if user_messages.is_empty() {
+            return Err(format!("Attempt {} has no user messages to replay", old_attempt_id));
+        }
+
+        let task_service = crate::services::TaskService::new(self.db_repository.pool().clone());
+        let attempt = task_service
+            .get_task_attempt(new_uuid)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Task attempt {} not found", new_attempt_id))?;
+
+        // Run in the project's subdirectory of the worktree, if it has one
+        let task_uuid = Uuid::parse_str(&attempt.task_id).map_err(|e| e.to_string())?;
+        let project = match task_service.get_task(task_uuid).await.map_err(|e| e.to_string())? {
+            Some(task) => {
+                let project_uuid = Uuid::parse_str(&task.project_id).map_err(|e| e.to_string())?;
+                crate::services::ProjectService::new(self.db_repository.pool().clone())
+                    .get_project(project_uuid)
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+            None => None,
+        };
+        let working_directory = match &project {
+            Some(project) => project.working_directory(&attempt.worktree_path),
+            None => attempt.worktree_path.clone(),
+        };
+
+        let prompt = format!("[REPLAY]\n\n{}", user_messages.join("\n\n---\n\n"));
+        info!("Replaying {} messages of attempt {} on attempt {}", user_messages.len(), old_attempt_id, new_attempt_id);
+
+        self.execute_prompt(
+            &prompt,
+            &attempt.task_id,
+            &attempt.id,
+            &working_directory,
+            CodingAgentType::from_executor(attempt.executor.as_deref()),
+            None,
+        ).await
+    }
+
     pub async fn stop_execution(&self, execution_id: &str) -> Result<(), String> {
         info!("Stopping execution: {}", execution_id);
         
-        let (agent_type, execution_context, attempt_id, task_id) = {
+        let (agent_type, execution_context, attempt_id, task_id, was_paused) = {
             let mut executions = self.executions.lock().unwrap();
             if let Some(mut process) = executions.remove(execution_id) {
                 let agent_type = process.execution.executor_type.clone();
                 let execution_context = process.execution_context.clone();
                 let attempt_id = process.execution_context.attempt_id.clone();
                 let task_id = process.execution_context.task_id.clone();
+                let was_paused = process.execution.status == CodingAgentExecutionStatus::Paused;
                 process.execution.status = CodingAgentExecutionStatus::Completed;
-                Some((agent_type, execution_context, attempt_id, task_id))
+                Some((agent_type, execution_context, attempt_id, task_id, was_paused))
             } else {
                 None
             }
@@ -400,9 +633,19 @@ impl CodingAgentExecutorService {
         
         // Get the appropriate agent
         if let Some(agent) = self.agents.get(&agent_type) {
+            // A suspended process can't act on the termination signal until it's continued
+            if was_paused {
+                if let Err(e) = agent.resume_execution(execution_id, &execution_context).await {
+                    log::warn!("Failed to continue paused execution {} before stopping it: {}", execution_id, e);
+                }
+            }
             agent.stop_execution(execution_id, &execution_context).await?;
         }
         
+        crate::services::TaskService::new(self.db_repository.pool().clone())
+            .record_attempt_event(&attempt_id, TaskEventKind::ExecutionStopped { execution_id: execution_id.to_string() })
+            .await;
+        
         // Emit execution:completed event
         let _ = self.app_handle.emit("execution:completed", serde_json::json!({
             "taskId": task_id,
@@ -414,12 +657,69 @@ impl CodingAgentExecutorService {
         Ok(())
     }
     
+    pub async fn pause_execution(&self, execution_id: &str) -> Result<CodingAgentExecution, String> {
+        info!("Pausing execution: {}", execution_id);
+        
+        let (agent_type, execution_context) = {
+            let executions = self.executions.lock().unwrap();
+            let process = executions.get(execution_id)
+                .ok_or_else(|| "Execution not found".to_string())?;
+            if process.execution.status != CodingAgentExecutionStatus::Running {
+                return Err("Only running executions can be paused".to_string());
+            }
+            (process.execution.executor_type.clone(), process.execution_context.clone())
+        };
+        
+        let agent = self.agents.get(&agent_type)
+            .ok_or_else(|| format!("Agent type {:?} not supported", agent_type))?;
+        agent.pause_execution(execution_id, &execution_context).await?;
+        
+        self.set_execution_status(execution_id, CodingAgentExecutionStatus::Paused)
+    }
+    
+    pub async fn resume_execution(&self, execution_id: &str) -> Result<CodingAgentExecution, String> {
+        info!("Resuming execution: {}", execution_id);
+        
+        let (agent_type, execution_context) = {
+            let executions = self.executions.lock().unwrap();
+            let process = executions.get(execution_id)
+                .ok_or_else(|| "Execution not found, the process may have exited while paused".to_string())?;
+            if process.execution.status != CodingAgentExecutionStatus::Paused {
+                return Err("Execution is not paused".to_string());
+            }
+            (process.execution.executor_type.clone(), process.execution_context.clone())
+        };
+        
+        let agent = self.agents.get(&agent_type)
+            .ok_or_else(|| format!("Agent type {:?} not supported", agent_type))?;
+        agent.resume_execution(execution_id, &execution_context).await?;
+        
+        self.set_execution_status(execution_id, CodingAgentExecutionStatus::Running)
+    }
+    
+    fn set_execution_status(
+        &self,
+        execution_id: &str,
+        status: CodingAgentExecutionStatus,
+    ) -> Result<CodingAgentExecution, String> {
+        let mut executions = self.executions.lock().unwrap();
+        let process = executions.get_mut(execution_id)
+            .ok_or_else(|| "Execution not found".to_string())?;
+        process.execution.status = status;
+        Ok(process.execution.clone())
+    }
+    
     // Query methods
     pub fn get_execution(&self, execution_id: &str) -> Option<CodingAgentExecution> {
         let executions = self.executions.lock().unwrap();
         executions.get(execution_id).map(|p| p.execution.clone())
     }
     
+    pub fn get_execution_attempt_id(&self, execution_id: &str) -> Option<String> {
+        let executions = self.executions.lock().unwrap();
+        executions.get(execution_id).map(|p| p.execution_context.attempt_id.clone())
+    }
+    
     pub fn list_executions(&self) -> Vec<CodingAgentExecution> {
         let executions = self.executions.lock().unwrap();
         executions.values().map(|p| p.execution.clone()).collect()
@@ -471,14 +771,125 @@ impl CodingAgentExecutorService {
             agent_type,
         }
     }
-    
-    
+
+    /// Build a timeline of the attempt's executions from its stored conversation.
+    /// Every user prompt starts an execution; since completion messages are not
+    /// persisted, an execution ends at its last message once the next prompt
+    /// arrives or the attempt is no longer running.
+    pub async fn get_execution_timeline(&self, attempt_id: &str) -> Result<Vec<TimelineEvent>, String> {
+        use crate::repository::ConversationRepository;
+
+        let attempt_uuid = Uuid::parse_str(attempt_id).map_err(|e| e.to_string())?;
+        let conversation = ConversationRepository::new(&self.db_repository)
+            .get_attempt_conversation(attempt_uuid)
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(conversation) = conversation else {
+            return Ok(Vec::new());
+        };
+
+        let mut timeline = Vec::new();
+        let mut execution_start: Option<DateTime<Utc>> = None;
+        let mut last_timestamp: Option<DateTime<Utc>> = None;
+        // Open tool calls as (tool_use_id, tool_name, started_at)
+        let mut open_tool_calls: Vec<(Option<String>, String, DateTime<Utc>)> = Vec::new();
+
+        for msg in conversation.messages {
+            let Ok(timestamp) = DateTime::parse_from_rfc3339(&msg.timestamp).map(|t| t.with_timezone(&Utc)) else {
+                continue;
+            };
+            let parsed = serde_json::from_str::<serde_json::Value>(&msg.content).ok();
+            let message_type = parsed.as_ref()
+                .and_then(|v| v.get("type"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("text")
+                .to_string();
+            let content = parsed.as_ref()
+                .and_then(|v| v.get("content"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(&msg.content)
+                .to_string();
+            let metadata = parsed.as_ref().and_then(|v| v.get("metadata"));
+            let tool_name = metadata
+                .and_then(|m| m.get("toolName"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let tool_use_id = metadata
+                .and_then(|m| m.get("toolUseId"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            if msg.role == "user" && message_type == "text" {
+                if let (Some(start), Some(end)) = (execution_start, last_timestamp) {
+                    timeline.push(execution_completed(start, end));
+                }
+                execution_start = Some(timestamp);
+                open_tool_calls.clear();
+                timeline.push(TimelineEvent {
+                    timestamp,
+                    event_type: TimelineEventType::ExecutionStarted,
+                    description: summarize(&content),
+                    duration_ms: None,
+                });
+            } else {
+                match message_type.as_str() {
+                    "tool_use" => {
+                        open_tool_calls.push((tool_use_id, tool_name.clone(), timestamp));
+                        timeline.push(TimelineEvent {
+                            timestamp,
+                            event_type: TimelineEventType::ToolCallStarted,
+                            description: tool_name,
+                            duration_ms: None,
+                        });
+                    }
+                    "tool_result" => {
+                        // Pair by tool use id, falling back to the oldest open call of the same tool
+                        let position = open_tool_calls.iter()
+                            .position(|(id, _, _)| tool_use_id.is_some() && *id == tool_use_id)
+                            .or_else(|| open_tool_calls.iter().position(|(_, name, _)| *name == tool_name));
+                        let duration_ms = position.map(|i| {
+                            let (_, _, started_at) = open_tool_calls.remove(i);
+                            duration_between(started_at, timestamp)
+                        });
+                        timeline.push(TimelineEvent {
+                            timestamp,
+                            event_type: TimelineEventType::ToolCallCompleted,
+                            description: tool_name,
+                            duration_ms,
+                        });
+                    }
+                    _ => {
+                        timeline.push(TimelineEvent {
+                            timestamp,
+                            event_type: TimelineEventType::MessageReceived,
+                            description: format!("{} {}: {}", msg.role, message_type, summarize(&content)),
+                            duration_ms: None,
+                        });
+                    }
+                }
+            }
+            last_timestamp = Some(timestamp);
+        }
+
+        if !self.is_attempt_active(attempt_id) {
+            if let (Some(start), Some(end)) = (execution_start, last_timestamp) {
+                timeline.push(execution_completed(start, end));
+            }
+        }
+
+        Ok(timeline)
+    }
+
     pub fn is_attempt_active(&self, attempt_id: &str) -> bool {
         let executions = self.executions.lock().unwrap();
         
         for (_exec_id, process) in executions.iter() {
             if process.execution_context.attempt_id == attempt_id {
-                return matches!(process.execution.status, CodingAgentExecutionStatus::Running | CodingAgentExecutionStatus::Starting);
+                return matches!(
+                    process.execution.status,
+                    CodingAgentExecutionStatus::Running | CodingAgentExecutionStatus::Starting | CodingAgentExecutionStatus::Paused
+                );
             }
         }
         
@@ -510,6 +921,134 @@ impl CodingAgentExecutorService {
     }
 }
 
+fn execution_completed(start: DateTime<Utc>, end: DateTime<Utc>) -> TimelineEvent {
+    TimelineEvent {
+        timestamp: end,
+        event_type: TimelineEventType::ExecutionCompleted,
+        description: "Execution completed".to_string(),
+        duration_ms: Some(duration_between(start, end)),
+    }
+}
+
+fn duration_between(start: DateTime<Utc>, end: DateTime<Utc>) -> u64 {
+    (end - start).num_milliseconds().max(0) as u64
+}
+
+// First line of a message, cut to a readable length for the timeline
+fn summarize(content: &str) -> String {
+    let first_line = content.lines().next().unwrap_or("").trim();
+    if first_line.chars().count() > 80 {
+        format!("{}…", first_line.chars().take(80).collect::<String>())
+    } else {
+        first_line.to_string()
+    }
+}
+
+// Stored form of a conversation message, with the type and metadata encoded in the content
+fn to_db_message(message: &ConversationMessage) -> crate::models::ConversationMessage {
+    crate::models::ConversationMessage {
+        role: match message.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+        }.to_string(),
+        content: serde_json::json!({
+            "type": message.message_type,
+            "content": message.content,
+            "metadata": message.metadata,
+        }).to_string(),
+        timestamp: message.timestamp.to_rfc3339(),
+    }
+}
+
+/// Length of the agent's final text kept in an auto-commit message
+const AUTO_COMMIT_SUMMARY_CHARS: usize = 200;
+
+fn auto_commit_message(title: &str, summary: Option<&str>) -> String {
+    let mut message = format!("task: {}", title.trim());
+    if let Some(summary) = summary.map(str::trim).filter(|s| !s.is_empty()) {
+        message.push_str("\n\n");
+        message.extend(summary.chars().take(AUTO_COMMIT_SUMMARY_CHARS));
+    }
+    message
+}
+
+/// Commit what an execution left in the attempt's worktree when its project
+/// has auto-commit enabled. A failed commit is posted to the conversation.
+async fn auto_commit_attempt(
+    db_repository: &crate::repository::DatabaseRepository,
+    app_handle: &AppHandle,
+    task_id: &str,
+    attempt_id: &str,
+    summary: Option<&str>,
+) {
+    use crate::services::git_service::GitService;
+    use crate::services::{ProjectService, TaskService};
+
+    let (Ok(task_uuid), Ok(attempt_uuid)) = (Uuid::parse_str(task_id), Uuid::parse_str(attempt_id)) else {
+        return;
+    };
+    let task_service = TaskService::new(db_repository.pool().clone());
+    let (task, attempt) = match (task_service.get_task(task_uuid).await, task_service.get_task_attempt(attempt_uuid).await) {
+        (Ok(Some(task)), Ok(Some(attempt))) => (task, attempt),
+        _ => return,
+    };
+
+    let project_service = ProjectService::new(db_repository.pool().clone());
+    let auto_commit = match Uuid::parse_str(&task.project_id) {
+        Ok(project_uuid) => matches!(project_service.get_project(project_uuid).await, Ok(Some(project)) if project.git_enabled && project.auto_commit),
+        Err(_) => false,
+    };
+    if !auto_commit {
+        return;
+    }
+
+    let worktree = std::path::Path::new(&attempt.worktree_path);
+    let result = match GitService::new().get_status(worktree) {
+        Ok(status) if status.is_clean() => return,
+        Ok(_) => {
+            let message = auto_commit_message(&task.title, summary);
+            GitService::commit_all(worktree, &message).map(|hash| (hash, message))
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((commit_hash, message)) => {
+            info!("Auto-committed {} for attempt {}", commit_hash, attempt_id);
+            if let Err(e) = task_service.record_attempt_commit(attempt_uuid, &commit_hash, &message).await {
+                log::error!("Failed to record commit {} for attempt {}: {}", commit_hash, attempt_id, e);
+            }
+            let _ = app_handle.emit("attempt:committed", serde_json::json!({
+                "taskId": task_id,
+                "attemptId": attempt_id,
+                "commitHash": commit_hash,
+                "message": message,
+            }));
+        }
+        Err(e) => {
+            log::warn!("Auto-commit failed for attempt {}: {}", attempt_id, e);
+            let notice = ConversationMessage::new(
+                MessageRole::System,
+                "error".to_string(),
+                format!("Auto-commit failed: {}", e.trim()),
+                Some(serde_json::json!({ "source": "auto_commit" })),
+            );
+
+            let conversation_repo = crate::repository::ConversationRepository::new(db_repository);
+            let db_message = to_db_message(&notice);
+            if let Err(e) = crate::db::retry_on_busy(|| conversation_repo.add_message(attempt_uuid, db_message.clone())).await {
+                log::error!("Failed to save message for attempt {}: {}", attempt_id, e);
+            }
+            let _ = app_handle.emit("message:added", serde_json::json!({
+                "taskId": task_id,
+                "attemptId": attempt_id,
+                "message": notice,
+            }));
+        }
+    }
+}
+
 // Convert AgentOutput to ConversationMessage
 pub fn convert_to_conversation_message(agent_output: &AgentOutput) -> Option<ConversationMessage> {
     
@@ -566,4 +1105,93 @@ pub fn convert_to_conversation_message(agent_output: &AgentOutput) -> Option<Con
     };
     msg.id = msg.generate_id();
     Some(msg)
-}
\ No newline at end of file
+}
+
+/// Split the selected servers and the project's own into running and stopped
+/// ones, add the running global servers, and return the selected ids that are
+/// no longer registered. Servers of another project are dropped, wherever
+/// they were selected.
+fn select_mcp_servers(
+    project_id: Option<&str>,
+    mut server_ids: Vec<String>,
+    agent_servers: Vec<McpServer>,
+    get_server: impl Fn(&str) -> Option<McpServer>,
+) -> (Vec<McpServer>, Vec<McpServer>, Vec<String>) {
+    // Global servers are only used while they run, the project's own like attached ones
+    let mut running = Vec::new();
+    for server in agent_servers {
+        if server_ids.contains(&server.id) || !server.is_available_to(project_id) {
+            continue;
+        }
+        match server.project_id {
+            Some(_) => server_ids.push(server.id),
+            None if server.status == McpServerStatus::Running => running.push(server),
+            None => {}
+        }
+    }
+    
+    let mut skipped = Vec::new();
+    let mut missing = Vec::new();
+    for server_id in server_ids {
+        match get_server(&server_id) {
+            Some(server) if !server.is_available_to(project_id) => {
+                log::warn!("MCP server {} belongs to another project, skipping it", server.name);
+            }
+            Some(server) if server.status == McpServerStatus::Running => running.push(server),
+            Some(server) => {
+                log::warn!("MCP server {} is not running, skipping it", server.name);
+                skipped.push(server);
+            }
+            None => missing.push(server_id),
+        }
+    }
+    
+    (running, skipped, missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mcp_server::NewMcpServer;
+
+    fn running_server(name: &str, project_id: Option<&str>) -> McpServer {
+        let mut server = NewMcpServer {
+            name: name.to_string(),
+            command: "mcp-server".to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            auto_restart: false,
+            health_check_interval_seconds: None,
+            request_timeout_seconds: None,
+            log_lines: None,
+        }
+        .into_server(project_id.map(str::to_string));
+        server.status = McpServerStatus::Running;
+        server
+    }
+
+    #[test]
+    fn another_projects_server_is_not_resolved_even_when_attached() {
+        let global = running_server("global", None);
+        let own = running_server("own", Some("project-a"));
+        let foreign = running_server("foreign", Some("project-b"));
+        let registered: HashMap<String, McpServer> = [&global, &own, &foreign]
+            .into_iter()
+            .map(|server| (server.id.clone(), server.clone()))
+            .collect();
+
+        // Attached to the project or selected for the attempt before the check existed
+        let (running, skipped, missing) = select_mcp_servers(
+            Some("project-a"),
+            vec![foreign.id.clone()],
+            vec![global.clone(), own.clone()],
+            |server_id| registered.get(server_id).cloned(),
+        );
+
+        let mut names: Vec<&str> = running.iter().map(|server| server.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["global", "own"]);
+        assert!(skipped.is_empty());
+        assert!(missing.is_empty());
+    }
+}
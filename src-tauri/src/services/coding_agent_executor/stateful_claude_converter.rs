@@ -11,6 +11,8 @@ pub struct StatefulClaudeMessageConverter {
     tool_map: Arc<Mutex<HashMap<String, String>>>,
     /// Inner converter for basic conversion
     inner_converter: ClaudeMessageConverter,
+    /// Cost reported by `result` messages, summed over the execution
+    total_cost_usd: Arc<Mutex<Option<f64>>>,
 }
 
 impl StatefulClaudeMessageConverter {
@@ -18,9 +20,15 @@ impl StatefulClaudeMessageConverter {
         Self {
             tool_map: Arc::new(Mutex::new(HashMap::new())),
             inner_converter: ClaudeMessageConverter,
+            total_cost_usd: Arc::new(Mutex::new(None)),
         }
     }
     
+    /// Total API cost seen so far, None if no result reported a cost
+    pub fn total_cost_usd(&self) -> Option<f64> {
+        self.total_cost_usd.lock().ok().and_then(|total| *total)
+    }
+    
     pub fn convert_to_unified(&self, raw_message: &str) -> Option<AgentOutput> {
         // Parse the Claude JSON message
         let json: Value = serde_json::from_str(raw_message).ok()?;
@@ -81,6 +89,15 @@ impl StatefulClaudeMessageConverter {
                 }
             }
             
+            Some("result") => {
+                // Older CLI versions report `cost_usd`, newer ones `total_cost_usd`
+                if let Some(cost) = json["total_cost_usd"].as_f64().or_else(|| json["cost_usd"].as_f64()) {
+                    if let Ok(mut total) = self.total_cost_usd.lock() {
+                        *total = Some(total.unwrap_or(0.0) + cost);
+                    }
+                }
+            }
+            
             _ => {}
         }
         
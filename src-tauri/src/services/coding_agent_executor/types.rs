@@ -9,6 +9,8 @@ pub struct CodingAgentExecution {
     pub working_directory: String,
     pub status: CodingAgentExecutionStatus,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub log_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -18,10 +20,21 @@ pub enum CodingAgentType {
     GeminiCli,
 }
 
+impl CodingAgentType {
+    /// Agent for an attempt's `executor` column, Claude when unset or unknown
+    pub fn from_executor(executor: Option<&str>) -> Self {
+        match executor {
+            Some("gemini") | Some("gemini_cli") | Some("GeminiCli") => CodingAgentType::GeminiCli,
+            _ => CodingAgentType::ClaudeCode,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CodingAgentExecutionStatus {
     Starting,
     Running,
+    Paused,
     Completed,
     Error(String),
 }
@@ -61,6 +74,25 @@ pub struct TaskExecutionSummary {
     pub agent_type: Option<CodingAgentType>,  // 当前运行的 agent 类型
 }
 
+// Attempt 的执行时间线（用于调试）
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct TimelineEvent {
+    pub timestamp: DateTime<Utc>,
+    pub event_type: TimelineEventType,
+    pub description: String,
+    pub duration_ms: Option<u64>,  // 仅结束类事件：与对应开始事件的间隔
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventType {
+    ExecutionStarted,
+    MessageReceived,
+    ToolCallStarted,
+    ToolCallCompleted,
+    ExecutionCompleted,
+}
+
 // Message-related structures
 // 统一的对话消息格式（用于前后端通信和数据库存储）
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -1,9 +1,11 @@
-use crate::models::{AppConfig, GitLabConfig, GitHubConfig};
+use crate::models::{AppConfig, BackupConfig, DevServerConfig, ExecutionConfig, FileWatcherConfig, GitHostsConfig, GitLabConfig, GitHubConfig, NetworkConfig, NotificationConfig, StorageConfig, WindowConfig};
+use crate::services::VcsSyncConfig;
 use sqlx::SqlitePool;
 
 pub struct ConfigService {
     pool: SqlitePool,
     config: AppConfig,
+    vcs_sync_config: VcsSyncConfig,
 }
 
 impl ConfigService {
@@ -13,6 +15,7 @@ impl ConfigService {
         Self {
             pool,
             config: AppConfig::default(),
+            vcs_sync_config: VcsSyncConfig::default(),
         }
     }
     
@@ -38,6 +41,126 @@ impl ConfigService {
         Ok(())
     }
     
+    pub fn get_execution_config(&self) -> &ExecutionConfig {
+        &self.config.execution
+    }
+    
+    pub async fn update_execution_config(&mut self, execution_config: ExecutionConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.execution = execution_config;
+        self.save_to_db("execution_config", &serde_json::to_string(&self.config.execution)?).await?;
+        crate::services::coding_agent_executor::raw_log::set_execution_log_dir(
+            self.config.execution.execution_log_dir.as_deref(),
+        );
+        Ok(())
+    }
+    
+    pub fn get_network_config(&self) -> &NetworkConfig {
+        &self.config.network
+    }
+    
+    /// Route outgoing HTTP traffic through `proxy_url`, `None` connects directly.
+    /// Proxies from the environment (`HTTPS_PROXY`, `NO_PROXY`) still take precedence.
+    pub async fn set_proxy(&mut self, proxy_url: Option<String>, no_proxy: Vec<String>) -> Result<(), String> {
+        let proxy_url = proxy_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+        if let Some(url) = proxy_url.as_deref() {
+            reqwest::Proxy::all(url).map_err(|e| format!("Invalid proxy URL {}: {}", url, e))?;
+        }
+        
+        self.config.network = NetworkConfig {
+            proxy_url,
+            proxy_no_proxy: no_proxy
+                .into_iter()
+                .map(|host| host.trim().to_string())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        };
+        crate::utils::http::set_network_config(self.config.network.clone());
+        
+        let value = serde_json::to_string(&self.config.network).map_err(|e| e.to_string())?;
+        self.save_to_db("network_config", &value).await.map_err(|e| e.to_string())
+    }
+    
+    pub fn get_window_config(&self) -> &WindowConfig {
+        &self.config.windows
+    }
+    
+    pub async fn update_window_config(&mut self, window_config: WindowConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.windows = window_config;
+        self.save_to_db("window_config", &serde_json::to_string(&self.config.windows)?).await?;
+        Ok(())
+    }
+    
+    pub fn get_backup_config(&self) -> &BackupConfig {
+        &self.config.backups
+    }
+    
+    pub async fn update_backup_config(&mut self, backup_config: BackupConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.backups = backup_config;
+        self.save_to_db("backup_config", &serde_json::to_string(&self.config.backups)?).await?;
+        Ok(())
+    }
+    
+    pub fn get_dev_server_config(&self) -> &DevServerConfig {
+        &self.config.dev_server
+    }
+    
+    pub async fn update_dev_server_config(&mut self, dev_server_config: DevServerConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.dev_server = dev_server_config;
+        self.save_to_db("dev_server_config", &serde_json::to_string(&self.config.dev_server)?).await?;
+        Ok(())
+    }
+    
+    pub fn get_storage_config(&self) -> &StorageConfig {
+        &self.config.storage
+    }
+    
+    pub async fn update_storage_config(&mut self, storage_config: StorageConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.storage = storage_config;
+        self.save_to_db("storage_config", &serde_json::to_string(&self.config.storage)?).await?;
+        Ok(())
+    }
+    
+    pub fn get_file_watcher_config(&self) -> &FileWatcherConfig {
+        &self.config.file_watcher
+    }
+    
+    pub async fn update_file_watcher_config(&mut self, file_watcher_config: FileWatcherConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.file_watcher = file_watcher_config;
+        self.save_to_db("file_watcher_config", &serde_json::to_string(&self.config.file_watcher)?).await?;
+        Ok(())
+    }
+    
+    pub fn get_notification_config(&self) -> &NotificationConfig {
+        &self.config.notifications
+    }
+    
+    pub async fn update_notification_config(&mut self, notification_config: NotificationConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.notifications = notification_config;
+        self.save_to_db("notification_config", &serde_json::to_string(&self.config.notifications)?).await?;
+        Ok(())
+    }
+    
+    pub fn get_git_hosts_config(&self) -> &GitHostsConfig {
+        &self.config.git_hosts
+    }
+    
+    pub async fn update_git_hosts_config(&mut self, git_hosts_config: GitHostsConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.git_hosts = git_hosts_config;
+        crate::models::set_git_host_mappings(self.config.git_hosts.hosts.clone());
+        self.save_to_db("git_hosts_config", &serde_json::to_string(&self.config.git_hosts)?).await?;
+        Ok(())
+    }
+    
+    pub fn get_vcs_sync_config(&self) -> &VcsSyncConfig {
+        &self.vcs_sync_config
+    }
+    
+    pub async fn update_vcs_sync_config(&mut self, vcs_sync_config: VcsSyncConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.vcs_sync_config = vcs_sync_config;
+        self.save_to_db("vcs_sync_config", &serde_json::to_string(&self.vcs_sync_config)?).await?;
+        Ok(())
+    }
+    
     async fn save_to_db(&self, key: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
         let mut conn = self.pool.acquire().await?;
         
@@ -79,6 +202,131 @@ impl ConfigService {
             }
         }
         
+        // Load execution settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'execution_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(execution_config) = serde_json::from_str::<ExecutionConfig>(&row.0) {
+                self.config.execution = execution_config;
+                crate::services::coding_agent_executor::raw_log::set_execution_log_dir(
+                    self.config.execution.execution_log_dir.as_deref(),
+                );
+            }
+        }
+        
+        // Load network settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'network_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(network_config) = serde_json::from_str::<NetworkConfig>(&row.0) {
+                self.config.network = network_config;
+                crate::utils::http::set_network_config(self.config.network.clone());
+            }
+        }
+        
+        // Load window settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'window_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(window_config) = serde_json::from_str::<WindowConfig>(&row.0) {
+                self.config.windows = window_config;
+            }
+        }
+        
+        // Load backup settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'backup_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(backup_config) = serde_json::from_str::<BackupConfig>(&row.0) {
+                self.config.backups = backup_config;
+            }
+        }
+        
+        // Load dev server settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'dev_server_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(dev_server_config) = serde_json::from_str::<DevServerConfig>(&row.0) {
+                self.config.dev_server = dev_server_config;
+            }
+        }
+        
+        // Load storage settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'storage_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(storage_config) = serde_json::from_str::<StorageConfig>(&row.0) {
+                self.config.storage = storage_config;
+            }
+        }
+        
+        // Load file watcher settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'file_watcher_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(file_watcher_config) = serde_json::from_str::<FileWatcherConfig>(&row.0) {
+                self.config.file_watcher = file_watcher_config;
+            }
+        }
+        
+        // Load notification settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'notification_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(notification_config) = serde_json::from_str::<NotificationConfig>(&row.0) {
+                self.config.notifications = notification_config;
+            }
+        }
+        
+        // Load git host mappings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'git_hosts_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(git_hosts_config) = serde_json::from_str::<GitHostsConfig>(&row.0) {
+                self.config.git_hosts = git_hosts_config;
+                crate::models::set_git_host_mappings(self.config.git_hosts.hosts.clone());
+            }
+        }
+        
+        // Load VCS sync settings
+        if let Ok(row) = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM app_config WHERE key = 'vcs_sync_config'"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            if let Ok(vcs_sync_config) = serde_json::from_str::<VcsSyncConfig>(&row.0) {
+                self.vcs_sync_config = vcs_sync_config;
+            }
+        }
+        
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,20 +1,131 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
 use notify::{Watcher, RecursiveMode, Event, EventKind};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use serde::{Serialize, Deserialize};
 use tokio::sync::mpsc;
+use crate::models::FileWatcherConfig;
+use crate::services::ConfigService;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileChangeEvent {
-    pub worktree_path: String,
+pub struct FileChange {
     pub file_path: String,
     pub kind: String,
 }
 
+/// Changes in a worktree collected over one debounce window. A path changed
+/// several times in the window is listed once, with its last kind of change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeFilesChangedEvent {
+    pub worktree_path: String,
+    pub changes: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFilesChangedEvent {
+    pub project_id: String,
+    pub changed_files: Vec<String>,
+    pub event_type: String,
+}
+
+/// Decides which changed paths under a watched directory are reported
+struct PathFilter {
+    root: PathBuf,
+    patterns: Vec<glob::Pattern>,
+    /// The repository the directory belongs to, when .gitignore is honored
+    repo: Option<git2::Repository>,
+}
+
+impl PathFilter {
+    fn new(root: &Path, config: &FileWatcherConfig) -> Self {
+        let patterns = config.ignore_patterns
+            .iter()
+            .filter_map(|pattern| match glob::Pattern::new(pattern) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => {
+                    log::warn!("Ignoring invalid file watcher pattern {}: {}", pattern, e);
+                    None
+                }
+            })
+            .collect();
+        let repo = if config.respect_gitignore {
+            git2::Repository::discover(root).ok().filter(|repo| repo.workdir().is_some())
+        } else {
+            None
+        };
+
+        Self { root: root.to_path_buf(), patterns, repo }
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        // Events may name the path differently than it was watched, e.g. resolved symlinks
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if relative.components().any(|c| c.as_os_str() == ".git") {
+            return true;
+        }
+
+        let matches_pattern = self.patterns.iter().any(|pattern| {
+            pattern.matches_path(relative)
+                || relative.components().any(|c| pattern.matches(&c.as_os_str().to_string_lossy()))
+        });
+        if matches_pattern {
+            return true;
+        }
+
+        // Ignore rules are looked up relative to the repository's working directory
+        self.repo.as_ref().is_some_and(|repo| {
+            repo.workdir()
+                .and_then(|workdir| path.strip_prefix(workdir).ok())
+                .is_some_and(|in_repo| repo.is_path_ignored(in_repo).unwrap_or(false))
+        })
+    }
+}
+
+/// Longest a batch is held back while changes keep coming, e.g. during a build
+const MAX_BATCH_WAIT: Duration = Duration::from_secs(2);
+
+/// Wait for the first event, then hand events to `collect` until the debounce
+/// window is quiet or `MAX_BATCH_WAIT` has passed since the first one. False
+/// once the watcher is gone.
+async fn collect_batch(rx: &mut mpsc::Receiver<Event>, debounce: Duration, mut collect: impl FnMut(Event)) -> bool {
+    let Some(first) = rx.recv().await else {
+        return false;
+    };
+    collect(first);
+
+    let deadline = tokio::time::Instant::now() + MAX_BATCH_WAIT.max(debounce);
+    loop {
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        match tokio::time::timeout(debounce.min(deadline - now), rx.recv()).await {
+            Ok(Some(event)) => collect(event),
+            // Report what was collected; the next call sees the closed channel
+            Ok(None) | Err(_) => return true,
+        }
+    }
+}
+
+/// One OS watch on a worktree, shared by everyone who asked to watch it
+struct WorktreeWatch {
+    _watcher: notify::RecommendedWatcher,
+    subscribers: usize,
+}
+
+/// The same worktree may be named with or without a trailing slash
+fn worktree_key(worktree_path: &str) -> String {
+    let trimmed = worktree_path.trim_end_matches('/');
+    if trimmed.is_empty() { worktree_path } else { trimmed }.to_string()
+}
+
 pub struct FileWatcherService {
-    watchers: Arc<Mutex<HashMap<String, notify::RecommendedWatcher>>>,
+    // Key: worktree path as returned by `worktree_key`
+    watchers: Arc<Mutex<HashMap<String, WorktreeWatch>>>,
+    // Key: project_id
+    project_watchers: Arc<Mutex<HashMap<String, notify::RecommendedWatcher>>>,
     app_handle: AppHandle,
 }
 
@@ -22,16 +133,46 @@ impl FileWatcherService {
     pub fn new(app_handle: AppHandle) -> Self {
         Self {
             watchers: Arc::new(Mutex::new(HashMap::new())),
+            project_watchers: Arc::new(Mutex::new(HashMap::new())),
             app_handle,
         }
     }
 
+    /// The file watcher settings at the time a directory starts being watched
+    async fn watcher_config(&self) -> FileWatcherConfig {
+        self.app_handle
+            .state::<Arc<tokio::sync::Mutex<ConfigService>>>()
+            .lock()
+            .await
+            .get_file_watcher_config()
+            .clone()
+    }
+
+    fn check_watch_limit(watched: usize, config: &FileWatcherConfig) -> Result<(), String> {
+        if watched >= config.max_watched_directories {
+            return Err(format!(
+                "Already watching {} directories, the most the file watching settings allow. Close some task details or raise the limit.",
+                watched
+            ));
+        }
+        Ok(())
+    }
+
+    /// Watch a worktree and emit a `worktree-files-changed` event per debounce
+    /// window with the paths that changed in it. Watching a worktree that is
+    /// already watched only adds a subscriber; each call needs a matching
+    /// `unwatch_worktree`.
     pub async fn watch_worktree(&self, worktree_path: String) -> Result<(), String> {
+        let config = self.watcher_config().await;
+        let worktree_path = worktree_key(&worktree_path);
         let mut watchers = self.watchers.lock().unwrap();
         
-        if watchers.contains_key(&worktree_path) {
+        if let Some(watch) = watchers.get_mut(&worktree_path) {
+            watch.subscribers += 1;
             return Ok(());
         }
+        let watched = watchers.len() + self.project_watchers.lock().unwrap().len();
+        Self::check_watch_limit(watched, &config)?;
 
         let (tx, mut rx) = mpsc::channel(100);
         let app_handle = self.app_handle.clone();
@@ -46,56 +187,154 @@ impl FileWatcherService {
         watcher.watch(Path::new(&worktree_path), RecursiveMode::Recursive)
             .map_err(|e| format!("Failed to watch path: {}", e))?;
 
-        watchers.insert(worktree_path.clone(), watcher);
+        watchers.insert(worktree_path.clone(), WorktreeWatch { _watcher: watcher, subscribers: 1 });
 
-        tokio::spawn(async move {
-            while let Some(event) = rx.recv().await {
-                let kind = match event.kind {
-                    EventKind::Create(_) => "create",
-                    EventKind::Modify(_) => "modify",
-                    EventKind::Remove(_) => "remove",
-                    _ => "other",
-                };
+        let debounce = Duration::from_millis(config.debounce_ms);
+        let mut filter = PathFilter::new(Path::new(&worktree_path), &config);
 
-                for path in event.paths {
-                    if let Some(file_path) = path.to_str() {
-                        if !should_ignore_path(file_path) {
-                            let file_change_event = FileChangeEvent {
-                                worktree_path: worktree_path_clone.clone(),
-                                file_path: file_path.to_string(),
-                                kind: kind.to_string(),
-                            };
+        tokio::spawn(async move {
+            loop {
+                // Ignored paths are dropped as they arrive, so a batch only grows with
+                // reported paths. The closure owns `&mut` borrows as the repository isn't Sync
+                let mut changes: BTreeMap<String, &'static str> = BTreeMap::new();
+                let (filter, batch) = (&mut filter, &mut changes);
+                let open = collect_batch(&mut rx, debounce, move |event| {
+                    let kind = match event.kind {
+                        EventKind::Create(_) => "create",
+                        EventKind::Modify(_) => "modify",
+                        EventKind::Remove(_) => "remove",
+                        _ => "other",
+                    };
 
-                            let _ = app_handle.emit("file-change", &file_change_event);
+                    for path in event.paths {
+                        if filter.is_ignored(&path) {
+                            continue;
+                        }
+                        if let Some(file_path) = path.to_str() {
+                            batch.insert(file_path.to_string(), kind);
                         }
                     }
+                }).await;
+                if !open {
+                    break;
+                }
+
+                if changes.is_empty() {
+                    continue;
                 }
+                let event = WorktreeFilesChangedEvent {
+                    worktree_path: worktree_path_clone.clone(),
+                    changes: changes
+                        .into_iter()
+                        .map(|(file_path, kind)| FileChange { file_path, kind: kind.to_string() })
+                        .collect(),
+                };
+                let _ = app_handle.emit("worktree-files-changed", &event);
             }
         });
 
         Ok(())
     }
 
+    /// Drop one subscriber of a worktree watch. The OS watch goes away with the last one.
     pub async fn unwatch_worktree(&self, worktree_path: String) -> Result<(), String> {
+        let worktree_path = worktree_key(&worktree_path);
         let mut watchers = self.watchers.lock().unwrap();
-        watchers.remove(&worktree_path);
+        if let Some(watch) = watchers.get_mut(&worktree_path) {
+            watch.subscribers = watch.subscribers.saturating_sub(1);
+            if watch.subscribers == 0 {
+                watchers.remove(&worktree_path);
+            }
+        }
         Ok(())
     }
 
+    /// Stop watching every worktree and project root, whoever subscribed to them
     pub async fn unwatch_all(&self) -> Result<(), String> {
-        let mut watchers = self.watchers.lock().unwrap();
-        watchers.clear();
+        let worktrees = std::mem::take(&mut *self.watchers.lock().unwrap());
+        let projects = std::mem::take(&mut *self.project_watchers.lock().unwrap());
+        log::info!("Stopped watching {} worktrees and {} project roots", worktrees.len(), projects.len());
+        Ok(())
+    }
+
+    /// Watch a project root and emit debounced `project-files-changed` events
+    pub async fn watch_project_root(&self, project_id: String, project_path: String) -> Result<(), String> {
+        let config = self.watcher_config().await;
+        // Locked one after the other, watch_worktree holds the worktree lock while taking this one
+        let watched_worktrees = self.watchers.lock().unwrap().len();
+        let mut project_watchers = self.project_watchers.lock().unwrap();
+
+        if project_watchers.contains_key(&project_id) {
+            return Ok(());
+        }
+        Self::check_watch_limit(watched_worktrees + project_watchers.len(), &config)?;
+
+        let (tx, mut rx) = mpsc::channel(100);
+        let app_handle = self.app_handle.clone();
+        let project_id_clone = project_id.clone();
+
+        let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let _ = tx.blocking_send(event);
+            }
+        }).map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+        watcher.watch(Path::new(&project_path), RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch path: {}", e))?;
+
+        project_watchers.insert(project_id.clone(), watcher);
+
+        let debounce = Duration::from_millis(config.debounce_ms);
+        let mut filter = PathFilter::new(Path::new(&project_path), &config);
+
+        tokio::spawn(async move {
+            loop {
+                let mut pending: HashMap<&'static str, BTreeSet<String>> = HashMap::new();
+                let (filter, batch) = (&mut filter, &mut pending);
+                let open = collect_batch(&mut rx, debounce, move |event| {
+                    collect_project_event(batch, filter, event);
+                }).await;
+                if !open {
+                    break;
+                }
+
+                for (event_type, files) in pending {
+                    let event = ProjectFilesChangedEvent {
+                        project_id: project_id_clone.clone(),
+                        changed_files: files.into_iter().collect(),
+                        event_type: event_type.to_string(),
+                    };
+                    let _ = app_handle.emit("project-files-changed", &event);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn unwatch_project_root(&self, project_id: String) -> Result<(), String> {
+        let mut project_watchers = self.project_watchers.lock().unwrap();
+        project_watchers.remove(&project_id);
         Ok(())
     }
 }
 
-fn should_ignore_path(path: &str) -> bool {
-    path.contains("/.git/") || 
-    path.contains("/node_modules/") ||
-    path.contains("/target/") ||
-    path.contains("/.DS_Store") ||
-    path.ends_with(".swp") ||
-    path.ends_with(".tmp")
+fn collect_project_event(pending: &mut HashMap<&'static str, BTreeSet<String>>, filter: &PathFilter, event: Event) {
+    let event_type = match event.kind {
+        EventKind::Create(_) => "created",
+        EventKind::Modify(_) => "modified",
+        EventKind::Remove(_) => "deleted",
+        _ => return,
+    };
+
+    for path in event.paths {
+        if filter.is_ignored(&path) {
+            continue;
+        }
+        if let Some(file_path) = path.to_str() {
+            pending.entry(event_type).or_default().insert(file_path.to_string());
+        }
+    }
 }
 
 #[tauri::command]
@@ -119,4 +358,33 @@ pub async fn unwatch_all(
     state: tauri::State<'_, Arc<FileWatcherService>>,
 ) -> Result<(), String> {
     state.unwatch_all().await
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn continuous_changes_are_reported_before_they_stop() {
+        let (tx, mut rx) = mpsc::channel(100);
+        let writer = tokio::spawn(async move {
+            // Writes every 20ms, well inside the debounce window, for longer than MAX_BATCH_WAIT
+            let stop = tokio::time::Instant::now() + MAX_BATCH_WAIT * 3;
+            while tokio::time::Instant::now() < stop {
+                let event = Event::new(EventKind::Modify(notify::event::ModifyKind::Any)).add_path(PathBuf::from("/repo/out.log"));
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        });
+
+        let started = tokio::time::Instant::now();
+        let mut collected = 0;
+        assert!(collect_batch(&mut rx, Duration::from_millis(100), |_| collected += 1).await);
+        assert!(collected > 1);
+        assert!(started.elapsed() < MAX_BATCH_WAIT * 2, "batch took {:?}", started.elapsed());
+
+        writer.abort();
+    }
+}
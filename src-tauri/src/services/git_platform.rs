@@ -1,5 +1,93 @@
 use async_trait::async_trait;
-use crate::models::{MergeRequestInfo, GitRemoteInfo};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::path::Path;
+use crate::error::PivoError;
+use crate::models::{GitProvider, MergeRequestInfo, MergeRequestState, GitRemoteInfo, MergeMethod, MergeRequestComment};
+use crate::services::git_service::GitService;
+use crate::utils::command::execute_git;
+
+/// Longest we are willing to sleep waiting for a rate limit window to reset
+const MAX_RATE_LIMIT_WAIT_SECS: i64 = 60;
+
+/// Number of times a rate-limited request is retried before giving up
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+fn header_i64(headers: &HeaderMap, name: &str) -> Option<i64> {
+    headers.get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Check whether `response` was rejected by a rate limit and, if so, sleep
+/// until it is worth retrying. Returns `Ok(true)` when the caller should
+/// resend the request and `Ok(false)` when the response was not rate limited.
+pub(crate) async fn wait_for_rate_limit_retry(
+    response: &reqwest::Response,
+    remaining_header: &str,
+    reset_header: &str,
+    attempt: u32,
+) -> Result<bool, String> {
+    let status = response.status();
+    let headers = response.headers();
+    
+    // GitHub reports primary and secondary limits as 403, GitLab uses 429
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN
+            && (headers.contains_key(RETRY_AFTER) || header_i64(headers, remaining_header) == Some(0)));
+    if !rate_limited {
+        return Ok(false);
+    }
+    
+    if attempt >= MAX_RATE_LIMIT_RETRIES {
+        return Err(format!("API rate limit exceeded (HTTP {}), gave up after {} retries", status, attempt));
+    }
+    
+    let wait_secs = header_i64(headers, RETRY_AFTER.as_str())
+        .or_else(|| header_i64(headers, reset_header).map(|reset| reset - chrono::Utc::now().timestamp()))
+        .map(|secs| secs.max(0) + 1)
+        .unwrap_or(1 << (attempt + 1));
+    
+    if wait_secs > MAX_RATE_LIMIT_WAIT_SECS {
+        return Err(format!("API rate limit exceeded (HTTP {}), resets in {} seconds", status, wait_secs));
+    }
+    
+    log::warn!(
+        "Rate limited by {} (HTTP {}), retrying in {} seconds (attempt {}/{})",
+        response.url().host_str().unwrap_or("API"),
+        status,
+        wait_secs,
+        attempt + 1,
+        MAX_RATE_LIMIT_RETRIES,
+    );
+    tokio::time::sleep(std::time::Duration::from_secs(wait_secs as u64)).await;
+    Ok(true)
+}
+
+/// Pause before the next paginated request when the provider reports that no
+/// calls are left. `reset_header` must carry the reset time as a Unix timestamp.
+pub(crate) async fn respect_rate_limit(
+    headers: &HeaderMap,
+    remaining_header: &str,
+    reset_header: &str,
+) -> Result<(), String> {
+    match header_i64(headers, remaining_header) {
+        Some(remaining) if remaining <= 0 => {}
+        _ => return Ok(()),
+    }
+    
+    let wait_secs = header_i64(headers, reset_header)
+        .map(|reset| (reset - chrono::Utc::now().timestamp()).max(0) + 1)
+        .unwrap_or(MAX_RATE_LIMIT_WAIT_SECS + 1);
+    
+    if wait_secs > MAX_RATE_LIMIT_WAIT_SECS {
+        return Err(format!("API rate limit exhausted, resets in {} seconds", wait_secs));
+    }
+    
+    log::warn!("API rate limit exhausted, waiting {} seconds", wait_secs);
+    tokio::time::sleep(std::time::Duration::from_secs(wait_secs as u64)).await;
+    Ok(())
+}
 
 /// Trait for Git platform services (GitHub, GitLab, etc.)
 #[async_trait]
@@ -21,6 +109,28 @@ pub trait GitPlatformService: Send + Sync {
         mr_number: i64,
     ) -> Result<MergeRequestInfo, String>;
     
+    /// List merge requests, following pagination until every page is fetched
+    /// or the configured page cap is reached. `None` lists all states.
+    async fn list_merge_requests(
+        &self,
+        remote_info: &GitRemoteInfo,
+        state: Option<&MergeRequestState>,
+    ) -> Result<Vec<MergeRequestInfo>, String>;
+    
+    /// The open merge request from `source_branch` into `target_branch`, if any
+    async fn find_open_merge_request(
+        &self,
+        remote_info: &GitRemoteInfo,
+        source_branch: &str,
+        target_branch: &str,
+    ) -> Result<Option<MergeRequestInfo>, String> {
+        Ok(self
+            .list_merge_requests(remote_info, Some(&MergeRequestState::Opened))
+            .await?
+            .into_iter()
+            .find(|mr| mr.source_branch == source_branch && mr.target_branch == target_branch))
+    }
+    
     /// Update merge request status
     async fn update_merge_request_status(
         &self,
@@ -28,6 +138,36 @@ pub trait GitPlatformService: Send + Sync {
         mr_number: i64,
     ) -> Result<MergeRequestInfo, String>;
     
+    /// Merge a merge request using the given method
+    async fn merge_merge_request(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+        merge_method: &MergeMethod,
+        delete_source_branch: bool,
+    ) -> Result<MergeRequestInfo, String>;
+    
+    /// Close a merge request without merging
+    async fn close_merge_request(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+    ) -> Result<MergeRequestInfo, String>;
+    
+    /// List comments on a merge request
+    async fn list_comments(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+    ) -> Result<Vec<MergeRequestComment>, String>;
+    
+    /// Post a comment on a merge request
+    async fn post_comment(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+        body: &str,
+    ) -> Result<MergeRequestComment, String>;
     
     /// Push branch to remote with authentication
     async fn push_branch(
@@ -36,4 +176,107 @@ pub trait GitPlatformService: Send + Sync {
         branch: &str,
         force: bool,
     ) -> Result<(), String>;
-}
\ No newline at end of file
+}
+
+/// Make sure `branch` on the remote matches the worktree at `worktree_path`
+/// before a merge request is opened from it. A missing branch, or one the
+/// worktree is only ahead of, is pushed when `auto_push` is set. A remote
+/// branch with commits the worktree lacks is never overwritten.
+pub async fn ensure_branch_pushed(
+    platform: &dyn GitPlatformService,
+    worktree_path: &Path,
+    branch: &str,
+    auto_push: bool,
+) -> Result<(), PivoError> {
+    let status = GitService::remote_branch_status(worktree_path, branch).map_err(PivoError::git)?;
+    if status.is_up_to_date() {
+        return Ok(());
+    }
+    if !auto_push || status.commits_behind > 0 {
+        return Err(PivoError::branch_out_of_sync(branch, &status));
+    }
+
+    log::info!("Pushing {} ({} commits ahead) before opening a merge request", branch, status.commits_ahead);
+    platform
+        .push_branch(&worktree_path.to_string_lossy(), branch, false)
+        .await
+        .map_err(PivoError::from)
+}
+
+/// Push `branch` to `origin` as it is configured, for `PushMode::Ssh`. git
+/// inherits the app's environment, so SSH remotes authenticate through the
+/// user's agent (`SSH_AUTH_SOCK`) and keys instead of an access token.
+pub fn push_over_ssh(repo_path: &str, branch: &str, force: bool) -> Result<(), String> {
+    let branch_spec = format!("{}:{}", branch, branch);
+    let mut push_args = vec!["push", "origin", &branch_spec];
+    if force {
+        push_args.push("--force");
+    }
+    
+    log::info!("Pushing {} to origin over SSH (force: {})", branch, force);
+    let output = execute_git(&push_args, Path::new(repo_path))
+        .map_err(|e| format!("Failed to push branch: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    log::error!("Git push over SSH failed: {}", stderr);
+    Err(ssh_push_error(&stderr))
+}
+
+/// Word SSH failures so `PivoError` can tell a rejected host key from a
+/// rejected key; anything else is reported as git's own output
+fn ssh_push_error(stderr: &str) -> String {
+    let lower = stderr.to_lowercase();
+    if lower.contains("host key verification failed") || lower.contains("remote host identification has changed") {
+        format!("SSH host key verification failed. Connect to the host once from a terminal to trust its key: {}", stderr)
+    } else if lower.contains("permission denied (publickey") || lower.contains("no supported authentication methods") {
+        format!("SSH authentication failed. Make sure your key is loaded in ssh-agent and can push to the repository: {}", stderr)
+    } else {
+        format!("Failed to push branch: {}", stderr)
+    }
+}
+
+/// Parse `remote_url` like `GitRemoteInfo::from_remote_url`, treating the
+/// GitHub Enterprise host as GitHub. With `probe`, a host that is still
+/// unknown is asked which API it serves.
+pub async fn detect_remote_info(remote_url: &str, enterprise_host: Option<&str>, probe: bool) -> Option<GitRemoteInfo> {
+    let mut info = GitRemoteInfo::from_remote_url_with_github_host(remote_url, enterprise_host)?;
+    if probe && info.provider == GitProvider::Other {
+        if let Some(base_url) = info.host.clone() {
+            if let Some(provider) = probe_git_provider(&base_url).await {
+                log::info!("Detected {} at {}", provider.display_name(), base_url);
+                info.provider = provider;
+            }
+        }
+    }
+    Some(info)
+}
+
+/// Guess the provider of a self-hosted server from its API: GitLab answers on
+/// `/api/v4/version` and GitHub Enterprise on `/api/v3`, both with 401 when
+/// anonymous access is off
+async fn probe_git_provider(base_url: &str) -> Option<GitProvider> {
+    let client = crate::utils::http::client();
+    let answers = |url: String| {
+        let client = client.clone();
+        async move {
+            match client.get(&url).timeout(std::time::Duration::from_secs(5)).send().await {
+                Ok(response) => matches!(response.status(), StatusCode::OK | StatusCode::UNAUTHORIZED),
+                Err(e) => {
+                    log::debug!("Probing {} failed: {}", url, e);
+                    false
+                }
+            }
+        }
+    };
+    
+    if answers(format!("{}/api/v4/version", base_url)).await {
+        Some(GitProvider::GitLab)
+    } else if answers(format!("{}/api/v3", base_url)).await {
+        Some(GitProvider::GitHub)
+    } else {
+        None
+    }
+}
@@ -1,6 +1,43 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use crate::models::{DiffMode, DiffResult, FileDiff, FileStatus, DiffStats, RebaseStatus, WorktreeInfo};
+use std::sync::OnceLock;
+use crate::models::{DiffChunk, DiffLine, DiffMode, DiffResult, DiffStatSummary, FileDiff, FileDiffDetail, LineType, FileStat, FileStatus, DiffStats, MergeMethod, MergeResult, RebaseStatus, RemoteBranchStatus, WorktreeInfo, BlameLine, CommitGraph, CommitNode, FileCommit, RemoteBranch, FetchResult};
 use crate::utils::command::execute_git;
+use crate::utils::project_type::{GitignoreTemplate, ProjectMarkers};
+
+/// Files bigger than this are returned truncated by `get_file_diff_detail`
+const LARGE_FILE_BYTES: usize = 1024 * 1024;
+
+/// Git treats content with a NUL byte in its first 8000 bytes as binary
+const BINARY_CHECK_BYTES: usize = 8000;
+
+/// One side of a file in `get_file_diff_detail`
+#[derive(Default)]
+struct FileSide {
+    content: Option<String>,
+    binary: bool,
+    truncated: bool,
+}
+
+impl FileSide {
+    fn from_bytes(mut bytes: Vec<u8>) -> Self {
+        if bytes.iter().take(BINARY_CHECK_BYTES).any(|&b| b == 0) {
+            return Self { content: None, binary: true, truncated: false };
+        }
+
+        let truncated = bytes.len() > LARGE_FILE_BYTES;
+        if truncated {
+            let end = bytes[..LARGE_FILE_BYTES].iter().rposition(|&b| b == b'\n').map_or(LARGE_FILE_BYTES, |i| i + 1);
+            bytes.truncate(end);
+        }
+
+        Self {
+            content: Some(String::from_utf8_lossy(&bytes).into_owned()),
+            binary: false,
+            truncated,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct GitService {
@@ -52,12 +89,15 @@ impl GitService {
         Ok(worktree_path)
     }
     
-    /// Create a new worktree with baseline tracking
+    /// Create a new worktree with baseline tracking. The worktree always covers
+    /// the whole repository; `subdirectory` (where the agent will run) must
+    /// exist on the base branch.
     pub fn create_worktree_with_baseline(
         &self,
         repo_path: &Path,
         branch_name: &str,
         base_branch: &str,
+        subdirectory: Option<&str>,
     ) -> Result<WorktreeInfo, String> {
         // Try to get the base branch, if it doesn't exist, try to detect the default branch
         let actual_base_branch = match self.get_branch_commit(repo_path, base_branch) {
@@ -74,6 +114,16 @@ impl GitService {
         // Get the base commit using the actual base branch
         let base_commit = self.get_branch_commit(repo_path, &actual_base_branch)?;
         
+        if let Some(subdirectory) = subdirectory {
+            let object = format!("{}:{}", base_commit, subdirectory);
+            let is_tree = execute_git(&["cat-file", "-t", &object], repo_path)
+                .map(|output| output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "tree")
+                .unwrap_or(false);
+            if !is_tree {
+                return Err(format!("Subdirectory {} does not exist on branch {}", subdirectory, actual_base_branch));
+            }
+        }
+        
         // Create the worktree
         let worktree_path = self.create_worktree(repo_path, branch_name, &actual_base_branch)?;
         
@@ -157,7 +207,10 @@ impl GitService {
 
     /// Remove a worktree
     pub fn remove_worktree(&self, repo_path: &Path, worktree_path: &Path) -> Result<(), String> {
-        // First, remove the worktree
+        // Drop cache symlinks first so nothing below can follow them into the main checkout
+        crate::services::WorktreeProvisioner::unlink_cache_links(worktree_path);
+        
+        // Remove the worktree
         let output = execute_git(
             &["worktree", "remove", worktree_path.to_str().unwrap(), "--force"],
             repo_path,
@@ -176,6 +229,25 @@ impl GitService {
         Ok(())
     }
 
+    /// Force delete a local branch
+    pub fn delete_branch(&self, repo_path: &Path, branch: &str) -> Result<(), String> {
+        let output = execute_git(&["branch", "-D", branch], repo_path)
+            .map_err(|e| format!("Failed to delete branch: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Whether `path` is inside a git working tree
+    pub fn is_repository(path: &Path) -> bool {
+        execute_git(&["rev-parse", "--is-inside-work-tree"], path)
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
     /// Get the current branch name
     pub fn get_current_branch(repo_path: &Path) -> Result<String, String> {
         let output = execute_git(&["rev-parse", "--abbrev-ref", "HEAD"], repo_path)
@@ -205,14 +277,73 @@ impl GitService {
         Ok(branches)
     }
 
+    /// Remote-tracking branches as of the last fetch. Nothing is fetched, see
+    /// `fetch_remote` to bring them up to date.
+    pub fn get_remote_branches(repo_path: &Path) -> Result<Vec<RemoteBranch>, String> {
+        let output = execute_git(&["branch", "-r", &format!("--format={}", REMOTE_BRANCH_FORMAT)], repo_path)
+            .map_err(|e| format!("Failed to list remote branches: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(parse_remote_branches(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Fetch `remote`, or every remote when `None`, and report which
+    /// remote-tracking branches appeared, disappeared or moved
+    pub fn fetch_remote(repo_path: &Path, remote: Option<&str>, prune: bool) -> Result<FetchResult, String> {
+        if remote.is_some_and(|r| r.is_empty() || r.starts_with('-')) {
+            return Err(format!("Invalid remote name: {}", remote.unwrap_or_default()));
+        }
+
+        let tracking_commits = || -> Result<BTreeMap<String, String>, String> {
+            Ok(Self::get_remote_branches(repo_path)?
+                .into_iter()
+                .filter(|b| remote.is_none_or(|r| b.remote == r))
+                .map(|b| (format!("{}/{}", b.remote, b.name), b.commit))
+                .collect())
+        };
+        let before = tracking_commits()?;
+
+        let mut args = vec!["fetch", remote.unwrap_or("--all")];
+        if prune {
+            args.push("--prune");
+        }
+        let output = execute_git(&args, repo_path)
+            .map_err(|e| format!("Failed to fetch: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        let after = tracking_commits()?;
+        let mut result = FetchResult::default();
+        for (branch, commit) in &after {
+            match before.get(branch) {
+                None => result.new_branches.push(branch.clone()),
+                Some(old_commit) if old_commit != commit => result.updated_branches.push(branch.clone()),
+                Some(_) => {}
+            }
+        }
+        result.deleted_branches = before.into_keys().filter(|branch| !after.contains_key(branch)).collect();
+
+        Ok(result)
+    }
+
     // Removed unused method create_branch
 
     /// Get git diff
-    pub fn get_diff(repo_path: &Path, staged: bool) -> Result<String, String> {
+    /// Patch of the working tree (or the index when `staged`), limited to
+    /// `pathspec` when given
+    pub fn get_diff(repo_path: &Path, staged: bool, pathspec: Option<&str>) -> Result<String, String> {
         let mut args = vec!["diff"];
         if staged {
             args.push("--staged");
         }
+        if let Some(pathspec) = pathspec {
+            args.extend(["--", pathspec]);
+        }
 
         let output = execute_git(&args, repo_path)
             .map_err(|e| format!("Failed to get diff: {}", e))?;
@@ -224,6 +355,93 @@ impl GitService {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
     
+    /// Summarize a diff as per-file line counts without the patch text.
+    /// Compares against `base_ref` when given, otherwise against the index
+    /// (or HEAD when `staged`). Binary files count as zero lines. Only files
+    /// matching `pathspec` are counted when it is given.
+    pub fn get_diff_stat(repo_path: &Path, base_ref: Option<&str>, staged: bool, pathspec: Option<&str>) -> Result<DiffStatSummary, String> {
+        let run = |format: &str| -> Result<String, String> {
+            let mut args = vec!["diff", format, "-M", "-z"];
+            if staged {
+                args.push("--staged");
+            }
+            if let Some(base_ref) = base_ref {
+                args.push(base_ref);
+            }
+            if let Some(pathspec) = pathspec {
+                args.extend(["--", pathspec]);
+            }
+            let output = execute_git(&args, repo_path)
+                .map_err(|e| format!("Failed to get diff stat: {}", e))?;
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).to_string());
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        };
+
+        let numstat = run("--numstat")?;
+        let name_status = run("--name-status")?;
+
+        // --name-status -z: "<status>\0<path>\0", renames and copies carry "<old>\0<new>\0"
+        let mut statuses = std::collections::HashMap::new();
+        let mut tokens = name_status.split('\0').filter(|t| !t.is_empty());
+        while let Some(code) = tokens.next() {
+            let status = match code.chars().next() {
+                Some('A') => FileStatus::Added,
+                Some('D') => FileStatus::Deleted,
+                Some('R') => FileStatus::Renamed,
+                Some('C') => FileStatus::Copied,
+                _ => FileStatus::Modified,
+            };
+            if matches!(status, FileStatus::Renamed | FileStatus::Copied) {
+                tokens.next();
+            }
+            if let Some(path) = tokens.next() {
+                statuses.insert(path.to_string(), status);
+            }
+        }
+
+        // --numstat -z: "<add>\t<del>\t<path>\0", renames leave the path empty
+        // and follow with "<old>\0<new>\0"; binary files report "-" counts
+        let mut summary = DiffStatSummary {
+            files_changed: 0,
+            insertions: 0,
+            deletions: 0,
+            files: Vec::new(),
+        };
+        let mut tokens = numstat.split('\0');
+        while let Some(entry) = tokens.next() {
+            let mut parts = entry.splitn(3, '\t');
+            let (additions, deletions, path) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(additions), Some(deletions), Some(path)) => (additions, deletions, path),
+                _ => continue,
+            };
+            let path = if path.is_empty() {
+                tokens.next();
+                match tokens.next() {
+                    Some(new_path) => new_path,
+                    None => continue,
+                }
+            } else {
+                path
+            };
+
+            let additions = additions.parse().unwrap_or(0);
+            let deletions = deletions.parse().unwrap_or(0);
+            summary.insertions += additions;
+            summary.deletions += deletions;
+            summary.files.push(FileStat {
+                path: path.to_string(),
+                additions,
+                deletions,
+                status: statuses.remove(path).unwrap_or(FileStatus::Modified),
+            });
+        }
+        summary.files_changed = summary.files.len();
+
+        Ok(summary)
+    }
+    
     /// Get comprehensive diff based on mode
     pub fn get_comprehensive_diff(&self, worktree_path: &Path, mode: DiffMode) -> Result<DiffResult, String> {
         match mode {
@@ -235,6 +453,129 @@ impl GitService {
         }
     }
     
+    /// Old and new content of one file of a `DiffResult` together with its hunks.
+    /// `old_path` is the path before a rename, if any.
+    pub fn get_file_diff_detail(
+        &self,
+        worktree_path: &Path,
+        file_path: &str,
+        old_path: Option<&str>,
+        mode: &DiffMode,
+    ) -> Result<FileDiffDetail, String> {
+        // The new side is the working tree when `new_ref` is None
+        let (old_ref, new_ref) = match mode {
+            DiffMode::WorkingDirectory => ("HEAD".to_string(), None),
+            DiffMode::BranchChanges { base_commit } => (base_commit.clone(), Some("HEAD".to_string())),
+            DiffMode::AgainstRemote { remote_branch } => (format!("origin/{}", remote_branch), Some("HEAD".to_string())),
+            DiffMode::CommitRange { from, to } => (from.clone(), Some(to.clone())),
+            DiffMode::MergePreview { target_branch } => (target_branch.clone(), Some("HEAD".to_string())),
+        };
+        let source_path = old_path.unwrap_or(file_path);
+
+        let old_side = Self::read_file_at_ref(worktree_path, &old_ref, source_path)?;
+        let new_side = match &new_ref {
+            Some(new_ref) => Self::read_file_at_ref(worktree_path, new_ref, file_path)?,
+            None => match std::fs::read(worktree_path.join(file_path)) {
+                Ok(bytes) => FileSide::from_bytes(bytes),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => FileSide::default(),
+                Err(e) => return Err(format!("Failed to read {}: {}", file_path, e)),
+            },
+        };
+
+        let binary = old_side.binary || new_side.binary;
+        let hunks = if binary {
+            vec![]
+        } else {
+            let mut args = vec!["diff", "--no-color", "--no-ext-diff", "-M", old_ref.as_str()];
+            if let Some(new_ref) = &new_ref {
+                args.push(new_ref);
+            }
+            args.push("--");
+            if source_path != file_path {
+                args.push(source_path);
+            }
+            args.push(file_path);
+
+            let output = execute_git(&args, worktree_path)
+                .map_err(|e| format!("Failed to get file diff: {}", e))?;
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).to_string());
+            }
+
+            let hunks = parse_unified_diff(&String::from_utf8_lossy(&output.stdout));
+            match (&old_side.content, &new_side.content) {
+                // Untracked files don't show up in `git diff`
+                (None, Some(content)) if hunks.is_empty() && !content.is_empty() => vec![added_file_hunk(content)],
+                _ => hunks,
+            }
+        };
+
+        Ok(FileDiffDetail {
+            path: file_path.to_string(),
+            old_path: old_path.map(|p| p.to_string()),
+            truncated: old_side.truncated || new_side.truncated,
+            old_content: old_side.content,
+            new_content: new_side.content,
+            hunks,
+            binary,
+        })
+    }
+
+    /// `path` as of `git_ref`; empty when the file doesn't exist there
+    fn read_file_at_ref(repo_path: &Path, git_ref: &str, path: &str) -> Result<FileSide, String> {
+        let object = format!("{}:{}", git_ref, path);
+        let exists = execute_git(&["cat-file", "-e", &object], repo_path)
+            .map_err(|e| format!("Failed to look up {}: {}", object, e))?;
+        if !exists.status.success() {
+            return Ok(FileSide::default());
+        }
+
+        let output = execute_git(&["show", &object], repo_path)
+            .map_err(|e| format!("Failed to read {}: {}", object, e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(FileSide::from_bytes(output.stdout))
+    }
+
+    /// Diff the tips of two branches, e.g. two attempts of the same task,
+    /// with per-file line counts
+    pub fn diff_branches(&self, repo_path: &Path, branch_a: &str, branch_b: &str) -> Result<DiffResult, String> {
+        for branch in [branch_a, branch_b] {
+            if self.get_branch_commit(repo_path, branch).is_err() {
+                return Err(format!("Branch '{}' does not exist", branch));
+            }
+        }
+        
+        let summary = Self::get_diff_stat(repo_path, Some(&format!("{}..{}", branch_a, branch_b)), false, None)?;
+        let files = summary.files
+            .into_iter()
+            .map(|file| FileDiff {
+                path: file.path,
+                old_path: None,
+                status: file.status,
+                chunks: vec![],
+                additions: file.additions,
+                deletions: file.deletions,
+                binary: false,
+            })
+            .collect();
+        
+        Ok(DiffResult {
+            mode: DiffMode::CommitRange { from: branch_a.to_string(), to: branch_b.to_string() },
+            files,
+            stats: DiffStats {
+                files_changed: summary.files_changed,
+                additions: summary.insertions,
+                deletions: summary.deletions,
+            },
+            has_conflicts: false,
+            conflicted_files: vec![],
+            large_files: vec![],
+        })
+    }
+    
     /// Get working directory changes (staged and unstaged)
     fn get_working_directory_diff(&self, repo_path: &Path) -> Result<DiffResult, String> {
         let mut all_files = Vec::new();
@@ -261,6 +602,7 @@ impl GitService {
             files: all_files,
             stats,
             has_conflicts: false,
+            conflicted_files: vec![],
             large_files: vec![],
         })
     }
@@ -291,6 +633,7 @@ impl GitService {
             files,
             stats,
             has_conflicts: false,
+            conflicted_files: vec![],
             large_files: vec![],
         })
     }
@@ -331,6 +674,7 @@ impl GitService {
             files,
             stats,
             has_conflicts: false,
+            conflicted_files: vec![],
             large_files: vec![],
         })
     }
@@ -361,25 +705,30 @@ impl GitService {
             files,
             stats,
             has_conflicts: false,
+            conflicted_files: vec![],
             large_files: vec![],
         })
     }
     
-    /// Get merge preview diff
+    /// Get merge preview diff: the changes against the target branch and the
+    /// files that would conflict if the branch were merged into it now
     fn get_merge_preview_diff(&self, repo_path: &Path, target_branch: &str) -> Result<DiffResult, String> {
-        // This is a bit more complex - we need to simulate a merge
-        let output = execute_git(
-            &["merge-tree", "--write-tree", target_branch, "HEAD"],
-            repo_path,
-        )
-        .map_err(|e| format!("Failed to get merge preview: {}", e))?;
-            
-        if !output.status.success() {
-            // Fallback to simple diff
-            return self.get_remote_diff(repo_path, target_branch);
-        }
+        // Branches that only exist on the remote are merged from origin
+        let target_exists = execute_git(&["rev-parse", "--verify", "-q", target_branch], repo_path)
+            .is_ok_and(|output| output.status.success());
+        let target_ref = if target_exists {
+            target_branch.to_string()
+        } else {
+            let fetch_output = execute_git(&["fetch", "origin", target_branch], repo_path)
+                .map_err(|e| format!("Failed to fetch remote: {}", e))?;
+            if !fetch_output.status.success() {
+                log::warn!("Failed to fetch remote: {}", String::from_utf8_lossy(&fetch_output.stderr));
+            }
+            format!("origin/{}", target_branch)
+        };
+        
+        let conflicted_files = Self::merge_conflicts(repo_path, &target_ref, "HEAD")?;
         
-        // Parse merge-tree output (this is simplified, real implementation would be more complex)
         let mut files = Vec::new();
         let mut stats = DiffStats {
             files_changed: 0,
@@ -387,12 +736,15 @@ impl GitService {
             deletions: 0,
         };
         
-        // For now, just get the diff against target branch
         let diff_output = execute_git(
-            &["diff", target_branch, "HEAD", "--numstat", "--name-status"],
+            &["diff", &target_ref, "HEAD", "--numstat", "--name-status"],
             repo_path,
         )
         .map_err(|e| format!("Failed to get diff: {}", e))?;
+        
+        if !diff_output.status.success() {
+            return Err(String::from_utf8_lossy(&diff_output.stderr).to_string());
+        }
             
         self.parse_diff_output(&diff_output.stdout, &mut files, &mut stats)?;
         
@@ -400,11 +752,48 @@ impl GitService {
             mode: DiffMode::MergePreview { target_branch: target_branch.to_string() },
             files,
             stats,
-            has_conflicts: false,
+            has_conflicts: !conflicted_files.is_empty(),
+            conflicted_files,
             large_files: vec![],
         })
     }
     
+    /// Files that would conflict when merging `theirs` into `ours`, worked out
+    /// with `git merge-tree` without touching the worktree or the index. Git
+    /// 2.38 and later do a real merge with `--write-tree`; older versions only
+    /// have the three-way form, whose output is scanned for conflict markers.
+    pub fn merge_conflicts(repo_path: &Path, ours: &str, theirs: &str) -> Result<Vec<String>, String> {
+        if git_version(repo_path).is_some_and(|version| version >= (2, 38)) {
+            let output = execute_git(
+                &["merge-tree", "--write-tree", "--name-only", "--no-messages", ours, theirs],
+                repo_path,
+            )
+            .map_err(|e| format!("Failed to run merge-tree: {}", e))?;
+            
+            // Exit code 1 means the merge has conflicts
+            return match output.status.code() {
+                Some(0) => Ok(Vec::new()),
+                Some(1) => Ok(parse_merge_tree_conflicts(&String::from_utf8_lossy(&output.stdout))),
+                _ => Err(String::from_utf8_lossy(&output.stderr).to_string()),
+            };
+        }
+        
+        let merge_base = execute_git(&["merge-base", ours, theirs], repo_path)
+            .map_err(|e| format!("Failed to find merge base: {}", e))?;
+        if !merge_base.status.success() {
+            return Err(String::from_utf8_lossy(&merge_base.stderr).to_string());
+        }
+        let merge_base = String::from_utf8_lossy(&merge_base.stdout).trim().to_string();
+        
+        let output = execute_git(&["merge-tree", &merge_base, ours, theirs], repo_path)
+            .map_err(|e| format!("Failed to run merge-tree: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        
+        Ok(parse_legacy_merge_tree_conflicts(&String::from_utf8_lossy(&output.stdout)))
+    }
+    
     /// Parse git diff output
     fn parse_diff_output(&self, output: &[u8], files: &mut Vec<FileDiff>, stats: &mut DiffStats) -> Result<(), String> {
         let output_str = String::from_utf8_lossy(output);
@@ -478,14 +867,187 @@ impl GitService {
         let behind = parts.get(0).and_then(|s| s.parse::<usize>().ok()).unwrap_or(0);
         let ahead = parts.get(1).and_then(|s| s.parse::<usize>().ok()).unwrap_or(0);
         
+        // Only a base that moved on can conflict with the branch
+        let conflicted_files = if behind > 0 && ahead > 0 {
+            Self::merge_conflicts(worktree_path, "HEAD", &remote_ref).unwrap_or_else(|e| {
+                log::warn!("Failed to check {} for conflicts with HEAD: {}", remote_ref, e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        
         Ok(RebaseStatus {
             needs_rebase: behind > 0,
             commits_behind: behind,
             commits_ahead: ahead,
             can_fast_forward: ahead == 0 && behind > 0,
-            has_conflicts: false, // Would need to actually try merge to detect
+            has_conflicts: !conflicted_files.is_empty(),
+            conflicted_files,
+        })
+    }
+
+    /// Merge `source_branch` into `target_branch`. The merge runs in the worktree
+    /// that has the target checked out, or in a temporary one if none does.
+    /// Conflicts abort the merge and are returned as a list of files.
+    pub fn merge_branch(
+        &self,
+        repo_path: &Path,
+        source_branch: &str,
+        target_branch: &str,
+        method: MergeMethod,
+        message: Option<&str>,
+    ) -> Result<MergeResult, String> {
+        for branch in [source_branch, target_branch] {
+            if self.get_branch_commit(repo_path, branch).is_err() {
+                return Err(format!("Branch '{}' does not exist", branch));
+            }
+        }
+        
+        let (merge_dir, temporary) = match Self::find_worktree_for_branch(repo_path, target_branch)? {
+            Some(path) => {
+                let dirty = Self::uncommitted_files(&path)?;
+                if !dirty.is_empty() {
+                    return Err(format!(
+                        "Cannot merge into '{}': {:?} has uncommitted changes in {}",
+                        target_branch, path, dirty.join(", ")
+                    ));
+                }
+                (path, false)
+            }
+            None => {
+                let path = self.temp_dir.join(format!("merge-{}", uuid::Uuid::new_v4()));
+                let output = execute_git(
+                    &["worktree", "add", path.to_str().unwrap(), target_branch],
+                    repo_path,
+                )
+                .map_err(|e| format!("Failed to check out '{}': {}", target_branch, e))?;
+                if !output.status.success() {
+                    return Err(format!(
+                        "Failed to check out '{}': {}",
+                        target_branch,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ));
+                }
+                (path, true)
+            }
+        };
+        
+        let result = Self::run_merge(&merge_dir, source_branch, target_branch, method, message);
+        
+        if temporary {
+            if let Err(e) = self.remove_worktree(repo_path, &merge_dir) {
+                log::warn!("Failed to remove temporary merge worktree {:?}: {}", merge_dir, e);
+            }
+        }
+        
+        result
+    }
+    
+    fn run_merge(
+        merge_dir: &Path,
+        source_branch: &str,
+        target_branch: &str,
+        method: MergeMethod,
+        message: Option<&str>,
+    ) -> Result<MergeResult, String> {
+        let default_message = format!("Merge branch '{}' into {}", source_branch, target_branch);
+        let message = message.unwrap_or(default_message.as_str());
+        
+        let args: Vec<&str> = match method {
+            MergeMethod::FastForward => vec!["merge", "--ff-only", source_branch],
+            MergeMethod::NoFf => vec!["merge", "--no-ff", "-m", message, source_branch],
+            MergeMethod::Squash => vec!["merge", "--squash", source_branch],
+        };
+        let output = execute_git(&args, merge_dir)
+            .map_err(|e| format!("Failed to merge: {}", e))?;
+        
+        if !output.status.success() {
+            let conflicts = Self::conflicted_files(merge_dir);
+            if !conflicts.is_empty() {
+                // Leave the target exactly as it was, `reset --merge` also undoes a squash
+                let _ = execute_git(&["reset", "--merge"], merge_dir);
+                return Ok(MergeResult::Conflicts { files: conflicts });
+            }
+            
+            if method == MergeMethod::FastForward {
+                return Err(format!(
+                    "Cannot fast-forward '{}' to '{}', the branches have diverged. Use a merge or squash instead.",
+                    target_branch, source_branch
+                ));
+            }
+            
+            return Err(format!(
+                "Failed to merge '{}' into '{}': {}",
+                source_branch,
+                target_branch,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        
+        if method == MergeMethod::Squash {
+            // Nothing staged means the branch was already part of the target
+            let staged = execute_git(&["diff", "--cached", "--quiet"], merge_dir)
+                .map_err(|e| format!("Failed to inspect squashed changes: {}", e))?;
+            if !staged.status.success() {
+                Self::commit(merge_dir, message)?;
+            }
+        }
+        
+        let head = execute_git(&["rev-parse", "HEAD"], merge_dir)
+            .map_err(|e| format!("Failed to get commit hash: {}", e))?;
+        
+        Ok(MergeResult::Merged {
+            commit: String::from_utf8_lossy(&head.stdout).trim().to_string(),
         })
     }
+    
+    /// Path of the worktree (main checkout included) that has `branch` checked out
+    fn find_worktree_for_branch(repo_path: &Path, branch: &str) -> Result<Option<PathBuf>, String> {
+        let output = execute_git(&["worktree", "list", "--porcelain"], repo_path)
+            .map_err(|e| format!("Failed to list worktrees: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        
+        let branch_ref = format!("refs/heads/{}", branch);
+        let mut current: Option<PathBuf> = None;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some(path) = line.strip_prefix("worktree ") {
+                current = Some(PathBuf::from(path));
+            } else if line.strip_prefix("branch ") == Some(branch_ref.as_str()) {
+                return Ok(current);
+            }
+        }
+        
+        Ok(None)
+    }
+    
+    /// Tracked files with uncommitted changes
+    pub fn uncommitted_files(repo_path: &Path) -> Result<Vec<String>, String> {
+        let output = execute_git(&["status", "--porcelain", "--untracked-files=no"], repo_path)
+            .map_err(|e| format!("Failed to get status: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.get(3..))
+            .map(|path| path.to_string())
+            .collect())
+    }
+    
+    fn conflicted_files(repo_path: &Path) -> Vec<String> {
+        execute_git(&["diff", "--name-only", "--diff-filter=U"], repo_path)
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(|line| line.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
     /// Stage files
     pub fn stage_files(repo_path: &Path, files: &[&str]) -> Result<(), String> {
@@ -501,6 +1063,53 @@ impl GitService {
         Ok(())
     }
 
+    /// Unstage files, keeping their changes in the working tree
+    pub fn unstage_files(repo_path: &Path, files: &[&str]) -> Result<(), String> {
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        // Without a commit there is no HEAD to reset to, so drop the files from the index instead
+        let mut args = if Self::has_commits(repo_path) {
+            vec!["reset", "-q", "HEAD", "--"]
+        } else {
+            vec!["rm", "--cached", "-r", "-q", "--ignore-unmatch", "--"]
+        };
+        args.extend_from_slice(files);
+        let output = execute_git(&args, repo_path)
+            .map_err(|e| format!("Failed to unstage files: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Unstage everything, keeping the changes in the working tree
+    pub fn unstage_all(repo_path: &Path) -> Result<(), String> {
+        let args: &[&str] = if Self::has_commits(repo_path) {
+            &["reset", "-q", "HEAD"]
+        } else {
+            &["rm", "--cached", "-r", "-q", "--ignore-unmatch", "--", "."]
+        };
+        let output = execute_git(args, repo_path)
+            .map_err(|e| format!("Failed to unstage files: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Whether HEAD points to a commit, false in a freshly initialised repository
+    fn has_commits(repo_path: &Path) -> bool {
+        execute_git(&["rev-parse", "--verify", "-q", "HEAD"], repo_path)
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
     /// Commit changes
     pub fn commit(repo_path: &Path, message: &str) -> Result<String, String> {
         let output = execute_git(&["commit", "-m", message], repo_path)
@@ -517,6 +1126,226 @@ impl GitService {
         Ok(String::from_utf8_lossy(&hash_output.stdout).trim().to_string())
     }
 
+    /// Stage every change, untracked files included, and commit it. Refuses
+    /// while there are unmerged paths or leftover conflict markers, leaving the
+    /// index as it was.
+    pub fn commit_all(repo_path: &Path, message: &str) -> Result<String, String> {
+        let conflicts = Self::conflicted_files(repo_path);
+        if !conflicts.is_empty() {
+            return Err(format!("Unresolved merge conflicts in {}", conflicts.join(", ")));
+        }
+
+        Self::stage_files(repo_path, &["-A"])?;
+
+        let check = execute_git(&["diff", "--cached", "--check"], repo_path)
+            .map_err(|e| format!("Failed to check staged changes: {}", e))?;
+        let markers: Vec<String> = String::from_utf8_lossy(&check.stdout)
+            .lines()
+            .filter(|line| line.contains("conflict marker"))
+            .filter_map(|line| line.split(':').next())
+            .map(|path| path.to_string())
+            .collect();
+        if !markers.is_empty() {
+            let _ = execute_git(&["reset", "-q"], repo_path);
+            return Err(format!("Conflict markers left in {}", markers.join(", ")));
+        }
+
+        Self::commit(repo_path, message)
+    }
+
+    /// Undo `commit_sha` with a new commit and return its hash. With `no_commit`
+    /// the reversal is only applied to the worktree and index, and the hash is
+    /// empty. Merge commits need `mainline`, the parent (from 1) to keep. A
+    /// revert that conflicts is aborted so the worktree is left as it was.
+    pub fn revert_commit(repo_path: &Path, commit_sha: &str, no_commit: bool, mainline: Option<u32>) -> Result<String, String> {
+        let changed = Self::uncommitted_files(repo_path)?;
+        if !changed.is_empty() {
+            return Err(format!("Commit or stash your changes before reverting: {}", changed.join(", ")));
+        }
+
+        let mainline = mainline.map(|parent| parent.to_string());
+        let mut args = vec!["revert", "--no-edit"];
+        if no_commit {
+            args.push("--no-commit");
+        }
+        if let Some(parent) = &mainline {
+            args.extend_from_slice(&["-m", parent]);
+        }
+        args.push(commit_sha);
+
+        let output = execute_git(&args, repo_path)
+            .map_err(|e| format!("Failed to revert commit: {}", e))?;
+        if !output.status.success() {
+            let conflicts = Self::conflicted_files(repo_path);
+            if !conflicts.is_empty() {
+                let _ = execute_git(&["revert", "--abort"], repo_path);
+                return Err(format!("Reverting {} conflicts in {}", commit_sha, conflicts.join(", ")));
+            }
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+
+        if no_commit {
+            return Ok(String::new());
+        }
+        let hash_output = execute_git(&["rev-parse", "HEAD"], repo_path)
+            .map_err(|e| format!("Failed to get commit hash: {}", e))?;
+        Ok(String::from_utf8_lossy(&hash_output.stdout).trim().to_string())
+    }
+
+    /// Undo every commit after `commit_sha` with a single commit that restores
+    /// its tree, and return the new commit's hash. Works across merge commits,
+    /// which `git revert` can't take as a range.
+    pub fn revert_to_commit(repo_path: &Path, commit_sha: &str) -> Result<String, String> {
+        let changed = Self::uncommitted_files(repo_path)?;
+        if !changed.is_empty() {
+            return Err(format!("Commit or stash your changes before reverting: {}", changed.join(", ")));
+        }
+
+        let is_ancestor = execute_git(&["merge-base", "--is-ancestor", commit_sha, "HEAD"], repo_path)
+            .map_err(|e| format!("Failed to check commit: {}", e))?;
+        if !is_ancestor.status.success() {
+            return Err(format!("{} is not an ancestor of HEAD", commit_sha));
+        }
+
+        let range = format!("{}..HEAD", commit_sha);
+        let reverted = execute_git(&["log", "--format=%h %s", &range], repo_path)
+            .map_err(|e| format!("Failed to list commits: {}", e))?;
+        let reverted = String::from_utf8_lossy(&reverted.stdout).trim().to_string();
+        if reverted.is_empty() {
+            return Err(format!("There are no commits after {}", commit_sha));
+        }
+
+        // Replace the index and worktree with the target tree; files added
+        // since are tracked, so they are removed too
+        let output = execute_git(&["read-tree", "--reset", "-u", commit_sha], repo_path)
+            .map_err(|e| format!("Failed to restore {}: {}", commit_sha, e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+
+        let short_sha = commit_sha.get(..7).unwrap_or(commit_sha);
+        let message = format!("Revert to {}\n\nThis reverts the commits after {}:\n{}", short_sha, commit_sha, reverted);
+        Self::commit(repo_path, &message)
+    }
+
+    /// The commits after `base_commit` on HEAD, oldest first. Refuses while
+    /// there are uncommitted changes or when `base_commit` isn't an ancestor.
+    fn commits_to_squash(repo_path: &Path, base_commit: &str) -> Result<Vec<CommitNode>, String> {
+        let changed = Self::uncommitted_files(repo_path)?;
+        if !changed.is_empty() {
+            return Err(format!("Commit or stash your changes before squashing: {}", changed.join(", ")));
+        }
+
+        let is_ancestor = execute_git(&["merge-base", "--is-ancestor", base_commit, "HEAD"], repo_path)
+            .map_err(|e| format!("Failed to check commit: {}", e))?;
+        if !is_ancestor.status.success() {
+            return Err(format!("{} is not an ancestor of HEAD", base_commit));
+        }
+
+        Self::list_commits_between(repo_path, base_commit, "HEAD")
+    }
+
+    /// Replace every commit after `base_commit` with a single commit holding
+    /// their changes, and return its hash. HEAD is put back if committing fails.
+    pub fn squash_commits(repo_path: &Path, base_commit: &str, message: &str) -> Result<String, String> {
+        if message.trim().is_empty() {
+            return Err("The squashed commit needs a message".to_string());
+        }
+        let commits = Self::commits_to_squash(repo_path, base_commit)?;
+        if commits.is_empty() {
+            return Err(format!("There are no commits after {}", base_commit));
+        }
+
+        let head = execute_git(&["rev-parse", "HEAD"], repo_path)
+            .map_err(|e| format!("Failed to read HEAD: {}", e))?;
+        let head = String::from_utf8_lossy(&head.stdout).trim().to_string();
+
+        let output = execute_git(&["reset", "--soft", base_commit], repo_path)
+            .map_err(|e| format!("Failed to reset to {}: {}", base_commit, e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+
+        Self::commit(repo_path, message).map_err(|e| {
+            let _ = execute_git(&["reset", "--soft", &head], repo_path);
+            e
+        })
+    }
+
+    /// The todo list `git rebase -i <base_commit>` would be given to squash
+    /// every commit after `base_commit` into the first one: that one is picked,
+    /// the others are squashed into it. Merge commits are left out, as rebase
+    /// drops them.
+    pub fn squash_commits_interactive_prepare(repo_path: &Path, base_commit: &str) -> Result<String, String> {
+        let commits: Vec<CommitNode> = Self::commits_to_squash(repo_path, base_commit)?
+            .into_iter()
+            .filter(|commit| commit.parents.len() <= 1)
+            .collect();
+        if commits.is_empty() {
+            return Err(format!("There are no commits after {}", base_commit));
+        }
+
+        let script = commits
+            .iter()
+            .enumerate()
+            .map(|(i, commit)| {
+                let command = if i == 0 { "pick" } else { "squash" };
+                let subject = commit.message.lines().next().unwrap_or_default();
+                format!("{} {} {}\n", command, commit.sha, subject)
+            })
+            .collect();
+        Ok(script)
+    }
+
+    /// Initialise a repository on `initial_branch` with a .gitignore for the
+    /// detected project type and commit the existing files as the initial commit
+    pub fn init_repository(repo_path: &Path, initial_branch: &str) -> Result<(), String> {
+        let template = ProjectMarkers::detect(repo_path).gitignore_template();
+        Self::init_repository_with_gitignore(repo_path, initial_branch, template)
+    }
+
+    /// Like `init_repository`, with the .gitignore template chosen by the caller.
+    /// An existing .gitignore is never overwritten.
+    pub fn init_repository_with_gitignore(
+        repo_path: &Path,
+        initial_branch: &str,
+        template: GitignoreTemplate,
+    ) -> Result<(), String> {
+        if !repo_path.is_dir() {
+            return Err(format!("{} is not a directory", repo_path.display()));
+        }
+        if repo_path.join(".git").exists() {
+            return Err(format!("{} is already a Git repository", repo_path.display()));
+        }
+
+        let initial_branch = initial_branch.trim();
+        let output = execute_git(&["init", "-b", initial_branch], repo_path)
+            .map_err(|e| format!("Failed to initialise repository: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        let gitignore_path = repo_path.join(".gitignore");
+        if !gitignore_path.exists() {
+            if let Some(contents) = template.contents() {
+                std::fs::write(&gitignore_path, contents)
+                    .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
+            }
+        }
+
+        Self::stage_files(repo_path, &["."])?;
+
+        // An empty directory still gets a commit so the branch exists for worktrees
+        let output = execute_git(&["commit", "--allow-empty", "-m", "Initial commit"], repo_path)
+            .map_err(|e| format!("Failed to commit: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        log::info!("Initialised repository at {:?} on branch {}", repo_path, initial_branch);
+        Ok(())
+    }
+
     /// Push to remote
     pub fn push(repo_path: &Path, branch: &str, force: bool) -> Result<(), String> {
         let mut args = vec!["push", "origin", branch];
@@ -534,6 +1363,57 @@ impl GitService {
         Ok(())
     }
 
+    /// Compare `branch` on `origin` with the HEAD of the worktree at `repo_path`.
+    /// The remote branch is fetched when it points at a commit we don't have.
+    pub fn remote_branch_status(repo_path: &Path, branch: &str) -> Result<RemoteBranchStatus, String> {
+        let remote_ref = format!("refs/heads/{}", branch);
+        let output = execute_git(&["ls-remote", "--heads", "origin", &remote_ref], repo_path)
+            .map_err(|e| format!("Failed to list remote branches: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        let listing = String::from_utf8_lossy(&output.stdout);
+        let Some(remote_head) = listing.split_whitespace().next().map(|hash| hash.to_string()) else {
+            // Everything not already on some branch of origin would be pushed
+            let output = execute_git(&["rev-list", "--count", "HEAD", "--not", "--remotes=origin"], repo_path)
+                .map_err(|e| format!("Failed to count commits: {}", e))?;
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).to_string());
+            }
+            let ahead = String::from_utf8_lossy(&output.stdout).trim().parse().unwrap_or(0);
+            return Ok(RemoteBranchStatus { exists: false, commits_ahead: ahead, commits_behind: 0 });
+        };
+
+        let output = execute_git(&["rev-parse", "HEAD"], repo_path)
+            .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        if String::from_utf8_lossy(&output.stdout).trim() == remote_head {
+            return Ok(RemoteBranchStatus { exists: true, commits_ahead: 0, commits_behind: 0 });
+        }
+
+        let output = execute_git(&["fetch", "origin", &remote_ref], repo_path)
+            .map_err(|e| format!("Failed to fetch: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        let output = execute_git(&["rev-list", "--left-right", "--count", &format!("{}...HEAD", remote_head)], repo_path)
+            .map_err(|e| format!("Failed to get ahead/behind count: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        let counts = String::from_utf8_lossy(&output.stdout);
+        let mut parts = counts.split_whitespace().map(|s| s.parse::<usize>().unwrap_or(0));
+        let behind = parts.next().unwrap_or(0);
+        let ahead = parts.next().unwrap_or(0);
+
+        Ok(RemoteBranchStatus { exists: true, commits_ahead: ahead, commits_behind: behind })
+    }
+
     /// Get repository status
     pub fn get_status(&self, repo_path: &Path) -> Result<GitStatus, String> {
         log::info!("[GitService] Getting status for path: {:?}", repo_path);
@@ -586,6 +1466,10 @@ impl GitService {
                     files.deleted.push(filename.clone());
                     files.staged.push(filename);
                 }
+                " D" => {
+                    files.deleted.push(filename.clone());
+                    files.changed.push(filename);
+                }
                 "DM" => {
                     files.deleted.push(filename.clone());
                     files.staged.push(filename.clone());
@@ -668,6 +1552,111 @@ impl GitService {
         Ok(files)
     }
 
+    /// Get the commit graph across all refs, newest first
+    pub fn get_commit_graph(repo_path: &Path, max_commits: usize) -> Result<CommitGraph, String> {
+        let max = max_commits.to_string();
+        let format = format!("--format={}", COMMIT_LOG_FORMAT);
+        let output = execute_git(
+            &["log", "--all", "--parents", "--date-order", &format, "-n", &max],
+            repo_path,
+        )
+        .map_err(|e| format!("Failed to get commit graph: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        let commits = parse_commit_log(&String::from_utf8_lossy(&output.stdout));
+        let edges = commits
+            .iter()
+            .flat_map(|commit| commit.parents.iter().map(|parent| (commit.sha.clone(), parent.clone())))
+            .collect();
+
+        Ok(CommitGraph { commits, edges })
+    }
+
+    /// Commits reachable from `head` but not from `base`, oldest first
+    pub fn list_commits_between(repo_path: &Path, base: &str, head: &str) -> Result<Vec<CommitNode>, String> {
+        let range = format!("{}..{}", base, head);
+        let output = execute_git(
+            &["log", "--reverse", &format!("--format={}", COMMIT_LOG_FORMAT), &range],
+            repo_path,
+        )
+        .map_err(|e| format!("Failed to list commits: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(parse_commit_log(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// The last `max_count` commits that touched `file_path`, newest first.
+    /// Renames are followed, so history from before a move is included.
+    pub fn get_file_history(repo_path: &Path, file_path: &str, max_count: usize) -> Result<Vec<FileCommit>, String> {
+        let max = max_count.to_string();
+        let output = execute_git(
+            &["log", "--follow", "--numstat", &format!("--format={}", FILE_HISTORY_FORMAT), "-n", &max, "--", file_path],
+            repo_path,
+        )
+        .map_err(|e| format!("Failed to get file history: {}", e))?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(parse_file_history(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Blame every line of `file_path` in the worktree, including uncommitted
+    /// changes, and flag the lines written after `base_commit`
+    pub fn blame_file(worktree_path: &Path, file_path: &str, base_commit: &str) -> Result<Vec<BlameLine>, String> {
+        // Blame needs the path in HEAD; a file that was never committed is all new
+        let in_head = execute_git(&["cat-file", "-e", &format!("HEAD:{}", file_path)], worktree_path)
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+        if !in_head {
+            let content = std::fs::read_to_string(worktree_path.join(file_path))
+                .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+            return Ok(content
+                .lines()
+                .enumerate()
+                .map(|(index, line)| BlameLine {
+                    line_number: index + 1,
+                    content: line.to_string(),
+                    commit: UNCOMMITTED_SHA.to_string(),
+                    author: UNCOMMITTED_AUTHOR.to_string(),
+                    timestamp: 0,
+                    introduced_in_attempt: true,
+                    uncommitted: true,
+                })
+                .collect());
+        }
+
+        let output = execute_git(&["blame", "--porcelain", "--", file_path], worktree_path)
+            .map_err(|e| format!("Failed to blame file: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        let mut lines = parse_blame_porcelain(&String::from_utf8_lossy(&output.stdout));
+
+        // Every blamed commit is reachable from HEAD, so the ones that are not
+        // reachable from the base are exactly the attempt's
+        let output = execute_git(&["rev-list", &format!("{}..HEAD", base_commit)], worktree_path)
+            .map_err(|e| format!("Failed to list attempt commits: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let attempt_commits: HashSet<&str> = stdout.lines().collect();
+
+        for line in &mut lines {
+            line.introduced_in_attempt = line.uncommitted || attempt_commits.contains(line.commit.as_str());
+        }
+
+        Ok(lines)
+    }
+
     pub fn get_file_from_ref(repo_path: &Path, file_ref: &str) -> Result<String, String> {
         let output = execute_git(&["show", file_ref], repo_path)
             .map_err(|e| format!("Failed to get file from ref: {}", e))?;
@@ -681,6 +1670,304 @@ impl GitService {
     }
 }
 
+// Fields are separated by \x1f and records by \x1e so messages can't break parsing
+const COMMIT_LOG_FORMAT: &str = "%H%x1f%P%x1f%D%x1f%an%x1f%aI%x1f%s%x1e";
+
+/// Version of the installed git as (major, minor), looked up once
+fn git_version(repo_path: &Path) -> Option<(u32, u32)> {
+    static GIT_VERSION: OnceLock<Option<(u32, u32)>> = OnceLock::new();
+    *GIT_VERSION.get_or_init(|| {
+        let output = execute_git(&["--version"], repo_path).ok()?;
+        parse_git_version(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+/// "git version 2.39.2 (Apple Git-143)" -> (2, 39)
+fn parse_git_version(version: &str) -> Option<(u32, u32)> {
+    let number = version.trim().strip_prefix("git version ")?.split_whitespace().next()?;
+    let mut parts = number.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Output of `merge-tree --write-tree --name-only --no-messages`: the tree
+/// id, then one conflicted path per line
+fn parse_merge_tree_conflicts(output: &str) -> Vec<String> {
+    let mut conflicted: Vec<String> = Vec::new();
+    for path in output.lines().skip(1).take_while(|line| !line.is_empty()) {
+        if !conflicted.iter().any(|existing| existing == path) {
+            conflicted.push(path.to_string());
+        }
+    }
+    conflicted
+}
+
+/// Output of the three-way `merge-tree <base> <ours> <theirs>`: a section per
+/// file ("changed in both", "added in remote", ...) listing the versions as
+/// "  our    100644 <sha> <path>", followed by a diff of the merged result.
+/// Files whose merged content has conflict markers are conflicted.
+fn parse_legacy_merge_tree_conflicts(output: &str) -> Vec<String> {
+    let mut conflicted: Vec<String> = Vec::new();
+    let mut path: Option<String> = None;
+    for line in output.lines() {
+        if line.starts_with(|c: char| c.is_ascii_lowercase()) {
+            // A new section starts
+            path = None;
+        } else if path.is_none() && line.starts_with("  ") {
+            let mut fields = line.split_whitespace();
+            if let (Some("base" | "our" | "their" | "result"), Some(_mode), Some(sha)) = (fields.next(), fields.next(), fields.next()) {
+                path = line.split_once(sha).map(|(_, rest)| rest.trim().to_string());
+            }
+        } else if line.starts_with("+<<<<<<<") {
+            if let Some(path) = &path {
+                if !conflicted.contains(path) {
+                    conflicted.push(path.clone());
+                }
+            }
+        }
+    }
+    conflicted
+}
+
+fn parse_commit_log(log_text: &str) -> Vec<CommitNode> {
+    let mut commits = Vec::new();
+
+    for record in log_text.split('\x1e') {
+        let fields: Vec<&str> = record.trim_start_matches('\n').split('\x1f').collect();
+        if fields.len() < 6 {
+            continue;
+        }
+
+        let refs = fields[2]
+            .split(", ")
+            .filter(|s| !s.is_empty())
+            .flat_map(|s| s.split(" -> "))
+            .map(|s| s.to_string())
+            .collect();
+        let date = chrono::DateTime::parse_from_rfc3339(fields[4])
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now());
+
+        commits.push(CommitNode {
+            sha: fields[0].to_string(),
+            parents: fields[1].split_whitespace().map(|s| s.to_string()).collect(),
+            refs,
+            author: fields[3].to_string(),
+            message: fields[5].to_string(),
+            date,
+        });
+    }
+
+    commits
+}
+
+// Full ref name, commit and, for `<remote>/HEAD`, the ref it points to
+const REMOTE_BRANCH_FORMAT: &str = "%(refname)%09%(objectname)%09%(symref)";
+
+fn parse_remote_branches(output: &str) -> Vec<RemoteBranch> {
+    let mut head_refs = Vec::new();
+    let mut branches = Vec::new();
+
+    for line in output.lines() {
+        let mut fields = line.split('\t');
+        let (Some(refname), Some(commit)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let Some((remote, name)) = refname.strip_prefix("refs/remotes/").and_then(|r| r.split_once('/')) else {
+            continue;
+        };
+
+        if name == "HEAD" {
+            head_refs.extend(fields.next().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string));
+            continue;
+        }
+        branches.push((
+            refname,
+            RemoteBranch {
+                name: name.to_string(),
+                remote: remote.to_string(),
+                commit: commit.to_string(),
+                is_head: false,
+            },
+        ));
+    }
+
+    branches
+        .into_iter()
+        .map(|(refname, branch)| RemoteBranch {
+            is_head: head_refs.iter().any(|head| head == refname),
+            ..branch
+        })
+        .collect()
+}
+
+// Each record starts with \x1e and its header is followed by the --numstat lines
+const FILE_HISTORY_FORMAT: &str = "%x1e%H%x1f%an%x1f%aI%x1f%s";
+
+fn parse_file_history(log_text: &str) -> Vec<FileCommit> {
+    let mut commits = Vec::new();
+
+    for record in log_text.split('\x1e') {
+        let (header, numstat) = record.split_once('\n').unwrap_or((record, ""));
+        let fields: Vec<&str> = header.split('\x1f').collect();
+        if fields.len() < 4 {
+            continue;
+        }
+
+        // Binary files are listed with "-" instead of line counts
+        let (additions, deletions) = numstat
+            .lines()
+            .filter_map(|line| {
+                let mut counts = line.split('\t');
+                Some((counts.next()?.parse::<usize>().ok()?, counts.next()?.parse::<usize>().ok()?))
+            })
+            .fold((0, 0), |(added, deleted), (a, d)| (added + a, deleted + d));
+
+        commits.push(FileCommit {
+            sha: fields[0].to_string(),
+            author: fields[1].to_string(),
+            date: fields[2].to_string(),
+            message: fields[3].to_string(),
+            additions,
+            deletions,
+        });
+    }
+
+    commits
+}
+
+/// `git blame` reports lines that are not committed yet as this commit
+const UNCOMMITTED_SHA: &str = "0000000000000000000000000000000000000000";
+const UNCOMMITTED_AUTHOR: &str = "Not Committed Yet";
+
+/// Lines of `git blame --porcelain` output, in file order. The author of a
+/// commit is only given the first time the commit shows up.
+fn parse_blame_porcelain(blame_text: &str) -> Vec<BlameLine> {
+    let mut authors: HashMap<String, (String, i64)> = HashMap::new();
+    let mut lines = Vec::new();
+    // Commit and final line number of the header being read
+    let mut current: Option<(String, usize)> = None;
+
+    for line in blame_text.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            let Some((commit, line_number)) = current.take() else {
+                continue;
+            };
+            let (author, timestamp) = authors.get(&commit).cloned().unwrap_or_default();
+            let uncommitted = commit.bytes().all(|b| b == b'0');
+            lines.push(BlameLine {
+                line_number,
+                content: content.to_string(),
+                commit,
+                author,
+                timestamp,
+                introduced_in_attempt: uncommitted,
+                uncommitted,
+            });
+        } else if let Some(author) = line.strip_prefix("author ") {
+            if let Some((commit, _)) = &current {
+                authors.entry(commit.clone()).or_default().0 = author.to_string();
+            }
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            if let Some((commit, _)) = &current {
+                authors.entry(commit.clone()).or_default().1 = time.parse().unwrap_or(0);
+            }
+        } else {
+            // "<sha> <original line> <final line> [<lines in group>]"
+            let mut fields = line.split(' ');
+            let (Some(sha), Some(_), Some(final_line)) = (fields.next(), fields.next(), fields.next()) else {
+                continue;
+            };
+            if sha.len() >= 40 && sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+                if let Ok(final_line) = final_line.parse() {
+                    current = Some((sha.to_string(), final_line));
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+/// Hunks of a unified diff of a single file
+fn parse_unified_diff(diff_text: &str) -> Vec<DiffChunk> {
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    let mut old_line = 0;
+    let mut new_line = 0;
+
+    for line in diff_text.lines() {
+        if let Some(header) = line.strip_prefix("@@ -") {
+            // "@@ -12,7 +12,9 @@ fn context"; a missing count means 1
+            let mut ranges = header.split(' ');
+            let parse_range = |range: Option<&str>| -> (usize, usize) {
+                let range = range.unwrap_or_default().trim_start_matches('+');
+                let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+                (start.parse().unwrap_or(0), count.parse().unwrap_or(1))
+            };
+            let (old_start, old_lines) = parse_range(ranges.next());
+            let (new_start, new_lines) = parse_range(ranges.next());
+
+            old_line = old_start;
+            new_line = new_start;
+            chunks.push(DiffChunk { old_start, old_lines, new_start, new_lines, lines: vec![] });
+            continue;
+        }
+
+        let Some(chunk) = chunks.last_mut() else {
+            // File header lines before the first hunk
+            continue;
+        };
+
+        let (line_type, content) = match line.split_at_checked(1) {
+            Some(("+", content)) => (LineType::Addition, content),
+            Some(("-", content)) => (LineType::Deletion, content),
+            Some((" ", content)) => (LineType::Context, content),
+            // Empty context lines can lose their leading space
+            None => (LineType::Context, ""),
+            // "\ No newline at end of file"
+            _ => continue,
+        };
+
+        let (old_line_number, new_line_number) = match line_type {
+            LineType::Addition => (None, Some(new_line)),
+            LineType::Deletion => (Some(old_line), None),
+            LineType::Context => (Some(old_line), Some(new_line)),
+        };
+        if old_line_number.is_some() {
+            old_line += 1;
+        }
+        if new_line_number.is_some() {
+            new_line += 1;
+        }
+
+        chunk.lines.push(DiffLine {
+            content: content.to_string(),
+            line_type,
+            old_line_number,
+            new_line_number,
+        });
+    }
+
+    chunks
+}
+
+/// A single hunk adding every line of `content`
+fn added_file_hunk(content: &str) -> DiffChunk {
+    let lines: Vec<DiffLine> = content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| DiffLine {
+            content: line.to_string(),
+            line_type: LineType::Addition,
+            old_line_number: None,
+            new_line_number: Some(i + 1),
+        })
+        .collect();
+
+    DiffChunk { old_start: 0, old_lines: 0, new_start: 1, new_lines: lines.len(), lines }
+}
+
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GitStatus {
     pub modified: Vec<String>,
@@ -696,10 +1983,167 @@ pub struct GitStatus {
     pub changed: Vec<String>,
 }
 
+impl GitStatus {
+    /// Nothing staged, modified, deleted or untracked
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty()
+            && self.added.is_empty()
+            && self.deleted.is_empty()
+            && self.untracked.is_empty()
+            && self.staged.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RemoteInfo {
     pub name: String,
     pub url: String,
 }
 
-// Removed unused GitStatus implementation
\ No newline at end of file
+// Removed unused GitStatus implementation
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let mut full_args = vec!["-c", "user.name=Pivo", "-c", "user.email=pivo@example.com"];
+        full_args.extend_from_slice(args);
+        let output = execute_git(&full_args, dir).unwrap();
+        assert!(output.status.success(), "git {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(dir: &Path, file: &str, content: &str, message: &str) {
+        std::fs::write(dir.join(file), content).unwrap();
+        git(dir, &["add", file]);
+        git(dir, &["commit", "-m", message]);
+    }
+
+    /// A repository where `feature` and `main` both change line 2 of
+    /// conflict.txt, and only `feature` touches feature.txt
+    fn conflicting_repo() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pivo-merge-tree-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        commit_file(&dir, "conflict.txt", "one\ntwo\nthree\n", "initial");
+
+        git(&dir, &["checkout", "-q", "-b", "feature"]);
+        commit_file(&dir, "conflict.txt", "one\ntwo from feature\nthree\n", "feature change");
+        commit_file(&dir, "feature.txt", "new file\n", "feature file");
+
+        git(&dir, &["checkout", "-q", "main"]);
+        commit_file(&dir, "conflict.txt", "one\ntwo from main\nthree\n", "main change");
+        git(&dir, &["branch", "clean", "HEAD~1"]);
+        dir
+    }
+
+    #[test]
+    fn merge_tree_reports_conflicted_files() {
+        let dir = conflicting_repo();
+        assert_eq!(GitService::merge_conflicts(&dir, "main", "feature").unwrap(), vec!["conflict.txt"]);
+        assert!(GitService::merge_conflicts(&dir, "clean", "feature").unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn legacy_merge_tree_output_is_scanned_for_markers() {
+        let dir = conflicting_repo();
+        let merge_base = git(&dir, &["merge-base", "main", "feature"]);
+        let conflicting = git(&dir, &["merge-tree", &merge_base, "main", "feature"]);
+        assert_eq!(parse_legacy_merge_tree_conflicts(&conflicting), vec!["conflict.txt"]);
+
+        let merge_base = git(&dir, &["merge-base", "clean", "feature"]);
+        let clean = git(&dir, &["merge-tree", &merge_base, "clean", "feature"]);
+        assert!(parse_legacy_merge_tree_conflicts(&clean).is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn blame_flags_lines_written_after_the_base() {
+        let dir = std::env::temp_dir().join(format!("pivo-blame-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        commit_file(&dir, "lib.rs", "one\ntwo\n", "initial");
+        let base = git(&dir, &["rev-parse", "HEAD"]);
+        commit_file(&dir, "lib.rs", "one\ntwo\nthree\n", "attempt change");
+        std::fs::write(dir.join("lib.rs"), "one\ntwo\nthree\nfour\n").unwrap();
+        std::fs::write(dir.join("new.rs"), "fresh\n").unwrap();
+
+        let lines = GitService::blame_file(&dir, "lib.rs", &base).unwrap();
+        let flags: Vec<(bool, bool)> = lines.iter().map(|l| (l.introduced_in_attempt, l.uncommitted)).collect();
+        assert_eq!(flags, vec![(false, false), (false, false), (true, false), (true, true)]);
+        assert_eq!(lines[0].commit, base);
+        assert_eq!(lines[2].author, "Pivo");
+
+        let lines = GitService::blame_file(&dir, "new.rs", &base).unwrap();
+        assert!(lines.len() == 1 && lines[0].uncommitted && lines[0].introduced_in_attempt);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn reverts_one_commit_or_everything_after_one() {
+        let dir = std::env::temp_dir().join(format!("pivo-revert-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        git(&dir, &["config", "user.name", "Pivo"]);
+        git(&dir, &["config", "user.email", "pivo@example.com"]);
+        commit_file(&dir, "a.txt", "one\n", "initial");
+        let initial = git(&dir, &["rev-parse", "HEAD"]);
+        commit_file(&dir, "b.txt", "two\n", "add b");
+        let add_b = git(&dir, &["rev-parse", "HEAD"]);
+        commit_file(&dir, "a.txt", "one\nmore\n", "extend a");
+
+        let reverted = GitService::revert_commit(&dir, &add_b, false, None).unwrap();
+        assert_eq!(reverted, git(&dir, &["rev-parse", "HEAD"]));
+        assert!(!dir.join("b.txt").exists());
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "one\nmore\n");
+
+        std::fs::write(dir.join("a.txt"), "dirty\n").unwrap();
+        assert!(GitService::revert_to_commit(&dir, &initial).is_err());
+        git(&dir, &["checkout", "--", "a.txt"]);
+
+        GitService::revert_to_commit(&dir, &initial).unwrap();
+        assert_eq!(git(&dir, &["diff", &initial, "HEAD"]), "");
+        assert!(GitService::uncommitted_files(&dir).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn squashes_the_commits_after_a_base() {
+        let dir = std::env::temp_dir().join(format!("pivo-squash-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        git(&dir, &["config", "user.name", "Pivo"]);
+        git(&dir, &["config", "user.email", "pivo@example.com"]);
+        commit_file(&dir, "a.txt", "one\n", "initial");
+        let base = git(&dir, &["rev-parse", "HEAD"]);
+        commit_file(&dir, "b.txt", "two\n", "add b");
+        let add_b = git(&dir, &["rev-parse", "HEAD"]);
+        commit_file(&dir, "a.txt", "one\nmore\n", "extend a\n\nwith a body");
+        let extend_a = git(&dir, &["rev-parse", "HEAD"]);
+
+        let script = GitService::squash_commits_interactive_prepare(&dir, &base).unwrap();
+        assert_eq!(script, format!("pick {} add b\nsquash {} extend a\n", add_b, extend_a));
+
+        assert!(GitService::squash_commits(&dir, &base, " ").is_err());
+        std::fs::write(dir.join("a.txt"), "dirty\n").unwrap();
+        assert!(GitService::squash_commits(&dir, &base, "squashed").is_err());
+        git(&dir, &["checkout", "--", "a.txt"]);
+
+        let squashed = GitService::squash_commits(&dir, &base, "add b and extend a").unwrap();
+        assert_eq!(squashed, git(&dir, &["rev-parse", "HEAD"]));
+        assert_eq!(git(&dir, &["rev-parse", "HEAD~1"]), base);
+        assert_eq!(git(&dir, &["diff", &extend_a, "HEAD"]), "");
+        assert!(GitService::squash_commits(&dir, &squashed, "nothing").is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn git_versions_are_parsed() {
+        assert_eq!(parse_git_version("git version 2.39.2 (Apple Git-143)\n"), Some((2, 39)));
+        assert_eq!(parse_git_version("git version 2.45.1.windows.1"), Some((2, 45)));
+        assert_eq!(parse_git_version("not git"), None);
+    }
+}
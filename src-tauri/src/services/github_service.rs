@@ -1,10 +1,11 @@
 use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
 use serde::{Deserialize, Serialize};
-use crate::models::{GitHubConfig, MergeRequestInfo, GitRemoteInfo, MergeRequestState, MergeStatus, PipelineStatus};
-use crate::services::git_platform::GitPlatformService;
+use crate::models::{BranchStatus, GitHubConfig, PushMode, RepositoryInfo, MergeRequestInfo, GitRemoteInfo, MergeRequestState, MergeStatus, PipelineStatus, PipelineJob, MergeMethod, MergeRequestComment};
+use crate::services::git_platform::{push_over_ssh, respect_rate_limit, wait_for_rate_limit_retry, GitPlatformService};
 use crate::utils::command::execute_git;
 
+#[derive(Clone)]
 pub struct GitHubService {
     config: GitHubConfig,
     client: reqwest::Client,
@@ -21,7 +22,11 @@ struct GitHubPullRequest {
     head: GitHubRef,
     base: GitHubRef,
     mergeable: Option<bool>,
+    // Only returned by the single PR endpoint; list responses carry merged_at
+    #[serde(default)]
     merged: bool,
+    #[serde(default)]
+    merged_at: Option<String>,
     draft: bool,
 }
 
@@ -32,6 +37,89 @@ struct GitHubRef {
     sha: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHubMergeResult {
+    sha: Option<String>,
+    merged: bool,
+    message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHubIssueComment {
+    id: i64,
+    body: Option<String>,
+    user: Option<GitHubUser>,
+    created_at: String,
+    html_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+impl From<GitHubIssueComment> for MergeRequestComment {
+    fn from(comment: GitHubIssueComment) -> Self {
+        MergeRequestComment {
+            id: comment.id,
+            author: comment.user.map(|u| u.login).unwrap_or_default(),
+            body: comment.body.unwrap_or_default(),
+            created_at: comment.created_at,
+            web_url: Some(comment.html_url),
+            resolvable: false,
+            resolved: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHubRepository {
+    name: String,
+    full_name: String,
+    description: Option<String>,
+    default_branch: String,
+    private: bool,
+    fork: bool,
+    clone_url: String,
+    ssh_url: String,
+    open_issues_count: u32,
+}
+
+impl From<GitHubRepository> for RepositoryInfo {
+    fn from(repository: GitHubRepository) -> Self {
+        RepositoryInfo {
+            name: repository.name,
+            full_name: repository.full_name,
+            description: repository.description.filter(|description| !description.is_empty()),
+            default_branch: repository.default_branch,
+            is_private: repository.private,
+            is_fork: repository.fork,
+            clone_url: repository.clone_url,
+            ssh_url: repository.ssh_url,
+            open_issues_count: repository.open_issues_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHubBranch {
+    name: String,
+    commit: GitHubBranchCommit,
+    #[serde(default)]
+    protected: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHubBranchCommit {
+    sha: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GitHubComparison {
+    ahead_by: u32,
+    behind_by: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GitHubCheckRuns {
     total_count: i32,
@@ -44,6 +132,43 @@ struct GitHubCheckRun {
     name: String,
     status: String,
     conclusion: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+    #[serde(default)]
+    details_url: Option<String>,
+}
+
+impl GitHubCheckRun {
+    fn pipeline_status(&self) -> PipelineStatus {
+        match (self.status.as_str(), self.conclusion.as_deref()) {
+            ("completed", Some("success")) => PipelineStatus::Success,
+            ("completed", Some("failure")) | ("completed", Some("timed_out")) => PipelineStatus::Failed,
+            ("completed", Some("cancelled")) => PipelineStatus::Canceled,
+            ("completed", Some("skipped")) | ("completed", Some("neutral")) => PipelineStatus::Skipped,
+            ("completed", Some("action_required")) => PipelineStatus::Manual,
+            ("queued", _) => PipelineStatus::Pending,
+            _ => PipelineStatus::Running,
+        }
+    }
+}
+
+impl From<GitHubCheckRun> for PipelineJob {
+    fn from(run: GitHubCheckRun) -> Self {
+        PipelineJob {
+            status: Some(run.pipeline_status()),
+            conclusion: run.conclusion.or(Some(run.status)),
+            url: run.details_url.or(run.html_url),
+            name: run.name,
+        }
+    }
+}
+
+/// Aggregated check runs of a commit
+struct CheckRunSummary {
+    status: PipelineStatus,
+    /// Link to the first failing check, or the first check when none failed
+    url: Option<String>,
+    jobs: Vec<PipelineJob>,
 }
 
 impl GitHubService {
@@ -67,7 +192,7 @@ impl GitHubService {
             HeaderValue::from_static("pivo-app"),
         );
         
-        let client = reqwest::Client::builder()
+        let client = crate::utils::http::client_builder()
             .default_headers(headers)
             .build()
             .unwrap();
@@ -75,31 +200,58 @@ impl GitHubService {
         Self { config, client }
     }
     
+    /// Self-hosted repositories are served from https://<host>/api/v3
+    fn api_base_url(&self, remote_info: &GitRemoteInfo) -> String {
+        match remote_info.host.as_deref() {
+            Some(host) => format!("{}/api/v3", host.trim_end_matches('/')),
+            None => self.config.api_base_url(),
+        }
+    }
+    
     fn get_api_url(&self, remote_info: &GitRemoteInfo, endpoint: &str) -> String {
         format!(
-            "https://api.github.com/repos/{}/{}/{}",
+            "{}/repos/{}/{}/{}",
+            self.api_base_url(remote_info),
             remote_info.owner,
             remote_info.repo,
             endpoint
         )
     }
     
+    /// Send a request, retrying when GitHub reports a primary or secondary rate limit
+    async fn send_with_retry(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<reqwest::Response, String> {
+        let mut attempt = 0;
+        loop {
+            let mut request = self.client.request(method.clone(), url);
+            
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            
+            let response = request
+                .send()
+                .await
+                .map_err(|e| format!("Request failed: {}", e))?;
+            
+            if !wait_for_rate_limit_retry(&response, "x-ratelimit-remaining", "x-ratelimit-reset", attempt).await? {
+                return Ok(response);
+            }
+            attempt += 1;
+        }
+    }
+    
     async fn make_request<T: serde::de::DeserializeOwned>(
         &self,
         url: &str,
         method: reqwest::Method,
         body: Option<serde_json::Value>,
     ) -> Result<T, String> {
-        let mut request = self.client.request(method, url);
-        
-        if let Some(body) = body {
-            request = request.json(&body);
-        }
-        
-        let response = request
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+        let response = self.send_with_retry(method, url, body.as_ref()).await?;
         
         if !response.status().is_success() {
             let status = response.status();
@@ -113,7 +265,48 @@ impl GitHubService {
             .map_err(|e| format!("Failed to parse response: {}", e))
     }
     
-    async fn get_check_runs(&self, remote_info: &GitRemoteInfo, sha: &str) -> Result<PipelineStatus, String> {
+    /// GET every page of a list endpoint by following the `Link: rel="next"` header
+    async fn get_all_pages<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<Vec<T>, String> {
+        let max_pages = self.config.max_pages();
+        let separator = if url.contains('?') { '&' } else { '?' };
+        
+        let mut items = Vec::new();
+        let mut next_url = Some(format!("{}{}per_page=100", url, separator));
+        let mut pages_fetched = 0;
+        
+        while let Some(page_url) = next_url.take() {
+            if pages_fetched >= max_pages {
+                log::warn!("Stopped paginating {} after {} pages", url, max_pages);
+                break;
+            }
+            
+            let response = self.send_with_retry(reqwest::Method::GET, &page_url, None).await?;
+            
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("GitHub API error ({}): {}", status, error_text));
+            }
+            
+            let headers = response.headers().clone();
+            let mut batch: Vec<T> = response.json().await
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            items.append(&mut batch);
+            pages_fetched += 1;
+            
+            next_url = headers.get(reqwest::header::LINK)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_next_link);
+            
+            if next_url.is_some() {
+                respect_rate_limit(&headers, "x-ratelimit-remaining", "x-ratelimit-reset").await?;
+            }
+        }
+        
+        Ok(items)
+    }
+    
+    async fn get_check_runs(&self, remote_info: &GitRemoteInfo, sha: &str) -> Result<CheckRunSummary, String> {
         let url = self.get_api_url(remote_info, &format!("commits/{}/check-runs", sha));
         
         let check_runs: GitHubCheckRuns = self.make_request(
@@ -123,18 +316,28 @@ impl GitHubService {
         ).await?;
         
         if check_runs.total_count == 0 {
-            return Ok(PipelineStatus::Success);
+            return Ok(CheckRunSummary {
+                status: PipelineStatus::Success,
+                url: None,
+                jobs: Vec::new(),
+            });
         }
         
         let mut has_pending = false;
         let mut has_failure = false;
+        let mut failure_url = None;
         
-        for run in check_runs.check_runs {
+        for run in &check_runs.check_runs {
             match run.status.as_str() {
                 "completed" => {
                     match run.conclusion.as_deref() {
                         Some("success") => {},
-                        Some("failure") | Some("cancelled") | Some("timed_out") => has_failure = true,
+                        Some("failure") | Some("cancelled") | Some("timed_out") => {
+                            has_failure = true;
+                            if failure_url.is_none() {
+                                failure_url = run.details_url.clone().or_else(|| run.html_url.clone());
+                            }
+                        }
                         _ => has_pending = true,
                     }
                 },
@@ -142,20 +345,107 @@ impl GitHubService {
             }
         }
         
-        Ok(if has_failure {
+        let status = if has_failure {
             PipelineStatus::Failed
         } else if has_pending {
             PipelineStatus::Running
         } else {
             PipelineStatus::Success
-        })
+        };
+        let jobs: Vec<PipelineJob> = check_runs.check_runs.into_iter().map(PipelineJob::from).collect();
+        let url = failure_url.or_else(|| jobs.first().and_then(|job| job.url.clone()));
+        
+        Ok(CheckRunSummary { status, url, jobs })
+    }
+    
+    /// List the repository's branches with the CI status of their head commit and
+    /// how far each one has diverged from the default branch. Per-branch lookups
+    /// that fail leave the corresponding fields empty instead of failing the list.
+    pub async fn list_branches_with_status(&self, remote_info: &GitRemoteInfo) -> Result<Vec<BranchStatus>, String> {
+        let repository = self.get_repository(remote_info).await?;
+        
+        let url = self.get_api_url(remote_info, "branches");
+        let branches: Vec<GitHubBranch> = self.get_all_pages(&url).await?;
+        
+        let mut statuses = Vec::with_capacity(branches.len());
+        for branch in branches {
+            let is_default = branch.name == repository.default_branch;
+            
+            let (pipeline_status, comparison) = tokio::join!(
+                async { self.get_check_runs(remote_info, &branch.commit.sha).await.map(|summary| summary.status) },
+                async {
+                    if is_default {
+                        Ok(GitHubComparison { ahead_by: 0, behind_by: 0 })
+                    } else {
+                        self.compare_branches(remote_info, &repository.default_branch, &branch.name).await
+                    }
+                }
+            );
+            
+            let pipeline_status = pipeline_status
+                .map_err(|e| log::warn!("Failed to get check runs for branch {}: {}", branch.name, e))
+                .ok();
+            let comparison = comparison
+                .map_err(|e| log::warn!("Failed to compare branch {} with {}: {}", branch.name, repository.default_branch, e))
+                .ok();
+            
+            statuses.push(BranchStatus {
+                name: branch.name,
+                commit_sha: branch.commit.sha,
+                pipeline_status,
+                is_default,
+                is_protected: branch.protected,
+                ahead_by: comparison.as_ref().map(|c| c.ahead_by),
+                behind_by: comparison.as_ref().map(|c| c.behind_by),
+            });
+        }
+        
+        Ok(statuses)
+    }
+    
+    async fn get_repository(&self, remote_info: &GitRemoteInfo) -> Result<GitHubRepository, String> {
+        let url = format!(
+            "{}/repos/{}/{}",
+            self.api_base_url(remote_info),
+            remote_info.owner,
+            remote_info.repo
+        );
+        self.make_request(&url, reqwest::Method::GET, None).await
+    }
+    
+    /// Name, description and default branch of a repository, for setting up a project from it
+    pub async fn get_repository_info(&self, remote_info: &GitRemoteInfo) -> Result<RepositoryInfo, String> {
+        self.get_repository(remote_info).await.map(RepositoryInfo::from)
+    }
+    
+    async fn compare_branches(&self, remote_info: &GitRemoteInfo, base: &str, head: &str) -> Result<GitHubComparison, String> {
+        let url = self.get_api_url(
+            remote_info,
+            &format!("compare/{}...{}", urlencoding::encode(base), urlencoding::encode(head)),
+        );
+        
+        self.make_request(&url, reqwest::Method::GET, None).await
+    }
+    
+    async fn delete_branch(&self, remote_info: &GitRemoteInfo, branch: &str) -> Result<(), String> {
+        let url = self.get_api_url(remote_info, &format!("git/refs/heads/{}", branch));
+        
+        let response = self.send_with_retry(reqwest::Method::DELETE, &url, None).await?;
+        
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("GitHub API error ({}): {}", status, error_text));
+        }
+        
+        Ok(())
     }
     
     pub async fn verify_token(&self) -> Result<serde_json::Value, String> {
-        let url = "https://api.github.com/user";
+        let url = format!("{}/user", self.config.api_base_url());
         
         let response = self.client
-            .get(url)
+            .get(&url)
             .send()
             .await
             .map_err(|e| format!("Failed to verify token: {}", e))?;
@@ -178,7 +468,7 @@ impl GitHubService {
     
     pub async fn check_org_access(&self, org_name: &str) -> Result<bool, String> {
         // Check if the token has access to the organization
-        let url = format!("https://api.github.com/orgs/{}", org_name);
+        let url = format!("{}/orgs/{}", self.config.api_base_url(), org_name);
         
         let response = self.client
             .get(&url)
@@ -201,10 +491,10 @@ impl GitHubService {
     }
     
     pub async fn list_user_orgs(&self) -> Result<Vec<String>, String> {
-        let url = "https://api.github.com/user/orgs";
+        let url = format!("{}/user/orgs", self.config.api_base_url());
         
         let response = self.client
-            .get(url)
+            .get(&url)
             .send()
             .await
             .map_err(|e| format!("Failed to list user orgs: {}", e))?;
@@ -231,16 +521,31 @@ impl GitHubService {
     }
 }
 
+/// Extract the `rel="next"` URL from a GitHub `Link` header
+fn parse_next_link(link_header: &str) -> Option<String> {
+    link_header.split(',').find_map(|part| {
+        let mut segments = part.split(';');
+        let url = segments.next()?.trim();
+        let is_next = segments.any(|s| s.trim() == "rel=\"next\"");
+        if is_next {
+            Some(url.trim_start_matches('<').trim_end_matches('>').to_string())
+        } else {
+            None
+        }
+    })
+}
+
 impl From<GitHubPullRequest> for MergeRequestInfo {
     fn from(pr: GitHubPullRequest) -> Self {
+        let merged = pr.merged || pr.merged_at.is_some();
         let state = match pr.state.as_str() {
             "open" => MergeRequestState::Opened,
-            "closed" if pr.merged => MergeRequestState::Merged,
+            "closed" if merged => MergeRequestState::Merged,
             "closed" => MergeRequestState::Closed,
             _ => MergeRequestState::Opened,
         };
         
-        let merge_status = if pr.merged {
+        let merge_status = if merged {
             Some(MergeStatus::CanBeMerged)
         } else if let Some(mergeable) = pr.mergeable {
             Some(if mergeable {
@@ -265,6 +570,8 @@ impl From<GitHubPullRequest> for MergeRequestInfo {
             merge_status,
             has_conflicts: pr.mergeable.map(|m| !m).unwrap_or(false),
             pipeline_status: None,
+            pipeline_url: None,
+            pipeline_jobs: Vec::new(),
             created_at: String::new(),
             updated_at: String::new(),
         }
@@ -300,8 +607,10 @@ impl GitPlatformService for GitHubService {
         let mut mr_info = MergeRequestInfo::from(pr);
         
         // Get pipeline status from check runs
-        if let Ok(pipeline_status) = self.get_check_runs(remote_info, &source_branch).await {
-            mr_info.pipeline_status = Some(pipeline_status);
+        if let Ok(checks) = self.get_check_runs(remote_info, &source_branch).await {
+            mr_info.pipeline_status = Some(checks.status);
+            mr_info.pipeline_url = checks.url;
+            mr_info.pipeline_jobs = checks.jobs;
         }
         
         Ok(mr_info)
@@ -323,13 +632,36 @@ impl GitPlatformService for GitHubService {
         let mut mr_info = MergeRequestInfo::from(pr);
         
         // Get pipeline status from check runs
-        if let Ok(pipeline_status) = self.get_check_runs(remote_info, &mr_info.source_branch).await {
-            mr_info.pipeline_status = Some(pipeline_status);
+        if let Ok(checks) = self.get_check_runs(remote_info, &mr_info.source_branch).await {
+            mr_info.pipeline_status = Some(checks.status);
+            mr_info.pipeline_url = checks.url;
+            mr_info.pipeline_jobs = checks.jobs;
         }
         
         Ok(mr_info)
     }
     
+    async fn list_merge_requests(
+        &self,
+        remote_info: &GitRemoteInfo,
+        state: Option<&MergeRequestState>,
+    ) -> Result<Vec<MergeRequestInfo>, String> {
+        // GitHub only knows open/closed; merged PRs are closed ones with merged_at set
+        let api_state = match state {
+            Some(MergeRequestState::Opened) => "open",
+            Some(MergeRequestState::Closed) | Some(MergeRequestState::Merged) => "closed",
+            Some(MergeRequestState::Locked) | None => "all",
+        };
+        let url = self.get_api_url(remote_info, &format!("pulls?state={}", api_state));
+        
+        let prs: Vec<GitHubPullRequest> = self.get_all_pages(&url).await?;
+        
+        Ok(prs.into_iter()
+            .map(MergeRequestInfo::from)
+            .filter(|mr| state.map_or(true, |s| &mr.state == s))
+            .collect())
+    }
+    
     async fn update_merge_request_status(
         &self,
         remote_info: &GitRemoteInfo,
@@ -338,6 +670,89 @@ impl GitPlatformService for GitHubService {
         self.get_merge_request(remote_info, pr_number).await
     }
     
+    async fn merge_merge_request(
+        &self,
+        remote_info: &GitRemoteInfo,
+        pr_number: i64,
+        merge_method: &MergeMethod,
+        delete_source_branch: bool,
+    ) -> Result<MergeRequestInfo, String> {
+        let url = self.get_api_url(remote_info, &format!("pulls/{}/merge", pr_number));
+        
+        let method = match merge_method {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        };
+        
+        let result: GitHubMergeResult = self.make_request(
+            &url,
+            reqwest::Method::PUT,
+            Some(serde_json::json!({ "merge_method": method })),
+        ).await?;
+        
+        if !result.merged {
+            return Err(format!("GitHub refused to merge PR #{}: {}", pr_number, result.message));
+        }
+        
+        log::info!("Merged PR #{} ({:?}) with {}", pr_number, result.sha, method);
+        
+        let mr_info = self.get_merge_request(remote_info, pr_number).await?;
+        
+        if delete_source_branch {
+            if let Err(e) = self.delete_branch(remote_info, &mr_info.source_branch).await {
+                log::warn!("Failed to delete source branch {}: {}", mr_info.source_branch, e);
+            }
+        }
+        
+        Ok(mr_info)
+    }
+    
+    async fn close_merge_request(
+        &self,
+        remote_info: &GitRemoteInfo,
+        pr_number: i64,
+    ) -> Result<MergeRequestInfo, String> {
+        let url = self.get_api_url(remote_info, &format!("pulls/{}", pr_number));
+        
+        let pr: GitHubPullRequest = self.make_request(
+            &url,
+            reqwest::Method::PATCH,
+            Some(serde_json::json!({ "state": "closed" })),
+        ).await?;
+        
+        Ok(MergeRequestInfo::from(pr))
+    }
+    
+    async fn list_comments(
+        &self,
+        remote_info: &GitRemoteInfo,
+        pr_number: i64,
+    ) -> Result<Vec<MergeRequestComment>, String> {
+        // PR conversation comments live on the issue endpoint
+        let url = self.get_api_url(remote_info, &format!("issues/{}/comments", pr_number));
+        
+        let comments: Vec<GitHubIssueComment> = self.get_all_pages(&url).await?;
+        
+        Ok(comments.into_iter().map(Into::into).collect())
+    }
+    
+    async fn post_comment(
+        &self,
+        remote_info: &GitRemoteInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<MergeRequestComment, String> {
+        let url = self.get_api_url(remote_info, &format!("issues/{}/comments", pr_number));
+        
+        let comment: GitHubIssueComment = self.make_request(
+            &url,
+            reqwest::Method::POST,
+            Some(serde_json::json!({ "body": body })),
+        ).await?;
+        
+        Ok(comment.into())
+    }
     
     async fn push_branch(
         &self,
@@ -347,6 +762,10 @@ impl GitPlatformService for GitHubService {
     ) -> Result<(), String> {
         log::info!("Starting push_branch - repo: {}, branch: {}, force: {}", repo_path, branch, force);
         
+        if self.config.push_mode == PushMode::Ssh {
+            return push_over_ssh(repo_path, branch, force);
+        }
+        
         // Get the remote URL
         let remote_output = execute_git(&["remote", "get-url", "origin"], repo_path.as_ref())
             .map_err(|e| format!("Failed to get remote URL: {}", e))?;
@@ -379,11 +798,9 @@ impl GitPlatformService for GitHubService {
         let auth_url = if remote_url.starts_with("https://") {
             // Try using token as username with empty password
             remote_url.replace("https://", &format!("https://{}:x-oauth-basic@", auth_token))
-        } else if remote_url.starts_with("git@github.com:") {
-            // Convert SSH URL to HTTPS with auth token
-            remote_url
-                .replace("git@github.com:", "https://github.com/")
-                .replace("https://", &format!("https://{}:x-oauth-basic@", auth_token))
+        } else if let Some((host, path)) = remote_url.strip_prefix("git@").and_then(|rest| rest.split_once(':')) {
+            // Convert SSH URL (github.com or an Enterprise host) to HTTPS with auth token
+            format!("https://{}:x-oauth-basic@{}/{}", auth_token, host, path)
         } else {
             log::error!("Unsupported remote URL format: {}", remote_url);
             return Err(format!("Unsupported remote URL format: {}", remote_url));
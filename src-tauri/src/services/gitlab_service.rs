@@ -3,11 +3,12 @@ use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use crate::utils::command::execute_git;
 use crate::models::{
-    GitLabConfig, MergeRequestInfo, GitRemoteInfo, MergeRequestState, 
-    MergeStatus, PipelineStatus
+    ApprovalState, GitLabConfig, GitLabConnectionError, GitLabUserInfo, MergeRequestInfo, GitRemoteInfo, MergeRequestState, 
+    MergeStatus, PipelineStatus, PipelineJob, MergeMethod, MergeRequestComment, PushMode
 };
-use super::git_platform::GitPlatformService;
+use super::git_platform::{push_over_ssh, respect_rate_limit, wait_for_rate_limit_retry, GitPlatformService};
 
+#[derive(Clone)]
 pub struct GitLabService {
     client: Client,
     config: GitLabConfig,
@@ -16,11 +17,61 @@ pub struct GitLabService {
 impl GitLabService {
     pub fn new(config: GitLabConfig) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::utils::http::client(),
             config,
         }
     }
     
+    /// Look up the account behind the configured token with `GET /api/v4/user`,
+    /// telling a wrong instance URL apart from a rejected token
+    pub async fn verify_token(&self) -> Result<GitLabUserInfo, GitLabConnectionError> {
+        let base_url = self.config.gitlab_url().trim_end_matches('/');
+        let parsed = reqwest::Url::parse(base_url)
+            .map_err(|e| GitLabConnectionError::InvalidUrl(format!("Invalid GitLab URL '{}': {}", base_url, e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(GitLabConnectionError::InvalidUrl(format!("GitLab URL must start with http:// or https://: {}", base_url)));
+        }
+        
+        let pat = self.config.pat.as_deref()
+            .filter(|pat| !pat.trim().is_empty())
+            .ok_or_else(|| GitLabConnectionError::InvalidToken("GitLab Personal Access Token not configured".to_string()))?;
+        
+        let url = format!("{}/api/v4/user", base_url);
+        let response = self.client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", pat))
+            .send()
+            .await
+            .map_err(|e| GitLabConnectionError::Network(format!("Failed to reach {}: {}", base_url, e)))?;
+        
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(GitLabConnectionError::InvalidToken(
+                    "GitLab rejected the Personal Access Token; check that it is valid and has the api scope".to_string()
+                ));
+            }
+            StatusCode::NOT_FOUND => {
+                return Err(GitLabConnectionError::InvalidUrl(format!("{} does not look like a GitLab instance", base_url)));
+            }
+            status => {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(GitLabConnectionError::Network(format!("GitLab API error ({}): {}", status, error_text)));
+            }
+        }
+        
+        // Anything but the user JSON (e.g. a login page) means this is not the API
+        let user: GitLabCurrentUser = response.json().await
+            .map_err(|_| GitLabConnectionError::InvalidUrl(format!("{} does not look like a GitLab instance", base_url)))?;
+        
+        log::info!("GitLab user verified: {}", user.username);
+        
+        Ok(GitLabUserInfo {
+            username: user.username,
+            email: user.email.or(user.public_email).filter(|email| !email.is_empty()),
+        })
+    }
+    
     fn get_api_url(&self, remote_info: &GitRemoteInfo, endpoint: &str) -> String {
         let base_url = remote_info.host.as_deref()
             .unwrap_or(self.config.gitlab_url());
@@ -31,29 +82,47 @@ impl GitLabService {
         format!("{}/api/v4/projects/{}/{}", base_url, encoded_path, endpoint)
     }
     
-    async fn make_request<T: for<'de> Deserialize<'de>>(
+    /// Send an authenticated request, retrying when GitLab reports a rate limit
+    async fn send_with_retry(
         &self,
-        url: &str,
         method: reqwest::Method,
-        body: Option<serde_json::Value>,
-    ) -> Result<T, String> {
+        url: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<reqwest::Response, String> {
         let pat = self.config.pat.as_ref()
             .ok_or("GitLab Personal Access Token not configured")?;
         
-        let mut request = self.client
-            .request(method, url)
-            .header("Authorization", format!("Bearer {}", pat))
-            .header("Content-Type", "application/json");
-        
-        if let Some(body) = body {
-            request = request.json(&body);
+        let mut attempt = 0;
+        loop {
+            let mut request = self.client
+                .request(method.clone(), url)
+                .header("Authorization", format!("Bearer {}", pat))
+                .header("Content-Type", "application/json");
+            
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            
+            let response = request.send().await
+                .map_err(|e| format!("Failed to send request: {}", e))?;
+            
+            if !wait_for_rate_limit_retry(&response, "ratelimit-remaining", "ratelimit-reset", attempt).await? {
+                return Ok(response);
+            }
+            attempt += 1;
         }
-        
-        let response = request.send().await
-            .map_err(|e| format!("Failed to send request: {}", e))?;
+    }
+    
+    async fn make_request<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &str,
+        method: reqwest::Method,
+        body: Option<serde_json::Value>,
+    ) -> Result<T, String> {
+        let response = self.send_with_retry(method, url, body.as_ref()).await?;
         
         match response.status() {
-            StatusCode::OK | StatusCode::CREATED => {
+            StatusCode::OK | StatusCode::CREATED | StatusCode::ACCEPTED => {
                 response.json::<T>().await
                     .map_err(|e| format!("Failed to parse response: {}", e))
             }
@@ -69,6 +138,138 @@ impl GitLabService {
             }
         }
     }
+    
+    /// GET every page of a list endpoint by following the `X-Next-Page` header
+    async fn get_all_pages<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<Vec<T>, String> {
+        let max_pages = self.config.max_pages();
+        let separator = if url.contains('?') { '&' } else { '?' };
+        
+        let mut items = Vec::new();
+        let mut page = 1;
+        let mut pages_fetched = 0;
+        
+        loop {
+            let page_url = format!("{}{}per_page=100&page={}", url, separator, page);
+            let response = self.send_with_retry(reqwest::Method::GET, &page_url, None).await?;
+            
+            match response.status() {
+                StatusCode::OK => {}
+                StatusCode::UNAUTHORIZED => {
+                    return Err("Unauthorized: Invalid GitLab Personal Access Token".to_string());
+                }
+                StatusCode::NOT_FOUND => {
+                    return Err("Not found: Repository or merge request not found".to_string());
+                }
+                status => {
+                    let error_text = response.text().await.unwrap_or_default();
+                    return Err(format!("GitLab API error ({}): {}", status, error_text));
+                }
+            }
+            
+            let headers = response.headers().clone();
+            let mut batch: Vec<T> = response.json().await
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            items.append(&mut batch);
+            pages_fetched += 1;
+            
+            // X-Next-Page is empty on the last page
+            let next_page = headers.get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u32>().ok());
+            
+            match next_page {
+                Some(next) if pages_fetched < max_pages => {
+                    respect_rate_limit(&headers, "ratelimit-remaining", "ratelimit-reset").await?;
+                    page = next;
+                }
+                Some(_) => {
+                    log::warn!("Stopped paginating {} after {} pages", url, max_pages);
+                    break;
+                }
+                None => break,
+            }
+        }
+        
+        Ok(items)
+    }
+    
+    /// List the jobs of a pipeline; retried jobs only show up with their latest run
+    async fn list_pipeline_jobs(&self, remote_info: &GitRemoteInfo, pipeline_id: i64) -> Result<Vec<PipelineJob>, String> {
+        let url = self.get_api_url(remote_info, &format!("pipelines/{}/jobs", pipeline_id));
+        
+        let jobs: Vec<GitLabJob> = self.get_all_pages(&url).await?;
+        
+        Ok(jobs.into_iter().map(PipelineJob::from).collect())
+    }
+    
+    /// Approve a merge request as the token's user
+    pub async fn approve_mr(&self, remote_info: &GitRemoteInfo, mr_iid: i64) -> Result<(), String> {
+        let url = self.get_api_url(remote_info, &format!("merge_requests/{}/approve", mr_iid));
+        let _: serde_json::Value = self.make_request(
+            &url,
+            reqwest::Method::POST,
+            None,
+        ).await?;
+        
+        Ok(())
+    }
+    
+    /// Withdraw the token user's approval of a merge request
+    pub async fn unapprove_mr(&self, remote_info: &GitRemoteInfo, mr_iid: i64) -> Result<(), String> {
+        let url = self.get_api_url(remote_info, &format!("merge_requests/{}/unapprove", mr_iid));
+        let _: serde_json::Value = self.make_request(
+            &url,
+            reqwest::Method::POST,
+            None,
+        ).await?;
+        
+        Ok(())
+    }
+    
+    pub async fn get_approval_state(&self, remote_info: &GitRemoteInfo, mr_iid: i64) -> Result<ApprovalState, String> {
+        let url = self.get_api_url(remote_info, &format!("merge_requests/{}/approvals", mr_iid));
+        let approvals: GitLabApprovals = self.make_request(
+            &url,
+            reqwest::Method::GET,
+            None,
+        ).await?;
+        
+        Ok(approvals.into())
+    }
+    
+    /// Rebase the source branch onto the target branch and wait for GitLab to finish
+    async fn rebase_merge_request(&self, remote_info: &GitRemoteInfo, mr_number: i64) -> Result<(), String> {
+        let url = self.get_api_url(remote_info, &format!("merge_requests/{}/rebase", mr_number));
+        let _: serde_json::Value = self.make_request(
+            &url,
+            reqwest::Method::PUT,
+            None,
+        ).await?;
+        
+        // Rebase runs asynchronously on GitLab's side
+        let status_url = self.get_api_url(
+            remote_info,
+            &format!("merge_requests/{}?include_rebase_in_progress=true", mr_number),
+        );
+        for _ in 0..30 {
+            let status: GitLabRebaseStatus = self.make_request(
+                &status_url,
+                reqwest::Method::GET,
+                None,
+            ).await?;
+            
+            if let Some(error) = status.merge_error {
+                return Err(format!("GitLab rebase failed: {}", error));
+            }
+            if !status.rebase_in_progress.unwrap_or(false) {
+                return Ok(());
+            }
+            
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+        
+        Err("Timed out waiting for GitLab rebase to finish".to_string())
+    }
 }
 
 #[async_trait]
@@ -116,6 +317,25 @@ impl GitPlatformService for GitLabService {
         Ok(response.into())
     }
     
+    async fn list_merge_requests(
+        &self,
+        remote_info: &GitRemoteInfo,
+        state: Option<&MergeRequestState>,
+    ) -> Result<Vec<MergeRequestInfo>, String> {
+        let state = match state {
+            Some(MergeRequestState::Opened) => "opened",
+            Some(MergeRequestState::Closed) => "closed",
+            Some(MergeRequestState::Merged) => "merged",
+            Some(MergeRequestState::Locked) => "locked",
+            None => "all",
+        };
+        let url = self.get_api_url(remote_info, &format!("merge_requests?state={}", state));
+        
+        let mrs: Vec<GitLabMergeRequest> = self.get_all_pages(&url).await?;
+        
+        Ok(mrs.into_iter().map(MergeRequestInfo::from).collect())
+    }
+    
     async fn update_merge_request_status(
         &self,
         remote_info: &GitRemoteInfo,
@@ -139,11 +359,97 @@ impl GitPlatformService for GitLabService {
         // Get pipeline status if available
         if let Some(pipeline) = detailed.head_pipeline {
             mr_info.pipeline_status = pipeline.status.and_then(|s| s.parse().ok());
+            mr_info.pipeline_url = Some(pipeline.web_url);
+            mr_info.pipeline_jobs = self.list_pipeline_jobs(remote_info, pipeline.id).await
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to list jobs of pipeline {}: {}", pipeline.id, e);
+                    Vec::new()
+                });
         }
         
         Ok(mr_info)
     }
     
+    async fn merge_merge_request(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+        merge_method: &MergeMethod,
+        delete_source_branch: bool,
+    ) -> Result<MergeRequestInfo, String> {
+        if *merge_method == MergeMethod::Rebase {
+            self.rebase_merge_request(remote_info, mr_number).await?;
+        }
+        
+        let url = self.get_api_url(remote_info, &format!("merge_requests/{}/merge", mr_number));
+        
+        let body = serde_json::json!({
+            "squash": *merge_method == MergeMethod::Squash,
+            "should_remove_source_branch": delete_source_branch,
+        });
+        
+        let response: GitLabMergeRequest = self.make_request(
+            &url,
+            reqwest::Method::PUT,
+            Some(body),
+        ).await?;
+        
+        Ok(response.into())
+    }
+    
+    async fn close_merge_request(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+    ) -> Result<MergeRequestInfo, String> {
+        let url = self.get_api_url(remote_info, &format!("merge_requests/{}", mr_number));
+        
+        let response: GitLabMergeRequest = self.make_request(
+            &url,
+            reqwest::Method::PUT,
+            Some(serde_json::json!({ "state_event": "close" })),
+        ).await?;
+        
+        Ok(response.into())
+    }
+    
+    async fn list_comments(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+    ) -> Result<Vec<MergeRequestComment>, String> {
+        let mr = self.get_merge_request(remote_info, mr_number).await?;
+        let url = self.get_api_url(
+            remote_info,
+            &format!("merge_requests/{}/notes?sort=asc&order_by=created_at", mr_number),
+        );
+        
+        let notes: Vec<GitLabNote> = self.get_all_pages(&url).await?;
+        
+        // Skip system notes such as "added 1 commit"
+        Ok(notes.into_iter()
+            .filter(|note| !note.system)
+            .map(|note| note.into_comment(&mr.web_url))
+            .collect())
+    }
+    
+    async fn post_comment(
+        &self,
+        remote_info: &GitRemoteInfo,
+        mr_number: i64,
+        body: &str,
+    ) -> Result<MergeRequestComment, String> {
+        let mr = self.get_merge_request(remote_info, mr_number).await?;
+        let url = self.get_api_url(remote_info, &format!("merge_requests/{}/notes", mr_number));
+        
+        let note: GitLabNote = self.make_request(
+            &url,
+            reqwest::Method::POST,
+            Some(serde_json::json!({ "body": body })),
+        ).await?;
+        
+        Ok(note.into_comment(&mr.web_url))
+    }
     
     async fn push_branch(
         &self,
@@ -151,6 +457,10 @@ impl GitPlatformService for GitLabService {
         branch: &str,
         force: bool,
     ) -> Result<(), String> {
+        if self.config.push_mode == PushMode::Ssh {
+            return push_over_ssh(repo_path, branch, force);
+        }
+        
         let pat = self.config.pat.as_ref()
             .ok_or("GitLab Personal Access Token not configured")?;
         
@@ -225,15 +535,108 @@ struct GitLabMergeRequestDetailed {
     head_pipeline: Option<GitLabPipeline>,
 }
 
+#[derive(Debug, Deserialize)]
+struct GitLabNote {
+    id: i64,
+    body: String,
+    author: GitLabUser,
+    created_at: String,
+    #[serde(default)]
+    system: bool,
+    #[serde(default)]
+    resolvable: bool,
+    #[serde(default)]
+    resolved: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabApprovals {
+    #[serde(default)]
+    approved: bool,
+    #[serde(default)]
+    approved_by: Vec<GitLabApprover>,
+    // Only reported on tiers with approval rules
+    #[serde(default)]
+    approvals_required: u32,
+    #[serde(default)]
+    approvals_left: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabApprover {
+    user: GitLabUser,
+}
+
+impl From<GitLabApprovals> for ApprovalState {
+    fn from(approvals: GitLabApprovals) -> Self {
+        ApprovalState {
+            approved: approvals.approved,
+            approved_by: approvals.approved_by.into_iter().map(|a| a.user.username).collect(),
+            approvals_required: approvals.approvals_required,
+            approvals_left: approvals.approvals_left,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabCurrentUser {
+    username: String,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    public_email: Option<String>,
+}
+
+impl GitLabNote {
+    fn into_comment(self, mr_web_url: &str) -> MergeRequestComment {
+        MergeRequestComment {
+            id: self.id,
+            author: self.author.username,
+            body: self.body,
+            created_at: self.created_at,
+            web_url: Some(format!("{}#note_{}", mr_web_url, self.id)),
+            resolvable: self.resolvable,
+            resolved: self.resolved,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabRebaseStatus {
+    rebase_in_progress: Option<bool>,
+    merge_error: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GitLabPipeline {
-    #[allow(dead_code)]
     id: i64,
     status: Option<String>,
-    #[allow(dead_code)]
     web_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct GitLabJob {
+    name: String,
+    status: String,
+    web_url: Option<String>,
+}
+
+impl From<GitLabJob> for PipelineJob {
+    fn from(job: GitLabJob) -> Self {
+        PipelineJob {
+            name: job.name,
+            status: job.status.parse().ok(),
+            conclusion: Some(job.status),
+            url: job.web_url,
+        }
+    }
+}
+
 // Convert GitLab response to our unified model
 impl From<GitLabMergeRequest> for MergeRequestInfo {
     fn from(mr: GitLabMergeRequest) -> Self {
@@ -256,6 +659,8 @@ impl From<GitLabMergeRequest> for MergeRequestInfo {
             merge_status: None,
             has_conflicts: false,
             pipeline_status: None,
+            pipeline_url: None,
+            pipeline_jobs: Vec::new(),
             created_at: mr.created_at,
             updated_at: mr.updated_at,
         }
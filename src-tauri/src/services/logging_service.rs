@@ -0,0 +1,87 @@
+use crate::logging::get_log_file_path;
+use crate::models::{LogEntry, LogLevel};
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use std::fs;
+
+/// Matches the `{d(%Y-%m-%d %H:%M:%S)}` part of the log pattern in `logging.rs`
+const LOG_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub struct LoggingService;
+
+impl LoggingService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parsed entries of the current log file, newest first. With a `level_filter`
+    /// only entries at that level or more severe are returned.
+    pub fn get_log_entries(&self, level_filter: Option<LogLevel>, limit: usize, offset: usize) -> Result<Vec<LogEntry>, String> {
+        let entries = self.read_entries(level_filter)?;
+
+        Ok(entries.into_iter().rev().skip(offset).take(limit).collect())
+    }
+
+    /// Number of entries `get_log_entries` pages through for `level_filter`
+    pub fn get_log_entry_count(&self, level_filter: Option<LogLevel>) -> Result<usize, String> {
+        Ok(self.read_entries(level_filter)?.len())
+    }
+
+    fn read_entries(&self, level_filter: Option<LogLevel>) -> Result<Vec<LogEntry>, String> {
+        let log_path = get_log_file_path();
+        if !log_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&log_path)
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+
+        let mut entries = parse_log(&content);
+        if let Some(max_level) = level_filter {
+            entries.retain(|entry| entry.level <= max_level);
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Parse log lines written as "<timestamp> | <level> | <file>:<line> — <message>".
+/// Lines that don't start a record continue the message of the previous one.
+fn parse_log(content: &str) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+
+    for line in content.lines() {
+        match parse_log_line(line) {
+            Some(entry) => entries.push(entry),
+            None => {
+                if let Some(last) = entries.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let mut parts = line.splitn(3, " | ");
+    let timestamp = parts.next()?;
+    let level = LogLevel::parse(parts.next()?)?;
+    let (target, message) = parts.next()?.split_once(" — ")?;
+
+    // Records are written in local time
+    let naive = NaiveDateTime::parse_from_str(timestamp, LOG_TIMESTAMP_FORMAT).ok()?;
+    let timestamp = Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc());
+
+    Some(LogEntry {
+        timestamp,
+        level,
+        target: target.trim().to_string(),
+        message: message.to_string(),
+    })
+}
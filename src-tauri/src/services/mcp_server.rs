@@ -1,12 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::process::{Command, Stdio};
 use std::io::{BufRead, BufReader, Write};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use uuid::Uuid;
 use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+use crate::db::DbPool;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServer {
@@ -17,6 +20,110 @@ pub struct McpServer {
     pub env: HashMap<String, String>,
     pub capabilities: McpCapabilities,
     pub status: McpServerStatus,
+    /// Relaunch the server with exponential backoff when it crashes
+    #[serde(default)]
+    pub auto_restart: bool,
+    /// Seconds between `ping` health probes, 0 disables them
+    #[serde(default)]
+    pub health_check_interval_seconds: Option<u64>,
+    /// Seconds to wait for a response before a request fails
+    #[serde(default)]
+    pub request_timeout_seconds: Option<u64>,
+    /// Number of stderr lines kept for `get_server_logs`
+    #[serde(default)]
+    pub log_lines: Option<usize>,
+    /// Project the server belongs to; None makes it available to every project
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+/// A server as the user describes it when registering it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewMcpServer {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub auto_restart: bool,
+    pub health_check_interval_seconds: Option<u64>,
+    pub request_timeout_seconds: Option<u64>,
+    pub log_lines: Option<usize>,
+}
+
+impl NewMcpServer {
+    /// A stopped server with a new id, scoped to `project_id` if given
+    pub fn into_server(self, project_id: Option<String>) -> McpServer {
+        McpServer {
+            id: Uuid::new_v4().to_string(),
+            name: self.name,
+            command: self.command,
+            args: self.args,
+            env: self.env,
+            capabilities: McpCapabilities {
+                tools: true,
+                resources: true,
+                prompts: true,
+            },
+            status: McpServerStatus::Stopped,
+            auto_restart: self.auto_restart,
+            health_check_interval_seconds: self.health_check_interval_seconds,
+            request_timeout_seconds: self.request_timeout_seconds,
+            log_lines: self.log_lines,
+            project_id,
+        }
+    }
+}
+
+impl McpServer {
+    /// Whether an agent working on `project_id` may use the server: global
+    /// servers and the project's own, never another project's
+    pub fn is_available_to(&self, project_id: Option<&str>) -> bool {
+        match self.project_id.as_deref() {
+            Some(server_project) => Some(server_project) == project_id,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct McpServerRow {
+    id: String,
+    project_id: Option<String>,
+    name: String,
+    command: String,
+    args: String,
+    env: String,
+    auto_restart: bool,
+    health_check_interval_seconds: Option<i64>,
+    request_timeout_seconds: Option<i64>,
+    log_lines: Option<i64>,
+}
+
+impl From<McpServerRow> for McpServer {
+    fn from(row: McpServerRow) -> Self {
+        McpServer {
+            id: row.id,
+            name: row.name,
+            command: row.command,
+            args: serde_json::from_str(&row.args).unwrap_or_default(),
+            env: serde_json::from_str(&row.env).unwrap_or_default(),
+            capabilities: McpCapabilities {
+                tools: true,
+                resources: true,
+                prompts: true,
+            },
+            status: McpServerStatus::Stopped,
+            auto_restart: row.auto_restart,
+            health_check_interval_seconds: row.health_check_interval_seconds.map(|s| s as u64),
+            request_timeout_seconds: row.request_timeout_seconds.map(|s| s as u64),
+            log_lines: row.log_lines.map(|n| n as usize),
+            project_id: row.project_id,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,50 +143,344 @@ pub enum McpServerStatus {
 
 // Removed unused McpTool, McpResource, McpPrompt and McpPromptArgument structs
 
+const DEFAULT_HEALTH_CHECK_INTERVAL_SECONDS: u64 = 30;
+const DEFAULT_REQUEST_TIMEOUT_SECONDS: u64 = 30;
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+const MAX_RESTART_DELAY_SECONDS: u64 = 60;
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_LOG_LINES: usize = 500;
+
+/// Registered servers and their processes. Server definitions are stored in
+/// `mcp_servers`; processes only live as long as the app.
+#[derive(Clone)]
 pub struct McpServerManager {
     servers: Arc<Mutex<HashMap<String, McpServerInstance>>>,
     app_handle: AppHandle,
+    pool: DbPool,
 }
 
 struct McpServerInstance {
     server: McpServer,
     process: Option<std::process::Child>,
     stdin: Option<std::process::ChildStdin>,
+    /// Bumped on every launch so monitors of a previous process exit
+    generation: u64,
+    restart_attempts: u32,
+    /// Id of the last health probe that has not been answered yet
+    pending_ping: Option<String>,
+    /// Requests waiting for a response, keyed by JSON-RPC id
+    pending_requests: HashMap<String, oneshot::Sender<Value>>,
+    /// Most recent stderr lines, kept across restarts so a crash can be diagnosed
+    logs: VecDeque<String>,
 }
 
 impl McpServerManager {
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(app_handle: AppHandle, pool: DbPool) -> Self {
         Self {
             servers: Arc::new(Mutex::new(HashMap::new())),
             app_handle,
+            pool,
         }
     }
 
-    pub fn register_server(&self, server: McpServer) -> Result<String, String> {
+    /// Register the stored servers, stopped. Returns how many there were.
+    pub async fn load_servers(&self) -> Result<usize, sqlx::Error> {
+        let rows = sqlx::query_as::<_, McpServerRow>("SELECT * FROM mcp_servers ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let count = rows.len();
+        for row in rows {
+            self.insert_instance(McpServer::from(row));
+        }
+
+        Ok(count)
+    }
+
+    /// Store a server and register it
+    pub async fn register_server(&self, server: McpServer) -> Result<String, String> {
+        sqlx::query(
+            r#"
+            INSERT INTO mcp_servers (id, project_id, name, command, args, env, auto_restart,
+                                     health_check_interval_seconds, request_timeout_seconds, log_lines, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
+            "#,
+        )
+        .bind(&server.id)
+        .bind(&server.project_id)
+        .bind(&server.name)
+        .bind(&server.command)
+        .bind(serde_json::to_string(&server.args).map_err(|e| e.to_string())?)
+        .bind(serde_json::to_string(&server.env).map_err(|e| e.to_string())?)
+        .bind(server.auto_restart)
+        .bind(server.health_check_interval_seconds.map(|s| s as i64))
+        .bind(server.request_timeout_seconds.map(|s| s as i64))
+        .bind(server.log_lines.map(|n| n as i64))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to store MCP server: {}", e))?;
+
         let server_id = server.id.clone();
+        self.insert_instance(server);
+        Ok(server_id)
+    }
+
+    fn insert_instance(&self, server: McpServer) {
         let mut servers = self.servers.lock().unwrap();
-        
-        servers.insert(server_id.clone(), McpServerInstance {
+        servers.insert(server.id.clone(), McpServerInstance {
             server,
             process: None,
             stdin: None,
+            generation: 0,
+            restart_attempts: 0,
+            pending_ping: None,
+            pending_requests: HashMap::new(),
+            logs: VecDeque::new(),
         });
+    }
 
-        Ok(server_id)
+    /// Stop and forget a server. Project associations are pruned on the next execution
+    pub async fn unregister_server(&self, server_id: &str) -> Result<(), String> {
+        self.remove_instance(server_id)?;
+
+        sqlx::query("DELETE FROM mcp_servers WHERE id = ?")
+            .bind(server_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to delete MCP server: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Stop and forget the servers of a deleted project; its rows go with the project
+    pub fn unregister_project_servers(&self, project_id: &str) {
+        let server_ids: Vec<String> = self.list_servers(Some(project_id))
+            .into_iter()
+            .filter(|server| server.project_id.as_deref() == Some(project_id))
+            .map(|server| server.id)
+            .collect();
+        for server_id in server_ids {
+            let _ = self.remove_instance(&server_id);
+        }
+    }
+
+    fn remove_instance(&self, server_id: &str) -> Result<(), String> {
+        let mut servers = self.servers.lock().unwrap();
+        let mut instance = servers.remove(server_id)
+            .ok_or_else(|| "Server not found".to_string())?;
+
+        if let Some(mut process) = instance.process.take() {
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+
+        Ok(())
     }
 
     pub fn start_server(&self, server_id: &str) -> Result<(), String> {
+        {
+            let mut servers = self.servers.lock().unwrap();
+            let instance = servers.get_mut(server_id)
+                .ok_or_else(|| "Server not found".to_string())?;
+
+            if instance.process.is_some() {
+                return Err("Server already running".to_string());
+            }
+
+            instance.restart_attempts = 0;
+            self.launch(server_id, instance)?;
+        }
+
+        // Initialize the server once the lock is released, post_request takes it again
+        self.initialize_server(server_id)?;
+
+        Ok(())
+    }
+
+    pub fn stop_server(&self, server_id: &str) -> Result<(), String> {
         let mut servers = self.servers.lock().unwrap();
         let instance = servers.get_mut(server_id)
             .ok_or_else(|| "Server not found".to_string())?;
 
-        if instance.process.is_some() {
-            return Err("Server already running".to_string());
+        if let Some(mut process) = instance.process.take() {
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+
+        instance.stdin = None;
+        instance.pending_ping = None;
+        // Dropping the senders fails the waiting requests right away
+        instance.pending_requests.clear();
+        self.set_status(instance, McpServerStatus::Stopped);
+
+        Ok(())
+    }
+
+    pub fn restart_server(&self, server_id: &str) -> Result<(), String> {
+        self.stop_server(server_id)?;
+        self.start_server(server_id)
+    }
+
+    /// Send a JSON-RPC request and wait for its result. Responses are matched by
+    /// id, so concurrent requests to the same server never see each other's answers.
+    pub async fn send_request(
+        &self,
+        server_id: &str,
+        method: &str,
+        params: Option<Value>,
+        timeout: Option<Duration>,
+    ) -> Result<Value, String> {
+        let (sender, receiver) = oneshot::channel();
+
+        let (request_id, server_name, timeout) = {
+            let mut servers = self.servers.lock().unwrap();
+            let instance = servers.get_mut(server_id)
+                .ok_or_else(|| "Server not found".to_string())?;
+
+            // The reader thread needs this lock to dispatch, so the response
+            // cannot arrive before the sender is registered
+            let request_id = Self::write_running_request(instance, method, params)?;
+            instance.pending_requests.insert(request_id.clone(), sender);
+
+            let timeout = timeout.unwrap_or_else(|| Duration::from_secs(
+                instance.server.request_timeout_seconds.unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECONDS),
+            ));
+            (request_id, instance.server.name.clone(), timeout)
+        };
+
+        let response = match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => {
+                return Err(format!("MCP server {} stopped before answering {}", server_name, method));
+            }
+            Err(_) => {
+                if let Some(instance) = self.servers.lock().unwrap().get_mut(server_id) {
+                    instance.pending_requests.remove(&request_id);
+                }
+                return Err(format!(
+                    "MCP request {} to {} timed out after {}s",
+                    method,
+                    server_name,
+                    timeout.as_secs()
+                ));
+            }
+        };
+
+        if let Some(error) = response.get("error") {
+            let message = error.get("message")
+                .and_then(|m| m.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| error.to_string());
+            return Err(format!("MCP server {} returned an error: {}", server_name, message));
+        }
+
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Invoke a tool via `tools/call` and return the `content` of its result.
+    /// Results flagged with `isError` are turned into an error with their text.
+    pub async fn call_tool(&self, server_id: &str, tool_name: &str, arguments: Value) -> Result<Value, String> {
+        let result = self.send_request(server_id, "tools/call", Some(json!({
+            "name": tool_name,
+            "arguments": arguments,
+        })), None).await?;
+
+        let content = result.get("content").cloned().unwrap_or_else(|| json!([]));
+
+        if result.get("isError").and_then(|e| e.as_bool()).unwrap_or(false) {
+            let text = content.as_array()
+                .map(|items| items.iter()
+                    .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+                .unwrap_or_default();
+            return Err(format!("Tool {} failed: {}", tool_name, text));
         }
 
-        // Update status
-        instance.server.status = McpServerStatus::Starting;
-        self.emit_server_status(&instance.server);
+        Ok(content)
+    }
+
+    /// Send a request without waiting; its response is emitted as `mcp-message`
+    fn post_request(&self, server_id: &str, method: &str, params: Option<Value>) -> Result<String, String> {
+        let mut servers = self.servers.lock().unwrap();
+        let instance = servers.get_mut(server_id)
+            .ok_or_else(|| "Server not found".to_string())?;
+
+        Self::write_running_request(instance, method, params)
+    }
+
+    /// Fail fast instead of writing to the stdin of a dead process
+    fn write_running_request(instance: &mut McpServerInstance, method: &str, params: Option<Value>) -> Result<String, String> {
+        match &instance.server.status {
+            McpServerStatus::Running => {}
+            McpServerStatus::Error(reason) => {
+                return Err(format!("MCP server {} is down: {}", instance.server.name, reason));
+            }
+            _ => return Err("Server not running".to_string()),
+        }
+
+        Self::write_request(instance, method, params)
+            .map_err(|e| format!("MCP server {} is not responding: {}", instance.server.name, e))
+    }
+
+    /// Every server, as the settings list them, or with `project_id` the ones
+    /// available to that project: global servers and its own
+    pub fn list_servers(&self, project_id: Option<&str>) -> Vec<McpServer> {
+        let servers = self.servers.lock().unwrap();
+        servers.values()
+            .map(|instance| &instance.server)
+            .filter(|server| match (project_id, server.project_id.as_deref()) {
+                (Some(project_id), Some(server_project)) => server_project == project_id,
+                _ => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Servers an agent working on `project_id` may use: the global ones and
+    /// the project's own. Another project's servers are never included, also
+    /// when the project is unknown.
+    pub fn list_agent_servers(&self, project_id: Option<&str>) -> Vec<McpServer> {
+        let servers = self.servers.lock().unwrap();
+        servers.values()
+            .map(|instance| &instance.server)
+            .filter(|server| server.is_available_to(project_id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_server(&self, server_id: &str) -> Option<McpServer> {
+        let servers = self.servers.lock().unwrap();
+        servers.get(server_id).map(|instance| instance.server.clone())
+    }
+
+    /// The last `tail` lines the server wrote to stderr, oldest first
+    pub fn get_server_logs(&self, server_id: &str, tail: usize) -> Result<Vec<String>, String> {
+        let servers = self.servers.lock().unwrap();
+        let instance = servers.get(server_id)
+            .ok_or_else(|| "Server not found".to_string())?;
+
+        let skip = instance.logs.len().saturating_sub(tail);
+        Ok(instance.logs.iter().skip(skip).cloned().collect())
+    }
+
+    fn append_log(&self, server_id: &str, line: String) {
+        let mut servers = self.servers.lock().unwrap();
+        let Some(instance) = servers.get_mut(server_id) else {
+            return;
+        };
+
+        let capacity = instance.server.log_lines.unwrap_or(DEFAULT_LOG_LINES);
+        instance.logs.push_back(line);
+        while instance.logs.len() > capacity {
+            instance.logs.pop_front();
+        }
+    }
+
+    /// Spawn the server process along with its output readers and monitor.
+    /// Callers hold the lock and must call `initialize_server` after releasing it.
+    fn launch(&self, server_id: &str, instance: &mut McpServerInstance) -> Result<(), String> {
+        self.set_status(instance, McpServerStatus::Starting);
 
         // Start process
         let mut cmd = Command::new(&instance.server.command);
@@ -93,8 +494,14 @@ impl McpServerManager {
             cmd.env(key, value);
         }
 
-        let mut child = cmd.spawn()
-            .map_err(|e| format!("Failed to start server: {}", e))?;
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                let error = format!("Failed to start server: {}", e);
+                self.set_status(instance, McpServerStatus::Error(error.clone()));
+                return Err(error);
+            }
+        };
 
         let stdin = child.stdin.take();
         let stdout = child.stdout.take()
@@ -104,16 +511,13 @@ impl McpServerManager {
 
         // Handle stdout
         let server_id_clone = server_id.to_string();
-        let app_handle_clone = self.app_handle.clone();
+        let manager = self.clone();
         thread::spawn(move || {
             let reader = BufReader::new(stdout);
             for line in reader.lines() {
                 if let Ok(line) = line {
                     if let Ok(msg) = serde_json::from_str::<Value>(&line) {
-                        let _ = app_handle_clone.emit("mcp-message", json!({
-                            "server_id": server_id_clone,
-                            "message": msg,
-                        }));
+                        manager.dispatch_message(&server_id_clone, msg);
                     }
                 }
             }
@@ -121,94 +525,224 @@ impl McpServerManager {
 
         // Handle stderr
         let server_id_clone = server_id.to_string();
-        let app_handle_clone = self.app_handle.clone();
+        let manager = self.clone();
         thread::spawn(move || {
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 if let Ok(line) = line {
-                    let _ = app_handle_clone.emit("mcp-error", json!({
+                    let _ = manager.app_handle.emit("mcp-error", json!({
                         "server_id": server_id_clone,
                         "error": line,
                     }));
+                    manager.append_log(&server_id_clone, line);
                 }
             }
         });
 
         instance.process = Some(child);
         instance.stdin = stdin;
-        instance.server.status = McpServerStatus::Running;
-        self.emit_server_status(&instance.server);
+        instance.pending_ping = None;
+        instance.generation += 1;
+        self.set_status(instance, McpServerStatus::Running);
 
-        // Initialize the server
-        self.initialize_server(server_id)?;
+        let manager = self.clone();
+        let server_id_clone = server_id.to_string();
+        let generation = instance.generation;
+        thread::spawn(move || manager.monitor(&server_id_clone, generation));
 
         Ok(())
     }
 
-    pub fn stop_server(&self, server_id: &str) -> Result<(), String> {
-        let mut servers = self.servers.lock().unwrap();
-        let instance = servers.get_mut(server_id)
-            .ok_or_else(|| "Server not found".to_string())?;
+    /// Watch one launch of a server: notice when the process exits and probe it
+    /// with `ping` so a hung server is treated like a crashed one
+    fn monitor(&self, server_id: &str, generation: u64) {
+        let mut last_probe = Instant::now();
+
+        loop {
+            thread::sleep(MONITOR_POLL_INTERVAL);
+
+            let failure = {
+                let mut servers = self.servers.lock().unwrap();
+                let instance = match servers.get_mut(server_id) {
+                    Some(instance) if instance.generation == generation => instance,
+                    _ => return,
+                };
+                let interval = instance.server.health_check_interval_seconds
+                    .unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL_SECONDS);
+                let process = match instance.process.as_mut() {
+                    Some(process) => process,
+                    // Stopped on purpose
+                    None => return,
+                };
+
+                match process.try_wait() {
+                    Ok(Some(exit_status)) => Some(format!("Process exited ({})", exit_status)),
+                    Err(e) => Some(format!("Failed to check process: {}", e)),
+                    Ok(None) if interval > 0 && last_probe.elapsed() >= Duration::from_secs(interval) => {
+                        last_probe = Instant::now();
+                        if instance.pending_ping.is_some() {
+                            Some(format!("No response to health check within {}s", interval))
+                        } else {
+                            match Self::write_request(instance, "ping", None) {
+                                Ok(ping_id) => {
+                                    instance.pending_ping = Some(ping_id);
+                                    None
+                                }
+                                Err(e) => Some(e),
+                            }
+                        }
+                    }
+                    Ok(None) => None,
+                }
+            };
 
-        if let Some(mut process) = instance.process.take() {
-            let _ = process.kill();
-            let _ = process.wait();
+            if let Some(reason) = failure {
+                self.handle_failure(server_id, generation, reason);
+                return;
+            }
         }
+    }
 
-        instance.stdin = None;
-        instance.server.status = McpServerStatus::Stopped;
-        self.emit_server_status(&instance.server);
+    /// Mark a failed server as errored and relaunch it with exponential backoff
+    /// when `auto_restart` is set, giving up after `MAX_RESTART_ATTEMPTS`
+    fn handle_failure(&self, server_id: &str, generation: u64, reason: String) {
+        let mut reason = reason;
+
+        loop {
+            let delay = {
+                let mut servers = self.servers.lock().unwrap();
+                let instance = match servers.get_mut(server_id) {
+                    Some(instance) if instance.generation == generation => instance,
+                    _ => return,
+                };
+
+                if let Some(mut process) = instance.process.take() {
+                    let _ = process.kill();
+                    let _ = process.wait();
+                }
+                instance.stdin = None;
+                instance.pending_ping = None;
+                instance.pending_requests.clear();
 
-        Ok(())
-    }
+                log::warn!("MCP server {} failed: {}", instance.server.name, reason);
+                self.set_status(instance, McpServerStatus::Error(reason.clone()));
 
-    pub fn send_request(
-        &self,
-        server_id: &str,
-        method: &str,
-        params: Option<Value>,
-    ) -> Result<String, String> {
-        let mut servers = self.servers.lock().unwrap();
-        let instance = servers.get_mut(server_id)
-            .ok_or_else(|| "Server not found".to_string())?;
+                if !instance.server.auto_restart {
+                    return;
+                }
+                if instance.restart_attempts >= MAX_RESTART_ATTEMPTS {
+                    log::error!("Giving up on MCP server {} after {} restarts", instance.server.name, instance.restart_attempts);
+                    return;
+                }
 
-        if let Some(stdin) = &mut instance.stdin {
-            let request_id = Uuid::new_v4().to_string();
-            let request = json!({
-                "jsonrpc": "2.0",
-                "id": request_id,
-                "method": method,
-                "params": params.unwrap_or(json!({})),
-            });
+                instance.restart_attempts += 1;
+                Duration::from_secs((1u64 << (instance.restart_attempts - 1)).min(MAX_RESTART_DELAY_SECONDS))
+            };
 
-            let mut request_str = serde_json::to_string(&request)
-                .map_err(|e| format!("Failed to serialize request: {}", e))?;
-            request_str.push('\n');
+            thread::sleep(delay);
 
-            stdin.write_all(request_str.as_bytes())
-                .map_err(|e| format!("Failed to write to stdin: {}", e))?;
-            stdin.flush()
-                .map_err(|e| format!("Failed to flush stdin: {}", e))?;
+            let relaunched = {
+                let mut servers = self.servers.lock().unwrap();
+                let instance = match servers.get_mut(server_id) {
+                    Some(instance) if instance.generation == generation => instance,
+                    _ => return,
+                };
 
-            Ok(request_id)
-        } else {
-            Err("Server not running".to_string())
+                // The user stopped or restarted the server while we were waiting
+                if instance.process.is_some() || !matches!(instance.server.status, McpServerStatus::Error(_)) {
+                    return;
+                }
+
+                log::info!("Restarting MCP server {} (attempt {})", instance.server.name, instance.restart_attempts);
+                self.launch(server_id, instance)
+            };
+
+            match relaunched {
+                Ok(()) => {
+                    let _ = self.initialize_server(server_id);
+                    return;
+                }
+                Err(e) => reason = e,
+            }
         }
     }
 
-    pub fn list_servers(&self) -> Vec<McpServer> {
-        let servers = self.servers.lock().unwrap();
-        servers.values().map(|instance| instance.server.clone()).collect()
+    /// Route a message read from the server. Responses go to the request waiting
+    /// for them, notifications are emitted as `mcp-notification` and anything else
+    /// (server-initiated requests, responses to posted requests) as `mcp-message`.
+    fn dispatch_message(&self, server_id: &str, msg: Value) {
+        let method = msg.get("method").and_then(|m| m.as_str());
+        let id = msg.get("id").and_then(|id| id.as_str());
+
+        match (method, id) {
+            (None, Some(id)) => {
+                let waiting = {
+                    let mut servers = self.servers.lock().unwrap();
+                    let instance = match servers.get_mut(server_id) {
+                        Some(instance) => instance,
+                        None => return,
+                    };
+
+                    // An answer to our health probe proves the server is healthy,
+                    // so the restart budget is refilled
+                    if instance.pending_ping.as_deref() == Some(id) {
+                        instance.pending_ping = None;
+                        instance.restart_attempts = 0;
+                        return;
+                    }
+
+                    instance.pending_requests.remove(id)
+                };
+
+                if let Some(sender) = waiting {
+                    let _ = sender.send(msg);
+                    return;
+                }
+            }
+            (Some(method), None) => {
+                let _ = self.app_handle.emit("mcp-notification", json!({
+                    "server_id": server_id,
+                    "method": method,
+                    "params": msg.get("params").cloned().unwrap_or(Value::Null),
+                }));
+                return;
+            }
+            _ => {}
+        }
+
+        let _ = self.app_handle.emit("mcp-message", json!({
+            "server_id": server_id,
+            "message": msg,
+        }));
     }
 
-    pub fn get_server(&self, server_id: &str) -> Option<McpServer> {
-        let servers = self.servers.lock().unwrap();
-        servers.get(server_id).map(|instance| instance.server.clone())
+    fn write_request(instance: &mut McpServerInstance, method: &str, params: Option<Value>) -> Result<String, String> {
+        let stdin = instance.stdin.as_mut()
+            .ok_or_else(|| "Server not running".to_string())?;
+
+        let request_id = Uuid::new_v4().to_string();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "method": method,
+            "params": params.unwrap_or(json!({})),
+        });
+
+        let mut request_str = serde_json::to_string(&request)
+            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+        request_str.push('\n');
+
+        stdin.write_all(request_str.as_bytes())
+            .map_err(|e| format!("Failed to write to stdin: {}", e))?;
+        stdin.flush()
+            .map_err(|e| format!("Failed to flush stdin: {}", e))?;
+
+        Ok(request_id)
     }
 
     fn initialize_server(&self, server_id: &str) -> Result<(), String> {
         // Send initialize request
-        self.send_request(server_id, "initialize", Some(json!({
+        self.post_request(server_id, "initialize", Some(json!({
             "protocolVersion": "2024-11-05",
             "capabilities": {
                 "tools": true,
@@ -224,6 +758,19 @@ impl McpServerManager {
         Ok(())
     }
 
+    fn set_status(&self, instance: &mut McpServerInstance, status: McpServerStatus) {
+        let previous_status = std::mem::replace(&mut instance.server.status, status);
+        self.emit_server_status(&instance.server);
+
+        if previous_status != instance.server.status {
+            let _ = self.app_handle.emit("mcp-server-status-changed", json!({
+                "server_id": instance.server.id,
+                "previous_status": previous_status,
+                "status": instance.server.status,
+            }));
+        }
+    }
+
     fn emit_server_status(&self, server: &McpServer) {
         let _ = self.app_handle.emit("mcp-server-status", server);
     }
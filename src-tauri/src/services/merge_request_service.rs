@@ -1,5 +1,14 @@
-use crate::models::{MergeRequest, MergeRequestRow, CreateMergeRequestData};
+use crate::models::{MergeRequest, MergeRequestRow, CreateMergeRequestData, DiffMode, GitProvider, GitRemoteInfo, MergeRequestDraft, MergeRequestInfo, TaskEventKind};
+use crate::repository::TaskRepository;
+use crate::services::coding_agent_executor::claude_agent::ClaudeCodeAgent;
+use crate::services::{GitHubService, GitLabService, GitPlatformService, GitService};
 use sqlx::SqlitePool;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long the coding agent gets to rewrite a generated PR/MR description
+const MERGE_REQUEST_POLISH_TIMEOUT: Duration = Duration::from_secs(120);
 
 pub struct MergeRequestService {
     pool: SqlitePool,
@@ -18,8 +27,8 @@ impl MergeRequestService {
                 task_attempt_id, provider, mr_id, mr_iid, mr_number,
                 title, description, state, source_branch, target_branch,
                 web_url, merge_status, has_conflicts, pipeline_status, pipeline_url,
-                created_at, updated_at, merged_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                pipeline_jobs, created_at, updated_at, merged_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#;
 
         sqlx::query(query)
@@ -38,6 +47,7 @@ impl MergeRequestService {
             .bind(data.has_conflicts)
             .bind(&data.pipeline_status)
             .bind(&data.pipeline_url)
+            .bind(serde_json::to_string(&data.pipeline_jobs)?)
             .bind(data.created_at.to_rfc3339())
             .bind(data.updated_at.to_rfc3339())
             .bind(data.merged_at.map(|dt| dt.to_rfc3339()))
@@ -51,8 +61,19 @@ impl MergeRequestService {
         .bind(data.mr_id)
         .fetch_one(&mut *conn)
         .await?;
+        drop(conn);
 
-        Ok(mr_row.into())
+        let mr: MergeRequest = mr_row.into();
+        crate::services::TaskService::new(self.pool.clone())
+            .record_attempt_event(&mr.task_attempt_id, TaskEventKind::MergeRequestCreated {
+                provider: mr.provider.clone(),
+                number: mr.mr_number,
+                title: mr.title.clone(),
+                url: mr.web_url.clone(),
+            })
+            .await;
+
+        Ok(mr)
     }
 
     pub async fn update_merge_request(&self, mr_id: i64, data: CreateMergeRequestData) -> Result<MergeRequest, Box<dyn std::error::Error + Send + Sync>> {
@@ -62,7 +83,7 @@ impl MergeRequestService {
             UPDATE merge_requests SET
                 title = ?, description = ?, state = ?, 
                 merge_status = ?, has_conflicts = ?, 
-                pipeline_status = ?, pipeline_url = ?,
+                pipeline_status = ?, pipeline_url = ?, pipeline_jobs = ?,
                 updated_at = ?, merged_at = ?, synced_at = CURRENT_TIMESTAMP
             WHERE id = ?
         "#;
@@ -75,6 +96,7 @@ impl MergeRequestService {
             .bind(data.has_conflicts)
             .bind(&data.pipeline_status)
             .bind(&data.pipeline_url)
+            .bind(serde_json::to_string(&data.pipeline_jobs)?)
             .bind(data.updated_at.to_rfc3339())
             .bind(data.merged_at.map(|dt| dt.to_rfc3339()))
             .bind(mr_id)
@@ -173,4 +195,273 @@ impl MergeRequestService {
         
         Ok(rows.into_iter().map(Into::into).collect())
     }
-}
\ No newline at end of file
+
+    /// Open MRs/PRs of all tasks in a project
+    pub async fn get_open_merge_requests_for_project(&self, project_id: &str) -> Result<Vec<MergeRequest>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.pool.acquire().await?;
+        
+        let rows = sqlx::query_as::<_, MergeRequestRow>(
+            r#"
+            SELECT mr.* FROM merge_requests mr
+            JOIN task_attempts ta ON ta.id = mr.task_attempt_id
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE t.project_id = ? AND mr.state IN ('opened', 'open')
+            ORDER BY mr.created_at DESC
+            "#
+        )
+        .bind(project_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Store the state, merge and pipeline status of a refreshed MR. An MR
+    /// that was just merged or closed is added to its task's timeline.
+    pub async fn update_sync_status(&self, mr: &MergeRequest) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let previous_state: Option<String> = sqlx::query_scalar("SELECT state FROM merge_requests WHERE id = ?")
+            .bind(mr.id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let query = r#"
+            UPDATE merge_requests SET
+                state = ?, 
+                merge_status = ?, 
+                has_conflicts = ?, 
+                pipeline_status = ?,
+                pipeline_url = ?,
+                pipeline_jobs = ?,
+                synced_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+        "#;
+
+        let pipeline_jobs = serde_json::to_string(&mr.pipeline_jobs)?;
+        
+        // Syncs of a whole project run concurrently with process output and conversation writes
+        crate::db::retry_on_busy(|| {
+            sqlx::query(query)
+                .bind(&mr.state)
+                .bind(&mr.merge_status)
+                .bind(mr.has_conflicts)
+                .bind(&mr.pipeline_status)
+                .bind(&mr.pipeline_url)
+                .bind(&pipeline_jobs)
+                .bind(mr.id)
+                .execute(&self.pool)
+        })
+        .await?;
+        
+        if previous_state.as_deref() != Some(mr.state.as_str()) {
+            let (provider, number, title, url) = (mr.provider.clone(), mr.mr_number, mr.title.clone(), mr.web_url.clone());
+            let kind = match mr.state.as_str() {
+                "merged" => Some(TaskEventKind::MergeRequestMerged { provider, number, title, url }),
+                "closed" => Some(TaskEventKind::MergeRequestClosed { provider, number, title, url }),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                crate::services::TaskService::new(self.pool.clone())
+                    .record_attempt_event(&mr.task_attempt_id, kind)
+                    .await;
+            }
+        }
+            
+        Ok(())
+    }
+
+    /// Store the number of approvals of an attempt's MR, identified by its iid
+    pub async fn update_approval_count(&self, task_attempt_id: &str, provider: &str, mr_iid: i64, approval_count: i64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            "UPDATE merge_requests SET approval_count = ? WHERE task_attempt_id = ? AND provider = ? AND mr_iid = ?"
+        )
+        .bind(approval_count)
+        .bind(task_attempt_id)
+        .bind(provider)
+        .bind(mr_iid)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Refresh every open MR/PR of a project from its provider, all at once.
+    /// MRs of a provider without a service are left alone, and MRs that fail to
+    /// refresh are logged and skipped. Returns the MRs that were updated.
+    pub async fn sync_all_for_project(
+        &self,
+        project_id: &str,
+        gitlab_service: Option<&GitLabService>,
+        github_service: Option<&GitHubService>,
+    ) -> Result<Vec<MergeRequest>, Box<dyn std::error::Error + Send + Sync>> {
+        let gitlab = gitlab_service.map(|service| Arc::new(service.clone()));
+        let github = github_service.map(|service| Arc::new(service.clone()));
+        
+        let mut handles = Vec::new();
+        for mr in self.get_open_merge_requests_for_project(project_id).await? {
+            let service = MergeRequestService::new(self.pool.clone());
+            let gitlab = gitlab.clone();
+            let github = github.clone();
+            handles.push(tokio::spawn(async move {
+                let info = match (mr.provider.as_str(), gitlab, github) {
+                    ("gitlab", Some(gitlab), _) => {
+                        let remote_info = remote_info_from_web_url(GitProvider::GitLab, &mr.web_url)?;
+                        gitlab.update_merge_request_status(&remote_info, mr.mr_iid).await?
+                    }
+                    ("github", _, Some(github)) => {
+                        let remote_info = remote_info_from_web_url(GitProvider::GitHub, &mr.web_url)?;
+                        github.update_merge_request_status(&remote_info, mr.mr_number).await?
+                    }
+                    _ => return Ok(None),
+                };
+                
+                let updated = apply_provider_status(&mr, info);
+                service.update_sync_status(&updated).await?;
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(Some(updated))
+            }));
+        }
+        
+        let mut updated = Vec::new();
+        for handle in handles {
+            match handle.await {
+                Ok(Ok(Some(mr))) => updated.push(mr),
+                Ok(Ok(None)) => {}
+                Ok(Err(e)) => log::warn!("Failed to sync merge request of project {}: {}", project_id, e),
+                Err(e) => log::error!("Merge request sync task failed: {}", e),
+            }
+        }
+        
+        Ok(updated)
+    }
+
+    /// Draft a PR/MR for an attempt from its task, the commits on its branch
+    /// since the base commit and the files they changed. With `polish` the
+    /// description is rewritten by Claude Code; if that fails the generated one
+    /// is kept.
+    pub async fn generate_merge_request_content(
+        &self,
+        task_attempt_id: &str,
+        polish: bool,
+    ) -> Result<MergeRequestDraft, Box<dyn std::error::Error + Send + Sync>> {
+        let attempt_uuid = uuid::Uuid::parse_str(task_attempt_id)?;
+        let attempt = TaskRepository::get_attempt(&self.pool, attempt_uuid)
+            .await?
+            .ok_or("Task attempt not found")?;
+        let task = TaskRepository::get_task(&self.pool, uuid::Uuid::parse_str(&attempt.task_id)?)
+            .await?
+            .ok_or("Task not found")?;
+        
+        let worktree = Path::new(&attempt.worktree_path);
+        if attempt.worktree_path.is_empty() || !worktree.exists() {
+            return Err("The attempt has no worktree to read its changes from".into());
+        }
+        let base = attempt.base_commit.clone().unwrap_or_else(|| attempt.base_branch.clone());
+        
+        let commits = GitService::list_commits_between(worktree, &base, "HEAD")?;
+        if commits.is_empty() {
+            return Err(format!(
+                "Branch '{}' has no commits since '{}'. Commit the changes before creating a merge request.",
+                attempt.branch, attempt.base_branch
+            ).into());
+        }
+        let diff = GitService::new().get_comprehensive_diff(worktree, DiffMode::BranchChanges { base_commit: base })?;
+        
+        let mut description = String::new();
+        if let Some(task_description) = task.description.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+            description.push_str(task_description);
+            description.push_str("\n\n");
+        }
+        description.push_str("## Commits\n\n");
+        for commit in &commits {
+            let short_sha: String = commit.sha.chars().take(8).collect();
+            description.push_str(&format!("- {} ({})\n", commit.message, short_sha));
+        }
+        description.push_str(&format!(
+            "\n## Files changed\n\n{} files, +{} -{}\n\n",
+            diff.stats.files_changed, diff.stats.additions, diff.stats.deletions
+        ));
+        for file in &diff.files {
+            description.push_str(&format!("- `{}` (+{} -{})\n", file.path, file.additions, file.deletions));
+        }
+        
+        let mut draft = MergeRequestDraft {
+            title: task.title.trim().to_string(),
+            description: description.trim_end().to_string(),
+            commit_count: commits.len(),
+            polished: false,
+        };
+        
+        if polish {
+            let prompt = format!(
+                "Rewrite the following pull request description so it is clear and concise for reviewers. \
+                 Keep it in markdown, keep the list of changed files, and reply with the description only.\n\n\
+                 Title: {}\n\n{}",
+                draft.title, draft.description
+            );
+            match ClaudeCodeAgent::complete(&prompt, &attempt.worktree_path, MERGE_REQUEST_POLISH_TIMEOUT).await {
+                Ok(polished) if !polished.is_empty() => {
+                    draft.description = polished;
+                    draft.polished = true;
+                }
+                Ok(_) => log::warn!("Coding agent returned an empty description for attempt {}", task_attempt_id),
+                Err(e) => log::warn!("Failed to polish merge request description for attempt {}: {}", task_attempt_id, e),
+            }
+        }
+        
+        Ok(draft)
+    }
+}
+
+/// `mr` with the state, merge and pipeline status reported by its provider
+pub fn apply_provider_status(mr: &MergeRequest, info: MergeRequestInfo) -> MergeRequest {
+    let mut updated = mr.clone();
+    updated.state = match info.state {
+        crate::models::MergeRequestState::Opened => "opened".to_string(),
+        crate::models::MergeRequestState::Closed => "closed".to_string(),
+        crate::models::MergeRequestState::Merged => "merged".to_string(),
+        crate::models::MergeRequestState::Locked => "locked".to_string(),
+    };
+    
+    if let Some(merge_status) = info.merge_status {
+        updated.merge_status = Some(format!("{:?}", merge_status));
+    }
+    updated.has_conflicts = info.has_conflicts;
+    
+    if let Some(pipeline_status) = info.pipeline_status {
+        updated.pipeline_status = Some(format!("{:?}", pipeline_status));
+    }
+    updated.pipeline_url = info.pipeline_url;
+    updated.pipeline_jobs = info.pipeline_jobs;
+    
+    updated
+}
+
+/// Repository of an MR/PR from its web URL, e.g. https://gitlab.com/owner/repo/-/merge_requests/123
+/// or https://github.com/owner/repo/pull/123. Hosts other than the public ones are kept.
+pub fn remote_info_from_web_url(provider: GitProvider, web_url: &str) -> Result<GitRemoteInfo, Box<dyn std::error::Error + Send + Sync>> {
+    let url = reqwest::Url::parse(web_url)?;
+    let host = url.host_str().ok_or("Invalid URL: no host")?;
+    
+    let path_segments: Vec<&str> = url.path_segments()
+        .ok_or("Invalid URL: no path")?
+        .collect();
+    
+    if path_segments.len() < 2 {
+        return Err(format!("Invalid {:?} URL format", provider).into());
+    }
+    
+    let public_host = match provider {
+        GitProvider::GitHub => "github.com",
+        _ => "gitlab.com",
+    };
+    
+    Ok(GitRemoteInfo {
+        provider,
+        owner: path_segments[0].to_string(),
+        repo: path_segments[1].to_string(),
+        host: if host != public_host {
+            Some(format!("https://{}", host))
+        } else {
+            None
+        },
+    })
+}
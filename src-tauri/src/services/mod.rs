@@ -13,6 +13,10 @@ pub mod merge_request_service;
 pub mod file_watcher_service;
 pub mod command_service;
 pub mod vcs_sync_service;
+pub mod worktree_provisioner;
+pub mod worktree_usage;
+pub mod notification_service;
+pub mod logging_service;
 
 pub use task_service::*;
 pub use project_service::*;
@@ -28,4 +32,6 @@ pub use config_service::*;
 pub use merge_request_service::*;
 pub use file_watcher_service::*;
 pub use command_service::*;
-pub use vcs_sync_service::*;
\ No newline at end of file
+pub use vcs_sync_service::*;
+pub use worktree_provisioner::*;
+pub use logging_service::*;
\ No newline at end of file
@@ -0,0 +1,175 @@
+use crate::models::{NotificationConfig, StatusChangeTrigger, Task};
+use crate::services::{ConfigService, TaskService};
+use crate::window_manager::ProjectWindowManager;
+use crate::AppState;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Listener, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Clicking a desktop notification only brings the app to the front, so a
+/// window focused this soon after a notification opens its task
+const CLICK_FOCUS_WINDOW: Duration = Duration::from_secs(30);
+
+/// The task of the last notification, opened when the app is focused soon after
+struct LastNotification {
+    project_id: String,
+    task_id: String,
+    sent_at: Instant,
+}
+
+/// Turns agent, task and merge request events into desktop notifications
+pub struct NotificationService {
+    app_handle: AppHandle,
+    task_service: Arc<TaskService>,
+    config_service: Arc<Mutex<ConfigService>>,
+    last_notification: std::sync::Mutex<Option<LastNotification>>,
+}
+
+impl NotificationService {
+    pub fn new(app_handle: AppHandle, task_service: Arc<TaskService>, config_service: Arc<Mutex<ConfigService>>) -> Self {
+        Self {
+            app_handle,
+            task_service,
+            config_service,
+            last_notification: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Listen for `execution:completed`, `task:status-changed` and
+    /// `vcs:merge-request-updated` for as long as the app runs
+    pub fn start(self: &Arc<Self>) {
+        for event_name in ["execution:completed", "task:status-changed", "vcs:merge-request-updated"] {
+            let service = self.clone();
+            self.app_handle.listen_any(event_name, move |event| {
+                let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+                    return;
+                };
+                let service = service.clone();
+                tauri::async_runtime::spawn(async move {
+                    service.handle_event(event_name, payload).await;
+                });
+            });
+        }
+    }
+
+    async fn handle_event(&self, event_name: &str, payload: Value) {
+        let config = self.config_service.lock().await.get_notification_config().clone();
+        let notification = match event_name {
+            "execution:completed" => self.execution_notification(&config, &payload).await,
+            "task:status-changed" => self.task_done_notification(&config, &payload).await,
+            "vcs:merge-request-updated" => self.merge_request_notification(&config, &payload).await,
+            _ => None,
+        };
+
+        if let Some((title, task)) = notification {
+            self.notify(title, &task);
+        }
+    }
+
+    async fn execution_notification(&self, config: &NotificationConfig, payload: &Value) -> Option<(&'static str, Task)> {
+        if !config.execution_completed {
+            return None;
+        }
+        // Stopped runs were stopped by the user, who already knows
+        let title = match payload["status"].as_str()? {
+            "success" | "completed" => "Agent finished",
+            "cancelled" => return None,
+            _ => "Agent failed",
+        };
+        Some((title, self.task(payload["taskId"].as_str()?).await?))
+    }
+
+    async fn task_done_notification(&self, config: &NotificationConfig, payload: &Value) -> Option<(&'static str, Task)> {
+        if !config.task_done || payload["newStatus"].as_str()? != "Done" {
+            return None;
+        }
+        let task = self.task(payload["taskId"].as_str()?).await?;
+        // The merge request notification already says so
+        if config.merge_request_updated && task.status_triggered_by == Some(StatusChangeTrigger::VcsMerged) {
+            return None;
+        }
+        Some(("Task done", task))
+    }
+
+    async fn merge_request_notification(&self, config: &NotificationConfig, payload: &Value) -> Option<(&'static str, Task)> {
+        if !config.merge_request_updated {
+            return None;
+        }
+        let merged = payload["new_state"] == "merged" && payload["previous_state"] != "merged";
+        let pipeline_failed = payload["new_pipeline_status"] == "Failed" && payload["previous_pipeline_status"] != "Failed";
+        let title = if merged {
+            "Merge request merged"
+        } else if pipeline_failed {
+            "Pipeline failed"
+        } else {
+            return None;
+        };
+
+        let attempt_id = Uuid::parse_str(payload["task_attempt_id"].as_str()?).ok()?;
+        let attempt = self.task_service.get_task_attempt(attempt_id).await.ok()??;
+        Some((title, self.task(&attempt.task_id).await?))
+    }
+
+    async fn task(&self, task_id: &str) -> Option<Task> {
+        let task_id = Uuid::parse_str(task_id).ok()?;
+        self.task_service.get_task(task_id).await.ok()?
+    }
+
+    /// Show a notification about a task unless the window showing its project,
+    /// or the main window when the project has none, is focused
+    fn notify(&self, title: &str, task: &Task) {
+        let window_label = ProjectWindowManager::window_label(&task.project_id);
+        let window = self.app_handle
+            .get_webview_window(&window_label)
+            .or_else(|| self.app_handle.get_webview_window("main"));
+        if window.is_some_and(|window| window.is_focused().unwrap_or(false)) {
+            return;
+        }
+
+        if let Err(e) = self.app_handle.notification().builder().title(title).body(&task.title).show() {
+            log::warn!("Failed to show notification for task {}: {}", task.id, e);
+            return;
+        }
+        // Focusing a window the user is already in says nothing about the notification
+        let app_focused = self.app_handle
+            .webview_windows()
+            .values()
+            .any(|window| window.is_focused().unwrap_or(false));
+        if app_focused {
+            return;
+        }
+        *self.last_notification.lock().unwrap() = Some(LastNotification {
+            project_id: task.project_id.clone(),
+            task_id: task.id.clone(),
+            sent_at: Instant::now(),
+        });
+    }
+
+    /// Called when any app window gains focus. Opens the task of a notification
+    /// sent moments ago, as that is most likely how the app got focused.
+    pub fn window_focused(&self) {
+        let Some(last) = self.last_notification.lock().unwrap().take() else {
+            return;
+        };
+        if last.sent_at.elapsed() > CLICK_FOCUS_WINDOW {
+            return;
+        }
+
+        let app_handle = self.app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app_handle.state::<AppState>();
+            let project = match Uuid::parse_str(&last.project_id) {
+                Ok(project_id) => state.project_service.get_project(project_id).await.ok().flatten(),
+                Err(_) => None,
+            };
+            let Some(project) = project else { return };
+            if let Err(e) = state.window_manager.open_project_window_at_task(&project.id, &project.name, &last.task_id).await {
+                log::warn!("Failed to open task {} from its notification: {}", last.task_id, e);
+            }
+        });
+    }
+}
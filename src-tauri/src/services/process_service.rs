@@ -1,13 +1,31 @@
 use crate::db::DbPool;
-use crate::models::{ExecutionProcess, ProcessStatus, ProcessType};
+use crate::models::{
+    CapturedLine, ExecutionProcess, OutputStreamType, ProcessOutputLine, ProcessOutputLineRow, ProcessOutputPage,
+    ProcessStatus, ProcessType, ProjectEnvironment, DEFAULT_ENV_FILES,
+};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::Emitter;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use uuid::Uuid;
 
+/// Captured output is written to the database in batches of about this size
+const OUTPUT_BATCH_BYTES: usize = 1024;
+/// ...or once the oldest line of a batch has waited this long
+const OUTPUT_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+/// How long a finished process's output may take to be stored before it's
+/// reported as finished anyway. A background child that inherited the pipes
+/// can keep them open after the process itself exits.
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Lines of output sent along with a failed setup script to show what went wrong
+const SETUP_FAILURE_OUTPUT_LINES: i64 = 20;
+
 pub struct ProcessService {
     pool: DbPool,
     running_processes: Arc<Mutex<std::collections::HashMap<Uuid, tokio::process::Child>>>,
@@ -21,17 +39,129 @@ impl ProcessService {
         }
     }
 
+    /// Append captured lines to a process's output. Output is written while
+    /// other services write too, so a busy database is retried rather than
+    /// dropping the lines.
+    pub async fn append_output(pool: &DbPool, process_id: Uuid, stream: OutputStreamType, lines: &[CapturedLine]) -> Result<(), sqlx::Error> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let process_id = process_id.to_string();
+        let process_id = process_id.as_str();
+        
+        crate::db::retry_on_busy(move || async move {
+            let mut tx = pool.begin().await?;
+            let next_line: i64 = sqlx::query_scalar(
+                "SELECT COALESCE(MAX(line_number) + 1, 0) FROM process_output_lines WHERE process_id = ? AND stream = ?"
+            )
+            .bind(process_id)
+            .bind(stream.as_str())
+            .fetch_one(&mut *tx)
+            .await?;
+            
+            for (index, line) in lines.iter().enumerate() {
+                sqlx::query(
+                    r#"
+                    INSERT INTO process_output_lines (process_id, stream, line_number, sequence, emitted_at, content)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(process_id)
+                .bind(stream.as_str())
+                .bind(next_line + index as i64)
+                .bind(line.sequence)
+                .bind(line.timestamp)
+                .bind(&line.content)
+                .execute(&mut *tx)
+                .await?;
+            }
+            
+            tx.commit().await
+        })
+        .await
+    }
+
+    /// Up to `limit` lines of a process's output starting at line `offset` (0-based)
+    pub async fn get_process_output(
+        &self,
+        process_id: Uuid,
+        stream: OutputStreamType,
+        offset: usize,
+        limit: usize,
+    ) -> Result<ProcessOutputPage, sqlx::Error> {
+        let process_id = process_id.to_string();
+        
+        let total_lines: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM process_output_lines WHERE process_id = ? AND stream = ?"
+        )
+        .bind(&process_id)
+        .bind(stream.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+        
+        let lines: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT content FROM process_output_lines
+            WHERE process_id = ? AND stream = ? AND line_number >= ?
+            ORDER BY line_number
+            LIMIT ?
+            "#,
+        )
+        .bind(&process_id)
+        .bind(stream.as_str())
+        .bind(offset as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(ProcessOutputPage {
+            next_offset: offset + lines.len(),
+            total_lines: total_lines as usize,
+            lines,
+        })
+    }
+
+    /// Everything a process wrote to stdout and stderr, interleaved in the order
+    /// it was read. Lines stored before sequences were recorded come stdout first.
+    pub async fn get_process_log(&self, process_id: Uuid) -> Result<Vec<ProcessOutputLine>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ProcessOutputLineRow>(
+            r#"
+            SELECT stream, line_number, sequence, emitted_at, content FROM process_output_lines
+            WHERE process_id = ?
+            ORDER BY sequence, CASE stream WHEN 'stdout' THEN 0 ELSE 1 END, line_number
+            "#,
+        )
+        .bind(process_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ProcessOutputLine::from).collect())
+    }
+
+    /// Spawn a managed process of a project. `task_attempt_id` is None for project-level scripts
     pub async fn spawn_process(
         &self,
-        task_attempt_id: Uuid,
+        project_id: &str,
+        task_attempt_id: Option<Uuid>,
         process_type: ProcessType,
         command: String,
         args: Vec<String>,
         working_directory: String,
+        env_files: Option<Vec<String>>,
         app_handle: tauri::AppHandle,
     ) -> Result<Uuid, Box<dyn std::error::Error>> {
         let id = Uuid::new_v4();
 
+        // Project environment variables, plus the env files of the working
+        // directory (`.env` and `.env.local` unless others are given)
+        let env_files = env_files
+            .unwrap_or_else(|| DEFAULT_ENV_FILES.iter().map(|file| file.to_string()).collect());
+        let env = crate::services::ProjectService::new(self.pool.clone())
+            .get_environment(project_id)
+            .await?
+            .with_env_files(std::path::Path::new(&working_directory), &env_files, false);
+        let logged_args: Vec<String> = args.iter().map(|arg| env.mask(arg)).collect();
+
         // Insert process record
         sqlx::query(
             r#"
@@ -40,11 +170,11 @@ impl ProcessService {
             "#,
         )
         .bind(id.to_string())
-        .bind(task_attempt_id.to_string())
+        .bind(task_attempt_id.map(|id| id.to_string()))
         .bind(format!("{:?}", process_type).to_lowercase())
         .bind(format!("{:?}", ProcessStatus::Running).to_lowercase())
-        .bind(&command)
-        .bind(serde_json::to_string(&args)?)
+        .bind(env.mask(&command))
+        .bind(serde_json::to_string(&logged_args)?)
         .bind(&working_directory)
         .execute(&self.pool)
         .await?;
@@ -53,143 +183,127 @@ impl ProcessService {
         let mut cmd = Command::new(&command);
         cmd.args(&args)
             .current_dir(&working_directory)
+            .envs(env.vars())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .stdin(Stdio::piped());
 
         let mut child = cmd.spawn()?;
 
-        // Handle stdout
+        // Both outputs share a line sequence so the combined log keeps the
+        // order lines were read in
+        let sequence = Arc::new(AtomicI64::new(0));
+        let mut readers = Vec::new();
         if let Some(stdout) = child.stdout.take() {
-            let reader = BufReader::new(stdout);
-            let pool = self.pool.clone();
-            let process_id = id;
-            let app = app_handle.clone();
-            
-            tokio::spawn(async move {
-                let mut lines = reader.lines();
-                let mut buffer = String::new();
-                
-                while let Ok(Some(line)) = lines.next_line().await {
-                    buffer.push_str(&line);
-                    buffer.push('\n');
-                    
-                    // Emit output event
-                    let _ = app.emit("process-output", serde_json::json!({
-                        "process_id": process_id,
-                        "type": "stdout",
-                        "data": line
-                    }));
-                    
-                    // Update database periodically
-                    if buffer.len() > 1024 {
-                        sqlx::query("UPDATE execution_processes SET stdout = stdout || ? WHERE id = ?")
-                            .bind(&buffer)
-                            .bind(process_id.to_string())
-                            .execute(&pool)
-                            .await
-                            .ok();
-                        buffer.clear();
-                    }
-                }
-                
-                // Final update
-                if !buffer.is_empty() {
-                    sqlx::query("UPDATE execution_processes SET stdout = stdout || ? WHERE id = ?")
-                        .bind(&buffer)
-                        .bind(process_id.to_string())
-                        .execute(&pool)
-                        .await
-                        .ok();
-                }
-            });
+            readers.push(self.capture_output(stdout, id, OutputStreamType::Stdout, sequence.clone(), env.clone(), app_handle.clone()));
         }
-
-        // Handle stderr
         if let Some(stderr) = child.stderr.take() {
-            let reader = BufReader::new(stderr);
-            let pool = self.pool.clone();
-            let process_id = id;
-            let app = app_handle.clone();
-            
-            tokio::spawn(async move {
-                let mut lines = reader.lines();
-                let mut buffer = String::new();
-                
-                while let Ok(Some(line)) = lines.next_line().await {
-                    buffer.push_str(&line);
-                    buffer.push('\n');
-                    
-                    // Emit output event
-                    let _ = app.emit("process-output", serde_json::json!({
-                        "process_id": process_id,
-                        "type": "stderr",
-                        "data": line
-                    }));
-                    
-                    // Update database periodically
-                    if buffer.len() > 1024 {
-                        sqlx::query("UPDATE execution_processes SET stderr = stderr || ? WHERE id = ?")
-                            .bind(&buffer)
-                            .bind(process_id.to_string())
-                            .execute(&pool)
-                            .await
-                            .ok();
-                        buffer.clear();
-                    }
-                }
-                
-                // Final update
-                if !buffer.is_empty() {
-                    sqlx::query("UPDATE execution_processes SET stderr = stderr || ? WHERE id = ?")
-                        .bind(&buffer)
-                        .bind(process_id.to_string())
-                        .execute(&pool)
-                        .await
-                        .ok();
-                }
-            });
+            readers.push(self.capture_output(stderr, id, OutputStreamType::Stderr, sequence.clone(), env.clone(), app_handle.clone()));
         }
 
         // Store the child process
         self.running_processes.lock().await.insert(id, child);
 
-        // Monitor process completion
+        // Monitor process completion. The child stays in the map while it runs
+        // so kill_process can still reach it; a missing entry means it was killed.
         let running_processes = self.running_processes.clone();
         let pool = self.pool.clone();
         let process_id = id;
         
         tokio::spawn(async move {
-            let mut processes = running_processes.lock().await;
-            if let Some(mut child) = processes.remove(&process_id) {
-                match child.wait().await {
-                    Ok(status) => {
-                        let exit_code = status.code();
-                        let final_status = if status.success() {
-                            ProcessStatus::Completed
+            let wait_result = loop {
+                let mut processes = running_processes.lock().await;
+                let child = match processes.get_mut(&process_id) {
+                    Some(child) => child,
+                    None => return,
+                };
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        processes.remove(&process_id);
+                        break Ok(status);
+                    }
+                    Ok(None) => {
+                        drop(processes);
+                        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    }
+                    Err(e) => {
+                        processes.remove(&process_id);
+                        break Err(e);
+                    }
+                }
+            };
+            
+            // Store the last of the output before anyone is told the process is
+            // done, so reading it back right away gets all of it
+            let deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+            for reader in readers {
+                if tokio::time::timeout_at(deadline, reader).await.is_err() {
+                    log::warn!("Output of process {} is still open after it exited", process_id);
+                    break;
+                }
+            }
+            
+            match wait_result {
+                Ok(status) => {
+                    let exit_code = status.code();
+                    let final_status = if status.success() {
+                        ProcessStatus::Completed
+                    } else {
+                        ProcessStatus::Failed
+                    };
+                    
+                    sqlx::query(
+                        "UPDATE execution_processes SET status = ?, exit_code = ?, completed_at = datetime('now') WHERE id = ?"
+                    )
+                    .bind(format!("{:?}", final_status).to_lowercase())
+                    .bind(exit_code)
+                    .bind(process_id.to_string())
+                    .execute(&pool)
+                    .await
+                    .ok();
+                    
+                    // A finished setup script marks the attempt's worktree as ready
+                    if let (ProcessType::SetupScript, Some(task_attempt_id)) = (&process_type, task_attempt_id) {
+                        let setup_status = if status.success() { "completed" } else { "failed" };
+                        sqlx::query("UPDATE task_attempts SET setup_status = ? WHERE id = ?")
+                            .bind(setup_status)
+                            .bind(task_attempt_id.to_string())
+                            .execute(&pool)
+                            .await
+                            .ok();
+                        
+                        let output = if status.success() {
+                            Vec::new()
                         } else {
-                            ProcessStatus::Failed
+                            let mut tail: Vec<String> = sqlx::query_scalar(
+                                "SELECT content FROM process_output_lines WHERE process_id = ? ORDER BY sequence DESC, CASE stream WHEN 'stdout' THEN 0 ELSE 1 END DESC, line_number DESC LIMIT ?"
+                            )
+                            .bind(process_id.to_string())
+                            .bind(SETUP_FAILURE_OUTPUT_LINES)
+                            .fetch_all(&pool)
+                            .await
+                            .unwrap_or_default();
+                            tail.reverse();
+                            tail
                         };
                         
-                        sqlx::query(
-                            "UPDATE execution_processes SET status = ?, exit_code = ?, completed_at = datetime('now') WHERE id = ?"
-                        )
-                        .bind(format!("{:?}", final_status).to_lowercase())
-                        .bind(exit_code)
-                        .bind(process_id.to_string())
-                        .execute(&pool)
-                        .await
-                        .ok();
-                        
-                        let _ = app_handle.emit("process-completed", serde_json::json!({
-                            "process_id": process_id,
-                            "exit_code": exit_code,
-                            "status": final_status
+                        let _ = app_handle.emit("attempt:setup-completed", serde_json::json!({
+                            "attemptId": task_attempt_id,
+                            "processId": process_id,
+                            "success": status.success(),
+                            "exitCode": exit_code,
+                            "output": output,
                         }));
                     }
-                    Err(e) => {
-                        eprintln!("Error waiting for process: {}", e);
-                    }
+                    
+                    let _ = app_handle.emit("process-completed", serde_json::json!({
+                        "process_id": process_id,
+                        "exit_code": exit_code,
+                        "status": final_status
+                    }));
+                }
+                Err(e) => {
+                    eprintln!("Error waiting for process: {}", e);
                 }
             }
         });
@@ -197,6 +311,76 @@ impl ProcessService {
         Ok(id)
     }
 
+    /// Read one output of a process line by line, emitting each line as a
+    /// `process-output` event and storing it in batches. The task ends once
+    /// the output is closed and its last batch is stored.
+    fn capture_output<R>(
+        &self,
+        output: R,
+        process_id: Uuid,
+        stream: OutputStreamType,
+        sequence: Arc<AtomicI64>,
+        env: ProjectEnvironment,
+        app: tauri::AppHandle,
+    ) -> JoinHandle<()>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let pool = self.pool.clone();
+        
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(output).lines();
+            let mut batch: Vec<CapturedLine> = Vec::new();
+            let mut batch_bytes = 0;
+            let mut flush_at: Option<Instant> = None;
+            
+            loop {
+                // A quiet process shouldn't leave lines unsaved, so waiting for
+                // the next line gives up when the batch is due. `next_line` is
+                // cancel safe, so nothing is lost when it does.
+                let next_line = match flush_at {
+                    Some(deadline) => tokio::time::timeout_at(deadline, lines.next_line()).await,
+                    None => Ok(lines.next_line().await),
+                };
+                
+                match next_line {
+                    Ok(Ok(Some(line))) => {
+                        let line = CapturedLine::new(sequence.fetch_add(1, Ordering::SeqCst), env.mask(&line));
+                        let _ = app.emit("process-output", serde_json::json!({
+                            "process_id": process_id,
+                            "type": stream,
+                            "data": line.content,
+                            "sequence": line.sequence,
+                            "timestamp": line.timestamp,
+                        }));
+                        
+                        batch_bytes += line.content.len() + 1;
+                        batch.push(line);
+                        flush_at.get_or_insert_with(|| Instant::now() + OUTPUT_FLUSH_INTERVAL);
+                        if batch_bytes <= OUTPUT_BATCH_BYTES {
+                            continue;
+                        }
+                    }
+                    // Output closed (or unreadable)
+                    Ok(_) => break,
+                    // The batch is due
+                    Err(_) => {}
+                }
+                
+                if let Err(e) = Self::append_output(&pool, process_id, stream, &batch).await {
+                    log::error!("Failed to save {} of process {}: {}", stream.as_str(), process_id, e);
+                }
+                batch.clear();
+                batch_bytes = 0;
+                flush_at = None;
+            }
+            
+            if let Err(e) = Self::append_output(&pool, process_id, stream, &batch).await {
+                log::error!("Failed to save {} of process {}: {}", stream.as_str(), process_id, e);
+            }
+        })
+    }
+
     pub async fn kill_process(&self, process_id: Uuid) -> Result<(), Box<dyn std::error::Error>> {
         let mut processes = self.running_processes.lock().await;
         
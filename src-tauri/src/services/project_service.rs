@@ -1,5 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use crate::db::DbPool;
-use crate::models::{CreateProjectRequest, Project, UpdateProjectRequest};
+use crate::models::{
+    CreateProjectRequest, GitProvider, Project, ProjectEnvVar, ProjectEnvironment, SetProjectEnvVarRequest,
+    UpdateCacheLinksRequest, UpdateProjectRequest,
+};
+use crate::models::{
+    BundleConversation, ConversationMessage, ImportProjectResult, MergeRequest, MergeRequestRow, ProjectBundle,
+    ReviewComment, ReviewCommentRow, Task, TaskAttempt, TaskAttemptRow, TaskRow, NEEDS_WORKTREE_SETUP_STATUS,
+    PROJECT_BUNDLE_VERSION,
+};
+use crate::services::git_service::GitService;
+use std::path::Path;
 use uuid::Uuid;
 
 pub struct ProjectService {
@@ -16,17 +27,19 @@ impl ProjectService {
         
         // Auto-detect git provider from git_repo URL
         let git_provider = req.git_repo.as_ref().map(|url| {
-            if url.contains("github.com") {
+            if GitProvider::from_remote_url(url) == GitProvider::GitHub {
                 "github".to_string()
             } else {
                 "gitlab".to_string()
             }
         });
+        let git_enabled = req.git_enabled
+            .unwrap_or_else(|| GitService::is_repository(Path::new(&req.path)));
 
         sqlx::query(
             r#"
-            INSERT INTO projects (id, name, description, path, git_repo, git_provider, main_branch, setup_script, dev_script, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+            INSERT INTO projects (id, name, description, path, git_repo, git_provider, main_branch, setup_script, dev_script, git_enabled, subdirectory, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
             "#,
         )
         .bind(id.to_string())
@@ -38,12 +51,49 @@ impl ProjectService {
         .bind(&req.main_branch.unwrap_or_else(|| "main".to_string()))
         .bind(&req.setup_script)
         .bind(&req.dev_script)
+        .bind(git_enabled)
+        .bind(&req.subdirectory)
         .execute(&self.pool)
         .await?;
 
         self.get_project(id).await.map(|opt| opt.unwrap())
     }
 
+    /// Check that `subdirectory` is a directory inside the project at
+    /// `project_path` and normalize it to a relative path without leading or
+    /// trailing slashes. An empty path means the whole repository.
+    pub fn validate_subdirectory(project_path: &str, subdirectory: &str) -> Result<Option<String>, String> {
+        use std::path::Component;
+
+        let trimmed = subdirectory.trim();
+        let trimmed = trimmed.strip_prefix("./").unwrap_or(trimmed).trim_matches('/');
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+
+        let relative = Path::new(trimmed);
+        if !relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return Err(format!("Subdirectory must be a relative path inside the project: {}", subdirectory));
+        }
+
+        let root = Path::new(project_path);
+        let full_path = root.join(relative);
+        if !full_path.is_dir() {
+            return Err(format!("Subdirectory {} does not exist in {}", trimmed, project_path));
+        }
+
+        // A symlink could still point outside the repository
+        let inside = match (root.canonicalize(), full_path.canonicalize()) {
+            (Ok(root), Ok(full_path)) => full_path.starts_with(root),
+            _ => false,
+        };
+        if !inside {
+            return Err(format!("Subdirectory {} is outside of {}", trimmed, project_path));
+        }
+
+        Ok(Some(trimmed.to_string()))
+    }
+
     pub async fn get_project(&self, id: Uuid) -> Result<Option<Project>, sqlx::Error> {
         use crate::models::ProjectRow;
         
@@ -93,7 +143,7 @@ impl ProjectService {
             params.push(git_repo.clone());
             
             // Auto-detect and update git provider when git_repo changes
-            let git_provider = if git_repo.contains("github.com") {
+            let git_provider = if GitProvider::from_remote_url(git_repo) == GitProvider::GitHub {
                 "github".to_string()
             } else {
                 "gitlab".to_string()
@@ -117,6 +167,50 @@ impl ProjectService {
             params.push(dev_script.clone());
         }
 
+        if let Some(branch_template) = &req.branch_template {
+            if branch_template.trim().is_empty() {
+                update_parts.push("branch_template = NULL");
+            } else {
+                update_parts.push("branch_template = ?");
+                params.push(branch_template.trim().to_string());
+            }
+        }
+
+        if let Some(auto_commit) = req.auto_commit {
+            update_parts.push(if auto_commit { "auto_commit = 1" } else { "auto_commit = 0" });
+        }
+
+        if let Some(default_executor) = &req.default_executor {
+            if default_executor.trim().is_empty() {
+                update_parts.push("default_executor = NULL");
+            } else {
+                update_parts.push("default_executor = ?");
+                params.push(default_executor.trim().to_string());
+            }
+        }
+
+        if let Some(default_model) = &req.default_model {
+            if default_model.trim().is_empty() {
+                update_parts.push("default_model = NULL");
+            } else {
+                update_parts.push("default_model = ?");
+                params.push(default_model.trim().to_string());
+            }
+        }
+
+        if let Some(git_enabled) = req.git_enabled {
+            update_parts.push(if git_enabled { "git_enabled = 1" } else { "git_enabled = 0" });
+        }
+
+        if let Some(subdirectory) = &req.subdirectory {
+            if subdirectory.trim().is_empty() {
+                update_parts.push("subdirectory = NULL");
+            } else {
+                update_parts.push("subdirectory = ?");
+                params.push(subdirectory.trim().to_string());
+            }
+        }
+
         let query = format!(
             "UPDATE projects SET {} WHERE id = ?",
             update_parts.join(", ")
@@ -134,6 +228,11 @@ impl ProjectService {
     }
 
     pub async fn delete_project(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM project_env_vars WHERE project_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
         sqlx::query("DELETE FROM projects WHERE id = ?")
             .bind(id.to_string())
             .execute(&self.pool)
@@ -142,6 +241,28 @@ impl ProjectService {
         Ok(())
     }
 
+    pub async fn update_cache_links(&self, id: Uuid, req: UpdateCacheLinksRequest) -> Result<Project, sqlx::Error> {
+        let cache_links: Vec<String> = req.cache_links
+            .iter()
+            .map(|link| link.trim().trim_end_matches('/').to_string())
+            .filter(|link| !link.is_empty())
+            .collect();
+        let cache_links_json = serde_json::to_string(&cache_links)
+            .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)))?;
+        let mode = format!("{:?}", req.cache_link_mode.unwrap_or_default()).to_lowercase();
+
+        sqlx::query(
+            "UPDATE projects SET cache_links = ?, cache_link_mode = ?, updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(&cache_links_json)
+        .bind(&mode)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        self.get_project(id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
     pub async fn update_last_opened(&self, id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query("UPDATE projects SET last_opened = datetime('now') WHERE id = ?")
             .bind(id.to_string())
@@ -151,6 +272,70 @@ impl ProjectService {
         Ok(())
     }
 
+    pub async fn update_last_dev_command(&self, id: Uuid, command: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE projects SET last_dev_command = ? WHERE id = ?")
+            .bind(command)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_dev_server_url(&self, id: Uuid, url: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE projects SET dev_server_url = ? WHERE id = ?")
+            .bind(url)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_git_provider(&self, id: Uuid, git_provider: &str) -> Result<Project, sqlx::Error> {
+        sqlx::query("UPDATE projects SET git_provider = ?, updated_at = datetime('now') WHERE id = ?")
+            .bind(git_provider)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        self.get_project(id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Make sure `main_branch` names a branch of the repository. Projects are
+    /// created with whatever branch the request named (usually "main"); if that
+    /// branch doesn't exist the repository's default branch is detected and stored.
+    /// Returns the main branch in effect.
+    pub async fn detect_and_update_main_branch(&self, project_id: Uuid) -> Result<String, String> {
+        let project = self
+            .get_project(project_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Project not found")?;
+
+        let git_service = GitService::new();
+        let repo_path = Path::new(&project.path);
+        if git_service.get_branch_commit(repo_path, &project.main_branch).is_ok() {
+            return Ok(project.main_branch);
+        }
+
+        let detected = git_service.detect_default_branch(repo_path)?;
+        if detected != project.main_branch {
+            sqlx::query("UPDATE projects SET main_branch = ?, updated_at = datetime('now') WHERE id = ?")
+                .bind(&detected)
+                .bind(project_id.to_string())
+                .execute(&self.pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            log::info!(
+                "Main branch of project {} changed from '{}' to '{}'",
+                project_id, project.main_branch, detected
+            );
+        }
+
+        Ok(detected)
+    }
+
     pub async fn get_recent_projects(&self, limit: i32) -> Result<Vec<Project>, sqlx::Error> {
         use crate::models::ProjectRow;
         
@@ -163,4 +348,504 @@ impl ProjectService {
 
         Ok(rows.into_iter().map(Project::from).collect())
     }
-}
\ No newline at end of file
+
+    pub async fn list_env_vars(&self, project_id: Uuid) -> Result<Vec<ProjectEnvVar>, sqlx::Error> {
+        use crate::models::ProjectEnvVarRow;
+
+        let rows = sqlx::query_as::<_, ProjectEnvVarRow>(
+            "SELECT * FROM project_env_vars WHERE project_id = ? ORDER BY key ASC",
+        )
+        .bind(project_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(ProjectEnvVar::from).collect())
+    }
+
+    /// Create or replace the variable with the given key
+    pub async fn set_env_var(&self, project_id: Uuid, req: SetProjectEnvVarRequest) -> Result<ProjectEnvVar, sqlx::Error> {
+        use crate::models::ProjectEnvVarRow;
+
+        let key = req.key.trim();
+        let valid_key = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(sqlx::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Invalid environment variable name: {}", req.key),
+            )));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO project_env_vars (id, project_id, key, value, is_secret, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+            ON CONFLICT(project_id, key) DO UPDATE SET
+                value = excluded.value,
+                is_secret = excluded.is_secret,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(project_id.to_string())
+        .bind(key)
+        .bind(&req.value)
+        .bind(req.is_secret)
+        .execute(&self.pool)
+        .await?;
+
+        let row = sqlx::query_as::<_, ProjectEnvVarRow>(
+            "SELECT * FROM project_env_vars WHERE project_id = ? AND key = ?",
+        )
+        .bind(project_id.to_string())
+        .bind(key)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ProjectEnvVar::from(row))
+    }
+
+    pub async fn delete_env_var(&self, project_id: Uuid, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM project_env_vars WHERE project_id = ? AND key = ?")
+            .bind(project_id.to_string())
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Environment variables of a project, empty for an unknown one
+    pub async fn get_environment(&self, project_id: &str) -> Result<ProjectEnvironment, sqlx::Error> {
+        use crate::models::ProjectEnvVarRow;
+
+        let rows = sqlx::query_as::<_, ProjectEnvVarRow>(
+            "SELECT * FROM project_env_vars WHERE project_id = ? ORDER BY key ASC",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ProjectEnvironment::from_vars(rows.into_iter().map(ProjectEnvVar::from).collect()))
+    }
+
+    pub async fn attach_mcp_server(&self, project_id: Uuid, server_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO project_mcp_servers (project_id, server_id, created_at) VALUES (?, ?, datetime('now'))",
+        )
+        .bind(project_id.to_string())
+        .bind(server_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn detach_mcp_server(&self, project_id: Uuid, server_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM project_mcp_servers WHERE project_id = ? AND server_id = ?")
+            .bind(project_id.to_string())
+            .bind(server_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drop a server from every project, used once it no longer exists
+    pub async fn detach_mcp_server_everywhere(&self, server_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM project_mcp_servers WHERE server_id = ?")
+            .bind(server_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_mcp_server_ids(&self, project_id: &str) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT server_id FROM project_mcp_servers WHERE project_id = ? ORDER BY created_at ASC",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Collect a project with its tasks, attempts, conversations, merge requests
+    /// and review comments.
+    /// Worktree paths are left out since they only make sense on this machine,
+    /// and deleted tasks since they are on their way out.
+    pub async fn export_project_bundle(&self, id: Uuid) -> Result<ProjectBundle, sqlx::Error> {
+        let project = self.get_project(id).await?.ok_or(sqlx::Error::RowNotFound)?;
+
+        let tasks = sqlx::query_as::<_, TaskRow>(
+            "SELECT * FROM tasks WHERE project_id = ? AND deleted_at IS NULL ORDER BY created_at ASC",
+        )
+        .bind(&project.id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(Task::from)
+        .collect();
+
+        let attempts = sqlx::query_as::<_, TaskAttemptRow>(
+            r#"
+            SELECT ta.* FROM task_attempts ta
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE t.project_id = ? AND t.deleted_at IS NULL
+            ORDER BY ta.created_at ASC
+            "#,
+        )
+        .bind(&project.id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| TaskAttempt {
+            worktree_path: String::new(),
+            ..TaskAttempt::from(row)
+        })
+        .collect();
+
+        let conversation_rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT ac.task_attempt_id, ac.messages FROM attempt_conversations ac
+            JOIN task_attempts ta ON ta.id = ac.task_attempt_id
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE t.project_id = ? AND t.deleted_at IS NULL
+            "#,
+        )
+        .bind(&project.id)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut conversations = Vec::with_capacity(conversation_rows.len());
+        for (task_attempt_id, messages_json) in conversation_rows {
+            let messages: Vec<ConversationMessage> = serde_json::from_str(&messages_json)
+                .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+            conversations.push(BundleConversation { task_attempt_id, messages });
+        }
+
+        let merge_requests = sqlx::query_as::<_, MergeRequestRow>(
+            r#"
+            SELECT mr.* FROM merge_requests mr
+            JOIN task_attempts ta ON ta.id = mr.task_attempt_id
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE t.project_id = ? AND t.deleted_at IS NULL
+            ORDER BY mr.created_at ASC
+            "#,
+        )
+        .bind(&project.id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(MergeRequest::from)
+        .collect();
+
+        let review_comments = sqlx::query_as::<_, ReviewCommentRow>(
+            r#"
+            SELECT rc.* FROM review_comments rc
+            JOIN task_attempts ta ON ta.id = rc.task_attempt_id
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE t.project_id = ? AND t.deleted_at IS NULL
+            ORDER BY rc.created_at ASC
+            "#,
+        )
+        .bind(&project.id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ReviewComment::from)
+        .collect();
+
+        Ok(ProjectBundle {
+            version: PROJECT_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now(),
+            project,
+            tasks,
+            attempts,
+            conversations,
+            merge_requests,
+            review_comments,
+        })
+    }
+
+    /// Recreate a bundle with fresh ids for the checkout at `target_path`.
+    /// If a project is already registered for that path the tasks are added to it,
+    /// skipping tasks whose title it already has. Attempts come back without a
+    /// worktree and have to be set up again before they can run.
+    pub async fn import_project_bundle(&self, bundle: ProjectBundle, target_path: &str) -> Result<ImportProjectResult, sqlx::Error> {
+        self.import_bundle(bundle, target_path, true).await
+    }
+
+    /// Recreate a bundle as a new project at the path it was exported from, even
+    /// if a project is already registered there. Nothing is merged, so every task
+    /// is imported.
+    pub async fn import_project_bundle_as_new(&self, bundle: ProjectBundle) -> Result<ImportProjectResult, sqlx::Error> {
+        let target_path = bundle.project.path.clone();
+        self.import_bundle(bundle, &target_path, false).await
+    }
+
+    async fn import_bundle(
+        &self,
+        bundle: ProjectBundle,
+        target_path: &str,
+        reuse_existing_project: bool,
+    ) -> Result<ImportProjectResult, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing_project: Option<(String,)> = if reuse_existing_project {
+            sqlx::query_as("SELECT id FROM projects WHERE path = ?")
+                .bind(target_path)
+                .fetch_optional(&mut *tx)
+                .await?
+        } else {
+            None
+        };
+
+        let project_id = match existing_project {
+            Some((id,)) => id,
+            None => {
+                let id = Uuid::new_v4().to_string();
+                let source = &bundle.project;
+                let cache_links_json = serde_json::to_string(&source.cache_links)
+                    .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)))?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO projects (
+                        id, name, description, path, git_repo, git_provider, main_branch, setup_script, dev_script,
+                        cache_links, cache_link_mode, branch_template, auto_commit, default_executor, default_model,
+                        git_enabled, subdirectory, created_at, updated_at
+                    )
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
+                    "#,
+                )
+                .bind(&id)
+                .bind(&source.name)
+                .bind(&source.description)
+                .bind(target_path)
+                .bind(&source.git_repo)
+                .bind(&source.git_provider)
+                .bind(&source.main_branch)
+                .bind(&source.setup_script)
+                .bind(&source.dev_script)
+                .bind(&cache_links_json)
+                .bind(format!("{:?}", source.cache_link_mode).to_lowercase())
+                .bind(&source.branch_template)
+                .bind(source.auto_commit)
+                .bind(&source.default_executor)
+                .bind(&source.default_model)
+                .bind(source.git_enabled)
+                .bind(&source.subdirectory)
+                .bind(source.created_at.to_rfc3339())
+                .execute(&mut *tx)
+                .await?;
+
+                id
+            }
+        };
+
+        let existing_titles: HashMap<String, String> = sqlx::query_as::<_, (String, String)>(
+            "SELECT title, id FROM tasks WHERE project_id = ?",
+        )
+        .bind(&project_id)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        // Bundle task id -> id in this database; skipped tasks map to the task they duplicate
+        let mut task_ids: HashMap<String, String> = HashMap::new();
+        let mut skipped_tasks: HashSet<String> = HashSet::new();
+
+        // Parents are linked in a second pass so the order of tasks in the bundle doesn't matter
+        for task in &bundle.tasks {
+            if let Some(existing_id) = existing_titles.get(&task.title) {
+                task_ids.insert(task.id.clone(), existing_id.clone());
+                skipped_tasks.insert(task.id.clone());
+                continue;
+            }
+
+            let id = Uuid::new_v4().to_string();
+            let tags_json = task.tags.as_ref().map(|t| serde_json::to_string(t).unwrap_or_default());
+
+            sqlx::query(
+                r#"
+                INSERT INTO tasks (id, project_id, title, description, status, priority, assignee, tags, position, created_at, updated_at, archived_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(&project_id)
+            .bind(&task.title)
+            .bind(&task.description)
+            .bind(format!("{:?}", task.status))
+            .bind(format!("{:?}", task.priority))
+            .bind(&task.assignee)
+            .bind(&tags_json)
+            .bind(task.position)
+            .bind(task.created_at.to_rfc3339())
+            .bind(task.updated_at.to_rfc3339())
+            .bind(task.archived_at.map(|at| at.to_rfc3339()))
+            .execute(&mut *tx)
+            .await?;
+
+            task_ids.insert(task.id.clone(), id);
+        }
+
+        for task in bundle.tasks.iter().filter(|t| !skipped_tasks.contains(&t.id)) {
+            let Some(parent_id) = task.parent_task_id.as_ref().and_then(|p| task_ids.get(p)) else {
+                continue;
+            };
+
+            sqlx::query("UPDATE tasks SET parent_task_id = ? WHERE id = ?")
+                .bind(parent_id)
+                .bind(&task_ids[&task.id])
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Attempts of skipped tasks stay behind, they belong to the task already here
+        let mut attempt_ids: HashMap<String, String> = HashMap::new();
+        for attempt in &bundle.attempts {
+            if skipped_tasks.contains(&attempt.task_id) {
+                continue;
+            }
+            let Some(task_id) = task_ids.get(&attempt.task_id) else {
+                continue;
+            };
+
+            let id = Uuid::new_v4().to_string();
+
+            // Session ids and MCP server ids refer to state on the exporting machine
+            sqlx::query(
+                r#"
+                INSERT INTO task_attempts (
+                    id, task_id, worktree_path, branch, base_branch, base_commit, executor, model, status,
+                    last_sync_commit, last_sync_at, created_at, completed_at, setup_status, cost_usd
+                )
+                VALUES (?, ?, '', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(task_id)
+            .bind(&attempt.branch)
+            .bind(&attempt.base_branch)
+            .bind(&attempt.base_commit)
+            .bind(&attempt.executor)
+            .bind(&attempt.model)
+            .bind(format!("{:?}", attempt.status).to_lowercase())
+            .bind(&attempt.last_sync_commit)
+            .bind(attempt.last_sync_at.map(|dt| dt.to_rfc3339()))
+            .bind(attempt.created_at.to_rfc3339())
+            .bind(attempt.completed_at.map(|dt| dt.to_rfc3339()))
+            .bind(NEEDS_WORKTREE_SETUP_STATUS)
+            .bind(attempt.cost_usd)
+            .execute(&mut *tx)
+            .await?;
+
+            attempt_ids.insert(attempt.id.clone(), id);
+        }
+
+        for conversation in &bundle.conversations {
+            let Some(attempt_id) = attempt_ids.get(&conversation.task_attempt_id) else {
+                continue;
+            };
+            let messages_json = serde_json::to_string(&conversation.messages)
+                .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO attempt_conversations (id, task_attempt_id, messages, created_at, updated_at)
+                VALUES (?, ?, ?, datetime('now'), datetime('now'))
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(attempt_id)
+            .bind(&messages_json)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for mr in &bundle.merge_requests {
+            let Some(attempt_id) = attempt_ids.get(&mr.task_attempt_id) else {
+                continue;
+            };
+            let pipeline_jobs_json = serde_json::to_string(&mr.pipeline_jobs)
+                .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)))?;
+
+            // The same MR may already be tracked here; provider and mr_id are unique
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO merge_requests (
+                    task_attempt_id, provider, mr_id, mr_iid, mr_number,
+                    title, description, state, source_branch, target_branch,
+                    web_url, merge_status, has_conflicts, pipeline_status, pipeline_url,
+                    pipeline_jobs, approval_count, created_at, updated_at, merged_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(attempt_id)
+            .bind(&mr.provider)
+            .bind(mr.mr_id)
+            .bind(mr.mr_iid)
+            .bind(mr.mr_number)
+            .bind(&mr.title)
+            .bind(&mr.description)
+            .bind(&mr.state)
+            .bind(&mr.source_branch)
+            .bind(&mr.target_branch)
+            .bind(&mr.web_url)
+            .bind(&mr.merge_status)
+            .bind(mr.has_conflicts)
+            .bind(&mr.pipeline_status)
+            .bind(&mr.pipeline_url)
+            .bind(&pipeline_jobs_json)
+            .bind(mr.approval_count)
+            .bind(mr.created_at.to_rfc3339())
+            .bind(mr.updated_at.to_rfc3339())
+            .bind(mr.merged_at.map(|dt| dt.to_rfc3339()))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for comment in &bundle.review_comments {
+            let Some(attempt_id) = attempt_ids.get(&comment.task_attempt_id) else {
+                continue;
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO review_comments (id, task_attempt_id, file_path, line, side, body, line_content, resolved, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(attempt_id)
+            .bind(&comment.file_path)
+            .bind(comment.line)
+            .bind(format!("{:?}", comment.side).to_lowercase())
+            .bind(&comment.body)
+            .bind(&comment.line_content)
+            .bind(comment.resolved)
+            .bind(comment.created_at.to_rfc3339())
+            .bind(comment.updated_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        let project_uuid = Uuid::parse_str(&project_id)
+            .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+        let project = self.get_project(project_uuid).await?.ok_or(sqlx::Error::RowNotFound)?;
+
+        Ok(ImportProjectResult {
+            project,
+            imported_tasks: bundle.tasks.len() - skipped_tasks.len(),
+            skipped_tasks: skipped_tasks.len(),
+            imported_attempts: attempt_ids.len(),
+        })
+    }
+}
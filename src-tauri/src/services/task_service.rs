@@ -1,15 +1,38 @@
 use crate::db::DbPool;
 use crate::models::{
-    CreateTaskRequest, Task, TaskStatus, UpdateTaskRequest,
-    CreateTaskAttemptRequest, TaskAttempt, TaskAttemptRow, AttemptStatus,
+    CreateTaskRequest, MatchField, MovedTask, Task, TaskPage, TaskSearchFilters, TaskSearchOrder, TaskSearchResult, TaskStatus,
+    UpdateTaskRequest,
+    CreateTaskAttemptRequest, TaskAttempt, AttemptStatus, WorktreeInfo, NEEDS_WORKTREE_SETUP_STATUS,
 };
-use crate::models::{AttemptConversation, ConversationMessage};
+use crate::models::{AttemptCommit, AttemptWorktree, AttemptConversation, ConversationMessage, PendingMessage};
+use crate::models::{CreateReviewCommentRequest, DiffSide, ReviewComment, UpdateReviewCommentRequest};
+use crate::models::{StatusChangeTrigger, TaskEvent, TaskEventKind, TaskStatusChange};
+use crate::models::{CreateTaskTemplateRequest, TaskPriority, TaskTemplate, UpdateTaskTemplateRequest};
+use crate::repository::{TaskRepository, TaskSearchHit, TaskSearchQuery, TaskTemplateValues};
 use crate::services::git_service::GitService;
+use crate::services::project_service::ProjectService;
+use crate::services::worktree_provisioner::WorktreeProvisioner;
 use uuid::Uuid;
 use std::path::Path;
+use base64::{Engine as _, engine::general_purpose};
 use deunicode::deunicode;
 use slug::slugify;
 
+/// Upper bound for `list_tasks_paginated` page sizes
+const MAX_TASK_PAGE_SIZE: usize = 500;
+
+/// Results returned by `search_tasks` when the caller sets no limit
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+/// Days a deleted task can be restored before it is purged on startup
+pub const DELETED_TASK_RETENTION_DAYS: i64 = 30;
+
+/// Branch naming used when a project has no `branch_template`
+const DEFAULT_BRANCH_TEMPLATE: &str = "task/{slug}-{id}";
+
+/// Safe for most git hosts and CI systems
+const MAX_BRANCH_NAME_LENGTH: usize = 45;
+
 pub struct TaskService {
     pool: DbPool,
 }
@@ -19,8 +42,16 @@ impl TaskService {
         Self { pool }
     }
     
-    /// Generate a branch name from task title
-    fn generate_branch_name(&self, title: &str, task_id: &Uuid) -> String {
+    /// Render a branch name from the project's template (or `DEFAULT_BRANCH_TEMPLATE`).
+    /// Supported placeholders: `{slug}` (from the title), `{id}` (first 8 chars of the
+    /// task id), `{assignee}` and `{date}` (YYYYMMDD). The slug is shortened so the
+    /// whole name stays within `MAX_BRANCH_NAME_LENGTH`.
+    fn generate_branch_name(&self, template: Option<&str>, title: &str, task_id: &Uuid, assignee: Option<&str>) -> String {
+        let template = template
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .unwrap_or(DEFAULT_BRANCH_TEMPLATE);
+        
         // First transliterate any non-ASCII characters to ASCII
         let ascii_title = deunicode(title);
         
@@ -31,21 +62,30 @@ impl TaskService {
         let uuid_string = task_id.to_string();
         let uuid_suffix = uuid_string.split('-').next().unwrap_or("00000000");
         
-        // Calculate max length for slug part
-        // Total max length: 45 chars (safe for most git systems, accounting for prefix)
-        // Format: task/{slug}-{uuid}
-        // Prefix: "task/" = 5 chars
-        // UUID part: 8 chars + 1 dash = 9 chars
-        // So slug can be at most: 45 - 5 - 9 = 31 chars
-        const MAX_TOTAL_LENGTH: usize = 45;
-        const PREFIX_LENGTH: usize = 5; // "task/"
-        const UUID_WITH_DASH_LENGTH: usize = 9; // 8 chars + 1 dash
-        const MAX_SLUG_LENGTH: usize = MAX_TOTAL_LENGTH - PREFIX_LENGTH - UUID_WITH_DASH_LENGTH;
+        let assignee = assignee
+            .map(|a| slugify(deunicode(a)))
+            .filter(|a| !a.is_empty())
+            .unwrap_or_else(|| "unassigned".to_string());
+        let date = chrono::Utc::now().format("%Y%m%d").to_string();
+        
+        let render = |slug: &str| {
+            sanitize_branch_name(
+                &template
+                    .replace("{slug}", slug)
+                    .replace("{id}", uuid_suffix)
+                    .replace("{assignee}", &assignee)
+                    .replace("{date}", &date),
+            )
+        };
+        
+        // Whatever the template uses besides the slug limits how long the slug can be
+        let fixed_length = render("").len();
+        let max_slug_length = MAX_BRANCH_NAME_LENGTH.saturating_sub(fixed_length);
         
         // Limit slug length and ensure it's not too short
-        let mut branch_name = if slug.len() > MAX_SLUG_LENGTH {
+        let mut slug_part = if slug.len() > max_slug_length {
             // Take only complete words if possible
-            let truncated = slug.chars().take(MAX_SLUG_LENGTH).collect::<String>();
+            let truncated = slug.chars().take(max_slug_length).collect::<String>();
             // Try to avoid cutting in the middle of a word
             if let Some(last_dash_pos) = truncated.rfind('-') {
                 if last_dash_pos > 10 { // Keep at least 10 chars
@@ -61,12 +101,21 @@ impl TaskService {
         };
         
         // If the slug is empty or too short, use "task"
-        if branch_name.is_empty() || branch_name.len() < 3 {
-            branch_name = "task".to_string();
+        if slug_part.is_empty() || slug_part.len() < 3 {
+            slug_part = "task".to_string();
+        }
+        
+        let mut branch_name = render(&slug_part);
+        if branch_name.len() > MAX_BRANCH_NAME_LENGTH {
+            // Only happens when the template alone is too long; names are ASCII here
+            branch_name.truncate(MAX_BRANCH_NAME_LENGTH);
+            branch_name = sanitize_branch_name(&branch_name);
+        }
+        if branch_name.is_empty() {
+            branch_name = format!("task/task-{}", uuid_suffix);
         }
         
-        // Combine with prefix and UUID suffix
-        format!("task/{}-{}", branch_name, &uuid_suffix[..8])
+        branch_name
     }
     
     /// Check if a branch already exists
@@ -82,8 +131,15 @@ impl TaskService {
     }
     
     /// Generate a unique branch name
-    async fn generate_unique_branch_name(&self, project_path: &str, title: &str, task_id: &Uuid) -> Result<String, sqlx::Error> {
-        let base_name = self.generate_branch_name(title, task_id);
+    async fn generate_unique_branch_name(
+        &self,
+        project_path: &str,
+        template: Option<&str>,
+        title: &str,
+        task_id: &Uuid,
+        assignee: Option<&str>,
+    ) -> Result<String, sqlx::Error> {
+        let base_name = self.generate_branch_name(template, title, task_id, assignee);
         
         // Check if the branch already exists
         if !self.branch_exists(project_path, &base_name).await? {
@@ -91,12 +147,10 @@ impl TaskService {
         }
         
         // If it exists, try adding a counter
-        // We need to ensure the total length stays within 45 chars (including prefix)
-        const MAX_BRANCH_LENGTH: usize = 45;
-        
+        // We need to ensure the total length stays within MAX_BRANCH_NAME_LENGTH
         for i in 2..=10 {
             let counter_suffix = format!("-{}", i);
-            let max_base_length = MAX_BRANCH_LENGTH - counter_suffix.len();
+            let max_base_length = MAX_BRANCH_NAME_LENGTH - counter_suffix.len();
             
             // Truncate base_name if needed to accommodate the counter
             let truncated_base = if base_name.len() > max_base_length {
@@ -122,357 +176,1337 @@ impl TaskService {
         }
         
         // If all attempts failed, fall back to UUID-based name with prefix
-        // Also ensure this doesn't exceed 45 chars
+        // Also ensure this doesn't exceed MAX_BRANCH_NAME_LENGTH
         let fallback = format!("task/task-{}", task_id);
-        if fallback.len() > MAX_BRANCH_LENGTH {
+        if fallback.len() > MAX_BRANCH_NAME_LENGTH {
             Ok(format!("task/task-{}", &task_id.to_string()[..30])) // 30 + 10 ("task/task-") = 40
         } else {
             Ok(fallback)
         }
     }
 
+    /// Create a task together with its first attempt. The worktree is created
+    /// first and both rows are written in one transaction; if that fails the
+    /// worktree is removed again, so no task is left without an attempt.
     pub async fn create_task(&self, req: CreateTaskRequest) -> Result<Task, sqlx::Error> {
         let id = Uuid::new_v4();
-        let tags_json = req.tags.map(|t| serde_json::to_string(&t).unwrap_or_default());
-
-        sqlx::query(
-            r#"
-            INSERT INTO tasks (id, project_id, title, description, status, priority, parent_task_id, assignee, tags, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
-            "#,
-        )
-        .bind(id.to_string())
-        .bind(req.project_id.to_string())
-        .bind(&req.title)
-        .bind(&req.description)
-        .bind("Backlog")
-        .bind(format!("{:?}", req.priority))
-        .bind(req.parent_task_id.map(|id| id.to_string()))
-        .bind(&req.assignee)
-        .bind(&tags_json)
-        .execute(&self.pool)
-        .await?;
-
-        let task = self.get_task(id).await.map(|opt| opt.unwrap())?;
-        
-        // Always create an initial attempt with worktree for the task
-        let attempt_req = CreateTaskAttemptRequest {
-            task_id: id,
-            executor: None,
-            base_branch: None,
-        };
+        let project_id = req.project_id.to_string();
         
-        match self.create_task_attempt(attempt_req).await {
-            Ok(_) => {
-                log::info!("Created initial attempt for task {}", id);
-                Ok(task)
-            },
-            Err(e) => {
+        let worktree = self
+            .create_attempt_worktree(&project_id, &id, &req.title, req.assignee.as_deref(), req.base_branch.clone())
+            .await
+            .map_err(|e| {
                 log::error!("Failed to create initial attempt for task {}: {}", id, e);
-                // Delete the task since we couldn't create the worktree
-                sqlx::query("DELETE FROM tasks WHERE id = ?")
-                    .bind(id.to_string())
-                    .execute(&self.pool)
-                    .await?;
-                
-                // Return the error to the user
-                Err(sqlx::Error::Io(std::io::Error::new(
+                sqlx::Error::Io(std::io::Error::new(
                     std::io::ErrorKind::Other,
                     format!("Failed to create worktree: {}", e)
-                )))
-            }
+                ))
+            })?;
+        
+        let attempt_id = Uuid::new_v4();
+        let result = async {
+            let settings = TaskRepository::attempt_project_settings(&self.pool, &project_id).await?;
+            let (executor, model) = settings.attempt_agent(req.executor.clone());
+            let mut tx = self.pool.begin().await?;
+            TaskRepository::insert_task(&mut tx, id, &req).await?;
+            TaskRepository::insert_attempt(&mut tx, attempt_id, id, &worktree, executor.as_deref(), model.as_deref()).await?;
+            tx.commit().await
         }
+        .await;
+        
+        if let Err(e) = result {
+            self.discard_attempt_worktree(&project_id, &worktree).await;
+            return Err(e);
+        }
+        
+        log::info!("Created initial attempt for task {}", id);
+        self.get_task(id).await?.ok_or(sqlx::Error::RowNotFound)
     }
 
-    pub async fn get_task(&self, id: Uuid) -> Result<Option<Task>, sqlx::Error> {
-        use crate::models::TaskRow;
+    /// Create a child task in the parent's project. Its worktree branches off the
+    /// branch of the parent's latest attempt unless `req.base_branch` is set;
+    /// `req.project_id` and `req.parent_task_id` are taken from the parent.
+    pub async fn create_subtask(&self, parent_id: Uuid, mut req: CreateTaskRequest) -> Result<Task, sqlx::Error> {
+        let parent = self.get_task(parent_id).await?.ok_or(sqlx::Error::RowNotFound)?;
         
-        let row = sqlx::query_as::<_, TaskRow>(
-            "SELECT * FROM tasks WHERE id = ?",
-        )
-        .bind(id.to_string())
-        .fetch_optional(&self.pool)
-        .await?;
+        req.project_id = Uuid::parse_str(&parent.project_id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        req.parent_task_id = Some(parent_id);
+        let has_base_branch = req.base_branch.as_deref().is_some_and(|b| !b.trim().is_empty());
+        if !has_base_branch {
+            req.base_branch = self
+                .list_task_attempts(parent_id)
+                .await?
+                .into_iter()
+                .next()
+                .map(|attempt| attempt.branch);
+        }
+        
+        self.create_task(req).await
+    }
+
+    pub async fn get_task(&self, id: Uuid) -> Result<Option<Task>, sqlx::Error> {
+        TaskRepository::get_task(&self.pool, id).await
+    }
 
-        Ok(row.map(Task::from))
+    /// Direct children of a task, oldest first
+    pub async fn list_subtasks(&self, parent_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+        TaskRepository::list_subtasks(&self.pool, parent_id).await
     }
 
-    pub async fn list_tasks(&self, project_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
-        use crate::models::TaskRow;
+    pub async fn list_tasks(&self, project_id: Uuid, include_archived: bool) -> Result<Vec<Task>, sqlx::Error> {
+        TaskRepository::list_tasks(&self.pool, project_id, include_archived).await
+    }
+
+    /// List tasks newest first, `limit` at a time. The cursor is the one returned
+    /// with the previous page and encodes its last `(created_at, id)` pair.
+    pub async fn list_tasks_paginated(
+        &self,
+        project_id: Uuid,
+        cursor: Option<String>,
+        limit: usize,
+        status_filter: Option<Vec<TaskStatus>>,
+    ) -> Result<TaskPage, sqlx::Error> {
+        let limit = limit.clamp(1, MAX_TASK_PAGE_SIZE);
+        let statuses: Vec<String> = status_filter
+            .unwrap_or_default()
+            .iter()
+            .map(|status| format!("{:?}", status))
+            .collect();
         
-        let rows = sqlx::query_as::<_, TaskRow>(
-            "SELECT * FROM tasks WHERE project_id = ? ORDER BY created_at DESC",
-        )
-        .bind(project_id.to_string())
-        .fetch_all(&self.pool)
-        .await?;
+        let total_count = TaskRepository::count_visible_tasks(&self.pool, project_id, &statuses).await?;
+        
+        // Fetch one extra row to find out whether another page follows
+        let after = cursor.as_deref().map(decode_task_cursor).transpose()?;
+        let mut rows = TaskRepository::list_visible_tasks_page(&self.pool, project_id, &statuses, after.as_ref(), limit + 1).await?;
+        
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(|row| encode_task_cursor(&row.created_at, &row.id))
+        } else {
+            None
+        };
+        
+        Ok(TaskPage {
+            tasks: rows.into_iter().map(Task::from).collect(),
+            next_cursor,
+            total_count,
+        })
+    }
 
-        Ok(rows.into_iter().map(Task::from).collect())
+    /// Search tasks by title, description and tags, and with
+    /// `filters.include_conversations` the messages of their attempt
+    /// conversations, in one project or all of them. Every word of `query` has
+    /// to match, as a prefix, somewhere in the task. Each task is listed once
+    /// with the field that matched best. An empty query only applies the
+    /// filters and lists the tasks with their title as snippet.
+    pub async fn search_tasks(
+        &self,
+        project_id: Option<Uuid>,
+        query: &str,
+        filters: TaskSearchFilters,
+    ) -> Result<Vec<TaskSearchResult>, sqlx::Error> {
+        let statuses: Vec<String> = filters.statuses
+            .unwrap_or_default()
+            .iter()
+            .map(|status| format!("{:?}", status))
+            .collect();
+        let assignee = filters.assignee.filter(|a| !a.trim().is_empty());
+        let match_expr = fts_match_expression(query).map(|expr| {
+            if filters.include_conversations {
+                expr
+            } else {
+                format!("{{title description tags}} : ({})", expr)
+            }
+        });
+        
+        let search = TaskSearchQuery {
+            project_id,
+            match_expr: match_expr.as_deref(),
+            statuses: &statuses,
+            assignee: assignee.as_deref(),
+            order_by_rank: filters.order_by == TaskSearchOrder::Relevance,
+            limit: filters.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_TASK_PAGE_SIZE),
+        };
+        let hits = TaskRepository::search_tasks(&self.pool, &search).await?;
+        
+        Ok(hits.into_iter().map(TaskSearchResult::from).collect())
     }
 
+    /// Edit a task by hand; a status change is recorded as made by the user
     pub async fn update_task(&self, id: Uuid, req: UpdateTaskRequest) -> Result<Task, sqlx::Error> {
-        let mut update_parts = vec!["updated_at = datetime('now')"];
-        let mut params: Vec<String> = vec![];
-
-        if let Some(title) = &req.title {
-            update_parts.push("title = ?");
-            params.push(title.clone());
+        let mut tx = self.pool.begin().await?;
+        let previous_status = TaskRepository::get_task(&mut *tx, id).await?.map(|task| task.status);
+        
+        TaskRepository::update_task(&mut tx, id, &req).await?;
+        
+        if let Some(status) = req.status {
+            if previous_status.as_ref() != Some(&status) {
+                TaskRepository::record_status_change(&mut tx, &id.to_string(), previous_status, status, StatusChangeTrigger::User).await?;
+            }
         }
+        tx.commit().await?;
+        
+        self.get_task(id).await.map(|opt| opt.unwrap())
+    }
 
-        if let Some(description) = &req.description {
-            update_parts.push("description = ?");
-            params.push(description.clone());
+    /// Move a task to the trash. It disappears from task lists and searches but
+    /// keeps its attempts, conversations and worktrees until it is purged.
+    pub async fn delete_task(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if !TaskRepository::soft_delete_task(&mut tx, id, chrono::Utc::now()).await? {
+            return Err(sqlx::Error::RowNotFound);
         }
+        tx.commit().await?;
 
-        if let Some(status) = &req.status {
-            update_parts.push("status = ?");
-            params.push(format!("{:?}", status).to_lowercase());
-        }
+        Ok(())
+    }
 
-        if let Some(priority) = &req.priority {
-            update_parts.push("priority = ?");
-            params.push(format!("{:?}", priority).to_lowercase());
+    /// Bring a deleted task back before it is purged
+    pub async fn restore_task(&self, id: Uuid) -> Result<Task, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if !TaskRepository::restore_task(&mut tx, id).await? {
+            return Err(sqlx::Error::RowNotFound);
         }
+        tx.commit().await?;
+
+        self.get_task(id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Tasks deleted before `cutoff`, oldest deletion first
+    pub async fn list_deleted_tasks_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<Vec<Task>, sqlx::Error> {
+        TaskRepository::list_deleted_tasks_before(&self.pool, cutoff).await
+    }
+
+    /// Remove deleted tasks for good: the worktrees of their attempts first, then
+    /// the tasks with their attempts, conversations, processes and merge requests
+    /// in one transaction. Running executions have to be stopped by the caller.
+    /// Returns the number of tasks purged.
+    pub async fn purge_tasks(&self, tasks: &[Task]) -> Result<usize, sqlx::Error> {
+        let git_service = GitService::new();
+        let mut task_ids = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            let id = Uuid::parse_str(&task.id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            // A task restored since it was listed stays
+            match self.get_task(id).await? {
+                Some(current) if current.deleted_at.is_some() => {}
+                _ => continue,
+            }
+            let project_path = TaskRepository::project_path(&self.pool, &task.project_id)
+                .await?
+                .ok_or(sqlx::Error::RowNotFound)?;
 
-        if let Some(assignee) = &req.assignee {
-            update_parts.push("assignee = ?");
-            params.push(assignee.clone());
+            for attempt in self.list_task_attempts(id).await? {
+                let worktree_path = Path::new(&attempt.worktree_path);
+                if attempt.worktree_path.is_empty() || !worktree_path.exists() {
+                    continue;
+                }
+                if let Err(e) = git_service.remove_worktree(Path::new(&project_path), worktree_path) {
+                    log::warn!("Failed to remove worktree {} of purged task {}: {}", attempt.worktree_path, id, e);
+                }
+            }
+            task_ids.push(id);
         }
 
-        if let Some(tags) = &req.tags {
-            update_parts.push("tags = ?");
-            params.push(serde_json::to_string(tags).unwrap());
+        let mut tx = self.pool.begin().await?;
+        let mut purged = 0;
+        for id in task_ids {
+            if TaskRepository::delete_task(&mut tx, id).await? {
+                purged += 1;
+            }
         }
+        tx.commit().await?;
 
-        let query = format!(
-            "UPDATE tasks SET {} WHERE id = ?",
-            update_parts.join(", ")
-        );
-        params.push(id.to_string());
+        Ok(purged)
+    }
 
-        let mut q = sqlx::query(&query);
-        for param in params {
-            q = q.bind(param);
+    /// Hide a task from task lists without deleting it. Worktrees of its attempts
+    /// are removed to free disk space; attempts and conversations stay in the
+    /// database and are marked as needing a new worktree.
+    pub async fn archive_task(&self, id: Uuid) -> Result<Task, sqlx::Error> {
+        let task = self.get_task(id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        if task.archived_at.is_some() {
+            return Ok(task);
         }
         
-        q.execute(&self.pool).await?;
+        let project_path = TaskRepository::project_path(&self.pool, &task.project_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
         
-        self.get_task(id).await.map(|opt| opt.unwrap())
+        let git_service = GitService::new();
+        for attempt in self.list_task_attempts(id).await? {
+            let worktree_path = Path::new(&attempt.worktree_path);
+            if attempt.worktree_path.is_empty() || attempt.branch.is_empty() || !worktree_path.exists() {
+                continue;
+            }
+            
+            match git_service.remove_worktree(Path::new(&project_path), worktree_path) {
+                Ok(()) => {
+                    let attempt_id = Uuid::parse_str(&attempt.id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                    self.update_attempt_setup_status(attempt_id, NEEDS_WORKTREE_SETUP_STATUS).await?;
+                }
+                Err(e) => log::warn!("Failed to remove worktree {} of archived task {}: {}", attempt.worktree_path, id, e),
+            }
+        }
+        
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::set_task_archived(&mut tx, id, Some(chrono::Utc::now())).await?;
+        tx.commit().await?;
+        
+        self.get_task(id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+    
+    /// Bring an archived task back into task lists. Worktrees are not recreated
+    /// until an attempt runs again.
+    pub async fn unarchive_task(&self, id: Uuid) -> Result<Task, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if !TaskRepository::set_task_archived(&mut tx, id, None).await? {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        tx.commit().await?;
+        
+        self.get_task(id).await?.ok_or(sqlx::Error::RowNotFound)
     }
 
-    pub async fn delete_task(&self, id: Uuid) -> Result<(), sqlx::Error> {
-        sqlx::query("DELETE FROM tasks WHERE id = ?")
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?;
+    /// Reassign a task and its subtasks to another project. Existing worktrees
+    /// stay where they are, they belong to the old project's repository and are
+    /// returned with the task.
+    pub async fn move_task_to_project(&self, task_id: Uuid, target_project_id: Uuid) -> Result<MovedTask, sqlx::Error> {
+        let task = self.get_task(task_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        
+        if TaskRepository::project_path(&self.pool, &target_project_id.to_string()).await?.is_none() {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        
+        if task.project_id == target_project_id.to_string() {
+            return Ok(MovedTask { task, left_behind_worktrees: Vec::new() });
+        }
+        
+        let mut tx = self.pool.begin().await?;
+        
+        // Subtasks at any depth, deleted ones included so a restore stays in the task's project
+        let moved_ids = TaskRepository::list_subtree_ids(&mut *tx, task_id).await?;
+        
+        // The manual ordering of the old project does not apply in the new one
+        TaskRepository::set_tasks_project(&mut tx, &moved_ids, target_project_id).await?;
+        
+        // The parent lives in the old project
+        TaskRepository::detach_from_parent(&mut tx, task_id).await?;
+        
+        tx.commit().await?;
+        
+        let mut left_behind_worktrees = Vec::new();
+        for id in &moved_ids {
+            let moved_id = Uuid::parse_str(id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            for attempt in self.list_task_attempts(moved_id).await? {
+                if !attempt.worktree_path.is_empty() && Path::new(&attempt.worktree_path).exists() {
+                    log::warn!(
+                        "Task {} moved to project {} but its worktree {} still belongs to project {}",
+                        moved_id, target_project_id, attempt.worktree_path, task.project_id
+                    );
+                    left_behind_worktrees.push(attempt.worktree_path);
+                }
+            }
+        }
+        
+        let task = self.get_task(task_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        Ok(MovedTask { task, left_behind_worktrees })
+    }
+
+    /// Persist a new manual ordering for the tasks of a project.
+    /// All ids must belong to the project, otherwise nothing is written.
+    /// Deleted tasks keep their position.
+    pub async fn reorder_tasks(&self, project_id: Uuid, ordered_ids: Vec<Uuid>) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut live_ids = Vec::with_capacity(ordered_ids.len());
+        for task_id in &ordered_ids {
+            let owner = TaskRepository::task_owners(&mut *tx, &[task_id.to_string()]).await?.pop();
+
+            match owner {
+                Some((_, owner_id, _, deleted)) if owner_id == project_id.to_string() => {
+                    if !deleted {
+                        live_ids.push(*task_id);
+                    }
+                }
+                Some(_) => {
+                    return Err(sqlx::Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("Task {} does not belong to project {}", task_id, project_id)
+                    )));
+                }
+                None => return Err(sqlx::Error::RowNotFound),
+            }
+        }
+
+        for (position, task_id) in live_ids.iter().enumerate() {
+            TaskRepository::set_task_position(&mut tx, *task_id, project_id, position as i64).await?;
+        }
+
+        tx.commit().await?;
 
         Ok(())
     }
 
-    pub async fn update_task_status(&self, id: Uuid, status: TaskStatus) -> Result<Task, sqlx::Error> {
-        sqlx::query(
-            "UPDATE tasks SET status = ?, updated_at = datetime('now') WHERE id = ?",
-        )
-        .bind(format!("{:?}", status))
-        .bind(id.to_string())
-        .execute(&self.pool)
-        .await?;
+    /// Move a task to `status`, recording what caused it if the status changes
+    pub async fn update_task_status(&self, id: Uuid, status: TaskStatus, trigger: StatusChangeTrigger) -> Result<Task, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let previous_status = TaskRepository::get_task(&mut *tx, id).await?.map(|task| task.status);
+
+        TaskRepository::set_tasks_status(&mut tx, &[id.to_string()], &status).await?;
+
+        if previous_status.as_ref() != Some(&status) {
+            TaskRepository::record_status_change(&mut tx, &id.to_string(), previous_status, status, trigger).await?;
+        }
+        tx.commit().await?;
 
         self.get_task(id).await.map(|opt| opt.unwrap())
     }
 
+    /// Set the status of several tasks with a single `UPDATE` in one transaction.
+    /// All tasks have to belong to the same project, otherwise nothing is written.
+    /// Deleted tasks are left as they are and not returned.
+    pub async fn update_tasks_status(&self, ids: Vec<Uuid>, status: TaskStatus, trigger: StatusChangeTrigger) -> Result<Vec<Task>, sqlx::Error> {
+        let mut ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        let mut seen = std::collections::HashSet::new();
+        ids.retain(|id| seen.insert(id.clone()));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let mut tx = self.pool.begin().await?;
+        
+        let owners = TaskRepository::task_owners(&mut *tx, &ids).await?;
+        if owners.len() != ids.len() {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        if let Some((id, ..)) = owners.iter().find(|(_, project_id, ..)| *project_id != owners[0].1) {
+            return Err(sqlx::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Task {} belongs to a different project", id)
+            )));
+        }
+        
+        let owners: Vec<(String, String, String, bool)> = owners.into_iter().filter(|(.., deleted)| !deleted).collect();
+        ids.retain(|id| owners.iter().any(|(owner_id, ..)| owner_id == id));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        TaskRepository::set_tasks_status(&mut tx, &ids, &status).await?;
+        
+        for (id, _, previous_status, _) in &owners {
+            let previous_status: Option<TaskStatus> = serde_json::from_str(&format!("\"{}\"", previous_status)).ok();
+            if previous_status.as_ref() != Some(&status) {
+                TaskRepository::record_status_change(&mut tx, id, previous_status, status.clone(), trigger).await?;
+            }
+        }
+        
+        let mut tasks = TaskRepository::list_tasks_by_id(&mut *tx, &ids).await?;
+        
+        tx.commit().await?;
+        
+        // Same order as requested
+        tasks.sort_by_key(|task| ids.iter().position(|id| *id == task.id));
+        Ok(tasks)
+    }
+
     // Task Attempt methods
     pub async fn create_task_attempt(&self, req: CreateTaskAttemptRequest) -> Result<TaskAttempt, sqlx::Error> {
         let id = Uuid::new_v4();
-        let task_id = req.task_id.to_string();
         
         // Get the task to find its project and title
         let task = self.get_task(req.task_id).await?
             .ok_or_else(|| sqlx::Error::RowNotFound)?;
         
-        // Get project path and main_branch
-        let project_row: (String, String) = sqlx::query_as(
-            "SELECT path, main_branch FROM projects WHERE id = ?"
-        )
-        .bind(task.project_id.to_string())
-        .fetch_one(&self.pool)
-        .await?;
+        let worktree = self
+            .create_attempt_worktree(&task.project_id, &req.task_id, &task.title, task.assignee.as_deref(), req.base_branch)
+            .await?;
+        
+        let result = async {
+            let settings = TaskRepository::attempt_project_settings(&self.pool, &task.project_id).await?;
+            let (executor, model) = settings.attempt_agent(req.executor);
+            let mut tx = self.pool.begin().await?;
+            TaskRepository::insert_attempt(&mut tx, id, req.task_id, &worktree, executor.as_deref(), model.as_deref()).await?;
+            tx.commit().await
+        }
+        .await;
+        
+        if let Err(e) = result {
+            self.discard_attempt_worktree(&task.project_id, &worktree).await;
+            return Err(e);
+        }
         
-        let project_path = project_row.0;
-        let project_main_branch = project_row.1;
+        self.get_task_attempt(id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+    
+    /// Create the branch and worktree for a new attempt of a task, based on
+    /// `base_branch` or the project's main branch. Attempts of a project
+    /// without git run in the project directory and have no branch.
+    async fn create_attempt_worktree(
+        &self,
+        project_id: &str,
+        task_id: &Uuid,
+        title: &str,
+        assignee: Option<&str>,
+        base_branch: Option<String>,
+    ) -> Result<WorktreeInfo, sqlx::Error> {
+        let settings = TaskRepository::attempt_project_settings(&self.pool, project_id).await?;
+        if !settings.git_enabled {
+            return Ok(WorktreeInfo {
+                path: settings.path,
+                branch: String::new(),
+                base_branch: String::new(),
+                base_commit: String::new(),
+            });
+        }
+        
+        // Fix a main branch that doesn't exist before anything is based on it
+        let project_uuid = Uuid::parse_str(project_id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let main_branch = ProjectService::new(self.pool.clone())
+            .detect_and_update_main_branch(project_uuid)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to detect main branch of project {}: {}", project_id, e);
+                settings.main_branch.clone()
+            });
         
         // Generate a meaningful branch name from the task title
-        let branch = self.generate_unique_branch_name(&project_path, &task.title, &req.task_id).await?;
-        let base_branch = req.base_branch.unwrap_or(project_main_branch);
+        let branch = self.generate_unique_branch_name(
+            &settings.path,
+            settings.branch_template.as_deref(),
+            title,
+            task_id,
+            assignee,
+        ).await?;
+        let base_branch = base_branch
+            .filter(|b| !b.trim().is_empty())
+            .unwrap_or(main_branch);
         
         // Create worktree with baseline tracking
         let git_service = GitService::new();
         let worktree_info = git_service.create_worktree_with_baseline(
-            Path::new(&project_path),
+            Path::new(&settings.path),
             &branch,
             &base_branch,
+            settings.subdirectory.as_deref(),
         ).map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
         
-        // Insert into database with base commit
-        sqlx::query(
-            r#"
-            INSERT INTO task_attempts (id, task_id, worktree_path, branch, base_branch, base_commit, executor, status, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
-            "#,
-        )
-        .bind(id.to_string())
-        .bind(task_id)
-        .bind(&worktree_info.path)
-        .bind(&worktree_info.branch)
-        .bind(&worktree_info.base_branch)
-        .bind(&worktree_info.base_commit)
-        .bind(&req.executor)
-        .bind("running")
-        .execute(&self.pool)
-        .await?;
-
-        self.get_task_attempt(id).await.map(|opt| opt.unwrap())
+        // Share dependency caches from the main checkout before any setup runs
+        let cache_links: Vec<String> = settings.cache_links
+            .and_then(|links| serde_json::from_str(&links).ok())
+            .unwrap_or_default();
+        if !cache_links.is_empty() {
+            let mode = serde_json::from_str(&format!("\"{}\"", settings.cache_link_mode)).unwrap_or_default();
+            WorktreeProvisioner::new(Path::new(&settings.path), mode)
+                .provision(Path::new(&worktree_info.path), &cache_links);
+        }
+        
+        Ok(worktree_info)
     }
     
-    pub async fn get_task_attempt(&self, id: Uuid) -> Result<Option<TaskAttempt>, sqlx::Error> {
-        let row = sqlx::query_as::<_, TaskAttemptRow>(
-            "SELECT * FROM task_attempts WHERE id = ?"
-        )
-        .bind(id.to_string())
-        .fetch_optional(&self.pool)
-        .await?;
+    /// Undo `create_attempt_worktree` after the attempt could not be saved
+    async fn discard_attempt_worktree(&self, project_id: &str, worktree: &WorktreeInfo) {
+        // The project directory itself, see `create_attempt_worktree`
+        if worktree.branch.is_empty() {
+            return;
+        }
+        
+        let project_path = match TaskRepository::attempt_project_settings(&self.pool, project_id).await {
+            Ok(settings) => settings.path,
+            Err(e) => {
+                log::error!("Failed to look up project {} to remove worktree {}: {}", project_id, worktree.path, e);
+                return;
+            }
+        };
         
-        Ok(row.map(TaskAttempt::from))
+        let git_service = GitService::new();
+        let repo_path = Path::new(&project_path);
+        if let Err(e) = git_service.remove_worktree(repo_path, Path::new(&worktree.path)) {
+            log::error!("Failed to remove worktree {} of unsaved attempt: {}", worktree.path, e);
+        }
+        if let Err(e) = git_service.delete_branch(repo_path, &worktree.branch) {
+            log::warn!("Failed to delete branch {} of unsaved attempt: {}", worktree.branch, e);
+        }
+    }
+    
+    pub async fn get_task_attempt(&self, id: Uuid) -> Result<Option<TaskAttempt>, sqlx::Error> {
+        TaskRepository::get_attempt(&self.pool, id).await
     }
     
     pub async fn list_task_attempts(&self, task_id: Uuid) -> Result<Vec<TaskAttempt>, sqlx::Error> {
-        let rows = sqlx::query_as::<_, TaskAttemptRow>(
-            "SELECT * FROM task_attempts WHERE task_id = ? ORDER BY created_at DESC"
-        )
-        .bind(task_id.to_string())
-        .fetch_all(&self.pool)
-        .await?;
-        
-        Ok(rows.into_iter().map(TaskAttempt::from).collect())
+        TaskRepository::list_attempts(&self.pool, task_id).await
+    }
+    
+    /// Attempts of every project that have a worktree of their own
+    pub async fn list_attempt_worktrees(&self) -> Result<Vec<AttemptWorktree>, sqlx::Error> {
+        TaskRepository::list_attempt_worktrees(&self.pool, None).await
+    }
+    
+    pub async fn get_attempt_worktree(&self, attempt_id: Uuid) -> Result<Option<AttemptWorktree>, sqlx::Error> {
+        let attempt_id = attempt_id.to_string();
+        Ok(TaskRepository::list_attempt_worktrees(&self.pool, Some(&attempt_id)).await?.pop())
     }
     
     pub async fn update_attempt_status(&self, id: Uuid, status: AttemptStatus) -> Result<TaskAttempt, sqlx::Error> {
-        let completed_at = match status {
-            AttemptStatus::Success | AttemptStatus::Failed | AttemptStatus::Cancelled => {
-                Some("datetime('now')")
-            },
-            _ => None
-        };
-        
-        if let Some(completed_at_val) = completed_at {
-            sqlx::query(&format!(
-                "UPDATE task_attempts SET status = ?, completed_at = {} WHERE id = ?",
-                completed_at_val
-            ))
-            .bind(format!("{:?}", status).to_lowercase())
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?;
-        } else {
-            sqlx::query(
-                "UPDATE task_attempts SET status = ? WHERE id = ?"
-            )
-            .bind(format!("{:?}", status).to_lowercase())
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?;
-        }
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::set_attempt_status(&mut tx, id, &status).await?;
+        tx.commit().await?;
         
         self.get_task_attempt(id).await.map(|opt| opt.unwrap())
     }
     
     pub async fn save_attempt_conversation(&self, attempt_id: Uuid, messages: Vec<ConversationMessage>) -> Result<AttemptConversation, sqlx::Error> {
-        let conversation_id = Uuid::new_v4();
         let messages_json = serde_json::to_string(&messages)
             .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
         
-        // Check if conversation already exists
-        let existing: Option<(String,)> = sqlx::query_as(
-            "SELECT id FROM attempt_conversations WHERE task_attempt_id = ?"
-        )
-        .bind(attempt_id.to_string())
-        .fetch_optional(&self.pool)
-        .await?;
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::save_conversation(&mut tx, attempt_id, &messages_json).await?;
+        tx.commit().await?;
         
-        if let Some((existing_id,)) = existing {
-            // Update existing conversation
-            sqlx::query(
-                "UPDATE attempt_conversations SET messages = ?, updated_at = datetime('now') WHERE id = ?"
-            )
-            .bind(&messages_json)
-            .bind(&existing_id)
-            .execute(&self.pool)
-            .await?;
-            
-            self.get_attempt_conversation(attempt_id).await.map(|opt| opt.unwrap())
+        self.get_attempt_conversation(attempt_id).await.map(|opt| opt.unwrap())
+    }
+    
+    pub async fn get_attempt_conversation(&self, attempt_id: Uuid) -> Result<Option<AttemptConversation>, sqlx::Error> {
+        TaskRepository::get_conversation(&self.pool, attempt_id).await
+    }
+    
+    pub async fn update_attempt_claude_session(&self, attempt_id: Uuid, claude_session_id: String) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::set_attempt_claude_session(&mut tx, attempt_id, &claude_session_id).await?;
+        tx.commit().await
+    }
+    
+    pub async fn update_attempt_mcp_servers(&self, attempt_id: Uuid, server_ids: &[String]) -> Result<(), sqlx::Error> {
+        let value = if server_ids.is_empty() {
+            None
         } else {
-            // Create new conversation
-            sqlx::query(
-                r#"
-                INSERT INTO attempt_conversations (id, task_attempt_id, messages, created_at, updated_at)
-                VALUES (?, ?, ?, datetime('now'), datetime('now'))
-                "#
-            )
-            .bind(conversation_id.to_string())
-            .bind(attempt_id.to_string())
-            .bind(&messages_json)
-            .execute(&self.pool)
-            .await?;
-            
-            self.get_attempt_conversation(attempt_id).await.map(|opt| opt.unwrap())
+            Some(serde_json::to_string(server_ids).unwrap_or_default())
+        };
+        
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::set_attempt_mcp_servers(&mut tx, attempt_id, value.as_deref()).await?;
+        tx.commit().await
+    }
+    
+    pub async fn update_attempt_executor(&self, attempt_id: Uuid, executor: String) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::set_attempt_executor(&mut tx, attempt_id, &executor).await?;
+        tx.commit().await
+    }
+    
+    pub async fn update_attempt_setup_status(&self, attempt_id: Uuid, setup_status: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::set_attempt_setup_status(&mut tx, attempt_id, setup_status).await?;
+        tx.commit().await
+    }
+    
+    /// Setup scripts still recorded as running when the app starts were cut off
+    /// when it last quit. They count as failed, so the setup can be run again or
+    /// skipped and the messages queued behind it aren't held forever.
+    pub async fn fail_interrupted_setups(&self) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let failed = TaskRepository::fail_running_setups(&mut tx).await?;
+        tx.commit().await?;
+        
+        Ok(failed)
+    }
+    
+    /// Add the cost of a finished execution to the attempt's running total
+    pub async fn update_attempt_cost(&self, attempt_id: Uuid, cost_usd: f64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::add_attempt_cost(&mut tx, attempt_id, cost_usd).await?;
+        tx.commit().await
+    }
+    
+    pub async fn get_total_cost_for_project(&self, project_id: Uuid) -> Result<f64, sqlx::Error> {
+        TaskRepository::total_project_cost(&self.pool, project_id).await
+    }
+    
+    pub async fn enqueue_pending_message(
+        &self,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        message: &str,
+        images: Option<&Vec<String>>,
+    ) -> Result<PendingMessage, sqlx::Error> {
+        let pending = PendingMessage {
+            id: Uuid::new_v4().to_string(),
+            task_attempt_id: attempt_id.to_string(),
+            task_id: task_id.to_string(),
+            message: message.to_string(),
+            images: images.cloned(),
+            created_at: chrono::Utc::now(),
+        };
+        
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::insert_pending_message(&mut tx, &pending).await?;
+        let saved = TaskRepository::get_pending_message(&mut *tx, &pending.id).await?;
+        tx.commit().await?;
+        
+        saved.ok_or(sqlx::Error::RowNotFound)
+    }
+    
+    pub async fn get_pending_message(&self, id: Uuid) -> Result<Option<PendingMessage>, sqlx::Error> {
+        TaskRepository::get_pending_message(&self.pool, &id.to_string()).await
+    }
+    
+    /// Pending messages of an attempt in the order they will be sent
+    pub async fn list_pending_messages(&self, attempt_id: Uuid) -> Result<Vec<PendingMessage>, sqlx::Error> {
+        TaskRepository::list_pending_messages(&self.pool, attempt_id).await
+    }
+    
+    pub async fn delete_pending_message(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::delete_pending_message(&mut tx, &id.to_string()).await?;
+        tx.commit().await
+    }
+    
+    /// Remove and return the oldest pending message of an attempt. Hand it to
+    /// `requeue_pending_message` if it can't be sent.
+    pub async fn take_next_pending_message(&self, attempt_id: Uuid) -> Result<Option<PendingMessage>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let next = TaskRepository::list_pending_messages(&mut *tx, attempt_id).await?.into_iter().next();
+        
+        if let Some(pending) = &next {
+            TaskRepository::delete_pending_message(&mut tx, &pending.id).await?;
         }
+        tx.commit().await?;
+        
+        Ok(next)
     }
     
-    pub async fn get_attempt_conversation(&self, attempt_id: Uuid) -> Result<Option<AttemptConversation>, sqlx::Error> {
-        let row: Option<(String, String, String, String, String)> = sqlx::query_as(
-            "SELECT id, task_attempt_id, messages, created_at, updated_at FROM attempt_conversations WHERE task_attempt_id = ?"
+    /// Put a taken message back in its place at the front of the queue
+    pub async fn requeue_pending_message(&self, pending: &PendingMessage) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::insert_pending_message(&mut tx, pending).await?;
+        tx.commit().await
+    }
+    
+    /// Task and attempt ids of every attempt with queued messages
+    pub async fn list_attempts_with_pending_messages(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        TaskRepository::list_attempts_with_pending_messages(&self.pool).await
+    }
+    
+    /// Drop every pending message of an attempt, returning what was removed
+    pub async fn clear_pending_messages(&self, attempt_id: Uuid) -> Result<Vec<PendingMessage>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let pending = TaskRepository::list_pending_messages(&mut *tx, attempt_id).await?;
+        TaskRepository::delete_pending_messages(&mut tx, attempt_id).await?;
+        tx.commit().await?;
+        
+        Ok(pending)
+    }
+
+    pub async fn record_attempt_commit(&self, attempt_id: Uuid, commit_hash: &str, message: &str) -> Result<AttemptCommit, sqlx::Error> {
+        let id = Uuid::new_v4();
+
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::insert_attempt_commit(&mut tx, id, attempt_id, commit_hash, message).await?;
+        tx.commit().await?;
+
+        let kind = TaskEventKind::Committed {
+            commit_hash: commit_hash.to_string(),
+            message: message.to_string(),
+        };
+        self.record_attempt_event(&attempt_id.to_string(), kind).await;
+
+        TaskRepository::get_attempt_commit(&self.pool, id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Add an event that happened in an attempt to its task's timeline. The
+    /// timeline only records what happened, so a failure is logged rather than
+    /// failing the change it describes.
+    pub async fn record_attempt_event(&self, attempt_id: &str, kind: TaskEventKind) {
+        let result = async {
+            let mut tx = self.pool.begin().await?;
+            TaskRepository::record_attempt_event(&mut tx, attempt_id, &kind).await?;
+            tx.commit().await
+        }
+        .await;
+
+        if let Err(e) = result {
+            log::error!("Failed to record {} event for attempt {}: {}", kind.event_type(), attempt_id, e);
+        }
+    }
+
+    /// A page of a task's activity: the latest `limit` events before `before`
+    /// (or the latest overall), oldest first. Pass the `created_at` of the first
+    /// event as `before` to get the page preceding it.
+    pub async fn get_task_timeline(&self, task_id: Uuid, limit: usize, before: Option<chrono::DateTime<chrono::Utc>>) -> Result<Vec<TaskEvent>, sqlx::Error> {
+        let rows = TaskRepository::list_events(&self.pool, task_id, limit, before).await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let id = row.id.clone();
+                TaskEvent::try_from(row)
+                    .map_err(|e| log::warn!("Skipping unreadable task event {}: {}", id, e))
+                    .ok()
+            })
+            .collect())
+    }
+
+    /// Every status change of a task and what caused it, oldest first
+    pub async fn get_task_status_history(&self, task_id: Uuid) -> Result<Vec<TaskStatusChange>, sqlx::Error> {
+        TaskRepository::list_status_changes(&self.pool, task_id).await
+    }
+
+    /// Automatic commits of an attempt, oldest first
+    pub async fn list_attempt_commits(&self, attempt_id: Uuid) -> Result<Vec<AttemptCommit>, sqlx::Error> {
+        TaskRepository::list_attempt_commits(&self.pool, attempt_id).await
+    }
+
+    /// Save a review comment. Comments on the attempt's version remember the
+    /// line they point at, so they can be resolved once the agent changes it.
+    pub async fn create_review_comment(&self, req: CreateReviewCommentRequest) -> Result<ReviewComment, sqlx::Error> {
+        let attempt_uuid = Uuid::parse_str(&req.task_attempt_id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        let attempt = self.get_task_attempt(attempt_uuid).await?.ok_or(sqlx::Error::RowNotFound)?;
+        let line_content = match req.side {
+            DiffSide::New => read_worktree_line(&attempt.worktree_path, &req.file_path, req.line),
+            DiffSide::Old => None,
+        };
+        
+        let id = Uuid::new_v4();
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::insert_review_comment(&mut tx, id, &attempt.id, &req, line_content.as_deref()).await?;
+        tx.commit().await?;
+        
+        self.get_review_comment(id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+    
+    pub async fn get_review_comment(&self, id: Uuid) -> Result<Option<ReviewComment>, sqlx::Error> {
+        TaskRepository::get_review_comment(&self.pool, id).await
+    }
+    
+    /// Review comments of an attempt in file and line order
+    pub async fn list_review_comments(&self, attempt_id: Uuid) -> Result<Vec<ReviewComment>, sqlx::Error> {
+        TaskRepository::list_review_comments(&self.pool, attempt_id).await
+    }
+    
+    pub async fn update_review_comment(&self, id: Uuid, req: UpdateReviewCommentRequest) -> Result<ReviewComment, sqlx::Error> {
+        let comment = self.get_review_comment(id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::update_review_comment(
+            &mut tx,
+            id,
+            req.body.as_deref().unwrap_or(&comment.body),
+            req.resolved.unwrap_or(comment.resolved),
         )
-        .bind(attempt_id.to_string())
-        .fetch_optional(&self.pool)
         .await?;
+        tx.commit().await?;
+        
+        self.get_review_comment(id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+    
+    /// Returns false if the comment didn't exist
+    pub async fn delete_review_comment(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let deleted = TaskRepository::delete_review_comment(&mut tx, id).await?;
+        tx.commit().await?;
+        
+        Ok(deleted)
+    }
+    
+    /// Resolve open comments whose line in the attempt's worktree no longer
+    /// reads as it did when the comment was made. Returns the resolved comments.
+    pub async fn resolve_changed_review_comments(&self, attempt_id: Uuid) -> Result<Vec<ReviewComment>, sqlx::Error> {
+        let Some(attempt) = self.get_task_attempt(attempt_id).await? else {
+            return Ok(Vec::new());
+        };
+        if attempt.worktree_path.is_empty() || !Path::new(&attempt.worktree_path).is_dir() {
+            return Ok(Vec::new());
+        }
         
-        if let Some((id, task_attempt_id, messages_json, created_at, updated_at)) = row {
-            let messages: Vec<ConversationMessage> = serde_json::from_str(&messages_json)
-                .map_err(|e| sqlx::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        let mut resolved = Vec::new();
+        for comment in self.list_review_comments(attempt_id).await? {
+            if comment.resolved || comment.side != DiffSide::New {
+                continue;
+            }
+            let Some(original) = comment.line_content.as_deref() else {
+                continue;
+            };
+            if read_worktree_line(&attempt.worktree_path, &comment.file_path, comment.line).as_deref() == Some(original) {
+                continue;
+            }
             
-            Ok(Some(AttemptConversation {
-                id,
-                task_attempt_id,
-                messages,
-                created_at,
-                updated_at,
-            }))
-        } else {
-            Ok(None)
+            let id = Uuid::parse_str(&comment.id).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+            resolved.push(self.update_review_comment(id, UpdateReviewCommentRequest { body: None, resolved: Some(true) }).await?);
         }
+        
+        Ok(resolved)
+    }
+
+    pub async fn create_task_template(&self, project_id: Uuid, req: CreateTaskTemplateRequest) -> Result<TaskTemplate, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let values = TaskTemplateValues {
+            name: req.name.trim(),
+            title_pattern: &req.title_pattern,
+            description_template: non_blank(req.description_template.as_deref()),
+            priority: req.priority.unwrap_or(TaskPriority::Medium),
+            tags: req.tags.as_ref(),
+            executor: non_blank(req.executor.as_deref()).map(str::trim),
+            initial_prompt: non_blank(req.initial_prompt.as_deref()),
+        };
+        
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::insert_task_template(&mut tx, id, project_id, &values).await?;
+        tx.commit().await?;
+        
+        self.get_task_template(id).await?.ok_or(sqlx::Error::RowNotFound)
     }
     
-    pub async fn update_attempt_claude_session(&self, attempt_id: Uuid, claude_session_id: String) -> Result<(), sqlx::Error> {
-        sqlx::query(
-            "UPDATE task_attempts SET claude_session_id = ? WHERE id = ?"
-        )
-        .bind(&claude_session_id)
-        .bind(attempt_id.to_string())
-        .execute(&self.pool)
-        .await?;
+    pub async fn get_task_template(&self, id: Uuid) -> Result<Option<TaskTemplate>, sqlx::Error> {
+        TaskRepository::get_task_template(&self.pool, id).await
+    }
+    
+    /// Task templates of a project by name
+    pub async fn list_task_templates(&self, project_id: Uuid) -> Result<Vec<TaskTemplate>, sqlx::Error> {
+        TaskRepository::list_task_templates(&self.pool, project_id).await
+    }
+    
+    pub async fn update_task_template(&self, id: Uuid, req: UpdateTaskTemplateRequest) -> Result<TaskTemplate, sqlx::Error> {
+        let template = self.get_task_template(id).await?.ok_or(sqlx::Error::RowNotFound)?;
         
-        Ok(())
+        let tags = req.tags.or(template.tags);
+        let values = TaskTemplateValues {
+            name: req.name.as_deref().map(str::trim).unwrap_or(&template.name),
+            title_pattern: req.title_pattern.as_deref().unwrap_or(&template.title_pattern),
+            description_template: match &req.description_template {
+                Some(description) => non_blank(Some(description.as_str())),
+                None => template.description_template.as_deref(),
+            },
+            priority: req.priority.unwrap_or(template.priority),
+            tags: tags.as_ref(),
+            executor: match &req.executor {
+                Some(executor) => non_blank(Some(executor.as_str())).map(str::trim),
+                None => template.executor.as_deref(),
+            },
+            initial_prompt: match &req.initial_prompt {
+                Some(prompt) => non_blank(Some(prompt.as_str())),
+                None => template.initial_prompt.as_deref(),
+            },
+        };
+        
+        let mut tx = self.pool.begin().await?;
+        TaskRepository::update_task_template(&mut tx, id, &values).await?;
+        tx.commit().await?;
+        
+        self.get_task_template(id).await?.ok_or(sqlx::Error::RowNotFound)
     }
     
-    pub async fn update_attempt_executor(&self, attempt_id: Uuid, executor: String) -> Result<(), sqlx::Error> {
-        sqlx::query(
-            "UPDATE task_attempts SET executor = ? WHERE id = ?"
-        )
-        .bind(&executor)
-        .bind(attempt_id.to_string())
-        .execute(&self.pool)
-        .await?;
+    /// Returns false if the template didn't exist
+    pub async fn delete_task_template(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let deleted = TaskRepository::delete_task_template(&mut tx, id).await?;
+        tx.commit().await?;
         
-        Ok(())
+        Ok(deleted)
+    }
+}
+
+/// `text` unless it is missing or blank
+fn non_blank(text: Option<&str>) -> Option<&str> {
+    text.filter(|t| !t.trim().is_empty())
+}
+
+/// Line `line` (1-based) of a file in a worktree, `None` if either is missing
+fn read_worktree_line(worktree_path: &str, file_path: &str, line: i64) -> Option<String> {
+    let index = usize::try_from(line).ok()?.checked_sub(1)?;
+    let content = std::fs::read_to_string(Path::new(worktree_path).join(file_path)).ok()?;
+    content.lines().nth(index).map(|l| l.to_string())
+}
+
+fn encode_task_cursor(created_at: &str, id: &str) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(format!("{}|{}", created_at, id))
+}
+
+fn decode_task_cursor(cursor: &str) -> Result<(String, String), sqlx::Error> {
+    let invalid = || sqlx::Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("Invalid task cursor: {}", cursor),
+    ));
+    
+    let decoded = general_purpose::URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let (created_at, id) = decoded.rsplit_once('|').ok_or_else(invalid)?;
+    
+    Ok((created_at.to_string(), id.to_string()))
+}
+
+/// Turn free text into an FTS5 query: each word is quoted, so operators and
+/// punctuation typed by the user are matched literally, and prefix matched.
+fn fts_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
     }
-}
\ No newline at end of file
+}
+
+impl From<TaskSearchHit> for TaskSearchResult {
+    /// The hit's best field is the first one, in order of weight, with a
+    /// highlighted match
+    fn from(hit: TaskSearchHit) -> Self {
+        let highlighted = [
+            (MatchField::Title, hit.title_snippet),
+            (MatchField::Tags, hit.tags_snippet),
+            (MatchField::Description, hit.description_snippet),
+            (MatchField::Conversation, hit.conversations_snippet),
+        ]
+        .into_iter()
+        .find_map(|(field, snippet)| snippet.filter(|s| s.contains("<mark>")).map(|s| (field, s)));
+        let task = Task::from(hit.task);
+        let (match_field, snippet) = highlighted.unwrap_or_else(|| (MatchField::Title, task.title.clone()));
+        
+        TaskSearchResult {
+            task,
+            score: -hit.rank as f32,
+            match_field,
+            snippet,
+        }
+    }
+}
+
+/// Replace characters git does not allow in ref names and tidy up separators
+fn sanitize_branch_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    for ch in name.chars() {
+        let ch = if ch.is_ascii_alphanumeric() || matches!(ch, '/' | '-' | '_' | '.') { ch } else { '-' };
+        // Collapse "//", ".." and "--" which are invalid or ugly in ref names
+        if matches!(ch, '/' | '.' | '-') && sanitized.ends_with(ch) {
+            continue;
+        }
+        // No path component may start with a dot
+        if ch == '.' && (sanitized.is_empty() || sanitized.ends_with('/')) {
+            continue;
+        }
+        sanitized.push(ch);
+    }
+    
+    let mut sanitized = sanitized.trim_matches(|c| matches!(c, '/' | '.' | '-')).to_string();
+    while let Some(stripped) = sanitized.strip_suffix(".lock") {
+        sanitized = stripped.trim_end_matches(|c| matches!(c, '/' | '.' | '-')).to_string();
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::TestDb;
+    use crate::models::CreateProjectRequest;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn task_timeline_follows_the_task_and_goes_with_it() {
+        let db = TestDb::new().await;
+        let (_, attempt_ids) = db.seed_attempts(1).await;
+        let task_service = TaskService::new(db.pool.clone());
+        let task_id = Uuid::parse_str(&task_service.get_task_attempt(attempt_ids[0]).await.unwrap().unwrap().task_id).unwrap();
+
+        task_service.update_task_status(task_id, TaskStatus::Working, StatusChangeTrigger::User).await.unwrap();
+        task_service.update_task_status(task_id, TaskStatus::Working, StatusChangeTrigger::User).await.unwrap();
+        task_service.record_attempt_commit(attempt_ids[0], "abc123", "task: first").await.unwrap();
+        let reviewing = task_service.update_task_status(task_id, TaskStatus::Reviewing, StatusChangeTrigger::AgentCompleted).await.unwrap();
+        assert_eq!(reviewing.status_triggered_by, Some(StatusChangeTrigger::AgentCompleted));
+
+        let timeline = task_service.get_task_timeline(task_id, 50, None).await.unwrap();
+        let kinds: Vec<TaskEventKind> = timeline.iter().map(|event| event.kind.clone()).collect();
+        assert_eq!(kinds, vec![
+            TaskEventKind::StatusChanged { from: Some(TaskStatus::Backlog), to: TaskStatus::Working },
+            TaskEventKind::Committed { commit_hash: "abc123".to_string(), message: "task: first".to_string() },
+            TaskEventKind::StatusChanged { from: Some(TaskStatus::Working), to: TaskStatus::Reviewing },
+        ]);
+        assert_eq!(timeline[1].task_attempt_id.as_deref(), Some(attempt_ids[0].to_string().as_str()));
+
+        let latest = task_service.get_task_timeline(task_id, 2, None).await.unwrap();
+        assert_eq!(latest.iter().map(|event| &event.id).collect::<Vec<_>>(), vec![&timeline[1].id, &timeline[2].id]);
+        let earlier = task_service.get_task_timeline(task_id, 2, Some(latest[0].created_at)).await.unwrap();
+        assert_eq!(earlier.iter().map(|event| &event.id).collect::<Vec<_>>(), vec![&timeline[0].id]);
+
+        let history = task_service.get_task_status_history(task_id).await.unwrap();
+        let changes: Vec<_> = history.iter().map(|change| (change.from_status.clone(), change.to_status.clone(), change.triggered_by)).collect();
+        assert_eq!(changes, vec![
+            (Some(TaskStatus::Backlog), TaskStatus::Working, StatusChangeTrigger::User),
+            (Some(TaskStatus::Working), TaskStatus::Reviewing, StatusChangeTrigger::AgentCompleted),
+        ]);
+
+        task_service.delete_task(task_id).await.unwrap();
+        let deleted = task_service.list_deleted_tasks_before(chrono::Utc::now() + chrono::Duration::seconds(1)).await.unwrap();
+        assert_eq!(task_service.purge_tasks(&deleted).await.unwrap(), 1);
+        for table in ["task_events", "task_status_history"] {
+            let (remaining,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table)).fetch_one(&db.pool).await.unwrap();
+            assert_eq!(remaining, 0);
+        }
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn search_finds_titles_descriptions_and_conversations() {
+        let db = TestDb::new().await;
+        let (project_id, attempt_ids) = db.seed_attempts(3).await;
+        let task_service = TaskService::new(db.pool.clone());
+        let mut task_ids = Vec::new();
+        for attempt_id in &attempt_ids {
+            task_ids.push(task_service.get_task_attempt(*attempt_id).await.unwrap().unwrap().task_id);
+        }
+
+        sqlx::query("UPDATE tasks SET title = 'Fix the flux capacitor' WHERE id = ?").bind(&task_ids[0]).execute(&db.pool).await.unwrap();
+        sqlx::query("UPDATE tasks SET description = 'Replace the capacitors' WHERE id = ?").bind(&task_ids[2]).execute(&db.pool).await.unwrap();
+        let message = ConversationMessage {
+            role: "user".to_string(),
+            content: "The capacitor overheats".to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        task_service.save_attempt_conversation(attempt_ids[1], vec![message]).await.unwrap();
+
+        let found = |results: &[TaskSearchResult]| {
+            let mut found: Vec<(String, MatchField)> = results.iter().map(|r| (r.task.id.clone(), r.match_field)).collect();
+            found.sort_by_key(|(id, _)| task_ids.iter().position(|task_id| task_id == id));
+            found
+        };
+
+        let results = task_service.search_tasks(Some(project_id), "capacit", TaskSearchFilters::default()).await.unwrap();
+        assert_eq!(found(&results), vec![
+            (task_ids[0].clone(), MatchField::Title),
+            (task_ids[2].clone(), MatchField::Description),
+        ]);
+        assert!(results.iter().any(|r| r.snippet == "Fix the flux <mark>capacitor</mark>"));
+
+        let with_conversations = TaskSearchFilters { include_conversations: true, ..Default::default() };
+        let results = task_service.search_tasks(None, "capacit", with_conversations.clone()).await.unwrap();
+        assert_eq!(found(&results), vec![
+            (task_ids[0].clone(), MatchField::Title),
+            (task_ids[1].clone(), MatchField::Conversation),
+            (task_ids[2].clone(), MatchField::Description),
+        ]);
+
+        assert!(task_service.search_tasks(Some(Uuid::new_v4()), "capacit", with_conversations).await.unwrap().is_empty());
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn deleted_task_is_hidden_until_restored_or_purged() {
+        let db = TestDb::new().await;
+        let pool = db.pool.clone();
+        let (project_id, attempt_ids) = db.seed_attempts(2).await;
+        let task_service = TaskService::new(pool.clone());
+        let task_id = Uuid::parse_str(&task_service.get_task_attempt(attempt_ids[0]).await.unwrap().unwrap().task_id).unwrap();
+        let message = ConversationMessage {
+            role: "user".to_string(),
+            content: "Keep this around".to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        task_service.save_attempt_conversation(attempt_ids[0], vec![message]).await.unwrap();
+
+        task_service.delete_task(task_id).await.unwrap();
+        let listed = task_service.list_tasks(project_id, true).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_ne!(listed[0].id, task_id.to_string());
+        assert!(task_service.search_tasks(None, "keep", TaskSearchFilters { include_conversations: true, ..Default::default() }).await.unwrap().is_empty());
+
+        // Bulk status updates and reordering leave the deleted task alone
+        let other_id = Uuid::parse_str(&listed[0].id).unwrap();
+        let position_of = |id: Uuid| {
+            let pool = pool.clone();
+            async move {
+                let (position,): (Option<i64>,) = sqlx::query_as("SELECT position FROM tasks WHERE id = ?")
+                    .bind(id.to_string())
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+                position
+            }
+        };
+        let updated = task_service.update_tasks_status(vec![task_id, other_id], TaskStatus::Done, StatusChangeTrigger::User).await.unwrap();
+        assert_eq!(updated.iter().map(|task| task.id.clone()).collect::<Vec<_>>(), vec![other_id.to_string()]);
+        let deleted_position = position_of(task_id).await;
+        task_service.reorder_tasks(project_id, vec![task_id, other_id]).await.unwrap();
+        assert_eq!(position_of(task_id).await, deleted_position);
+        assert_eq!(position_of(other_id).await, Some(0));
+
+        // Still inside the restore window
+        let expired = task_service.list_deleted_tasks_before(chrono::Utc::now() - chrono::Duration::days(30)).await.unwrap();
+        assert_eq!(task_service.purge_tasks(&expired).await.unwrap(), 0);
+
+        let restored = task_service.restore_task(task_id).await.unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(restored.status, TaskStatus::Backlog);
+        assert_eq!(task_service.list_tasks(project_id, false).await.unwrap().len(), 2);
+        assert!(task_service.get_attempt_conversation(attempt_ids[0]).await.unwrap().is_some());
+        assert!(task_service.restore_task(task_id).await.is_err());
+
+        // Restored after being listed for purging
+        task_service.delete_task(task_id).await.unwrap();
+        let deleted = task_service.list_deleted_tasks_before(chrono::Utc::now() + chrono::Duration::seconds(1)).await.unwrap();
+        task_service.restore_task(task_id).await.unwrap();
+        assert_eq!(task_service.purge_tasks(&deleted).await.unwrap(), 0);
+
+        task_service.delete_task(task_id).await.unwrap();
+        let deleted = task_service.list_deleted_tasks_before(chrono::Utc::now() + chrono::Duration::seconds(1)).await.unwrap();
+        assert_eq!(task_service.purge_tasks(&deleted).await.unwrap(), 1);
+        assert!(task_service.get_task(task_id).await.unwrap().is_none());
+        assert!(task_service.get_task_attempt(attempt_ids[0]).await.unwrap().is_none());
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn task_template_renders_placeholders_and_goes_with_its_project() {
+        let db = TestDb::new().await;
+        let (project_id, _) = db.seed_attempts(0).await;
+        let task_service = TaskService::new(db.pool.clone());
+
+        let template = task_service
+            .create_task_template(project_id, CreateTaskTemplateRequest {
+                name: "Bug fix".to_string(),
+                title_pattern: "Fix {{ component }}: {{summary}}".to_string(),
+                description_template: Some("Broken in {{component}} since {{version}}. Keep {{literal braces}}.".to_string()),
+                priority: Some(TaskPriority::High),
+                tags: Some(vec!["bug".to_string()]),
+                executor: Some(" gemini_cli ".to_string()),
+                initial_prompt: Some("Reproduce the {{summary}} bug first".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(template.placeholders, vec!["component", "summary", "version"]);
+        assert_eq!(template.priority, TaskPriority::High);
+        assert_eq!(template.executor.as_deref(), Some("gemini_cli"));
+
+        let values: HashMap<String, String> = [("component", "parser"), ("summary", "crash on empty input")]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let error = template.render(&values).unwrap_err();
+        assert!(error.contains("version") && !error.contains("component"));
+
+        let mut values = values;
+        values.insert("version".to_string(), "2.1".to_string());
+        let rendered = template.render(&values).unwrap();
+        assert_eq!(rendered.title, "Fix parser: crash on empty input");
+        assert_eq!(rendered.description.as_deref(), Some("Broken in parser since 2.1. Keep {{literal braces}}."));
+        assert_eq!(rendered.initial_prompt.as_deref(), Some("Reproduce the crash on empty input bug first"));
+
+        let template_id = Uuid::parse_str(&template.id).unwrap();
+        let updated = task_service
+            .update_task_template(template_id, UpdateTaskTemplateRequest {
+                name: None,
+                title_pattern: None,
+                description_template: None,
+                priority: None,
+                tags: None,
+                executor: Some(String::new()),
+                initial_prompt: Some("  ".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(updated.name, "Bug fix");
+        assert!(updated.executor.is_none() && updated.initial_prompt.is_none());
+        assert_eq!(updated.placeholders, vec!["component", "summary", "version"]);
+        assert_eq!(task_service.list_task_templates(project_id).await.unwrap().len(), 1);
+
+        ProjectService::new(db.pool.clone()).delete_project(project_id).await.unwrap();
+        assert!(task_service.get_task_template(template_id).await.unwrap().is_none());
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn moved_task_takes_its_subtasks_along() {
+        let db = TestDb::new().await;
+        let (project_id, attempt_ids) = db.seed_attempts(4).await;
+        let task_service = TaskService::new(db.pool.clone());
+        let mut task_ids = Vec::new();
+        for attempt_id in &attempt_ids {
+            task_ids.push(Uuid::parse_str(&task_service.get_task_attempt(*attempt_id).await.unwrap().unwrap().task_id).unwrap());
+        }
+        // 0 <- 1 <- 2, while 3 stays on its own
+        for (child, parent) in [(1, 0), (2, 1)] {
+            sqlx::query("UPDATE tasks SET parent_task_id = ? WHERE id = ?")
+                .bind(task_ids[parent].to_string())
+                .bind(task_ids[child].to_string())
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+        task_service.delete_task(task_ids[2]).await.unwrap();
+
+        let target_path = db.dir().join("target");
+        std::fs::create_dir(&target_path).unwrap();
+        let target = ProjectService::new(db.pool.clone())
+            .create_project(CreateProjectRequest {
+                name: "Target".to_string(),
+                description: None,
+                path: target_path.to_string_lossy().to_string(),
+                git_repo: None,
+                main_branch: None,
+                setup_script: None,
+                dev_script: None,
+                git_enabled: None,
+                subdirectory: None,
+            })
+            .await
+            .unwrap();
+        let target_id = Uuid::parse_str(&target.id).unwrap();
+
+        // The worktree of a subtask's attempt stays in the old project's repository
+        let worktree_path = db.dir().join("worktree-2").to_string_lossy().to_string();
+        std::fs::create_dir(&worktree_path).unwrap();
+        sqlx::query("UPDATE task_attempts SET worktree_path = ? WHERE id = ?")
+            .bind(&worktree_path)
+            .bind(attempt_ids[2].to_string())
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let moved = task_service.move_task_to_project(task_ids[1], target_id).await.unwrap();
+        assert_eq!(moved.task.project_id, target.id);
+        assert!(moved.task.parent_task_id.is_none());
+        assert_eq!(moved.left_behind_worktrees, vec![worktree_path]);
+        let project_of = |id: Uuid| {
+            let pool = db.pool.clone();
+            async move {
+                let (project_id, parent_id): (String, Option<String>) = sqlx::query_as("SELECT project_id, parent_task_id FROM tasks WHERE id = ?")
+                    .bind(id.to_string())
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+                (project_id, parent_id)
+            }
+        };
+        assert_eq!(project_of(task_ids[2]).await, (target.id.clone(), Some(task_ids[1].to_string())));
+        assert_eq!(project_of(task_ids[0]).await.0, project_id.to_string());
+        assert_eq!(project_of(task_ids[3]).await.0, project_id.to_string());
+        assert!(task_service.list_subtasks(task_ids[0]).await.unwrap().is_empty());
+
+        task_service.restore_task(task_ids[2]).await.unwrap();
+        assert_eq!(task_service.list_subtasks(task_ids[1]).await.unwrap().len(), 1);
+
+        db.close().await;
+    }
+
+    #[tokio::test]
+    async fn interrupted_setups_count_as_failed() {
+        let db = TestDb::new().await;
+        let (_, attempt_ids) = db.seed_attempts(2).await;
+        let task_service = TaskService::new(db.pool.clone());
+        task_service.update_attempt_setup_status(attempt_ids[0], "running").await.unwrap();
+        task_service.update_attempt_setup_status(attempt_ids[1], "completed").await.unwrap();
+
+        assert_eq!(task_service.fail_interrupted_setups().await.unwrap(), 1);
+        let setup_status = |attempt: Option<TaskAttempt>| attempt.unwrap().setup_status;
+        assert_eq!(setup_status(task_service.get_task_attempt(attempt_ids[0]).await.unwrap()).as_deref(), Some("failed"));
+        assert_eq!(setup_status(task_service.get_task_attempt(attempt_ids[1]).await.unwrap()).as_deref(), Some("completed"));
+
+        db.close().await;
+    }
+}
@@ -1,12 +1,135 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio::time::{interval, Duration};
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{watch, Mutex};
+use tokio::time::{interval, Duration, Instant};
 use sqlx::SqlitePool;
 use uuid::Uuid;
 use tauri::{AppHandle, Emitter};
 
-use crate::models::{TaskStatus, MergeRequest};
+use crate::models::{StatusChangeTrigger, TaskStatus, MergeRequest};
 use crate::services::{GitLabService, GitHubService, MergeRequestService, TaskService, git_platform::GitPlatformService};
+use crate::services::merge_request_service::{apply_provider_status, remote_info_from_web_url};
+
+/// A task status change caused by a single sync cycle
+#[derive(Debug, Clone, Serialize)]
+pub struct VcsSyncChange {
+    pub task_id: String,
+    pub old_status: TaskStatus,
+    pub new_status: TaskStatus,
+    pub mr_id: i64,
+}
+
+/// Longest an MR or provider waits between checks after repeated failures
+const MAX_BACKOFF_SECONDS: u64 = 60 * 60;
+
+/// Consecutive 404s after which an MR is treated as deleted and no longer synced
+const MAX_NOT_FOUND_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SyncErrorKind {
+    NotFound,
+    RateLimited,
+    Unauthorized,
+    Other,
+}
+
+impl SyncErrorKind {
+    /// Platform services report failures as "<Provider> API error (<status>): ..."
+    fn classify(error: &str) -> Self {
+        if error.contains("(404") {
+            SyncErrorKind::NotFound
+        } else if error.contains("(429") || error.to_lowercase().contains("rate limit") {
+            SyncErrorKind::RateLimited
+        } else if error.contains("(401") || error.contains("(403") {
+            SyncErrorKind::Unauthorized
+        } else {
+            SyncErrorKind::Other
+        }
+    }
+    
+    /// Errors that will fail every request to the provider, not just this MR
+    fn affects_provider(self) -> bool {
+        matches!(self, SyncErrorKind::RateLimited | SyncErrorKind::Unauthorized)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Backoff {
+    consecutive_failures: u32,
+    not_found_failures: u32,
+    next_check_at: Option<Instant>,
+}
+
+impl Backoff {
+    fn is_due(&self, now: Instant) -> bool {
+        self.next_check_at.map(|at| now >= at).unwrap_or(true)
+    }
+    
+    /// Double the delay for every consecutive failure, starting at the sync interval
+    fn record_failure(&mut self, base_seconds: u64, now: Instant) -> Duration {
+        self.consecutive_failures += 1;
+        let factor = 2u64.saturating_pow(self.consecutive_failures.saturating_sub(1).min(16));
+        let delay = Duration::from_secs(base_seconds.saturating_mul(factor).min(MAX_BACKOFF_SECONDS));
+        self.next_check_at = Some(now + delay);
+        delay
+    }
+}
+
+/// Failure tracking for individual MRs and for each provider as a whole
+#[derive(Debug, Default)]
+struct SyncBackoff {
+    merge_requests: HashMap<i64, Backoff>,
+    providers: HashMap<String, Backoff>,
+}
+
+impl SyncBackoff {
+    fn is_due(&self, mr: &MergeRequest, now: Instant) -> bool {
+        let provider_due = self.providers.get(&mr.provider)
+            .map(|b| b.is_due(now))
+            .unwrap_or(true);
+        let mr_due = self.merge_requests.get(&mr.id)
+            .map(|b| b.not_found_failures < MAX_NOT_FOUND_FAILURES && b.is_due(now))
+            .unwrap_or(true);
+        provider_due && mr_due
+    }
+    
+    fn record_success(&mut self, mr: &MergeRequest) {
+        self.merge_requests.remove(&mr.id);
+        self.providers.remove(&mr.provider);
+    }
+    
+    fn record_failure(&mut self, mr: &MergeRequest, kind: SyncErrorKind, base_seconds: u64) {
+        let now = Instant::now();
+        
+        if kind.affects_provider() {
+            let delay = self.providers.entry(mr.provider.clone()).or_default()
+                .record_failure(base_seconds, now);
+            log::warn!("Backing off {} sync for {:?} after {:?} error", mr.provider, delay, kind);
+            return;
+        }
+        
+        let backoff = self.merge_requests.entry(mr.id).or_default();
+        if kind == SyncErrorKind::NotFound {
+            backoff.not_found_failures += 1;
+            if backoff.not_found_failures >= MAX_NOT_FOUND_FAILURES {
+                log::warn!("MR/PR {} returned 404 {} times in a row, no longer syncing it", mr.title, backoff.not_found_failures);
+                return;
+            }
+        } else {
+            backoff.not_found_failures = 0;
+        }
+        
+        let delay = backoff.record_failure(base_seconds, now);
+        log::debug!("Next sync of MR/PR {} in {:?}", mr.title, delay);
+    }
+    
+    /// Forget MRs that are no longer open
+    fn retain(&mut self, open_ids: &HashSet<i64>) {
+        self.merge_requests.retain(|id, _| open_ids.contains(id));
+    }
+}
 
 /// VCS (Version Control System) Sync Service
 /// Periodically syncs MR/PR status and updates task status accordingly
@@ -16,7 +139,12 @@ pub struct VcsSyncService {
     github_service: Arc<Mutex<GitHubService>>,
     merge_request_service: Arc<MergeRequestService>,
     task_service: Arc<TaskService>,
-    sync_interval_seconds: u64,
+    /// Live settings; a new value restarts the interval or pauses the loop
+    config_rx: watch::Receiver<VcsSyncConfig>,
+    /// Set once the first (startup) cycle has finished
+    initial_sync_done: AtomicBool,
+    /// Delays for MRs and providers whose last sync failed
+    backoff: std::sync::Mutex<SyncBackoff>,
     app_handle: AppHandle,
 }
 
@@ -25,7 +153,7 @@ impl VcsSyncService {
         pool: SqlitePool,
         gitlab_service: Arc<Mutex<GitLabService>>,
         github_service: Arc<Mutex<GitHubService>>,
-        sync_interval_seconds: u64,
+        config_rx: watch::Receiver<VcsSyncConfig>,
         app_handle: AppHandle,
     ) -> Self {
         let merge_request_service = Arc::new(MergeRequestService::new(pool.clone()));
@@ -37,22 +165,46 @@ impl VcsSyncService {
             github_service,
             merge_request_service,
             task_service,
-            sync_interval_seconds,
+            config_rx,
+            initial_sync_done: AtomicBool::new(false),
+            backoff: std::sync::Mutex::new(SyncBackoff::default()),
             app_handle,
         }
     }
 
-    /// Start the background sync service
+    /// Start the background sync service. Runs until the config sender is dropped
     pub async fn start_background_sync(self: Arc<Self>) {
-        log::info!("Starting VCS sync service with interval: {} seconds", self.sync_interval_seconds);
-        
-        let mut interval = interval(Duration::from_secs(self.sync_interval_seconds));
+        let mut config_rx = self.config_rx.clone();
         
         loop {
-            interval.tick().await;
+            let config = config_rx.borrow_and_update().clone();
+            
+            if !config.enabled {
+                log::info!("VCS sync paused");
+                if config_rx.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            }
             
-            if let Err(e) = self.sync_all_merge_requests().await {
-                log::error!("Error during VCS sync: {:?}", e);
+            log::info!("Starting VCS sync service with interval: {} seconds", config.interval_seconds());
+            let mut interval = interval(Duration::from_secs(config.interval_seconds()));
+            
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if let Err(e) = self.sync_all_merge_requests().await {
+                            log::error!("Error during VCS sync: {:?}", e);
+                        }
+                    }
+                    changed = config_rx.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                        // Restart with the new settings
+                        break;
+                    }
+                }
             }
         }
     }
@@ -63,19 +215,52 @@ impl VcsSyncService {
         
         // Get all open merge requests from database
         let merge_requests = self.get_open_merge_requests().await?;
+        let mut changes = Vec::new();
+        let base_seconds = self.config_rx.borrow().interval_seconds();
+        
+        let open_ids: HashSet<i64> = merge_requests.iter().map(|mr| mr.id).collect();
+        self.backoff.lock().unwrap().retain(&open_ids);
         
         for mr in merge_requests {
-            if let Err(e) = self.sync_single_merge_request(mr).await {
-                log::error!("Failed to sync merge request: {:?}", e);
+            // Skip MRs (or whole providers) that are still backing off
+            if !self.backoff.lock().unwrap().is_due(&mr, Instant::now()) {
+                continue;
             }
+            
+            let result = self.sync_single_merge_request(mr.clone()).await;
+            let mut backoff = self.backoff.lock().unwrap();
+            match result {
+                Ok(change) => {
+                    backoff.record_success(&mr);
+                    changes.extend(change);
+                }
+                Err(e) => {
+                    log::error!("Failed to sync merge request: {:?}", e);
+                    backoff.record_failure(&mr, SyncErrorKind::classify(&e.to_string()), base_seconds);
+                }
+            }
+        }
+        
+        self.initial_sync_done.store(true, Ordering::SeqCst);
+        
+        // One event per cycle so the frontend refreshes the board only once
+        if !changes.is_empty() {
+            let _ = self.app_handle.emit("vcs:sync-cycle-completed", serde_json::json!({
+                "changes": changes,
+            }));
         }
         
-        log::debug!("VCS sync cycle completed");
+        log::debug!("VCS sync cycle completed with {} task changes", changes.len());
         Ok(())
     }
+    
+    /// Per-MR events are skipped during the startup cycle to avoid event storms
+    fn should_emit_individual_events(&self) -> bool {
+        self.config_rx.borrow().emit_individual_events && self.initial_sync_done.load(Ordering::SeqCst)
+    }
 
     /// Sync a single merge request
-    async fn sync_single_merge_request(&self, mr: MergeRequest) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn sync_single_merge_request(&self, mr: MergeRequest) -> Result<Option<VcsSyncChange>, Box<dyn std::error::Error + Send + Sync>> {
         log::debug!("Syncing MR/PR: {} ({})", mr.title, mr.provider);
         
         // Fetch latest status from provider
@@ -84,29 +269,39 @@ impl VcsSyncService {
             "github" => self.sync_github_pr(&mr).await?,
             _ => {
                 log::debug!("Unknown provider: {}", mr.provider);
-                return Ok(());
+                return Ok(None);
             }
         };
         
-        // Emit event when MR/PR status changes
-        if mr.state != updated_mr.state {
+        // Emit event when MR/PR status or its pipeline's status changes
+        let changed = mr.state != updated_mr.state || mr.pipeline_status != updated_mr.pipeline_status;
+        if changed && self.should_emit_individual_events() {
             let _ = self.app_handle.emit("vcs:merge-request-updated", serde_json::json!({
                 "mr_id": updated_mr.id,
                 "previous_state": mr.state,
                 "new_state": updated_mr.state,
+                "previous_pipeline_status": mr.pipeline_status,
+                "new_pipeline_status": updated_mr.pipeline_status,
                 "task_attempt_id": updated_mr.task_attempt_id,
             }));
             
+        }
+        
+        if mr.state != updated_mr.state {
             log::info!("MR/PR {} state changed from {} to {}", mr.title, mr.state, updated_mr.state);
         }
         
-        // Check if MR was just merged
+        // Check if MR was just merged or closed without merging
         if mr.state != "merged" && updated_mr.state == "merged" {
             log::info!("MR/PR {} has been merged, updating task status", mr.title);
-            self.update_task_status_to_done(&updated_mr).await?;
+            return self.update_task_status_for_mr(&updated_mr, TaskStatus::Done).await;
+        }
+        if mr.state != "closed" && updated_mr.state == "closed" {
+            log::info!("MR/PR {} has been closed without merging, updating task status", mr.title);
+            return self.update_task_status_for_mr(&updated_mr, TaskStatus::Cancelled).await;
         }
         
-        Ok(())
+        Ok(None)
     }
 
     /// Sync GitLab merge request
@@ -119,23 +314,7 @@ impl VcsSyncService {
         // Call GitLab API to get latest MR status
         let updated_mr_info = gitlab.update_merge_request_status(&remote_info, mr.mr_iid).await?;
         
-        // Update the MergeRequest with the new status
-        let mut updated_mr = mr.clone();
-        updated_mr.state = match updated_mr_info.state {
-            crate::models::MergeRequestState::Opened => "opened".to_string(),
-            crate::models::MergeRequestState::Closed => "closed".to_string(),
-            crate::models::MergeRequestState::Merged => "merged".to_string(),
-            crate::models::MergeRequestState::Locked => "locked".to_string(),
-        };
-        
-        if let Some(merge_status) = updated_mr_info.merge_status {
-            updated_mr.merge_status = Some(format!("{:?}", merge_status));
-        }
-        updated_mr.has_conflicts = updated_mr_info.has_conflicts;
-        
-        if let Some(pipeline_status) = updated_mr_info.pipeline_status {
-            updated_mr.pipeline_status = Some(format!("{:?}", pipeline_status));
-        }
+        let updated_mr = apply_provider_status(mr, updated_mr_info);
         
         // Update in database
         self.update_merge_request_in_db(&updated_mr).await?;
@@ -153,23 +332,7 @@ impl VcsSyncService {
         // Call GitHub API to get latest PR status
         let updated_pr_info = github.update_merge_request_status(&remote_info, mr.mr_number).await?;
         
-        // Update the MergeRequest with the new status
-        let mut updated_mr = mr.clone();
-        updated_mr.state = match updated_pr_info.state {
-            crate::models::MergeRequestState::Opened => "opened".to_string(),
-            crate::models::MergeRequestState::Closed => "closed".to_string(),
-            crate::models::MergeRequestState::Merged => "merged".to_string(),
-            crate::models::MergeRequestState::Locked => "locked".to_string(),
-        };
-        
-        if let Some(merge_status) = updated_pr_info.merge_status {
-            updated_mr.merge_status = Some(format!("{:?}", merge_status));
-        }
-        updated_mr.has_conflicts = updated_pr_info.has_conflicts;
-        
-        if let Some(pipeline_status) = updated_pr_info.pipeline_status {
-            updated_mr.pipeline_status = Some(format!("{:?}", pipeline_status));
-        }
+        let updated_mr = apply_provider_status(mr, updated_pr_info);
         
         // Update in database
         self.update_merge_request_in_db(&updated_mr).await?;
@@ -177,8 +340,10 @@ impl VcsSyncService {
         Ok(updated_mr)
     }
 
-    /// Update task status to DONE when MR is merged
-    async fn update_task_status_to_done(&self, mr: &MergeRequest) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// Move the task behind an MR to `new_status`: Done when merged, Cancelled
+    /// when closed without merging. Finished tasks are left alone, and a closed
+    /// MR only cancels tasks that are still in progress.
+    async fn update_task_status_for_mr(&self, mr: &MergeRequest, new_status: TaskStatus) -> Result<Option<VcsSyncChange>, Box<dyn std::error::Error + Send + Sync>> {
         // Get task attempt from MR
         let mut conn = self.pool.acquire().await?;
         
@@ -192,23 +357,47 @@ impl VcsSyncService {
         .await?;
         
         if let Some(task_id_str) = task_id {
-            // Parse UUID and update task status to Done
+            // Parse UUID and read the current status from the database
             let task_uuid = Uuid::parse_str(&task_id_str)?;
-            let previous_status = TaskStatus::Working; // Assume it was Working before merged
-            let updated_task = self.task_service.update_task_status(task_uuid, TaskStatus::Done).await?;
+            let previous_status = match self.task_service.get_task(task_uuid).await? {
+                Some(task) => task.status,
+                None => return Ok(None),
+            };
             
-            // Emit event to frontend
-            let _ = self.app_handle.emit("task:status-changed", serde_json::json!({
-                "taskId": task_id_str,
-                "previousStatus": previous_status,
-                "newStatus": TaskStatus::Done,
-                "task": updated_task,
-            }));
+            let should_update = match new_status {
+                TaskStatus::Cancelled => matches!(previous_status, TaskStatus::Working | TaskStatus::Reviewing),
+                _ => previous_status != TaskStatus::Done && previous_status != new_status,
+            };
+            if !should_update {
+                return Ok(None);
+            }
+            
+            let trigger = match new_status {
+                TaskStatus::Done => StatusChangeTrigger::VcsMerged,
+                _ => StatusChangeTrigger::VcsClosed,
+            };
+            let updated_task = self.task_service.update_task_status(task_uuid, new_status.clone(), trigger).await?;
+            
+            if self.should_emit_individual_events() {
+                let _ = self.app_handle.emit("task:status-changed", serde_json::json!({
+                    "taskId": task_id_str,
+                    "previousStatus": previous_status,
+                    "newStatus": new_status,
+                    "task": updated_task,
+                }));
+            }
             
-            log::info!("Updated task {} status to Done and notified frontend", task_id_str);
+            log::info!("Updated task {} status from {:?} to {:?}", task_id_str, previous_status, new_status);
+            
+            return Ok(Some(VcsSyncChange {
+                task_id: task_id_str,
+                old_status: previous_status,
+                new_status,
+                mr_id: mr.id,
+            }));
         }
         
-        Ok(())
+        Ok(None)
     }
 
     /// Get all open merge requests from database
@@ -218,92 +407,33 @@ impl VcsSyncService {
     
     /// Update merge request in database
     async fn update_merge_request_in_db(&self, mr: &MergeRequest) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut conn = self.pool.acquire().await?;
-        
-        let query = r#"
-            UPDATE merge_requests SET
-                state = ?, 
-                merge_status = ?, 
-                has_conflicts = ?, 
-                pipeline_status = ?,
-                synced_at = CURRENT_TIMESTAMP
-            WHERE id = ?
-        "#;
-
-        sqlx::query(query)
-            .bind(&mr.state)
-            .bind(&mr.merge_status)
-            .bind(mr.has_conflicts)
-            .bind(&mr.pipeline_status)
-            .bind(mr.id)
-            .execute(&mut *conn)
-            .await?;
-            
-        Ok(())
+        self.merge_request_service.update_sync_status(mr).await
     }
     
     /// Parse GitLab URL to extract GitRemoteInfo
     fn parse_gitlab_remote_info(&self, web_url: &str) -> Result<crate::models::GitRemoteInfo, Box<dyn std::error::Error + Send + Sync>> {
-        // Example URL: https://gitlab.com/owner/repo/-/merge_requests/123
-        // or https://gitlab.company.com/owner/repo/-/merge_requests/123
-        let url = reqwest::Url::parse(web_url)?;
-        let host = url.host_str().ok_or("Invalid URL: no host")?;
-        
-        let path_segments: Vec<&str> = url.path_segments()
-            .ok_or("Invalid URL: no path")?
-            .collect();
-        
-        if path_segments.len() < 2 {
-            return Err("Invalid GitLab URL format".into());
-        }
-        
-        let owner = path_segments[0].to_string();
-        let repo = path_segments[1].to_string();
-        
-        Ok(crate::models::GitRemoteInfo {
-            provider: crate::models::GitProvider::GitLab,
-            owner,
-            repo,
-            host: if host != "gitlab.com" {
-                Some(format!("https://{}", host))
-            } else {
-                None
-            },
-        })
+        remote_info_from_web_url(crate::models::GitProvider::GitLab, web_url)
     }
     
     /// Parse GitHub URL to extract GitRemoteInfo
     fn parse_github_remote_info(&self, web_url: &str) -> Result<crate::models::GitRemoteInfo, Box<dyn std::error::Error + Send + Sync>> {
-        // Example URL: https://github.com/owner/repo/pull/123
-        let url = reqwest::Url::parse(web_url)?;
-        
-        let path_segments: Vec<&str> = url.path_segments()
-            .ok_or("Invalid URL: no path")?
-            .collect();
-        
-        if path_segments.len() < 2 {
-            return Err("Invalid GitHub URL format".into());
-        }
-        
-        let owner = path_segments[0].to_string();
-        let repo = path_segments[1].to_string();
-        
-        Ok(crate::models::GitRemoteInfo {
-            provider: crate::models::GitProvider::GitHub,
-            owner,
-            repo,
-            host: None, // GitHub doesn't need custom host
-        })
+        remote_info_from_web_url(crate::models::GitProvider::GitHub, web_url)
     }
 }
 
-/// Configuration for VCS sync service
-#[derive(Debug, Clone)]
+/// Shortest interval accepted, to stay clear of provider rate limits
+pub const MIN_VCS_SYNC_INTERVAL_SECONDS: u64 = 10;
+
+/// Configuration for VCS sync service, persisted by `ConfigService`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct VcsSyncConfig {
     /// Sync interval in seconds (default: 60)
     pub sync_interval_seconds: u64,
     /// Enable auto-sync (default: true)
     pub enabled: bool,
+    /// Emit per-MR/task events alongside the batched cycle event (default: true)
+    pub emit_individual_events: bool,
 }
 
 impl Default for VcsSyncConfig {
@@ -311,6 +441,13 @@ impl Default for VcsSyncConfig {
         Self {
             sync_interval_seconds: 60, // Check every minute
             enabled: true,
+            emit_individual_events: true,
         }
     }
-}
\ No newline at end of file
+}
+
+impl VcsSyncConfig {
+    pub fn interval_seconds(&self) -> u64 {
+        self.sync_interval_seconds.max(MIN_VCS_SYNC_INTERVAL_SECONDS)
+    }
+}
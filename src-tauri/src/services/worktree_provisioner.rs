@@ -0,0 +1,235 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+use crate::models::CacheLinkMode;
+use crate::utils::command::execute_git;
+
+/// File inside the worktree's private git dir listing the paths we linked
+const MANIFEST_FILE: &str = "pivo-cache-links";
+
+/// Shares dependency/build caches (node_modules, target, ...) from the main
+/// checkout with a freshly created worktree so setup does not start from scratch.
+pub struct WorktreeProvisioner {
+    source_root: PathBuf,
+    mode: CacheLinkMode,
+}
+
+impl WorktreeProvisioner {
+    pub fn new(source_root: &Path, mode: CacheLinkMode) -> Self {
+        Self {
+            source_root: source_root.to_path_buf(),
+            mode,
+        }
+    }
+
+    /// Link or clone every cache path into the worktree. Paths missing from the
+    /// source checkout or already present in the worktree are skipped.
+    /// Returns the relative paths that were provisioned.
+    pub fn provision(&self, worktree_path: &Path, cache_links: &[String]) -> Vec<String> {
+        let mut provisioned = Vec::new();
+
+        for link in cache_links {
+            let relative = match sanitize_relative_path(link) {
+                Some(relative) => relative,
+                None => {
+                    log::warn!("Ignoring invalid cache link path: {}", link);
+                    continue;
+                }
+            };
+
+            let source = self.source_root.join(&relative);
+            if !source.exists() {
+                log::info!("Cache path {:?} does not exist in the main checkout, skipping", source);
+                continue;
+            }
+
+            let target = worktree_path.join(&relative);
+            if fs::symlink_metadata(&target).is_ok() {
+                log::info!("Cache path {:?} already exists in the worktree, skipping", target);
+                continue;
+            }
+
+            if let Some(parent) = target.parent() {
+                if let Err(e) = fs::create_dir_all(parent) {
+                    log::warn!("Failed to create {:?}: {}", parent, e);
+                    continue;
+                }
+            }
+
+            let result = match self.mode {
+                CacheLinkMode::Symlink => create_symlink(&source, &target),
+                CacheLinkMode::Clone => clone_tree(&source, &target),
+            };
+
+            match result {
+                Ok(()) => {
+                    log::info!("Provisioned cache path {} ({:?})", relative.display(), self.mode);
+                    provisioned.push(relative.to_string_lossy().to_string());
+                }
+                Err(e) => log::warn!("Failed to provision cache path {}: {}", relative.display(), e),
+            }
+        }
+
+        if !provisioned.is_empty() {
+            if let Err(e) = write_manifest(worktree_path, &provisioned) {
+                log::warn!("Failed to record cache links for {:?}: {}", worktree_path, e);
+            }
+            if let Err(e) = exclude_from_git(worktree_path, &provisioned) {
+                log::warn!("Failed to exclude cache links from git status: {}", e);
+            }
+        }
+
+        provisioned
+    }
+
+    /// Remove the symlinks created by `provision` so that deleting the worktree
+    /// can never reach into the main checkout through them. Must run before
+    /// `git worktree remove`, which deletes the manifest along with the admin dir.
+    pub fn unlink_cache_links(worktree_path: &Path) {
+        let manifest = match worktree_git_dir(worktree_path) {
+            Some(git_dir) => git_dir.join(MANIFEST_FILE),
+            None => return,
+        };
+        let contents = match fs::read_to_string(&manifest) {
+            Ok(contents) => contents,
+            Err(_) => return,
+        };
+
+        for relative in contents.lines().filter_map(sanitize_relative_path) {
+            let target = worktree_path.join(&relative);
+            let is_symlink = fs::symlink_metadata(&target)
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or(false);
+            if !is_symlink {
+                continue;
+            }
+
+            // remove_file on a symlink deletes the link itself, never its target
+            #[cfg(windows)]
+            let result = fs::remove_dir(&target).or_else(|_| fs::remove_file(&target));
+            #[cfg(not(windows))]
+            let result = fs::remove_file(&target);
+
+            if let Err(e) = result {
+                log::warn!("Failed to remove cache link {:?}: {}", target, e);
+            }
+        }
+    }
+}
+
+/// Accept only plain relative paths that stay inside the checkout
+fn sanitize_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path.trim());
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    if relative.as_os_str().is_empty() || relative.starts_with(".git") {
+        None
+    } else {
+        Some(relative)
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn create_symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    if source.is_dir() {
+        std::os::windows::fs::symlink_dir(source, target)
+    } else {
+        std::os::windows::fs::symlink_file(source, target)
+    }
+}
+
+/// Recreate the directory structure and hard link every file, falling back to
+/// a copy when hard links are not possible (e.g. across filesystems)
+fn clone_tree(source: &Path, target: &Path) -> std::io::Result<()> {
+    if source.is_file() {
+        return fs::hard_link(source, target).or_else(|_| fs::copy(source, target).map(|_| ()));
+    }
+
+    for entry in WalkDir::new(source).follow_links(false) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let destination = target.join(relative);
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            fs::create_dir_all(&destination)?;
+        } else if file_type.is_symlink() {
+            let link_target = fs::read_link(entry.path())?;
+            create_symlink(&link_target, &destination)?;
+        } else if fs::hard_link(entry.path(), &destination).is_err() {
+            fs::copy(entry.path(), &destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve the private git dir of a linked worktree from its `.git` file
+fn worktree_git_dir(worktree_path: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(worktree_path.join(".git")).ok()?;
+    let git_dir = contents.trim().strip_prefix("gitdir:")?.trim();
+    let git_dir = PathBuf::from(git_dir);
+    Some(if git_dir.is_absolute() { git_dir } else { worktree_path.join(git_dir) })
+}
+
+fn write_manifest(worktree_path: &Path, provisioned: &[String]) -> std::io::Result<()> {
+    let git_dir = worktree_git_dir(worktree_path)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "worktree git dir not found"))?;
+    fs::write(git_dir.join(MANIFEST_FILE), provisioned.join("\n"))
+}
+
+/// Symlinked directories are not matched by `dir/` ignore patterns, so add
+/// anchored entries to the repository's exclude file to keep them out of commits
+fn exclude_from_git(worktree_path: &Path, provisioned: &[String]) -> Result<(), String> {
+    let output = execute_git(&["rev-parse", "--git-path", "info/exclude"], worktree_path)
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let exclude_path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let exclude_path = if exclude_path.is_absolute() {
+        exclude_path
+    } else {
+        worktree_path.join(exclude_path)
+    };
+
+    let existing = fs::read_to_string(&exclude_path).unwrap_or_default();
+    let missing: Vec<String> = provisioned
+        .iter()
+        .map(|p| format!("/{}", p.replace('\\', "/")))
+        .filter(|entry| !existing.lines().any(|line| line.trim() == entry))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(parent) = exclude_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&exclude_path)
+        .map_err(|e| e.to_string())?;
+    let prefix = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+    writeln!(file, "{}{}", prefix, missing.join("\n")).map_err(|e| e.to_string())
+}
@@ -0,0 +1,132 @@
+use crate::models::{AllWorktreesUsage, AttemptWorktree, PathUsage, WorktreeUsage};
+use crate::services::{ConfigService, TaskService};
+use crate::utils::disk_usage::directory_usage;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+
+/// How often worktree sizes are compared with the configured limit
+const WORKTREE_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Largest top-level paths reported per worktree
+const LARGEST_PATHS: usize = 10;
+
+/// Disk usage of an attempt's worktree. Its `.git` is a file pointing at the
+/// project repository, whose objects are shared and not counted here.
+pub fn worktree_usage(worktree: AttemptWorktree) -> WorktreeUsage {
+    let (bytes, entries) = directory_usage(Path::new(&worktree.worktree_path), &[".git"]);
+
+    WorktreeUsage {
+        attempt_id: worktree.attempt_id,
+        task_id: worktree.task_id,
+        task_title: worktree.task_title,
+        project_id: worktree.project_id,
+        project_path: worktree.project_path,
+        worktree_path: worktree.worktree_path,
+        bytes,
+        largest_paths: entries
+            .into_iter()
+            .take(LARGEST_PATHS)
+            .map(|(path, bytes)| PathUsage { path, bytes })
+            .collect(),
+    }
+}
+
+/// Disk usage of every worktree that still exists, biggest first, with the
+/// `.git` of each project they belong to counted once
+pub fn all_worktrees_usage(worktrees: Vec<AttemptWorktree>) -> AllWorktreesUsage {
+    let worktrees: Vec<AttemptWorktree> = worktrees
+        .into_iter()
+        .filter(|worktree| Path::new(&worktree.worktree_path).is_dir())
+        .collect();
+
+    let project_paths: HashSet<&str> = worktrees.iter().map(|w| w.project_path.as_str()).collect();
+    let shared_git_bytes = project_paths
+        .into_iter()
+        .map(|path| directory_usage(&Path::new(path).join(".git"), &[]).0)
+        .sum();
+
+    let mut usages: Vec<WorktreeUsage> = worktrees.into_iter().map(worktree_usage).collect();
+    usages.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    AllWorktreesUsage {
+        total_bytes: shared_git_bytes + usages.iter().map(|usage| usage.bytes).sum::<u64>(),
+        shared_git_bytes,
+        worktrees: usages,
+    }
+}
+
+/// Measure the attempt worktrees every few minutes for as long as the app
+/// runs and emit `worktree-size-warning` when one grows past the configured
+/// limit. A worktree is reported again only after it was below the limit.
+pub async fn run_worktree_size_checks(
+    app: AppHandle,
+    task_service: Arc<TaskService>,
+    config_service: Arc<Mutex<ConfigService>>,
+) {
+    let mut ticker = interval(WORKTREE_CHECK_INTERVAL);
+    let mut warned: HashSet<String> = HashSet::new();
+
+    loop {
+        ticker.tick().await;
+
+        let limit = config_service.lock().await.get_storage_config().worktree_warning_bytes;
+        if limit == 0 {
+            warned.clear();
+            continue;
+        }
+
+        let worktrees = match task_service.list_attempt_worktrees().await {
+            Ok(worktrees) => worktrees,
+            Err(e) => {
+                log::warn!("Failed to list attempt worktrees for the size check: {}", e);
+                continue;
+            }
+        };
+
+        // Walking big worktrees blocks, keep it off the async workers
+        let usages = match tokio::task::spawn_blocking(move || {
+            worktrees
+                .into_iter()
+                .filter(|worktree| Path::new(&worktree.worktree_path).is_dir())
+                .map(worktree_usage)
+                .collect::<Vec<_>>()
+        })
+        .await
+        {
+            Ok(usages) => usages,
+            Err(e) => {
+                log::warn!("Worktree size check failed: {}", e);
+                continue;
+            }
+        };
+
+        for usage in usages {
+            if usage.bytes <= limit {
+                warned.remove(&usage.attempt_id);
+                continue;
+            }
+            if !warned.insert(usage.attempt_id.clone()) {
+                continue;
+            }
+
+            log::warn!(
+                "Worktree {} of attempt {} uses {} bytes, more than the limit of {}",
+                usage.worktree_path, usage.attempt_id, usage.bytes, limit
+            );
+            let _ = app.emit("worktree-size-warning", serde_json::json!({
+                "attemptId": usage.attempt_id,
+                "taskId": usage.task_id,
+                "taskTitle": usage.task_title,
+                "projectId": usage.project_id,
+                "worktreePath": usage.worktree_path,
+                "bytes": usage.bytes,
+                "limitBytes": limit,
+                "largestPaths": usage.largest_paths,
+            }));
+        }
+    }
+}
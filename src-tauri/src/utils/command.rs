@@ -79,6 +79,54 @@ pub fn build_shell_command(program: &str, args: &[&str]) -> String {
     shell_command
 }
 
+/// Program and arguments that run a user-provided script through the platform shell
+pub fn shell_script_command(script: &str) -> (String, Vec<String>) {
+    #[cfg(target_os = "windows")]
+    {
+        ("cmd".to_string(), vec!["/C".to_string(), script.to_string()])
+    }
+    
+    #[cfg(not(target_os = "windows"))]
+    {
+        ("sh".to_string(), vec!["-c".to_string(), script.to_string()])
+    }
+}
+
+/// Program and arguments that run a user command in a worktree. On macOS this
+/// goes through the user's login shell so PATH matches their terminal. When
+/// `args` is empty, `command` is treated as a full shell command line.
+pub fn user_shell_command(command: &str, args: &[String]) -> (String, Vec<String>) {
+    let line = if args.is_empty() {
+        command.to_string()
+    } else {
+        #[cfg(target_os = "windows")]
+        {
+            format!("{} {}", command, args.join(" "))
+        }
+        
+        #[cfg(not(target_os = "windows"))]
+        {
+            let mut line = shell_escape::escape(command.into()).to_string();
+            for arg in args {
+                line.push(' ');
+                line.push_str(&shell_escape::escape(arg.as_str().into()));
+            }
+            line
+        }
+    };
+    
+    #[cfg(target_os = "macos")]
+    {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+        (shell, vec!["-l".to_string(), "-c".to_string(), line])
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        shell_script_command(&line)
+    }
+}
+
 /// Execute git command with proper environment
 pub fn execute_git(args: &[&str], current_dir: &Path) -> Result<Output, std::io::Error> {
     execute_command("git", args, Some(current_dir))
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Total size in bytes of the files below `dir`, and the size of each of its
+/// top-level entries, biggest first. Symlinks are not followed, so linked
+/// caches are not counted, and top-level entries named in `skip` are left out.
+/// Entries that can't be read are skipped.
+pub fn directory_usage(dir: &Path, skip: &[&str]) -> (u64, Vec<(String, u64)>) {
+    let mut total = 0;
+    let mut by_entry: HashMap<String, u64> = HashMap::new();
+
+    let walker = WalkDir::new(dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() != 1 || !skip.iter().any(|name| entry.file_name() == *name));
+
+    for entry in walker.filter_map(Result::ok) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let Some(top_level) = entry
+            .path()
+            .strip_prefix(dir)
+            .ok()
+            .and_then(|relative| relative.components().next())
+        else {
+            continue;
+        };
+
+        total += metadata.len();
+        *by_entry
+            .entry(top_level.as_os_str().to_string_lossy().to_string())
+            .or_default() += metadata.len();
+    }
+
+    let mut entries: Vec<(String, u64)> = by_entry.into_iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    (total, entries)
+}
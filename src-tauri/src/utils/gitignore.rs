@@ -0,0 +1,122 @@
+use glob::{MatchOptions, Pattern};
+use std::path::Path;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// One line of a `.gitignore`
+#[derive(Debug)]
+struct IgnoreRule {
+    pattern: Pattern,
+    /// `!pattern` re-includes what an earlier rule excluded
+    negated: bool,
+    /// `pattern/` only matches directories
+    dir_only: bool,
+    /// Patterns containing a slash match the whole path, others only the name
+    anchored: bool,
+}
+
+/// The rules of one `.gitignore` file (or an equivalent list of patterns).
+/// Supports comments, `!` negation, trailing `/` for directories, leading `/`
+/// anchoring and `*`, `?`, `[...]` and `**` wildcards.
+#[derive(Debug, Default)]
+pub struct GitIgnore {
+    rules: Vec<IgnoreRule>,
+}
+
+impl GitIgnore {
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let rules = lines
+            .into_iter()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                let line = line.strip_prefix('/').unwrap_or(line);
+                if line.is_empty() {
+                    return None;
+                }
+
+                let pattern = Pattern::new(line).ok()?;
+                Some(IgnoreRule { pattern, negated, dir_only, anchored })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Rules of the `.gitignore` in `dir`, if it has one
+    pub fn from_dir(dir: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(dir.join(".gitignore")).ok()?;
+        Some(Self::parse(contents.lines()))
+    }
+
+    /// Whether the rules decide on `relative_path` (relative to the directory
+    /// of the `.gitignore`, `/`-separated): `Some(true)` when it is ignored,
+    /// `Some(false)` when a negation includes it again, `None` when no rule
+    /// matches. The last matching rule wins, as in git.
+    pub fn matched(&self, relative_path: &str, is_dir: bool) -> Option<bool> {
+        let name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                if rule.dir_only && !is_dir {
+                    return false;
+                }
+                let candidate = if rule.anchored { relative_path } else { name };
+                rule.pattern.matches_with(candidate, MATCH_OPTIONS)
+            })
+            .map(|rule| !rule.negated)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unanchored_patterns_match_names_anywhere() {
+        let ignore = GitIgnore::parse(["*.log", "build/"]);
+        assert_eq!(ignore.matched("debug.log", false), Some(true));
+        assert_eq!(ignore.matched("logs/debug.log", false), Some(true));
+        assert_eq!(ignore.matched("packages/app/build", true), Some(true));
+        // build/ only matches directories
+        assert_eq!(ignore.matched("build", false), None);
+        assert_eq!(ignore.matched("src/main.rs", false), None);
+    }
+
+    #[test]
+    fn anchored_patterns_match_from_the_root() {
+        let ignore = GitIgnore::parse(["/dist", "docs/*.html", "**/generated"]);
+        assert_eq!(ignore.matched("dist", true), Some(true));
+        assert_eq!(ignore.matched("app/dist", true), None);
+        assert_eq!(ignore.matched("docs/index.html", false), Some(true));
+        assert_eq!(ignore.matched("docs/api/index.html", false), None);
+        assert_eq!(ignore.matched("src/generated", true), Some(true));
+    }
+
+    #[test]
+    fn later_negation_includes_again() {
+        let ignore = GitIgnore::parse(["# env files", "*.env", "!example.env"]);
+        assert_eq!(ignore.matched("prod.env", false), Some(true));
+        assert_eq!(ignore.matched("example.env", false), Some(false));
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::RwLock;
+use lazy_static::lazy_static;
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use crate::models::NetworkConfig;
+
+lazy_static! {
+    /// Proxy settings from ConfigService, mirrored here so every HTTP client can use them
+    static ref NETWORK_CONFIG: RwLock<NetworkConfig> = RwLock::new(NetworkConfig::default());
+}
+
+const PROXY_ENV_VARS: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"];
+
+pub fn set_network_config(config: NetworkConfig) {
+    if let Ok(mut current) = NETWORK_CONFIG.write() {
+        *current = config;
+    }
+}
+
+/// Whether a proxy is configured through the environment, which takes precedence
+fn env_proxy_configured() -> bool {
+    PROXY_ENV_VARS.iter().any(|var| std::env::var(var).map(|v| !v.trim().is_empty()).unwrap_or(false))
+}
+
+/// Builder for outgoing HTTP clients. reqwest honours `HTTPS_PROXY`/`NO_PROXY`
+/// on its own; the configured proxy is only used when the environment has none.
+pub fn client_builder() -> ClientBuilder {
+    let builder = reqwest::Client::builder();
+    if env_proxy_configured() {
+        return builder;
+    }
+
+    let config = match NETWORK_CONFIG.read() {
+        Ok(config) => config.clone(),
+        Err(_) => return builder,
+    };
+    let proxy_url = match config.proxy_url.as_deref() {
+        Some(url) => url,
+        None => return builder,
+    };
+
+    match Proxy::all(proxy_url) {
+        Ok(proxy) => builder.proxy(proxy.no_proxy(NoProxy::from_string(&config.proxy_no_proxy.join(",")))),
+        Err(e) => {
+            log::warn!("Ignoring invalid proxy URL {}: {}", proxy_url, e);
+            builder
+        }
+    }
+}
+
+/// Default client honouring the proxy settings
+pub fn client() -> reqwest::Client {
+    client_builder().build().unwrap_or_default()
+}
+
+/// Proxy variables for child processes (agent CLIs) when only the config sets a proxy
+pub fn proxy_env_vars() -> Vec<(String, String)> {
+    if env_proxy_configured() {
+        return Vec::new();
+    }
+
+    let config = match NETWORK_CONFIG.read() {
+        Ok(config) => config.clone(),
+        Err(_) => return Vec::new(),
+    };
+    let proxy_url = match config.proxy_url {
+        Some(url) => url,
+        None => return Vec::new(),
+    };
+
+    let mut vars = vec![
+        ("HTTPS_PROXY".to_string(), proxy_url.clone()),
+        ("HTTP_PROXY".to_string(), proxy_url),
+    ];
+    if !config.proxy_no_proxy.is_empty() {
+        vars.push(("NO_PROXY".to_string(), config.proxy_no_proxy.join(",")));
+    }
+    vars
+}
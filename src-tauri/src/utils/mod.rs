@@ -1 +1,5 @@
-pub mod command;
\ No newline at end of file
+pub mod command;
+pub mod http;
+pub mod project_type;
+pub mod gitignore;
+pub mod disk_usage;
@@ -0,0 +1,97 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+/// Build and dependency files found at the root of a project
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProjectMarkers {
+    pub package_json: bool,
+    pub composer_json: bool,
+    pub cargo_toml: bool,
+    pub pom_xml: bool,
+    pub build_gradle: bool,
+    pub requirements_txt: bool,
+    pub pipfile: bool,
+    pub gemfile: bool,
+    pub go_mod: bool,
+}
+
+impl ProjectMarkers {
+    pub fn detect(project_path: &Path) -> Self {
+        Self {
+            package_json: project_path.join("package.json").exists(),
+            composer_json: project_path.join("composer.json").exists(),
+            cargo_toml: project_path.join("Cargo.toml").exists(),
+            pom_xml: project_path.join("pom.xml").exists(),
+            build_gradle: project_path.join("build.gradle").exists(),
+            requirements_txt: project_path.join("requirements.txt").exists(),
+            pipfile: project_path.join("Pipfile").exists(),
+            gemfile: project_path.join("Gemfile").exists(),
+            go_mod: project_path.join("go.mod").exists(),
+        }
+    }
+
+    /// Template for the detected project type, checked in the same order as the setup scripts
+    pub fn gitignore_template(&self) -> GitignoreTemplate {
+        if self.package_json {
+            GitignoreTemplate::Node
+        } else if self.composer_json {
+            GitignoreTemplate::Php
+        } else if self.cargo_toml {
+            GitignoreTemplate::Rust
+        } else if self.pom_xml || self.build_gradle {
+            GitignoreTemplate::Java
+        } else if self.requirements_txt || self.pipfile {
+            GitignoreTemplate::Python
+        } else if self.gemfile {
+            GitignoreTemplate::Ruby
+        } else if self.go_mod {
+            GitignoreTemplate::Go
+        } else {
+            GitignoreTemplate::Generic
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitignoreTemplate {
+    Node,
+    Php,
+    Rust,
+    Java,
+    Python,
+    Ruby,
+    Go,
+    /// Editor and OS files only
+    Generic,
+    /// Don't write a .gitignore
+    None,
+}
+
+const COMMON_IGNORES: &str = "# OS and editor files\n.DS_Store\nThumbs.db\n.idea/\n.vscode/\n*.swp\n\n# Local environment\n.env\n.env.local\n";
+
+impl GitignoreTemplate {
+    pub fn parse(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.trim().to_lowercase())).ok()
+    }
+
+    pub fn contents(self) -> Option<String> {
+        let specific = match self {
+            GitignoreTemplate::None => return None,
+            GitignoreTemplate::Generic => "",
+            GitignoreTemplate::Node => "# Node\nnode_modules/\ndist/\nbuild/\ncoverage/\n*.log\n",
+            GitignoreTemplate::Php => "# PHP\nvendor/\n.phpunit.result.cache\n",
+            GitignoreTemplate::Rust => "# Rust\ntarget/\n",
+            GitignoreTemplate::Java => "# Java\ntarget/\nbuild/\n.gradle/\n*.class\n",
+            GitignoreTemplate::Python => "# Python\n__pycache__/\n*.py[cod]\n.venv/\nvenv/\n*.egg-info/\n.pytest_cache/\n",
+            GitignoreTemplate::Ruby => "# Ruby\n.bundle/\nvendor/bundle/\nlog/\ntmp/\n",
+            GitignoreTemplate::Go => "# Go\nbin/\n*.test\n*.out\n",
+        };
+
+        if specific.is_empty() {
+            Some(COMMON_IGNORES.to_string())
+        } else {
+            Some(format!("{}\n{}", specific, COMMON_IGNORES))
+        }
+    }
+}
@@ -1,67 +1,151 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use sqlx::FromRow;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
 use tokio::sync::Mutex;
+use uuid::Uuid;
+use crate::db::DbPool;
+use crate::services::ProjectService;
+
+/// Geometry of a project window that was open when the app quit (logical pixels)
+#[derive(Debug, FromRow)]
+struct SavedWindow {
+    project_id: String,
+    x: Option<i64>,
+    y: Option<i64>,
+    width: Option<i64>,
+    height: Option<i64>,
+    maximized: bool,
+}
 
 /// Manages project windows, ensuring each project has its own window
 pub struct ProjectWindowManager {
     /// Maps project IDs to window labels
     project_windows: Arc<Mutex<HashMap<String, String>>>,
     app_handle: AppHandle,
+    pool: DbPool,
+    /// Set once the app exits so windows closing with it stay saved
+    quitting: Arc<AtomicBool>,
 }
 
 impl ProjectWindowManager {
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(app_handle: AppHandle, pool: DbPool) -> Self {
         Self {
             project_windows: Arc::new(Mutex::new(HashMap::new())),
             app_handle,
+            pool,
+            quitting: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    /// Opens a window for a project, creating a new one if it doesn't exist
+    /// Label of the window of a project, whether it is open or not
+    pub fn window_label(project_id: &str) -> String {
+        format!("project-{}", project_id)
+    }
+
+    /// Opens a window for a project, focusing the existing one if there is one
     pub async fn open_project_window(&self, project_id: &str, project_name: &str) -> Result<String, String> {
+        self.open_window(project_id, project_name, None, None)
+            .await
+            .map(|(label, _)| label)
+    }
+
+    /// Opens (or focuses) the project window and selects `task_id` in it.
+    /// A new window reads the task from its URL, an existing one gets a
+    /// `navigate-to-task` event.
+    pub async fn open_project_window_at_task(&self, project_id: &str, project_name: &str, task_id: &str) -> Result<String, String> {
+        let (window_label, created) = self.open_window(project_id, project_name, Some(task_id), None).await?;
+
+        if !created {
+            self.app_handle
+                .emit_to(window_label.as_str(), "navigate-to-task", serde_json::json!({
+                    "projectId": project_id,
+                    "taskId": task_id,
+                }))
+                .map_err(|e| format!("Failed to navigate to task: {}", e))?;
+        }
+
+        Ok(window_label)
+    }
+
+    /// Returns the window label and whether the window was newly created
+    async fn open_window(
+        &self,
+        project_id: &str,
+        project_name: &str,
+        task_id: Option<&str>,
+        saved: Option<&SavedWindow>,
+    ) -> Result<(String, bool), String> {
         let mut windows = self.project_windows.lock().await;
-        
-        // Check if window already exists for this project
-        if let Some(window_label) = windows.get(project_id) {
-            // Window exists, bring it to front
-            if let Some(window) = self.app_handle.get_webview_window(window_label) {
-                window.show().map_err(|e| format!("Failed to show window: {}", e))?;
-                window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
-                return Ok(window_label.clone());
-            } else {
-                // Window was closed, remove from map
-                windows.remove(project_id);
+        let window_label = Self::window_label(project_id);
+
+        // One window per project: bring the existing one to front, even if it is no longer tracked
+        if let Some(window) = self.app_handle.get_webview_window(&window_label) {
+            if window.is_minimized().unwrap_or(false) {
+                let _ = window.unminimize();
             }
+            window.show().map_err(|e| format!("Failed to show window: {}", e))?;
+            window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
+            windows.insert(project_id.to_string(), window_label.clone());
+            return Ok((window_label, false));
         }
-        
+        windows.remove(project_id);
+
         // Create new window
-        let window_label = format!("project-{}", project_id);
         let window_title = format!("Pivo - {}", project_name);
-        
-        let window = WebviewWindowBuilder::new(
+        let mut url = format!("index.html?projectId={}", project_id);
+        if let Some(task_id) = task_id {
+            url.push_str(&format!("&taskId={}", urlencoding::encode(task_id)));
+        }
+
+        let mut builder = WebviewWindowBuilder::new(
             &self.app_handle,
             &window_label,
-            WebviewUrl::App(format!("index.html?projectId={}", project_id).into())
+            WebviewUrl::App(url.into())
         )
         .title(&window_title)
         .inner_size(1440.0, 900.0)
         .min_inner_size(1200.0, 700.0)
-        .resizable(true)
-        .build()
-        .map_err(|e| format!("Failed to create window: {}", e))?;
-        
+        .resizable(true);
+
+        if let Some(saved) = saved {
+            if let (Some(width), Some(height)) = (saved.width, saved.height) {
+                builder = builder.inner_size(width as f64, height as f64);
+            }
+            if let (Some(x), Some(y)) = (saved.x, saved.y) {
+                builder = builder.position(x as f64, y as f64);
+            }
+            builder = builder.maximized(saved.maximized);
+        }
+
+        let window = builder
+            .build()
+            .map_err(|e| format!("Failed to create window: {}", e))?;
+
         // Store project ID in window state for later retrieval
         window.eval(&format!(
-            "window.__TAURI_PROJECT_ID__ = '{}';", 
+            "window.__TAURI_PROJECT_ID__ = '{}';",
             project_id
         )).map_err(|e| format!("Failed to set project ID: {}", e))?;
-        
+
         // Listen for window close events to clean up tracking
         let windows_clone = self.project_windows.clone();
         let project_id_clone = project_id.to_string();
-        window.on_window_event(move |event| {
-            if let tauri::WindowEvent::CloseRequested { .. } | tauri::WindowEvent::Destroyed = event {
+        let pool = self.pool.clone();
+        let quitting = self.quitting.clone();
+        window.on_window_event(move |event| match event {
+            tauri::WindowEvent::CloseRequested { .. } if !quitting.load(Ordering::SeqCst) => {
+                // Closed by the user, so it is not reopened on the next start
+                let pool = pool.clone();
+                let project_id = project_id_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = forget_window(&pool, &project_id).await {
+                        log::warn!("Failed to forget window of project {}: {}", project_id, e);
+                    }
+                });
+            }
+            tauri::WindowEvent::Destroyed => {
                 let windows = windows_clone.clone();
                 let project_id_to_remove = project_id_clone.clone();
                 tauri::async_runtime::spawn(async move {
@@ -69,52 +153,155 @@ impl ProjectWindowManager {
                     windows.remove(&project_id_to_remove);
                 });
             }
+            _ => {}
         });
-        
+
         // Add to tracking map
         windows.insert(project_id.to_string(), window_label.clone());
-        
-        Ok(window_label)
+        drop(windows);
+
+        // Record the window right away so it is restored even after a crash
+        if let Err(e) = self.save_window_state(project_id, &window).await {
+            log::warn!("Failed to save window of project {}: {}", project_id, e);
+        }
+
+        Ok((window_label, true))
     }
-    
+
     /// Closes a project window
     pub async fn close_project_window(&self, project_id: &str) -> Result<(), String> {
         let mut windows = self.project_windows.lock().await;
-        
+
         if let Some(window_label) = windows.remove(project_id) {
             if let Some(window) = self.app_handle.get_webview_window(&window_label) {
                 window.close().map_err(|e| format!("Failed to close window: {}", e))?;
             }
         }
-        
+
+        if let Err(e) = forget_window(&self.pool, project_id).await {
+            log::warn!("Failed to forget window of project {}: {}", project_id, e);
+        }
+
         Ok(())
     }
-    
+
     /// Gets the window label for a project
     pub async fn get_project_window(&self, project_id: &str) -> Option<String> {
         let windows = self.project_windows.lock().await;
         windows.get(project_id).cloned()
     }
-    
+
     /// Lists all open project windows
     pub async fn list_open_projects(&self) -> Vec<(String, String)> {
         let windows = self.project_windows.lock().await;
         windows.iter().map(|(id, label)| (id.clone(), label.clone())).collect()
     }
-    
+
     /// Cleanup closed windows from tracking
     pub async fn cleanup_closed_windows(&self) {
         let mut windows = self.project_windows.lock().await;
         let mut to_remove = Vec::new();
-        
+
         for (project_id, window_label) in windows.iter() {
             if self.app_handle.get_webview_window(window_label).is_none() {
                 to_remove.push(project_id.clone());
             }
         }
-        
+
         for project_id in to_remove {
             windows.remove(&project_id);
         }
     }
-}
\ No newline at end of file
+
+    /// Persist the geometry of every open project window. Called when the app exits.
+    pub async fn save_open_windows(&self) {
+        self.quitting.store(true, Ordering::SeqCst);
+
+        let open: Vec<(String, WebviewWindow)> = {
+            let windows = self.project_windows.lock().await;
+            windows
+                .iter()
+                .filter_map(|(project_id, label)| {
+                    self.app_handle
+                        .get_webview_window(label)
+                        .map(|window| (project_id.clone(), window))
+                })
+                .collect()
+        };
+
+        for (project_id, window) in open {
+            if let Err(e) = self.save_window_state(&project_id, &window).await {
+                log::warn!("Failed to save window of project {}: {}", project_id, e);
+            }
+        }
+    }
+
+    /// Reopen the windows saved by `save_open_windows`. Windows of projects
+    /// deleted since the last session are dropped.
+    pub async fn restore_windows(&self, project_service: &ProjectService) {
+        let saved = match sqlx::query_as::<_, SavedWindow>(
+            "SELECT project_id, x, y, width, height, maximized FROM project_windows ORDER BY updated_at ASC"
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(saved) => saved,
+            Err(e) => {
+                log::warn!("Failed to load saved project windows: {}", e);
+                return;
+            }
+        };
+
+        for window in saved {
+            let project = match Uuid::parse_str(&window.project_id) {
+                Ok(uuid) => project_service.get_project(uuid).await.ok().flatten(),
+                Err(_) => None,
+            };
+
+            match project {
+                Some(project) => {
+                    if let Err(e) = self.open_window(&project.id, &project.name, None, Some(&window)).await {
+                        log::warn!("Failed to restore window of project {}: {}", project.id, e);
+                    }
+                }
+                None => {
+                    log::info!("Project {} no longer exists, not restoring its window", window.project_id);
+                    let _ = forget_window(&self.pool, &window.project_id).await;
+                }
+            }
+        }
+    }
+
+    async fn save_window_state(&self, project_id: &str, window: &WebviewWindow) -> Result<(), sqlx::Error> {
+        let scale_factor = window.scale_factor().unwrap_or(1.0);
+        let position = window.outer_position().ok().map(|p| p.to_logical::<f64>(scale_factor));
+        let size = window.inner_size().ok().map(|s| s.to_logical::<f64>(scale_factor));
+        let maximized = window.is_maximized().unwrap_or(false);
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO project_windows (project_id, x, y, width, height, maximized, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+            "#,
+        )
+        .bind(project_id)
+        .bind(position.map(|p| p.x as i64))
+        .bind(position.map(|p| p.y as i64))
+        .bind(size.map(|s| s.width as i64))
+        .bind(size.map(|s| s.height as i64))
+        .bind(maximized)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+async fn forget_window(pool: &DbPool, project_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM project_windows WHERE project_id = ?")
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
-import { useState, useRef, useCallback } from 'react';
+import { useState, useRef, useCallback, useEffect } from 'react';
 import { Button } from '@/components/ui/button';
+import { Switch } from '@/components/ui/switch';
 import { ScrollArea } from '@/components/ui/scroll-area';
 import { useToast } from '@/hooks/use-toast';
-import { Play, Square, Loader2, Trash2 } from 'lucide-react';
-import { Project } from '@/types';
+import { Play, Square, Loader2, Trash2, ExternalLink } from 'lucide-react';
+import { DevServerConfig, DevServerInfo, Project } from '@/types';
+import { configApi } from '@/lib/api';
 import { invoke } from '@tauri-apps/api/core';
 import { useEvent } from '@/lib/events';
+import { errorMessage } from '@/lib/errors';
 
 interface DevPanelProps {
   project: Project;
@@ -15,6 +18,8 @@ interface DevServerState {
   processId: string | null;
   status: 'idle' | 'starting' | 'running' | 'stopping';
   output: string[];
+  /** Address detected in the server output */
+  url: string | null;
 }
 
 export function DevPanel({ project }: DevPanelProps) {
@@ -22,8 +27,10 @@ export function DevPanel({ project }: DevPanelProps) {
   const [devServer, setDevServer] = useState<DevServerState>({
     processId: null,
     status: 'idle',
-    output: []
+    output: [],
+    url: null
   });
+  const [serverConfig, setServerConfig] = useState<DevServerConfig | null>(null);
   
   const scrollAreaRef = useRef<HTMLDivElement>(null);
   const outputEndRef = useRef<HTMLDivElement>(null);
@@ -31,8 +38,44 @@ export function DevPanel({ project }: DevPanelProps) {
   // Maximum number of output lines to keep in memory
   const MAX_OUTPUT_LINES = 1000;
   
+  // Pick up a dev server that was started from another window
+  useEffect(() => {
+    invoke<DevServerInfo[]>('list_dev_servers')
+      .then(servers => {
+        const running = servers.find(server =>
+          server.project_id === project.id && !server.task_attempt_id && server.name === 'dev'
+        );
+        if (!running) return;
+        setDevServer(prev => prev.processId ? prev : {
+          processId: running.process_id,
+          status: 'running',
+          output: running.output,
+          url: running.url ?? null
+        });
+      })
+      .catch(error => console.error('Failed to list dev servers:', error));
+  }, [project.id]);
+
+  useEffect(() => {
+    configApi.getDevServerConfig()
+      .then(setServerConfig)
+      .catch(error => console.error('Failed to load dev server config:', error));
+  }, []);
+
+  const toggleAutoRestart = useCallback(async (autoRestart: boolean) => {
+    if (!serverConfig) return;
+    const config = { ...serverConfig, autoRestart };
+    setServerConfig(config);
+    try {
+      await configApi.updateDevServerConfig(config);
+    } catch (error) {
+      console.error('Failed to save dev server config:', error);
+      setServerConfig(serverConfig);
+    }
+  }, [serverConfig]);
+
   // Handle process output events using useEvent hook
-  useEvent('dev-server-output', useCallback((payload: { process_id: string; type: string; data: string }) => {
+  useEvent('dev-server-output', useCallback((payload: { project_id: string; process_id: string; type: string; data: string }) => {
     console.log('[DEV_PANEL] Received output event:', payload);
     
     // Use functional state update to access current processId
@@ -61,20 +104,49 @@ export function DevPanel({ project }: DevPanelProps) {
     });
   }, []));
 
+  // Show the open in browser button once the server announces its address
+  useEvent('dev-server-ready', useCallback((payload: { project_id: string; process_id: string; url: string; port?: number | null }) => {
+    setDevServer(prev => payload.process_id === prev.processId ? { ...prev, url: payload.url } : prev);
+  }, []));
+
+  // The server crashed and was started again under the same process ID
+  useEvent('dev-server-restarted', useCallback((payload: { project_id: string; process_id: string; exit_code: number | null; restarts: number }) => {
+    setDevServer(prev => payload.process_id === prev.processId
+      ? {
+          ...prev,
+          url: null,
+          output: [...prev.output, `\n🔁 Dev server crashed${payload.exit_code !== null ? ` (exit code: ${payload.exit_code})` : ''}, restarting (${payload.restarts})...`]
+        }
+      : prev);
+  }, []));
+
+  useEvent('dev-server-crash-loop', useCallback((payload: { project_id: string; process_id: string; restarts: number }) => {
+    if (payload.project_id !== project.id) return;
+    setDevServer(prev => payload.process_id === prev.processId
+      ? { ...prev, output: [...prev.output, '\n❌ Dev server keeps crashing on start, not restarting it again'] }
+      : prev);
+    toast({
+      title: 'Dev server keeps crashing',
+      description: `Stopped restarting after ${payload.restarts} restarts. Check the output for errors.`,
+      variant: 'destructive'
+    });
+  }, [project.id, toast]));
+
   // Handle process completion events
-  useEvent('dev-server-stopped', useCallback((payload: { process_id: string; exit_code?: number }) => {
+  useEvent('dev-server-stopped', useCallback((payload: { project_id: string; process_id?: string; exit_code?: number | null }) => {
     setDevServer(prev => {
-      if (payload.process_id === prev.processId) {
+      if (payload.project_id === project.id && (!payload.process_id || payload.process_id === prev.processId)) {
         return {
           ...prev,
           status: 'idle',
           processId: null,
-          output: [...prev.output, `\n✅ Dev server stopped${payload.exit_code !== undefined ? ` (exit code: ${payload.exit_code})` : ''}`]
+          url: null,
+          output: [...prev.output, `\n✅ Dev server stopped${payload.exit_code != null ? ` (exit code: ${payload.exit_code})` : ''}`]
         };
       }
       return prev; // No state change
     });
-  }, []));
+  }, [project.id]));
 
   const startDevServer = useCallback(async () => {
     if (!project.dev_script) {
@@ -91,14 +163,15 @@ export function DevPanel({ project }: DevPanelProps) {
     setDevServer(prev => ({
       ...prev,
       status: 'starting',
+      url: null,
       output: [...prev.output, '\n🚀 Starting development server...', `📁 Working directory: ${project.path}`, `🖥️ Command: ${project.dev_script}`]
     }));
 
     try {
       // Start dev server using Tauri command
       console.log('[DEV_PANEL] Calling start_dev_server');
-      const result = await invoke<{ process_id: string }>('start_dev_server', {
-        projectPath: project.path,
+      const result = await invoke<{ process_id: string; already_running: boolean }>('start_dev_server', {
+        projectId: project.id,
         command: project.dev_script
       });
 
@@ -108,28 +181,25 @@ export function DevPanel({ project }: DevPanelProps) {
         ...prev,
         status: 'running',
         processId: result.process_id,
-        output: [...prev.output, `✅ Dev server started (Process ID: ${result.process_id})\n`]
+        output: [...prev.output, result.already_running
+          ? `✅ Dev server already running (Process ID: ${result.process_id})\n`
+          : `✅ Dev server started (Process ID: ${result.process_id})\n`]
       }));
 
     } catch (error) {
       console.error('Failed to start dev server:', error);
       
-      // Extract meaningful error message
-      const errorMessage = error instanceof Error 
-        ? error.message 
-        : typeof error === 'string' 
-          ? error 
-          : 'Unknown error occurred';
+      const message = errorMessage(error);
       
       setDevServer(prev => ({
         ...prev,
         status: 'idle',
-        output: [...prev.output, `\n❌ Error: ${errorMessage}`]
+        output: [...prev.output, `\n❌ Error: ${message}`]
       }));
       
       toast({
         title: 'Failed to start dev server',
-        description: errorMessage,
+        description: message,
         variant: 'destructive'
       });
     }
@@ -146,7 +216,7 @@ export function DevPanel({ project }: DevPanelProps) {
 
     try {
       await invoke('stop_dev_server', {
-        processId: devServer.processId
+        projectId: project.id
       });
       
       // The actual state update will be handled by the dev-server-stopped event
@@ -154,27 +224,35 @@ export function DevPanel({ project }: DevPanelProps) {
     } catch (error) {
       console.error('Failed to stop dev server:', error);
       
-      // Extract meaningful error message
-      const errorMessage = error instanceof Error 
-        ? error.message 
-        : typeof error === 'string' 
-          ? error 
-          : 'Unknown error occurred';
+      const message = errorMessage(error);
       
       setDevServer(prev => ({
         ...prev,
         status: 'idle',
         processId: null,
-        output: [...prev.output, `\n❌ Error stopping server: ${errorMessage}`]
+        output: [...prev.output, `\n❌ Error stopping server: ${message}`]
       }));
       
       toast({
         title: 'Failed to stop dev server',
-        description: errorMessage,
+        description: message,
         variant: 'destructive'
       });
     }
-  }, [devServer.processId, toast]);
+  }, [devServer.processId, project.id, toast]);
+
+  const openInBrowser = useCallback(async () => {
+    try {
+      await invoke('open_dev_server_in_browser', { projectId: project.id });
+    } catch (error) {
+      console.error('Failed to open dev server in browser:', error);
+      toast({
+        title: 'Failed to open browser',
+        description: errorMessage(error),
+        variant: 'destructive'
+      });
+    }
+  }, [project.id, toast]);
 
   const clearOutput = useCallback(() => {
     setDevServer(prev => ({
@@ -213,6 +291,29 @@ export function DevPanel({ project }: DevPanelProps) {
         </div>
         
         <div className="flex items-center gap-2">
+          {serverConfig && (
+            <label className="flex items-center gap-2 text-sm text-muted-foreground mr-2">
+              <Switch
+                checked={serverConfig.autoRestart}
+                onCheckedChange={toggleAutoRestart}
+              />
+              Auto-restart
+            </label>
+          )}
+          
+          {isRunning && devServer.url && (
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={openInBrowser}
+              className="h-8"
+              title={devServer.url}
+            >
+              <ExternalLink className="h-4 w-4 mr-1" />
+              Open in browser
+            </Button>
+          )}
+          
           {devServer.output.length > 0 && (
             <Button
               variant="ghost"
@@ -10,6 +10,7 @@ import { writeTextFile, readTextFile, BaseDirectory, exists, mkdir } from "@taur
 import { useTranslation } from "react-i18next";
 import { Toaster } from "@/components/ui/toaster";
 import { toast } from "@/hooks/use-toast";
+import { errorMessage } from "@/lib/errors";
 
 export function LogViewerPage() {
   const { t } = useTranslation();
@@ -32,7 +33,7 @@ export function LogViewerPage() {
       setBackendLogs(logs);
     } catch (error) {
       console.error("Failed to load backend logs:", error);
-      setBackendLogs(`${t('logs.failedToLoad')}\n\nError: ${error}`);
+      setBackendLogs(`${t('logs.failedToLoad')}\n\nError: ${errorMessage(error)}`);
     }
   };
 
@@ -64,7 +65,7 @@ export function LogViewerPage() {
       setFrontendLogs(logs);
     } catch (error) {
       console.error("Failed to load frontend logs:", error);
-      setFrontendLogs(`${t('logs.failedToLoad')}\n\nError: ${error}`);
+      setFrontendLogs(`${t('logs.failedToLoad')}\n\nError: ${errorMessage(error)}`);
     }
   };
 
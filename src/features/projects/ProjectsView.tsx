@@ -5,7 +5,7 @@
 import { useState, useEffect } from 'react';
 import { ProjectList } from '@/features/projects/components/ProjectList';
 import { ProjectSettingsDialog } from '@/features/projects/components/ProjectSettingsDialog';
-import { projectApi, ProjectInfo, windowApi } from '@/services/api';
+import { gitApi, gitHubApi, projectApi, ProjectInfo, windowApi } from '@/services/api';
 import { Project } from '@/types';
 import { transformGitUrl } from '@/lib/gitUrlUtils';
 
@@ -62,6 +62,16 @@ export function ProjectsView() {
           const transformedUrl = transformGitUrl(projectInfo.git_repo);
           console.log('Transforming backend Git URL:', projectInfo.git_repo, '->', transformedUrl);
           projectInfo.git_repo = transformedUrl;
+          
+          // Pre-fill from GitHub when the remote is a GitHub repository
+          try {
+            const repository = await gitHubApi.getRepositoryInfo(projectInfo.git_repo);
+            projectInfo.name = repository.name;
+            projectInfo.description = projectInfo.description || repository.description || undefined;
+            projectInfo.main_branch = repository.defaultBranch;
+          } catch (error) {
+            console.debug('No GitHub repository info for remote:', error);
+          }
         }
         
         setSelectedProjectInfo(projectInfo);
@@ -104,6 +114,11 @@ export function ProjectsView() {
             }
           }}
           onSubmit={async (values) => {
+            // Directories without git get a repository before the project is created, unless opted out
+            const withoutGit = selectedProjectInfo ? !selectedProjectInfo.has_git : false;
+            if (withoutGit && values.init_git) {
+              await gitApi.initRepository(values.path, values.main_branch || 'main');
+            }
             const project = await projectApi.create({
               name: values.name,
               path: values.path,
@@ -111,7 +126,9 @@ export function ProjectsView() {
               git_repo: values.git_repo,
               main_branch: values.main_branch,
               setup_script: values.setup_script,
-              dev_script: values.dev_script
+              dev_script: values.dev_script,
+              git_enabled: !withoutGit || values.init_git,
+              subdirectory: values.subdirectory || undefined
             });
             await loadProjects();
             setSelectedProjectInfo(null);
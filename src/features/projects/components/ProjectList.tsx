@@ -118,6 +118,7 @@ export function ProjectList({
             git_repo: selectedProject.git_repo || undefined,
             setup_script: selectedProject.setup_script || undefined,
             dev_script: selectedProject.dev_script || undefined,
+            subdirectory: selectedProject.subdirectory || undefined,
             has_git: !!selectedProject.git_repo,
             has_package_json: false,
           }}
@@ -131,6 +132,7 @@ export function ProjectList({
               git_repo: values.git_repo,
               setup_script: values.setup_script,
               dev_script: values.dev_script,
+              subdirectory: values.subdirectory,
             });
             onProjectsChange?.();
           }}
@@ -10,6 +10,7 @@ import {
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
+import { Checkbox } from "@/components/ui/checkbox";
 import { Textarea } from "@/components/ui/textarea";
 import { FolderOpen } from "lucide-react";
 import { open as openDialog } from "@tauri-apps/plugin-dialog";
@@ -47,7 +48,10 @@ export function ProjectSettingsDialog({
     main_branch: initialValues?.main_branch || "main",
     setup_script: initialValues?.setup_script || "",
     dev_script: initialValues?.dev_script || "",
+    subdirectory: initialValues?.subdirectory || "",
+    init_git: true,
   });
+  const needsGit = isCreating && initialValues?.has_git === false;
 
   const handleSelectPath = async () => {
     const selected = await openDialog({
@@ -139,6 +143,22 @@ export function ProjectSettingsDialog({
               </div>
             </div>
             
+            {needsGit && (
+              <div className="grid gap-2">
+                <div className="flex items-center gap-2">
+                  <Checkbox
+                    id="init_git"
+                    checked={formData.init_git}
+                    onCheckedChange={(checked) => setFormData({ ...formData, init_git: checked === true })}
+                  />
+                  <Label htmlFor="init_git">{t('project.initGitRepository')}</Label>
+                </div>
+                {!formData.init_git && (
+                  <p className="text-xs text-muted-foreground">{t('project.withoutGitHint')}</p>
+                )}
+              </div>
+            )}
+            
             <div className="grid gap-2">
               <Label htmlFor="git_repo">{t('project.gitRepositoryUrl')}</Label>
               <Input
@@ -155,6 +175,17 @@ export function ProjectSettingsDialog({
               />
             </div>
             
+            <div className="grid gap-2">
+              <Label htmlFor="subdirectory">{t('project.subdirectory')}</Label>
+              <Input
+                id="subdirectory"
+                value={formData.subdirectory}
+                onChange={(e) => setFormData({ ...formData, subdirectory: e.target.value })}
+                placeholder="packages/api"
+              />
+              <p className="text-xs text-muted-foreground">{t('project.subdirectoryHint')}</p>
+            </div>
+            
             <div className="grid gap-2">
               <Label htmlFor="main_branch">{t('project.mainBranch')}</Label>
               <Input
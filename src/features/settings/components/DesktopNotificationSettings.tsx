@@ -0,0 +1,61 @@
+import { useEffect, useState } from "react";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import { useTranslation } from "react-i18next";
+import { toast } from "@/hooks/use-toast";
+import { configApi } from "@/lib/api";
+import { errorMessage } from "@/lib/errors";
+import type { NotificationConfig } from "@/types";
+
+const CATEGORIES = ["executionCompleted", "taskDone", "mergeRequestUpdated"] as const;
+
+/** Which events raise a desktop notification; each switch is saved right away */
+export function DesktopNotificationSettings() {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState<NotificationConfig | null>(null);
+
+  useEffect(() => {
+    configApi.getNotificationConfig()
+      .then(setConfig)
+      .catch((error) => console.error("Failed to load notification settings:", error));
+  }, []);
+
+  const handleToggle = async (category: keyof NotificationConfig, enabled: boolean) => {
+    if (!config) return;
+
+    const previous = config;
+    const updated = { ...config, [category]: enabled };
+    setConfig(updated);
+    try {
+      await configApi.updateNotificationConfig(updated);
+    } catch (error) {
+      setConfig(previous);
+      toast({
+        title: t("toast.error"),
+        description: errorMessage(error),
+        variant: "destructive",
+      });
+    }
+  };
+
+  if (!config) return null;
+
+  return (
+    <>
+      {CATEGORIES.map((category) => (
+        <div key={category} className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor={`notify-${category}`}>{t(`settings.desktopNotifications.${category}`)}</Label>
+            <p className="text-sm text-muted-foreground">{t(`settings.desktopNotifications.${category}Help`)}</p>
+          </div>
+          <Switch
+            id={`notify-${category}`}
+            checked={config[category]}
+            onCheckedChange={(checked) => handleToggle(category, checked)}
+          />
+        </div>
+      ))}
+      <p className="text-sm text-muted-foreground">{t("settings.desktopNotifications.focusHint")}</p>
+    </>
+  );
+}
@@ -0,0 +1,122 @@
+import { useEffect, useState } from "react";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import { Textarea } from "@/components/ui/textarea";
+import { Loader2 } from "lucide-react";
+import { useTranslation } from "react-i18next";
+import { toast } from "@/hooks/use-toast";
+import { configApi } from "@/lib/api";
+import { errorMessage } from "@/lib/errors";
+import type { FileWatcherConfig } from "@/types";
+
+/** Which file changes in worktrees refresh the diff view, and how often */
+export function FileWatcherSettings() {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState<FileWatcherConfig | null>(null);
+  const [patterns, setPatterns] = useState("");
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    configApi.getFileWatcherConfig()
+      .then((loaded) => {
+        setConfig(loaded);
+        setPatterns(loaded.ignorePatterns.join("\n"));
+      })
+      .catch((error) => console.error("Failed to load file watcher settings:", error));
+  }, []);
+
+  const handleSave = async () => {
+    if (!config || !Number.isFinite(config.debounceMs) || config.debounceMs < 0) return;
+    if (!Number.isInteger(config.maxWatchedDirectories) || config.maxWatchedDirectories < 1) return;
+
+    try {
+      setSaving(true);
+      const updated = {
+        ...config,
+        ignorePatterns: patterns.split("\n").map((p) => p.trim()).filter(Boolean),
+      };
+      await configApi.updateFileWatcherConfig(updated);
+      setConfig(updated);
+      toast({ title: t("settings.fileWatcher.saved") });
+    } catch (error) {
+      toast({
+        title: t("toast.error"),
+        description: errorMessage(error),
+        variant: "destructive",
+      });
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (!config) return null;
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{t("settings.fileWatcher.title")}</CardTitle>
+        <CardDescription>{t("settings.fileWatcher.description")}</CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="space-y-2">
+          <Label htmlFor="fileWatcherDebounce">{t("settings.fileWatcher.debounce")}</Label>
+          <Input
+            id="fileWatcherDebounce"
+            type="number"
+            min={0}
+            step={50}
+            className="w-32"
+            value={config.debounceMs}
+            onChange={(e) => setConfig({ ...config, debounceMs: Number(e.target.value) })}
+          />
+          <p className="text-sm text-muted-foreground">{t("settings.fileWatcher.debounceHelp")}</p>
+        </div>
+
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor="fileWatcherGitignore">{t("settings.fileWatcher.respectGitignore")}</Label>
+            <p className="text-sm text-muted-foreground">{t("settings.fileWatcher.respectGitignoreHelp")}</p>
+          </div>
+          <Switch
+            id="fileWatcherGitignore"
+            checked={config.respectGitignore}
+            onCheckedChange={(checked) => setConfig({ ...config, respectGitignore: checked })}
+          />
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="fileWatcherPatterns">{t("settings.fileWatcher.ignorePatterns")}</Label>
+          <Textarea
+            id="fileWatcherPatterns"
+            rows={5}
+            className="font-mono text-sm"
+            value={patterns}
+            onChange={(e) => setPatterns(e.target.value)}
+          />
+          <p className="text-sm text-muted-foreground">{t("settings.fileWatcher.ignorePatternsHelp")}</p>
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="fileWatcherMaxDirectories">{t("settings.fileWatcher.maxWatchedDirectories")}</Label>
+          <Input
+            id="fileWatcherMaxDirectories"
+            type="number"
+            min={1}
+            className="w-32"
+            value={config.maxWatchedDirectories}
+            onChange={(e) => setConfig({ ...config, maxWatchedDirectories: Number(e.target.value) })}
+          />
+          <p className="text-sm text-muted-foreground">{t("settings.fileWatcher.maxWatchedDirectoriesHelp")}</p>
+        </div>
+
+        <Button type="button" onClick={handleSave} disabled={saving}>
+          {saving && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+          {t("common.save")}
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
@@ -5,6 +5,7 @@ import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@
 import { Switch } from "@/components/ui/switch";
 import { useTranslation } from "react-i18next";
 import { useSettings } from "@/contexts/SettingsContext";
+import { DesktopNotificationSettings } from "./DesktopNotificationSettings";
 
 export function GeneralSettings() {
   const { t, i18n } = useTranslation();
@@ -92,6 +93,7 @@ export function GeneralSettings() {
               onCheckedChange={setNotifications}
             />
           </div>
+          <DesktopNotificationSettings />
         </CardContent>
       </Card>
     </div>
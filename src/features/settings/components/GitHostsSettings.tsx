@@ -0,0 +1,129 @@
+import { useEffect, useState } from "react";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
+import { Loader2, Plus, Trash2 } from "lucide-react";
+import { useTranslation } from "react-i18next";
+import { toast } from "@/hooks/use-toast";
+import { configApi, projectApi } from "@/lib/api";
+import type { GitHostMapping, GitHostsConfig } from "@/types";
+import { errorMessage } from "@/lib/errors";
+
+/** Self-hosted servers whose provider can't be told from the host name */
+export function GitHostsSettings() {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState<GitHostsConfig>({ hosts: [], probeUnknownHosts: false });
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    configApi.getGitHostsConfig()
+      .then(setConfig)
+      .catch((error) => console.error("Failed to load git hosts:", error))
+      .finally(() => setLoading(false));
+  }, []);
+
+  const updateHost = (index: number, changes: Partial<GitHostMapping>) => {
+    setConfig({
+      ...config,
+      hosts: config.hosts.map((mapping, i) => (i === index ? { ...mapping, ...changes } : mapping)),
+    });
+  };
+
+  const handleSave = async () => {
+    try {
+      setSaving(true);
+      await configApi.updateGitHostsConfig(config);
+      // Existing projects pick up the new mapping
+      await projectApi.refreshAllGitProviders();
+      toast({ title: t("settings.gitHosts.saved") });
+    } catch (error) {
+      console.error("Failed to save git hosts:", error);
+      toast({
+        title: t("toast.error"),
+        description: errorMessage(error),
+        variant: "destructive",
+      });
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center p-8">
+        <Loader2 className="h-8 w-8 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{t("settings.gitHosts.title")}</CardTitle>
+        <CardDescription>{t("settings.gitHosts.description")}</CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {config.hosts.map((mapping, index) => (
+          <div key={index} className="flex items-center gap-2">
+            <Input
+              value={mapping.host}
+              onChange={(e) => updateHost(index, { host: e.target.value })}
+              placeholder="git.internal.example.com"
+            />
+            <Select
+              value={mapping.provider}
+              onValueChange={(provider) => updateHost(index, { provider: provider as GitHostMapping["provider"] })}
+            >
+              <SelectTrigger className="w-36">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="gitlab">GitLab</SelectItem>
+                <SelectItem value="github">GitHub</SelectItem>
+              </SelectContent>
+            </Select>
+            <Button
+              type="button"
+              variant="ghost"
+              size="icon"
+              onClick={() => setConfig({ ...config, hosts: config.hosts.filter((_, i) => i !== index) })}
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+        ))}
+
+        <Button
+          type="button"
+          variant="outline"
+          size="sm"
+          onClick={() => setConfig({ ...config, hosts: [...config.hosts, { host: "", provider: "gitlab" }] })}
+        >
+          <Plus className="h-4 w-4 mr-1" />
+          {t("settings.gitHosts.addHost")}
+        </Button>
+
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label htmlFor="probeUnknownHosts">{t("settings.gitHosts.probe")}</Label>
+            <p className="text-sm text-muted-foreground">{t("settings.gitHosts.probeHelp")}</p>
+          </div>
+          <Switch
+            id="probeUnknownHosts"
+            checked={config.probeUnknownHosts}
+            onCheckedChange={(checked) => setConfig({ ...config, probeUnknownHosts: checked })}
+          />
+        </div>
+
+        <Button type="button" onClick={handleSave} disabled={saving}>
+          {saving && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+          {t("common.save")}
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
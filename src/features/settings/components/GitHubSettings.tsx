@@ -2,6 +2,7 @@ import { useEffect, useState } from 'react';
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
 import { Input } from '@/components/ui/input';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select';
 import { Button } from '@/components/ui/button';
 import { Badge } from '@/components/ui/badge';
 import { Alert, AlertDescription } from '@/components/ui/alert';
@@ -10,6 +11,7 @@ import { useTranslation } from 'react-i18next';
 import { toast } from '@/hooks/use-toast';
 import { GitHubAuthDialog } from '@/features/vcs/components/github/GitHubAuthDialog';
 import { useGitHubAuth } from '@/hooks/domain/useVcs';
+import type { PushMode } from '@/types';
 
 export function GitHubSettings() {
   const { t } = useTranslation();
@@ -18,6 +20,7 @@ export function GitHubSettings() {
     username: '',
     defaultBranch: 'main',
     accessToken: undefined as string | undefined,
+    pushMode: 'token' as PushMode,
   });
   const [githubUser, setGithubUser] = useState<{ login: string; name?: string } | null>(null);
   const [saving, setSaving] = useState(false);
@@ -31,6 +34,7 @@ export function GitHubSettings() {
         username: (savedConfig as any)?.username || '',
         defaultBranch: (savedConfig as any)?.defaultBranch || 'main',
         accessToken: (savedConfig as any)?.accessToken,
+        pushMode: (savedConfig as any)?.pushMode || 'token',
       });
     }
   }, [savedConfig]);
@@ -68,6 +72,12 @@ export function GitHubSettings() {
     setHasChanges(true);
   };
 
+  const handlePushModeChange = (pushMode: PushMode) => {
+    const newConfig = { ...config, pushMode };
+    setConfig(newConfig);
+    handleAutoSave(newConfig);
+  };
+
   const handleFieldBlur = () => {
     if (hasChanges) {
       handleAutoSave(config);
@@ -190,6 +200,24 @@ export function GitHubSettings() {
                 {t('settings.github.defaultBranchHelp')}
               </p>
             </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="pushMode">
+                {t('settings.github.pushMode')}
+              </Label>
+              <Select value={config.pushMode} onValueChange={(value) => handlePushModeChange(value as PushMode)}>
+                <SelectTrigger id="pushMode">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="token">{t('settings.github.pushModeToken')}</SelectItem>
+                  <SelectItem value="ssh">{t('settings.github.pushModeSsh')}</SelectItem>
+                </SelectContent>
+              </Select>
+              <p className="text-sm text-muted-foreground">
+                {t('settings.github.pushModeHelp')}
+              </p>
+            </div>
           </div>
 
           {/* Status Messages */}
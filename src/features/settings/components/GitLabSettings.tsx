@@ -2,24 +2,31 @@ import { useEffect, useState } from 'react';
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
 import { Label } from '@/components/ui/label';
 import { Input } from '@/components/ui/input';
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
-import { Loader2, CheckCircle } from 'lucide-react';
+import { Loader2, CheckCircle, XCircle } from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 import { toast } from '@/hooks/use-toast';
 import { useGitLabAuth } from '@/hooks/domain/useVcs';
+import type { GitLabConnectionTest } from '@/services/api';
+import type { PushMode } from '@/types';
+import { errorMessage } from '@/lib/errors';
 
 export function GitLabSettings() {
   const { t } = useTranslation();
-  const { config: savedConfig, loading, update: updateConfig } = useGitLabAuth();
+  const { config: savedConfig, loading, update: updateConfig, testConnection } = useGitLabAuth();
   const [config, setConfig] = useState({
     pat: '',
     gitlabUrl: 'https://gitlab.com',
     defaultBranch: 'main',
+    push_mode: 'token' as PushMode,
   });
   const [saving, setSaving] = useState(false);
   const [showToken, setShowToken] = useState(false);
   const [hasChanges, setHasChanges] = useState(false);
+  const [testing, setTesting] = useState(false);
+  const [testResult, setTestResult] = useState<GitLabConnectionTest | null>(null);
 
   // Update local state when saved config changes
   useEffect(() => {
@@ -28,6 +35,7 @@ export function GitLabSettings() {
         pat: (savedConfig as any)?.pat || '',
         gitlabUrl: (savedConfig as any)?.gitlabUrl || 'https://gitlab.com',
         defaultBranch: (savedConfig as any)?.defaultBranch || 'main',
+        push_mode: (savedConfig as any)?.push_mode || 'token',
       });
     }
   }, [savedConfig]);
@@ -53,6 +61,27 @@ export function GitLabSettings() {
     const newConfig = { ...config, [field]: value };
     setConfig(newConfig);
     setHasChanges(true);
+    setTestResult(null);
+  };
+
+  const handleTestConnection = async () => {
+    try {
+      setTesting(true);
+      setTestResult(await testConnection(config));
+    } catch (error) {
+      console.error('Failed to test GitLab connection:', error);
+      setTestResult({ status: 'network_error', message: errorMessage(error) });
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  const handlePushModeChange = (push_mode: PushMode) => {
+    const newConfig = { ...config, push_mode };
+    setConfig(newConfig);
+    if (config.pat) {
+      handleAutoSave(newConfig);
+    }
   };
 
   const handleFieldBlur = () => {
@@ -136,6 +165,24 @@ export function GitLabSettings() {
           </p>
         </div>
 
+        <div className="space-y-2">
+          <Label htmlFor="pushMode">
+            {t('settings.gitlab.pushMode')}
+          </Label>
+          <Select value={config.push_mode} onValueChange={(value) => handlePushModeChange(value as PushMode)}>
+            <SelectTrigger id="pushMode">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="token">{t('settings.gitlab.pushModeToken')}</SelectItem>
+              <SelectItem value="ssh">{t('settings.gitlab.pushModeSsh')}</SelectItem>
+            </SelectContent>
+          </Select>
+          <p className="text-sm text-muted-foreground">
+            {t('settings.gitlab.pushModeHelp')}
+          </p>
+        </div>
+
         {config.pat && (
           <Alert>
             <CheckCircle className="h-4 w-4 text-green-500" />
@@ -145,6 +192,31 @@ export function GitLabSettings() {
           </Alert>
         )}
 
+        <Button
+          type="button"
+          variant="outline"
+          onClick={handleTestConnection}
+          disabled={testing || !config.pat}
+        >
+          {testing && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+          {t('settings.gitlab.testConnection')}
+        </Button>
+
+        {testResult && (
+          <Alert variant={testResult.status === 'ok' ? 'default' : 'destructive'}>
+            {testResult.status === 'ok' ? (
+              <CheckCircle className="h-4 w-4 text-green-500" />
+            ) : (
+              <XCircle className="h-4 w-4" />
+            )}
+            <AlertDescription>
+              {testResult.status === 'ok'
+                ? t('settings.gitlab.connectedAs', { username: testResult.username })
+                : `${t(`settings.gitlab.connection.${testResult.status}`)}: ${testResult.message}`}
+            </AlertDescription>
+          </Alert>
+        )}
+
         {/* Auto-save indicator */}
         {saving && (
           <div className="flex items-center gap-2 text-sm text-muted-foreground">
@@ -2,6 +2,7 @@ import { useState } from "react";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { GitHubSettings } from "./GitHubSettings";
 import { GitLabSettings } from "./GitLabSettings";
+import { GitHostsSettings } from "./GitHostsSettings";
 import { useTranslation } from "react-i18next";
 
 export function GitServicesSettings() {
@@ -33,6 +34,8 @@ export function GitServicesSettings() {
           <GitLabSettings />
         </TabsContent>
       </Tabs>
+
+      <GitHostsSettings />
     </div>
   );
 }
\ No newline at end of file
@@ -7,6 +7,7 @@ import { toast } from "@/hooks/use-toast";
 import { Download, Upload, Save } from "lucide-react";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { readTextFile, writeTextFile } from "@tauri-apps/plugin-fs";
+import { errorMessage } from "@/lib/errors";
 
 interface McpConfig {
   servers: {
@@ -81,7 +82,7 @@ export function McpConfigManager() {
     } catch (error) {
       toast({
         title: "错误",
-        description: `导入配置失败: ${error}`,
+        description: `导入配置失败: ${errorMessage(error)}`,
         variant: "destructive",
       });
     }
@@ -116,7 +117,7 @@ export function McpConfigManager() {
     } catch (error) {
       toast({
         title: "错误",
-        description: `导出配置失败: ${error}`,
+        description: `导出配置失败: ${errorMessage(error)}`,
         variant: "destructive",
       });
     }
@@ -142,7 +143,7 @@ export function McpConfigManager() {
     } catch (error) {
       toast({
         title: "错误",
-        description: `应用配置失败: ${error}`,
+        description: `应用配置失败: ${errorMessage(error)}`,
         variant: "destructive",
       });
     }
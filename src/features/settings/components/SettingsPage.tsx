@@ -1,10 +1,11 @@
 import { useState } from "react";
 import { Button } from "@/components/ui/button";
-import { ArrowLeft, Settings as SettingsIcon, Monitor, GitBranch, Server } from "lucide-react";
+import { ArrowLeft, Settings as SettingsIcon, Monitor, GitBranch, Server, HardDrive } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { McpServerManager } from "@/features/mcp/components/McpServerManager";
 import { GeneralSettings } from "./GeneralSettings";
 import { GitServicesSettings } from "./GitServicesSettings";
+import { StorageSettings } from "./StorageSettings";
 import { useTranslation } from "react-i18next";
 
 interface SettingsPageProps {
@@ -12,7 +13,7 @@ interface SettingsPageProps {
   initialCategory?: string;
 }
 
-type SettingsCategory = "general" | "git-services" | "mcp";
+type SettingsCategory = "general" | "git-services" | "mcp" | "storage";
 
 interface CategoryItem {
   id: SettingsCategory;
@@ -41,6 +42,11 @@ export function SettingsPage({ onBack, initialCategory }: SettingsPageProps) {
       id: "mcp",
       label: t("settings.categories.mcp"),
       icon: <Server className="h-4 w-4" />
+    },
+    {
+      id: "storage",
+      label: t("settings.categories.storage"),
+      icon: <HardDrive className="h-4 w-4" />
     }
   ];
 
@@ -52,6 +58,8 @@ export function SettingsPage({ onBack, initialCategory }: SettingsPageProps) {
         return <GitServicesSettings />;
       case "mcp":
         return <McpServerManager />;
+      case "storage":
+        return <StorageSettings />;
       default:
         return null;
     }
@@ -0,0 +1,176 @@
+import { useCallback, useEffect, useState } from "react";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Loader2, RefreshCw, Trash2 } from "lucide-react";
+import { useTranslation } from "react-i18next";
+import { toast } from "@/hooks/use-toast";
+import { configApi, gitApi, taskAttemptApi } from "@/lib/api";
+import { errorMessage } from "@/lib/errors";
+import { formatBytes } from "@/lib/utils";
+import type { AllWorktreesUsage, StorageConfig, WorktreeUsage } from "@/types";
+import { FileWatcherSettings } from "./FileWatcherSettings";
+
+const GIB = 1024 * 1024 * 1024;
+
+/** Worktree size warning and the disk space used by every attempt's worktree */
+export function StorageSettings() {
+  const { t } = useTranslation();
+  const [config, setConfig] = useState<StorageConfig | null>(null);
+  const [limitGb, setLimitGb] = useState("");
+  const [usage, setUsage] = useState<AllWorktreesUsage | null>(null);
+  const [loadingUsage, setLoadingUsage] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [removing, setRemoving] = useState<string | null>(null);
+
+  const loadUsage = useCallback(async () => {
+    try {
+      setLoadingUsage(true);
+      setUsage(await taskAttemptApi.getAllWorktreesUsage());
+    } catch (error) {
+      console.error("Failed to load worktree usage:", error);
+    } finally {
+      setLoadingUsage(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    configApi.getStorageConfig()
+      .then((loaded) => {
+        setConfig(loaded);
+        setLimitGb(String(loaded.worktreeWarningBytes / GIB));
+      })
+      .catch((error) => console.error("Failed to load storage settings:", error));
+    loadUsage();
+  }, [loadUsage]);
+
+  const handleSave = async () => {
+    const gb = Number(limitGb);
+    if (!config || !Number.isFinite(gb) || gb < 0) return;
+
+    try {
+      setSaving(true);
+      const updated = { ...config, worktreeWarningBytes: Math.round(gb * GIB) };
+      await configApi.updateStorageConfig(updated);
+      setConfig(updated);
+      toast({ title: t("settings.storage.saved") });
+    } catch (error) {
+      toast({
+        title: t("toast.error"),
+        description: errorMessage(error),
+        variant: "destructive",
+      });
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleRemove = async (worktree: WorktreeUsage) => {
+    if (!window.confirm(t("settings.storage.removeConfirm", { task: worktree.taskTitle }))) return;
+
+    try {
+      setRemoving(worktree.attemptId);
+      await gitApi.removeWorktree(worktree.projectPath, worktree.worktreePath);
+      toast({ title: t("settings.storage.removed") });
+      await loadUsage();
+    } catch (error) {
+      toast({
+        title: t("toast.error"),
+        description: errorMessage(error),
+        variant: "destructive",
+      });
+    } finally {
+      setRemoving(null);
+    }
+  };
+
+  return (
+    <div className="space-y-6">
+      <div>
+        <h2 className="text-2xl font-semibold tracking-tight">
+          {t("settings.categories.storage")}
+        </h2>
+        <p className="text-muted-foreground">
+          {t("settings.storage.description")}
+        </p>
+      </div>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t("settings.storage.limitTitle")}</CardTitle>
+          <CardDescription>{t("settings.storage.warningLimitHelp")}</CardDescription>
+        </CardHeader>
+        <CardContent className="flex items-end gap-2">
+          <div className="space-y-2">
+            <Label htmlFor="worktreeWarningLimit">{t("settings.storage.warningLimit")}</Label>
+            <Input
+              id="worktreeWarningLimit"
+              type="number"
+              min={0}
+              step={0.5}
+              className="w-32"
+              value={limitGb}
+              onChange={(e) => setLimitGb(e.target.value)}
+            />
+          </div>
+          <Button type="button" onClick={handleSave} disabled={saving || !config}>
+            {saving && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+            {t("common.save")}
+          </Button>
+        </CardContent>
+      </Card>
+
+      <FileWatcherSettings />
+
+      <Card>
+        <CardHeader className="flex flex-row items-center justify-between">
+          <div className="space-y-1.5">
+            <CardTitle>{t("settings.storage.usageTitle")}</CardTitle>
+            {usage && (
+              <CardDescription>
+                {t("settings.storage.total", { size: formatBytes(usage.totalBytes) })}
+                {" · "}
+                {t("settings.storage.sharedGit", { size: formatBytes(usage.sharedGitBytes) })}
+              </CardDescription>
+            )}
+          </div>
+          <Button type="button" variant="ghost" size="icon" onClick={loadUsage} disabled={loadingUsage}>
+            <RefreshCw className={loadingUsage ? "h-4 w-4 animate-spin" : "h-4 w-4"} />
+          </Button>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          {usage && usage.worktrees.length === 0 && (
+            <p className="text-sm text-muted-foreground">{t("settings.storage.noWorktrees")}</p>
+          )}
+          {usage?.worktrees.map((worktree) => (
+            <div key={worktree.attemptId} className="flex items-start justify-between gap-4 border-b pb-3 last:border-0">
+              <div className="min-w-0 space-y-1">
+                <div className="font-medium truncate">{worktree.taskTitle}</div>
+                <div className="text-xs text-muted-foreground truncate">{worktree.worktreePath}</div>
+                <div className="text-xs text-muted-foreground">
+                  {worktree.largestPaths.slice(0, 3).map((entry) => `${entry.path} ${formatBytes(entry.bytes)}`).join(", ")}
+                </div>
+              </div>
+              <div className="flex items-center gap-2 flex-shrink-0">
+                <span className="text-sm font-medium">{formatBytes(worktree.bytes)}</span>
+                <Button
+                  type="button"
+                  variant="ghost"
+                  size="icon"
+                  title={t("settings.storage.remove")}
+                  onClick={() => handleRemove(worktree)}
+                  disabled={removing === worktree.attemptId}
+                >
+                  {removing === worktree.attemptId
+                    ? <Loader2 className="h-4 w-4 animate-spin" />
+                    : <Trash2 className="h-4 w-4" />}
+                </Button>
+              </div>
+            </div>
+          ))}
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
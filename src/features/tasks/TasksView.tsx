@@ -19,7 +19,9 @@ import { ImperativePanelHandle } from 'react-resizable-panels';
 import { Task, TaskStatus, CreateTaskRequest, UpdateTaskRequest } from '@/types';
 import { useEvent } from '@/lib/events';
 import { useToast } from '@/hooks/use-toast';
+import { ToastAction } from '@/components/ui/toast';
 import { useTaskCommand } from './conversation/hooks/useTaskCommand';
+import { errorMessage } from '@/lib/errors';
 
 export function TasksView() {
   const { t } = useTranslation();
@@ -42,6 +44,10 @@ export function TasksView() {
   // Local state
   const [tasks, setTasks] = useState<Task[]>([]);
   const [selectedTask, setSelectedTask] = useState<Task | null>(null);
+  // Task to select once loaded, from the window URL or a navigate-to-task event
+  const [pendingTaskId, setPendingTaskId] = useState<string | null>(
+    () => new URLSearchParams(window.location.search).get('taskId')
+  );
   const [showCreateTaskDialog, setShowCreateTaskDialog] = useState(false);
   const [showEditTaskDialog, setShowEditTaskDialog] = useState(false);
   const [taskToEdit, setTaskToEdit] = useState<Task | null>(null);
@@ -61,6 +67,22 @@ export function TasksView() {
     }
   }, [currentProject]);
   
+  // Select the requested task as soon as it is in the list
+  useEffect(() => {
+    if (!pendingTaskId) return;
+    const task = tasks.find(t => t.id === pendingTaskId);
+    if (task) {
+      setSelectedTask(task);
+      setPendingTaskId(null);
+    }
+  }, [tasks, pendingTaskId]);
+  
+  useEvent('navigate-to-task', ({ projectId, taskId }) => {
+    if (projectId !== currentProject?.id) return;
+    setPendingTaskId(taskId);
+    loadTasks();
+  }, [currentProject?.id]);
+  
   // Listen for task status updates
   useEvent('task:status-changed', ({ task: updatedTask }) => {
     setTasks(prevTasks => 
@@ -73,6 +95,17 @@ export function TasksView() {
       prevSelected?.id === updatedTask.id ? updatedTask : prevSelected
     );
   }, []);
+
+  // Bulk status updates arrive as one event so the board re-renders once
+  useEvent('task:bulk-status-changed', ({ tasks: updatedTasks }) => {
+    const updatedById = new Map(updatedTasks.map(task => [task.id, task]));
+    setTasks(prevTasks =>
+      prevTasks.map(task => updatedById.get(task.id) ?? task)
+    );
+    setSelectedTask(prevSelected =>
+      prevSelected ? updatedById.get(prevSelected.id) ?? prevSelected : prevSelected
+    );
+  }, []);
   
   
   const loadTasks = async () => {
@@ -116,22 +149,18 @@ export function TasksView() {
       console.error('Failed to create task:', error);
       
       // Parse error message
-      let errorMessage = t('task.createTaskError');
-      if (error instanceof Error) {
-        // Check if it's a worktree creation error
-        if (error.message.includes('ambiguous argument') || error.message.includes('unknown revision')) {
-          errorMessage = t('task.worktreeCreationError', { 
-            defaultValue: 'Failed to create worktree. Please check your project\'s main branch configuration in project settings.' 
-          });
-        } else {
-          errorMessage = error.message;
-        }
+      let description = errorMessage(error) || t('task.createTaskError');
+      // Check if it's a worktree creation error
+      if (description.includes('ambiguous argument') || description.includes('unknown revision')) {
+        description = t('task.worktreeCreationError', { 
+          defaultValue: 'Failed to create worktree. Please check your project\'s main branch configuration in project settings.' 
+        });
       }
       
       // Show error toast
       toast({
         title: t('task.createTaskError'),
-        description: errorMessage,
+        description,
         variant: 'destructive',
       });
     }
@@ -155,8 +184,33 @@ export function TasksView() {
       if (selectedTask?.id === id) {
         setSelectedTask(null);
       }
+      toast({
+        title: t('task.taskDeleted'),
+        action: (
+          <ToastAction altText={t('task.undoDelete')} onClick={() => handleRestoreTask(id)}>
+            {t('task.undoDelete')}
+          </ToastAction>
+        ),
+      });
     } catch (error) {
       console.error('Failed to delete task:', error);
+      toast({
+        title: t('task.deleteTaskError'),
+        variant: 'destructive',
+      });
+    }
+  };
+  
+  const handleRestoreTask = async (id: string) => {
+    try {
+      await taskApi.restore(id);
+      await loadTasks();
+    } catch (error) {
+      console.error('Failed to restore task:', error);
+      toast({
+        title: t('task.restoreTaskError'),
+        variant: 'destructive',
+      });
     }
   };
   
@@ -1,5 +1,6 @@
 import { useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { TaskCommandResult } from '@/types';
 
 // Simplified command types based on RFC
 export type TaskCommand = 
@@ -21,7 +22,7 @@ export type TaskCommand =
 export function useTaskCommand() {
   const sendCommand = useCallback(async (command: TaskCommand) => {
     try {
-      return await invoke('execute_task_command', { command });
+      return await invoke<TaskCommandResult>('execute_task_command', { command });
     } catch (error) {
       console.error('Command failed:', command.type, error);
       throw error;
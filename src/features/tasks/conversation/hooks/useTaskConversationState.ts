@@ -21,6 +21,7 @@ export interface ConversationState {
   canSendMessage: boolean;
   currentExecution?: any; // CodingAgentExecution
   worktreePath?: string;
+  setupRunning?: boolean;
 }
 
 /**
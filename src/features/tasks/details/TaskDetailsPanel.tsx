@@ -32,7 +32,7 @@ export function TaskDetailsPanel({
   const { t } = useTranslation();
   const [currentAttempt, setCurrentAttempt] = useState<TaskAttempt | null>(null);
   const [refreshKey, setRefreshKey] = useState(0);
-  const [changedFilePath, setChangedFilePath] = useState<string | null>(null);
+  const [changedFilePaths, setChangedFilePaths] = useState<string[]>([]);
   
   const handleOpenInTerminal = async (path: string) => {
     try {
@@ -100,20 +100,28 @@ export function TaskDetailsPanel({
     if (!worktreePath) return;
 
     let unlisten: (() => void) | undefined;
+    // Watches are shared and counted, so only a watch that was granted is released
+    let watching = false;
+    let disposed = false;
 
     const setupFileWatcher = async () => {
       try {
         // Start watching the worktree
         await invoke("watch_worktree", { worktreePath });
+        watching = true;
+        if (disposed) {
+          invoke("unwatch_worktree", { worktreePath }).catch(console.error);
+          return;
+        }
         console.log("Started watching worktree:", worktreePath);
 
-        // Listen for file change events
-        unlisten = eventBus.subscribe("file-change", (payload: { worktree_path: string; file_path: string; kind: string }) => {
+        // Listen for batched file change events
+        unlisten = eventBus.subscribe("worktree-files-changed", (payload) => {
           // Only refresh if the event is for our worktree
           if (payload.worktree_path === worktreePath) {
-            console.log("File changed in worktree:", payload.file_path, payload.kind);
-            // Set the changed file path and trigger refresh
-            setChangedFilePath(payload.file_path);
+            console.log("Files changed in worktree:", payload.changes.length);
+            // Set the changed file paths and trigger refresh
+            setChangedFilePaths(payload.changes.map(change => change.file_path));
             setRefreshKey(prev => prev + 1);
           }
         });
@@ -126,11 +134,12 @@ export function TaskDetailsPanel({
 
     // Cleanup
     return () => {
+      disposed = true;
       if (unlisten) {
         unlisten();
       }
       // Unwatch the worktree when component unmounts or worktree changes
-      if (worktreePath) {
+      if (watching) {
         invoke("unwatch_worktree", { worktreePath }).catch(console.error);
         console.log("Stopped watching worktree:", worktreePath);
       }
@@ -143,13 +152,18 @@ export function TaskDetailsPanel({
     return (
       <Card className="h-full">
         <CardContent className="h-full p-0">
-          {project && task ? (
+          {project?.git_enabled === false ? (
+            <div className="flex items-center justify-center h-full text-muted-foreground">
+              {t('git.notGitProject')}
+            </div>
+          ) : project && task ? (
             <FileTreeDiff 
               projectPath={project.path} 
+              projectId={project.id}
               taskId={task.id} 
               worktreePath={currentAttempt?.worktree_path}
               refreshKey={refreshKey}
-              changedFilePath={changedFilePath}
+              changedFilePaths={changedFilePaths}
             />
           ) : (
             <div className="flex items-center justify-center h-full text-muted-foreground">
@@ -171,13 +185,18 @@ export function TaskDetailsPanel({
       {/* Top section - File changes */}
       <Card className="h-full">
         <CardContent className="h-full p-0">
-          {project && task ? (
+          {project?.git_enabled === false ? (
+            <div className="flex items-center justify-center h-full text-muted-foreground">
+              {t('git.notGitProject')}
+            </div>
+          ) : project && task ? (
             <FileTreeDiff 
               projectPath={project.path} 
+              projectId={project.id}
               taskId={task.id} 
               worktreePath={currentAttempt?.worktree_path}
               refreshKey={refreshKey}
-              changedFilePath={changedFilePath}
+              changedFilePaths={changedFilePaths}
             />
           ) : (
             <div className="flex items-center justify-center h-full text-muted-foreground">
@@ -323,7 +342,11 @@ export function TaskDetailsPanel({
               </TabsContent>
 
               <TabsContent value="integration" className="h-full p-0">
-                {project && task ? (
+                {project?.git_enabled === false ? (
+                  <div className="flex items-center justify-center h-full text-muted-foreground">
+                    {t('git.notGitProject')}
+                  </div>
+                ) : project && task ? (
                   <IntegrationPanel 
                     task={task} 
                     project={project} 
@@ -8,8 +8,8 @@ import {
   DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
 import { cn } from "@/lib/utils";
-import { Task, TaskStatus } from "@/types";
-import { Clock, User, Play, MoreVertical, Trash2, Edit } from "lucide-react";
+import { StatusChangeTrigger, Task, TaskStatus } from "@/types";
+import { Clock, User, Play, MoreVertical, Trash2, Edit, GitMerge, Bot, GitPullRequestClosed } from "lucide-react";
 import { useTranslation } from "react-i18next";
 
 interface TaskCardProps {
@@ -37,8 +37,16 @@ const statusColors: Record<TaskStatus, string> = {
   [TaskStatus.Cancelled]: "border-l-red-500",
 };
 
+/** Badges for tasks the app moved on its own; manual moves need none */
+const triggerIcons: Partial<Record<StatusChangeTrigger, typeof Bot>> = {
+  agent_completed: Bot,
+  vcs_merged: GitMerge,
+  vcs_closed: GitPullRequestClosed,
+};
+
 export function TaskCard({ task, isDragging, onClick, onExecute, onEdit, onDelete }: TaskCardProps) {
   const { t } = useTranslation();
+  const TriggerIcon = task.status_triggered_by ? triggerIcons[task.status_triggered_by] : undefined;
   
   return (
     <Card
@@ -119,6 +127,11 @@ export function TaskCard({ task, isDragging, onClick, onExecute, onEdit, onDelet
                 <Clock className="h-3 w-3 text-muted-foreground/70" />
                 <span className="text-muted-foreground">{new Date(task.updated_at).toLocaleDateString()}</span>
               </div>
+              {TriggerIcon && (
+                <span title={t(`task.statusTrigger.${task.status_triggered_by}`)}>
+                  <TriggerIcon className="h-3 w-3 text-muted-foreground/70" />
+                </span>
+              )}
             </div>
             <span className="text-xs font-mono text-muted-foreground/60">#{task.id.slice(0, 8)}</span>
           </div>
@@ -37,10 +37,12 @@ const generateId = () => `comment-${Date.now()}-${Math.random().toString(36).sub
 
 interface FileTreeDiffProps {
   projectPath: string;
+  /** Scopes the tree and diff to the project's subdirectory */
+  projectId?: string;
   taskId: string;
   worktreePath?: string;
   refreshKey?: number;
-  changedFilePath?: string | null;
+  changedFilePaths?: string[];
 }
 
 interface FileNode {
@@ -62,7 +64,7 @@ interface OpenFile {
   newContent: string;
 }
 
-export function FileTreeDiff({ projectPath, taskId, worktreePath, refreshKey = 0, changedFilePath }: FileTreeDiffProps) {
+export function FileTreeDiff({ projectPath, projectId, taskId, worktreePath, refreshKey = 0, changedFilePaths = [] }: FileTreeDiffProps) {
   const { t } = useTranslation();
   const { renderContextMenuItems } = useFileContextMenu();
   const [selectedFile, setSelectedFile] = useState<string | null>(null);
@@ -135,12 +137,12 @@ export function FileTreeDiff({ projectPath, taskId, worktreePath, refreshKey = 0
     }
   }, [refreshKey, worktreePath]);
 
-  // Refresh open files when specific file changes and git status is updated
+  // Refresh open files among the changed ones when git status is updated
   useEffect(() => {
-    if (refreshKey > 0 && worktreePath && changedFilePath && openFiles.length > 0 && Object.keys(gitStatusMap).length > 0) {
-      refreshChangedFile(changedFilePath);
+    if (refreshKey > 0 && worktreePath && changedFilePaths.length > 0 && openFiles.length > 0 && Object.keys(gitStatusMap).length > 0) {
+      changedFilePaths.forEach(refreshChangedFile);
     }
-  }, [refreshKey, worktreePath, changedFilePath, gitStatusMap]);
+  }, [refreshKey, worktreePath, changedFilePaths, gitStatusMap]);
 
   const loadGitStatus = async () => {
     // Only load if we have a worktree path
@@ -217,10 +219,8 @@ export function FileTreeDiff({ projectPath, taskId, worktreePath, refreshKey = 0
         updatedFile = { ...file, oldContent: content, newContent: content };
       }
       
-      // Update only the changed file in the openFiles array
-      const newOpenFiles = [...openFiles];
-      newOpenFiles[fileIndex] = updatedFile;
-      setOpenFiles(newOpenFiles);
+      // Update only the changed file; other files may be refreshing at the same time
+      setOpenFiles(prev => prev.map(f => (f.path === filePath ? updatedFile : f)));
       
       console.log(`Refreshed open file: ${filePath}`);
     } catch (error) {
@@ -234,7 +234,7 @@ export function FileTreeDiff({ projectPath, taskId, worktreePath, refreshKey = 0
     console.log("[FileTreeDiff] Loading all files for worktree:", worktreePath);
     
     try {
-      const fileList = await gitApi.listAllFiles(worktreePath);
+      const fileList = await gitApi.listAllFiles(worktreePath, projectId);
       console.log("[FileTreeDiff] All files loaded:", {
         count: fileList.length,
         sampleFiles: fileList.slice(0, 10), // Show first 10 files as sample
@@ -502,7 +502,7 @@ export function FileTreeDiff({ projectPath, taskId, worktreePath, refreshKey = 0
         }
       } else if (fileStatus === 'deleted') {
         // Deleted file, we need to get the content from git
-        const diffContent = await gitApi.getDiff(worktreePath, false);
+        const diffContent = await gitApi.getDiff(worktreePath, false, projectId);
         const { oldContent } = parseGitDiff(diffContent, relativePath);
         
         if (existingIndex === -1) {
@@ -18,6 +18,7 @@ import {
   FileEdit
 } from "lucide-react";
 import { toast } from "@/hooks/use-toast";
+import { errorMessage } from "@/lib/errors";
 import { FileListItem } from "./FileListItem";
 
 interface GitStatusPanelProps {
@@ -131,7 +132,7 @@ export function GitStatusPanel({ projectPath, onRefresh }: GitStatusPanelProps)
       console.error("Failed to commit:", error);
       toast({
         title: "错误",
-        description: `提交失败: ${error}`,
+        description: `提交失败: ${errorMessage(error)}`,
         variant: "destructive",
       });
     } finally {
@@ -151,7 +152,7 @@ export function GitStatusPanel({ projectPath, onRefresh }: GitStatusPanelProps)
       console.error("Failed to push:", error);
       toast({
         title: "错误",
-        description: `推送失败: ${error}`,
+        description: `推送失败: ${errorMessage(error)}`,
         variant: "destructive",
       });
     } finally {
@@ -10,6 +10,7 @@ import { Task } from "@/types";
 import { GitBranch, FolderOpen, Plus, Trash2, RefreshCw } from "lucide-react";
 import { open } from "@tauri-apps/plugin-shell";
 import { toast } from "@/hooks/use-toast";
+import { errorMessage } from "@/lib/errors";
 
 interface WorktreeManagerProps {
   task: Task;
@@ -79,7 +80,7 @@ export function WorktreeManager({ task, projectPath }: WorktreeManagerProps) {
       console.error("Failed to create worktree:", error);
       toast({
         title: "错误",
-        description: `创建工作树失败: ${error}`,
+        description: `创建工作树失败: ${errorMessage(error)}`,
         variant: "destructive",
       });
     } finally {
@@ -101,7 +102,7 @@ export function WorktreeManager({ task, projectPath }: WorktreeManagerProps) {
       console.error("Failed to remove worktree:", error);
       toast({
         title: "错误",
-        description: `删除工作树失败: ${error}`,
+        description: `删除工作树失败: ${errorMessage(error)}`,
         variant: "destructive",
       });
     }
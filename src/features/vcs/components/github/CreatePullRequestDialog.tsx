@@ -12,6 +12,7 @@ import { useTranslation } from 'react-i18next';
 import { toast } from '@/hooks/use-toast';
 import type { TaskAttempt } from '@/types';
 import { taskApi } from '@/services/api';
+import { errorMessage } from '@/lib/errors';
 
 interface CreatePullRequestDialogProps {
   open: boolean;
@@ -105,14 +106,7 @@ export function CreatePullRequestDialog({
     try {
       setLoading(true);
 
-      // First push the branch
-      await gitHubApi.pushToGitHub(
-        taskAttempt.worktree_path,
-        taskAttempt.branch,
-        false
-      );
-
-      // Create the pull request
+      // The branch is pushed first if the remote doesn't have it yet
       await gitHubApi.createPullRequest({
         taskAttemptId: taskAttempt.id,
         remoteUrl,
@@ -120,6 +114,7 @@ export function CreatePullRequestDialog({
         description,
         sourceBranch: taskAttempt.branch,
         targetBranch,
+        autoPush: true,
       });
 
       toast({
@@ -129,11 +124,11 @@ export function CreatePullRequestDialog({
 
       onOpenChange(false);
       onSuccess?.();
-    } catch (error: any) {
+    } catch (error) {
       console.error('Failed to create pull request:', error);
       toast({
         title: t('toast.error'),
-        description: error.message || t('pullRequests.prError'),
+        description: errorMessage(error) || t('pullRequests.prError'),
         variant: 'destructive',
       });
     } finally {
@@ -11,6 +11,7 @@ import { gitLabApi } from '@/services/api';
 import { useTranslation } from 'react-i18next';
 import { toast } from '@/hooks/use-toast';
 import type { TaskAttempt } from '@/types';
+import { errorMessage } from '@/lib/errors';
 
 interface CreateMergeRequestDialogProps {
   open: boolean;
@@ -92,21 +93,15 @@ export function CreateMergeRequestDialog({
     try {
       setLoading(true);
 
-      // First push the branch
-      await gitLabApi.pushToGitLab(
-        taskAttempt.worktree_path,
-        taskAttempt.branch,
-        false
-      );
-
-      // Create the merge request
+      // The branch is pushed first if the remote doesn't have it yet
       await gitLabApi.createMergeRequest({
         taskAttemptId: taskAttempt.id,
         remoteUrl,
         title,
         description,
         sourceBranch: taskAttempt.branch,
-        targetBranch
+        targetBranch,
+        autoPush: true
       });
 
       toast({
@@ -116,11 +111,11 @@ export function CreateMergeRequestDialog({
 
       onOpenChange(false);
       onSuccess?.();
-    } catch (error: any) {
+    } catch (error) {
       console.error('Failed to create merge request:', error);
       toast({
         title: t('toast.error'),
-        description: error.message || t('mergeRequests.mrError'),
+        description: errorMessage(error) || t('mergeRequests.mrError'),
         variant: 'destructive',
       });
     } finally {
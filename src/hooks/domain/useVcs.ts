@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { gitHubApi, gitLabApi } from '@/services/api';
+import type { GitLabConfig } from '@/services/api';
 import type { MergeRequestInfo } from '@/types';
 import { listen } from '@tauri-apps/api/event';
 
@@ -59,6 +60,7 @@ export function useGitLabAuth() {
     config,
     loading,
     update: gitLabApi.updateConfig,
+    testConnection: (config?: GitLabConfig) => gitLabApi.testConnection(config),
   };
 }
 
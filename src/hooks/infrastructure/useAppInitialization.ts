@@ -11,6 +11,8 @@ import { useTranslation } from 'react-i18next';
 import { useApp } from '@/contexts/AppContext';
 import { projectApi, mcpApi, windowApi } from '@/services/api';
 import { useErrorDialog } from '@/hooks/use-error-dialog';
+import { formatBytes } from '@/lib/utils';
+import { errorMessage } from '@/lib/errors';
 
 export function useAppInitialization() {
   const { t } = useTranslation();
@@ -88,6 +90,19 @@ export function useAppInitialization() {
     });
   });
   
+  useEvent('worktree-size-warning', ({ taskTitle, bytes, limitBytes, largestPaths }) => {
+    toast({
+      title: t('settings.storage.sizeWarningTitle'),
+      description: t('settings.storage.sizeWarningDescription', {
+        task: taskTitle,
+        size: formatBytes(bytes),
+        limit: formatBytes(limitBytes),
+        largest: largestPaths.slice(0, 3).map((entry) => `${entry.path} (${formatBytes(entry.bytes)})`).join(', '),
+      }),
+      variant: 'destructive',
+    });
+  });
+  
   useEvent('menu-settings', () => {
     navigateTo('settings');
   });
@@ -104,13 +119,11 @@ export function useAppInitialization() {
         // Navigate to projects view - it will handle the project creation form
         navigateTo('projects');
       }
-    } catch (error: any) {
+    } catch (error) {
       logger.error('Failed to open project', error);
       console.error('Error object:', error);
-      // Tauri errors come as strings, not Error objects
-      const errorMessage = typeof error === 'string' ? error : (error.message || t('project.openFailed'));
       showError(
-        errorMessage,
+        errorMessage(error) || t('project.openFailed'),
         t('common.error')
       );
     }
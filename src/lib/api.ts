@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { 
   Task, 
+  TaskSearchResult,
   Project, 
   TaskStatus, 
   CreateTaskRequest, 
@@ -8,10 +9,44 @@ import {
   CreateProjectRequest,
   UpdateProjectRequest,
   ExecutionProcess,
+  OutputStreamType,
+  ProcessOutputLine,
+  ProcessOutputPage,
   GitStatus,
+  FileCommit,
+  RemoteBranch,
+  FetchResult,
   McpServer,
+  NewMcpServer,
   TaskAttempt,
+  NetworkConfig,
+  WindowConfig,
+  ImportProjectResult,
+  TaskSearchFilters,
+  BackupConfig,
+  DevServerConfig,
+  GitHostsConfig,
+  StorageConfig,
+  FileWatcherConfig,
+  NotificationConfig,
+  WorktreeUsage,
+  AllWorktreesUsage,
+  DatabaseBackup,
+  RolledBackMigration,
+  LogEntry,
+  LogLevel,
+  AttemptCommit,
+  TaskEvent,
+  TaskStatusChange,
+  ReviewComment,
+  CreateReviewCommentRequest,
+  UpdateReviewCommentRequest,
+  TaskTemplate,
+  CreateTaskTemplateRequest,
+  UpdateTaskTemplateRequest,
+  TaskCommandResult,
 } from "@/types";
+import { TimelineEvent } from "@/types/execution";
 
 // Task API
 export const taskApi = {
@@ -19,12 +54,25 @@ export const taskApi = {
     return await invoke("create_task", { request });
   },
 
+  /** The subtask's project comes from the parent; its worktree branches off the parent's branch */
+  createSubtask: async (parentId: string, request: CreateTaskRequest): Promise<Task> => {
+    return await invoke("create_subtask", { parentId, request });
+  },
+
+  listSubtasks: async (parentId: string): Promise<Task[]> => {
+    return await invoke("list_subtasks", { parentId });
+  },
+
   get: async (id: string): Promise<Task | null> => {
     return await invoke("get_task", { id });
   },
 
-  list: async (projectId: string): Promise<Task[]> => {
-    return await invoke("list_tasks", { projectId });
+  list: async (projectId: string, includeArchived?: boolean): Promise<Task[]> => {
+    return await invoke("list_tasks", { projectId, includeArchived });
+  },
+
+  search: async (projectId: string | undefined, query: string, filters?: TaskSearchFilters): Promise<TaskSearchResult[]> => {
+    return await invoke("search_tasks", { projectId, query, filters });
   },
 
   update: async (id: string, request: UpdateTaskRequest): Promise<Task> => {
@@ -35,9 +83,43 @@ export const taskApi = {
     return await invoke("delete_task", { id });
   },
 
+  restore: async (taskId: string): Promise<Task> => {
+    return await invoke("restore_task", { taskId });
+  },
+
+  purgeDeleted: async (olderThanDays: number): Promise<number> => {
+    return await invoke("purge_deleted_tasks", { olderThanDays });
+  },
+
+  archive: async (id: string): Promise<Task> => {
+    return await invoke("archive_task", { id });
+  },
+
+  unarchive: async (id: string): Promise<Task> => {
+    return await invoke("unarchive_task", { id });
+  },
+
   updateStatus: async (id: string, status: TaskStatus): Promise<Task> => {
     return await invoke("update_task_status", { id, status });
   },
+
+  bulkUpdateStatus: async (taskIds: string[], status: TaskStatus): Promise<Task[]> => {
+    return await invoke("bulk_update_task_status", { taskIds, status });
+  },
+
+  moveToProject: async (id: string, targetProjectId: string): Promise<Task> => {
+    return await invoke("move_task_to_project", { id, targetProjectId });
+  },
+
+  /** The latest `limit` events of a task before `before`, oldest first */
+  getTimeline: async (taskId: string, limit?: number, before?: string): Promise<TaskEvent[]> => {
+    return await invoke("get_task_timeline", { taskId, limit, before });
+  },
+
+  /** Every status change of a task and what caused it, oldest first */
+  getStatusHistory: async (taskId: string): Promise<TaskStatusChange[]> => {
+    return await invoke("get_task_status_history", { taskId });
+  },
 };
 
 // Task Attempt API
@@ -50,9 +132,101 @@ get: async (id: string): Promise<TaskAttempt | null> => {
     return await invoke("list_task_attempts", { taskId });
   },
 
+  listCommits: async (attemptId: string): Promise<AttemptCommit[]> => {
+    return await invoke("list_attempt_commits", { attemptId });
+  },
+
+  /** Disk space taken by the attempt's worktree and its largest top-level paths */
+  getWorktreeUsage: async (attemptId: string): Promise<WorktreeUsage> => {
+    return await invoke("get_worktree_usage", { attemptId });
+  },
+
+  getAllWorktreesUsage: async (): Promise<AllWorktreesUsage> => {
+    return await invoke("get_all_worktrees_usage");
+  },
+
+  create: async (taskId: string, baseBranch?: string): Promise<TaskAttempt> => {
+    return await invoke("create_task_attempt", { taskId, baseBranch });
+  },
+
+  /** Start a new attempt of the same task that re-sends this attempt's user messages */
+  replay: async (attemptId: string): Promise<TaskAttempt> => {
+    return await invoke("replay_attempt", { attemptId });
+  },
+
+  /** Send the attempt's unresolved review comments to its agent */
+  sendReview: async (attemptId: string): Promise<TaskCommandResult> => {
+    return await invoke("send_review_to_agent", { attemptId });
+  },
+
+  /** Stop the attempt's setup script, or give up on a failed one, and send queued messages */
+  skipSetup: async (attemptId: string): Promise<void> => {
+    return await invoke("skip_attempt_setup", { attemptId });
+  },
+
+  /** Run the project's setup script in the attempt's worktree again */
+  rerunSetup: async (attemptId: string): Promise<void> => {
+    return await invoke("rerun_attempt_setup", { attemptId });
+  },
+
 updateClaudeSessionId: async (attemptId: string, claudeSessionId: string): Promise<void> => {
     return await invoke("update_attempt_claude_session", { attemptId, claudeSessionId });
   },
+
+  updateMcpServers: async (attemptId: string, serverIds: string[]): Promise<void> => {
+    return await invoke("update_attempt_mcp_servers", { attemptId, serverIds });
+  },
+
+  getExecutionTimeline: async (attemptId: string): Promise<TimelineEvent[]> => {
+    return await invoke("get_execution_timeline", { attemptId });
+  },
+};
+
+// Review Comment API
+export const reviewCommentApi = {
+  create: async (request: CreateReviewCommentRequest): Promise<ReviewComment> => {
+    return await invoke("create_review_comment", { request });
+  },
+
+  list: async (attemptId: string): Promise<ReviewComment[]> => {
+    return await invoke("list_review_comments", { attemptId });
+  },
+
+  update: async (id: string, request: UpdateReviewCommentRequest): Promise<ReviewComment> => {
+    return await invoke("update_review_comment", { id, request });
+  },
+
+  delete: async (id: string): Promise<void> => {
+    return await invoke("delete_review_comment", { id });
+  },
+};
+
+// Task Template API
+export const taskTemplateApi = {
+  create: async (projectId: string, request: CreateTaskTemplateRequest): Promise<TaskTemplate> => {
+    return await invoke("create_task_template", { projectId, request });
+  },
+
+  list: async (projectId: string): Promise<TaskTemplate[]> => {
+    return await invoke("list_task_templates", { projectId });
+  },
+
+  get: async (id: string): Promise<TaskTemplate> => {
+    return await invoke("get_task_template", { id });
+  },
+
+  update: async (id: string, request: UpdateTaskTemplateRequest): Promise<TaskTemplate> => {
+    return await invoke("update_task_template", { id, request });
+  },
+
+  delete: async (id: string): Promise<void> => {
+    return await invoke("delete_task_template", { id });
+  },
+
+  /** Fails without creating anything when a placeholder has no value */
+  createTask: async (templateId: string, placeholderValues: Record<string, string>): Promise<Task> => {
+    return await invoke("create_task_from_template", { templateId, placeholderValues });
+  },
 };
 
 // Project API
@@ -73,6 +247,10 @@ export const projectApi = {
     return await invoke("update_project", { id, request });
   },
 
+  detectMainBranch: async (id: string): Promise<string> => {
+    return await invoke("detect_main_branch", { id });
+  },
+
   delete: async (id: string): Promise<void> => {
     return await invoke("delete_project", { id });
   },
@@ -80,6 +258,22 @@ export const projectApi = {
   refreshAllGitProviders: async (): Promise<Project[]> => {
     return await invoke("refresh_all_git_providers");
   },
+
+  exportData: async (projectId: string, path: string): Promise<void> => {
+    return await invoke("export_project_data", { projectId, path });
+  },
+
+  importData: async (path: string, targetProjectPath: string): Promise<ImportProjectResult> => {
+    return await invoke("import_project_data", { path, targetProjectPath });
+  },
+
+  exportJson: async (id: string): Promise<string> => {
+    return await invoke("export_project", { id });
+  },
+
+  importJson: async (json: string): Promise<ImportProjectResult> => {
+    return await invoke("import_project", { json });
+  },
 };
 
 // Process API
@@ -88,9 +282,37 @@ export const processApi = {
     return await invoke("get_process", { id });
   },
 
+  /** Up to `limit` lines of a process's output starting at line `offset` */
+  getOutput: async (
+    processId: string,
+    stream: OutputStreamType,
+    offset: number,
+    limit: number
+  ): Promise<ProcessOutputPage> => {
+    return await invoke("get_process_output", { processId, stream, offset, limit });
+  },
+
+  /** Everything a process wrote to stdout and stderr, in the order it was written */
+  getLog: async (processId: string): Promise<ProcessOutputLine[]> => {
+    return await invoke("get_process_log", { processId });
+  },
+
   listForAttempt: async (taskAttemptId: string): Promise<ExecutionProcess[]> => {
     return await invoke("list_processes_for_attempt", { taskAttemptId });
   },
+
+  runAttemptCommand: async (
+    attemptId: string,
+    command: string,
+    args: string[] = [],
+    envFiles?: string[]
+  ): Promise<string> => {
+    return await invoke("run_attempt_command", { attemptId, command, args, envFiles });
+  },
+
+  killAttemptProcess: async (attemptId: string, processId: string): Promise<void> => {
+    return await invoke("kill_attempt_process", { attemptId, processId });
+  },
 };
 
 // Git API
@@ -122,6 +344,14 @@ export const gitApi = {
     return await invoke("list_branches", { repoPath });
   },
 
+  getRemoteBranches: async (repoPath: string): Promise<RemoteBranch[]> => {
+    return await invoke("get_remote_branches", { repoPath });
+  },
+
+  fetchRemote: async (repoPath: string, remote?: string, prune?: boolean): Promise<FetchResult> => {
+    return await invoke("fetch_remote", { repoPath, remote, prune });
+  },
+
   getStatus: async (repoPath: string): Promise<GitStatus> => {
     return await invoke("get_git_status", { repoPath });
   },
@@ -130,6 +360,14 @@ export const gitApi = {
     return await invoke("stage_files", { repoPath, files });
   },
 
+  unstageFiles: async (repoPath: string, files: string[]): Promise<void> => {
+    return await invoke("unstage_files", { repoPath, files });
+  },
+
+  unstageAll: async (repoPath: string): Promise<void> => {
+    return await invoke("unstage_all", { repoPath });
+  },
+
   commit: async (repoPath: string, message: string): Promise<string> => {
     return await invoke("commit_changes", { repoPath, message });
   },
@@ -138,18 +376,24 @@ export const gitApi = {
     return await invoke("push_branch", { repoPath, branch, force });
   },
 
-  getDiff: async (repoPath: string, staged: boolean = false): Promise<string> => {
-    return await invoke("get_diff", { repoPath, staged });
+  /** `projectId` confines the diff to the project's subdirectory */
+  getDiff: async (repoPath: string, staged: boolean = false, projectId?: string): Promise<string> => {
+    return await invoke("get_diff", { repoPath, staged, projectId });
   },
 
-  listAllFiles: async (directoryPath: string): Promise<any[]> => {
-    return await invoke("list_all_files", { repoPath: directoryPath });
+  /** `projectId` confines the listing to the project's subdirectory */
+  listAllFiles: async (directoryPath: string, projectId?: string): Promise<any[]> => {
+    return await invoke("list_all_files", { repoPath: directoryPath, projectId });
   },
 
   readFileContent: async (repoPath: string, filePath: string): Promise<string> => {
     return await invoke("read_file_content", { repoPath: repoPath, filePath: filePath });
   },
 
+  getFileHistory: async (repoPath: string, filePath: string, maxCount?: number): Promise<FileCommit[]> => {
+    return await invoke("get_file_history", { repoPath, filePath, maxCount });
+  },
+
   getFileFromRef: async (repoPath: string, fileRef: string): Promise<string> => {
     return await invoke("get_file_from_ref", { repoPath: repoPath, fileRef: fileRef });
   },
@@ -161,9 +405,27 @@ export const mcpApi = {
     name: string,
     command: string,
     args: string[],
-    env: Record<string, string>
+    env: Record<string, string>,
+    autoRestart?: boolean,
+    healthCheckIntervalSeconds?: number,
+    requestTimeoutSeconds?: number,
+    logLines?: number
   ): Promise<string> => {
-    return await invoke("register_mcp_server", { name, command, args, env });
+    return await invoke("register_mcp_server", {
+      name,
+      command,
+      args,
+      env,
+      autoRestart,
+      healthCheckIntervalSeconds,
+      requestTimeoutSeconds,
+      logLines,
+    });
+  },
+
+  /** Register a server only this project's agent sessions use */
+  registerProjectServer: async (projectId: string, server: NewMcpServer): Promise<string> => {
+    return await invoke("register_project_mcp_server", { projectId, server });
   },
 
   startServer: async (serverId: string): Promise<void> => {
@@ -174,35 +436,49 @@ export const mcpApi = {
     return await invoke("stop_mcp_server", { serverId });
   },
 
-  listServers: async (): Promise<McpServer[]> => {
-    return await invoke("list_mcp_servers");
+  restartServer: async (serverId: string): Promise<void> => {
+    return await invoke("restart_mcp_server", { serverId });
+  },
+
+  /** Every server, or with a project the global ones and that project's own */
+  listServers: async (projectId?: string): Promise<McpServer[]> => {
+    return await invoke("list_mcp_servers", { projectId });
   },
 
   getServer: async (serverId: string): Promise<McpServer | null> => {
     return await invoke("get_mcp_server", { serverId });
   },
 
+  getServerLogs: async (serverId: string, tail: number = 500): Promise<string[]> => {
+    return await invoke("get_mcp_server_logs", { serverId, tail });
+  },
+
   sendRequest: async (
     serverId: string,
     method: string,
-    params?: any
-  ): Promise<string> => {
-    return await invoke("send_mcp_request", { serverId, method, params });
+    params?: any,
+    timeoutSeconds?: number
+  ): Promise<any> => {
+    return await invoke("send_mcp_request", { serverId, method, params, timeoutSeconds });
   },
 
-  listTools: async (serverId: string): Promise<string> => {
+  listTools: async (serverId: string): Promise<any> => {
     return await invoke("list_mcp_tools", { serverId });
   },
 
-  listResources: async (serverId: string): Promise<string> => {
+  callTool: async (serverId: string, toolName: string, args: any): Promise<any> => {
+    return await invoke("call_mcp_tool", { serverId, toolName, arguments: args });
+  },
+
+  listResources: async (serverId: string): Promise<any> => {
     return await invoke("list_mcp_resources", { serverId });
   },
 
-  readResource: async (serverId: string, uri: string): Promise<string> => {
+  readResource: async (serverId: string, uri: string): Promise<any> => {
     return await invoke("read_mcp_resource", { serverId, uri });
   },
 
-  listPrompts: async (serverId: string): Promise<string> => {
+  listPrompts: async (serverId: string): Promise<any> => {
     return await invoke("list_mcp_prompts", { serverId });
   },
 
@@ -210,9 +486,25 @@ export const mcpApi = {
     serverId: string,
     name: string,
     args: any
-  ): Promise<string> => {
+  ): Promise<any> => {
     return await invoke("get_mcp_prompt", { serverId, name, arguments: args });
   },
+
+  unregisterServer: async (serverId: string): Promise<void> => {
+    return await invoke("unregister_mcp_server", { serverId });
+  },
+
+  attachToProject: async (projectId: string, serverId: string): Promise<void> => {
+    return await invoke("attach_mcp_server_to_project", { projectId, serverId });
+  },
+
+  detachFromProject: async (projectId: string, serverId: string): Promise<void> => {
+    return await invoke("detach_mcp_server_from_project", { projectId, serverId });
+  },
+
+  listProjectServers: async (projectId: string): Promise<McpServer[]> => {
+    return await invoke("list_project_mcp_servers", { projectId });
+  },
 };
 
 // CLI API
@@ -241,6 +533,14 @@ export const loggingApi = {
     return await invoke("get_log_content", { lines });
   },
 
+  getLogEntries: async (levelFilter?: LogLevel, limit?: number, offset?: number): Promise<LogEntry[]> => {
+    return await invoke("get_log_entries", { levelFilter, limit, offset });
+  },
+
+  getLogEntryCount: async (levelFilter?: LogLevel): Promise<number> => {
+    return await invoke("get_log_entry_count", { levelFilter });
+  },
+
   getLogPath: async (): Promise<string> => {
     return await invoke("get_log_path");
   },
@@ -252,4 +552,94 @@ export const loggingApi = {
   clearLogs: async (): Promise<void> => {
     return await invoke("clear_logs");
   },
-};
\ No newline at end of file
+};
+
+// Config API
+export const configApi = {
+  getProxyConfig: async (): Promise<NetworkConfig> => {
+    return await invoke("get_proxy_config");
+  },
+
+  setProxyConfig: async (proxyUrl: string | null, noProxy: string[]): Promise<void> => {
+    return await invoke("set_proxy_config", { proxyUrl, noProxy });
+  },
+
+  getWindowConfig: async (): Promise<WindowConfig> => {
+    return await invoke("get_window_config");
+  },
+
+  updateWindowConfig: async (config: WindowConfig): Promise<void> => {
+    return await invoke("update_window_config", { config });
+  },
+
+  getBackupConfig: async (): Promise<BackupConfig> => {
+    return await invoke("get_backup_config");
+  },
+
+  updateBackupConfig: async (config: BackupConfig): Promise<void> => {
+    return await invoke("update_backup_config", { config });
+  },
+
+  getDevServerConfig: async (): Promise<DevServerConfig> => {
+    return await invoke("get_dev_server_config");
+  },
+
+  updateDevServerConfig: async (config: DevServerConfig): Promise<void> => {
+    return await invoke("update_dev_server_config", { config });
+  },
+
+  getStorageConfig: async (): Promise<StorageConfig> => {
+    return await invoke("get_storage_config");
+  },
+
+  updateStorageConfig: async (config: StorageConfig): Promise<void> => {
+    return await invoke("update_storage_config", { config });
+  },
+
+  getFileWatcherConfig: async (): Promise<FileWatcherConfig> => {
+    return await invoke("get_file_watcher_config");
+  },
+
+  updateFileWatcherConfig: async (config: FileWatcherConfig): Promise<void> => {
+    return await invoke("update_file_watcher_config", { config });
+  },
+
+  getNotificationConfig: async (): Promise<NotificationConfig> => {
+    return await invoke("get_notification_config");
+  },
+
+  updateNotificationConfig: async (config: NotificationConfig): Promise<void> => {
+    return await invoke("update_notification_config", { config });
+  },
+
+  getGitHostsConfig: async (): Promise<GitHostsConfig> => {
+    return await invoke("get_git_hosts_config");
+  },
+
+  updateGitHostsConfig: async (config: GitHostsConfig): Promise<void> => {
+    return await invoke("update_git_hosts_config", { config });
+  },
+};
+
+// Database backup API
+export const databaseApi = {
+  listBackups: async (): Promise<DatabaseBackup[]> => {
+    return await invoke("list_database_backups");
+  },
+
+  createBackup: async (): Promise<DatabaseBackup> => {
+    return await invoke("create_database_backup");
+  },
+
+  // The app restarts once the backup is in place. If it can't be put in place
+  // the error says whether the current database was kept; the app has to be
+  // restarted either way.
+  restoreBackup: async (backupId: string): Promise<void> => {
+    return await invoke("restore_database_backup", { backupId });
+  },
+
+  // The app quits afterwards so the migration isn't applied again straight away
+  rollbackLastMigration: async (): Promise<RolledBackMigration> => {
+    return await invoke("rollback_last_migration");
+  },
+};
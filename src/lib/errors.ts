@@ -0,0 +1,42 @@
+/** Error returned by migrated backend commands (see `src-tauri/src/error.rs`) */
+export interface PivoError {
+  code:
+    | "not_found"
+    | "invalid_input"
+    | "vcs_not_configured"
+    | "vcs_auth"
+    | "rate_limited"
+    | "network"
+    | "ssh_host_key"
+    | "ssh_auth"
+    | "git_command_failed"
+    | "not_git_repository"
+    | "branch_not_pushed"
+    | "branch_stale"
+    | "agent_not_installed"
+    | "database_error"
+    | "io"
+    | "other";
+  message: string;
+  details?: string;
+  /** Set for `branch_not_pushed` and `branch_stale` */
+  branch?: string;
+  commitsAhead?: number;
+  commitsBehind?: number;
+}
+
+export function isPivoError(error: unknown): error is PivoError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as PivoError).code === "string" &&
+    typeof (error as PivoError).message === "string"
+  );
+}
+
+/** Readable message for errors thrown by `invoke`, whether a plain string or a `PivoError` */
+export function errorMessage(error: unknown): string {
+  if (isPivoError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
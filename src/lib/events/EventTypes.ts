@@ -3,7 +3,7 @@
  * All events flowing through the system should be defined here
  */
 
-import { Task, Project, TaskAttempt, McpServer } from '@/types';
+import { Task, Project, TaskAttempt, McpServer, PendingMessage, ReviewComment, PathUsage } from '@/types';
 import { UnifiedMessage } from '@/types/execution';
 
 /**
@@ -18,6 +18,25 @@ export interface AppEvents {
     newStatus: string;
     task: Task;
   };
+  'task:bulk-status-changed': {
+    projectId: string;
+    taskIds: string[];
+    previousStatuses: Record<string, string>;
+    newStatus: string;
+    tasks: Task[];
+  };
+  'navigate-to-task': {
+    projectId: string;
+    taskId: string;
+  };
+  'task:moved': {
+    taskId: string;
+    previousProjectId: string;
+    newProjectId: string;
+    task: Task;
+    // Worktrees that stay in the previous project's repository
+    leftBehindWorktrees: string[];
+  };
   'task:attempt-created': {
     taskId: string;
     attempt: TaskAttempt;
@@ -32,6 +51,11 @@ export interface AppEvents {
   'project-selected': { projectId: string };
   'project-created': { project: Project };
   'project-updated': { project: Project };
+  'vcs:project-sync-completed': {
+    projectId: string;
+    provider: 'gitlab' | 'github';
+    updatedCount: number;
+  };
   // Execution lifecycle events (RFC redesign)
   'execution:started': {
     taskId: string;
@@ -44,6 +68,52 @@ export interface AppEvents {
     executionId: string;
     status: 'success' | 'failed' | 'cancelled';
   };
+  'attempt:committed': {
+    taskId: string;
+    attemptId: string;
+    commitHash: string;
+    message: string;
+  };
+  /** Open comments whose line the agent changed, now marked resolved */
+  'review:comments-resolved': {
+    taskId: string;
+    attemptId: string;
+    comments: ReviewComment[];
+  };
+  'attempt:setup-started': {
+    taskId: string;
+    attemptId: string;
+    processId: string;
+    command: string;
+  };
+  'attempt:setup-completed': {
+    attemptId: string;
+    processId?: string;
+    success: boolean;
+    exitCode?: number;
+    skipped?: boolean;
+    error?: string;
+    /** Last lines of a failed setup script's output */
+    output?: string[];
+  };
+  'execution:paused': {
+    taskId: string;
+    attemptId: string;
+    executionId: string;
+  };
+  'execution:resumed': {
+    taskId: string;
+    attemptId: string;
+    executionId: string;
+  };
+  'bulk-execution-progress': {
+    bulkId: string;
+    taskId: string | null;
+    status: 'started' | 'completed' | 'skipped' | 'error' | 'finished';
+    error: string | null;
+    running: number;
+    queued: number;
+  };
   
   // Message events (RFC redesign)
   'message:added': {
@@ -52,6 +122,19 @@ export interface AppEvents {
     message: UnifiedMessage;
   };
   
+  'message:queued': {
+    taskId: string;
+    attemptId: string;
+    pendingMessage: PendingMessage;
+  };
+  'message:dequeued': {
+    taskId: string;
+    attemptId: string;
+    pendingMessageId: string;
+    /** A failed message stays first in the queue and is retried with the next dispatch */
+    reason: 'dispatched' | 'cancelled' | 'failed';
+  };
+  
   // Session events
   'session:received': {
     attemptId: string;
@@ -86,6 +169,16 @@ export interface AppEvents {
   
   // MCP events
   'mcp-server-status': McpServer;
+  'mcp-notification': {
+    server_id: string;
+    method: string;
+    params: any;
+  };
+  'mcp-server-status-changed': {
+    server_id: string;
+    previous_status: McpServer['status'];
+    status: McpServer['status'];
+  };
   
   // Terminal events
   'terminal-output': {
@@ -94,21 +187,55 @@ export interface AppEvents {
   };
   
   // File events
-  'file-change': {
+  /** Files changed in a watched worktree over one debounce window */
+  'worktree-files-changed': {
     worktree_path: string;
-    file_path: string;
-    kind: string;
+    changes: { file_path: string; kind: string }[];
   };
   
   // Dev server events
   'dev-server-output': {
+    project_id: string;
     process_id: string;
     type: string;
     data: string;
   };
   'dev-server-stopped': {
+    project_id: string;
+    process_id?: string;
+    name?: string;
+    task_attempt_id?: string | null;
+    exit_code?: number | null;
+  };
+  'dev-server-ready': {
+    project_id: string;
+    process_id: string;
+    url: string;
+    port: number | null;
+  };
+  'dev-server-restarted': {
+    project_id: string;
     process_id: string;
-    exit_code?: number;
+    pid: number;
+    exit_code: number | null;
+    restarts: number;
+  };
+  'dev-server-crash-loop': {
+    project_id: string;
+    process_id: string;
+    name: string;
+    exit_code: number | null;
+    restarts: number;
+  };
+  'worktree-size-warning': {
+    attemptId: string;
+    taskId: string;
+    taskTitle: string;
+    projectId: string;
+    worktreePath: string;
+    bytes: number;
+    limitBytes: number;
+    largestPaths: PathUsage[];
   };
 }
 
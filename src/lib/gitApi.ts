@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { BlameLine, FetchResult, FileCommit, RemoteBranch } from "@/types";
 
 // Git diff types
 export interface DiffMode {
@@ -15,6 +16,8 @@ export interface DiffResult {
   files: FileDiff[];
   stats: DiffStats;
   hasConflicts: boolean;
+  /** Files that would conflict, for merge previews */
+  conflictedFiles: string[];
   largeFiles: string[];
 }
 
@@ -49,41 +52,88 @@ export interface DiffStats {
   deletions: number;
 }
 
+export interface FileStat {
+  path: string;
+  additions: number;
+  deletions: number;
+  status: FileDiff['status'];
+}
+
+export interface DiffStatSummary {
+  filesChanged: number;
+  insertions: number;
+  deletions: number;
+  files: FileStat[];
+}
+
 export interface RebaseStatus {
   needsRebase: boolean;
   commitsBehind: number;
   commitsAhead: number;
   canFastForward: boolean;
   hasConflicts: boolean;
+  /** Files that would conflict when bringing in the base branch */
+  conflictedFiles: string[];
+}
+
+export interface FileDiffDetail {
+  path: string;
+  oldPath?: string;
+  /** Missing when the file doesn't exist on that side or is binary */
+  oldContent?: string;
+  newContent?: string;
+  hunks: DiffChunk[];
+  binary: boolean;
+  /** Content was cut for a large file; hunks still cover the whole file */
+  truncated: boolean;
+}
+
+export type MergeMethod = 'fast-forward' | 'no-ff' | 'squash';
+
+export type MergeResult =
+  | { status: 'merged'; commit: string }
+  | { status: 'conflicts'; files: string[] };
+
+// Convert the mode to the format expected by the backend
+function toBackendMode(mode: DiffMode): any {
+  switch (mode.type) {
+    case 'workingDirectory':
+      return 'WorkingDirectory';
+    case 'branchChanges':
+      return { BranchChanges: { base_commit: mode.baseCommit } };
+    case 'againstRemote':
+      return { AgainstRemote: { remote_branch: mode.remoteBranch } };
+    case 'commitRange':
+      return { CommitRange: { from: mode.from, to: mode.to } };
+    case 'mergePreview':
+      return { MergePreview: { target_branch: mode.targetBranch } };
+  }
 }
 
 // Git API functions
 export const gitApi = {
   // Enhanced diff functions
-  getDiff: async (worktreePath: string, mode: DiffMode): Promise<DiffResult> => {
-    // Convert the mode to the format expected by the backend
-    let backendMode: any;
-    switch (mode.type) {
-      case 'workingDirectory':
-        backendMode = 'WorkingDirectory';
-        break;
-      case 'branchChanges':
-        backendMode = { BranchChanges: { base_commit: mode.baseCommit } };
-        break;
-      case 'againstRemote':
-        backendMode = { AgainstRemote: { remote_branch: mode.remoteBranch } };
-        break;
-      case 'commitRange':
-        backendMode = { CommitRange: { from: mode.from, to: mode.to } };
-        break;
-      case 'mergePreview':
-        backendMode = { MergePreview: { target_branch: mode.targetBranch } };
-        break;
-    }
-    
+  /** `projectId` confines the diff to the project's subdirectory */
+  getDiff: async (worktreePath: string, mode: DiffMode, projectId?: string): Promise<DiffResult> => {
     return invoke<DiffResult>("get_git_diff", { 
       worktreePath,
-      mode: backendMode
+      mode: toBackendMode(mode),
+      projectId,
+    });
+  },
+
+  // Both sides of one file of a diff, for side-by-side review
+  getFileDiffDetail: async (
+    worktreePath: string,
+    filePath: string,
+    mode: DiffMode,
+    oldPath?: string
+  ): Promise<FileDiffDetail> => {
+    return invoke<FileDiffDetail>("get_file_diff_detail", {
+      worktreePath,
+      filePath,
+      oldPath,
+      mode: toBackendMode(mode)
     });
   },
 
@@ -94,6 +144,21 @@ export const gitApi = {
     });
   },
 
+  compareAttempts: async (attemptIdA: string, attemptIdB: string): Promise<DiffResult> => {
+    return invoke<DiffResult>("compare_attempts", {
+      attemptIdA,
+      attemptIdB
+    });
+  },
+
+  integrateAttempt: async (attemptId: string, method: MergeMethod, push = false): Promise<MergeResult> => {
+    return invoke<MergeResult>("integrate_attempt", {
+      attemptId,
+      method,
+      push
+    });
+  },
+
   getBranchCommit: async (repoPath: string, branch: string): Promise<string> => {
     return invoke<string>("get_branch_commit", { 
       repoPath,
@@ -125,6 +190,16 @@ export const gitApi = {
     return invoke<string[]>("list_branches", { repoPath });
   },
 
+  /** Remote-tracking branches as of the last fetch, without fetching */
+  getRemoteBranches: async (repoPath: string): Promise<RemoteBranch[]> => {
+    return invoke<RemoteBranch[]>("get_remote_branches", { repoPath });
+  },
+
+  /** Fetch `remote`, or all remotes, and list the remote branches that changed */
+  fetchRemote: async (repoPath: string, remote?: string, prune?: boolean): Promise<FetchResult> => {
+    return invoke<FetchResult>("fetch_remote", { repoPath, remote, prune });
+  },
+
   getStatus: async (repoPath: string): Promise<{
     modified: string[];
     added: string[];
@@ -145,20 +220,62 @@ export const gitApi = {
     return invoke("stage_files", { repoPath, files });
   },
 
+  unstageFiles: async (repoPath: string, files: string[]): Promise<void> => {
+    return invoke("unstage_files", { repoPath, files });
+  },
+
+  unstageAll: async (repoPath: string): Promise<void> => {
+    return invoke("unstage_all", { repoPath });
+  },
+
   commit: async (repoPath: string, message: string): Promise<string> => {
     return invoke<string>("commit_changes", { repoPath, message });
   },
 
+  /** Undo one commit; returns the revert commit's hash, or "" with `noCommit` */
+  revertCommit: async (
+    repoPath: string,
+    commitSha: string,
+    noCommit: boolean = false,
+    mainline?: number
+  ): Promise<string> => {
+    return invoke<string>("revert_commit", { repoPath, commitSha, noCommit, mainline });
+  },
+
+  /** Undo every commit after `commitSha` as a single commit */
+  revertToCommit: async (repoPath: string, commitSha: string): Promise<string> => {
+    return invoke<string>("revert_to_commit", { repoPath, commitSha });
+  },
+
+  /** Squash every commit after `baseCommit` into one; returns its hash */
+  squashCommits: async (repoPath: string, baseCommit: string, message: string): Promise<string> => {
+    return invoke<string>("squash_commits", { repoPath, baseCommit, message });
+  },
+
+  /** The `git rebase -i` todo list that squashes every commit after `baseCommit` into the first */
+  prepareInteractiveRebase: async (repoPath: string, baseCommit: string): Promise<string> => {
+    return invoke<string>("prepare_interactive_rebase", { repoPath, baseCommit });
+  },
+
   push: async (repoPath: string, branch: string, force: boolean = false): Promise<void> => {
     return invoke("push_branch", { repoPath, branch, force });
   },
 
-  getSimpleDiff: async (repoPath: string, staged: boolean = false): Promise<string> => {
-    return invoke<string>("get_diff", { repoPath, staged });
+  getSimpleDiff: async (repoPath: string, staged: boolean = false, projectId?: string): Promise<string> => {
+    return invoke<string>("get_diff", { repoPath, staged, projectId });
   },
 
-  listAllFiles: async (repoPath: string): Promise<string[]> => {
-    return invoke<string[]>("list_all_files", { repoPath });
+  getDiffStat: async (
+    repoPath: string,
+    baseRef?: string,
+    staged: boolean = false,
+    projectId?: string
+  ): Promise<DiffStatSummary> => {
+    return invoke<DiffStatSummary>("get_diff_stat", { repoPath, baseRef, staged, projectId });
+  },
+
+  listAllFiles: async (repoPath: string, projectId?: string): Promise<string[]> => {
+    return invoke<string[]>("list_all_files", { repoPath, projectId });
   },
 
   readFileContent: async (repoPath: string, filePath: string): Promise<string> => {
@@ -167,5 +284,14 @@ export const gitApi = {
 
   getFileFromRef: async (repoPath: string, fileRef: string): Promise<string> => {
     return invoke<string>("get_file_from_ref", { repoPath, fileRef });
+  },
+
+  getFileHistory: async (repoPath: string, filePath: string, maxCount?: number): Promise<FileCommit[]> => {
+    return invoke<FileCommit[]>("get_file_history", { repoPath, filePath, maxCount });
+  },
+
+  // Blame a file of an attempt's worktree, flagging the lines the attempt wrote
+  getFileBlame: async (attemptId: string, filePath: string): Promise<BlameLine[]> => {
+    return invoke<BlameLine[]>("get_file_blame", { attemptId, filePath });
   }
 };
\ No newline at end of file
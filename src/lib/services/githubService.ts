@@ -1,10 +1,28 @@
 import { invoke } from '@tauri-apps/api/core';
 import type { MergeRequestInfo } from './gitlabService';
+import type { BranchStatus } from '@/lib/types/mergeRequest';
+import type { PushMode } from '@/types';
 
 export interface GitHubConfig {
   accessToken?: string;
   username?: string;
   defaultBranch?: string;
+  maxPages?: number;
+  enterpriseHost?: string;
+  pushMode?: PushMode;
+}
+
+export interface RepositoryInfo {
+  name: string;
+  /** `owner/name` */
+  fullName: string;
+  description?: string | null;
+  defaultBranch: string;
+  isPrivate: boolean;
+  isFork: boolean;
+  cloneUrl: string;
+  sshUrl: string;
+  openIssuesCount: number;
 }
 
 export interface CreatePullRequestParams {
@@ -14,6 +32,8 @@ export interface CreatePullRequestParams {
   description: string;
   sourceBranch: string;
   targetBranch: string;
+  /** Push the source branch first when it is missing or behind on the remote */
+  autoPush?: boolean;
 }
 
 class GitHubService {
@@ -37,7 +57,7 @@ class GitHubService {
 
   async createPullRequest(params: CreatePullRequestParams): Promise<MergeRequestInfo> {
     try {
-      const { taskAttemptId, remoteUrl, title, description, sourceBranch, targetBranch } = params;
+      const { taskAttemptId, remoteUrl, title, description, sourceBranch, targetBranch, autoPush = false } = params;
       return await invoke<MergeRequestInfo>('create_github_pr', {
         taskAttemptId,
         remoteUrl,
@@ -45,6 +65,7 @@ class GitHubService {
         description,
         sourceBranch,
         targetBranch,
+        autoPush,
       });
     } catch (error) {
       console.error('Failed to create GitHub PR:', error);
@@ -69,6 +90,24 @@ class GitHubService {
     }
   }
 
+  async listRepoBranches(remoteUrl: string): Promise<BranchStatus[]> {
+    try {
+      return await invoke<BranchStatus[]>('list_repo_branches', { remoteUrl });
+    } catch (error) {
+      console.error('Failed to list GitHub branches:', error);
+      throw error;
+    }
+  }
+
+  async getRepositoryInfo(remoteUrl: string): Promise<RepositoryInfo> {
+    try {
+      return await invoke<RepositoryInfo>('get_repository_info', { remoteUrl });
+    } catch (error) {
+      console.error('Failed to get GitHub repository info:', error);
+      throw error;
+    }
+  }
+
   async pushToGitHub(repoPath: string, branch: string, force: boolean = false): Promise<void> {
     try {
       await invoke('push_to_github', { repoPath, branch, force });
@@ -123,6 +162,15 @@ class GitHubService {
       throw error;
     }
   }
+
+  async syncProjectPullRequests(projectId: string): Promise<MergeRequestInfo[]> {
+    try {
+      return await invoke<MergeRequestInfo[]>('sync_project_pull_requests', { projectId });
+    } catch (error) {
+      console.error('Failed to sync project pull requests:', error);
+      throw error;
+    }
+  }
 }
 
 export const githubService = new GitHubService();
\ No newline at end of file
@@ -1,21 +1,35 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { MergeRequest, MergeRequestInfo } from '../types/mergeRequest';
+import type { ApprovalState, MergeRequest, MergeRequestDraft, MergeRequestInfo } from '../types/mergeRequest';
+import type { PushMode } from '@/types';
 
-export type { MergeRequestInfo } from '../types/mergeRequest';
+export type { ApprovalState, MergeRequestInfo } from '../types/mergeRequest';
 
 export interface GitLabConfig {
   pat?: string;
   gitlabUrl?: string;
   defaultBranch?: string;
+  username?: string;
+  primary_email?: string;
+  push_mode?: PushMode;
 }
 
+export type GitLabConnectionTest =
+  | { status: 'ok'; username: string; email?: string | null }
+  | { status: 'invalid_url'; message: string }
+  | { status: 'invalid_token'; message: string }
+  | { status: 'network_error'; message: string };
+
 export const gitlabService = {
   async getConfig(): Promise<GitLabConfig | null> {
     return invoke('get_gitlab_config');
   },
 
-  async updateConfig(config: GitLabConfig): Promise<void> {
-    return invoke('update_gitlab_config', { config });
+  async updateConfig(config: GitLabConfig, validate?: boolean): Promise<void> {
+    return invoke('update_gitlab_config', { config, validate });
+  },
+
+  async testConnection(config?: GitLabConfig): Promise<GitLabConnectionTest> {
+    return invoke('test_gitlab_connection', { config });
   },
 
   async createMergeRequest(
@@ -24,7 +38,8 @@ export const gitlabService = {
     title: string,
     description: string,
     sourceBranch: string,
-    targetBranch: string
+    targetBranch: string,
+    autoPush: boolean = false
   ): Promise<MergeRequestInfo> {
     return invoke('create_gitlab_mr', {
       taskAttemptId,
@@ -33,6 +48,7 @@ export const gitlabService = {
       description,
       sourceBranch,
       targetBranch,
+      autoPush,
     });
   },
 
@@ -48,6 +64,42 @@ export const gitlabService = {
     });
   },
 
+  async approveMergeRequest(
+    taskAttemptId: string,
+    remoteUrl: string,
+    mrNumber: number
+  ): Promise<ApprovalState> {
+    return invoke('approve_gitlab_mr', {
+      taskAttemptId,
+      remoteUrl,
+      mrNumber,
+    });
+  },
+
+  async unapproveMergeRequest(
+    taskAttemptId: string,
+    remoteUrl: string,
+    mrNumber: number
+  ): Promise<ApprovalState> {
+    return invoke('unapprove_gitlab_mr', {
+      taskAttemptId,
+      remoteUrl,
+      mrNumber,
+    });
+  },
+
+  async getApprovalState(
+    taskAttemptId: string,
+    remoteUrl: string,
+    mrNumber: number
+  ): Promise<ApprovalState> {
+    return invoke('get_gitlab_mr_approvals', {
+      taskAttemptId,
+      remoteUrl,
+      mrNumber,
+    });
+  },
+
   async pushToGitLab(
     repoPath: string,
     branch: string,
@@ -75,4 +127,13 @@ export const gitlabService = {
   async getActiveMergeRequests(provider?: string): Promise<MergeRequest[]> {
     return invoke('get_active_merge_requests', { provider });
   },
+
+  async syncProjectMergeRequests(projectId: string): Promise<MergeRequest[]> {
+    return invoke('sync_project_merge_requests', { projectId });
+  },
+
+  /** Title and description suggested for a new MR/PR of the attempt, for either provider */
+  async generateMergeRequestContent(taskAttemptId: string, polish: boolean = false): Promise<MergeRequestDraft> {
+    return invoke('generate_merge_request_content', { taskAttemptId, polish });
+  },
 };
\ No newline at end of file
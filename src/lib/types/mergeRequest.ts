@@ -15,6 +15,9 @@ export interface MergeRequest {
   hasConflicts: boolean;
   pipelineStatus?: string;
   pipelineUrl?: string;
+  pipelineJobs: PipelineJob[];
+  /** Approvals given so far; only tracked for GitLab */
+  approvalCount: number;
   createdAt: string;
   updatedAt: string;
   mergedAt?: string;
@@ -34,10 +37,53 @@ export interface MergeRequestInfo {
   mergeStatus?: MergeStatus;
   hasConflicts: boolean;
   pipelineStatus?: PipelineStatus;
+  pipelineUrl?: string;
+  pipelineJobs: PipelineJob[];
   createdAt: string;
   updatedAt: string;
 }
 
+export interface ApprovalState {
+  approved: boolean;
+  /** Usernames of the approvers */
+  approvedBy: string[];
+  approvalsRequired: number;
+  approvalsLeft: number;
+}
+
+export interface PipelineJob {
+  name: string;
+  status?: PipelineStatus;
+  conclusion?: string;
+  url?: string;
+}
+
+export interface AttemptPipelineStatus {
+  merge_request_id: number;
+  status?: PipelineStatus;
+  url?: string;
+  jobs: PipelineJob[];
+}
+
+export interface MergeRequestDraft {
+  title: string;
+  /** Markdown */
+  description: string;
+  commit_count: number;
+  /** Whether the coding agent rewrote the generated description */
+  polished: boolean;
+}
+
+export interface BranchStatus {
+  name: string;
+  commitSha: string;
+  pipelineStatus?: PipelineStatus;
+  isDefault: boolean;
+  isProtected: boolean;
+  aheadBy?: number;
+  behindBy?: number;
+}
+
 export enum MergeRequestState {
   Opened = 'opened',
   Closed = 'closed',
@@ -8,6 +8,16 @@ export interface FileSearchResult {
   is_directory: boolean;
 }
 
+export interface DirNode {
+  name: string;
+  path: string;
+  is_dir: boolean;
+  /** Missing for files and for directories below the requested depth */
+  children: DirNode[] | null;
+  size: number | null;
+  modified: string | null;
+}
+
 export class FileSystemApi {
   static async searchProjectFiles(
     projectPath: string,
@@ -32,4 +42,20 @@ export class FileSystemApi {
       maxResults,
     });
   }
-}
\ No newline at end of file
+
+  /**
+   * Directory tree for a file explorer. `.git`, `node_modules`, `target` and
+   * `.gitignore`d entries are always left out; `ignorePatterns` use gitignore syntax.
+   */
+  static async readProjectTree(
+    rootPath: string,
+    maxDepth: number,
+    ignorePatterns?: string[]
+  ): Promise<DirNode> {
+    return invoke<DirNode>("read_project_tree", {
+      rootPath,
+      maxDepth,
+      ignorePatterns,
+    });
+  }
+}
@@ -4,7 +4,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { gitApi as originalGitApi } from '@/lib/api';
-import { GitStatus } from '@/types';
+import { FetchResult, FileCommit, GitStatus, RemoteBranch } from '@/types';
 
 export class GitApi {
   private api = originalGitApi;
@@ -51,6 +51,14 @@ export class GitApi {
     return this.api.push(path, branch, force);
   }
   
+  /**
+   * Initialise a repository with an initial commit. The .gitignore template
+   * ("node", "rust", "python", "none", ...) defaults to the detected project type
+   */
+  async initRepository(path: string, initialBranch: string, gitignoreTemplate?: string): Promise<void> {
+    return invoke('init_repository', { path, initialBranch, gitignoreTemplate });
+  }
+  
   /**
    * Pull changes
    */
@@ -67,24 +75,44 @@ export class GitApi {
     return this.api.listBranches(repoPath);
   }
   
-  async getDiff(repoPath: string, staged: boolean = false): Promise<string> {
-    return this.api.getDiff(repoPath, staged);
+  async getRemoteBranches(repoPath: string): Promise<RemoteBranch[]> {
+    return this.api.getRemoteBranches(repoPath);
+  }
+  
+  async fetchRemote(repoPath: string, remote?: string, prune?: boolean): Promise<FetchResult> {
+    return this.api.fetchRemote(repoPath, remote, prune);
+  }
+  
+  async getDiff(repoPath: string, staged: boolean = false, projectId?: string): Promise<string> {
+    return this.api.getDiff(repoPath, staged, projectId);
   }
   
   async stageFiles(repoPath: string, files: string[]): Promise<void> {
     return this.api.stageFiles(repoPath, files);
   }
   
+  async unstageFiles(repoPath: string, files: string[]): Promise<void> {
+    return this.api.unstageFiles(repoPath, files);
+  }
+  
+  async unstageAll(repoPath: string): Promise<void> {
+    return this.api.unstageAll(repoPath);
+  }
+  
   async readFileContent(repoPath: string, filePath: string): Promise<string> {
     return this.api.readFileContent(repoPath, filePath);
   }
   
+  async getFileHistory(repoPath: string, filePath: string, maxCount?: number): Promise<FileCommit[]> {
+    return this.api.getFileHistory(repoPath, filePath, maxCount);
+  }
+  
   async getFileFromRef(repoPath: string, fileRef: string): Promise<string> {
     return this.api.getFileFromRef(repoPath, fileRef);
   }
   
-  async listAllFiles(directoryPath: string): Promise<any[]> {
-    return this.api.listAllFiles(directoryPath);
+  async listAllFiles(directoryPath: string, projectId?: string): Promise<any[]> {
+    return this.api.listAllFiles(directoryPath, projectId);
   }
 }
 
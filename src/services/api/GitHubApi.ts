@@ -3,13 +3,18 @@
  */
 
 import { githubService } from '@/lib/services/githubService';
+import type { RepositoryInfo } from '@/lib/services/githubService';
 import { logger } from '@/lib/logger';
 import type { MergeRequestInfo } from '@/lib/services/gitlabService';
+import type { PushMode } from '@/types';
+
+export type { RepositoryInfo } from '@/lib/services/githubService';
 
 export interface GitHubConfig {
   accessToken?: string;
   username?: string;
   defaultBranch?: string;
+  pushMode?: PushMode;
 }
 
 export interface CreatePullRequestParams {
@@ -19,6 +24,8 @@ export interface CreatePullRequestParams {
   description: string;
   sourceBranch: string;
   targetBranch: string;
+  /** Push the source branch first when it is missing or behind on the remote */
+  autoPush?: boolean;
 }
 
 export interface DeviceFlowResponse {
@@ -54,6 +61,14 @@ export class GitHubApi {
     return this.api.updateConfig(config);
   }
   
+  /**
+   * Get metadata of the repository behind a GitHub remote
+   */
+  async getRepositoryInfo(remoteUrl: string): Promise<RepositoryInfo> {
+    logger.debug('Getting GitHub repository info', { remoteUrl });
+    return this.api.getRepositoryInfo(remoteUrl);
+  }
+  
   /**
    * Create a pull request
    */
@@ -137,6 +152,14 @@ export class GitHubApi {
     logger.debug('Getting pull requests by task', { taskId });
     return this.api.getPullRequestsByTask(taskId);
   }
+  
+  /**
+   * Refresh all open pull requests of a project from GitHub
+   */
+  async syncProjectPullRequests(projectId: string): Promise<MergeRequestInfo[]> {
+    logger.debug('Syncing project pull requests', { projectId });
+    return this.api.syncProjectPullRequests(projectId);
+  }
 }
 
 // Export singleton instance
@@ -3,13 +3,20 @@
  */
 
 import { gitlabService } from '@/lib/services/gitlabService';
+import type { GitLabConnectionTest } from '@/lib/services/gitlabService';
 import { logger } from '@/lib/logger';
 import type { MergeRequest, MergeRequestInfo } from '@/lib/types/mergeRequest';
+import type { PushMode } from '@/types';
+
+export type { GitLabConnectionTest } from '@/lib/services/gitlabService';
 
 export interface GitLabConfig {
   pat?: string;
   gitlabUrl?: string;
   defaultBranch?: string;
+  username?: string;
+  primary_email?: string;
+  push_mode?: PushMode;
 }
 
 export interface CreateMergeRequestParams {
@@ -19,6 +26,8 @@ export interface CreateMergeRequestParams {
   description: string;
   sourceBranch: string;
   targetBranch: string;
+  /** Push the source branch first when it is missing or behind on the remote */
+  autoPush?: boolean;
 }
 
 export class GitLabApi {
@@ -35,9 +44,17 @@ export class GitLabApi {
   /**
    * Update GitLab configuration
    */
-  async updateConfig(config: GitLabConfig): Promise<void> {
-    logger.info('Updating GitLab config');
-    return this.api.updateConfig(config);
+  async updateConfig(config: GitLabConfig, validate?: boolean): Promise<void> {
+    logger.info('Updating GitLab config', { validate });
+    return this.api.updateConfig(config, validate);
+  }
+  
+  /**
+   * Check a GitLab URL and token without saving them (saved settings when omitted)
+   */
+  async testConnection(config?: GitLabConfig): Promise<GitLabConnectionTest> {
+    logger.debug('Testing GitLab connection');
+    return this.api.testConnection(config);
   }
   
   /**
@@ -56,7 +73,8 @@ export class GitLabApi {
       params.title,
       params.description,
       params.sourceBranch,
-      params.targetBranch
+      params.targetBranch,
+      params.autoPush
     );
   }
   
@@ -148,6 +166,14 @@ export class GitLabApi {
     return this.api.getActiveMergeRequests(provider);
   }
   
+  /**
+   * Refresh all open merge requests of a project from GitLab
+   */
+  async syncProjectMergeRequests(projectId: string): Promise<MergeRequest[]> {
+    logger.debug('Syncing project merge requests', { projectId });
+    return this.api.syncProjectMergeRequests(projectId);
+  }
+  
   /**
    * Check if authenticated
    */
@@ -4,6 +4,7 @@
 
 import { loggingApi as originalLoggingApi } from '@/lib/api';
 import { logger } from '@/lib/logger';
+import type { LogEntry, LogLevel } from '@/types';
 
 export class LoggingApi {
   private api = originalLoggingApi;
@@ -14,6 +15,20 @@ export class LoggingApi {
   async getLogContent(lines?: number): Promise<string> {
     return this.api.getLogContent(lines);
   }
+
+  /**
+   * Get parsed log entries, newest first
+   */
+  async getLogEntries(levelFilter?: LogLevel, limit?: number, offset?: number): Promise<LogEntry[]> {
+    return this.api.getLogEntries(levelFilter, limit, offset);
+  }
+
+  /**
+   * Get the number of log entries matching a level filter
+   */
+  async getLogEntryCount(levelFilter?: LogLevel): Promise<number> {
+    return this.api.getLogEntryCount(levelFilter);
+  }
   
   /**
    * Get the path to the log file
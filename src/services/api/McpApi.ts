@@ -57,6 +57,13 @@ export class McpApi {
     return this.api.getServer(serverId);
   }
   
+  /**
+   * Get the last lines an MCP server wrote to stderr
+   */
+  async getServerLogs(serverId: string, tail?: number): Promise<string[]> {
+    return this.api.getServerLogs(serverId, tail);
+  }
+  
   /**
    * Send a request to an MCP server
    */
@@ -17,6 +17,7 @@ export interface ProjectInfo {
   main_branch?: string;
   setup_script?: string;
   dev_script?: string;
+  subdirectory?: string;
   has_git: boolean;
   has_package_json: boolean;
 }
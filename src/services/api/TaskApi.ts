@@ -19,6 +19,20 @@ export class TaskApi {
     return invoke<Task>('create_task', { request });
   }
   
+  /**
+   * Create a subtask branching off the parent's current branch
+   */
+  async createSubtask(parentId: string, request: CreateTaskRequest): Promise<Task> {
+    return invoke<Task>('create_subtask', { parentId, request });
+  }
+  
+  /**
+   * List the direct subtasks of a task
+   */
+  async listSubtasks(parentId: string): Promise<Task[]> {
+    return invoke<Task[]>('list_subtasks', { parentId });
+  }
+  
   /**
    * Get a task by ID
    */
@@ -53,6 +67,20 @@ export class TaskApi {
   async delete(id: string): Promise<void> {
     return invoke<void>('delete_task', { id });
   }
+
+  /**
+   * Restore a deleted task that hasn't been purged yet
+   */
+  async restore(taskId: string): Promise<Task> {
+    return invoke<Task>('restore_task', { taskId });
+  }
+  
+  /**
+   * Permanently remove tasks deleted more than the given number of days ago
+   */
+  async purgeDeleted(olderThanDays: number): Promise<number> {
+    return invoke<number>('purge_deleted_tasks', { olderThanDays });
+  }
   
 }
 
@@ -3,7 +3,14 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
-import { TaskAttempt } from '@/types';
+import {
+  AttemptCommit,
+  CreateReviewCommentRequest,
+  ReviewComment,
+  TaskAttempt,
+  TaskCommandResult,
+  UpdateReviewCommentRequest,
+} from '@/types';
 
 export class TaskAttemptApi {
   
@@ -21,6 +28,55 @@ export class TaskAttemptApi {
     return invoke<TaskAttempt[]>('list_task_attempts', { taskId });
   }
   
+  /**
+   * List commits made automatically after executions
+   */
+  async listCommits(attemptId: string): Promise<AttemptCommit[]> {
+    return invoke<AttemptCommit[]>('list_attempt_commits', { attemptId });
+  }
+
+  /**
+   * Start a new attempt that re-sends the user messages of this attempt
+   */
+  async replay(attemptId: string): Promise<TaskAttempt> {
+    return invoke<TaskAttempt>('replay_attempt', { attemptId });
+  }
+
+  /**
+   * Comment on a line of the attempt's diff
+   */
+  async createReviewComment(request: CreateReviewCommentRequest): Promise<ReviewComment> {
+    return invoke<ReviewComment>('create_review_comment', { request });
+  }
+
+  /**
+   * List review comments of an attempt, resolved ones included
+   */
+  async listReviewComments(attemptId: string): Promise<ReviewComment[]> {
+    return invoke<ReviewComment[]>('list_review_comments', { attemptId });
+  }
+
+  /**
+   * Edit a review comment or mark it (un)resolved
+   */
+  async updateReviewComment(id: string, request: UpdateReviewCommentRequest): Promise<ReviewComment> {
+    return invoke<ReviewComment>('update_review_comment', { id, request });
+  }
+
+  /**
+   * Delete a review comment
+   */
+  async deleteReviewComment(id: string): Promise<void> {
+    return invoke('delete_review_comment', { id });
+  }
+
+  /**
+   * Send the unresolved review comments to the attempt's agent
+   */
+  async sendReview(attemptId: string): Promise<TaskCommandResult> {
+    return invoke<TaskCommandResult>('send_review_to_agent', { attemptId });
+  }
+
   
   /**
    * Update Claude session ID
@@ -5,6 +5,10 @@ export class WindowApi {
     return invoke('open_project_window', { projectId, projectName });
   }
 
+  static async openProjectWindowAtTask(projectId: string, taskId: string): Promise<string> {
+    return invoke('open_project_window_at_task', { projectId, taskId });
+  }
+
   static async closeProjectWindow(projectId: string): Promise<void> {
     return invoke('close_project_window', { projectId });
   }
@@ -58,4 +58,19 @@ export interface TaskExecutionSummary {
   active_attempt_id: string | null;
   is_running: boolean;
   agent_type: string | null;
-}
\ No newline at end of file
+}
+
+// Execution timeline of an attempt (for debugging)
+export type TimelineEventType =
+  | "execution_started"
+  | "message_received"
+  | "tool_call_started"
+  | "tool_call_completed"
+  | "execution_completed";
+
+export interface TimelineEvent {
+  timestamp: string;
+  event_type: TimelineEventType;
+  description: string;
+  duration_ms: number | null;
+}
@@ -8,8 +8,38 @@ export interface Task {
   parent_task_id?: string;
   assignee?: string;
   tags?: string[];
+  position?: number;
   created_at: string;
   updated_at: string;
+  archived_at?: string;
+  /** Set while the task is deleted and can still be restored */
+  deleted_at?: string | null;
+  /** What caused the latest status change */
+  status_triggered_by?: StatusChangeTrigger | null;
+}
+
+export type StatusChangeTrigger = "user" | "agent_completed" | "vcs_merged" | "vcs_closed" | "system";
+
+/** One change in a task's status history */
+export interface TaskStatusChange {
+  id: string;
+  task_id: string;
+  from_status?: TaskStatus | null;
+  to_status: TaskStatus;
+  triggered_by: StatusChangeTrigger;
+  created_at: string;
+}
+
+export type MatchField = "title" | "description" | "tags" | "conversation";
+
+/** A task found by searching titles, descriptions, tags and agent conversations */
+export interface TaskSearchResult {
+  task: Task;
+  /** Higher is better */
+  score: number;
+  match_field: MatchField;
+  /** Text around the match with the matching words in <mark> tags */
+  snippet: string;
 }
 
 export enum TaskStatus {
@@ -40,6 +70,64 @@ export interface Project {
   created_at: string;
   updated_at: string;
   last_opened?: string;
+  cache_links: string[];
+  cache_link_mode: CacheLinkMode;
+  branch_template?: string;
+  last_dev_command?: string;
+  dev_server_url?: string;
+  /** Commit the worktree changes after each agent execution */
+  auto_commit: boolean;
+  /** Agent for new attempts when none is picked, e.g. "claude_code" or "gemini_cli" */
+  default_executor?: string;
+  default_model?: string;
+  /** Without git, attempts run in the project directory and git features are off */
+  git_enabled: boolean;
+  /** Repository-relative directory the agent runs in; diffs and file search are limited to it */
+  subdirectory?: string | null;
+}
+
+export type CacheLinkMode = 'symlink' | 'clone';
+
+export interface DevServerInfo {
+  project_id: string;
+  process_id: string;
+  /** Unique per project and attempt, "dev" unless given */
+  name: string;
+  /** Attempt whose worktree the server runs in */
+  task_attempt_id?: string | null;
+  pid: number;
+  command: string;
+  uptime_seconds: number;
+  url?: string;
+  output: string[];
+}
+
+export interface UpdateCacheLinksRequest {
+  cache_links: string[];
+  cache_link_mode?: CacheLinkMode;
+}
+
+export interface ImportProjectResult {
+  project: Project;
+  imported_tasks: number;
+  skipped_tasks: number; // title already present in the target project
+  imported_attempts: number;
+}
+
+export interface ProjectEnvVar {
+  id: string;
+  project_id: string;
+  key: string;
+  value: string; // masked for secrets
+  is_secret: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SetProjectEnvVarRequest {
+  key: string;
+  value: string;
+  is_secret?: boolean;
 }
 
 export interface CreateProjectRequest {
@@ -50,6 +138,9 @@ export interface CreateProjectRequest {
   main_branch?: string;
   setup_script?: string;
   dev_script?: string;
+  /** Detected from the directory when omitted */
+  git_enabled?: boolean;
+  subdirectory?: string;
 }
 
 export interface UpdateProjectRequest {
@@ -60,6 +151,17 @@ export interface UpdateProjectRequest {
   main_branch?: string;
   setup_script?: string;
   dev_script?: string;
+  /** Placeholders: {slug}, {id}, {assignee}, {date}. Empty string restores the default */
+  branch_template?: string;
+  auto_commit?: boolean;
+  /** Empty string clears the default */
+  default_executor?: string;
+  /** Empty string clears the default */
+  default_model?: string;
+  /** Can only be turned on once the directory is a git repository */
+  git_enabled?: boolean;
+  /** An empty string scopes the project to the whole repository again */
+  subdirectory?: string;
 }
 
 export interface CreateTaskRequest {
@@ -70,6 +172,9 @@ export interface CreateTaskRequest {
   parent_task_id?: string;
   assignee?: string;
   tags?: string[];
+  base_branch?: string;
+  /** Agent of the first attempt, defaults to the project's */
+  executor?: string;
 }
 
 export interface UpdateTaskRequest {
@@ -95,6 +200,10 @@ export interface TaskAttempt {
   created_at: string;
   completed_at?: string;
   claude_session_id?: string;
+  setup_status?: 'running' | 'completed' | 'failed' | 'skipped' | 'needs_worktree';
+  cost_usd?: number;
+  mcp_server_ids: string[];
+  model?: string;
 }
 
 export enum AttemptStatus {
@@ -106,27 +215,50 @@ export enum AttemptStatus {
 
 export interface ExecutionProcess {
   id: string;
-  task_attempt_id: string;
+  task_attempt_id?: string;
   process_type: ProcessType;
   executor_type?: string;
   status: ProcessStatus;
   command: string;
   args?: string;
   working_directory: string;
-  stdout?: string;
-  stderr?: string;
   exit_code?: number;
   started_at: string;
   completed_at?: string;
 }
 
+export type OutputStreamType = "stdout" | "stderr";
+
+/** One line of a process's output, as recorded in its combined log */
+export interface ProcessOutputLine {
+  stream: OutputStreamType;
+  /** Position of the line within its own stream */
+  line_number: number;
+  /** Position of the line across both streams */
+  sequence: number;
+  /** Milliseconds since the Unix epoch */
+  timestamp: number;
+  content: string;
+}
+
+/** A window of the lines a process wrote to one of its outputs */
+export interface ProcessOutputPage {
+  lines: string[];
+  total_lines: number;
+  /** Offset of the line after this page; equals total_lines at the end */
+  next_offset: number;
+}
+
 export enum ProcessType {
   SetupScript = "setupscript",
   CodingAgent = "codingagent",
   DevServer = "devserver",
   Terminal = "terminal",
+  Manual = "manual",
 }
 
+export type ScriptType = 'setup' | 'dev' | { custom: string };
+
 export enum ProcessStatus {
   Running = "running",
   Completed = "completed",
@@ -168,6 +300,48 @@ export interface GitStatus {
   untracked: string[];
 }
 
+/** A remote-tracking branch as of the last fetch */
+export interface RemoteBranch {
+  /** Branch name without the remote prefix */
+  name: string;
+  remote: string;
+  commit: string;
+  /** The remote's default branch */
+  isHead: boolean;
+}
+
+/** Remote-tracking branches changed by a fetch, as `<remote>/<branch>` */
+export interface FetchResult {
+  newBranches: string[];
+  deletedBranches: string[];
+  updatedBranches: string[];
+}
+
+/** A commit that touched a file, with the lines it changed there */
+export interface FileCommit {
+  sha: string;
+  message: string;
+  author: string;
+  date: string;
+  additions: number;
+  deletions: number;
+}
+
+/** Who last changed a line of a file in an attempt's worktree */
+export interface BlameLine {
+  lineNumber: number;
+  content: string;
+  /** All zeros for lines that are not committed yet */
+  commit: string;
+  author: string;
+  /** Unix seconds */
+  timestamp: number;
+  /** Written after the attempt's base commit */
+  introducedInAttempt: boolean;
+  /** Only in the working tree, from the current session */
+  uncommitted: boolean;
+}
+
 
 
 export interface McpServer {
@@ -178,6 +352,24 @@ export interface McpServer {
   env: Record<string, string>;
   capabilities: McpCapabilities;
   status: McpServerStatus;
+  auto_restart: boolean;
+  health_check_interval_seconds?: number;
+  request_timeout_seconds?: number;
+  log_lines?: number;
+  /** Project the server belongs to; null makes it available to every project */
+  project_id?: string | null;
+}
+
+/** A server as described when registering it */
+export interface NewMcpServer {
+  name: string;
+  command: string;
+  args?: string[];
+  env?: Record<string, string>;
+  autoRestart?: boolean;
+  healthCheckIntervalSeconds?: number;
+  requestTimeoutSeconds?: number;
+  logLines?: number;
 }
 
 export interface McpCapabilities {
@@ -249,6 +441,7 @@ export interface CodingAgentExecution {
   working_directory: string;
   status: CodingAgentExecutionStatus;
   created_at: string;
+  log_path?: string;
 }
 
 export enum CodingAgentType {
@@ -259,6 +452,7 @@ export enum CodingAgentType {
 export enum CodingAgentExecutionStatus {
   Starting = "Starting",
   Running = "Running",
+  Paused = "Paused",
   Completed = "Completed",
   Error = "Error",
 }
@@ -303,6 +497,10 @@ export interface CommitInfo {
 export type { 
   MergeRequest, 
   MergeRequestInfo, 
+  BranchStatus,
+  PipelineJob,
+  AttemptPipelineStatus,
+  MergeRequestDraft,
   MergeRequestState, 
   MergeStatus, 
   PipelineStatus 
@@ -312,4 +510,269 @@ export type {
 export type {
   Command,
   CommandSearchResult
-} from './command';
\ No newline at end of file
+} from './command';
+export interface VcsSyncConfig {
+  syncIntervalSeconds: number;
+  enabled: boolean;
+  emitIndividualEvents: boolean;
+}
+
+export type BulkTaskResult =
+  | { status: 'started' }
+  | { status: 'queued' }
+  | { status: 'skipped'; reason: string }
+  | { status: 'error'; message: string };
+
+export interface BulkExecutionResponse {
+  bulkId: string;
+  results: Record<string, BulkTaskResult>;
+}
+
+export type TaskCommandResult =
+  | { status: 'started' }
+  | { status: 'queued'; pendingMessageId: string }
+  | { status: 'stopped' };
+
+export interface PendingMessage {
+  id: string;
+  task_attempt_id: string;
+  task_id: string;
+  message: string;
+  images?: string[];
+  created_at: string;
+}
+
+export interface AttemptCommit {
+  id: string;
+  task_attempt_id: string;
+  commit_hash: string;
+  message: string;
+  created_at: string;
+}
+
+/** What happened in a task event, tagged by `type` */
+export type TaskEventKind =
+  | { type: "status_changed"; from?: TaskStatus | null; to: TaskStatus }
+  | { type: "execution_started"; execution_id: string; executor: string }
+  | { type: "execution_completed"; execution_id: string; cost_usd?: number | null }
+  | { type: "execution_stopped"; execution_id: string }
+  | { type: "committed"; commit_hash: string; message: string }
+  | { type: "merge_request_created"; provider: string; number: number; title: string; url: string }
+  | { type: "merge_request_merged"; provider: string; number: number; title: string; url: string }
+  | { type: "merge_request_closed"; provider: string; number: number; title: string; url: string };
+
+/** An entry of a task's activity timeline */
+export type TaskEvent = TaskEventKind & {
+  id: string;
+  task_id: string;
+  task_attempt_id?: string | null;
+  created_at: string;
+};
+
+/** `old` points at the base version of the file, `new` at the attempt's */
+export type DiffSide = 'old' | 'new';
+
+export interface ReviewComment {
+  id: string;
+  task_attempt_id: string;
+  file_path: string;
+  /** 1-based line number on `side` */
+  line: number;
+  side: DiffSide;
+  body: string;
+  /** The commented line when the comment was made */
+  line_content?: string;
+  resolved: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateReviewCommentRequest {
+  task_attempt_id: string;
+  file_path: string;
+  line: number;
+  side?: DiffSide;
+  body: string;
+}
+
+export interface UpdateReviewCommentRequest {
+  body?: string;
+  resolved?: boolean;
+}
+
+/** A reusable task blueprint; `{{name}}` placeholders are filled in on creation */
+export interface TaskTemplate {
+  id: string;
+  project_id: string;
+  name: string;
+  title_pattern: string;
+  description_template?: string;
+  priority: TaskPriority;
+  tags?: string[];
+  /** Agent of the first attempt, the project's default when unset */
+  executor?: string;
+  /** Sent to the agent once the first attempt's worktree is ready */
+  initial_prompt?: string;
+  /** Placeholder names in the order they first appear */
+  placeholders: string[];
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateTaskTemplateRequest {
+  name: string;
+  title_pattern: string;
+  description_template?: string;
+  priority?: TaskPriority;
+  tags?: string[];
+  executor?: string;
+  initial_prompt?: string;
+}
+
+/** Unset fields are kept; an empty description, executor or initial prompt clears it */
+export type UpdateTaskTemplateRequest = Partial<CreateTaskTemplateRequest>;
+
+export interface ExecutionConfig {
+  interruptOnNewMessage: boolean;
+  runSetupScript: boolean;
+  requireSetupSuccess: boolean;
+  /** Days raw agent output is kept, checked on startup; 0 keeps it forever */
+  rawLogRetentionDays: number;
+  /** Where raw agent output is written; unset uses the app's log directory */
+  executionLogDir?: string;
+}
+
+export interface WindowConfig {
+  restoreProjectWindows: boolean;
+}
+
+export interface BackupConfig {
+  /** Startup, daily and manual backups to keep; recovery backups are kept 30 days */
+  maxBackups: number;
+}
+
+/** `token` pushes over HTTPS with the access token, `ssh` pushes to `origin` with the user's SSH agent */
+export type PushMode = 'token' | 'ssh';
+
+export interface GitHostMapping {
+  /** Host name, with scheme and port when not served over https on the default port */
+  host: string;
+  provider: 'github' | 'gitlab';
+}
+
+export interface GitHostsConfig {
+  hosts: GitHostMapping[];
+  /** Ask unknown hosts whether they serve the GitLab or GitHub Enterprise API */
+  probeUnknownHosts: boolean;
+}
+
+export interface DevServerConfig {
+  /** Extra regexes for output lines announcing the dev server address */
+  urlPatterns: string[];
+  /** Start the server again when it exits with an error */
+  autoRestart: boolean;
+  maxRestarts: number;
+  /** Crashes right after starting tolerated in a row before restarts stop */
+  crashLoopLimit: number;
+  /** Env files loaded from the working directory, e.g. ".env"; empty for none */
+  envFiles: string[];
+  /** Let the env files replace variables inherited from the app's environment */
+  overrideEnv: boolean;
+}
+
+/** Disk space checks of attempt worktrees */
+export interface StorageConfig {
+  /** Size in bytes above which a worktree triggers a warning; 0 turns it off */
+  worktreeWarningBytes: number;
+}
+
+/** Which file changes in worktrees and project roots are reported, and how often */
+export interface FileWatcherConfig {
+  /** Changes are collected until none arrive for this long, then reported together */
+  debounceMs: number;
+  /** Leave out paths the repository's .gitignore files ignore */
+  respectGitignore: boolean;
+  /** Globs matched against the relative path and each of its components, e.g. "node_modules" */
+  ignorePatterns: string[];
+  /** Worktrees and project roots watched at the same time */
+  maxWatchedDirectories: number;
+}
+
+/** Desktop notifications, sent only while the window showing the task isn't focused */
+export interface NotificationConfig {
+  /** An agent run finished or failed */
+  executionCompleted: boolean;
+  /** A task moved to Done */
+  taskDone: boolean;
+  /** A merge request was merged or its pipeline failed */
+  mergeRequestUpdated: boolean;
+}
+
+/** Disk space taken by one top-level entry of a worktree */
+export interface PathUsage {
+  path: string;
+  bytes: number;
+}
+
+/** Disk space taken by an attempt's worktree, without the shared git objects */
+export interface WorktreeUsage {
+  attemptId: string;
+  taskId: string;
+  taskTitle: string;
+  projectId: string;
+  projectPath: string;
+  worktreePath: string;
+  bytes: number;
+  /** Biggest first */
+  largestPaths: PathUsage[];
+}
+
+export interface AllWorktreesUsage {
+  /** Worktrees plus the shared git data */
+  totalBytes: number;
+  /** `.git` of the projects with worktrees, each counted once */
+  sharedGitBytes: number;
+  /** Biggest first */
+  worktrees: WorktreeUsage[];
+}
+
+export interface DatabaseBackup {
+  id: string;
+  kind: 'startup' | 'daily' | 'manual' | 'pre-restore' | 'pre-rollback' | 'corrupt';
+  created_at: string;
+  size_bytes: number;
+}
+
+export interface RolledBackMigration {
+  version: number;
+  description: string;
+}
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export interface LogEntry {
+  timestamp: string;
+  level: LogLevel;
+  target: string;
+  message: string;
+}
+
+export interface NetworkConfig {
+  proxyUrl?: string;
+  proxyNoProxy: string[];
+}
+
+export interface TaskPage {
+  tasks: Task[];
+  next_cursor?: string;
+  total_count: number;
+}
+
+export interface TaskSearchFilters {
+  statuses?: TaskStatus[];
+  assignee?: string;
+  order_by?: 'relevance' | 'date';
+  limit?: number;
+  /** Also search the messages of the tasks' attempt conversations */
+  include_conversations?: boolean;
+}